@@ -2,27 +2,49 @@ package main
 
 import (
 	"context"
+	"log"
 	"os"
 	"path/filepath"
+	"time"
 
+	"github.com/wailsapp/wails/v2/pkg/runtime"
+
+	"zone.digit.tommie/internal/collections"
+	"zone.digit.tommie/internal/config"
+	"zone.digit.tommie/internal/cookies"
+	"zone.digit.tommie/internal/environments"
+	"zone.digit.tommie/internal/har"
+	"zone.digit.tommie/internal/history"
+	"zone.digit.tommie/internal/hosts"
+	"zone.digit.tommie/internal/i18n"
+	"zone.digit.tommie/internal/monitors"
 	"zone.digit.tommie/internal/proxy"
+	"zone.digit.tommie/internal/sessions"
 	"zone.digit.tommie/internal/storage"
 )
 
 // App struct holds the application state and provides IPC bindings.
 type App struct {
-	ctx context.Context
-	db  *storage.Database
+	ctx     context.Context
+	db      storage.Store
+	service *proxy.HTTPService
+	locale  string // Language of error messages, "" for English
+
+	monitors *monitors.Scheduler
+
+	interceptors []proxy.Interceptor
 }
 
-// NewApp creates a new App instance.
-func NewApp() *App {
-	return &App{}
+// NewApp creates a new App instance. Interceptors are registered on the
+// proxy service when it starts.
+func NewApp(interceptors ...proxy.Interceptor) *App {
+	return &App{interceptors: interceptors}
 }
 
-// startup is called when the app starts. It initializes the database.
+// startup is called when the app starts. It initializes the database and proxy service.
 func (a *App) startup(ctx context.Context) {
 	a.ctx = ctx
+	cfg := config.Load()
 
 	// Initialize database in app data directory
 	dataDir, err := os.UserConfigDir()
@@ -35,17 +57,62 @@ func (a *App) startup(ctx context.Context) {
 		panic("failed to create app directory: " + err.Error())
 	}
 
-	dbPath := filepath.Join(appDir, "storage.db")
-	db, err := storage.New(dbPath)
+	dbPath := cfg.StoragePath
+	if dbPath == "" {
+		dbPath = filepath.Join(appDir, "storage.db")
+	}
+	db, err := storage.Open(cfg.StorageBackend, dbPath)
 	if err != nil {
 		panic("failed to initialize database: " + err.Error())
 	}
 
 	a.db = db
+
+	// Plugins are loaded from the data directory unless configured
+	if cfg.PluginDir == "" {
+		cfg.PluginDir = filepath.Join(appDir, "plugins")
+		if err := os.MkdirAll(cfg.PluginDir, 0755); err != nil {
+			panic("failed to create plugin directory: " + err.Error())
+		}
+	}
+
+	// Initialize proxy service from environment configuration
+	opts, err := proxy.OptionsFromConfig(cfg)
+	if err != nil {
+		panic("failed to initialize proxy service: " + err.Error())
+	}
+	opts.IntegratedAuth = true
+	opts.AllowDownloadPath = true
+	if opts.FixtureDir == "" {
+		opts.FixtureDir = filepath.Join(appDir, "fixtures")
+	}
+	opts.Store = db
+	opts.Interceptors = append(opts.Interceptors, a.interceptors...)
+
+	a.service = proxy.NewHTTPService(opts)
+
+	// Monitors keep checking in the background while the app runs
+	a.monitors = monitors.NewScheduler(db, a.service.ExecuteIn)
+	a.monitors.OnNotify(func(_ string, event monitors.Event) {
+		// The frontend raises the notification of the operating system
+		runtime.EventsEmit(a.ctx, "monitor:notification", event)
+	})
+	if _, err := a.monitors.Start(); err != nil {
+		log.Printf("Failed to start monitors: %v", err)
+	}
+
+	// Translate error messages to the configured or system language
+	a.locale = i18n.Supported(cfg.Locale)
+	if a.locale == "" {
+		a.locale = i18n.Supported(os.Getenv("LANG"))
+	}
 }
 
 // shutdown is called when the app is closing.
 func (a *App) shutdown(ctx context.Context) {
+	if a.monitors != nil {
+		a.monitors.Stop()
+	}
 	if a.db != nil {
 		a.db.Close()
 	}
@@ -54,7 +121,118 @@ func (a *App) shutdown(ctx context.Context) {
 // ProxyRequest executes an HTTP request and returns the response.
 // This is the main IPC binding for the proxy functionality.
 func (a *App) ProxyRequest(request proxy.ProxyRequest) proxy.ProxyResponse {
-	return proxy.ExecuteRequest(request)
+	return proxy.Localized(a.service.Execute(request), a.locale)
+}
+
+// ProxyBatch executes several requests with bounded concurrency and returns
+// their results with aggregate stats.
+func (a *App) ProxyBatch(batch proxy.BatchRequest) proxy.BatchResponse {
+	return proxy.Localized(a.service.ExecuteBatch(a.ctx, a.db, batch), a.locale)
+}
+
+// ProxyStream executes a request like ProxyRequest, emitting a "proxy:event"
+// event for each server-sent event of a text/event-stream response as it arrives.
+func (a *App) ProxyStream(request proxy.ProxyRequest) proxy.ProxyResponse {
+	return proxy.Localized(a.service.ExecuteStream(a.ctx, a.db, request, func(event proxy.ServerSentEvent) {
+		runtime.EventsEmit(a.ctx, "proxy:event", event)
+	}), a.locale)
+}
+
+// LoadTest sends a request repeatedly with fixed concurrency, emitting a
+// "loadtest:progress" event with the stats so far while it runs.
+func (a *App) LoadTest(test proxy.LoadTestRequest) proxy.LoadTestResponse {
+	return a.service.ExecuteLoadTest(a.ctx, a.db, test, func(progress proxy.LoadTestResponse) {
+		runtime.EventsEmit(a.ctx, "loadtest:progress", progress)
+	})
+}
+
+// GraphQLSchema introspects the GraphQL endpoint of a request and returns its schema as SDL.
+func (a *App) GraphQLSchema(request proxy.ProxyRequest) proxy.GraphQLSchemaResponse {
+	return proxy.Localized(a.service.IntrospectGraphQL(a.db, request), a.locale)
+}
+
+// ProxyETagAudit fetches a request repeatedly and reports whether its ETag
+// and Last-Modified validators are stable.
+func (a *App) ProxyETagAudit(audit proxy.ETagAuditRequest) proxy.ETagAuditResponse {
+	return proxy.Localized(a.service.ExecuteETagAudit(a.db, audit), a.locale)
+}
+
+// GRPCCall makes a unary gRPC or gRPC-Web call.
+func (a *App) GRPCCall(call proxy.GRPCRequest) proxy.GRPCResponse {
+	return proxy.Localized(a.service.ExecuteGRPC(a.db, call), a.locale)
+}
+
+// DNSLookup queries A, AAAA, CNAME, MX, TXT, NS and CAA records of a name,
+// or the types requested, with per-query timing.
+func (a *App) DNSLookup(lookup proxy.DNSLookupRequest) proxy.DNSLookupResponse {
+	return proxy.Localized(a.service.LookupDNS(lookup), a.locale)
+}
+
+// EmailPosture looks up the MX, SPF and DMARC records of a domain and
+// summarizes its mail authentication.
+func (a *App) EmailPosture(lookup proxy.EmailPostureRequest) proxy.EmailPostureResponse {
+	return proxy.Localized(a.service.LookupEmailPosture(lookup), a.locale)
+}
+
+// EvalScript runs a sandboxed script over a stored response and returns
+// the value it derives.
+func (a *App) EvalScript(request proxy.ScriptRequest) proxy.ScriptResponse {
+	return proxy.Localized(a.service.EvaluateScript(a.ctx, a.db, request), a.locale)
+}
+
+// DecodeJWT decodes a JSON Web Token and checks its expiry, verifying the
+// signature when a key is given.
+func (a *App) DecodeJWT(request proxy.JWTRequest) proxy.JWTResponse {
+	return proxy.Localized(a.service.DecodeJWT(request), a.locale)
+}
+
+// EvaluateJSONPath applies a JSONPath expression to a JSON document.
+func (a *App) EvaluateJSONPath(request proxy.JSONPathRequest) proxy.JSONPathResponse {
+	return proxy.Localized(a.service.EvaluateJSONPath(request), a.locale)
+}
+
+// ProxyDataRun executes a templated request once per row of CSV or JSON
+// data and aggregates the results.
+func (a *App) ProxyDataRun(run proxy.DataRunRequest) proxy.DataRunResponse {
+	return proxy.Localized(a.service.ExecuteDataRun(a.ctx, a.db, run), a.locale)
+}
+
+// ProxyPipeline pipelines requests on one HTTP/1.1 connection, an
+// experimental diagnostic for legacy servers and intermediaries.
+func (a *App) ProxyPipeline(pipeline proxy.PipelineRequest) proxy.PipelineResponse {
+	return proxy.Localized(a.service.ExecutePipeline(pipeline), a.locale)
+}
+
+// ProxyBurst sends a request in timed bursts to probe the server's rate limiter.
+func (a *App) ProxyBurst(burst proxy.BurstRequest) proxy.BurstResponse {
+	return proxy.Localized(a.service.ExecuteBurst(a.ctx, a.db, burst), a.locale)
+}
+
+// ProxyIdempotency sends a request twice with one idempotency key and reports
+// whether the server deduplicated it.
+func (a *App) ProxyIdempotency(check proxy.IdempotencyRequest) proxy.IdempotencyResponse {
+	return proxy.Localized(a.service.ExecuteIdempotencyCheck(a.db, check), a.locale)
+}
+
+// WebSocketConnect opens a WebSocket session through the proxy pipeline.
+func (a *App) WebSocketConnect(request proxy.WebSocketRequest) (*proxy.WebSocketHandshake, error) {
+	return a.service.OpenWebSocket("", request)
+}
+
+// WebSocketSend sends a message on an open WebSocket session.
+func (a *App) WebSocketSend(id string, message proxy.WebSocketMessage) (*proxy.WebSocketFrame, error) {
+	return a.service.SendWebSocket("", id, message)
+}
+
+// WebSocketFrames returns the frames of a session recorded after sequence number after.
+func (a *App) WebSocketFrames(id string, after int) (*proxy.WebSocketFrameLog, error) {
+	frames, _, err := a.service.WebSocketFrames("", id, after)
+	return frames, err
+}
+
+// WebSocketClose closes a WebSocket session and discards its frames.
+func (a *App) WebSocketClose(id string) error {
+	return a.service.CloseWebSocket("", id)
 }
 
 // StorageGet retrieves a value from storage.
@@ -86,3 +264,292 @@ func (a *App) StorageClear(store string) error {
 func (a *App) StorageKeys(store string) ([]string, error) {
 	return a.db.Keys(store)
 }
+
+// CookieJars returns the names of the stored cookie jars.
+func (a *App) CookieJars() ([]string, error) {
+	return cookies.List(a.db)
+}
+
+// CookieJar returns the cookies stored in a jar.
+func (a *App) CookieJar(name string) (*cookies.Jar, error) {
+	return cookies.Load(a.db, name)
+}
+
+// ClearCookieJar deletes a cookie jar.
+func (a *App) ClearCookieJar(name string) error {
+	return cookies.Clear(a.db, name)
+}
+
+// History returns a page of recorded requests, newest first.
+func (a *App) History(query history.Query) (*history.Page, error) {
+	return history.List(a.db, query)
+}
+
+// HistoryEntry returns a single recorded request, or nil if it does not exist.
+func (a *App) HistoryEntry(id string) (*history.Entry, error) {
+	return history.Get(a.db, id)
+}
+
+// SearchHistoryBody finds a pattern in the stored response body of a
+// recorded request.
+func (a *App) SearchHistoryBody(id string, query history.SearchQuery) (*history.SearchResult, error) {
+	return history.Search(a.db, id, query)
+}
+
+// RemoveHistoryEntry deletes a recorded request.
+func (a *App) RemoveHistoryEntry(id string) error {
+	return history.Remove(a.db, id)
+}
+
+// ClearHistory deletes all recorded requests.
+func (a *App) ClearHistory() error {
+	return history.Clear(a.db)
+}
+
+// StartSession starts recording every executed request in a new session,
+// stopping the one being recorded.
+func (a *App) StartSession(name string) (*sessions.Session, error) {
+	return sessions.Start(a.db, name)
+}
+
+// StopSession stops recording, returning the stopped session or nil when
+// none was being recorded.
+func (a *App) StopSession() (*sessions.Session, error) {
+	return sessions.Stop(a.db)
+}
+
+// ActiveSession returns the session being recorded, or nil.
+func (a *App) ActiveSession() (*sessions.Session, error) {
+	return sessions.Active(a.db)
+}
+
+// ListSessions returns all recorded sessions, newest first.
+func (a *App) ListSessions() ([]sessions.Session, error) {
+	return sessions.List(a.db)
+}
+
+// RenameSession changes the name of a session.
+func (a *App) RenameSession(id, name string) (*sessions.Session, error) {
+	return sessions.Rename(a.db, id, name)
+}
+
+// AddSessionNote appends a timestamped note to a session.
+func (a *App) AddSessionNote(id, text string) (*sessions.Session, error) {
+	return sessions.AddNote(a.db, id, text)
+}
+
+// DeleteSession removes a session and its recorded exchanges.
+func (a *App) DeleteSession(id string) error {
+	return sessions.Delete(a.db, id)
+}
+
+// ExportSessionHAR converts the exchanges of a session to an HTTP Archive.
+func (a *App) ExportSessionHAR(id string) (*har.HAR, error) {
+	bundle, err := sessions.Export(a.db, id)
+	if err != nil {
+		return nil, err
+	}
+	exchanges, err := har.SessionExchanges(bundle.Exchanges)
+	if err != nil {
+		return nil, err
+	}
+	return har.ExportSession(bundle.Session, exchanges), nil
+}
+
+// ExportSessionBundle returns a session with its notes and every exchange
+// as recorded, to be imported again with ImportSessionBundle.
+func (a *App) ExportSessionBundle(id string) (*sessions.Bundle, error) {
+	return sessions.Export(a.db, id)
+}
+
+// ImportSessionBundle stores an exported session under a new ID.
+func (a *App) ImportSessionBundle(bundle sessions.Bundle) (*sessions.Session, error) {
+	return sessions.Import(a.db, bundle)
+}
+
+// ReplaySession executes the requests of a session again, in the order they
+// were recorded.
+func (a *App) ReplaySession(id string) (*har.ImportResult, error) {
+	recorded, err := sessions.Exchanges(a.db, id)
+	if err != nil {
+		return nil, err
+	}
+	exchanges, err := har.SessionExchanges(recorded)
+	if err != nil {
+		return nil, err
+	}
+	return har.ReplaySession(exchanges, a.service, a.db), nil
+}
+
+// Environments lists the names of the stored environments.
+func (a *App) Environments() ([]string, error) {
+	return environments.List(a.db)
+}
+
+// Environment returns the variables of an environment, or nil if it does not exist.
+func (a *App) Environment(name string) (*environments.Environment, error) {
+	return environments.Load(a.db, name)
+}
+
+// SaveEnvironment replaces the variables of an environment.
+func (a *App) SaveEnvironment(name string, variables map[string]string) (*environments.Environment, error) {
+	return environments.Save(a.db, name, variables)
+}
+
+// DeleteEnvironment removes an environment.
+func (a *App) DeleteEnvironment(name string) error {
+	return environments.Delete(a.db, name)
+}
+
+// HostOverrides lists the hostnames mapped to fixed addresses.
+func (a *App) HostOverrides() ([]hosts.Override, error) {
+	return hosts.List(a.db)
+}
+
+// SetHostOverride maps a hostname to an IP address, used instead of DNS.
+func (a *App) SetHostOverride(host, address string) (*hosts.Override, error) {
+	return hosts.Set(a.db, host, address)
+}
+
+// RemoveHostOverride deletes the override of a hostname.
+func (a *App) RemoveHostOverride(host string) error {
+	return hosts.Remove(a.db, host)
+}
+
+// Collections lists the saved request collections.
+func (a *App) Collections() ([]collections.Summary, error) {
+	return collections.List(a.db)
+}
+
+// Collection returns a collection with its folders and requests.
+func (a *App) Collection(id string) (*collections.Collection, error) {
+	return collections.Get(a.db, id)
+}
+
+// CreateCollection creates an empty collection.
+func (a *App) CreateCollection(name, description string) (*collections.Collection, error) {
+	return collections.Create(a.db, name, description)
+}
+
+// UpdateCollection renames a collection and replaces its description.
+func (a *App) UpdateCollection(id, name, description string) (*collections.Collection, error) {
+	return collections.Update(a.db, id, name, description)
+}
+
+// DeleteCollection removes a collection with everything in it.
+func (a *App) DeleteCollection(id string) error {
+	return collections.Delete(a.db, id)
+}
+
+// CreateFolder adds a folder to a collection; an empty parentID places it at the root.
+func (a *App) CreateFolder(collectionID, name, parentID string) (*collections.Folder, error) {
+	return collections.CreateFolder(a.db, collectionID, name, parentID)
+}
+
+// RenameFolder renames a folder.
+func (a *App) RenameFolder(collectionID, folderID, name string) (*collections.Folder, error) {
+	return collections.RenameFolder(a.db, collectionID, folderID, name)
+}
+
+// DeleteFolder removes a folder with its subfolders and requests.
+func (a *App) DeleteFolder(collectionID, folderID string) error {
+	return collections.DeleteFolder(a.db, collectionID, folderID)
+}
+
+// MoveFolder moves a folder under a new parent; a negative position appends.
+func (a *App) MoveFolder(collectionID, folderID, parentID string, position int) (*collections.Collection, error) {
+	return collections.MoveFolder(a.db, collectionID, folderID, parentID, position)
+}
+
+// SaveRequest creates a saved request, or updates it when it has an ID.
+func (a *App) SaveRequest(collectionID string, request collections.SavedRequest) (*collections.SavedRequest, error) {
+	return collections.SaveRequest(a.db, collectionID, request)
+}
+
+// DeleteRequest removes a saved request.
+func (a *App) DeleteRequest(collectionID, requestID string) error {
+	return collections.DeleteRequest(a.db, collectionID, requestID)
+}
+
+// MoveRequest moves a saved request into a folder; a negative position appends.
+func (a *App) MoveRequest(collectionID, requestID, folderID string, position int) (*collections.Collection, error) {
+	return collections.MoveRequest(a.db, collectionID, requestID, folderID, position)
+}
+
+// ListMonitors returns all monitors sorted by name.
+func (a *App) ListMonitors() ([]monitors.Monitor, error) {
+	return monitors.List(a.db)
+}
+
+// CreateMonitor stores a monitor and schedules its checks when enabled.
+func (a *App) CreateMonitor(m monitors.Monitor) (*monitors.Monitor, error) {
+	created, err := monitors.Create(a.db, m)
+	if err != nil {
+		return nil, err
+	}
+	a.monitors.Schedule("", *created)
+	return created, nil
+}
+
+// UpdateMonitor replaces the definition of a monitor and reschedules it.
+func (a *App) UpdateMonitor(id string, m monitors.Monitor) (*monitors.Monitor, error) {
+	updated, err := monitors.Update(a.db, id, m)
+	if err != nil {
+		return nil, err
+	}
+	a.monitors.Schedule("", *updated)
+	return updated, nil
+}
+
+// DeleteMonitor stops a monitor and removes it with its results.
+func (a *App) DeleteMonitor(id string) error {
+	a.monitors.Unschedule("", id)
+	return monitors.Delete(a.db, id)
+}
+
+// CheckMonitor runs the check of a monitor right away.
+func (a *App) CheckMonitor(id string) (*monitors.Result, error) {
+	return a.monitors.Check("", id)
+}
+
+// MonitorResults returns a page of the results of a monitor, newest first.
+func (a *App) MonitorResults(id string, offset, limit int) (*monitors.ResultPage, error) {
+	return monitors.Results(a.db, id, offset, limit)
+}
+
+// MonitorUptime reports the uptime of a monitor over the last day, week and month.
+func (a *App) MonitorUptime(id string) ([]monitors.Uptime, error) {
+	return monitors.Stats(a.db, id, time.Now())
+}
+
+// ExportHAR converts executed requests to an HTTP Archive.
+func (a *App) ExportHAR(exchanges []har.Exchange) *har.HAR {
+	return har.Export(exchanges)
+}
+
+// ImportHAR reconstructs the requests of an HTTP Archive and, depending on
+// mode, stores them in history or replays them.
+func (a *App) ImportHAR(data string, mode string) (*har.ImportResult, error) {
+	archive, err := har.Parse([]byte(data))
+	if err != nil {
+		return nil, err
+	}
+	return har.Import(archive, mode, a.service, a.db)
+}
+
+// ToCurl renders a request as a curl command.
+func (a *App) ToCurl(request proxy.ProxyRequest) string {
+	return request.ToCurl()
+}
+
+// ToRedactedCurl renders a request as a curl command safe to share, with
+// credentials replaced by shell variables declared in a preamble.
+func (a *App) ToRedactedCurl(request proxy.ProxyRequest) proxy.CurlExport {
+	return a.service.RedactedCurl(request)
+}
+
+// ImportCurl parses a curl command line into a request. Files referenced with
+// @file are read from the local disk.
+func (a *App) ImportCurl(command string) (*proxy.CurlImport, error) {
+	return proxy.ParseCurl(command, os.ReadFile)
+}
@@ -0,0 +1,33 @@
+// Package main checks the proxy response protocol against its snapshots.
+// It exits non-zero when the serialized shape changed without a bump of
+// proxy.ProtocolVersion, so builds fail before the frontend and extension
+// receive fields they do not expect. Run it from the module root.
+package main
+
+import (
+	"flag"
+	"log"
+
+	"zone.digit.tommie/internal/protocol"
+	"zone.digit.tommie/internal/proxy"
+)
+
+func main() {
+	dir := flag.String("dir", "internal/protocol/snapshots", "directory holding the snapshots")
+	update := flag.Bool("update", false, "record the snapshots after bumping ProtocolVersion")
+	flag.Parse()
+
+	if !*update {
+		if _, err := protocol.Check(*dir); err != nil {
+			log.Fatalf("Protocol check failed: %v", err)
+		}
+		log.Printf("Protocol version %d matches its snapshots", proxy.ProtocolVersion)
+		return
+	}
+
+	result, err := protocol.Update(*dir)
+	if err != nil {
+		log.Fatalf("Failed to record protocol snapshots: %v", err)
+	}
+	log.Printf("Recorded protocol version %d snapshots in %s (%d changed)", proxy.ProtocolVersion, *dir, len(result.Changed))
+}
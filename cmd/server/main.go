@@ -1,6 +1,6 @@
 // Package main provides the web server entry point.
-// This server ONLY serves static frontend files - no proxy API.
-// Proxy requests are handled by the browser extension when using the hosted version.
+// This server serves the static frontend files and a small JSON API. The proxy API is
+// opt-in (ENABLE_PROXY); by default proxy requests are handled by the browser extension.
 package main
 
 import (
@@ -8,19 +8,71 @@ import (
 	"log"
 	"net/http"
 
+	"zone.digit.tommie/internal/api"
 	"zone.digit.tommie/internal/config"
+	"zone.digit.tommie/internal/proxy"
 	"zone.digit.tommie/internal/static"
+	"zone.digit.tommie/internal/storage"
 )
 
 func main() {
-	cfg := config.Load()
+	cfg, err := config.Read()
+	if err != nil {
+		log.Fatalf("Failed to read config file: %v", err)
+	}
+
+	// Without an explicit database path the server keeps its data in memory
+	backend := cfg.StorageBackend
+	if cfg.StoragePath == "" {
+		backend = storage.BackendMemory
+	}
+	store, err := storage.Open(backend, cfg.StoragePath)
+	if err != nil {
+		log.Fatalf("Failed to open storage: %v", err)
+	}
+	defer store.Close()
+
+	opts, err := proxy.OptionsFromConfig(cfg)
+	if err != nil {
+		log.Fatalf("Failed to initialize proxy service: %v", err)
+	}
+	service := proxy.NewHTTPService(opts)
 
-	// Serve static files only - no API endpoints
+	// Serve the API and static files
+	server := api.New(cfg, store, service)
+	if cfg.ConfigFile != "" {
+		watcher := config.NewWatcher(cfg, service.Reconfigure)
+		server.WatchConfig(watcher)
+		watcher.Start()
+		log.Printf("Watching %s for policy, quota and redaction changes", cfg.ConfigFile)
+	}
+	// Monitors send requests and record their results, which read-only mode forbids
+	if cfg.ReadOnly {
+		log.Printf("Monitors are not scheduled in read-only mode")
+	} else if n, err := server.StartMonitors(); err != nil {
+		log.Printf("Failed to start monitors: %v", err)
+	} else if n > 0 {
+		log.Printf("Scheduled %d monitor(s)", n)
+	}
+	http.Handle("/api/", server.Handler())
 	http.Handle("/", static.Handler())
 
 	addr := fmt.Sprintf(":%d", cfg.Port)
 	log.Printf("Project Tommie web server starting on http://localhost%s", addr)
-	log.Printf("Note: This server only serves static files. Proxy requests are handled by the browser extension.")
+	if cfg.EnableProxy {
+		log.Printf("Proxy API enabled at /api/proxy")
+		if cfg.Simulate {
+			log.Printf("Simulation mode enabled: proxy responses are fabricated without network access")
+		}
+	} else {
+		log.Printf("Note: Proxy API disabled. Proxy requests are handled by the browser extension.")
+	}
+	if cfg.ReadOnly {
+		log.Printf("Read-only mode enabled: mutating API routes are disabled")
+	}
+	if cfg.AuthEnabled() {
+		log.Printf("Authentication enabled for %d API token(s)", len(cfg.AuthTokens))
+	}
 
 	if err := http.ListenAndServe(addr, nil); err != nil {
 		log.Fatalf("Server failed: %v", err)
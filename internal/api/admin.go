@@ -0,0 +1,55 @@
+package api
+
+import (
+	"net/http"
+	"sort"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+// userUsage summarizes what a user has stored on the server.
+type userUsage struct {
+	User   string         `json:"user"`
+	Admin  bool           `json:"admin"`
+	Stores map[string]int `json:"stores"` // Key count per store
+}
+
+func (s *Server) handleAdminUsers(w http.ResponseWriter, r *http.Request) {
+	var users []string
+	for _, user := range s.cfg.AuthTokens {
+		users = append(users, user)
+	}
+	sort.Strings(users)
+
+	result := []userUsage{}
+	for i, user := range users {
+		if i > 0 && users[i-1] == user {
+			continue
+		}
+
+		usage := userUsage{
+			User:   user,
+			Admin:  s.cfg.IsAdmin(user),
+			Stores: make(map[string]int),
+		}
+
+		store := storage.NewNamespaced(s.store, user)
+		names, err := store.Stores()
+		if err != nil {
+			writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+			return
+		}
+		for _, name := range names {
+			keys, err := store.Keys(name)
+			if err != nil {
+				writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+				return
+			}
+			usage.Stores[name] = len(keys)
+		}
+
+		result = append(result, usage)
+	}
+
+	writeJSON(w, http.StatusOK, result)
+}
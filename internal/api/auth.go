@@ -0,0 +1,84 @@
+package api
+
+import (
+	"context"
+	"crypto/subtle"
+	"net/http"
+	"strings"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+type contextKey int
+
+const (
+	userContextKey contextKey = iota
+	// keyContextKey holds the keyID of the bearer token, for usage accounting
+	keyContextKey
+)
+
+// authenticate resolves the bearer token to a user when auth is enabled.
+// The health endpoint stays public so load balancers can probe it.
+func (s *Server) authenticate(next http.Handler) http.Handler {
+	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if !s.cfg.AuthEnabled() || r.URL.Path == "/api/health" {
+			next.ServeHTTP(w, r)
+			return
+		}
+
+		token, ok := strings.CutPrefix(r.Header.Get("Authorization"), "Bearer ")
+		if !ok {
+			writeError(w, http.StatusUnauthorized, "Missing bearer token", "UNAUTHORIZED")
+			return
+		}
+
+		user, ok := s.lookupToken(token)
+		if !ok {
+			writeError(w, http.StatusUnauthorized, "Invalid bearer token", "UNAUTHORIZED")
+			return
+		}
+
+		ctx := context.WithValue(r.Context(), userContextKey, user)
+		next.ServeHTTP(w, r.WithContext(context.WithValue(ctx, keyContextKey, keyID(token))))
+	})
+}
+
+// lookupToken finds the user owning a token using constant-time comparisons.
+func (s *Server) lookupToken(token string) (string, bool) {
+	var user string
+	found := false
+	for candidate, owner := range s.cfg.AuthTokens {
+		if subtle.ConstantTimeCompare([]byte(candidate), []byte(token)) == 1 {
+			user = owner
+			found = true
+		}
+	}
+	return user, found
+}
+
+// requireAdmin only allows authenticated admin users through.
+func (s *Server) requireAdmin(next http.Handler) http.Handler {
+	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		user, ok := userFromContext(r.Context())
+		if !ok || !s.cfg.IsAdmin(user) {
+			writeError(w, http.StatusForbidden, "Admin access required", "FORBIDDEN")
+			return
+		}
+		next.ServeHTTP(w, r)
+	})
+}
+
+// userFromContext returns the authenticated user of a request, if any.
+func userFromContext(ctx context.Context) (string, bool) {
+	user, ok := ctx.Value(userContextKey).(string)
+	return user, ok
+}
+
+// userStore returns the store scoped to the requesting user.
+// Without auth all requests share the root namespace.
+func (s *Server) userStore(r *http.Request) storage.Store {
+	if user, ok := userFromContext(r.Context()); ok {
+		return storage.NewNamespaced(s.store, user)
+	}
+	return s.store
+}
@@ -0,0 +1,207 @@
+package api
+
+import (
+	"encoding/json"
+	"errors"
+	"net/http"
+
+	"zone.digit.tommie/internal/collections"
+)
+
+// maxCollectionBytes limits the size of a collection payload.
+const maxCollectionBytes = 10 << 20
+
+// collectionRequest creates or updates a collection.
+type collectionRequest struct {
+	Name        string `json:"name"`
+	Description string `json:"description"`
+}
+
+// folderRequest creates or renames a folder.
+type folderRequest struct {
+	Name     string `json:"name"`
+	ParentID string `json:"parentId"`
+}
+
+// moveRequest places a folder or request under a new parent.
+type moveRequest struct {
+	ParentID string `json:"parentId"` // Target folder; empty for the collection root
+	Position *int   `json:"position"` // Index among the new siblings; nil appends
+}
+
+func (m moveRequest) position() int {
+	if m.Position == nil {
+		return -1
+	}
+	return *m.Position
+}
+
+// decodeCollectionBody decodes a JSON payload, writing an error response on failure.
+func decodeCollectionBody(w http.ResponseWriter, r *http.Request, v interface{}) bool {
+	if err := json.NewDecoder(http.MaxBytesReader(w, r.Body, maxCollectionBytes)).Decode(v); err != nil {
+		writeError(w, http.StatusBadRequest, "Invalid request body: "+err.Error(), "INVALID_REQUEST")
+		return false
+	}
+	return true
+}
+
+// writeCollectionError maps collection errors to responses.
+func writeCollectionError(w http.ResponseWriter, err error) {
+	var validation *collections.ValidationError
+	switch {
+	case errors.Is(err, collections.ErrNotFound):
+		writeError(w, http.StatusNotFound, "Collection, folder or request not found", "NOT_FOUND")
+	case errors.As(err, &validation):
+		writeError(w, http.StatusBadRequest, validation.Message, "INVALID_REQUEST")
+	default:
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+	}
+}
+
+func (s *Server) handleCollections(w http.ResponseWriter, r *http.Request) {
+	summaries, err := collections.List(s.userStore(r))
+	if err != nil {
+		writeCollectionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, summaries)
+}
+
+func (s *Server) handleCollectionCreate(w http.ResponseWriter, r *http.Request) {
+	var request collectionRequest
+	if !decodeCollectionBody(w, r, &request) {
+		return
+	}
+	c, err := collections.Create(s.userStore(r), request.Name, request.Description)
+	if err != nil {
+		writeCollectionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusCreated, c)
+}
+
+func (s *Server) handleCollectionGet(w http.ResponseWriter, r *http.Request) {
+	c, err := collections.Get(s.userStore(r), r.PathValue("id"))
+	if err != nil {
+		writeCollectionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, c)
+}
+
+func (s *Server) handleCollectionUpdate(w http.ResponseWriter, r *http.Request) {
+	var request collectionRequest
+	if !decodeCollectionBody(w, r, &request) {
+		return
+	}
+	c, err := collections.Update(s.userStore(r), r.PathValue("id"), request.Name, request.Description)
+	if err != nil {
+		writeCollectionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, c)
+}
+
+func (s *Server) handleCollectionDelete(w http.ResponseWriter, r *http.Request) {
+	if err := collections.Delete(s.userStore(r), r.PathValue("id")); err != nil {
+		writeCollectionError(w, err)
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+func (s *Server) handleFolderCreate(w http.ResponseWriter, r *http.Request) {
+	var request folderRequest
+	if !decodeCollectionBody(w, r, &request) {
+		return
+	}
+	folder, err := collections.CreateFolder(s.userStore(r), r.PathValue("id"), request.Name, request.ParentID)
+	if err != nil {
+		writeCollectionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusCreated, folder)
+}
+
+func (s *Server) handleFolderRename(w http.ResponseWriter, r *http.Request) {
+	var request folderRequest
+	if !decodeCollectionBody(w, r, &request) {
+		return
+	}
+	folder, err := collections.RenameFolder(s.userStore(r), r.PathValue("id"), r.PathValue("folder"), request.Name)
+	if err != nil {
+		writeCollectionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, folder)
+}
+
+func (s *Server) handleFolderDelete(w http.ResponseWriter, r *http.Request) {
+	if err := collections.DeleteFolder(s.userStore(r), r.PathValue("id"), r.PathValue("folder")); err != nil {
+		writeCollectionError(w, err)
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+func (s *Server) handleFolderMove(w http.ResponseWriter, r *http.Request) {
+	var request moveRequest
+	if !decodeCollectionBody(w, r, &request) {
+		return
+	}
+	c, err := collections.MoveFolder(s.userStore(r), r.PathValue("id"), r.PathValue("folder"), request.ParentID, request.position())
+	if err != nil {
+		writeCollectionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, c)
+}
+
+func (s *Server) handleRequestCreate(w http.ResponseWriter, r *http.Request) {
+	var request collections.SavedRequest
+	if !decodeCollectionBody(w, r, &request) {
+		return
+	}
+	request.ID = ""
+	saved, err := collections.SaveRequest(s.userStore(r), r.PathValue("id"), request)
+	if err != nil {
+		writeCollectionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusCreated, saved)
+}
+
+func (s *Server) handleRequestUpdate(w http.ResponseWriter, r *http.Request) {
+	var request collections.SavedRequest
+	if !decodeCollectionBody(w, r, &request) {
+		return
+	}
+	request.ID = r.PathValue("request")
+	saved, err := collections.SaveRequest(s.userStore(r), r.PathValue("id"), request)
+	if err != nil {
+		writeCollectionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, saved)
+}
+
+func (s *Server) handleRequestDelete(w http.ResponseWriter, r *http.Request) {
+	if err := collections.DeleteRequest(s.userStore(r), r.PathValue("id"), r.PathValue("request")); err != nil {
+		writeCollectionError(w, err)
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+func (s *Server) handleRequestMove(w http.ResponseWriter, r *http.Request) {
+	var request moveRequest
+	if !decodeCollectionBody(w, r, &request) {
+		return
+	}
+	c, err := collections.MoveRequest(s.userStore(r), r.PathValue("id"), r.PathValue("request"), request.ParentID, request.position())
+	if err != nil {
+		writeCollectionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, c)
+}
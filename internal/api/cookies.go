@@ -0,0 +1,44 @@
+package api
+
+import (
+	"net/http"
+
+	"zone.digit.tommie/internal/cookies"
+)
+
+// cookieJarsResponse lists the names of the stored cookie jars.
+type cookieJarsResponse struct {
+	Jars []string `json:"jars"`
+}
+
+func (s *Server) handleCookieJars(w http.ResponseWriter, r *http.Request) {
+	names, err := cookies.List(s.userStore(r))
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	if names == nil {
+		names = []string{}
+	}
+	writeJSON(w, http.StatusOK, cookieJarsResponse{Jars: names})
+}
+
+func (s *Server) handleCookieJarGet(w http.ResponseWriter, r *http.Request) {
+	jar, err := cookies.Load(s.userStore(r), r.PathValue("name"))
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	if jar.Cookies == nil {
+		jar.Cookies = []cookies.Cookie{}
+	}
+	writeJSON(w, http.StatusOK, jar)
+}
+
+func (s *Server) handleCookieJarClear(w http.ResponseWriter, r *http.Request) {
+	if err := cookies.Clear(s.userStore(r), r.PathValue("name")); err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
@@ -0,0 +1,150 @@
+package api
+
+import (
+	"fmt"
+	"net/http"
+	"runtime"
+	"runtime/pprof"
+	"strconv"
+	"time"
+
+	"zone.digit.tommie/internal/proxy"
+)
+
+const (
+	// defaultProfileSeconds is how long a CPU profile runs when not given.
+	defaultProfileSeconds = 10
+	// maxProfileSeconds bounds how long a CPU profile may run.
+	maxProfileSeconds = 60
+)
+
+// runtimeReport describes the server process, for diagnosing the backend itself.
+type runtimeReport struct {
+	GoVersion  string             `json:"goVersion"`
+	Uptime     uint64             `json:"uptime"` // Milliseconds since the API server started
+	CPUs       int                `json:"cpus"`
+	MaxProcs   int                `json:"maxProcs"`
+	Goroutines int                `json:"goroutines"`
+	CgoCalls   int64              `json:"cgoCalls"`
+	Memory     memoryReport       `json:"memory"`
+	GC         gcReport           `json:"gc"`
+	Service    proxy.ServiceStats `json:"service"`
+}
+
+// memoryReport summarizes the allocator statistics.
+type memoryReport struct {
+	HeapAlloc    uint64 `json:"heapAlloc"` // Bytes of live and not yet swept heap objects
+	HeapInuse    uint64 `json:"heapInuse"`
+	HeapObjects  uint64 `json:"heapObjects"`
+	StackInuse   uint64 `json:"stackInuse"`
+	Sys          uint64 `json:"sys"`        // Bytes obtained from the OS
+	TotalAlloc   uint64 `json:"totalAlloc"` // Cumulative bytes allocated
+	Mallocs      uint64 `json:"mallocs"`
+	Frees        uint64 `json:"frees"`
+	HeapReleased uint64 `json:"heapReleased"`
+}
+
+// gcReport summarizes garbage collection.
+type gcReport struct {
+	Cycles      uint32     `json:"cycles"`
+	PauseTotal  float64    `json:"pauseTotal"` // Milliseconds
+	LastPause   float64    `json:"lastPause"`  // Milliseconds
+	Last        *time.Time `json:"last,omitempty"`
+	NextTarget  uint64     `json:"nextTarget"`  // Heap size triggering the next cycle
+	CPUFraction float64    `json:"cpuFraction"` // Share of CPU time spent in GC since start
+}
+
+// handleDebugRuntime reports runtime statistics, or with ?profile= returns a
+// pprof profile: cpu (sampled for ?seconds=, default 10), heap, allocs,
+// goroutine, block, mutex or threadcreate. Profiles open with `go tool pprof`,
+// which also renders them as flame graphs.
+func (s *Server) handleDebugRuntime(w http.ResponseWriter, r *http.Request) {
+	if name := r.URL.Query().Get("profile"); name != "" {
+		s.writeProfile(w, r, name)
+		return
+	}
+
+	var mem runtime.MemStats
+	runtime.ReadMemStats(&mem)
+
+	report := runtimeReport{
+		GoVersion:  runtime.Version(),
+		Uptime:     uint64(time.Since(s.started).Milliseconds()),
+		CPUs:       runtime.NumCPU(),
+		MaxProcs:   runtime.GOMAXPROCS(0),
+		Goroutines: runtime.NumGoroutine(),
+		CgoCalls:   runtime.NumCgoCall(),
+		Memory: memoryReport{
+			HeapAlloc:    mem.HeapAlloc,
+			HeapInuse:    mem.HeapInuse,
+			HeapObjects:  mem.HeapObjects,
+			StackInuse:   mem.StackInuse,
+			Sys:          mem.Sys,
+			TotalAlloc:   mem.TotalAlloc,
+			Mallocs:      mem.Mallocs,
+			Frees:        mem.Frees,
+			HeapReleased: mem.HeapReleased,
+		},
+		GC: gcReport{
+			Cycles:      mem.NumGC,
+			PauseTotal:  durationMS(time.Duration(mem.PauseTotalNs)),
+			NextTarget:  mem.NextGC,
+			CPUFraction: mem.GCCPUFraction,
+		},
+		Service: s.service.Stats(),
+	}
+	if mem.NumGC > 0 {
+		report.GC.LastPause = durationMS(time.Duration(mem.PauseNs[(mem.NumGC+255)%256]))
+		last := time.Unix(0, int64(mem.LastGC)).UTC()
+		report.GC.Last = &last
+	}
+
+	writeJSON(w, http.StatusOK, report)
+}
+
+// writeProfile writes a pprof profile as a download.
+func (s *Server) writeProfile(w http.ResponseWriter, r *http.Request, name string) {
+	if name != "cpu" {
+		profile := pprof.Lookup(name)
+		if profile == nil {
+			writeError(w, http.StatusBadRequest, fmt.Sprintf("Unknown profile %q", name), "INVALID_REQUEST")
+			return
+		}
+		setProfileHeaders(w, name)
+		profile.WriteTo(w, 0)
+		return
+	}
+
+	seconds := defaultProfileSeconds
+	if value := r.URL.Query().Get("seconds"); value != "" {
+		n, err := strconv.Atoi(value)
+		if err != nil || n < 1 || n > maxProfileSeconds {
+			writeError(w, http.StatusBadRequest, fmt.Sprintf("seconds must be between 1 and %d", maxProfileSeconds), "INVALID_REQUEST")
+			return
+		}
+		seconds = n
+	}
+
+	// The profile is buffered by pprof and only written once stopped, so
+	// failing to start can still be reported as an error
+	setProfileHeaders(w, name)
+	if err := pprof.StartCPUProfile(w); err != nil {
+		w.Header().Del("Content-Disposition")
+		writeError(w, http.StatusConflict, "A CPU profile is already running", "PROFILE_BUSY")
+		return
+	}
+	select {
+	case <-time.After(time.Duration(seconds) * time.Second):
+	case <-r.Context().Done():
+	}
+	pprof.StopCPUProfile()
+}
+
+func setProfileHeaders(w http.ResponseWriter, name string) {
+	w.Header().Set("Content-Type", "application/octet-stream")
+	w.Header().Set("Content-Disposition", fmt.Sprintf(`attachment; filename="%s.pprof"`, name))
+}
+
+func durationMS(d time.Duration) float64 {
+	return float64(d.Microseconds()) / 1000
+}
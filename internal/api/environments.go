@@ -0,0 +1,65 @@
+package api
+
+import (
+	"encoding/json"
+	"net/http"
+
+	"zone.digit.tommie/internal/environments"
+)
+
+// environmentsResponse lists the names of the stored environments.
+type environmentsResponse struct {
+	Environments []string `json:"environments"`
+}
+
+// environmentRequest replaces the variables of an environment.
+type environmentRequest struct {
+	Variables map[string]string `json:"variables"`
+}
+
+func (s *Server) handleEnvironments(w http.ResponseWriter, r *http.Request) {
+	names, err := environments.List(s.userStore(r))
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	if names == nil {
+		names = []string{}
+	}
+	writeJSON(w, http.StatusOK, environmentsResponse{Environments: names})
+}
+
+func (s *Server) handleEnvironmentGet(w http.ResponseWriter, r *http.Request) {
+	env, err := environments.Load(s.userStore(r), r.PathValue("name"))
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	if env == nil {
+		writeError(w, http.StatusNotFound, "Environment not found", "NOT_FOUND")
+		return
+	}
+	writeJSON(w, http.StatusOK, env)
+}
+
+func (s *Server) handleEnvironmentSave(w http.ResponseWriter, r *http.Request) {
+	var request environmentRequest
+	if err := json.NewDecoder(http.MaxBytesReader(w, r.Body, maxCollectionBytes)).Decode(&request); err != nil {
+		writeError(w, http.StatusBadRequest, "Invalid request body: "+err.Error(), "INVALID_REQUEST")
+		return
+	}
+	env, err := environments.Save(s.userStore(r), r.PathValue("name"), request.Variables)
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	writeJSON(w, http.StatusOK, env)
+}
+
+func (s *Server) handleEnvironmentDelete(w http.ResponseWriter, r *http.Request) {
+	if err := environments.Delete(s.userStore(r), r.PathValue("name")); err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
@@ -0,0 +1,114 @@
+package api
+
+import (
+	"encoding/json"
+	"io"
+	"net/http"
+
+	"zone.digit.tommie/internal/har"
+	"zone.digit.tommie/internal/proxy"
+)
+
+// harExportRequest lists the exchanges to export.
+type harExportRequest struct {
+	Exchanges []har.Exchange `json:"exchanges"`
+}
+
+// curlExportResponse holds a generated curl command.
+type curlExportResponse struct {
+	Command string `json:"command"`
+}
+
+func (s *Server) handleExportCurl(w http.ResponseWriter, r *http.Request) {
+	var request proxy.ProxyRequest
+	if err := json.NewDecoder(http.MaxBytesReader(w, r.Body, maxProxyRequestBytes)).Decode(&request); err != nil {
+		writeError(w, http.StatusBadRequest, "Invalid request body: "+err.Error(), "INVALID_REQUEST")
+		return
+	}
+	// ?redact=true replaces credentials by shell variables declared in a preamble
+	if r.URL.Query().Get("redact") == "true" {
+		writeJSON(w, http.StatusOK, s.service.RedactedCurl(request))
+		return
+	}
+	writeJSON(w, http.StatusOK, curlExportResponse{Command: request.ToCurl()})
+}
+
+// curlImportRequest holds a pasted curl command.
+type curlImportRequest struct {
+	Command string `json:"command"`
+}
+
+// handleImportCurl parses a curl command. Referenced files are never read on
+// the server.
+func (s *Server) handleImportCurl(w http.ResponseWriter, r *http.Request) {
+	var request curlImportRequest
+	if err := json.NewDecoder(http.MaxBytesReader(w, r.Body, maxProxyRequestBytes)).Decode(&request); err != nil {
+		writeError(w, http.StatusBadRequest, "Invalid request body: "+err.Error(), "INVALID_REQUEST")
+		return
+	}
+
+	result, err := proxy.ParseCurl(request.Command, nil)
+	if err != nil {
+		writeError(w, http.StatusBadRequest, err.Error(), "INVALID_CURL")
+		return
+	}
+	writeJSON(w, http.StatusOK, result)
+}
+
+func (s *Server) handleExportHAR(w http.ResponseWriter, r *http.Request) {
+	var request harExportRequest
+	if err := json.NewDecoder(http.MaxBytesReader(w, r.Body, maxProxyRequestBytes)).Decode(&request); err != nil {
+		writeError(w, http.StatusBadRequest, "Invalid request body: "+err.Error(), "INVALID_REQUEST")
+		return
+	}
+
+	w.Header().Set("Content-Disposition", `attachment; filename="export.har"`)
+	writeJSON(w, http.StatusOK, har.Export(request.Exchanges))
+}
+
+func (s *Server) handleImportHAR(w http.ResponseWriter, r *http.Request) {
+	mode := r.URL.Query().Get("mode")
+	if mode == har.ModeReplay && !s.cfg.EnableProxy {
+		writeError(w, http.StatusForbidden, "Replay requires the proxy API to be enabled", "PROXY_DISABLED")
+		return
+	}
+
+	data, err := io.ReadAll(http.MaxBytesReader(w, r.Body, maxProxyRequestBytes))
+	if err != nil {
+		writeError(w, http.StatusRequestEntityTooLarge, "Archive too large", "VALUE_TOO_LARGE")
+		return
+	}
+	archive, err := har.Parse(data)
+	if err != nil {
+		writeError(w, http.StatusBadRequest, err.Error(), "INVALID_HAR")
+		return
+	}
+
+	// A replay is cut down to the requests left of the quota, as a session replay is
+	var reserved *reservation
+	if mode == har.ModeReplay {
+		entries := archive.Log.Entries
+		if reserved = s.reserve(w, r, max(len(entries), 1)); reserved == nil {
+			return
+		}
+		archive.Log.Entries = entries[:min(len(entries), reserved.granted)]
+	}
+	result, err := har.Import(archive, mode, s.service, s.userStore(r))
+	if reserved != nil {
+		var used usage
+		if result != nil {
+			for _, entry := range result.Entries {
+				if entry.Replay != nil {
+					used.requests++
+					used.bytes += entry.Replay.Size
+				}
+			}
+		}
+		reserved.settleOrLog(used)
+	}
+	if err != nil {
+		writeError(w, http.StatusBadRequest, err.Error(), "IMPORT_ERROR")
+		return
+	}
+	writeJSON(w, http.StatusOK, result)
+}
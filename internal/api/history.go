@@ -0,0 +1,113 @@
+package api
+
+import (
+	"encoding/json"
+	"fmt"
+	"net/http"
+	"net/url"
+	"strconv"
+
+	"zone.digit.tommie/internal/history"
+)
+
+func (s *Server) handleHistory(w http.ResponseWriter, r *http.Request) {
+	params := r.URL.Query()
+	query := history.Query{
+		Host:   params.Get("host"),
+		Status: params.Get("status"),
+	}
+	var err error
+	if query.Offset, err = queryInt(params, "offset"); err != nil {
+		writeError(w, http.StatusBadRequest, err.Error(), "INVALID_REQUEST")
+		return
+	}
+	if query.Limit, err = queryInt(params, "limit"); err != nil {
+		writeError(w, http.StatusBadRequest, err.Error(), "INVALID_REQUEST")
+		return
+	}
+
+	page, err := history.List(s.userStore(r), query)
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	writeJSON(w, http.StatusOK, page)
+}
+
+func (s *Server) handleHistoryGet(w http.ResponseWriter, r *http.Request) {
+	entry, err := history.Get(s.userStore(r), r.PathValue("id"))
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	if entry == nil {
+		writeError(w, http.StatusNotFound, "History entry not found", "NOT_FOUND")
+		return
+	}
+	writeJSON(w, http.StatusOK, entry)
+}
+
+func (s *Server) handleHistorySearch(w http.ResponseWriter, r *http.Request) {
+	var query history.SearchQuery
+	if err := json.NewDecoder(http.MaxBytesReader(w, r.Body, maxProxyRequestBytes)).Decode(&query); err != nil {
+		writeError(w, http.StatusBadRequest, "Invalid request body: "+err.Error(), "INVALID_REQUEST")
+		return
+	}
+
+	store := s.userStore(r)
+	id := r.PathValue("id")
+	entry, err := history.Get(store, id)
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	if entry == nil {
+		writeError(w, http.StatusNotFound, "History entry not found", "NOT_FOUND")
+		return
+	}
+
+	body, err := history.Body(store, id)
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	if body == nil {
+		writeError(w, http.StatusNotFound, "No response body is stored for this entry", "NOT_FOUND")
+		return
+	}
+	result, err := history.SearchBody(*body, query)
+	if err != nil {
+		writeError(w, http.StatusBadRequest, err.Error(), "INVALID_REQUEST")
+		return
+	}
+	writeJSON(w, http.StatusOK, result)
+}
+
+func (s *Server) handleHistoryRemove(w http.ResponseWriter, r *http.Request) {
+	if err := history.Remove(s.userStore(r), r.PathValue("id")); err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+func (s *Server) handleHistoryClear(w http.ResponseWriter, r *http.Request) {
+	if err := history.Clear(s.userStore(r)); err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+// queryInt parses an optional non-negative integer query parameter.
+func queryInt(params url.Values, name string) (int, error) {
+	value := params.Get(name)
+	if value == "" {
+		return 0, nil
+	}
+	n, err := strconv.Atoi(value)
+	if err != nil || n < 0 {
+		return 0, fmt.Errorf("%s must be a non-negative integer", name)
+	}
+	return n, nil
+}
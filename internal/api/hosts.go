@@ -0,0 +1,54 @@
+package api
+
+import (
+	"encoding/json"
+	"net"
+	"net/http"
+
+	"zone.digit.tommie/internal/hosts"
+)
+
+// hostOverridesResponse lists the stored host overrides.
+type hostOverridesResponse struct {
+	Overrides []hosts.Override `json:"overrides"`
+}
+
+// hostOverrideRequest sets the address of a host override.
+type hostOverrideRequest struct {
+	Address string `json:"address"`
+}
+
+func (s *Server) handleHostOverrides(w http.ResponseWriter, r *http.Request) {
+	overrides, err := hosts.List(s.userStore(r))
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	writeJSON(w, http.StatusOK, hostOverridesResponse{Overrides: overrides})
+}
+
+func (s *Server) handleHostOverrideSet(w http.ResponseWriter, r *http.Request) {
+	var request hostOverrideRequest
+	if err := json.NewDecoder(http.MaxBytesReader(w, r.Body, maxCollectionBytes)).Decode(&request); err != nil {
+		writeError(w, http.StatusBadRequest, "Invalid request body: "+err.Error(), "INVALID_REQUEST")
+		return
+	}
+	if net.ParseIP(request.Address) == nil {
+		writeError(w, http.StatusBadRequest, "Address must be an IP address", "INVALID_REQUEST")
+		return
+	}
+	override, err := hosts.Set(s.userStore(r), r.PathValue("host"), request.Address)
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	writeJSON(w, http.StatusOK, override)
+}
+
+func (s *Server) handleHostOverrideRemove(w http.ResponseWriter, r *http.Request) {
+	if err := hosts.Remove(s.userStore(r), r.PathValue("host")); err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
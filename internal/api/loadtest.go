@@ -0,0 +1,59 @@
+package api
+
+import (
+	"encoding/json"
+	"fmt"
+	"net/http"
+	"strings"
+
+	"zone.digit.tommie/internal/proxy"
+)
+
+// handleLoadTest runs a load test. Clients accepting text/event-stream get
+// a "progress" event with the stats so far while it runs, then the final
+// "response"; others get the final stats as JSON. The test stops when the
+// client disconnects.
+func (s *Server) handleLoadTest(w http.ResponseWriter, r *http.Request) {
+	var test proxy.LoadTestRequest
+	if err := json.NewDecoder(http.MaxBytesReader(w, r.Body, maxProxyRequestBytes)).Decode(&test); err != nil {
+		writeError(w, http.StatusBadRequest, "Invalid request body: "+err.Error(), "INVALID_REQUEST")
+		return
+	}
+
+	// Iterations are capped to the requests left of the quota
+	planned := test.MaxRequests()
+	reservation := s.reserve(w, r, planned)
+	if reservation == nil {
+		return
+	}
+	if granted := reservation.granted; granted < planned {
+		test.Iterations = &granted
+	}
+
+	var onProgress func(proxy.LoadTestResponse)
+	flusher, ok := w.(http.Flusher)
+	stream := ok && strings.Contains(r.Header.Get("Accept"), "text/event-stream")
+	if stream {
+		w.Header().Set("Content-Type", "text/event-stream")
+		w.Header().Set("Cache-Control", "no-cache")
+		w.WriteHeader(http.StatusOK)
+		flusher.Flush()
+		onProgress = func(progress proxy.LoadTestResponse) {
+			data, _ := json.Marshal(progress)
+			fmt.Fprintf(w, "event: progress\ndata: %s\n\n", data)
+			flusher.Flush()
+		}
+	}
+
+	result := s.service.ExecuteLoadTest(r.Context(), s.userStore(r), test, onProgress)
+
+	reservation.settleOrLog(usage{requests: result.Completed, bytes: result.Bytes})
+
+	if !stream {
+		writeJSON(w, http.StatusOK, result)
+		return
+	}
+	data, _ := json.Marshal(result)
+	fmt.Fprintf(w, "event: response\ndata: %s\n\n", data)
+	flusher.Flush()
+}
@@ -0,0 +1,59 @@
+package api
+
+import (
+	"net/http"
+	"strings"
+
+	"zone.digit.tommie/internal/i18n"
+)
+
+// statelessPrefixes are POST routes that only transform their input and stay
+// available in read-only mode. Scripts are not among them: they run code on
+// the server rather than transform a document.
+var statelessPrefixes = []string{"/api/export/", "/api/import/curl", "/api/tools/"}
+
+// readOnly rejects mutating requests when the server runs in read-only mode.
+// Safe methods are always allowed so recorded data can still be viewed.
+func (s *Server) readOnly(next http.Handler) http.Handler {
+	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if s.cfg.ReadOnly && !isSafeMethod(r.Method) && !isStateless(r.URL.Path) {
+			writeError(w, http.StatusForbidden, "Server is running in read-only mode", "READ_ONLY")
+			return
+		}
+		next.ServeHTTP(w, r)
+	})
+}
+
+// isSafeMethod reports whether an HTTP method never modifies server state.
+func isSafeMethod(method string) bool {
+	switch method {
+	case http.MethodGet, http.MethodHead, http.MethodOptions:
+		return true
+	default:
+		return false
+	}
+}
+
+// isStateless reports whether a route never modifies server state regardless of method.
+func isStateless(path string) bool {
+	for _, prefix := range statelessPrefixes {
+		if strings.HasPrefix(path, prefix) {
+			return true
+		}
+	}
+	// Searching history reads a stored body
+	return strings.HasPrefix(path, "/api/history/") && strings.HasSuffix(path, "/search")
+}
+
+// localize picks the locale of error messages from the Accept-Language
+// header, falling back to the configured locale. Error codes stay English.
+func (s *Server) localize(next http.Handler) http.Handler {
+	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		locale := i18n.Match(r.Header.Get("Accept-Language"), s.cfg.Locale)
+		if locale != "" && locale != i18n.Default {
+			w = &localeWriter{ResponseWriter: w, locale: locale}
+		}
+		w.Header().Add("Vary", "Accept-Language")
+		next.ServeHTTP(w, r)
+	})
+}
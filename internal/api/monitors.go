@@ -0,0 +1,139 @@
+package api
+
+import (
+	"errors"
+	"net/http"
+	"time"
+
+	"zone.digit.tommie/internal/monitors"
+)
+
+// uptimeResponse reports the uptime of a monitor over each window.
+type uptimeResponse struct {
+	Windows []monitors.Uptime `json:"windows"`
+}
+
+// writeMonitorError maps monitor errors to responses.
+func writeMonitorError(w http.ResponseWriter, err error) {
+	var validation *monitors.ValidationError
+	switch {
+	case errors.Is(err, monitors.ErrNotFound):
+		writeError(w, http.StatusNotFound, "Monitor not found", "NOT_FOUND")
+	case errors.As(err, &validation):
+		writeError(w, http.StatusBadRequest, validation.Message, "INVALID_REQUEST")
+	default:
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+	}
+}
+
+// userNamespace returns the storage namespace of the requesting user, as
+// used by userStore; empty without auth.
+func (s *Server) userNamespace(r *http.Request) string {
+	user, _ := userFromContext(r.Context())
+	return user
+}
+
+func (s *Server) handleMonitors(w http.ResponseWriter, r *http.Request) {
+	list, err := monitors.List(s.userStore(r))
+	if err != nil {
+		writeMonitorError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, list)
+}
+
+func (s *Server) handleMonitorCreate(w http.ResponseWriter, r *http.Request) {
+	var request monitors.Monitor
+	if !decodeCollectionBody(w, r, &request) {
+		return
+	}
+	m, err := monitors.Create(s.userStore(r), request)
+	if err != nil {
+		writeMonitorError(w, err)
+		return
+	}
+	s.monitors.Schedule(s.userNamespace(r), *m)
+	writeJSON(w, http.StatusCreated, m)
+}
+
+func (s *Server) handleMonitorGet(w http.ResponseWriter, r *http.Request) {
+	m, err := monitors.Get(s.userStore(r), r.PathValue("id"))
+	if err != nil {
+		writeMonitorError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, m)
+}
+
+func (s *Server) handleMonitorUpdate(w http.ResponseWriter, r *http.Request) {
+	var request monitors.Monitor
+	if !decodeCollectionBody(w, r, &request) {
+		return
+	}
+	m, err := monitors.Update(s.userStore(r), r.PathValue("id"), request)
+	if err != nil {
+		writeMonitorError(w, err)
+		return
+	}
+	s.monitors.Schedule(s.userNamespace(r), *m)
+	writeJSON(w, http.StatusOK, m)
+}
+
+func (s *Server) handleMonitorDelete(w http.ResponseWriter, r *http.Request) {
+	id := r.PathValue("id")
+	s.monitors.Unschedule(s.userNamespace(r), id)
+	if err := monitors.Delete(s.userStore(r), id); err != nil {
+		writeMonitorError(w, err)
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+func (s *Server) handleMonitorCheck(w http.ResponseWriter, r *http.Request) {
+	result, err := s.monitors.Check(s.userNamespace(r), r.PathValue("id"))
+	if err != nil {
+		writeMonitorError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, result)
+}
+
+func (s *Server) handleMonitorResults(w http.ResponseWriter, r *http.Request) {
+	params := r.URL.Query()
+	offset, err := queryInt(params, "offset")
+	if err != nil {
+		writeError(w, http.StatusBadRequest, err.Error(), "INVALID_REQUEST")
+		return
+	}
+	limit, err := queryInt(params, "limit")
+	if err != nil {
+		writeError(w, http.StatusBadRequest, err.Error(), "INVALID_REQUEST")
+		return
+	}
+
+	store, id := s.userStore(r), r.PathValue("id")
+	if _, err := monitors.Get(store, id); err != nil {
+		writeMonitorError(w, err)
+		return
+	}
+	page, err := monitors.Results(store, id, offset, limit)
+	if err != nil {
+		writeMonitorError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, page)
+}
+
+func (s *Server) handleMonitorUptime(w http.ResponseWriter, r *http.Request) {
+	store, id := s.userStore(r), r.PathValue("id")
+	if _, err := monitors.Get(store, id); err != nil {
+		writeMonitorError(w, err)
+		return
+	}
+	windows, err := monitors.Stats(store, id, time.Now())
+	if err != nil {
+		writeMonitorError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, uptimeResponse{Windows: windows})
+}
@@ -0,0 +1,223 @@
+package api
+
+import (
+	"encoding/json"
+	"fmt"
+	"mime"
+	"net/http"
+	"os"
+
+	"zone.digit.tommie/internal/proxy"
+)
+
+// maxProxyRequestBytes limits the size of a proxy request payload.
+const maxProxyRequestBytes = 50 << 20
+
+func (s *Server) handleProxy(w http.ResponseWriter, r *http.Request) {
+	var request proxy.ProxyRequest
+	if !decodeProxyBody(w, r, &request) {
+		return
+	}
+	s.metered(w, r, 1, func() (interface{}, usage) {
+		response := s.service.ExecuteContext(r.Context(), s.userStore(r), request)
+		return response, responsesUsage(response)
+	})
+}
+
+// handleProxyStream executes a request like handleProxy but answers with
+// server-sent events: an "event" for each event of a text/event-stream
+// response as it arrives, then the full "response".
+func (s *Server) handleProxyStream(w http.ResponseWriter, r *http.Request) {
+	var request proxy.ProxyRequest
+	if !decodeProxyBody(w, r, &request) {
+		return
+	}
+
+	flusher, ok := w.(http.Flusher)
+	if !ok {
+		writeError(w, http.StatusInternalServerError, "Streaming is not supported", "STREAMING_UNSUPPORTED")
+		return
+	}
+	reservation := s.reserve(w, r, 1)
+	if reservation == nil {
+		return
+	}
+	w.Header().Set("Content-Type", "text/event-stream")
+	w.Header().Set("Cache-Control", "no-cache")
+	w.WriteHeader(http.StatusOK)
+	flusher.Flush()
+
+	locale := responseLocale(w)
+	send := func(event string, v interface{}) {
+		proxy.Localize(&v, locale)
+		data, _ := json.Marshal(v)
+		fmt.Fprintf(w, "event: %s\ndata: %s\n\n", event, data)
+		flusher.Flush()
+	}
+	response := s.service.ExecuteStream(r.Context(), s.userStore(r), request, func(event proxy.ServerSentEvent) {
+		send("event", event)
+	})
+	reservation.settleOrLog(responsesUsage(response))
+
+	send("response", response)
+}
+
+func (s *Server) handleProxyBatch(w http.ResponseWriter, r *http.Request) {
+	var batch proxy.BatchRequest
+	if !decodeProxyBody(w, r, &batch) {
+		return
+	}
+	if len(batch.Requests) > proxy.MaxBatchRequests {
+		writeError(w, http.StatusBadRequest, fmt.Sprintf("Batch exceeds %d requests", proxy.MaxBatchRequests), "BATCH_TOO_LARGE")
+		return
+	}
+	s.meteredRun(w, r, &batch, func() (interface{}, usage) {
+		result := s.service.ExecuteBatch(r.Context(), s.userStore(r), batch)
+		return result, responsesUsage(result.Results...)
+	})
+}
+
+func (s *Server) handleProxyDataRun(w http.ResponseWriter, r *http.Request) {
+	var run proxy.DataRunRequest
+	if !decodeProxyBody(w, r, &run) {
+		return
+	}
+	s.meteredRun(w, r, &run, func() (interface{}, usage) {
+		result := s.service.ExecuteDataRun(r.Context(), s.userStore(r), run)
+		used := usage{requests: len(result.Rows)}
+		for _, row := range result.Rows {
+			used.bytes += row.Size
+		}
+		return result, used
+	})
+}
+
+func (s *Server) handleProxyBurst(w http.ResponseWriter, r *http.Request) {
+	var burst proxy.BurstRequest
+	if !decodeProxyBody(w, r, &burst) {
+		return
+	}
+	s.meteredRun(w, r, &burst, func() (interface{}, usage) {
+		result := s.service.ExecuteBurst(r.Context(), s.userStore(r), burst)
+		// Samples carry no sizes, so each counts as a request without bytes
+		return result, usage{requests: len(result.Samples)}
+	})
+}
+
+func (s *Server) handleProxyIdempotency(w http.ResponseWriter, r *http.Request) {
+	var check proxy.IdempotencyRequest
+	if !decodeProxyBody(w, r, &check) {
+		return
+	}
+	s.metered(w, r, 2, func() (interface{}, usage) {
+		result := s.service.ExecuteIdempotencyCheck(s.userStore(r), check)
+		return result, responsesUsage(result.First, result.Second)
+	})
+}
+
+// handleGraphQLSchema introspects the GraphQL endpoint of a request and
+// returns its schema as SDL.
+func (s *Server) handleGraphQLSchema(w http.ResponseWriter, r *http.Request) {
+	var request proxy.ProxyRequest
+	if !decodeProxyBody(w, r, &request) {
+		return
+	}
+	s.metered(w, r, 1, func() (interface{}, usage) {
+		return s.service.IntrospectGraphQL(s.userStore(r), request), usage{requests: 1}
+	})
+}
+
+func (s *Server) handleProxyETagAudit(w http.ResponseWriter, r *http.Request) {
+	var audit proxy.ETagAuditRequest
+	if !decodeProxyBody(w, r, &audit) {
+		return
+	}
+	s.metered(w, r, audit.MaxRequests(), func() (interface{}, usage) {
+		result := s.service.ExecuteETagAudit(s.userStore(r), audit)
+		used := usage{requests: len(result.Samples)}
+		for _, sample := range result.Samples {
+			used.bytes += sample.Size
+		}
+		return result, used
+	})
+}
+
+func (s *Server) handleProxyPipeline(w http.ResponseWriter, r *http.Request) {
+	var pipeline proxy.PipelineRequest
+	if !decodeProxyBody(w, r, &pipeline) {
+		return
+	}
+	s.meteredRun(w, r, &pipeline, func() (interface{}, usage) {
+		result := s.service.ExecutePipeline(pipeline)
+		var used usage
+		for _, sample := range result.Results {
+			if sample.Status != nil {
+				used.requests++
+				used.bytes += sample.Size
+			}
+		}
+		return result, used
+	})
+}
+
+func (s *Server) handleGRPC(w http.ResponseWriter, r *http.Request) {
+	var call proxy.GRPCRequest
+	if !decodeProxyBody(w, r, &call) {
+		return
+	}
+	s.metered(w, r, 1, func() (interface{}, usage) {
+		result := s.service.ExecuteGRPC(s.userStore(r), call)
+		if result.HTTP == nil {
+			return result, usage{}
+		}
+		return result, usage{requests: 1, bytes: result.RequestSize + result.HTTP.Size}
+	})
+}
+
+func (s *Server) handleDNSLookup(w http.ResponseWriter, r *http.Request) {
+	var lookup proxy.DNSLookupRequest
+	if !decodeProxyBody(w, r, &lookup) {
+		return
+	}
+	s.metered(w, r, 1, func() (interface{}, usage) {
+		return s.service.LookupDNS(lookup), usage{requests: 1}
+	})
+}
+
+func (s *Server) handleEmailPosture(w http.ResponseWriter, r *http.Request) {
+	var lookup proxy.EmailPostureRequest
+	if !decodeProxyBody(w, r, &lookup) {
+		return
+	}
+	s.metered(w, r, 1, func() (interface{}, usage) {
+		return s.service.LookupEmailPosture(lookup), usage{requests: 1}
+	})
+}
+
+// handleDownload serves a response body that a request streamed to disk.
+func (s *Server) handleDownload(w http.ResponseWriter, r *http.Request) {
+	download, ok := s.service.Download(r.PathValue("id"))
+	if !ok {
+		writeError(w, http.StatusNotFound, "Download not found or expired", "NOT_FOUND")
+		return
+	}
+	file, err := os.Open(download.Path)
+	if err != nil {
+		writeError(w, http.StatusNotFound, "Download not found or expired", "NOT_FOUND")
+		return
+	}
+	defer file.Close()
+	info, err := file.Stat()
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "DOWNLOAD_ERROR")
+		return
+	}
+
+	contentType := download.ContentType
+	if contentType == "" {
+		contentType = "application/octet-stream"
+	}
+	w.Header().Set("Content-Type", contentType)
+	w.Header().Set("Content-Disposition", mime.FormatMediaType("attachment", map[string]string{"filename": download.FileName}))
+	http.ServeContent(w, r, download.FileName, info.ModTime(), file)
+}
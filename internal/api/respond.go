@@ -0,0 +1,51 @@
+package api
+
+import (
+	"encoding/json"
+	"net/http"
+
+	"zone.digit.tommie/internal/proxy"
+)
+
+// writeJSON writes a value as a JSON response with the given status code.
+// Error messages are translated to the locale chosen by the localize middleware.
+func writeJSON(w http.ResponseWriter, status int, v interface{}) {
+	if locale := responseLocale(w); locale != "" {
+		proxy.Localize(&v, locale)
+	}
+	w.Header().Set("Content-Type", "application/json")
+	w.WriteHeader(status)
+	json.NewEncoder(w).Encode(v)
+}
+
+// writeError writes an error response using the extension protocol error shape.
+func writeError(w http.ResponseWriter, status int, message, code string) {
+	writeJSON(w, status, proxy.NewErrorResponse(message, code))
+}
+
+// localeWriter carries the locale of a response to writeJSON.
+type localeWriter struct {
+	http.ResponseWriter
+	locale string
+}
+
+// Flush lets streaming handlers flush through the wrapper.
+func (w *localeWriter) Flush() {
+	if flusher, ok := w.ResponseWriter.(http.Flusher); ok {
+		flusher.Flush()
+	}
+}
+
+// Unwrap exposes the wrapped writer to http.ResponseController.
+func (w *localeWriter) Unwrap() http.ResponseWriter {
+	return w.ResponseWriter
+}
+
+// responseLocale returns the locale error messages of w are translated to,
+// or "" for English.
+func responseLocale(w http.ResponseWriter) string {
+	if lw, ok := w.(*localeWriter); ok {
+		return lw.locale
+	}
+	return ""
+}
@@ -0,0 +1,35 @@
+package api
+
+import (
+	"encoding/json"
+	"net/http"
+
+	"zone.digit.tommie/internal/proxy"
+)
+
+func (s *Server) handleScript(w http.ResponseWriter, r *http.Request) {
+	var request proxy.ScriptRequest
+	if err := json.NewDecoder(http.MaxBytesReader(w, r.Body, maxProxyRequestBytes)).Decode(&request); err != nil {
+		writeError(w, http.StatusBadRequest, "Invalid request body: "+err.Error(), "INVALID_REQUEST")
+		return
+	}
+	writeJSON(w, http.StatusOK, s.service.EvaluateScript(r.Context(), s.userStore(r), request))
+}
+
+func (s *Server) handleJWT(w http.ResponseWriter, r *http.Request) {
+	var request proxy.JWTRequest
+	if err := json.NewDecoder(http.MaxBytesReader(w, r.Body, maxProxyRequestBytes)).Decode(&request); err != nil {
+		writeError(w, http.StatusBadRequest, "Invalid request body: "+err.Error(), "INVALID_REQUEST")
+		return
+	}
+	writeJSON(w, http.StatusOK, s.service.DecodeJWT(request))
+}
+
+func (s *Server) handleJSONPath(w http.ResponseWriter, r *http.Request) {
+	var request proxy.JSONPathRequest
+	if err := json.NewDecoder(http.MaxBytesReader(w, r.Body, maxProxyRequestBytes)).Decode(&request); err != nil {
+		writeError(w, http.StatusBadRequest, "Invalid request body: "+err.Error(), "INVALID_REQUEST")
+		return
+	}
+	writeJSON(w, http.StatusOK, s.service.EvaluateJSONPath(request))
+}
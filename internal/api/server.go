@@ -0,0 +1,224 @@
+// Package api provides the JSON API served alongside the static frontend.
+package api
+
+import (
+	"net/http"
+	"time"
+
+	"zone.digit.tommie/internal/config"
+	"zone.digit.tommie/internal/monitors"
+	"zone.digit.tommie/internal/proxy"
+	"zone.digit.tommie/internal/storage"
+)
+
+// Server holds the dependencies shared by the API handlers.
+type Server struct {
+	cfg      *config.Config
+	store    storage.Store
+	service  *proxy.HTTPService
+	usage    *usageTracker
+	mux      *http.ServeMux
+	started  time.Time
+	watcher  *config.Watcher     // Nil unless a config file is watched
+	monitors *monitors.Scheduler // Nil unless the proxy is enabled
+}
+
+// New creates a new API server and registers its routes.
+func New(cfg *config.Config, store storage.Store, service *proxy.HTTPService) *Server {
+	s := &Server{
+		cfg:     cfg,
+		store:   store,
+		service: service,
+		usage:   newUsageTracker(store),
+		mux:     http.NewServeMux(),
+		started: time.Now(),
+	}
+	if cfg.EnableProxy {
+		s.monitors = monitors.NewScheduler(store, service.ExecuteIn)
+	}
+	s.routes()
+	return s
+}
+
+// StartMonitors schedules the checks of the stored monitors, returning how
+// many were scheduled. Monitors need the proxy API.
+func (s *Server) StartMonitors() (int, error) {
+	if s.monitors == nil {
+		return 0, nil
+	}
+	return s.monitors.Start()
+}
+
+// WatchConfig takes reloadable settings, such as quotas, from a config file
+// watcher and reports its status on the health endpoint.
+func (s *Server) WatchConfig(w *config.Watcher) {
+	s.watcher = w
+}
+
+// quotas returns the daily request and byte quotas in effect.
+func (s *Server) quotas() (int, int) {
+	cfg := s.cfg
+	if s.watcher != nil {
+		cfg = s.watcher.Current()
+	}
+	return cfg.DailyRequestQuota, cfg.DailyByteQuota
+}
+
+// Handler returns the API handler wrapped in its middleware chain.
+func (s *Server) Handler() http.Handler {
+	return s.localize(s.authenticate(s.readOnly(s.mux)))
+}
+
+// routes registers all API routes.
+func (s *Server) routes() {
+	s.mux.HandleFunc("GET /api/health", s.handleHealth)
+
+	if s.cfg.EnableProxy {
+		s.mux.HandleFunc("POST /api/proxy", s.handleProxy)
+		s.mux.HandleFunc("POST /api/proxy/batch", s.handleProxyBatch)
+		s.mux.HandleFunc("POST /api/proxy/burst", s.handleProxyBurst)
+		s.mux.HandleFunc("POST /api/proxy/data-run", s.handleProxyDataRun)
+		s.mux.HandleFunc("POST /api/proxy/idempotency", s.handleProxyIdempotency)
+		s.mux.HandleFunc("POST /api/proxy/etag-audit", s.handleProxyETagAudit)
+		s.mux.HandleFunc("POST /api/proxy/pipeline", s.handleProxyPipeline)
+		s.mux.HandleFunc("POST /api/proxy/stream", s.handleProxyStream)
+		s.mux.HandleFunc("POST /api/loadtest", s.handleLoadTest)
+		s.mux.HandleFunc("POST /api/graphql/schema", s.handleGraphQLSchema)
+		s.mux.HandleFunc("POST /api/grpc", s.handleGRPC)
+		s.mux.HandleFunc("POST /api/dns", s.handleDNSLookup)
+		s.mux.HandleFunc("POST /api/dns/email", s.handleEmailPosture)
+		s.mux.HandleFunc("GET /api/downloads/{id}", s.handleDownload)
+		s.mux.HandleFunc("POST /api/script", s.handleScript)
+
+		s.mux.HandleFunc("POST /api/ws", s.handleWebSocketOpen)
+		s.mux.HandleFunc("GET /api/ws/{id}/frames", s.handleWebSocketFrames)
+		s.mux.HandleFunc("POST /api/ws/{id}/send", s.handleWebSocketSend)
+		s.mux.HandleFunc("DELETE /api/ws/{id}", s.handleWebSocketClose)
+
+		s.mux.HandleFunc("GET /api/monitors", s.handleMonitors)
+		s.mux.HandleFunc("POST /api/monitors", s.handleMonitorCreate)
+		s.mux.HandleFunc("GET /api/monitors/{id}", s.handleMonitorGet)
+		s.mux.HandleFunc("PUT /api/monitors/{id}", s.handleMonitorUpdate)
+		s.mux.HandleFunc("DELETE /api/monitors/{id}", s.handleMonitorDelete)
+		s.mux.HandleFunc("POST /api/monitors/{id}/check", s.handleMonitorCheck)
+		s.mux.HandleFunc("GET /api/monitors/{id}/results", s.handleMonitorResults)
+		s.mux.HandleFunc("GET /api/monitors/{id}/uptime", s.handleMonitorUptime)
+	}
+
+	s.mux.HandleFunc("GET /api/storage/{store}", s.handleStorageKeys)
+	s.mux.HandleFunc("DELETE /api/storage/{store}", s.handleStorageClear)
+	s.mux.HandleFunc("GET /api/storage/{store}/{key}", s.handleStorageGet)
+	s.mux.HandleFunc("PUT /api/storage/{store}/{key}", s.handleStorageSet)
+	s.mux.HandleFunc("DELETE /api/storage/{store}/{key}", s.handleStorageRemove)
+
+	s.mux.HandleFunc("GET /api/cookie-jars", s.handleCookieJars)
+	s.mux.HandleFunc("GET /api/cookie-jars/{name}", s.handleCookieJarGet)
+	s.mux.HandleFunc("DELETE /api/cookie-jars/{name}", s.handleCookieJarClear)
+
+	s.mux.HandleFunc("GET /api/history", s.handleHistory)
+	s.mux.HandleFunc("DELETE /api/history", s.handleHistoryClear)
+	s.mux.HandleFunc("GET /api/history/{id}", s.handleHistoryGet)
+	s.mux.HandleFunc("DELETE /api/history/{id}", s.handleHistoryRemove)
+	s.mux.HandleFunc("POST /api/history/{id}/search", s.handleHistorySearch)
+
+	s.mux.HandleFunc("GET /api/sessions", s.handleSessions)
+	s.mux.HandleFunc("POST /api/sessions/start", s.handleSessionStart)
+	s.mux.HandleFunc("POST /api/sessions/stop", s.handleSessionStop)
+	s.mux.HandleFunc("GET /api/sessions/active", s.handleSessionActive)
+	s.mux.HandleFunc("POST /api/sessions/import", s.handleSessionImport)
+	s.mux.HandleFunc("GET /api/sessions/{id}", s.handleSessionGet)
+	s.mux.HandleFunc("PUT /api/sessions/{id}", s.handleSessionRename)
+	s.mux.HandleFunc("DELETE /api/sessions/{id}", s.handleSessionDelete)
+	s.mux.HandleFunc("POST /api/sessions/{id}/notes", s.handleSessionNote)
+	s.mux.HandleFunc("GET /api/sessions/{id}/har", s.handleSessionHAR)
+	s.mux.HandleFunc("GET /api/sessions/{id}/bundle", s.handleSessionBundle)
+	s.mux.HandleFunc("POST /api/sessions/{id}/replay", s.handleSessionReplay)
+
+	s.mux.HandleFunc("POST /api/tools/jwt", s.handleJWT)
+	s.mux.HandleFunc("POST /api/tools/jsonpath", s.handleJSONPath)
+
+	s.mux.HandleFunc("GET /api/environments", s.handleEnvironments)
+	s.mux.HandleFunc("GET /api/environments/{name}", s.handleEnvironmentGet)
+	s.mux.HandleFunc("PUT /api/environments/{name}", s.handleEnvironmentSave)
+	s.mux.HandleFunc("DELETE /api/environments/{name}", s.handleEnvironmentDelete)
+
+	s.mux.HandleFunc("GET /api/hosts", s.handleHostOverrides)
+	s.mux.HandleFunc("PUT /api/hosts/{host}", s.handleHostOverrideSet)
+	s.mux.HandleFunc("DELETE /api/hosts/{host}", s.handleHostOverrideRemove)
+
+	s.mux.HandleFunc("GET /api/collections", s.handleCollections)
+	s.mux.HandleFunc("POST /api/collections", s.handleCollectionCreate)
+	s.mux.HandleFunc("GET /api/collections/{id}", s.handleCollectionGet)
+	s.mux.HandleFunc("PUT /api/collections/{id}", s.handleCollectionUpdate)
+	s.mux.HandleFunc("DELETE /api/collections/{id}", s.handleCollectionDelete)
+	s.mux.HandleFunc("POST /api/collections/{id}/folders", s.handleFolderCreate)
+	s.mux.HandleFunc("PUT /api/collections/{id}/folders/{folder}", s.handleFolderRename)
+	s.mux.HandleFunc("DELETE /api/collections/{id}/folders/{folder}", s.handleFolderDelete)
+	s.mux.HandleFunc("POST /api/collections/{id}/folders/{folder}/move", s.handleFolderMove)
+	s.mux.HandleFunc("POST /api/collections/{id}/requests", s.handleRequestCreate)
+	s.mux.HandleFunc("PUT /api/collections/{id}/requests/{request}", s.handleRequestUpdate)
+	s.mux.HandleFunc("DELETE /api/collections/{id}/requests/{request}", s.handleRequestDelete)
+	s.mux.HandleFunc("POST /api/collections/{id}/requests/{request}/move", s.handleRequestMove)
+
+	s.mux.HandleFunc("POST /api/export/har", s.handleExportHAR)
+	s.mux.HandleFunc("POST /api/export/curl", s.handleExportCurl)
+	s.mux.HandleFunc("POST /api/import/har", s.handleImportHAR)
+	s.mux.HandleFunc("POST /api/import/curl", s.handleImportCurl)
+
+	s.mux.Handle("GET /api/admin/users", s.requireAdmin(http.HandlerFunc(s.handleAdminUsers)))
+	s.mux.Handle("GET /api/admin/usage", s.requireAdmin(http.HandlerFunc(s.handleAdminUsage)))
+	s.mux.Handle("GET /api/debug/runtime", s.requireAdmin(http.HandlerFunc(s.handleDebugRuntime)))
+}
+
+// healthResponse is returned by the health endpoint.
+type healthResponse struct {
+	Status   string        `json:"status"`
+	ReadOnly bool          `json:"readOnly"`
+	Auth     bool          `json:"auth"`
+	Proxy    bool          `json:"proxy"`
+	Protocol int           `json:"protocolVersion"`  // Version of the proxy response shape
+	Reload   *reloadStatus `json:"reload,omitempty"` // Present when a config file is watched
+}
+
+// reloadStatus reports the config file watcher.
+type reloadStatus struct {
+	File           string     `json:"file"`
+	LastCheck      *time.Time `json:"lastCheck,omitempty"`
+	LastReload     *time.Time `json:"lastReload,omitempty"`
+	Reloads        int        `json:"reloads"`
+	Applied        []string   `json:"applied,omitempty"`        // Variables changed by the last reload
+	PendingRestart []string   `json:"pendingRestart,omitempty"` // Changed variables that need a restart
+	Error          *string    `json:"error,omitempty"`          // Why the last change was not applied
+}
+
+func (s *Server) handleHealth(w http.ResponseWriter, r *http.Request) {
+	health := healthResponse{
+		Status:   "ok",
+		ReadOnly: s.cfg.ReadOnly,
+		Auth:     s.cfg.AuthEnabled(),
+		Proxy:    s.cfg.EnableProxy,
+		Protocol: proxy.ProtocolVersion,
+	}
+	if s.watcher != nil {
+		status := s.watcher.Status()
+		health.Reload = &reloadStatus{
+			File:           status.File,
+			LastCheck:      optionalTime(status.LastCheck),
+			LastReload:     optionalTime(status.LastReload),
+			Reloads:        status.Reloads,
+			Applied:        status.Applied,
+			PendingRestart: status.PendingRestart,
+		}
+		if status.Error != "" {
+			health.Reload.Error = &status.Error
+		}
+	}
+	writeJSON(w, http.StatusOK, health)
+}
+
+func optionalTime(t time.Time) *time.Time {
+	if t.IsZero() {
+		return nil
+	}
+	return &t
+}
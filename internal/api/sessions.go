@@ -0,0 +1,201 @@
+package api
+
+import (
+	"encoding/json"
+	"errors"
+	"net/http"
+
+	"zone.digit.tommie/internal/har"
+	"zone.digit.tommie/internal/sessions"
+)
+
+// sessionRequest names a session when it is started or renamed.
+type sessionRequest struct {
+	Name string `json:"name"`
+}
+
+// sessionNoteRequest holds a note to add to a session.
+type sessionNoteRequest struct {
+	Text string `json:"text"`
+}
+
+// writeSessionError maps session errors to responses.
+func writeSessionError(w http.ResponseWriter, err error) {
+	var validation *sessions.ValidationError
+	switch {
+	case errors.Is(err, sessions.ErrNotFound):
+		writeError(w, http.StatusNotFound, "Session not found", "NOT_FOUND")
+	case errors.As(err, &validation):
+		writeError(w, http.StatusBadRequest, validation.Message, "INVALID_REQUEST")
+	default:
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+	}
+}
+
+func (s *Server) handleSessions(w http.ResponseWriter, r *http.Request) {
+	list, err := sessions.List(s.userStore(r))
+	if err != nil {
+		writeSessionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, list)
+}
+
+// handleSessionStart starts recording a session, stopping the one being
+// recorded.
+func (s *Server) handleSessionStart(w http.ResponseWriter, r *http.Request) {
+	var request sessionRequest
+	if !decodeCollectionBody(w, r, &request) {
+		return
+	}
+	session, err := sessions.Start(s.userStore(r), request.Name)
+	if err != nil {
+		writeSessionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusCreated, session)
+}
+
+func (s *Server) handleSessionStop(w http.ResponseWriter, r *http.Request) {
+	session, err := sessions.Stop(s.userStore(r))
+	if err != nil {
+		writeSessionError(w, err)
+		return
+	}
+	if session == nil {
+		writeError(w, http.StatusConflict, "No session is being recorded", "NOT_RECORDING")
+		return
+	}
+	writeJSON(w, http.StatusOK, session)
+}
+
+// handleSessionActive returns the session being recorded, or null.
+func (s *Server) handleSessionActive(w http.ResponseWriter, r *http.Request) {
+	session, err := sessions.Active(s.userStore(r))
+	if err != nil {
+		writeSessionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, session)
+}
+
+func (s *Server) handleSessionGet(w http.ResponseWriter, r *http.Request) {
+	session, err := sessions.Get(s.userStore(r), r.PathValue("id"))
+	if err != nil {
+		writeSessionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, session)
+}
+
+func (s *Server) handleSessionRename(w http.ResponseWriter, r *http.Request) {
+	var request sessionRequest
+	if !decodeCollectionBody(w, r, &request) {
+		return
+	}
+	session, err := sessions.Rename(s.userStore(r), r.PathValue("id"), request.Name)
+	if err != nil {
+		writeSessionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, session)
+}
+
+func (s *Server) handleSessionDelete(w http.ResponseWriter, r *http.Request) {
+	if err := sessions.Delete(s.userStore(r), r.PathValue("id")); err != nil {
+		writeSessionError(w, err)
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+func (s *Server) handleSessionNote(w http.ResponseWriter, r *http.Request) {
+	var request sessionNoteRequest
+	if !decodeCollectionBody(w, r, &request) {
+		return
+	}
+	session, err := sessions.AddNote(s.userStore(r), r.PathValue("id"), request.Text)
+	if err != nil {
+		writeSessionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, session)
+}
+
+// handleSessionHAR exports the exchanges of a session as an HTTP Archive.
+func (s *Server) handleSessionHAR(w http.ResponseWriter, r *http.Request) {
+	store, id := s.userStore(r), r.PathValue("id")
+	bundle, err := sessions.Export(store, id)
+	if err != nil {
+		writeSessionError(w, err)
+		return
+	}
+	exchanges, err := har.SessionExchanges(bundle.Exchanges)
+	if err != nil {
+		writeSessionError(w, err)
+		return
+	}
+	w.Header().Set("Content-Disposition", `attachment; filename="session.har"`)
+	writeJSON(w, http.StatusOK, har.ExportSession(bundle.Session, exchanges))
+}
+
+// handleSessionBundle exports a session with its notes and every exchange
+// as recorded, to be imported again with handleSessionImport.
+func (s *Server) handleSessionBundle(w http.ResponseWriter, r *http.Request) {
+	bundle, err := sessions.Export(s.userStore(r), r.PathValue("id"))
+	if err != nil {
+		writeSessionError(w, err)
+		return
+	}
+	w.Header().Set("Content-Disposition", `attachment; filename="session.json"`)
+	writeJSON(w, http.StatusOK, bundle)
+}
+
+func (s *Server) handleSessionImport(w http.ResponseWriter, r *http.Request) {
+	var bundle sessions.Bundle
+	if err := json.NewDecoder(http.MaxBytesReader(w, r.Body, maxProxyRequestBytes)).Decode(&bundle); err != nil {
+		writeError(w, http.StatusBadRequest, "Invalid request body: "+err.Error(), "INVALID_REQUEST")
+		return
+	}
+	session, err := sessions.Import(s.userStore(r), bundle)
+	if err != nil {
+		writeSessionError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusCreated, session)
+}
+
+// handleSessionReplay executes the requests of a session again, in the
+// order they were recorded.
+func (s *Server) handleSessionReplay(w http.ResponseWriter, r *http.Request) {
+	if !s.cfg.EnableProxy {
+		writeError(w, http.StatusForbidden, "Replay requires the proxy API to be enabled", "PROXY_DISABLED")
+		return
+	}
+
+	store := s.userStore(r)
+	recorded, err := sessions.Exchanges(store, r.PathValue("id"))
+	if err != nil {
+		writeSessionError(w, err)
+		return
+	}
+	exchanges, err := har.SessionExchanges(recorded)
+	if err != nil {
+		writeSessionError(w, err)
+		return
+	}
+
+	// Exchanges are cut down to the requests left of the quota
+	reservation := s.reserve(w, r, max(len(exchanges), 1))
+	if reservation == nil {
+		return
+	}
+	exchanges = exchanges[:min(len(exchanges), reservation.granted)]
+	result := har.ReplaySession(exchanges, s.service, store)
+	used := usage{requests: len(result.Entries)}
+	for _, entry := range result.Entries {
+		used.bytes += entry.Replay.Size
+	}
+	reservation.settleOrLog(used)
+	writeJSON(w, http.StatusOK, result)
+}
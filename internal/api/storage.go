@@ -0,0 +1,83 @@
+package api
+
+import (
+	"errors"
+	"io"
+	"net/http"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+// maxStorageValueBytes limits the size of a single stored value.
+const maxStorageValueBytes = 10 << 20
+
+// storageValueResponse mirrors the StorageGet binding of the desktop app.
+type storageValueResponse struct {
+	Value *string `json:"value"`
+}
+
+// storageKeysResponse mirrors the StorageKeys binding of the desktop app.
+type storageKeysResponse struct {
+	Keys []string `json:"keys"`
+}
+
+func (s *Server) handleStorageKeys(w http.ResponseWriter, r *http.Request) {
+	keys, err := s.userStore(r).Keys(r.PathValue("store"))
+	if err != nil {
+		storageError(w, err)
+		return
+	}
+	if keys == nil {
+		keys = []string{}
+	}
+	writeJSON(w, http.StatusOK, storageKeysResponse{Keys: keys})
+}
+
+func (s *Server) handleStorageClear(w http.ResponseWriter, r *http.Request) {
+	if err := s.userStore(r).Clear(r.PathValue("store")); err != nil {
+		storageError(w, err)
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+func (s *Server) handleStorageGet(w http.ResponseWriter, r *http.Request) {
+	value, err := s.userStore(r).Get(r.PathValue("store"), r.PathValue("key"))
+	if err != nil {
+		storageError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusOK, storageValueResponse{Value: value})
+}
+
+func (s *Server) handleStorageSet(w http.ResponseWriter, r *http.Request) {
+	value, err := io.ReadAll(http.MaxBytesReader(w, r.Body, maxStorageValueBytes))
+	if err != nil {
+		writeError(w, http.StatusRequestEntityTooLarge, "Value too large", "VALUE_TOO_LARGE")
+		return
+	}
+
+	if err := s.userStore(r).Set(r.PathValue("store"), r.PathValue("key"), string(value)); err != nil {
+		storageError(w, err)
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+func (s *Server) handleStorageRemove(w http.ResponseWriter, r *http.Request) {
+	if err := s.userStore(r).Remove(r.PathValue("store"), r.PathValue("key")); err != nil {
+		storageError(w, err)
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
+
+// storageError answers a failed storage operation. Invalid store names are
+// the client's fault, not the backend's.
+func storageError(w http.ResponseWriter, err error) {
+	if errors.Is(err, storage.ErrInvalidStoreName) {
+		writeError(w, http.StatusBadRequest, err.Error(), "INVALID_REQUEST")
+		return
+	}
+	writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+}
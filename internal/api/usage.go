@@ -0,0 +1,280 @@
+package api
+
+import (
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"log"
+	"net/http"
+	"sort"
+	"strings"
+	"sync"
+	"time"
+
+	"zone.digit.tommie/internal/proxy"
+	"zone.digit.tommie/internal/storage"
+)
+
+const (
+	// usageStore is the root store holding per-key usage counters.
+	usageStore = "usage"
+	// anonymousUser accounts requests made while auth is disabled.
+	anonymousUser = "anonymous"
+)
+
+// usageRecord holds the proxied request counters of one API key for one day.
+type usageRecord struct {
+	Key      string `json:"key"` // Fingerprint of the API key, see keyID
+	User     string `json:"user"`
+	Date     string `json:"date"` // UTC date (YYYY-MM-DD)
+	Requests int    `json:"requests"`
+	Bytes    int    `json:"bytes"`
+}
+
+// usageAccount is the API key a request is accounted to, and its owner.
+type usageAccount struct {
+	key  string
+	user string
+}
+
+// usage is what an execution sent: its requests and their bytes.
+type usage struct {
+	requests int
+	bytes    int
+}
+
+// usageTracker persists per-key usage counters in the store.
+type usageTracker struct {
+	store storage.Store
+	mu    sync.Mutex
+}
+
+func newUsageTracker(store storage.Store) *usageTracker {
+	return &usageTracker{store: store}
+}
+
+func usageKey(key, date string) string {
+	return key + "/" + date
+}
+
+func usageDate(t time.Time) string {
+	return t.UTC().Format("2006-01-02")
+}
+
+// keyID fingerprints an API key, so usage is kept per key without storing it.
+func keyID(token string) string {
+	sum := sha256.Sum256([]byte(token))
+	return hex.EncodeToString(sum[:8])
+}
+
+// get loads the counters of a key for a day.
+func (t *usageTracker) get(key, date string) (usageRecord, error) {
+	record := usageRecord{Key: key, Date: date}
+
+	value, err := t.store.Get(usageStore, usageKey(key, date))
+	if err != nil || value == nil {
+		return record, err
+	}
+
+	err = json.Unmarshal([]byte(*value), &record)
+	return record, err
+}
+
+func (t *usageTracker) save(record usageRecord) error {
+	data, err := json.Marshal(record)
+	if err != nil {
+		return err
+	}
+	return t.store.Set(usageStore, usageKey(record.Key, record.Date), string(data))
+}
+
+// reservation is a number of requests counted against today's quota of an
+// account before they are sent.
+type reservation struct {
+	tracker *usageTracker
+	account usageAccount
+	date    string
+	granted int
+}
+
+// reserve checks the quota of an account and counts up to n requests
+// against it in one step, so concurrent callers cannot overshoot it
+// together. It returns nil when the quota is exhausted. Bytes are only known
+// afterwards, so the byte quota is checked against what was recorded so far.
+func (t *usageTracker) reserve(account usageAccount, n, maxRequests, maxBytes int) (*reservation, error) {
+	t.mu.Lock()
+	defer t.mu.Unlock()
+
+	record, err := t.get(account.key, usageDate(time.Now()))
+	if err != nil {
+		return nil, err
+	}
+	if maxBytes > 0 && record.Bytes >= maxBytes {
+		return nil, nil
+	}
+	if maxRequests > 0 {
+		n = min(n, maxRequests-record.Requests)
+	}
+	if n <= 0 {
+		return nil, nil
+	}
+
+	record.User = account.user
+	record.Requests += n
+	if err := t.save(record); err != nil {
+		return nil, err
+	}
+	return &reservation{tracker: t, account: account, date: record.Date, granted: n}, nil
+}
+
+// settle records the bytes of what ran under a reservation and corrects
+// its request count, refunding requests that were not sent.
+func (r *reservation) settle(used usage) error {
+	t := r.tracker
+	t.mu.Lock()
+	defer t.mu.Unlock()
+
+	record, err := t.get(r.account.key, r.date)
+	if err != nil {
+		return err
+	}
+	record.Requests = max(record.Requests+used.requests-r.granted, 0)
+	record.Bytes += used.bytes
+	return t.save(record)
+}
+
+// list returns all recorded usage, optionally restricted to one date.
+func (t *usageTracker) list(date string) ([]usageRecord, error) {
+	t.mu.Lock()
+	defer t.mu.Unlock()
+
+	keys, err := t.store.Keys(usageStore)
+	if err != nil {
+		return nil, err
+	}
+	sort.Strings(keys)
+
+	records := []usageRecord{}
+	for _, key := range keys {
+		id, day, ok := strings.Cut(key, "/")
+		if !ok || (date != "" && day != date) {
+			continue
+		}
+
+		record, err := t.get(id, day)
+		if err != nil {
+			return nil, err
+		}
+		records = append(records, record)
+	}
+
+	return records, nil
+}
+
+// requestUser returns the user a request is accounted to.
+func requestUser(r *http.Request) string {
+	if user, ok := userFromContext(r.Context()); ok {
+		return user
+	}
+	return anonymousUser
+}
+
+// requestAccount returns the API key a request is accounted to.
+func requestAccount(r *http.Request) usageAccount {
+	key, ok := r.Context().Value(keyContextKey).(string)
+	if !ok {
+		key = anonymousUser
+	}
+	return usageAccount{key: key, user: requestUser(r)}
+}
+
+// reserve reserves up to n requests of the caller's daily quota. When none
+// are left it writes the error response and returns nil.
+func (s *Server) reserve(w http.ResponseWriter, r *http.Request, n int) *reservation {
+	maxRequests, maxBytes := s.quotas()
+	reservation, err := s.usage.reserve(requestAccount(r), n, maxRequests, maxBytes)
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return nil
+	}
+	if reservation == nil {
+		writeError(w, http.StatusTooManyRequests, "Daily quota exceeded", "QUOTA_EXCEEDED")
+		return nil
+	}
+	return reservation
+}
+
+// settleOrLog settles a reservation, logging rather than failing the request
+// when usage cannot be recorded.
+func (r *reservation) settleOrLog(used usage) {
+	if err := r.settle(used); err != nil {
+		log.Printf("Failed to record usage for %s: %v", r.account.user, err)
+	}
+}
+
+// limitedRun is an execution of several requests that can be cut down to
+// fewer of them.
+type limitedRun interface {
+	MaxRequests() int
+	LimitRequests(n int)
+}
+
+// metered reserves n requests of the caller's daily quota, runs execute and
+// writes the result it returns as JSON. When fewer than n requests are left,
+// nothing runs. The usage execute reports settles the reservation.
+func (s *Server) metered(w http.ResponseWriter, r *http.Request, n int, execute func() (interface{}, usage)) {
+	reservation := s.reserve(w, r, n)
+	if reservation == nil {
+		return
+	}
+	if reservation.granted < n {
+		reservation.settleOrLog(usage{})
+		writeError(w, http.StatusTooManyRequests, "Daily quota exceeded", "QUOTA_EXCEEDED")
+		return
+	}
+	result, used := execute()
+	reservation.settleOrLog(used)
+	writeJSON(w, http.StatusOK, result)
+}
+
+// meteredRun is metered for a run that is cut down to the requests left of
+// the quota, as a load test is, instead of failing.
+func (s *Server) meteredRun(w http.ResponseWriter, r *http.Request, run limitedRun, execute func() (interface{}, usage)) {
+	reservation := s.reserve(w, r, max(run.MaxRequests(), 1))
+	if reservation == nil {
+		return
+	}
+	run.LimitRequests(reservation.granted)
+	result, used := execute()
+	reservation.settleOrLog(used)
+	writeJSON(w, http.StatusOK, result)
+}
+
+// decodeProxyBody decodes a JSON request body of at most
+// maxProxyRequestBytes into v, writing the error response when it fails.
+func decodeProxyBody(w http.ResponseWriter, r *http.Request, v interface{}) bool {
+	if err := json.NewDecoder(http.MaxBytesReader(w, r.Body, maxProxyRequestBytes)).Decode(v); err != nil {
+		writeError(w, http.StatusBadRequest, "Invalid request body: "+err.Error(), "INVALID_REQUEST")
+		return false
+	}
+	return true
+}
+
+// responsesUsage counts a request with the bytes it transferred for each response.
+func responsesUsage(responses ...proxy.ProxyResponse) usage {
+	var used usage
+	for _, response := range responses {
+		used.requests++
+		used.bytes += response.TransferredBytes()
+	}
+	return used
+}
+
+func (s *Server) handleAdminUsage(w http.ResponseWriter, r *http.Request) {
+	records, err := s.usage.list(r.URL.Query().Get("date"))
+	if err != nil {
+		writeError(w, http.StatusInternalServerError, err.Error(), "STORAGE_ERROR")
+		return
+	}
+	writeJSON(w, http.StatusOK, records)
+}
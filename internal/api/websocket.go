@@ -0,0 +1,156 @@
+package api
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"net/http"
+	"strconv"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/proxy"
+)
+
+// frameStreamKeepAlive is the interval of comments sent on an idle frame stream.
+const frameStreamKeepAlive = 15 * time.Second
+
+// writeWebSocketError maps WebSocket session errors to responses.
+func writeWebSocketError(w http.ResponseWriter, err error) {
+	var wsErr *proxy.WebSocketError
+	if !errors.As(err, &wsErr) {
+		writeError(w, http.StatusInternalServerError, err.Error(), "INTERNAL_ERROR")
+		return
+	}
+	status := http.StatusBadGateway
+	switch wsErr.Code {
+	case "NOT_FOUND":
+		status = http.StatusNotFound
+	case "INVALID_URL", "INVALID_REQUEST":
+		status = http.StatusBadRequest
+	case "SESSION_CLOSED":
+		status = http.StatusConflict
+	case "BLOCKED_BY_POLICY":
+		status = http.StatusForbidden
+	case "QUOTA_EXCEEDED", "TOO_MANY_SESSIONS":
+		status = http.StatusTooManyRequests
+	}
+	writeError(w, status, wsErr.Message, wsErr.Code)
+}
+
+func (s *Server) handleWebSocketOpen(w http.ResponseWriter, r *http.Request) {
+	var request proxy.WebSocketRequest
+	if err := json.NewDecoder(http.MaxBytesReader(w, r.Body, maxProxyRequestBytes)).Decode(&request); err != nil {
+		writeError(w, http.StatusBadRequest, "Invalid request body: "+err.Error(), "INVALID_REQUEST")
+		return
+	}
+
+	reservation := s.reserve(w, r, 1)
+	if reservation == nil {
+		return
+	}
+	handshake, err := s.service.OpenWebSocket(requestUser(r), request)
+	if err != nil {
+		reservation.settleOrLog(usage{})
+		writeWebSocketError(w, err)
+		return
+	}
+	reservation.settleOrLog(usage{requests: 1})
+	writeJSON(w, http.StatusOK, handshake)
+}
+
+func (s *Server) handleWebSocketSend(w http.ResponseWriter, r *http.Request) {
+	var message proxy.WebSocketMessage
+	if err := json.NewDecoder(http.MaxBytesReader(w, r.Body, maxProxyRequestBytes)).Decode(&message); err != nil {
+		writeError(w, http.StatusBadRequest, "Invalid request body: "+err.Error(), "INVALID_REQUEST")
+		return
+	}
+
+	reservation := s.reserve(w, r, 1)
+	if reservation == nil {
+		return
+	}
+	frame, err := s.service.SendWebSocket(requestUser(r), r.PathValue("id"), message)
+	if err != nil {
+		reservation.settleOrLog(usage{})
+		writeWebSocketError(w, err)
+		return
+	}
+	reservation.settleOrLog(usage{requests: 1, bytes: frame.Size})
+	writeJSON(w, http.StatusOK, frame)
+}
+
+// handleWebSocketFrames returns the frames recorded after ?after=N, or
+// streams them as server-sent events when the client accepts text/event-stream.
+func (s *Server) handleWebSocketFrames(w http.ResponseWriter, r *http.Request) {
+	after := 0
+	if v := r.URL.Query().Get("after"); v != "" {
+		n, err := strconv.Atoi(v)
+		if err != nil {
+			writeError(w, http.StatusBadRequest, "Invalid after parameter", "INVALID_REQUEST")
+			return
+		}
+		after = n
+	}
+
+	user, id := requestUser(r), r.PathValue("id")
+	frames, changed, err := s.service.WebSocketFrames(user, id, after)
+	if err != nil {
+		writeWebSocketError(w, err)
+		return
+	}
+	if !strings.Contains(r.Header.Get("Accept"), "text/event-stream") {
+		writeJSON(w, http.StatusOK, frames)
+		return
+	}
+
+	flusher, ok := w.(http.Flusher)
+	if !ok {
+		writeJSON(w, http.StatusOK, frames)
+		return
+	}
+	w.Header().Set("Content-Type", "text/event-stream")
+	w.Header().Set("Cache-Control", "no-cache")
+	w.WriteHeader(http.StatusOK)
+
+	keepAlive := time.NewTicker(frameStreamKeepAlive)
+	defer keepAlive.Stop()
+	for {
+		for _, frame := range frames.Frames {
+			data, _ := json.Marshal(frame)
+			fmt.Fprintf(w, "id: %d\nevent: frame\ndata: %s\n\n", frame.Seq, data)
+			after = frame.Seq
+		}
+		if frames.Closed {
+			data, _ := json.Marshal(frames)
+			fmt.Fprintf(w, "event: closed\ndata: %s\n\n", data)
+			flusher.Flush()
+			return
+		}
+		flusher.Flush()
+
+		select {
+		case <-r.Context().Done():
+			return
+		case <-keepAlive.C:
+			fmt.Fprint(w, ": keep-alive\n\n")
+			flusher.Flush()
+			frames.Frames = nil
+			continue
+		case <-changed:
+		}
+
+		// The session is gone once it has been deleted
+		if frames, changed, err = s.service.WebSocketFrames(user, id, after); err != nil {
+			return
+		}
+	}
+}
+
+func (s *Server) handleWebSocketClose(w http.ResponseWriter, r *http.Request) {
+	if err := s.service.CloseWebSocket(requestUser(r), r.PathValue("id")); err != nil {
+		writeWebSocketError(w, err)
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
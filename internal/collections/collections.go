@@ -0,0 +1,520 @@
+// Package collections keeps named requests organized in folders in a storage.Store.
+package collections
+
+import (
+	"crypto/rand"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"sort"
+	"strings"
+	"sync"
+	"time"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+// StoreName is the storage store holding collections, keyed by collection ID.
+const StoreName = "collections"
+
+// ErrNotFound is returned when a collection, folder or request does not exist.
+var ErrNotFound = errors.New("not found")
+
+// ValidationError reports invalid input such as an empty name or a folder
+// moved into itself.
+type ValidationError struct {
+	Message string
+}
+
+func (e *ValidationError) Error() string {
+	return e.Message
+}
+
+// Collection is a named set of folders and saved requests.
+type Collection struct {
+	ID          string         `json:"id"`
+	Name        string         `json:"name"`
+	Description string         `json:"description,omitempty"`
+	Folders     []Folder       `json:"folders"`
+	Requests    []SavedRequest `json:"requests"`
+	Created     time.Time      `json:"created"`
+	Updated     time.Time      `json:"updated"`
+}
+
+// Summary describes a collection without its contents.
+type Summary struct {
+	ID          string    `json:"id"`
+	Name        string    `json:"name"`
+	Description string    `json:"description,omitempty"`
+	Folders     int       `json:"folders"`
+	Requests    int       `json:"requests"`
+	Updated     time.Time `json:"updated"`
+}
+
+// Folder groups requests and other folders.
+type Folder struct {
+	ID       string `json:"id"`
+	Name     string `json:"name"`
+	ParentID string `json:"parentId,omitempty"` // Empty at the collection root
+	Order    int    `json:"order"`              // Position among its siblings
+}
+
+// SavedRequest is a named request.
+type SavedRequest struct {
+	ID         string            `json:"id"`
+	Name       string            `json:"name"`
+	FolderID   string            `json:"folderId,omitempty"` // Empty at the collection root
+	Order      int               `json:"order"`              // Position among its siblings
+	Method     string            `json:"method"`
+	URL        string            `json:"url"`
+	Headers    map[string]string `json:"headers,omitempty"`
+	Body       *string           `json:"body,omitempty"`
+	Auth       *Auth             `json:"auth,omitempty"`
+	Scripts    *Scripts          `json:"scripts,omitempty"`
+	Assertions []Assertion       `json:"assertions,omitempty"` // Checked against each response; see proxy.Assertion
+	Extract    []Extraction      `json:"extract,omitempty"`    // Saved to the environment from each response; see proxy.Extraction
+}
+
+// Auth holds the credentials applied to a saved request.
+type Auth struct {
+	Type     string `json:"type"` // basic, bearer or apiKey
+	Username string `json:"username,omitempty"`
+	Password string `json:"password,omitempty"`
+	Token    string `json:"token,omitempty"`
+	Key      string `json:"key,omitempty"`   // API key name
+	Value    string `json:"value,omitempty"` // API key value
+	In       string `json:"in,omitempty"`    // header or query
+}
+
+// Scripts are the sandboxed scripts run around a saved request; see
+// proxy.RequestScripts.
+type Scripts struct {
+	PreRequest string `json:"preRequest,omitempty"`
+	Test       string `json:"test,omitempty"`
+}
+
+// Assertion is a declarative check of a saved request's response.
+type Assertion struct {
+	Type   string          `json:"type"`
+	Name   *string         `json:"name,omitempty"`
+	Header string          `json:"header,omitempty"`
+	Path   string          `json:"path,omitempty"`
+	Value  json.RawMessage `json:"value,omitempty"`
+}
+
+// Extraction saves a value of a saved request's JSON response to a variable.
+type Extraction struct {
+	Variable string `json:"variable"`
+	Path     string `json:"path"`
+}
+
+// mu serializes read-modify-write cycles on collections.
+var mu sync.Mutex
+
+// List returns summaries of all collections sorted by name.
+func List(store storage.Store) ([]Summary, error) {
+	ids, err := store.Keys(StoreName)
+	if err != nil {
+		return nil, err
+	}
+
+	summaries := []Summary{}
+	for _, id := range ids {
+		c, err := Get(store, id)
+		if err != nil {
+			return nil, err
+		}
+		summaries = append(summaries, Summary{
+			ID:          c.ID,
+			Name:        c.Name,
+			Description: c.Description,
+			Folders:     len(c.Folders),
+			Requests:    len(c.Requests),
+			Updated:     c.Updated,
+		})
+	}
+	sort.Slice(summaries, func(i, j int) bool {
+		return strings.ToLower(summaries[i].Name) < strings.ToLower(summaries[j].Name)
+	})
+	return summaries, nil
+}
+
+// Get returns a collection with its folders and requests in order.
+func Get(store storage.Store, id string) (*Collection, error) {
+	value, err := store.Get(StoreName, id)
+	if err != nil {
+		return nil, err
+	}
+	if value == nil {
+		return nil, ErrNotFound
+	}
+	var c Collection
+	if err := json.Unmarshal([]byte(*value), &c); err != nil {
+		return nil, fmt.Errorf("corrupt collection %s: %w", id, err)
+	}
+	sort.SliceStable(c.Folders, func(i, j int) bool { return c.Folders[i].Order < c.Folders[j].Order })
+	sort.SliceStable(c.Requests, func(i, j int) bool { return c.Requests[i].Order < c.Requests[j].Order })
+	return &c, nil
+}
+
+// Create stores a new, empty collection.
+func Create(store storage.Store, name, description string) (*Collection, error) {
+	if err := requireName(name); err != nil {
+		return nil, err
+	}
+	id, err := newID()
+	if err != nil {
+		return nil, err
+	}
+
+	now := time.Now().UTC()
+	c := &Collection{
+		ID:          id,
+		Name:        strings.TrimSpace(name),
+		Description: description,
+		Folders:     []Folder{},
+		Requests:    []SavedRequest{},
+		Created:     now,
+	}
+	if err := save(store, c); err != nil {
+		return nil, err
+	}
+	return c, nil
+}
+
+// Update renames a collection and replaces its description.
+func Update(store storage.Store, id, name, description string) (*Collection, error) {
+	if err := requireName(name); err != nil {
+		return nil, err
+	}
+	return modify(store, id, func(c *Collection) error {
+		c.Name = strings.TrimSpace(name)
+		c.Description = description
+		return nil
+	})
+}
+
+// Delete removes a collection with everything in it.
+func Delete(store storage.Store, id string) error {
+	mu.Lock()
+	defer mu.Unlock()
+	return store.Remove(StoreName, id)
+}
+
+// CreateFolder adds a folder at the end of its parent.
+func CreateFolder(store storage.Store, collectionID, name, parentID string) (*Folder, error) {
+	if err := requireName(name); err != nil {
+		return nil, err
+	}
+	id, err := newID()
+	if err != nil {
+		return nil, err
+	}
+
+	folder := Folder{ID: id, Name: strings.TrimSpace(name), ParentID: parentID}
+	_, err = modify(store, collectionID, func(c *Collection) error {
+		if parentID != "" && c.folder(parentID) == nil {
+			return ErrNotFound
+		}
+		folder.Order = len(c.childFolders(parentID))
+		c.Folders = append(c.Folders, folder)
+		return nil
+	})
+	if err != nil {
+		return nil, err
+	}
+	return &folder, nil
+}
+
+// RenameFolder renames a folder.
+func RenameFolder(store storage.Store, collectionID, folderID, name string) (*Folder, error) {
+	if err := requireName(name); err != nil {
+		return nil, err
+	}
+	var renamed Folder
+	_, err := modify(store, collectionID, func(c *Collection) error {
+		f := c.folder(folderID)
+		if f == nil {
+			return ErrNotFound
+		}
+		f.Name = strings.TrimSpace(name)
+		renamed = *f
+		return nil
+	})
+	if err != nil {
+		return nil, err
+	}
+	return &renamed, nil
+}
+
+// DeleteFolder removes a folder together with its subfolders and requests.
+func DeleteFolder(store storage.Store, collectionID, folderID string) error {
+	_, err := modify(store, collectionID, func(c *Collection) error {
+		if c.folder(folderID) == nil {
+			return ErrNotFound
+		}
+		removed := c.descendants(folderID)
+
+		folders := c.Folders[:0]
+		for _, f := range c.Folders {
+			if !removed[f.ID] {
+				folders = append(folders, f)
+			}
+		}
+		c.Folders = folders
+
+		requests := c.Requests[:0]
+		for _, r := range c.Requests {
+			if !removed[r.FolderID] {
+				requests = append(requests, r)
+			}
+		}
+		c.Requests = requests
+		return nil
+	})
+	return err
+}
+
+// MoveFolder moves a folder under parentID at position among its new
+// siblings. A negative or out-of-range position appends.
+func MoveFolder(store storage.Store, collectionID, folderID, parentID string, position int) (*Collection, error) {
+	return modify(store, collectionID, func(c *Collection) error {
+		f := c.folder(folderID)
+		if f == nil || (parentID != "" && c.folder(parentID) == nil) {
+			return ErrNotFound
+		}
+		if c.descendants(folderID)[parentID] {
+			return &ValidationError{Message: "a folder cannot be moved into itself"}
+		}
+
+		oldParent := f.ParentID
+		f.ParentID = parentID
+		c.reorderFolders(oldParent, "", -1)
+		c.reorderFolders(parentID, folderID, position)
+		return nil
+	})
+}
+
+// SaveRequest creates a request when its ID is empty and otherwise replaces
+// the stored one, which must exist. New requests are appended to their folder.
+func SaveRequest(store storage.Store, collectionID string, request SavedRequest) (*SavedRequest, error) {
+	if err := requireName(request.Name); err != nil {
+		return nil, err
+	}
+	request.Name = strings.TrimSpace(request.Name)
+	request.Method = strings.ToUpper(request.Method)
+	if request.Method == "" {
+		request.Method = "GET"
+	}
+
+	isNew := request.ID == ""
+	if isNew {
+		id, err := newID()
+		if err != nil {
+			return nil, err
+		}
+		request.ID = id
+	}
+
+	_, err := modify(store, collectionID, func(c *Collection) error {
+		if request.FolderID != "" && c.folder(request.FolderID) == nil {
+			return ErrNotFound
+		}
+		existing := c.request(request.ID)
+		if existing == nil && !isNew {
+			return ErrNotFound
+		}
+		if existing == nil {
+			request.Order = len(c.childRequests(request.FolderID))
+			c.Requests = append(c.Requests, request)
+			return nil
+		}
+
+		// Placement changes go through MoveRequest
+		request.FolderID = existing.FolderID
+		request.Order = existing.Order
+		*existing = request
+		return nil
+	})
+	if err != nil {
+		return nil, err
+	}
+	return &request, nil
+}
+
+// DeleteRequest removes a saved request.
+func DeleteRequest(store storage.Store, collectionID, requestID string) error {
+	_, err := modify(store, collectionID, func(c *Collection) error {
+		r := c.request(requestID)
+		if r == nil {
+			return ErrNotFound
+		}
+		folderID := r.FolderID
+		for i := range c.Requests {
+			if c.Requests[i].ID == requestID {
+				c.Requests = append(c.Requests[:i], c.Requests[i+1:]...)
+				break
+			}
+		}
+		c.reorderRequests(folderID, "", -1)
+		return nil
+	})
+	return err
+}
+
+// MoveRequest moves a request into folderID at position among its new
+// siblings. A negative or out-of-range position appends.
+func MoveRequest(store storage.Store, collectionID, requestID, folderID string, position int) (*Collection, error) {
+	return modify(store, collectionID, func(c *Collection) error {
+		r := c.request(requestID)
+		if r == nil || (folderID != "" && c.folder(folderID) == nil) {
+			return ErrNotFound
+		}
+		oldFolder := r.FolderID
+		r.FolderID = folderID
+		c.reorderRequests(oldFolder, "", -1)
+		c.reorderRequests(folderID, requestID, position)
+		return nil
+	})
+}
+
+// modify loads a collection, applies fn and saves the result.
+func modify(store storage.Store, id string, fn func(c *Collection) error) (*Collection, error) {
+	mu.Lock()
+	defer mu.Unlock()
+
+	c, err := Get(store, id)
+	if err != nil {
+		return nil, err
+	}
+	if err := fn(c); err != nil {
+		return nil, err
+	}
+	if err := save(store, c); err != nil {
+		return nil, err
+	}
+	return Get(store, id)
+}
+
+func save(store storage.Store, c *Collection) error {
+	c.Updated = time.Now().UTC()
+	data, err := json.Marshal(c)
+	if err != nil {
+		return err
+	}
+	return store.Set(StoreName, c.ID, string(data))
+}
+
+func (c *Collection) folder(id string) *Folder {
+	for i := range c.Folders {
+		if c.Folders[i].ID == id {
+			return &c.Folders[i]
+		}
+	}
+	return nil
+}
+
+func (c *Collection) request(id string) *SavedRequest {
+	for i := range c.Requests {
+		if c.Requests[i].ID == id {
+			return &c.Requests[i]
+		}
+	}
+	return nil
+}
+
+// descendants returns the IDs of a folder and every folder below it.
+func (c *Collection) descendants(folderID string) map[string]bool {
+	ids := map[string]bool{folderID: true}
+	for changed := true; changed; {
+		changed = false
+		for _, f := range c.Folders {
+			if ids[f.ParentID] && !ids[f.ID] {
+				ids[f.ID] = true
+				changed = true
+			}
+		}
+	}
+	return ids
+}
+
+func (c *Collection) childFolders(parentID string) []*Folder {
+	var children []*Folder
+	for i := range c.Folders {
+		if c.Folders[i].ParentID == parentID {
+			children = append(children, &c.Folders[i])
+		}
+	}
+	sort.SliceStable(children, func(i, j int) bool { return children[i].Order < children[j].Order })
+	return children
+}
+
+func (c *Collection) childRequests(folderID string) []*SavedRequest {
+	var children []*SavedRequest
+	for i := range c.Requests {
+		if c.Requests[i].FolderID == folderID {
+			children = append(children, &c.Requests[i])
+		}
+	}
+	sort.SliceStable(children, func(i, j int) bool { return children[i].Order < children[j].Order })
+	return children
+}
+
+// reorderFolders renumbers the folders under parentID, placing movedID at
+// position when it is set.
+func (c *Collection) reorderFolders(parentID, movedID string, position int) {
+	children := c.childFolders(parentID)
+	ids := make([]string, len(children))
+	for i, f := range children {
+		ids[i] = f.ID
+	}
+	for i, id := range placeAt(ids, movedID, position) {
+		c.folder(id).Order = i
+	}
+}
+
+// reorderRequests renumbers the requests in folderID, placing movedID at
+// position when it is set.
+func (c *Collection) reorderRequests(folderID, movedID string, position int) {
+	children := c.childRequests(folderID)
+	ids := make([]string, len(children))
+	for i, r := range children {
+		ids[i] = r.ID
+	}
+	for i, id := range placeAt(ids, movedID, position) {
+		c.request(id).Order = i
+	}
+}
+
+// placeAt moves id to position within ids, appending when position is out of range.
+func placeAt(ids []string, id string, position int) []string {
+	if id == "" {
+		return ids
+	}
+	rest := make([]string, 0, len(ids))
+	for _, other := range ids {
+		if other != id {
+			rest = append(rest, other)
+		}
+	}
+	if position < 0 || position > len(rest) {
+		position = len(rest)
+	}
+	return append(rest[:position], append([]string{id}, rest[position:]...)...)
+}
+
+func requireName(name string) error {
+	if strings.TrimSpace(name) == "" {
+		return &ValidationError{Message: "name is required"}
+	}
+	return nil
+}
+
+func newID() (string, error) {
+	b := make([]byte, 8)
+	if _, err := rand.Read(b); err != nil {
+		return "", err
+	}
+	return hex.EncodeToString(b), nil
+}
@@ -4,6 +4,7 @@ package config
 import (
 	"os"
 	"strconv"
+	"strings"
 )
 
 const (
@@ -14,20 +15,226 @@ const (
 // Config holds the application configuration.
 type Config struct {
 	Port int
+	// ReadOnly disables all mutating API routes so the instance only serves recorded data.
+	ReadOnly bool
+	// ExtraCABundle is a PEM file or directory of additional trusted root certificates.
+	ExtraCABundle string
+	// StorageBackend selects the persistence backend ("sqlite" or "memory").
+	StorageBackend string
+	// StoragePath is the database location for file-based backends.
+	StoragePath string
+	// AuthTokens maps API bearer tokens to user names. Auth is enabled when non-empty.
+	AuthTokens map[string]string
+	// AdminUsers lists the users allowed to access admin routes.
+	AdminUsers []string
+	// EnableProxy exposes the /api/proxy execution route on the server.
+	EnableProxy bool
+	// DailyRequestQuota limits proxied requests per API key per day (0 = unlimited).
+	DailyRequestQuota int
+	// DailyByteQuota limits proxied bytes per API key per day (0 = unlimited).
+	DailyByteQuota int
+	// EgressBytesPerMinute is the global proxied byte budget per minute (0 = unlimited).
+	EgressBytesPerMinute int
+	// EgressBytesPerDay is the global proxied byte budget per UTC day (0 = unlimited).
+	EgressBytesPerDay int
+	// HTTPProxy, HTTPSProxy and NoProxy configure the upstream proxy using the
+	// standard HTTP_PROXY, HTTPS_PROXY and NO_PROXY environment variables.
+	HTTPProxy  string
+	HTTPSProxy string
+	NoProxy    string
+	// PACURL is a proxy auto-config file (URL or path). When set it takes
+	// precedence over HTTPProxy, HTTPSProxy and NoProxy.
+	PACURL string
+	// BlockPrivateIPs refuses requests to loopback, private, link-local and
+	// other internal addresses. Enable when the server is publicly reachable.
+	BlockPrivateIPs bool
+	// BlockMetadata refuses requests to cloud metadata endpoints such as 169.254.169.254.
+	BlockMetadata bool
+	// DeniedCIDRs lists additional address ranges requests may not reach.
+	DeniedCIDRs []string
+	// AllowedCIDRs lists address ranges exempt from BlockPrivateIPs and DeniedCIDRs.
+	AllowedCIDRs []string
+	// AllowedHosts restricts requests to these hostnames when non-empty.
+	// A leading dot also matches subdomains.
+	AllowedHosts []string
+	// HistoryEnabled records a summary of every executed request per user.
+	HistoryEnabled bool
+	// HistoryMaxEntries is the number of history entries kept per user (0 = unlimited).
+	HistoryMaxEntries int
+	// HistoryMaxAgeDays drops history entries older than this many days (0 = unlimited).
+	HistoryMaxAgeDays int
+	// HistoryMaxBodyBytes is the response body kept per history entry for
+	// server-side search (0 = none).
+	HistoryMaxBodyBytes int
+	// DNSServers resolves hostnames through these nameservers instead of the
+	// system resolver: plain IPs, tcp://, tls:// (DNS-over-TLS) or https://
+	// (DNS-over-HTTPS) URLs, tried in order.
+	DNSServers []string
+	// MaxInFlightBodyBytes bounds the body memory held by concurrent proxied requests (0 = unlimited).
+	MaxInFlightBodyBytes int
+	// MaxBodyBytes is the response body kept per request; longer bodies are truncated.
+	MaxBodyBytes int
+	// DownloadDir keeps response bodies streamed to disk until they are fetched.
+	DownloadDir string
+	// MaxDownloadBytes bounds a response body streamed to disk, both as
+	// received and decoded (0 = unlimited).
+	MaxDownloadBytes int
+	// FixtureDir receives full responses captured for frontend fixtures ("" = disabled).
+	FixtureDir string
+	// PluginDir holds WebAssembly plugins run on every request and response ("" = none).
+	PluginDir string
+	// BusyWaitMS is how long a request waits for in-flight memory before failing with BUSY.
+	BusyWaitMS int
+	// Simulate answers proxy requests with fabricated responses instead of
+	// reaching the network, for demos and offline development.
+	Simulate bool
+	// SimulateLatency overrides the simulated phase latencies, e.g. "ttfb=200,jitter=0".
+	SimulateLatency []string
+	// IntegratedAuthHosts are the intranet hosts the desktop app answers
+	// Negotiate/NTLM challenges of with the logged-on user's credentials
+	// without the request asking.
+	IntegratedAuthHosts []string
+	// RedactNames adds header, query parameter and body field names whose
+	// values redacted curl exports replace by variables.
+	RedactNames []string
+	// Locale is the language of error messages when a request's
+	// Accept-Language names no supported one, e.g. "nl". Codes stay English.
+	Locale string
+	// ConfigFile is a file of KEY=VALUE lines overriding the environment.
+	// Reloadable settings are applied live when it changes.
+	ConfigFile string
 }
 
-// Load loads configuration from environment variables.
+// AuthEnabled reports whether API requests must be authenticated.
+func (c *Config) AuthEnabled() bool {
+	return len(c.AuthTokens) > 0
+}
+
+// IsAdmin reports whether the named user may access admin routes.
+func (c *Config) IsAdmin(user string) bool {
+	for _, admin := range c.AdminUsers {
+		if admin == user {
+			return true
+		}
+	}
+	return false
+}
+
+// Load loads configuration from environment variables, overridden by the
+// file named by CONFIG_FILE. A file that cannot be read is ignored; use Read
+// to report it.
 func Load() *Config {
+	cfg, err := Read()
+	if err != nil {
+		return load(os.Getenv)
+	}
+	return cfg
+}
+
+// load builds the configuration from a variable lookup.
+func load(env lookup) *Config {
 	return &Config{
-		Port: getEnvInt("PORT", DefaultPort),
+		Port:                 env.getInt("PORT", DefaultPort),
+		ReadOnly:             env.getBool("READ_ONLY", false),
+		ExtraCABundle:        env("EXTRA_CA_BUNDLE"),
+		StorageBackend:       env.get("STORAGE_BACKEND", "sqlite"),
+		StoragePath:          env("STORAGE_PATH"),
+		AuthTokens:           parseAuthTokens(env("AUTH_TOKENS")),
+		AdminUsers:           env.getList("ADMIN_USERS"),
+		EnableProxy:          env.getBool("ENABLE_PROXY", false),
+		DailyRequestQuota:    env.getInt("DAILY_REQUEST_QUOTA", 0),
+		DailyByteQuota:       env.getInt("DAILY_BYTE_QUOTA", 0),
+		EgressBytesPerMinute: env.getInt("EGRESS_BYTES_PER_MINUTE", 0),
+		EgressBytesPerDay:    env.getInt("EGRESS_BYTES_PER_DAY", 0),
+		HTTPProxy:            env.getAny("HTTP_PROXY", "http_proxy"),
+		HTTPSProxy:           env.getAny("HTTPS_PROXY", "https_proxy"),
+		NoProxy:              env.getAny("NO_PROXY", "no_proxy"),
+		PACURL:               env.get("PAC_URL", ""),
+		BlockPrivateIPs:      env.getBool("BLOCK_PRIVATE_IPS", false),
+		BlockMetadata:        env.getBool("BLOCK_METADATA_ENDPOINTS", false),
+		DeniedCIDRs:          env.getList("DENIED_CIDRS"),
+		AllowedCIDRs:         env.getList("ALLOWED_CIDRS"),
+		AllowedHosts:         env.getList("ALLOWED_HOSTS"),
+		HistoryEnabled:       env.getBool("HISTORY_ENABLED", true),
+		HistoryMaxEntries:    env.getInt("HISTORY_MAX_ENTRIES", 1000),
+		HistoryMaxAgeDays:    env.getInt("HISTORY_MAX_AGE_DAYS", 0),
+		HistoryMaxBodyBytes:  env.getInt("HISTORY_MAX_BODY_BYTES", 1048576),
+		DNSServers:           env.getList("DNS_SERVERS"),
+		MaxInFlightBodyBytes: env.getInt("MAX_INFLIGHT_BODY_BYTES", 0),
+		MaxBodyBytes:         env.getInt("MAX_BODY_BYTES", 50<<20),
+		DownloadDir:          env.get("DOWNLOAD_DIR", ""),
+		MaxDownloadBytes:     env.getInt("MAX_DOWNLOAD_BYTES", 1<<30),
+		FixtureDir:           env.get("FIXTURE_DIR", ""),
+		PluginDir:            env.get("PLUGIN_DIR", ""),
+		BusyWaitMS:           env.getInt("BUSY_WAIT_MS", 10000),
+		Simulate:             env.getBool("SIMULATE", false),
+		SimulateLatency:      env.getList("SIMULATE_LATENCY"),
+		IntegratedAuthHosts:  env.getList("INTEGRATED_AUTH_HOSTS"),
+		RedactNames:          env.getList("REDACT_NAMES"),
+		Locale:               env.get("LOCALE", ""),
+		ConfigFile:           os.Getenv("CONFIG_FILE"),
+	}
+}
+
+// parseAuthTokens parses "user:token,user2:token2" into a token to user map.
+// Users containing "/" are skipped, as user names prefix the names of their
+// stores and could otherwise reach into another user's.
+func parseAuthTokens(val string) map[string]string {
+	tokens := make(map[string]string)
+	for _, entry := range strings.Split(val, ",") {
+		user, token, ok := strings.Cut(strings.TrimSpace(entry), ":")
+		if !ok || user == "" || token == "" || strings.Contains(user, "/") {
+			continue
+		}
+		tokens[token] = user
 	}
+	return tokens
 }
 
-func getEnvInt(key string, defaultVal int) int {
-	if val := os.Getenv(key); val != "" {
+// lookup returns the value of a configuration variable, or "" if unset.
+type lookup func(key string) string
+
+func (env lookup) getList(key string) []string {
+	var list []string
+	for _, item := range strings.Split(env(key), ",") {
+		if item = strings.TrimSpace(item); item != "" {
+			list = append(list, item)
+		}
+	}
+	return list
+}
+
+func (env lookup) get(key, defaultVal string) string {
+	if val := env(key); val != "" {
+		return val
+	}
+	return defaultVal
+}
+
+// getAny returns the first non-empty value among the given variables.
+func (env lookup) getAny(keys ...string) string {
+	for _, key := range keys {
+		if val := env(key); val != "" {
+			return val
+		}
+	}
+	return ""
+}
+
+func (env lookup) getInt(key string, defaultVal int) int {
+	if val := env(key); val != "" {
 		if i, err := strconv.Atoi(val); err == nil {
 			return i
 		}
 	}
 	return defaultVal
 }
+
+func (env lookup) getBool(key string, defaultVal bool) bool {
+	if val := env(key); val != "" {
+		if b, err := strconv.ParseBool(val); err == nil {
+			return b
+		}
+	}
+	return defaultVal
+}
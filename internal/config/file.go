@@ -0,0 +1,61 @@
+package config
+
+import (
+	"bufio"
+	"fmt"
+	"os"
+	"strings"
+)
+
+// Read loads configuration like Load, but fails when the file named by
+// CONFIG_FILE cannot be read or parsed.
+func Read() (*Config, error) {
+	path := os.Getenv("CONFIG_FILE")
+	if path == "" {
+		return load(os.Getenv), nil
+	}
+	values, err := readFile(path)
+	if err != nil {
+		return nil, err
+	}
+	return load(func(key string) string {
+		if val, ok := values[key]; ok {
+			return val
+		}
+		return os.Getenv(key)
+	}), nil
+}
+
+// readFile parses a file of KEY=VALUE lines named like the environment
+// variables. Blank lines and lines starting with # are skipped, and values
+// may be quoted.
+func readFile(path string) (map[string]string, error) {
+	file, err := os.Open(path)
+	if err != nil {
+		return nil, err
+	}
+	defer file.Close()
+
+	values := make(map[string]string)
+	scanner := bufio.NewScanner(file)
+	for line := 1; scanner.Scan(); line++ {
+		text := strings.TrimSpace(scanner.Text())
+		if text == "" || strings.HasPrefix(text, "#") {
+			continue
+		}
+		key, val, ok := strings.Cut(strings.TrimPrefix(text, "export "), "=")
+		key = strings.TrimSpace(key)
+		if !ok || key == "" {
+			return nil, fmt.Errorf("%s:%d: expected KEY=VALUE", path, line)
+		}
+		val = strings.TrimSpace(val)
+		if len(val) >= 2 && (val[0] == '"' || val[0] == '\'') && val[len(val)-1] == val[0] {
+			val = val[1 : len(val)-1]
+		}
+		values[key] = val
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, err
+	}
+	return values, nil
+}
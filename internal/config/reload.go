@@ -0,0 +1,174 @@
+package config
+
+import (
+	"os"
+	"reflect"
+	"sync"
+	"time"
+)
+
+// ReloadInterval is how often a Watcher checks the config file for changes.
+const ReloadInterval = 2 * time.Second
+
+// setting is a configuration field and the variable that sets it.
+type setting struct {
+	key        string
+	field      string
+	reloadable bool // Applied live; other settings take effect after a restart
+}
+
+var settings = []setting{
+	{"PORT", "Port", false},
+	{"READ_ONLY", "ReadOnly", false},
+	{"EXTRA_CA_BUNDLE", "ExtraCABundle", false},
+	{"STORAGE_BACKEND", "StorageBackend", false},
+	{"STORAGE_PATH", "StoragePath", false},
+	{"AUTH_TOKENS", "AuthTokens", false},
+	{"ADMIN_USERS", "AdminUsers", false},
+	{"ENABLE_PROXY", "EnableProxy", false},
+	{"DAILY_REQUEST_QUOTA", "DailyRequestQuota", true},
+	{"DAILY_BYTE_QUOTA", "DailyByteQuota", true},
+	{"EGRESS_BYTES_PER_MINUTE", "EgressBytesPerMinute", true},
+	{"EGRESS_BYTES_PER_DAY", "EgressBytesPerDay", true},
+	{"HTTP_PROXY", "HTTPProxy", false},
+	{"HTTPS_PROXY", "HTTPSProxy", false},
+	{"NO_PROXY", "NoProxy", false},
+	{"PAC_URL", "PACURL", false},
+	{"BLOCK_PRIVATE_IPS", "BlockPrivateIPs", true},
+	{"BLOCK_METADATA_ENDPOINTS", "BlockMetadata", true},
+	{"DENIED_CIDRS", "DeniedCIDRs", true},
+	{"ALLOWED_CIDRS", "AllowedCIDRs", true},
+	{"ALLOWED_HOSTS", "AllowedHosts", true},
+	{"HISTORY_ENABLED", "HistoryEnabled", false},
+	{"HISTORY_MAX_ENTRIES", "HistoryMaxEntries", false},
+	{"HISTORY_MAX_AGE_DAYS", "HistoryMaxAgeDays", false},
+	{"HISTORY_MAX_BODY_BYTES", "HistoryMaxBodyBytes", false},
+	{"DNS_SERVERS", "DNSServers", false},
+	{"MAX_INFLIGHT_BODY_BYTES", "MaxInFlightBodyBytes", false},
+	{"MAX_BODY_BYTES", "MaxBodyBytes", false},
+	{"DOWNLOAD_DIR", "DownloadDir", false},
+	{"MAX_DOWNLOAD_BYTES", "MaxDownloadBytes", false},
+	{"FIXTURE_DIR", "FixtureDir", false},
+	{"PLUGIN_DIR", "PluginDir", false},
+	{"BUSY_WAIT_MS", "BusyWaitMS", false},
+	{"SIMULATE", "Simulate", false},
+	{"SIMULATE_LATENCY", "SimulateLatency", false},
+	{"INTEGRATED_AUTH_HOSTS", "IntegratedAuthHosts", false},
+	{"REDACT_NAMES", "RedactNames", true},
+	{"LOCALE", "Locale", false},
+}
+
+// ReloadStatus reports the outcome of watching the config file.
+type ReloadStatus struct {
+	File           string
+	LastCheck      time.Time
+	LastReload     time.Time // Zero until a change was applied
+	Reloads        int
+	Applied        []string // Variables changed by the last reload
+	PendingRestart []string // Changed variables that only take effect after a restart
+	Error          string   // Why the last change could not be applied; cleared on success
+}
+
+// Watcher polls the config file and applies reloadable settings when it
+// changes. Settings that need a restart keep their startup value.
+type Watcher struct {
+	apply func(*Config) error
+
+	mu      sync.Mutex
+	current *Config
+	modTime time.Time
+	size    int64
+	status  ReloadStatus
+}
+
+// NewWatcher watches the config file of cfg. apply is called with the new
+// configuration before it becomes current; an error keeps the old one.
+func NewWatcher(cfg *Config, apply func(*Config) error) *Watcher {
+	w := &Watcher{apply: apply, current: cfg, status: ReloadStatus{File: cfg.ConfigFile}}
+	if info, err := os.Stat(cfg.ConfigFile); err == nil {
+		w.modTime, w.size = info.ModTime(), info.Size()
+	}
+	return w
+}
+
+// Start checks the file every ReloadInterval for the life of the process.
+func (w *Watcher) Start() {
+	go func() {
+		ticker := time.NewTicker(ReloadInterval)
+		defer ticker.Stop()
+		for range ticker.C {
+			w.check()
+		}
+	}()
+}
+
+// Current returns the configuration in effect.
+func (w *Watcher) Current() *Config {
+	w.mu.Lock()
+	defer w.mu.Unlock()
+	return w.current
+}
+
+// Status returns the outcome of the latest checks.
+func (w *Watcher) Status() ReloadStatus {
+	w.mu.Lock()
+	defer w.mu.Unlock()
+	return w.status
+}
+
+// check reloads the file if its modification time or size changed.
+func (w *Watcher) check() {
+	w.mu.Lock()
+	defer w.mu.Unlock()
+
+	w.status.LastCheck = time.Now().UTC()
+	info, err := os.Stat(w.status.File)
+	if err != nil {
+		w.status.Error = err.Error()
+		return
+	}
+	if info.ModTime().Equal(w.modTime) && info.Size() == w.size {
+		return
+	}
+	w.modTime, w.size = info.ModTime(), info.Size()
+
+	next, err := Read()
+	if err != nil {
+		w.status.Error = err.Error()
+		return
+	}
+	merged, applied, pending := w.current.merge(next)
+	if len(applied) > 0 {
+		if err := w.apply(merged); err != nil {
+			w.status.Error = err.Error()
+			return
+		}
+		w.current = merged
+		w.status.LastReload = w.status.LastCheck
+		w.status.Reloads++
+		w.status.Applied = applied
+	}
+	w.status.PendingRestart = pending
+	w.status.Error = ""
+}
+
+// merge returns c with the reloadable settings of next, the variables it
+// changed, and the changed variables that need a restart.
+func (c *Config) merge(next *Config) (merged *Config, applied, pending []string) {
+	copied := *c
+	dst := reflect.ValueOf(&copied).Elem()
+	src := reflect.ValueOf(next).Elem()
+	for _, s := range settings {
+		from, to := src.FieldByName(s.field), dst.FieldByName(s.field)
+		if reflect.DeepEqual(from.Interface(), to.Interface()) {
+			continue
+		}
+		if !s.reloadable {
+			pending = append(pending, s.key)
+			continue
+		}
+		to.Set(from)
+		applied = append(applied, s.key)
+	}
+	return &copied, applied, pending
+}
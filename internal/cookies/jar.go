@@ -0,0 +1,202 @@
+// Package cookies implements persistent, named cookie jars kept in a storage.Store.
+package cookies
+
+import (
+	"net"
+	"net/http"
+	"net/url"
+	"strings"
+	"time"
+
+	"golang.org/x/net/publicsuffix"
+)
+
+// Cookie is a stored cookie with the attributes needed to decide where it is sent.
+type Cookie struct {
+	Name     string     `json:"name"`
+	Value    string     `json:"value"`
+	Domain   string     `json:"domain"`
+	Path     string     `json:"path"`
+	HostOnly bool       `json:"hostOnly"` // Set without a Domain attribute
+	Secure   bool       `json:"secure"`
+	HTTPOnly bool       `json:"httpOnly"`
+	SameSite string     `json:"sameSite,omitempty"` // Strict, Lax, None or empty
+	Expires  *time.Time `json:"expires,omitempty"`  // Nil for session cookies
+	Created  time.Time  `json:"created"`
+}
+
+// Jar is a persistent cookie jar following the RFC 6265 storage model.
+type Jar struct {
+	Cookies []Cookie `json:"cookies"`
+}
+
+// SetCookies stores cookies received in a response from u.
+// Cookies with an empty value and an expiry in the past are removed.
+func (j *Jar) SetCookies(u *url.URL, received []*http.Cookie) {
+	now := time.Now()
+	host := canonicalHost(u.Hostname())
+
+	for _, c := range received {
+		cookie := Cookie{
+			Name:     c.Name,
+			Value:    c.Value,
+			Path:     c.Path,
+			Secure:   c.Secure,
+			HTTPOnly: c.HttpOnly,
+			SameSite: sameSiteString(c.SameSite),
+			Created:  now,
+		}
+
+		domain, hostOnly, ok := cookieDomain(host, c.Domain)
+		if !ok {
+			continue
+		}
+		cookie.Domain = domain
+		cookie.HostOnly = hostOnly
+
+		if cookie.Path == "" || !strings.HasPrefix(cookie.Path, "/") {
+			cookie.Path = defaultPath(u.Path)
+		}
+
+		// Max-Age takes precedence over Expires
+		switch {
+		case c.MaxAge < 0:
+			j.remove(cookie.Name, cookie.Domain, cookie.Path)
+			continue
+		case c.MaxAge > 0:
+			expires := now.Add(time.Duration(c.MaxAge) * time.Second)
+			cookie.Expires = &expires
+		case !c.Expires.IsZero():
+			if !c.Expires.After(now) {
+				j.remove(cookie.Name, cookie.Domain, cookie.Path)
+				continue
+			}
+			expires := c.Expires
+			cookie.Expires = &expires
+		}
+
+		j.store(cookie)
+	}
+}
+
+// Cookies returns the stored cookies that would be sent to u by a same-site
+// request, most specific path first.
+func (j *Jar) Cookies(u *url.URL) []*http.Cookie {
+	cookies, _ := j.Evaluate(u, RequestContext{Navigation: true})
+	return cookies
+}
+
+// RemoveExpired drops cookies whose expiry has passed.
+func (j *Jar) RemoveExpired() {
+	now := time.Now()
+	kept := j.Cookies[:0]
+	for _, c := range j.Cookies {
+		if !c.Expired(now) {
+			kept = append(kept, c)
+		}
+	}
+	j.Cookies = kept
+}
+
+// Expired reports whether the cookie has expired at now.
+func (c Cookie) Expired(now time.Time) bool {
+	return c.Expires != nil && !c.Expires.After(now)
+}
+
+func (c Cookie) domainMatch(host string) bool {
+	if c.HostOnly {
+		return host == c.Domain
+	}
+	return host == c.Domain || (strings.HasSuffix(host, "."+c.Domain) && net.ParseIP(host) == nil)
+}
+
+// store replaces a cookie with the same name, domain and path, keeping its creation time.
+func (j *Jar) store(cookie Cookie) {
+	for i, existing := range j.Cookies {
+		if existing.Name == cookie.Name && existing.Domain == cookie.Domain && existing.Path == cookie.Path {
+			cookie.Created = existing.Created
+			j.Cookies[i] = cookie
+			return
+		}
+	}
+	j.Cookies = append(j.Cookies, cookie)
+}
+
+func (j *Jar) remove(name, domain, path string) {
+	for i, c := range j.Cookies {
+		if c.Name == name && c.Domain == domain && c.Path == path {
+			j.Cookies = append(j.Cookies[:i], j.Cookies[i+1:]...)
+			return
+		}
+	}
+}
+
+func canonicalHost(host string) string {
+	return strings.TrimSuffix(strings.ToLower(host), ".")
+}
+
+// DomainAllowed reports whether a response from host may set a cookie with
+// the Domain attribute attr, as SetCookies would store it.
+func DomainAllowed(host, attr string) bool {
+	_, _, ok := cookieDomain(canonicalHost(host), attr)
+	return ok
+}
+
+// cookieDomain validates a Domain attribute against the request host.
+// It rejects domains the host does not belong to and public suffixes.
+func cookieDomain(host, attr string) (domain string, hostOnly bool, ok bool) {
+	attr = canonicalHost(strings.TrimPrefix(attr, "."))
+	if attr == "" {
+		return host, true, true
+	}
+
+	if net.ParseIP(host) != nil {
+		return host, true, attr == host
+	}
+
+	// Public suffixes may only be set as host-only cookies on the suffix itself
+	if suffix, _ := publicsuffix.PublicSuffix(attr); suffix == attr {
+		return host, true, attr == host
+	}
+
+	if host != attr && !strings.HasSuffix(host, "."+attr) {
+		return "", false, false
+	}
+	return attr, false, true
+}
+
+// defaultPath computes the default cookie path of a request path (RFC 6265 5.1.4).
+func defaultPath(path string) string {
+	if path == "" || path[0] != '/' {
+		return "/"
+	}
+	i := strings.LastIndex(path, "/")
+	if i == 0 {
+		return "/"
+	}
+	return path[:i]
+}
+
+// pathMatch implements RFC 6265 5.1.4 path matching.
+func pathMatch(requestPath, cookiePath string) bool {
+	if requestPath == cookiePath {
+		return true
+	}
+	if !strings.HasPrefix(requestPath, cookiePath) {
+		return false
+	}
+	return strings.HasSuffix(cookiePath, "/") || requestPath[len(cookiePath)] == '/'
+}
+
+func sameSiteString(mode http.SameSite) string {
+	switch mode {
+	case http.SameSiteStrictMode:
+		return "Strict"
+	case http.SameSiteLaxMode:
+		return "Lax"
+	case http.SameSiteNoneMode:
+		return "None"
+	default:
+		return ""
+	}
+}
@@ -0,0 +1,203 @@
+package cookies_test
+
+import (
+	"net/http"
+	"net/url"
+	"reflect"
+	"testing"
+	"time"
+
+	"zone.digit.tommie/internal/cookies"
+	"zone.digit.tommie/internal/storage"
+)
+
+// received parses Set-Cookie header values as a response would carry them.
+func received(lines ...string) []*http.Cookie {
+	resp := http.Response{Header: http.Header{"Set-Cookie": lines}}
+	return resp.Cookies()
+}
+
+func mustParse(t *testing.T, raw string) *url.URL {
+	u, err := url.Parse(raw)
+	if err != nil {
+		t.Fatal(err)
+	}
+	return u
+}
+
+func names(list []*http.Cookie) []string {
+	out := []string{}
+	for _, c := range list {
+		out = append(out, c.Name)
+	}
+	return out
+}
+
+func TestDomainAllowed(t *testing.T) {
+	tests := []struct {
+		host, attr string
+		want       bool
+	}{
+		{"www.example.com", "", true},
+		{"www.example.com", "example.com", true},
+		{"www.example.com", ".EXAMPLE.com", true},
+		{"www.example.com.", "example.com", true},
+		{"www.example.com", "www.example.com", true},
+		{"example.com", "www.example.com", false},
+		{"www.example.com", "other.com", false},
+		{"www.example.com", "ample.com", false},
+		{"www.example.com", "com", false},
+		{"www.example.co.uk", "co.uk", false},
+		{"github.io", "github.io", true},
+		{"192.168.0.1", "192.168.0.1", true},
+		{"192.168.0.1", "168.0.1", false},
+	}
+	for _, tt := range tests {
+		if got := cookies.DomainAllowed(tt.host, tt.attr); got != tt.want {
+			t.Errorf("DomainAllowed(%q, %q) = %v, want %v", tt.host, tt.attr, got, tt.want)
+		}
+	}
+}
+
+func TestJarCookies(t *testing.T) {
+	var jar cookies.Jar
+	jar.SetCookies(mustParse(t, "https://www.example.com/app/login"), received(
+		"host=1",
+		"dom=2; Domain=example.com; Path=/",
+		"secure=3; Path=/; Secure",
+		"deep=4; Path=/app/admin",
+		"evil=5; Domain=other.com",
+		"suffix=6; Domain=com",
+	))
+
+	tests := []struct {
+		url  string
+		want []string
+	}{
+		{"https://www.example.com/app/admin/users", []string{"deep", "host", "dom", "secure"}},
+		{"http://www.example.com/app", []string{"host", "dom"}},
+		{"https://www.example.com/application", []string{"dom", "secure"}},
+		{"https://api.example.com/", []string{"dom"}},
+		{"https://WWW.EXAMPLE.COM/app/", []string{"host", "dom", "secure"}},
+		{"https://other.com/", []string{}},
+	}
+	for _, tt := range tests {
+		t.Run(tt.url, func(t *testing.T) {
+			if got := names(jar.Cookies(mustParse(t, tt.url))); !reflect.DeepEqual(got, tt.want) {
+				t.Errorf("Cookies = %v, want %v", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestJarUpdates(t *testing.T) {
+	u, _ := url.Parse("https://example.com/")
+	tests := []struct {
+		name  string
+		lines []string
+		want  map[string]string // Stored values by name
+	}{
+		{"replaced", []string{"a=1", "a=2"}, map[string]string{"a": "2"}},
+		{"removed by max-age", []string{"a=1", "b=1", "a=; Max-Age=0"}, map[string]string{"b": "1"}},
+		{"removed by past expiry", []string{"a=1", "a=1; Expires=Thu, 01 Jan 1970 00:00:00 GMT"}, map[string]string{}},
+		{"other path kept", []string{"a=1; Path=/", "a=2; Path=/x", "a=; Path=/x; Max-Age=0"}, map[string]string{"a": "1"}},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			var jar cookies.Jar
+			for _, line := range tt.lines {
+				jar.SetCookies(u, received(line))
+			}
+			got := map[string]string{}
+			for _, c := range jar.Cookies {
+				got[c.Name] = c.Value
+			}
+			if !reflect.DeepEqual(got, tt.want) {
+				t.Errorf("stored %v, want %v", got, tt.want)
+			}
+		})
+	}
+
+	var jar cookies.Jar
+	jar.SetCookies(u, received("a=1; Max-Age=3600"))
+	if c := jar.Cookies[0]; c.Expires == nil || time.Until(*c.Expires) < 59*time.Minute {
+		t.Errorf("Max-Age=3600 expires at %v, want in an hour", c.Expires)
+	}
+}
+
+func TestJarSameSite(t *testing.T) {
+	var jar cookies.Jar
+	target := mustParse(t, "https://example.com/")
+	jar.SetCookies(target, received(
+		"strict=1; SameSite=Strict; Secure",
+		"lax=1; SameSite=Lax",
+		"none=1; SameSite=None; Secure",
+		"insecureNone=1; SameSite=None",
+		"default=1",
+	))
+
+	tests := []struct {
+		name string
+		rc   cookies.RequestContext
+		want []string
+	}{
+		{"typed navigation", cookies.RequestContext{Navigation: true}, []string{"strict", "lax", "none", "insecureNone", "default"}},
+		{"same site", cookies.RequestContext{Initiator: mustParse(t, "https://www.example.com/"), Method: "POST"}, []string{"strict", "lax", "none", "insecureNone", "default"}},
+		{"cross-site navigation", cookies.RequestContext{Initiator: mustParse(t, "https://other.com/"), Navigation: true, Method: "GET"}, []string{"lax", "none", "default"}},
+		{"cross-site post", cookies.RequestContext{Initiator: mustParse(t, "https://other.com/"), Navigation: true, Method: "POST"}, []string{"none"}},
+		{"cross-site fetch", cookies.RequestContext{Initiator: mustParse(t, "https://other.com/"), Method: "GET"}, []string{"none"}},
+		{"other scheme", cookies.RequestContext{Initiator: mustParse(t, "http://example.com/"), Navigation: true}, []string{"lax", "none", "default"}},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			sent, decisions := jar.Evaluate(target, tt.rc)
+			if got := names(sent); !reflect.DeepEqual(got, tt.want) {
+				t.Errorf("sent %v, want %v", got, tt.want)
+			}
+			if len(decisions) != len(jar.Cookies) {
+				t.Fatalf("got %d decisions, want one per cookie", len(decisions))
+			}
+			for _, d := range decisions {
+				if d.Sent == (d.Reason != "") {
+					t.Errorf("decision %+v: a cookie is withheld exactly when there is a reason", d)
+				}
+			}
+		})
+	}
+}
+
+func TestStore(t *testing.T) {
+	store := storage.NewMemory()
+	past := time.Now().Add(-time.Hour)
+	jar := &cookies.Jar{Cookies: []cookies.Cookie{
+		{Name: "kept", Value: "1", Domain: "example.com", Path: "/"},
+		{Name: "expired", Value: "1", Domain: "example.com", Path: "/", Expires: &past},
+	}}
+	for _, name := range []string{"work", "home"} {
+		if err := cookies.Save(store, name, jar); err != nil {
+			t.Fatal(err)
+		}
+	}
+
+	loaded, err := cookies.Load(store, "work")
+	if err != nil {
+		t.Fatal(err)
+	}
+	if len(loaded.Cookies) != 1 || loaded.Cookies[0].Name != "kept" {
+		t.Errorf("loaded %+v, want only the unexpired cookie", loaded.Cookies)
+	}
+	if list, _ := cookies.List(store); !reflect.DeepEqual(list, []string{"home", "work"}) {
+		t.Errorf("List = %v, want [home work]", list)
+	}
+	if err := cookies.Clear(store, "work"); err != nil {
+		t.Fatal(err)
+	}
+	if empty, err := cookies.Load(store, "work"); err != nil || len(empty.Cookies) != 0 {
+		t.Errorf("after Clear: Load = %+v, %v, want an empty jar", empty, err)
+	}
+
+	store.Set(cookies.StoreName, "corrupt", "{")
+	if _, err := cookies.Load(store, "corrupt"); err == nil {
+		t.Error("a corrupt jar was loaded")
+	}
+}
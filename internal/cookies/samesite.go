@@ -0,0 +1,139 @@
+package cookies
+
+import (
+	"net"
+	"net/http"
+	"net/url"
+	"sort"
+	"strings"
+	"time"
+
+	"golang.org/x/net/publicsuffix"
+)
+
+// RequestContext describes how a browser would issue a request, which
+// decides whether SameSite cookies are attached.
+type RequestContext struct {
+	Initiator  *url.URL // Page issuing the request; nil for a typed or bookmarked navigation
+	Navigation bool     // Top-level navigation rather than a fetch or subresource load
+	Method     string
+}
+
+// Decision explains whether a stored cookie would be sent by a browser.
+type Decision struct {
+	Name   string
+	Domain string
+	Path   string
+	Sent   bool
+	Reason string
+}
+
+// Evaluate applies browser cookie rules (domain, path, Secure, expiry and
+// SameSite) to every stored cookie whose domain matches u. It returns the
+// cookies to send, most specific path first, and a decision per cookie.
+func (j *Jar) Evaluate(u *url.URL, rc RequestContext) ([]*http.Cookie, []Decision) {
+	now := time.Now()
+	host := canonicalHost(u.Hostname())
+	secure := u.Scheme == "https" || u.Scheme == "wss"
+	path := u.Path
+	if path == "" {
+		path = "/"
+	}
+	crossSite := rc.Initiator != nil && !sameSite(rc.Initiator, u)
+
+	var sent []Cookie
+	var decisions []Decision
+	for _, c := range j.Cookies {
+		if !c.domainMatch(host) {
+			continue
+		}
+		reason := c.withheldReason(now, path, secure, crossSite, rc)
+		decisions = append(decisions, Decision{
+			Name:   c.Name,
+			Domain: c.Domain,
+			Path:   c.Path,
+			Sent:   reason == "",
+			Reason: reason,
+		})
+		if reason == "" {
+			sent = append(sent, c)
+		}
+	}
+
+	sort.SliceStable(sent, func(a, b int) bool {
+		if len(sent[a].Path) != len(sent[b].Path) {
+			return len(sent[a].Path) > len(sent[b].Path)
+		}
+		return sent[a].Created.Before(sent[b].Created)
+	})
+
+	cookies := make([]*http.Cookie, len(sent))
+	for i, c := range sent {
+		cookies[i] = &http.Cookie{Name: c.Name, Value: c.Value}
+	}
+	return cookies, decisions
+}
+
+// withheldReason returns why a browser would not send the cookie, or "" if it would.
+func (c Cookie) withheldReason(now time.Time, path string, secure, crossSite bool, rc RequestContext) string {
+	switch {
+	case c.Expired(now):
+		return "expired"
+	case !pathMatch(path, c.Path):
+		return "path " + c.Path + " does not match"
+	case c.Secure && !secure:
+		return "Secure cookie on an insecure request"
+	case !crossSite:
+		return ""
+	}
+
+	switch c.SameSite {
+	case "None":
+		if !c.Secure {
+			return "SameSite=None without Secure is rejected by browsers"
+		}
+		return ""
+	case "Strict":
+		return "SameSite=Strict on a cross-site request"
+	case "Lax":
+		if rc.Navigation && safeMethod(rc.Method) {
+			return ""
+		}
+		return "SameSite=Lax on a cross-site request that is not a safe top-level navigation"
+	default:
+		if rc.Navigation && safeMethod(rc.Method) {
+			return ""
+		}
+		return "no SameSite attribute (treated as Lax) on a cross-site request that is not a safe top-level navigation"
+	}
+}
+
+func safeMethod(method string) bool {
+	switch strings.ToUpper(method) {
+	case "", "GET", "HEAD", "OPTIONS", "TRACE":
+		return true
+	}
+	return false
+}
+
+// sameSite reports whether two URLs are schemefully same-site: same scheme
+// and same registrable domain.
+func sameSite(a, b *url.URL) bool {
+	if !strings.EqualFold(a.Scheme, b.Scheme) {
+		return false
+	}
+	return site(a.Hostname()) == site(b.Hostname())
+}
+
+// site returns the registrable domain of host, or host itself for IPs and
+// hosts without one.
+func site(host string) string {
+	host = canonicalHost(host)
+	if net.ParseIP(host) != nil {
+		return host
+	}
+	if etld1, err := publicsuffix.EffectiveTLDPlusOne(host); err == nil {
+		return etld1
+	}
+	return host
+}
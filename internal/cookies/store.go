@@ -0,0 +1,53 @@
+package cookies
+
+import (
+	"encoding/json"
+	"fmt"
+	"sort"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+// StoreName is the storage store holding cookie jars, keyed by jar name.
+const StoreName = "cookieJars"
+
+// Load returns the named jar, or an empty jar if it does not exist yet.
+func Load(store storage.Store, name string) (*Jar, error) {
+	value, err := store.Get(StoreName, name)
+	if err != nil {
+		return nil, err
+	}
+	jar := &Jar{}
+	if value == nil {
+		return jar, nil
+	}
+	if err := json.Unmarshal([]byte(*value), jar); err != nil {
+		return nil, fmt.Errorf("corrupt cookie jar %s: %w", name, err)
+	}
+	jar.RemoveExpired()
+	return jar, nil
+}
+
+// Save persists the named jar.
+func Save(store storage.Store, name string, jar *Jar) error {
+	data, err := json.Marshal(jar)
+	if err != nil {
+		return err
+	}
+	return store.Set(StoreName, name, string(data))
+}
+
+// List returns the names of all stored jars in sorted order.
+func List(store storage.Store) ([]string, error) {
+	names, err := store.Keys(StoreName)
+	if err != nil {
+		return nil, err
+	}
+	sort.Strings(names)
+	return names, nil
+}
+
+// Clear deletes the named jar.
+func Clear(store storage.Store, name string) error {
+	return store.Remove(StoreName, name)
+}
@@ -0,0 +1,92 @@
+// Package environments keeps named variable sets in a storage.Store and
+// expands {{variable}} templates with them.
+package environments
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"regexp"
+	"sort"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+// StoreName is the storage store holding environments, keyed by name.
+const StoreName = "environments"
+
+// Environment is a named set of template variables.
+type Environment struct {
+	Name      string            `json:"name"`
+	Variables map[string]string `json:"variables"`
+	Updated   time.Time         `json:"updated"`
+}
+
+// templatePattern matches {{name}} with optional surrounding spaces.
+var templatePattern = regexp.MustCompile(`\{\{\s*([A-Za-z0-9_.\-]+)\s*\}\}`)
+
+// Load returns the named environment, or nil if it does not exist.
+func Load(store storage.Store, name string) (*Environment, error) {
+	value, err := store.Get(StoreName, name)
+	if err != nil || value == nil {
+		return nil, err
+	}
+	var env Environment
+	if err := json.Unmarshal([]byte(*value), &env); err != nil {
+		return nil, fmt.Errorf("corrupt environment %s: %w", name, err)
+	}
+	env.Name = name
+	if env.Variables == nil {
+		env.Variables = map[string]string{}
+	}
+	return &env, nil
+}
+
+// Save stores the variables of the named environment, replacing previous ones.
+func Save(store storage.Store, name string, variables map[string]string) (*Environment, error) {
+	if strings.TrimSpace(name) == "" {
+		return nil, errors.New("environment name is required")
+	}
+	if variables == nil {
+		variables = map[string]string{}
+	}
+	env := &Environment{Name: name, Variables: variables, Updated: time.Now().UTC()}
+	data, err := json.Marshal(env)
+	if err != nil {
+		return nil, err
+	}
+	if err := store.Set(StoreName, name, string(data)); err != nil {
+		return nil, err
+	}
+	return env, nil
+}
+
+// List returns the names of all environments in sorted order.
+func List(store storage.Store) ([]string, error) {
+	names, err := store.Keys(StoreName)
+	if err != nil {
+		return nil, err
+	}
+	sort.Strings(names)
+	return names, nil
+}
+
+// Delete removes the named environment.
+func Delete(store storage.Store, name string) error {
+	return store.Remove(StoreName, name)
+}
+
+// Expand replaces {{name}} references with their values. References without
+// a value are left as written and added to unresolved.
+func Expand(text string, variables map[string]string, unresolved map[string]bool) string {
+	return templatePattern.ReplaceAllStringFunc(text, func(ref string) string {
+		name := templatePattern.FindStringSubmatch(ref)[1]
+		if value, ok := variables[name]; ok {
+			return value
+		}
+		unresolved[name] = true
+		return ref
+	})
+}
@@ -0,0 +1,408 @@
+package grpc
+
+import (
+	"encoding/base64"
+	"encoding/json"
+	"fmt"
+	"math"
+	"strconv"
+)
+
+// Marshal encodes a message given in its proto3 JSON form.
+func Marshal(m *Message, value map[string]interface{}) ([]byte, error) {
+	var b []byte
+	for key, v := range value {
+		f, ok := m.byName[key]
+		if !ok {
+			return nil, fmt.Errorf("%s has no field %q", m.Name, key)
+		}
+		if v == nil {
+			continue
+		}
+		var err error
+		if b, err = appendField(b, f, v); err != nil {
+			return nil, fmt.Errorf("%s.%s: %w", m.Name, f.Name, err)
+		}
+	}
+	return b, nil
+}
+
+// appendField encodes every value of a field.
+func appendField(b []byte, f *Field, v interface{}) ([]byte, error) {
+	if f.message != nil && f.message.mapEntry {
+		entries, ok := v.(map[string]interface{})
+		if !ok {
+			return nil, fmt.Errorf("expected an object, got %T", v)
+		}
+		key, value := f.message.byNumber[1], f.message.byNumber[2]
+		for k, item := range entries {
+			var entry []byte
+			var err error
+			if entry, err = appendValue(entry, key, k); err != nil {
+				return nil, err
+			}
+			if entry, err = appendValue(entry, value, item); err != nil {
+				return nil, err
+			}
+			b = appendBytes(b, f.Number, entry)
+		}
+		return b, nil
+	}
+
+	if !f.Repeated {
+		return appendValue(b, f, v)
+	}
+	items, ok := v.([]interface{})
+	if !ok {
+		return nil, fmt.Errorf("expected an array, got %T", v)
+	}
+	if !packable(f.Type) {
+		for _, item := range items {
+			var err error
+			if b, err = appendValue(b, f, item); err != nil {
+				return nil, err
+			}
+		}
+		return b, nil
+	}
+
+	// Repeated scalars are packed, as proto3 does by default
+	var packed []byte
+	for _, item := range items {
+		var err error
+		if packed, err = appendScalar(packed, f, item); err != nil {
+			return nil, err
+		}
+	}
+	return appendBytes(b, f.Number, packed), nil
+}
+
+// appendValue encodes a single value with its tag.
+func appendValue(b []byte, f *Field, v interface{}) ([]byte, error) {
+	switch f.Type {
+	case typeString:
+		s, ok := v.(string)
+		if !ok {
+			return nil, fmt.Errorf("expected a string, got %T", v)
+		}
+		return appendBytes(b, f.Number, []byte(s)), nil
+	case typeBytes:
+		s, ok := v.(string)
+		if !ok {
+			return nil, fmt.Errorf("expected base64 bytes, got %T", v)
+		}
+		data, err := base64.StdEncoding.DecodeString(s)
+		if err != nil {
+			if data, err = base64.URLEncoding.DecodeString(s); err != nil {
+				return nil, fmt.Errorf("invalid base64: %w", err)
+			}
+		}
+		return appendBytes(b, f.Number, data), nil
+	case typeMessage, typeGroup:
+		obj, ok := v.(map[string]interface{})
+		if !ok {
+			return nil, fmt.Errorf("expected an object, got %T", v)
+		}
+		data, err := Marshal(f.message, obj)
+		if err != nil {
+			return nil, err
+		}
+		return appendBytes(b, f.Number, data), nil
+	}
+	b = appendTag(b, f.Number, wireType(f.Type))
+	return appendScalar(b, f, v)
+}
+
+// appendScalar encodes a numeric, bool or enum value without a tag.
+func appendScalar(b []byte, f *Field, v interface{}) ([]byte, error) {
+	switch f.Type {
+	case typeBool:
+		flag, ok := v.(bool)
+		if !ok {
+			return nil, fmt.Errorf("expected a bool, got %T", v)
+		}
+		if flag {
+			return append(b, 1), nil
+		}
+		return append(b, 0), nil
+	case typeEnum:
+		if name, ok := v.(string); ok {
+			number, ok := f.enum.values[name]
+			if !ok {
+				return nil, fmt.Errorf("%s has no value %q", f.enum.Name, name)
+			}
+			return appendVarint(b, uint64(int64(number))), nil
+		}
+		n, err := toInt(v, 32)
+		if err != nil {
+			return nil, err
+		}
+		return appendVarint(b, uint64(n)), nil
+	case typeDouble, typeFloat:
+		x, err := toFloat(v)
+		if err != nil {
+			return nil, err
+		}
+		if f.Type == typeFloat {
+			return appendFixed(b, uint64(math.Float32bits(float32(x))), 4), nil
+		}
+		return appendFixed(b, math.Float64bits(x), 8), nil
+	case typeUint32, typeUint64, typeFixed32, typeFixed64:
+		bits := 64
+		if f.Type == typeUint32 || f.Type == typeFixed32 {
+			bits = 32
+		}
+		n, err := toUint(v, bits)
+		if err != nil {
+			return nil, err
+		}
+		switch f.Type {
+		case typeFixed32:
+			return appendFixed(b, n, 4), nil
+		case typeFixed64:
+			return appendFixed(b, n, 8), nil
+		}
+		return appendVarint(b, n), nil
+	}
+
+	bits := 64
+	if f.Type == typeInt32 || f.Type == typeSint32 || f.Type == typeSfixed32 {
+		bits = 32
+	}
+	n, err := toInt(v, bits)
+	if err != nil {
+		return nil, err
+	}
+	switch f.Type {
+	case typeSint32, typeSint64:
+		return appendVarint(b, uint64(n<<1)^uint64(n>>63)), nil
+	case typeSfixed32:
+		return appendFixed(b, uint64(n), 4), nil
+	case typeSfixed64:
+		return appendFixed(b, uint64(n), 8), nil
+	}
+	return appendVarint(b, uint64(n)), nil
+}
+
+// Unmarshal decodes a message into its proto3 JSON form. Fields missing
+// from the schema are kept under their number.
+func Unmarshal(m *Message, data []byte) (map[string]interface{}, error) {
+	return unmarshal(m, data, 0)
+}
+
+func unmarshal(m *Message, data []byte, depth int) (map[string]interface{}, error) {
+	if depth > maxDepth {
+		return nil, fmt.Errorf("message nesting exceeds %d levels", maxDepth)
+	}
+	fields, err := readFields(data)
+	if err != nil {
+		return nil, err
+	}
+	out := make(map[string]interface{})
+	for _, raw := range fields {
+		f, ok := m.byNumber[raw.number]
+		if !ok {
+			out[strconv.Itoa(raw.number)] = rawValue(raw, depth)
+			continue
+		}
+
+		if f.message != nil && f.message.mapEntry {
+			entry, err := unmarshal(f.message, raw.bytes, depth+1)
+			if err != nil {
+				return nil, fmt.Errorf("%s.%s: %w", m.Name, f.Name, err)
+			}
+			entries, _ := out[f.JSONName].(map[string]interface{})
+			if entries == nil {
+				entries = make(map[string]interface{})
+				out[f.JSONName] = entries
+			}
+			keyField, valueField := f.message.byNumber[1], f.message.byNumber[2]
+			entries[fmt.Sprint(entry[keyField.JSONName])] = entry[valueField.JSONName]
+			continue
+		}
+
+		var values []interface{}
+		if raw.wire == wireBytes && f.Repeated && packable(f.Type) {
+			values, err = unpack(f, raw.bytes)
+		} else {
+			var v interface{}
+			v, err = decodeValue(f, raw, depth)
+			values = []interface{}{v}
+		}
+		if err != nil {
+			return nil, fmt.Errorf("%s.%s: %w", m.Name, f.Name, err)
+		}
+
+		if !f.Repeated {
+			// The last occurrence of a singular field wins
+			out[f.JSONName] = values[len(values)-1]
+			continue
+		}
+		existing, _ := out[f.JSONName].([]interface{})
+		out[f.JSONName] = append(existing, values...)
+	}
+	return out, nil
+}
+
+// unpack decodes a packed repeated scalar field.
+func unpack(f *Field, data []byte) ([]interface{}, error) {
+	var values []interface{}
+	for len(data) > 0 {
+		raw := field{number: f.Number, wire: wireType(f.Type)}
+		n := 0
+		switch raw.wire {
+		case wireVarint:
+			raw.value, n = readVarint(data)
+		case wireFixed64:
+			if len(data) >= 8 {
+				raw.value, n = leUint(data[:8]), 8
+			}
+		case wireFixed32:
+			if len(data) >= 4 {
+				raw.value, n = leUint(data[:4]), 4
+			}
+		}
+		if n == 0 {
+			return nil, errTruncated
+		}
+		data = data[n:]
+		v, err := decodeValue(f, raw, 0)
+		if err != nil {
+			return nil, err
+		}
+		values = append(values, v)
+	}
+	return values, nil
+}
+
+// decodeValue converts one field value to its proto3 JSON form. 64-bit
+// integers are strings, as JSON numbers cannot hold them exactly.
+func decodeValue(f *Field, raw field, depth int) (interface{}, error) {
+	if expected := wireType(f.Type); raw.wire != expected {
+		return nil, fmt.Errorf("wire type %d does not match field type", raw.wire)
+	}
+	v := raw.value
+	switch f.Type {
+	case typeString:
+		return string(raw.bytes), nil
+	case typeBytes:
+		return base64.StdEncoding.EncodeToString(raw.bytes), nil
+	case typeMessage, typeGroup:
+		return unmarshal(f.message, raw.bytes, depth+1)
+	case typeBool:
+		return v != 0, nil
+	case typeEnum:
+		if name, ok := f.enum.names[int32(v)]; ok {
+			return name, nil
+		}
+		return int32(v), nil
+	case typeInt32, typeSfixed32:
+		return int32(v), nil
+	case typeUint32, typeFixed32:
+		return uint32(v), nil
+	case typeSint32:
+		return int32(uint32(v)>>1) ^ -int32(v&1), nil
+	case typeInt64, typeSfixed64:
+		return strconv.FormatInt(int64(v), 10), nil
+	case typeUint64, typeFixed64:
+		return strconv.FormatUint(v, 10), nil
+	case typeSint64:
+		return strconv.FormatInt(int64(v>>1)^-int64(v&1), 10), nil
+	case typeFloat:
+		return jsonFloat(float64(math.Float32frombits(uint32(v)))), nil
+	case typeDouble:
+		return jsonFloat(math.Float64frombits(v)), nil
+	}
+	return nil, fmt.Errorf("unsupported field type %d", f.Type)
+}
+
+// jsonFloat spells out the values JSON numbers cannot represent.
+func jsonFloat(x float64) interface{} {
+	switch {
+	case math.IsNaN(x):
+		return "NaN"
+	case math.IsInf(x, 1):
+		return "Infinity"
+	case math.IsInf(x, -1):
+		return "-Infinity"
+	}
+	return x
+}
+
+func wireType(fieldType int) int {
+	switch fieldType {
+	case typeDouble, typeFixed64, typeSfixed64:
+		return wireFixed64
+	case typeFloat, typeFixed32, typeSfixed32:
+		return wireFixed32
+	case typeString, typeBytes, typeMessage, typeGroup:
+		return wireBytes
+	}
+	return wireVarint
+}
+
+func packable(fieldType int) bool {
+	return wireType(fieldType) != wireBytes
+}
+
+// toInt accepts JSON numbers and decimal strings within the given size.
+func toInt(v interface{}, bits int) (int64, error) {
+	var n int64
+	switch x := v.(type) {
+	case float64:
+		if x != math.Trunc(x) {
+			return 0, fmt.Errorf("%v is not an integer", x)
+		}
+		n = int64(x)
+	case json.Number:
+		var err error
+		if n, err = x.Int64(); err != nil {
+			return 0, err
+		}
+	case string:
+		var err error
+		if n, err = strconv.ParseInt(x, 10, bits); err != nil {
+			return 0, err
+		}
+	default:
+		return 0, fmt.Errorf("expected an integer, got %T", v)
+	}
+	if bits == 32 && (n < math.MinInt32 || n > math.MaxInt32) {
+		return 0, fmt.Errorf("%d overflows a 32-bit integer", n)
+	}
+	return n, nil
+}
+
+func toUint(v interface{}, bits int) (uint64, error) {
+	if s, ok := v.(string); ok {
+		return strconv.ParseUint(s, 10, bits)
+	}
+	n, err := toInt(v, 64)
+	if err != nil {
+		return 0, err
+	}
+	if n < 0 || (bits == 32 && n > math.MaxUint32) {
+		return 0, fmt.Errorf("%d is out of range", n)
+	}
+	return uint64(n), nil
+}
+
+func toFloat(v interface{}) (float64, error) {
+	switch x := v.(type) {
+	case float64:
+		return x, nil
+	case json.Number:
+		return x.Float64()
+	case string:
+		switch x {
+		case "NaN":
+			return math.NaN(), nil
+		case "Infinity":
+			return math.Inf(1), nil
+		case "-Infinity":
+			return math.Inf(-1), nil
+		}
+		return strconv.ParseFloat(x, 64)
+	}
+	return 0, fmt.Errorf("expected a number, got %T", v)
+}
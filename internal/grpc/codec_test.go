@@ -0,0 +1,147 @@
+package grpc_test
+
+import (
+	"encoding/json"
+	"reflect"
+	"testing"
+
+	"zone.digit.tommie/internal/grpc"
+)
+
+func TestMarshalRoundTrip(t *testing.T) {
+	item := itemMessage(t)
+	tests := []struct {
+		name  string
+		input string
+		want  map[string]interface{}
+	}{
+		{
+			"scalars",
+			`{"name":"widget","id":"9007199254740993","delta":-5,"ratio":0.5,"blob":"AQID","displayName":"W"}`,
+			map[string]interface{}{"name": "widget", "id": "9007199254740993", "delta": int32(-5), "ratio": 0.5, "blob": "AQID", "displayName": "W"},
+		},
+		{
+			"proto field names",
+			`{"display_name":"W","id":42}`,
+			map[string]interface{}{"displayName": "W", "id": "42"},
+		},
+		{
+			"packed repeated",
+			`{"scores":[1,-2,3]}`,
+			map[string]interface{}{"scores": []interface{}{int32(1), int32(-2), int32(3)}},
+		},
+		{"enum by name", `{"color":"GREEN"}`, map[string]interface{}{"color": "GREEN"}},
+		{"enum by number", `{"color":1}`, map[string]interface{}{"color": "GREEN"}},
+		{"unknown enum number", `{"color":7}`, map[string]interface{}{"color": int32(7)}},
+		{
+			"map and nested message",
+			`{"tags":{"a":1,"b":2},"child":{"name":"inner","child":{"id":"1"}}}`,
+			map[string]interface{}{
+				"tags":  map[string]interface{}{"a": int32(1), "b": int32(2)},
+				"child": map[string]interface{}{"name": "inner", "child": map[string]interface{}{"id": "1"}},
+			},
+		},
+		{"NaN", `{"ratio":"NaN"}`, map[string]interface{}{"ratio": "NaN"}},
+		{"negative infinity", `{"ratio":"-Infinity"}`, map[string]interface{}{"ratio": "-Infinity"}},
+		{"null", `{"name":null}`, map[string]interface{}{}},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			var value map[string]interface{}
+			if err := json.Unmarshal([]byte(tt.input), &value); err != nil {
+				t.Fatal(err)
+			}
+			data, err := grpc.Marshal(item, value)
+			if err != nil {
+				t.Fatalf("Marshal: %v", err)
+			}
+			got, err := grpc.Unmarshal(item, data)
+			if err != nil {
+				t.Fatalf("Unmarshal: %v", err)
+			}
+			if !reflect.DeepEqual(got, tt.want) {
+				t.Errorf("round trip = %#v, want %#v", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestMarshalErrors(t *testing.T) {
+	item := itemMessage(t)
+	for _, input := range []string{
+		`{"nope":1}`,
+		`{"scores":"1"}`,
+		`{"color":"PURPLE"}`,
+		`{"delta":1e10}`,
+		`{"id":1.5}`,
+		`{"name":1}`,
+		`{"blob":"%%%"}`,
+		`{"tags":[1]}`,
+		`{"child":{"nope":true}}`,
+	} {
+		t.Run(input, func(t *testing.T) {
+			var value map[string]interface{}
+			if err := json.Unmarshal([]byte(input), &value); err != nil {
+				t.Fatal(err)
+			}
+			if _, err := grpc.Marshal(item, value); err == nil {
+				t.Error("the value was encoded")
+			}
+		})
+	}
+}
+
+func TestUnmarshal(t *testing.T) {
+	item := itemMessage(t)
+
+	got, err := grpc.Unmarshal(item, pb(nil).str(1, "first").str(1, "last").varint(99, 7))
+	if err != nil {
+		t.Fatal(err)
+	}
+	if want := map[string]interface{}{"name": "last", "99": uint64(7)}; !reflect.DeepEqual(got, want) {
+		t.Errorf("Unmarshal = %#v, want %#v", got, want)
+	}
+
+	// Unpacked repeated scalars are accepted too
+	got, err = grpc.Unmarshal(item, pb(nil).varint(3, 1).varint(3, 2))
+	if err != nil {
+		t.Fatal(err)
+	}
+	if want := []interface{}{int32(1), int32(2)}; !reflect.DeepEqual(got["scores"], want) {
+		t.Errorf("scores = %#v, want %#v", got["scores"], want)
+	}
+
+	for name, data := range map[string][]byte{
+		"wire type mismatch": pb(nil).varint(1, 5),
+		"truncated":          {0x0a, 5, 'a'},
+		"truncated varint":   {0x10, 0x80},
+	} {
+		if _, err := grpc.Unmarshal(item, data); err == nil {
+			t.Errorf("%s: the message was decoded", name)
+		}
+	}
+}
+
+func TestDecodeRaw(t *testing.T) {
+	tests := []struct {
+		name string
+		data []byte
+		want map[string]interface{}
+	}{
+		{"scalars", pb(nil).str(1, "widget").varint(2, 5), map[string]interface{}{"1": "widget", "2": uint64(5)}},
+		{"repeated", pb(nil).varint(3, 1).varint(3, 2), map[string]interface{}{"3": []interface{}{uint64(1), uint64(2)}}},
+		{"nested", pb(nil).field(6, pb(nil).str(1, "inner")), map[string]interface{}{"6": map[string]interface{}{"1": "inner"}}},
+		{"text that parses as protobuf", pb(nil).str(1, "hi"), map[string]interface{}{"1": "hi"}},
+		{"binary", pb(nil).field(9, []byte{0xff, 0xfe}), map[string]interface{}{"9": "//4="}},
+		{"beyond 2^53", pb(nil).varint(2, 1<<60), map[string]interface{}{"2": "1152921504606846976"}},
+		{"empty", nil, map[string]interface{}{}},
+		{"not protobuf", []byte{0xff}, nil},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := grpc.DecodeRaw(tt.data); !reflect.DeepEqual(got, tt.want) {
+				t.Errorf("DecodeRaw = %#v, want %#v", got, tt.want)
+			}
+		})
+	}
+}
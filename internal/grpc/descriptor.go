@@ -0,0 +1,309 @@
+package grpc
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+)
+
+// Field types of FieldDescriptorProto.
+const (
+	typeDouble   = 1
+	typeFloat    = 2
+	typeInt64    = 3
+	typeUint64   = 4
+	typeInt32    = 5
+	typeFixed64  = 6
+	typeFixed32  = 7
+	typeBool     = 8
+	typeString   = 9
+	typeGroup    = 10
+	typeMessage  = 11
+	typeBytes    = 12
+	typeUint32   = 13
+	typeEnum     = 14
+	typeSfixed32 = 15
+	typeSfixed64 = 16
+	typeSint32   = 17
+	typeSint64   = 18
+)
+
+const labelRepeated = 3
+
+// Registry holds the messages, enums and methods of a FileDescriptorSet.
+type Registry struct {
+	messages map[string]*Message
+	enums    map[string]*Enum
+	methods  map[string]*Method
+}
+
+// Message is the schema of a protobuf message.
+type Message struct {
+	Name     string // Fully qualified, without the leading dot
+	Fields   []*Field
+	mapEntry bool
+	byName   map[string]*Field
+	byNumber map[int]*Field
+}
+
+// Field is a field of a message.
+type Field struct {
+	Name     string
+	JSONName string
+	Number   int
+	Repeated bool
+	Type     int
+	typeName string
+	message  *Message
+	enum     *Enum
+}
+
+// Enum is the schema of a protobuf enum.
+type Enum struct {
+	Name   string
+	names  map[int32]string
+	values map[string]int32
+}
+
+// Method is an RPC of a service.
+type Method struct {
+	Name            string // package.Service/Method
+	Input           *Message
+	Output          *Message
+	ClientStreaming bool
+	ServerStreaming bool
+}
+
+// ParseDescriptorSet reads a serialized FileDescriptorSet, as written by
+// protoc --descriptor_set_out or buf build.
+func ParseDescriptorSet(data []byte) (*Registry, error) {
+	files, err := readFields(data)
+	if err != nil {
+		return nil, fmt.Errorf("invalid descriptor set: %w", err)
+	}
+	r := &Registry{
+		messages: make(map[string]*Message),
+		enums:    make(map[string]*Enum),
+		methods:  make(map[string]*Method),
+	}
+
+	type pendingMethod struct {
+		method        *Method
+		input, output string
+	}
+	var methods []pendingMethod
+	for _, file := range files {
+		if file.number != 1 || file.wire != wireBytes {
+			continue
+		}
+		fields, err := readFields(file.bytes)
+		if err != nil {
+			return nil, fmt.Errorf("invalid file descriptor: %w", err)
+		}
+		pkg := ""
+		for _, f := range fields {
+			if f.number == 2 {
+				pkg = string(f.bytes)
+			}
+		}
+		for _, f := range fields {
+			switch f.number {
+			case 4:
+				if err := r.addMessage(pkg, f.bytes); err != nil {
+					return nil, err
+				}
+			case 5:
+				if err := r.addEnum(pkg, f.bytes); err != nil {
+					return nil, err
+				}
+			case 6:
+				service, err := readFields(f.bytes)
+				if err != nil {
+					return nil, fmt.Errorf("invalid service descriptor: %w", err)
+				}
+				name := qualify(pkg, stringField(service, 1))
+				for _, m := range service {
+					if m.number != 2 {
+						continue
+					}
+					mf, err := readFields(m.bytes)
+					if err != nil {
+						return nil, fmt.Errorf("invalid method descriptor: %w", err)
+					}
+					method := &Method{
+						Name:            name + "/" + stringField(mf, 1),
+						ClientStreaming: varintField(mf, 5) != 0,
+						ServerStreaming: varintField(mf, 6) != 0,
+					}
+					methods = append(methods, pendingMethod{method, stringField(mf, 2), stringField(mf, 3)})
+				}
+			}
+		}
+	}
+
+	// Type names can refer to files later in the set, so resolve them last
+	for _, m := range r.messages {
+		for _, f := range m.Fields {
+			switch f.Type {
+			case typeMessage, typeGroup:
+				if f.message = r.messages[strings.TrimPrefix(f.typeName, ".")]; f.message == nil {
+					return nil, fmt.Errorf("%s.%s: unknown message type %s", m.Name, f.Name, f.typeName)
+				}
+			case typeEnum:
+				if f.enum = r.enums[strings.TrimPrefix(f.typeName, ".")]; f.enum == nil {
+					return nil, fmt.Errorf("%s.%s: unknown enum type %s", m.Name, f.Name, f.typeName)
+				}
+			}
+		}
+	}
+	for _, p := range methods {
+		p.method.Input = r.messages[strings.TrimPrefix(p.input, ".")]
+		p.method.Output = r.messages[strings.TrimPrefix(p.output, ".")]
+		if p.method.Input == nil || p.method.Output == nil {
+			return nil, fmt.Errorf("%s: unknown request or response type", p.method.Name)
+		}
+		r.methods[p.method.Name] = p.method
+	}
+	return r, nil
+}
+
+// Method looks up a method by package.Service/Method, with or without a leading slash.
+func (r *Registry) Method(name string) (*Method, bool) {
+	m, ok := r.methods[strings.TrimPrefix(name, "/")]
+	return m, ok
+}
+
+// Methods lists the names of every method in the set.
+func (r *Registry) Methods() []string {
+	names := make([]string, 0, len(r.methods))
+	for name := range r.methods {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	return names
+}
+
+// addMessage registers a DescriptorProto and its nested types.
+func (r *Registry) addMessage(scope string, data []byte) error {
+	fields, err := readFields(data)
+	if err != nil {
+		return fmt.Errorf("invalid message descriptor: %w", err)
+	}
+	m := &Message{
+		Name:     qualify(scope, stringField(fields, 1)),
+		byName:   make(map[string]*Field),
+		byNumber: make(map[int]*Field),
+	}
+	for _, f := range fields {
+		switch f.number {
+		case 2:
+			ff, err := readFields(f.bytes)
+			if err != nil {
+				return fmt.Errorf("invalid field descriptor in %s: %w", m.Name, err)
+			}
+			field := &Field{
+				Name:     stringField(ff, 1),
+				Number:   int(varintField(ff, 3)),
+				Repeated: varintField(ff, 4) == labelRepeated,
+				Type:     int(varintField(ff, 5)),
+				typeName: stringField(ff, 6),
+				JSONName: stringField(ff, 10),
+			}
+			if field.JSONName == "" {
+				field.JSONName = jsonName(field.Name)
+			}
+			m.Fields = append(m.Fields, field)
+			m.byName[field.Name] = field
+			m.byName[field.JSONName] = field
+			m.byNumber[field.Number] = field
+		case 3:
+			if err := r.addMessage(m.Name, f.bytes); err != nil {
+				return err
+			}
+		case 4:
+			if err := r.addEnum(m.Name, f.bytes); err != nil {
+				return err
+			}
+		case 7:
+			// MessageOptions.map_entry
+			if options, err := readFields(f.bytes); err == nil {
+				m.mapEntry = varintField(options, 7) != 0
+			}
+		}
+	}
+	r.messages[m.Name] = m
+	return nil
+}
+
+// addEnum registers an EnumDescriptorProto.
+func (r *Registry) addEnum(scope string, data []byte) error {
+	fields, err := readFields(data)
+	if err != nil {
+		return fmt.Errorf("invalid enum descriptor: %w", err)
+	}
+	e := &Enum{
+		Name:   qualify(scope, stringField(fields, 1)),
+		names:  make(map[int32]string),
+		values: make(map[string]int32),
+	}
+	for _, f := range fields {
+		if f.number != 2 {
+			continue
+		}
+		vf, err := readFields(f.bytes)
+		if err != nil {
+			return fmt.Errorf("invalid enum value in %s: %w", e.Name, err)
+		}
+		name, number := stringField(vf, 1), int32(varintField(vf, 2))
+		if _, ok := e.names[number]; !ok {
+			e.names[number] = name
+		}
+		e.values[name] = number
+	}
+	r.enums[e.Name] = e
+	return nil
+}
+
+func qualify(scope, name string) string {
+	if scope == "" {
+		return name
+	}
+	return scope + "." + name
+}
+
+func stringField(fields []field, number int) string {
+	for _, f := range fields {
+		if f.number == number && f.wire == wireBytes {
+			return string(f.bytes)
+		}
+	}
+	return ""
+}
+
+func varintField(fields []field, number int) uint64 {
+	for _, f := range fields {
+		if f.number == number && f.wire == wireVarint {
+			return f.value
+		}
+	}
+	return 0
+}
+
+// jsonName converts a field name to lowerCamelCase as protoc does.
+func jsonName(name string) string {
+	var b strings.Builder
+	upper := false
+	for _, r := range name {
+		switch {
+		case r == '_':
+			upper = true
+		case upper && r >= 'a' && r <= 'z':
+			b.WriteRune(r - 'a' + 'A')
+			upper = false
+		default:
+			b.WriteRune(r)
+			upper = false
+		}
+	}
+	return b.String()
+}
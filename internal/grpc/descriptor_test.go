@@ -0,0 +1,168 @@
+package grpc_test
+
+import (
+	"encoding/binary"
+	"reflect"
+	"testing"
+
+	"zone.digit.tommie/internal/grpc"
+)
+
+// pb appends protobuf fields, to write descriptor sets and messages by hand.
+type pb []byte
+
+func (b pb) varint(number int, v uint64) pb {
+	b = binary.AppendUvarint(b, uint64(number)<<3)
+	return binary.AppendUvarint(b, v)
+}
+
+func (b pb) field(number int, data []byte) pb {
+	b = binary.AppendUvarint(b, uint64(number)<<3|2)
+	b = binary.AppendUvarint(b, uint64(len(data)))
+	return append(b, data...)
+}
+
+func (b pb) str(number int, s string) pb {
+	return b.field(number, []byte(s))
+}
+
+// fieldDesc encodes a FieldDescriptorProto; label 3 is repeated.
+func fieldDesc(name string, number, label, typ int, typeName string) []byte {
+	d := pb(nil).str(1, name).varint(3, uint64(number)).varint(4, uint64(label)).varint(5, uint64(typ))
+	if typeName != "" {
+		d = d.str(6, typeName)
+	}
+	return d
+}
+
+func methodDesc(name, input, output string, clientStreaming, serverStreaming bool) []byte {
+	d := pb(nil).str(1, name).str(2, input).str(3, output)
+	if clientStreaming {
+		d = d.varint(5, 1)
+	}
+	if serverStreaming {
+		d = d.varint(6, 1)
+	}
+	return d
+}
+
+// descriptorSet describes package demo: a message Item with the given
+// fields and a nested map entry, an enum Color and a service Items.
+func descriptorSet(fields, methods [][]byte) []byte {
+	entry := pb(nil).str(1, "TagsEntry").
+		field(2, fieldDesc("key", 1, 1, 9, "")).
+		field(2, fieldDesc("value", 2, 1, 5, "")).
+		field(7, pb(nil).varint(7, 1))
+	item := pb(nil).str(1, "Item")
+	for _, f := range fields {
+		item = item.field(2, f)
+	}
+	item = item.field(3, entry)
+	color := pb(nil).str(1, "Color").
+		field(2, pb(nil).str(1, "RED").varint(2, 0)).
+		field(2, pb(nil).str(1, "GREEN").varint(2, 1))
+	service := pb(nil).str(1, "Items")
+	for _, m := range methods {
+		service = service.field(2, m)
+	}
+	file := pb(nil).str(1, "demo.proto").str(2, "demo").field(4, item).field(5, color).field(6, service)
+	return pb(nil).field(1, file)
+}
+
+var itemFields = [][]byte{
+	fieldDesc("name", 1, 1, 9, ""),
+	fieldDesc("id", 2, 1, 3, ""),
+	fieldDesc("scores", 3, 3, 5, ""),
+	fieldDesc("color", 4, 1, 14, ".demo.Color"),
+	fieldDesc("tags", 5, 3, 11, ".demo.Item.TagsEntry"),
+	fieldDesc("child", 6, 1, 11, ".demo.Item"),
+	fieldDesc("delta", 7, 1, 17, ""),
+	fieldDesc("ratio", 8, 1, 1, ""),
+	fieldDesc("blob", 9, 1, 12, ""),
+	fieldDesc("display_name", 10, 1, 9, ""),
+}
+
+var itemMethods = [][]byte{
+	methodDesc("Get", ".demo.Item", ".demo.Item", false, false),
+	methodDesc("Watch", ".demo.Item", ".demo.Item", true, true),
+}
+
+// itemMessage returns the schema of demo.Item.
+func itemMessage(t *testing.T) *grpc.Message {
+	t.Helper()
+	registry, err := grpc.ParseDescriptorSet(descriptorSet(itemFields, itemMethods))
+	if err != nil {
+		t.Fatal(err)
+	}
+	method, _ := registry.Method("demo.Items/Get")
+	return method.Input
+}
+
+func TestParseDescriptorSet(t *testing.T) {
+	registry, err := grpc.ParseDescriptorSet(descriptorSet(itemFields, itemMethods))
+	if err != nil {
+		t.Fatal(err)
+	}
+	if got, want := registry.Methods(), []string{"demo.Items/Get", "demo.Items/Watch"}; !reflect.DeepEqual(got, want) {
+		t.Errorf("Methods = %v, want %v", got, want)
+	}
+
+	tests := []struct {
+		name      string
+		found     bool
+		streaming bool
+	}{
+		{"demo.Items/Get", true, false},
+		{"/demo.Items/Get", true, false},
+		{"/demo.Items/Watch", true, true},
+		{"demo.Items/Missing", false, false},
+		{"Items/Get", false, false},
+	}
+	for _, tt := range tests {
+		method, ok := registry.Method(tt.name)
+		if ok != tt.found {
+			t.Errorf("Method(%q) found = %v, want %v", tt.name, ok, tt.found)
+			continue
+		}
+		if !ok {
+			continue
+		}
+		if method.Input.Name != "demo.Item" || method.Output.Name != "demo.Item" {
+			t.Errorf("Method(%q) = %s -> %s, want demo.Item -> demo.Item", tt.name, method.Input.Name, method.Output.Name)
+		}
+		if method.ClientStreaming != tt.streaming || method.ServerStreaming != tt.streaming {
+			t.Errorf("Method(%q) streams client %v, server %v, want %v", tt.name, method.ClientStreaming, method.ServerStreaming, tt.streaming)
+		}
+	}
+
+	item := itemMessage(t)
+	names := make(map[string]string)
+	for _, f := range item.Fields {
+		names[f.Name] = f.JSONName
+	}
+	if len(item.Fields) != len(itemFields) || names["display_name"] != "displayName" || names["name"] != "name" {
+		t.Errorf("Item has JSON names %v, want every field with display_name as displayName", names)
+	}
+}
+
+func TestParseDescriptorSetErrors(t *testing.T) {
+	withField := func(f []byte) [][]byte {
+		return append(append([][]byte{}, itemFields...), f)
+	}
+	tests := []struct {
+		name string
+		data []byte
+	}{
+		{"truncated", []byte{0x0a, 0x10, 0x0a}},
+		{"unknown message type", descriptorSet(withField(fieldDesc("owner", 11, 1, 11, ".demo.User")), itemMethods)},
+		{"unknown enum type", descriptorSet(withField(fieldDesc("shade", 12, 1, 14, ".demo.Shade")), itemMethods)},
+		{"unknown request type", descriptorSet(itemFields, [][]byte{methodDesc("List", ".demo.ListRequest", ".demo.Item", false, true)})},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if _, err := grpc.ParseDescriptorSet(tt.data); err == nil {
+				t.Error("the descriptor set was accepted")
+			}
+		})
+	}
+}
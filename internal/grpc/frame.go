@@ -0,0 +1,88 @@
+package grpc
+
+import (
+	"encoding/binary"
+	"fmt"
+	"net/http"
+	"net/url"
+	"strings"
+)
+
+// Frame flags of the gRPC length-prefixed message format.
+const (
+	flagCompressed = 0x01
+	flagTrailer    = 0x80 // gRPC-Web only: the frame carries the trailers
+)
+
+// Frame is a length-prefixed message of a gRPC or gRPC-Web body.
+type Frame struct {
+	Compressed bool
+	Trailer    bool
+	Payload    []byte
+}
+
+// EncodeFrame prefixes an uncompressed message with its flag and length.
+func EncodeFrame(payload []byte) []byte {
+	b := make([]byte, 5, 5+len(payload))
+	binary.BigEndian.PutUint32(b[1:], uint32(len(payload)))
+	return append(b, payload...)
+}
+
+// ParseFrames splits a body into its frames. Bytes after the last complete
+// frame are reported as an error along with the frames read so far.
+func ParseFrames(body []byte) ([]Frame, error) {
+	var frames []Frame
+	for len(body) > 0 {
+		if len(body) < 5 {
+			return frames, fmt.Errorf("%d trailing bytes do not form a frame header", len(body))
+		}
+		length := binary.BigEndian.Uint32(body[1:5])
+		if uint64(len(body)-5) < uint64(length) {
+			return frames, fmt.Errorf("frame of %d bytes is truncated to %d", length, len(body)-5)
+		}
+		frames = append(frames, Frame{
+			Compressed: body[0]&flagCompressed != 0,
+			Trailer:    body[0]&flagTrailer != 0,
+			Payload:    body[5 : 5+length],
+		})
+		body = body[5+length:]
+	}
+	return frames, nil
+}
+
+// ParseWebTrailers reads the trailers of a gRPC-Web trailer frame, which
+// are encoded as an HTTP/1 header block.
+func ParseWebTrailers(payload []byte) http.Header {
+	trailers := make(http.Header)
+	for _, line := range strings.Split(string(payload), "\n") {
+		name, value, ok := strings.Cut(strings.TrimRight(line, "\r"), ":")
+		if ok {
+			trailers.Add(strings.TrimSpace(name), strings.TrimSpace(value))
+		}
+	}
+	return trailers
+}
+
+// statusNames are the canonical names of gRPC status codes.
+var statusNames = []string{
+	"OK", "CANCELLED", "UNKNOWN", "INVALID_ARGUMENT", "DEADLINE_EXCEEDED",
+	"NOT_FOUND", "ALREADY_EXISTS", "PERMISSION_DENIED", "RESOURCE_EXHAUSTED",
+	"FAILED_PRECONDITION", "ABORTED", "OUT_OF_RANGE", "UNIMPLEMENTED",
+	"INTERNAL", "UNAVAILABLE", "DATA_LOSS", "UNAUTHENTICATED",
+}
+
+// StatusName returns the name of a gRPC status code.
+func StatusName(code int) string {
+	if code >= 0 && code < len(statusNames) {
+		return statusNames[code]
+	}
+	return fmt.Sprintf("CODE_%d", code)
+}
+
+// DecodeMessage decodes a grpc-message value, which is percent-encoded.
+func DecodeMessage(value string) string {
+	if decoded, err := url.PathUnescape(value); err == nil {
+		return decoded
+	}
+	return value
+}
@@ -0,0 +1,78 @@
+package grpc_test
+
+import (
+	"bytes"
+	"net/http"
+	"reflect"
+	"testing"
+
+	"zone.digit.tommie/internal/grpc"
+)
+
+func TestParseFrames(t *testing.T) {
+	message := grpc.EncodeFrame([]byte("hello"))
+	trailer := append([]byte{0x80, 0, 0, 0, 16}, "grpc-status: 0\r\n"...)
+	compressed := []byte{0x01, 0, 0, 0, 2, 0x1f, 0x8b}
+
+	tests := []struct {
+		name   string
+		body   []byte
+		frames []grpc.Frame
+		err    bool
+	}{
+		{"empty", nil, nil, false},
+		{"message", message, []grpc.Frame{{Payload: []byte("hello")}}, false},
+		{"empty message", grpc.EncodeFrame(nil), []grpc.Frame{{Payload: []byte{}}}, false},
+		{"message and trailer", append(append([]byte{}, message...), trailer...), []grpc.Frame{
+			{Payload: []byte("hello")},
+			{Trailer: true, Payload: []byte("grpc-status: 0\r\n")},
+		}, false},
+		{"compressed", compressed, []grpc.Frame{{Compressed: true, Payload: []byte{0x1f, 0x8b}}}, false},
+		{"truncated header", append(append([]byte{}, message...), 0, 0, 0), []grpc.Frame{{Payload: []byte("hello")}}, true},
+		{"truncated payload", message[:len(message)-1], nil, true},
+		{"length past the body", []byte{0, 0xff, 0xff, 0xff, 0xff, 1}, nil, true},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			frames, err := grpc.ParseFrames(tt.body)
+			if (err != nil) != tt.err {
+				t.Errorf("err = %v, want an error: %v", err, tt.err)
+			}
+			if !reflect.DeepEqual(frames, tt.frames) {
+				t.Errorf("frames = %+v, want %+v", frames, tt.frames)
+			}
+		})
+	}
+}
+
+func TestEncodeFrame(t *testing.T) {
+	if got, want := grpc.EncodeFrame([]byte{1, 2, 3}), []byte{0, 0, 0, 0, 3, 1, 2, 3}; !bytes.Equal(got, want) {
+		t.Errorf("EncodeFrame = %x, want %x", got, want)
+	}
+}
+
+func TestParseWebTrailers(t *testing.T) {
+	got := grpc.ParseWebTrailers([]byte("grpc-status:3\r\ngrpc-message: bad%20request%3A%20id\r\nx-extra: a\r\nx-extra: b\r\nmalformed\r\n"))
+	want := http.Header{
+		"Grpc-Status":  {"3"},
+		"Grpc-Message": {"bad%20request%3A%20id"},
+		"X-Extra":      {"a", "b"},
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Errorf("ParseWebTrailers = %v, want %v", got, want)
+	}
+	if msg := grpc.DecodeMessage(got.Get("grpc-message")); msg != "bad request: id" {
+		t.Errorf("DecodeMessage = %q, want %q", msg, "bad request: id")
+	}
+	if msg := grpc.DecodeMessage("100%"); msg != "100%" {
+		t.Errorf("DecodeMessage of an invalid escape = %q, want it unchanged", msg)
+	}
+}
+
+func TestStatusName(t *testing.T) {
+	for code, want := range map[int]string{0: "OK", 3: "INVALID_ARGUMENT", 14: "UNAVAILABLE", 16: "UNAUTHENTICATED", 17: "CODE_17", -1: "CODE_-1"} {
+		if got := grpc.StatusName(code); got != want {
+			t.Errorf("StatusName(%d) = %q, want %q", code, got, want)
+		}
+	}
+}
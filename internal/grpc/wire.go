@@ -0,0 +1,180 @@
+// Package grpc encodes and decodes gRPC messages: the protobuf wire format,
+// message schemas read from a FileDescriptorSet, and gRPC and gRPC-Web framing.
+package grpc
+
+import (
+	"encoding/base64"
+	"errors"
+	"fmt"
+	"strconv"
+	"unicode/utf8"
+)
+
+// Wire types of the protobuf encoding.
+const (
+	wireVarint  = 0
+	wireFixed64 = 1
+	wireBytes   = 2
+	wireFixed32 = 5
+)
+
+// maxDepth bounds the nesting followed when decoding.
+const maxDepth = 64
+
+var errTruncated = errors.New("truncated protobuf message")
+
+// field is a single encoded field. Varint and fixed values are kept in value.
+type field struct {
+	number int
+	wire   int
+	value  uint64
+	bytes  []byte
+}
+
+// readFields splits an encoded message into its fields.
+func readFields(b []byte) ([]field, error) {
+	var fields []field
+	for len(b) > 0 {
+		tag, n := readVarint(b)
+		if n == 0 {
+			return nil, errTruncated
+		}
+		b = b[n:]
+		f := field{number: int(tag >> 3), wire: int(tag & 7)}
+		if f.number <= 0 {
+			return nil, fmt.Errorf("invalid field number %d", f.number)
+		}
+
+		switch f.wire {
+		case wireVarint:
+			if f.value, n = readVarint(b); n == 0 {
+				return nil, errTruncated
+			}
+		case wireFixed64:
+			if len(b) < 8 {
+				return nil, errTruncated
+			}
+			f.value, n = leUint(b[:8]), 8
+		case wireFixed32:
+			if len(b) < 4 {
+				return nil, errTruncated
+			}
+			f.value, n = leUint(b[:4]), 4
+		case wireBytes:
+			length, m := readVarint(b)
+			if m == 0 || uint64(len(b)-m) < length {
+				return nil, errTruncated
+			}
+			f.bytes, n = b[m:m+int(length)], m+int(length)
+		default:
+			return nil, fmt.Errorf("unsupported wire type %d in field %d", f.wire, f.number)
+		}
+		b = b[n:]
+		fields = append(fields, f)
+	}
+	return fields, nil
+}
+
+// readVarint decodes a varint, returning 0 bytes read when it is malformed.
+func readVarint(b []byte) (uint64, int) {
+	var v uint64
+	for i := 0; i < len(b) && i < 10; i++ {
+		v |= uint64(b[i]&0x7f) << (7 * i)
+		if b[i] < 0x80 {
+			return v, i + 1
+		}
+	}
+	return 0, 0
+}
+
+func leUint(b []byte) uint64 {
+	var v uint64
+	for i := len(b) - 1; i >= 0; i-- {
+		v = v<<8 | uint64(b[i])
+	}
+	return v
+}
+
+func appendVarint(b []byte, v uint64) []byte {
+	for v >= 0x80 {
+		b = append(b, byte(v)|0x80)
+		v >>= 7
+	}
+	return append(b, byte(v))
+}
+
+func appendTag(b []byte, number, wire int) []byte {
+	return appendVarint(b, uint64(number)<<3|uint64(wire))
+}
+
+func appendFixed(b []byte, v uint64, size int) []byte {
+	for i := 0; i < size; i++ {
+		b = append(b, byte(v>>(8*i)))
+	}
+	return b
+}
+
+func appendBytes(b []byte, number int, data []byte) []byte {
+	b = appendTag(b, number, wireBytes)
+	b = appendVarint(b, uint64(len(data)))
+	return append(b, data...)
+}
+
+// DecodeRaw decodes a message without a schema, keyed by field number.
+// Length-delimited fields are shown as a nested message when they parse as
+// one, otherwise as text or base64. It returns nil when b is not protobuf.
+func DecodeRaw(b []byte) map[string]interface{} {
+	return decodeRaw(b, 0)
+}
+
+func decodeRaw(b []byte, depth int) map[string]interface{} {
+	fields, err := readFields(b)
+	if err != nil || (len(fields) == 0 && len(b) > 0) || depth > maxDepth {
+		return nil
+	}
+	out := make(map[string]interface{})
+	for _, f := range fields {
+		value := rawValue(f, depth)
+		key := strconv.Itoa(f.number)
+		switch existing := out[key].(type) {
+		case nil:
+			out[key] = value
+		case []interface{}:
+			out[key] = append(existing, value)
+		default:
+			out[key] = []interface{}{existing, value}
+		}
+	}
+	return out
+}
+
+// rawValue interprets a field value without a schema.
+func rawValue(f field, depth int) interface{} {
+	if f.wire != wireBytes {
+		if f.value > 1<<53 {
+			return strconv.FormatUint(f.value, 10)
+		}
+		return f.value
+	}
+	if nested := decodeRaw(f.bytes, depth+1); len(nested) > 0 && !isText(f.bytes) {
+		return nested
+	}
+	if utf8.Valid(f.bytes) {
+		return string(f.bytes)
+	}
+	return base64.StdEncoding.EncodeToString(f.bytes)
+}
+
+// isText reports whether b is printable UTF-8, which also happens to parse
+// as protobuf surprisingly often.
+func isText(b []byte) bool {
+	if len(b) == 0 || !utf8.Valid(b) {
+		return false
+	}
+	for _, r := range string(b) {
+		if r < 0x20 && r != '\n' && r != '\r' && r != '\t' {
+			return false
+		}
+	}
+	return true
+}
@@ -0,0 +1,332 @@
+package har
+
+import (
+	"encoding/base64"
+	"net/http"
+	"net/url"
+	"sort"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/proxy"
+	"zone.digit.tommie/pkg/statustext"
+)
+
+// Exchange is an executed request together with its response.
+type Exchange struct {
+	Request   proxy.ProxyRequest  `json:"request"`
+	Response  proxy.ProxyResponse `json:"response"`
+	StartedAt *time.Time          `json:"startedAt,omitempty"` // Defaults to the export time minus the total duration
+}
+
+// Export converts exchanges to an HTTP Archive. Every redirect hop becomes an
+// entry of its own so the chain reads the same as in browser tools.
+func Export(exchanges []Exchange) *HAR {
+	entries := []Entry{}
+	for _, ex := range exchanges {
+		entries = append(entries, exchangeEntries(ex)...)
+	}
+
+	return &HAR{Log: Log{
+		Version: "1.2",
+		Creator: Creator,
+		Entries: entries,
+	}}
+}
+
+func exchangeEntries(ex Exchange) []Entry {
+	req := ex.Request
+	resp := ex.Response
+
+	if !resp.Success || resp.Data == nil {
+		started := time.Now()
+		if ex.StartedAt != nil {
+			started = *ex.StartedAt
+		}
+		entry := Entry{
+			StartedDateTime: formatTime(started),
+			Request:         buildRequest(req, req.Method, req.URL, "", true),
+			Response:        Response{Cookies: []Cookie{}, Headers: []NameValue{}, HeadersSize: -1, BodySize: -1},
+			Timings:         Timings{Blocked: -1, DNS: -1, Connect: -1, SSL: -1},
+		}
+		if resp.Error != nil {
+			entry.Error = resp.Error.Code + ": " + resp.Error.Message
+		}
+		return []Entry{entry}
+	}
+
+	data := resp.Data
+	started := time.Now().Add(-time.Duration(data.Timing.Total) * time.Millisecond)
+	if ex.StartedAt != nil {
+		started = *ex.StartedAt
+	}
+
+	protocol := ""
+	if data.Protocol != nil {
+		protocol = *data.Protocol
+	}
+
+	var entries []Entry
+	offset := time.Duration(0)
+	for i, hop := range data.RedirectChain {
+		method := hop.Method
+		if method == "" {
+			method = req.Method
+		}
+		next := data.URL
+		if i+1 < len(data.RedirectChain) {
+			next = data.RedirectChain[i+1].URL
+		}
+		hopProtocol := protocol
+		if hop.Protocol != nil {
+			hopProtocol = *hop.Protocol
+		}
+
+		entry := Entry{
+			StartedDateTime: formatTime(started.Add(offset)),
+			Request:         buildRequest(req, method, hop.URL, hopProtocol, method == req.Method),
+			Response: Response{
+				Status:      int(hop.Status),
+				StatusText:  statustext.Get(int(hop.Status)),
+				HTTPVersion: hopProtocol,
+				Cookies:     []Cookie{},
+				Headers:     mapHeaders(hop.Headers),
+				Content:     Content{MimeType: hop.Headers["content-type"]},
+				RedirectURL: next,
+				HeadersSize: -1,
+				BodySize:    -1,
+			},
+		}
+		if hop.Timing != nil {
+			entry.Timings = buildTimings(*hop.Timing)
+		} else {
+			entry.Timings = Timings{Blocked: -1, DNS: -1, Connect: -1, SSL: -1, Wait: float64(hop.Duration)}
+		}
+		entry.Time = entry.Timings.total()
+		if hop.ServerIP != nil {
+			entry.ServerIPAddress = *hop.ServerIP
+		}
+		entries = append(entries, entry)
+		offset += time.Duration(hop.Duration) * time.Millisecond
+	}
+
+	method := req.Method
+	if data.RequestMethod != nil && *data.RequestMethod != "" {
+		method = *data.RequestMethod
+	}
+	entry := Entry{
+		StartedDateTime: formatTime(started.Add(offset)),
+		Request:         buildRequest(req, method, data.URL, protocol, method == req.Method),
+		Response:        buildResponse(data, protocol),
+		Timings:         buildTimings(data.Timing),
+	}
+	entry.Time = entry.Timings.total()
+	if data.ServerIP != nil {
+		entry.ServerIPAddress = *data.ServerIP
+	}
+	return append(entries, entry)
+}
+
+func buildRequest(req proxy.ProxyRequest, method, rawURL, protocol string, withBody bool) Request {
+	r := Request{
+		Method:      method,
+		URL:         rawURL,
+		HTTPVersion: protocol,
+		Cookies:     []Cookie{},
+		Headers:     mapHeaders(req.Headers),
+		QueryString: []NameValue{},
+		HeadersSize: -1,
+	}
+
+	if parsed, err := url.Parse(rawURL); err == nil {
+		for name, values := range parsed.Query() {
+			for _, value := range values {
+				r.QueryString = append(r.QueryString, NameValue{Name: name, Value: value})
+			}
+		}
+		sort.SliceStable(r.QueryString, func(i, j int) bool { return r.QueryString[i].Name < r.QueryString[j].Name })
+	}
+
+	for _, h := range r.Headers {
+		if strings.EqualFold(h.Name, "Cookie") {
+			if cookies, err := http.ParseCookie(h.Value); err == nil {
+				for _, c := range cookies {
+					r.Cookies = append(r.Cookies, Cookie{Name: c.Name, Value: c.Value})
+				}
+			}
+		}
+	}
+
+	if withBody {
+		r.PostData = buildPostData(req)
+	}
+	r.BodySize = postDataSize(r.PostData)
+	return r
+}
+
+func buildPostData(req proxy.ProxyRequest) *PostData {
+	mimeType := ""
+	for name, value := range req.Headers {
+		if strings.EqualFold(name, "Content-Type") {
+			mimeType = value
+		}
+	}
+
+	switch {
+	case len(req.FormData) > 0:
+		post := &PostData{MimeType: "multipart/form-data"}
+		for _, part := range req.FormData {
+			param := Param{Name: part.Name}
+			if part.Value != nil {
+				param.Value = *part.Value
+			}
+			if part.FileBase64 != nil {
+				param.FileName = part.Name
+				if part.Filename != nil {
+					param.FileName = *part.Filename
+				}
+				param.ContentType = "application/octet-stream"
+				if part.ContentType != nil {
+					param.ContentType = *part.ContentType
+				}
+			}
+			post.Params = append(post.Params, param)
+		}
+		return post
+	case req.BodyBase64 != nil:
+		return &PostData{MimeType: mimeType, Text: *req.BodyBase64, Encoding: "base64"}
+	case req.Body != nil:
+		return &PostData{MimeType: mimeType, Text: *req.Body}
+	}
+	return nil
+}
+
+// postDataSize returns the body length, or -1 for generated multipart bodies.
+func postDataSize(post *PostData) int {
+	switch {
+	case post == nil:
+		return 0
+	case len(post.Params) > 0:
+		return -1
+	case post.Encoding == "base64":
+		data, err := base64.StdEncoding.DecodeString(post.Text)
+		if err != nil {
+			return -1
+		}
+		return len(data)
+	}
+	return len(post.Text)
+}
+
+func buildResponse(data *proxy.ResponseData, protocol string) Response {
+	r := Response{
+		Status:      int(data.Status),
+		StatusText:  data.StatusText,
+		HTTPVersion: protocol,
+		Cookies:     []Cookie{},
+		Headers:     []NameValue{},
+		HeadersSize: -1,
+		BodySize:    data.Size,
+		Content:     Content{Size: data.Size, Text: data.Body},
+	}
+	if data.BodyBase64 != nil {
+		r.Content.Text = *data.BodyBase64
+		r.Content.Encoding = "base64"
+	}
+
+	for _, h := range data.Headers {
+		r.Headers = append(r.Headers, NameValue{Name: h.Name, Value: h.Value})
+		switch strings.ToLower(h.Name) {
+		case "content-type":
+			r.Content.MimeType = h.Value
+		case "set-cookie":
+			if c, err := http.ParseSetCookie(h.Value); err == nil {
+				r.Cookies = append(r.Cookies, buildCookie(c))
+			}
+		}
+	}
+
+	if sizes := data.SizeBreakdown; sizes != nil {
+		r.HeadersSize = sizes.Headers
+		if sizes.Compressed != nil {
+			r.BodySize = *sizes.Compressed
+			r.Content.Compression = data.Size - *sizes.Compressed
+		}
+	}
+	return r
+}
+
+func buildCookie(c *http.Cookie) Cookie {
+	cookie := Cookie{
+		Name:     c.Name,
+		Value:    c.Value,
+		Path:     c.Path,
+		Domain:   c.Domain,
+		HTTPOnly: c.HttpOnly,
+		Secure:   c.Secure,
+	}
+	if !c.Expires.IsZero() {
+		cookie.Expires = formatTime(c.Expires)
+	}
+	return cookie
+}
+
+// buildTimings maps measured phases onto HAR timings. HAR counts the TLS
+// handshake in both connect and ssl, as it does the QUIC handshake.
+func buildTimings(t proxy.TimingInfo) Timings {
+	timings := Timings{
+		Blocked: optional(t.Blocked),
+		DNS:     optional(t.DNS),
+		Connect: -1,
+		SSL:     optional(t.TLS),
+		Wait:    float64(value(t.TTFB)),
+		Receive: float64(value(t.Download)),
+	}
+	if t.TCP != nil || t.TLS != nil {
+		timings.Connect = float64(value(t.TCP) + value(t.TLS))
+	}
+	if t.QUIC != nil {
+		timings.Connect = float64(*t.QUIC)
+		timings.SSL = float64(*t.QUIC)
+	}
+	return timings
+}
+
+// total sums the timings that count towards the entry time.
+func (t Timings) total() float64 {
+	total := 0.0
+	for _, phase := range []float64{t.Blocked, t.DNS, t.Connect, t.Send, t.Wait, t.Receive} {
+		if phase > 0 {
+			total += phase
+		}
+	}
+	return total
+}
+
+// mapHeaders converts a header map to a list sorted by name.
+func mapHeaders(headers map[string]string) []NameValue {
+	list := make([]NameValue, 0, len(headers))
+	for name, value := range headers {
+		list = append(list, NameValue{Name: name, Value: value})
+	}
+	sort.Slice(list, func(i, j int) bool { return list[i].Name < list[j].Name })
+	return list
+}
+
+func optional(v *uint64) float64 {
+	if v == nil {
+		return -1
+	}
+	return float64(*v)
+}
+
+func value(v *uint64) uint64 {
+	if v == nil {
+		return 0
+	}
+	return *v
+}
+
+func formatTime(t time.Time) string {
+	return t.UTC().Format("2006-01-02T15:04:05.000Z07:00")
+}
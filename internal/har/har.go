@@ -0,0 +1,131 @@
+// Package har converts executed requests to and from HTTP Archive (HAR 1.2) files.
+package har
+
+// Creator identifies this application in exported archives.
+var Creator = NameVersion{Name: "Project Tommie", Version: "1.0"}
+
+// HAR is the root object of an HTTP Archive.
+type HAR struct {
+	Log Log `json:"log"`
+}
+
+// Log holds the exported entries.
+type Log struct {
+	Version string       `json:"version"`
+	Creator NameVersion  `json:"creator"`
+	Browser *NameVersion `json:"browser,omitempty"`
+	Pages   []Page       `json:"pages,omitempty"`
+	Entries []Entry      `json:"entries"`
+	Comment string       `json:"comment,omitempty"`
+}
+
+// NameVersion identifies the creator or browser of a log.
+type NameVersion struct {
+	Name    string `json:"name"`
+	Version string `json:"version"`
+}
+
+// Page groups entries loaded by one page.
+type Page struct {
+	StartedDateTime string `json:"startedDateTime"`
+	ID              string `json:"id"`
+	Title           string `json:"title"`
+}
+
+// Entry is a single request and its response.
+type Entry struct {
+	Pageref         string   `json:"pageref,omitempty"`
+	StartedDateTime string   `json:"startedDateTime"` // ISO 8601
+	Time            float64  `json:"time"`            // Total milliseconds, the sum of the timings
+	Request         Request  `json:"request"`
+	Response        Response `json:"response"`
+	Cache           Cache    `json:"cache"`
+	Timings         Timings  `json:"timings"`
+	ServerIPAddress string   `json:"serverIPAddress,omitempty"`
+	Connection      string   `json:"connection,omitempty"`
+	Comment         string   `json:"comment,omitempty"`
+	Error           string   `json:"_error,omitempty"` // Failure of a request that got no response
+}
+
+// Request describes the request of an entry.
+type Request struct {
+	Method      string      `json:"method"`
+	URL         string      `json:"url"`
+	HTTPVersion string      `json:"httpVersion"`
+	Cookies     []Cookie    `json:"cookies"`
+	Headers     []NameValue `json:"headers"`
+	QueryString []NameValue `json:"queryString"`
+	PostData    *PostData   `json:"postData,omitempty"`
+	HeadersSize int         `json:"headersSize"` // -1 when unknown
+	BodySize    int         `json:"bodySize"`    // -1 when unknown
+}
+
+// Response describes the response of an entry.
+type Response struct {
+	Status      int         `json:"status"`
+	StatusText  string      `json:"statusText"`
+	HTTPVersion string      `json:"httpVersion"`
+	Cookies     []Cookie    `json:"cookies"`
+	Headers     []NameValue `json:"headers"`
+	Content     Content     `json:"content"`
+	RedirectURL string      `json:"redirectURL"`
+	HeadersSize int         `json:"headersSize"` // -1 when unknown
+	BodySize    int         `json:"bodySize"`    // Bytes received, -1 when unknown
+}
+
+// NameValue is a header or query string parameter.
+type NameValue struct {
+	Name    string `json:"name"`
+	Value   string `json:"value"`
+	Comment string `json:"comment,omitempty"`
+}
+
+// Cookie is a cookie sent or received.
+type Cookie struct {
+	Name     string `json:"name"`
+	Value    string `json:"value"`
+	Path     string `json:"path,omitempty"`
+	Domain   string `json:"domain,omitempty"`
+	Expires  string `json:"expires,omitempty"`
+	HTTPOnly bool   `json:"httpOnly,omitempty"`
+	Secure   bool   `json:"secure,omitempty"`
+}
+
+// PostData is the body of a request.
+type PostData struct {
+	MimeType string  `json:"mimeType"`
+	Params   []Param `json:"params,omitempty"`
+	Text     string  `json:"text,omitempty"`
+	Encoding string  `json:"_encoding,omitempty"` // base64 for binary bodies
+}
+
+// Param is a posted form field.
+type Param struct {
+	Name        string `json:"name"`
+	Value       string `json:"value,omitempty"`
+	FileName    string `json:"fileName,omitempty"`
+	ContentType string `json:"contentType,omitempty"`
+}
+
+// Content describes a response body.
+type Content struct {
+	Size        int    `json:"size"`                  // Decoded length
+	Compression int    `json:"compression,omitempty"` // Bytes saved by content encoding
+	MimeType    string `json:"mimeType"`
+	Text        string `json:"text,omitempty"`
+	Encoding    string `json:"encoding,omitempty"`
+}
+
+// Cache is always empty; requests are executed without a cache.
+type Cache struct{}
+
+// Timings are the phase durations of an entry in milliseconds, -1 when not applicable.
+type Timings struct {
+	Blocked float64 `json:"blocked"`
+	DNS     float64 `json:"dns"`
+	Connect float64 `json:"connect"` // Includes ssl
+	Send    float64 `json:"send"`
+	Wait    float64 `json:"wait"`
+	Receive float64 `json:"receive"`
+	SSL     float64 `json:"ssl"`
+}
@@ -0,0 +1,263 @@
+package har
+
+import (
+	"encoding/json"
+	"fmt"
+	"net/url"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/history"
+	"zone.digit.tommie/internal/proxy"
+	"zone.digit.tommie/internal/storage"
+)
+
+// Import modes.
+const (
+	ModeRequests = "requests" // Only reconstruct the requests
+	ModeHistory  = "history"  // Also store the recorded exchanges in history
+	ModeReplay   = "replay"   // Also execute the requests one after another
+)
+
+// skippedHeaders are recomputed on replay, or are HTTP/2 pseudo-headers.
+var skippedHeaders = map[string]bool{
+	"host":              true,
+	"content-length":    true,
+	"connection":        true,
+	"keep-alive":        true,
+	"transfer-encoding": true,
+	"upgrade":           true,
+}
+
+// ImportResult summarizes an imported archive.
+type ImportResult struct {
+	Mode    string          `json:"mode"`
+	Entries []ImportedEntry `json:"entries"`
+}
+
+// ImportedEntry is one archive entry and what became of it.
+type ImportedEntry struct {
+	Index    int                 `json:"index"`
+	Request  *proxy.ProxyRequest `json:"request,omitempty"`
+	Recorded *uint16             `json:"recorded,omitempty"` // Status captured in the archive
+	Skipped  *string             `json:"skipped,omitempty"`  // Why the entry was not imported
+	Replay   *ReplayResult       `json:"replay,omitempty"`
+}
+
+// ReplayResult summarizes the response of a replayed request.
+type ReplayResult struct {
+	Success   bool    `json:"success"`
+	Status    *uint16 `json:"status,omitempty"`
+	Duration  uint64  `json:"duration"` // Milliseconds
+	Size      int     `json:"size"`
+	ErrorCode *string `json:"errorCode,omitempty"`
+	Error     *string `json:"error,omitempty"`
+}
+
+// Parse decodes an HTTP Archive.
+func Parse(data []byte) (*HAR, error) {
+	var archive HAR
+	if err := json.Unmarshal(data, &archive); err != nil {
+		return nil, fmt.Errorf("invalid HAR: %w", err)
+	}
+	if archive.Log.Entries == nil {
+		return nil, fmt.Errorf("invalid HAR: no log entries")
+	}
+	return &archive, nil
+}
+
+// Import reconstructs the requests of an archive. In history mode the recorded
+// exchanges are stored in store under the service's retention; in replay mode the requests are executed in
+// order through service. Requests that a browser issued only to follow a
+// redirect are skipped, as the executor follows redirects itself.
+func Import(archive *HAR, mode string, service *proxy.HTTPService, store storage.Store) (*ImportResult, error) {
+	switch mode {
+	case "":
+		mode = ModeRequests
+	case ModeRequests, ModeHistory, ModeReplay:
+	default:
+		return nil, fmt.Errorf("unknown import mode: %s", mode)
+	}
+	var retention *history.Retention
+	if service != nil {
+		retention = service.HistoryRetention()
+	}
+	if mode == ModeHistory && (store == nil || retention == nil) {
+		return nil, fmt.Errorf("history is not available")
+	}
+	if mode == ModeReplay && service == nil {
+		return nil, fmt.Errorf("replay is not available")
+	}
+
+	result := &ImportResult{Mode: mode, Entries: []ImportedEntry{}}
+	redirectTarget := ""
+	for i, entry := range archive.Log.Entries {
+		imported := ImportedEntry{Index: i}
+		if entry.Response.Status > 0 {
+			status := uint16(entry.Response.Status)
+			imported.Recorded = &status
+		}
+
+		followed := redirectTarget != "" && sameURL(redirectTarget, entry.Request.URL)
+		redirectTarget = ""
+		if entry.Response.RedirectURL != "" {
+			redirectTarget = resolveURL(entry.Request.URL, entry.Response.RedirectURL)
+		}
+		if followed {
+			imported.Skipped = strPtr("Followed redirect of the previous entry")
+			result.Entries = append(result.Entries, imported)
+			continue
+		}
+
+		request := buildProxyRequest(entry.Request)
+		imported.Request = &request
+
+		switch mode {
+		case ModeHistory:
+			// Base64 content is binary and not kept for search
+			body := ""
+			if entry.Response.Content.Encoding == "" {
+				body = entry.Response.Content.Text
+			}
+			if err := history.Add(store, historyEntry(entry), body, *retention); err != nil {
+				return nil, err
+			}
+		case ModeReplay:
+			imported.Replay = replay(service.ExecuteIn(store, request))
+		}
+		result.Entries = append(result.Entries, imported)
+	}
+	return result, nil
+}
+
+// buildProxyRequest reconstructs an executable request from a HAR request.
+func buildProxyRequest(r Request) proxy.ProxyRequest {
+	request := proxy.ProxyRequest{
+		Method:  r.Method,
+		URL:     r.URL,
+		Headers: map[string]string{},
+	}
+	for _, h := range r.Headers {
+		name := strings.ToLower(h.Name)
+		if strings.HasPrefix(name, ":") || skippedHeaders[name] {
+			continue
+		}
+		// Repeated headers are folded into one
+		if existing, ok := request.Headers[h.Name]; ok {
+			separator := ", "
+			if name == "cookie" {
+				separator = "; "
+			}
+			request.Headers[h.Name] = existing + separator + h.Value
+			continue
+		}
+		request.Headers[h.Name] = h.Value
+	}
+
+	post := r.PostData
+	switch {
+	case post == nil:
+	case post.Text == "" && len(post.Params) > 0:
+		// Files have no recorded content, so only text fields can be rebuilt
+		for _, p := range post.Params {
+			if p.FileName != "" {
+				continue
+			}
+			value := p.Value
+			request.FormData = append(request.FormData, proxy.FormPart{Name: p.Name, Value: &value})
+		}
+		for name := range request.Headers {
+			if strings.EqualFold(name, "Content-Type") {
+				delete(request.Headers, name)
+			}
+		}
+	case post.Encoding == "base64":
+		text := post.Text
+		request.BodyBase64 = &text
+	default:
+		text := post.Text
+		request.Body = &text
+	}
+	return request
+}
+
+// historyEntry summarizes a recorded exchange for the history.
+func historyEntry(entry Entry) history.Entry {
+	started, err := time.Parse(time.RFC3339Nano, entry.StartedDateTime)
+	if err != nil {
+		started = time.Now()
+	}
+
+	h := history.Entry{
+		Timestamp:    started,
+		Method:       entry.Request.Method,
+		URL:          entry.Request.URL,
+		Status:       uint16(max(entry.Response.Status, 0)),
+		RequestSize:  max(entry.Request.BodySize, 0),
+		ResponseSize: max(entry.Response.Content.Size, 0),
+		Protocol:     entry.Response.HTTPVersion,
+		Timing: history.Timing{
+			Total:    uint64(max(entry.Time, 0)),
+			DNS:      timingPtr(entry.Timings.DNS),
+			TCP:      timingPtr(entry.Timings.Connect - max(entry.Timings.SSL, 0)),
+			TLS:      timingPtr(entry.Timings.SSL),
+			TTFB:     timingPtr(entry.Timings.Wait),
+			Download: timingPtr(entry.Timings.Receive),
+		},
+	}
+	if parsed, err := url.Parse(entry.Request.URL); err == nil {
+		h.Host = parsed.Hostname()
+	}
+	if entry.Response.Status == 0 {
+		h.ErrorCode = "IMPORTED_ERROR"
+		h.Error = entry.Error
+	}
+	return h
+}
+
+func replay(resp proxy.ProxyResponse) *ReplayResult {
+	if !resp.Success {
+		return &ReplayResult{ErrorCode: strPtr(resp.Error.Code), Error: strPtr(resp.Error.Message)}
+	}
+	return &ReplayResult{
+		Success:  true,
+		Status:   &resp.Data.Status,
+		Duration: resp.Data.Timing.Total,
+		Size:     resp.Data.Size,
+	}
+}
+
+// timingPtr converts a HAR timing, where -1 means not applicable.
+func timingPtr(ms float64) *uint64 {
+	if ms < 0 {
+		return nil
+	}
+	v := uint64(ms)
+	return &v
+}
+
+func resolveURL(base, ref string) string {
+	b, err := url.Parse(base)
+	if err != nil {
+		return ref
+	}
+	r, err := b.Parse(ref)
+	if err != nil {
+		return ref
+	}
+	return r.String()
+}
+
+func sameURL(a, b string) bool {
+	ua, errA := url.Parse(a)
+	ub, errB := url.Parse(b)
+	if errA != nil || errB != nil {
+		return a == b
+	}
+	ua.Fragment, ub.Fragment = "", ""
+	return strings.EqualFold(ua.Host, ub.Host) && ua.Scheme == ub.Scheme && ua.RequestURI() == ub.RequestURI()
+}
+
+func strPtr(s string) *string {
+	return &s
+}
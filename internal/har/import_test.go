@@ -0,0 +1,25 @@
+package har_test
+
+import (
+	"testing"
+
+	"zone.digit.tommie/internal/har"
+)
+
+func FuzzParse(f *testing.F) {
+	f.Add([]byte(`{"log":{"entries":[]}}`))
+	f.Add([]byte(`{"log":{"entries":[{"request":{"method":"GET","url":"https://example.com/","headers":[{"name":"Accept","value":"*/*"}]},"response":{"status":200}}]}}`))
+	f.Add([]byte(`{"log":{"entries":[{"request":{"method":"POST","url":"https://example.com/items","postData":{"mimeType":"application/json","text":"{\"name\":\"example\"}"}},"response":{"status":301,"redirectURL":"/items/1"}}]}}`))
+	f.Add([]byte(`{"log":{"entries":[{"request":{"method":"POST","url":"https://example.com/upload","postData":{"mimeType":"multipart/form-data","params":[{"name":"file","fileName":"a.txt","value":"a"}]}}}]}}`))
+	f.Add([]byte(`{"log":{}}`))
+	f.Add([]byte(`[`))
+
+	f.Fuzz(func(t *testing.T, data []byte) {
+		archive, err := har.Parse(data)
+		if err != nil {
+			return
+		}
+		// Only requests are built, so nothing is executed or stored
+		har.Import(archive, har.ModeRequests, nil, nil)
+	})
+}
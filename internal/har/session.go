@@ -0,0 +1,59 @@
+package har
+
+import (
+	"encoding/json"
+	"fmt"
+	"strings"
+
+	"zone.digit.tommie/internal/proxy"
+	"zone.digit.tommie/internal/sessions"
+	"zone.digit.tommie/internal/storage"
+)
+
+// SessionExchanges decodes the exchanges recorded in a session.
+func SessionExchanges(recorded []sessions.Exchange) ([]Exchange, error) {
+	exchanges := make([]Exchange, len(recorded))
+	for i, exchange := range recorded {
+		if err := json.Unmarshal(exchange.Data, &exchanges[i]); err != nil {
+			return nil, fmt.Errorf("corrupt session exchange %d: %w", i+1, err)
+		}
+	}
+	return exchanges, nil
+}
+
+// ExportSession converts a recorded session to an HTTP Archive with one page
+// for the session. Its notes become the comment of the log.
+func ExportSession(session sessions.Session, exchanges []Exchange) *HAR {
+	archive := Export(exchanges)
+	archive.Log.Pages = []Page{{
+		StartedDateTime: formatTime(session.Started),
+		ID:              session.ID,
+		Title:           session.Name,
+	}}
+	for i := range archive.Log.Entries {
+		archive.Log.Entries[i].Pageref = session.ID
+	}
+	notes := make([]string, len(session.Notes))
+	for i, note := range session.Notes {
+		notes[i] = formatTime(note.Timestamp) + " " + note.Text
+	}
+	archive.Log.Comment = strings.Join(notes, "\n")
+	return archive
+}
+
+// ReplaySession executes the requests of a session one after another, as
+// they were recorded, and compares the statuses with the recorded ones.
+func ReplaySession(exchanges []Exchange, service *proxy.HTTPService, store storage.Store) *ImportResult {
+	result := &ImportResult{Mode: ModeReplay, Entries: []ImportedEntry{}}
+	for i, exchange := range exchanges {
+		request := exchange.Request
+		imported := ImportedEntry{Index: i, Request: &request}
+		if exchange.Response.Success && exchange.Response.Data != nil {
+			status := exchange.Response.Data.Status
+			imported.Recorded = &status
+		}
+		imported.Replay = replay(service.ExecuteIn(store, request))
+		result.Entries = append(result.Entries, imported)
+	}
+	return result
+}
@@ -0,0 +1,242 @@
+// Package history records summaries of executed requests, and optionally
+// their response bodies, in a storage.Store.
+package history
+
+import (
+	"crypto/rand"
+	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"sort"
+	"strconv"
+	"strings"
+	"time"
+	"unicode/utf8"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+// StoreName is the storage store holding history entries, keyed by entry ID.
+const StoreName = "history"
+
+// BodyStoreName is the storage store holding the response bodies of history
+// entries, keyed by entry ID.
+const BodyStoreName = "history-bodies"
+
+// DefaultPageSize is the number of entries returned when a query sets no limit.
+const DefaultPageSize = 50
+
+// Entry summarizes one executed request.
+type Entry struct {
+	ID            string    `json:"id"`
+	Timestamp     time.Time `json:"timestamp"`
+	Method        string    `json:"method"`
+	URL           string    `json:"url"`
+	Host          string    `json:"host"`
+	FinalURL      string    `json:"finalUrl,omitempty"` // Set when redirects were followed
+	Status        uint16    `json:"status,omitempty"`   // Zero when the request failed
+	ErrorCode     string    `json:"errorCode,omitempty"`
+	Error         string    `json:"error,omitempty"`
+	RequestSize   int       `json:"requestSize"`
+	ResponseSize  int       `json:"responseSize"`
+	Redirects     int       `json:"redirects,omitempty"`
+	Protocol      string    `json:"protocol,omitempty"`
+	Timing        Timing    `json:"timing"`
+	BodySize      int       `json:"bodySize,omitempty"`      // Bytes of the response body kept for search
+	BodyTruncated bool      `json:"bodyTruncated,omitempty"` // Set when the body exceeded Retention.MaxBodyBytes
+}
+
+// Timing holds the phase durations of a request in milliseconds.
+type Timing struct {
+	Total    uint64  `json:"total"`
+	DNS      *uint64 `json:"dns,omitempty"`
+	TCP      *uint64 `json:"tcp,omitempty"`
+	TLS      *uint64 `json:"tls,omitempty"`
+	TTFB     *uint64 `json:"ttfb,omitempty"`
+	Download *uint64 `json:"download,omitempty"`
+}
+
+// Retention bounds how much history is kept.
+type Retention struct {
+	MaxEntries   int           // Oldest entries beyond this are dropped (0 = unlimited)
+	MaxAge       time.Duration // Entries older than this are dropped (0 = unlimited)
+	MaxBodyBytes int           // Response body kept per entry for search (0 = none)
+}
+
+// Query selects a page of history, newest first.
+type Query struct {
+	Host   string `json:"host,omitempty"`   // Exact host, case-insensitive
+	Status string `json:"status,omitempty"` // Exact code ("404"), class ("4xx") or "error"
+	Offset int    `json:"offset,omitempty"`
+	Limit  int    `json:"limit,omitempty"`
+}
+
+// Page is a slice of history matching a query.
+type Page struct {
+	Entries []Entry `json:"entries"`
+	Total   int     `json:"total"` // Entries matching the query across all pages
+	Offset  int     `json:"offset"`
+	Limit   int     `json:"limit"`
+}
+
+// Add stores an entry, assigning its ID, and applies the retention limits.
+// A text body is kept for Search up to Retention.MaxBodyBytes.
+func Add(store storage.Store, entry Entry, body string, retention Retention) error {
+	suffix := make([]byte, 4)
+	if _, err := rand.Read(suffix); err != nil {
+		return err
+	}
+	// IDs sort chronologically
+	entry.ID = fmt.Sprintf("%019d-%s", entry.Timestamp.UnixNano(), hex.EncodeToString(suffix))
+
+	if body != "" && retention.MaxBodyBytes > 0 {
+		if len(body) > retention.MaxBodyBytes {
+			body = truncateUTF8(body, retention.MaxBodyBytes)
+			entry.BodyTruncated = true
+		}
+		if err := store.Set(BodyStoreName, entry.ID, body); err != nil {
+			return err
+		}
+		entry.BodySize = len(body)
+	}
+
+	data, err := json.Marshal(entry)
+	if err != nil {
+		return err
+	}
+	if err := store.Set(StoreName, entry.ID, string(data)); err != nil {
+		return err
+	}
+	return prune(store, retention)
+}
+
+// Get returns an entry, or nil if it does not exist.
+func Get(store storage.Store, id string) (*Entry, error) {
+	value, err := store.Get(StoreName, id)
+	if err != nil || value == nil {
+		return nil, err
+	}
+	var entry Entry
+	if err := json.Unmarshal([]byte(*value), &entry); err != nil {
+		return nil, fmt.Errorf("corrupt history entry %s: %w", id, err)
+	}
+	return &entry, nil
+}
+
+// List returns the page of entries matching the query, newest first.
+func List(store storage.Store, query Query) (*Page, error) {
+	ids, err := sortedIDs(store)
+	if err != nil {
+		return nil, err
+	}
+
+	limit := query.Limit
+	if limit <= 0 {
+		limit = DefaultPageSize
+	}
+	page := &Page{Entries: []Entry{}, Offset: query.Offset, Limit: limit}
+
+	for i := len(ids) - 1; i >= 0; i-- {
+		entry, err := Get(store, ids[i])
+		if err != nil {
+			return nil, err
+		}
+		if entry == nil || !query.matches(entry) {
+			continue
+		}
+		if page.Total >= query.Offset && len(page.Entries) < limit {
+			page.Entries = append(page.Entries, *entry)
+		}
+		page.Total++
+	}
+	return page, nil
+}
+
+// Body returns the stored response body of an entry, or nil if none was kept.
+func Body(store storage.Store, id string) (*string, error) {
+	return store.Get(BodyStoreName, id)
+}
+
+// Remove deletes an entry and its body.
+func Remove(store storage.Store, id string) error {
+	if err := store.Remove(BodyStoreName, id); err != nil {
+		return err
+	}
+	return store.Remove(StoreName, id)
+}
+
+// Clear deletes all history.
+func Clear(store storage.Store) error {
+	if err := store.Clear(BodyStoreName); err != nil {
+		return err
+	}
+	return store.Clear(StoreName)
+}
+
+func (q Query) matches(entry *Entry) bool {
+	if q.Host != "" && !strings.EqualFold(q.Host, entry.Host) {
+		return false
+	}
+
+	status := strings.ToLower(q.Status)
+	switch {
+	case status == "":
+		return true
+	case status == "error":
+		return entry.ErrorCode != ""
+	case len(status) == 3 && strings.HasSuffix(status, "xx"):
+		return entry.Status != 0 && strconv.Itoa(int(entry.Status))[0] == status[0]
+	default:
+		return strconv.Itoa(int(entry.Status)) == status
+	}
+}
+
+// prune drops entries beyond the retention limits, oldest first.
+func prune(store storage.Store, retention Retention) error {
+	ids, err := sortedIDs(store)
+	if err != nil {
+		return err
+	}
+
+	drop := 0
+	if retention.MaxEntries > 0 && len(ids) > retention.MaxEntries {
+		drop = len(ids) - retention.MaxEntries
+	}
+	if retention.MaxAge > 0 {
+		cutoff := time.Now().Add(-retention.MaxAge).UnixNano()
+		for drop < len(ids) && idTime(ids[drop]) < cutoff {
+			drop++
+		}
+	}
+
+	for _, id := range ids[:drop] {
+		if err := Remove(store, id); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// truncateUTF8 cuts s to at most n bytes without splitting a character.
+func truncateUTF8(s string, n int) string {
+	for n > 0 && !utf8.RuneStart(s[n]) {
+		n--
+	}
+	return s[:n]
+}
+
+func sortedIDs(store storage.Store) ([]string, error) {
+	ids, err := store.Keys(StoreName)
+	if err != nil {
+		return nil, err
+	}
+	sort.Strings(ids)
+	return ids, nil
+}
+
+// idTime extracts the timestamp encoded in an entry ID.
+func idTime(id string) int64 {
+	nanos, _, _ := strings.Cut(id, "-")
+	n, _ := strconv.ParseInt(nanos, 10, 64)
+	return n
+}
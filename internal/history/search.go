@@ -0,0 +1,168 @@
+package history
+
+import (
+	"errors"
+	"regexp"
+	"sort"
+	"strings"
+	"unicode/utf8"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+// Search limits.
+const (
+	DefaultSearchMatches = 100
+	MaxSearchMatches     = 1000
+	DefaultSearchContext = 2
+	MaxSearchContext     = 10
+	// maxContextLineBytes bounds each returned line, so a match in a
+	// minified body does not return the whole body as context
+	maxContextLineBytes = 400
+	// maxCountedMatches bounds the matches counted beyond those returned
+	maxCountedMatches = 100000
+)
+
+// ErrNoBody is returned by Search when the entry has no stored body.
+var ErrNoBody = errors.New("no response body is stored for this entry")
+
+// SearchQuery is a search over the stored response body of an entry.
+type SearchQuery struct {
+	Pattern       string `json:"pattern"`
+	Regex         bool   `json:"regex,omitempty"` // Pattern is an RE2 regular expression
+	CaseSensitive bool   `json:"caseSensitive,omitempty"`
+	Context       *int   `json:"context,omitempty"`    // Lines around each match (default 2, at most 10)
+	MaxMatches    int    `json:"maxMatches,omitempty"` // Default 100, at most 1000
+}
+
+// SearchResult lists the matches of a body search in body order.
+type SearchResult struct {
+	Matches   []Match `json:"matches"`
+	Total     int     `json:"total"`     // Matches in the body, including those not returned (counted up to 100000)
+	Truncated bool    `json:"truncated"` // Set when Total exceeds the returned matches
+	BodySize  int     `json:"bodySize"`
+}
+
+// Match is one occurrence of a search pattern.
+type Match struct {
+	Offset  int           `json:"offset"` // Byte offset in the body
+	Length  int           `json:"length"` // Byte length of the match
+	Line    int           `json:"line"`   // 1-based
+	Column  int           `json:"column"` // 1-based byte column
+	Context []ContextLine `json:"context"`
+}
+
+// ContextLine is a line of the body around a match.
+type ContextLine struct {
+	Line      int    `json:"line"`
+	Text      string `json:"text"`
+	Start     int    `json:"start,omitempty"`     // Byte offset of Text in its line, non-zero when clipped
+	Truncated bool   `json:"truncated,omitempty"` // Set when Text is clipped from a longer line
+}
+
+// Search finds a pattern in the stored response body of an entry. It
+// returns ErrNoBody when none was kept.
+func Search(store storage.Store, id string, query SearchQuery) (*SearchResult, error) {
+	body, err := Body(store, id)
+	if err != nil {
+		return nil, err
+	}
+	if body == nil {
+		return nil, ErrNoBody
+	}
+	return SearchBody(*body, query)
+}
+
+// SearchBody finds a pattern in a body. Invalid queries return an error
+// describing the problem.
+func SearchBody(body string, query SearchQuery) (*SearchResult, error) {
+	if query.Pattern == "" {
+		return nil, errors.New("pattern is required")
+	}
+	pattern := query.Pattern
+	if !query.Regex {
+		pattern = regexp.QuoteMeta(pattern)
+	}
+	if !query.CaseSensitive {
+		pattern = "(?i)" + pattern
+	}
+	re, err := regexp.Compile(pattern)
+	if err != nil {
+		return nil, errors.New("invalid pattern: " + err.Error())
+	}
+
+	context := DefaultSearchContext
+	if query.Context != nil {
+		context = min(max(*query.Context, 0), MaxSearchContext)
+	}
+	limit := query.MaxMatches
+	if limit <= 0 {
+		limit = DefaultSearchMatches
+	}
+	limit = min(limit, MaxSearchMatches)
+
+	result := &SearchResult{Matches: []Match{}, BodySize: len(body)}
+	lines := lineStarts(body)
+	for _, loc := range re.FindAllStringIndex(body, maxCountedMatches) {
+		// Empty matches carry no information
+		if loc[0] == loc[1] {
+			continue
+		}
+		result.Total++
+		if len(result.Matches) >= limit {
+			continue
+		}
+		line := sort.Search(len(lines), func(i int) bool { return lines[i] > loc[0] }) - 1
+		match := Match{
+			Offset: loc[0],
+			Length: loc[1] - loc[0],
+			Line:   line + 1,
+			Column: loc[0] - lines[line] + 1,
+		}
+		for i := max(line-context, 0); i <= min(line+context, len(lines)-1); i++ {
+			focus := 0
+			if i == line {
+				focus = loc[0] - lines[i]
+			}
+			match.Context = append(match.Context, contextLine(body, lines, i, focus))
+		}
+		result.Matches = append(result.Matches, match)
+	}
+	result.Truncated = result.Total > len(result.Matches)
+	return result, nil
+}
+
+// lineStarts returns the byte offset at which each line of body starts.
+func lineStarts(body string) []int {
+	starts := []int{0}
+	for i := 0; i < len(body); i++ {
+		if body[i] == '\n' {
+			starts = append(starts, i+1)
+		}
+	}
+	return starts
+}
+
+// contextLine returns line i of body, clipped around the byte offset focus
+// when it is longer than maxContextLineBytes.
+func contextLine(body string, lines []int, i, focus int) ContextLine {
+	end := len(body)
+	if i+1 < len(lines) {
+		end = lines[i+1] - 1
+	}
+	text := strings.TrimSuffix(body[lines[i]:end], "\r")
+	if len(text) <= maxContextLineBytes {
+		return ContextLine{Line: i + 1, Text: text}
+	}
+
+	start := max(min(focus-maxContextLineBytes/4, len(text)-maxContextLineBytes), 0)
+	stop := start + maxContextLineBytes
+	// Keep characters whole
+	for start > 0 && !utf8.RuneStart(text[start]) {
+		start--
+	}
+	for stop < len(text) && !utf8.RuneStart(text[stop]) {
+		stop--
+	}
+	return ContextLine{Line: i + 1, Text: text[start:stop], Start: start, Truncated: true}
+}
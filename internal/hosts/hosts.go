@@ -0,0 +1,105 @@
+// Package hosts keeps hosts-file style overrides that map hostnames to
+// addresses in a storage.Store, so requests can reach a chosen server while
+// keeping the hostname for SNI and the Host header.
+package hosts
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"net"
+	"sort"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+// StoreName is the storage store holding overrides, keyed by hostname.
+const StoreName = "hosts"
+
+// Override maps a hostname to the address connected to instead of its DNS answer.
+type Override struct {
+	Host    string    `json:"host"`
+	Address string    `json:"address"`
+	Updated time.Time `json:"updated"`
+}
+
+// Normalize lowercases a hostname and drops a trailing dot, as lookups do.
+func Normalize(host string) string {
+	return strings.TrimSuffix(strings.ToLower(strings.TrimSpace(host)), ".")
+}
+
+// Load returns the override of a hostname, or nil if it has none.
+func Load(store storage.Store, host string) (*Override, error) {
+	host = Normalize(host)
+	value, err := store.Get(StoreName, host)
+	if err != nil || value == nil {
+		return nil, err
+	}
+	var override Override
+	if err := json.Unmarshal([]byte(*value), &override); err != nil {
+		return nil, fmt.Errorf("corrupt host override %s: %w", host, err)
+	}
+	override.Host = host
+	return &override, nil
+}
+
+// Set maps a hostname to an IP address, replacing any previous override.
+func Set(store storage.Store, host, address string) (*Override, error) {
+	host = Normalize(host)
+	if host == "" {
+		return nil, errors.New("hostname is required")
+	}
+	ip := net.ParseIP(strings.TrimSpace(address))
+	if ip == nil {
+		return nil, fmt.Errorf("address %q is not an IP address", address)
+	}
+	override := &Override{Host: host, Address: ip.String(), Updated: time.Now().UTC()}
+	data, err := json.Marshal(override)
+	if err != nil {
+		return nil, err
+	}
+	if err := store.Set(StoreName, host, string(data)); err != nil {
+		return nil, err
+	}
+	return override, nil
+}
+
+// List returns all overrides sorted by hostname.
+func List(store storage.Store) ([]Override, error) {
+	names, err := store.Keys(StoreName)
+	if err != nil {
+		return nil, err
+	}
+	sort.Strings(names)
+	overrides := make([]Override, 0, len(names))
+	for _, name := range names {
+		override, err := Load(store, name)
+		if err != nil {
+			return nil, err
+		}
+		if override != nil {
+			overrides = append(overrides, *override)
+		}
+	}
+	return overrides, nil
+}
+
+// Map returns the overrides as a hostname to address map.
+func Map(store storage.Store) (map[string]string, error) {
+	overrides, err := List(store)
+	if err != nil {
+		return nil, err
+	}
+	mapped := make(map[string]string, len(overrides))
+	for _, override := range overrides {
+		mapped[override.Host] = override.Address
+	}
+	return mapped, nil
+}
+
+// Remove deletes the override of a hostname.
+func Remove(store storage.Store, host string) error {
+	return store.Remove(StoreName, Normalize(host))
+}
@@ -0,0 +1,214 @@
+package i18n
+
+// catalog holds the translated message of each error code per locale. Codes
+// missing from a locale keep their English message.
+var catalog = map[string]map[string]string{
+	"nl": {
+		"BATCH_TOO_LARGE":          "De batch bevat te veel verzoeken",
+		"BLOCKED_BY_POLICY":        "Het verzoek is geblokkeerd door het beleid van de server",
+		"BODY_READ_ERROR":          "De body van het antwoord kon niet worden gelezen",
+		"BUSY":                     "De server is bezig; probeer het later opnieuw",
+		"CANCELLED":                "Het verzoek is geannuleerd",
+		"CERT_INVALID":             "Het certificaat van de server is ongeldig",
+		"COOKIE_JAR_ERROR":         "De cookiejar kon niet worden gelezen of opgeslagen",
+		"DATA_TOO_LARGE":           "De gegevens bevatten te veel rijen",
+		"DECOMPRESSION_ERROR":      "De body van het antwoord kon niet worden uitgepakt",
+		"DNS_ERROR":                "De hostnaam kon niet worden opgezocht",
+		"DOWNLOAD_ERROR":           "De download kon niet worden opgeslagen",
+		"DOWNLOAD_TOO_LARGE":       "De download is groter dan toegestaan",
+		"ENVIRONMENT_NOT_FOUND":    "De omgeving bestaat niet",
+		"FORBIDDEN":                "Je hebt geen toegang tot deze actie",
+		"IMPORT_ERROR":             "Het importeren is mislukt",
+		"INTERNAL_ERROR":           "Er is een interne fout opgetreden",
+		"INTROSPECTION_FAILED":     "Het GraphQL-schema kon niet worden opgevraagd",
+		"INVALID_AUTH":             "De authenticatie-instellingen zijn ongeldig",
+		"INVALID_BODY":             "De body van het verzoek is ongeldig",
+		"INVALID_BROWSER_CONTEXT":  "De browsercontext is ongeldig",
+		"INVALID_CURL":             "Het curl-commando kon niet worden gelezen",
+		"INVALID_DATA":             "De gegevens zijn ongeldig",
+		"INVALID_DESCRIPTOR":       "De gRPC-descriptor is ongeldig",
+		"INVALID_GRAPHQL_RESPONSE": "Het antwoord is geen GraphQL",
+		"INVALID_HAR":              "Het HAR-bestand kon niet worden gelezen",
+		"INVALID_HTTP_VERSION":     "De HTTP-versie wordt niet ondersteund",
+		"INVALID_JWT":              "Het JWT kon niet worden gelezen",
+		"INVALID_MESSAGE":          "Het gRPC-bericht kon niet worden gecodeerd",
+		"INVALID_REQUEST":          "Het verzoek is ongeldig",
+		"INVALID_URL":              "De URL is ongeldig",
+		"METHOD_NOT_FOUND":         "De gRPC-methode staat niet in de descriptor",
+		"MISSING_GRPC_STATUS":      "Het antwoord bevat geen grpc-status",
+		"NOT_FOUND":                "Niet gevonden",
+		"PLUGIN_ERROR":             "Een plug-in is mislukt",
+		"PROFILE_BUSY":             "Er loopt al een CPU-profiel",
+		"PROTOCOL_NOT_NEGOTIATED":  "Het gevraagde protocol is niet onderhandeld",
+		"PROXY_CONFIG_ERROR":       "De upstream-proxy is ongeldig ingesteld",
+		"PROXY_DISABLED":           "De proxy-API is uitgeschakeld",
+		"QUOTA_EXCEEDED":           "Het dagelijkse quotum is bereikt",
+		"READ_ONLY":                "De server draait in alleen-lezen-modus",
+		"REQUEST_BUILD_ERROR":      "Het verzoek kon niet worden opgebouwd",
+		"REQUEST_FAILED":           "Het verzoek is mislukt",
+		"SCRIPT_ERROR":             "Het script is mislukt",
+		"STORAGE_ERROR":            "De opslag is niet beschikbaar",
+		"STREAMING_UNSUPPORTED":    "Streamen wordt niet ondersteund",
+		"TOO_MANY_REDIRECTS":       "Te veel omleidingen",
+		"UNAUTHORIZED":             "Authenticatie is vereist",
+		"UNSUPPORTED_METHOD":       "Alleen unary gRPC-aanroepen worden ondersteund",
+		"UNSUPPORTED_PROTOCOL":     "Het protocol wordt niet ondersteund",
+		"VALUE_TOO_LARGE":          "De waarde is te groot",
+	},
+	"de": {
+		"BATCH_TOO_LARGE":          "Der Batch enthält zu viele Anfragen",
+		"BLOCKED_BY_POLICY":        "Die Anfrage wurde durch die Serverrichtlinie blockiert",
+		"BODY_READ_ERROR":          "Der Antwortinhalt konnte nicht gelesen werden",
+		"BUSY":                     "Der Server ist ausgelastet; bitte später erneut versuchen",
+		"CANCELLED":                "Die Anfrage wurde abgebrochen",
+		"CERT_INVALID":             "Das Zertifikat des Servers ist ungültig",
+		"COOKIE_JAR_ERROR":         "Die Cookie-Sammlung konnte nicht gelesen oder gespeichert werden",
+		"DATA_TOO_LARGE":           "Die Daten enthalten zu viele Zeilen",
+		"DECOMPRESSION_ERROR":      "Der Antwortinhalt konnte nicht entpackt werden",
+		"DNS_ERROR":                "Der Hostname konnte nicht aufgelöst werden",
+		"DOWNLOAD_ERROR":           "Der Download konnte nicht gespeichert werden",
+		"DOWNLOAD_TOO_LARGE":       "Der Download ist größer als erlaubt",
+		"ENVIRONMENT_NOT_FOUND":    "Die Umgebung existiert nicht",
+		"FORBIDDEN":                "Keine Berechtigung für diese Aktion",
+		"IMPORT_ERROR":             "Der Import ist fehlgeschlagen",
+		"INTERNAL_ERROR":           "Ein interner Fehler ist aufgetreten",
+		"INTROSPECTION_FAILED":     "Das GraphQL-Schema konnte nicht abgefragt werden",
+		"INVALID_AUTH":             "Die Authentifizierungseinstellungen sind ungültig",
+		"INVALID_BODY":             "Der Inhalt der Anfrage ist ungültig",
+		"INVALID_BROWSER_CONTEXT":  "Der Browserkontext ist ungültig",
+		"INVALID_CURL":             "Der curl-Befehl konnte nicht gelesen werden",
+		"INVALID_DATA":             "Die Daten sind ungültig",
+		"INVALID_DESCRIPTOR":       "Der gRPC-Deskriptor ist ungültig",
+		"INVALID_GRAPHQL_RESPONSE": "Die Antwort ist kein GraphQL",
+		"INVALID_HAR":              "Die HAR-Datei konnte nicht gelesen werden",
+		"INVALID_HTTP_VERSION":     "Die HTTP-Version wird nicht unterstützt",
+		"INVALID_JWT":              "Das JWT konnte nicht gelesen werden",
+		"INVALID_MESSAGE":          "Die gRPC-Nachricht konnte nicht kodiert werden",
+		"INVALID_REQUEST":          "Die Anfrage ist ungültig",
+		"INVALID_URL":              "Die URL ist ungültig",
+		"METHOD_NOT_FOUND":         "Die gRPC-Methode ist nicht im Deskriptor enthalten",
+		"MISSING_GRPC_STATUS":      "Die Antwort enthält keinen grpc-status",
+		"NOT_FOUND":                "Nicht gefunden",
+		"PLUGIN_ERROR":             "Ein Plug-in ist fehlgeschlagen",
+		"PROFILE_BUSY":             "Es läuft bereits ein CPU-Profil",
+		"PROTOCOL_NOT_NEGOTIATED":  "Das angeforderte Protokoll wurde nicht ausgehandelt",
+		"PROXY_CONFIG_ERROR":       "Der Upstream-Proxy ist ungültig konfiguriert",
+		"PROXY_DISABLED":           "Die Proxy-API ist deaktiviert",
+		"QUOTA_EXCEEDED":           "Das tägliche Kontingent ist erschöpft",
+		"READ_ONLY":                "Der Server läuft im Nur-Lese-Modus",
+		"REQUEST_BUILD_ERROR":      "Die Anfrage konnte nicht erstellt werden",
+		"REQUEST_FAILED":           "Die Anfrage ist fehlgeschlagen",
+		"SCRIPT_ERROR":             "Das Skript ist fehlgeschlagen",
+		"STORAGE_ERROR":            "Der Speicher ist nicht verfügbar",
+		"STREAMING_UNSUPPORTED":    "Streaming wird nicht unterstützt",
+		"TOO_MANY_REDIRECTS":       "Zu viele Weiterleitungen",
+		"UNAUTHORIZED":             "Authentifizierung erforderlich",
+		"UNSUPPORTED_METHOD":       "Nur unäre gRPC-Aufrufe werden unterstützt",
+		"UNSUPPORTED_PROTOCOL":     "Das Protokoll wird nicht unterstützt",
+		"VALUE_TOO_LARGE":          "Der Wert ist zu groß",
+	},
+	"fr": {
+		"BATCH_TOO_LARGE":          "Le lot contient trop de requêtes",
+		"BLOCKED_BY_POLICY":        "La requête a été bloquée par la politique du serveur",
+		"BODY_READ_ERROR":          "Le corps de la réponse n'a pas pu être lu",
+		"BUSY":                     "Le serveur est occupé ; réessayez plus tard",
+		"CANCELLED":                "La requête a été annulée",
+		"CERT_INVALID":             "Le certificat du serveur n'est pas valide",
+		"COOKIE_JAR_ERROR":         "Le pot à cookies n'a pas pu être lu ou enregistré",
+		"DATA_TOO_LARGE":           "Les données contiennent trop de lignes",
+		"DECOMPRESSION_ERROR":      "Le corps de la réponse n'a pas pu être décompressé",
+		"DNS_ERROR":                "Le nom d'hôte n'a pas pu être résolu",
+		"DOWNLOAD_ERROR":           "Le téléchargement n'a pas pu être enregistré",
+		"DOWNLOAD_TOO_LARGE":       "Le téléchargement dépasse la taille autorisée",
+		"ENVIRONMENT_NOT_FOUND":    "L'environnement n'existe pas",
+		"FORBIDDEN":                "Vous n'avez pas accès à cette action",
+		"IMPORT_ERROR":             "L'importation a échoué",
+		"INTERNAL_ERROR":           "Une erreur interne est survenue",
+		"INTROSPECTION_FAILED":     "Le schéma GraphQL n'a pas pu être récupéré",
+		"INVALID_AUTH":             "Les paramètres d'authentification sont invalides",
+		"INVALID_BODY":             "Le corps de la requête est invalide",
+		"INVALID_BROWSER_CONTEXT":  "Le contexte navigateur est invalide",
+		"INVALID_CURL":             "La commande curl n'a pas pu être lue",
+		"INVALID_DATA":             "Les données sont invalides",
+		"INVALID_DESCRIPTOR":       "Le descripteur gRPC est invalide",
+		"INVALID_GRAPHQL_RESPONSE": "La réponse n'est pas du GraphQL",
+		"INVALID_HAR":              "Le fichier HAR n'a pas pu être lu",
+		"INVALID_HTTP_VERSION":     "La version HTTP n'est pas prise en charge",
+		"INVALID_JWT":              "Le JWT n'a pas pu être lu",
+		"INVALID_MESSAGE":          "Le message gRPC n'a pas pu être encodé",
+		"INVALID_REQUEST":          "La requête est invalide",
+		"INVALID_URL":              "L'URL est invalide",
+		"METHOD_NOT_FOUND":         "La méthode gRPC ne figure pas dans le descripteur",
+		"MISSING_GRPC_STATUS":      "La réponse ne contient pas de grpc-status",
+		"NOT_FOUND":                "Introuvable",
+		"PLUGIN_ERROR":             "Un plug-in a échoué",
+		"PROFILE_BUSY":             "Un profil CPU est déjà en cours",
+		"PROTOCOL_NOT_NEGOTIATED":  "Le protocole demandé n'a pas été négocié",
+		"PROXY_CONFIG_ERROR":       "Le proxy amont est mal configuré",
+		"PROXY_DISABLED":           "L'API proxy est désactivée",
+		"QUOTA_EXCEEDED":           "Le quota journalier est atteint",
+		"READ_ONLY":                "Le serveur fonctionne en lecture seule",
+		"REQUEST_BUILD_ERROR":      "La requête n'a pas pu être construite",
+		"REQUEST_FAILED":           "La requête a échoué",
+		"SCRIPT_ERROR":             "Le script a échoué",
+		"STORAGE_ERROR":            "Le stockage est indisponible",
+		"STREAMING_UNSUPPORTED":    "Le streaming n'est pas pris en charge",
+		"TOO_MANY_REDIRECTS":       "Trop de redirections",
+		"UNAUTHORIZED":             "Authentification requise",
+		"UNSUPPORTED_METHOD":       "Seuls les appels gRPC unaires sont pris en charge",
+		"UNSUPPORTED_PROTOCOL":     "Le protocole n'est pas pris en charge",
+		"VALUE_TOO_LARGE":          "La valeur est trop grande",
+	},
+	"es": {
+		"BATCH_TOO_LARGE":          "El lote contiene demasiadas solicitudes",
+		"BLOCKED_BY_POLICY":        "La política del servidor bloqueó la solicitud",
+		"BODY_READ_ERROR":          "No se pudo leer el cuerpo de la respuesta",
+		"BUSY":                     "El servidor está ocupado; inténtalo más tarde",
+		"CANCELLED":                "La solicitud se ha cancelado",
+		"CERT_INVALID":             "El certificado del servidor no es válido",
+		"COOKIE_JAR_ERROR":         "No se pudo leer ni guardar el almacén de cookies",
+		"DATA_TOO_LARGE":           "Los datos contienen demasiadas filas",
+		"DECOMPRESSION_ERROR":      "No se pudo descomprimir el cuerpo de la respuesta",
+		"DNS_ERROR":                "No se pudo resolver el nombre de host",
+		"DOWNLOAD_ERROR":           "No se pudo guardar la descarga",
+		"DOWNLOAD_TOO_LARGE":       "La descarga supera el tamaño permitido",
+		"ENVIRONMENT_NOT_FOUND":    "El entorno no existe",
+		"FORBIDDEN":                "No tienes acceso a esta acción",
+		"IMPORT_ERROR":             "La importación falló",
+		"INTERNAL_ERROR":           "Se produjo un error interno",
+		"INTROSPECTION_FAILED":     "No se pudo obtener el esquema GraphQL",
+		"INVALID_AUTH":             "La configuración de autenticación no es válida",
+		"INVALID_BODY":             "El cuerpo de la solicitud no es válido",
+		"INVALID_BROWSER_CONTEXT":  "El contexto del navegador no es válido",
+		"INVALID_CURL":             "No se pudo leer el comando curl",
+		"INVALID_DATA":             "Los datos no son válidos",
+		"INVALID_DESCRIPTOR":       "El descriptor gRPC no es válido",
+		"INVALID_GRAPHQL_RESPONSE": "La respuesta no es GraphQL",
+		"INVALID_HAR":              "No se pudo leer el archivo HAR",
+		"INVALID_HTTP_VERSION":     "La versión de HTTP no es compatible",
+		"INVALID_JWT":              "No se pudo leer el JWT",
+		"INVALID_MESSAGE":          "No se pudo codificar el mensaje gRPC",
+		"INVALID_REQUEST":          "La solicitud no es válida",
+		"INVALID_URL":              "La URL no es válida",
+		"METHOD_NOT_FOUND":         "El método gRPC no está en el descriptor",
+		"MISSING_GRPC_STATUS":      "La respuesta no contiene grpc-status",
+		"NOT_FOUND":                "No encontrado",
+		"PLUGIN_ERROR":             "Un complemento ha fallado",
+		"PROFILE_BUSY":             "Ya hay un perfil de CPU en curso",
+		"PROTOCOL_NOT_NEGOTIATED":  "No se negoció el protocolo solicitado",
+		"PROXY_CONFIG_ERROR":       "El proxy de salida está mal configurado",
+		"PROXY_DISABLED":           "La API de proxy está desactivada",
+		"QUOTA_EXCEEDED":           "Se alcanzó la cuota diaria",
+		"READ_ONLY":                "El servidor funciona en modo de solo lectura",
+		"REQUEST_BUILD_ERROR":      "No se pudo construir la solicitud",
+		"REQUEST_FAILED":           "La solicitud falló",
+		"SCRIPT_ERROR":             "El script ha fallado",
+		"STORAGE_ERROR":            "El almacenamiento no está disponible",
+		"STREAMING_UNSUPPORTED":    "No se admite la transmisión",
+		"TOO_MANY_REDIRECTS":       "Demasiadas redirecciones",
+		"UNAUTHORIZED":             "Se requiere autenticación",
+		"UNSUPPORTED_METHOD":       "Solo se admiten llamadas gRPC unarias",
+		"UNSUPPORTED_PROTOCOL":     "El protocolo no es compatible",
+		"VALUE_TOO_LARGE":          "El valor es demasiado grande",
+	},
+}
@@ -0,0 +1,77 @@
+// Package i18n translates the messages of error codes. Codes are the stable
+// contract with clients; messages are English by default and replaced by a
+// catalog entry when the chosen locale has one.
+package i18n
+
+import (
+	"sort"
+	"strconv"
+	"strings"
+)
+
+// Default is the locale messages are written in.
+const Default = "en"
+
+// Locales returns the supported locales, Default first.
+func Locales() []string {
+	locales := []string{Default}
+	for locale := range catalog {
+		locales = append(locales, locale)
+	}
+	sort.Strings(locales[1:])
+	return locales
+}
+
+// Supported returns the supported locale of a language tag, such as "nl" for
+// "nl-BE", or "" when there is none.
+func Supported(tag string) string {
+	primary, _, _ := strings.Cut(strings.ToLower(strings.TrimSpace(tag)), "-")
+	primary, _, _ = strings.Cut(primary, "_")
+	if primary == Default {
+		return Default
+	}
+	if _, ok := catalog[primary]; ok {
+		return primary
+	}
+	return ""
+}
+
+// Match picks the supported locale an Accept-Language header prefers most,
+// or the supported locale of fallback when it names none.
+func Match(acceptLanguage, fallback string) string {
+	type weighted struct {
+		tag string
+		q   float64
+	}
+	var tags []weighted
+	for _, part := range strings.Split(acceptLanguage, ",") {
+		tag, params, _ := strings.Cut(part, ";")
+		q := 1.0
+		if value, ok := strings.CutPrefix(strings.TrimSpace(params), "q="); ok {
+			parsed, err := strconv.ParseFloat(value, 64)
+			if err != nil {
+				continue
+			}
+			q = parsed
+		}
+		if tag = strings.TrimSpace(tag); tag != "" && tag != "*" && q > 0 {
+			tags = append(tags, weighted{tag, q})
+		}
+	}
+	sort.SliceStable(tags, func(i, j int) bool { return tags[i].q > tags[j].q })
+
+	for _, t := range tags {
+		if locale := Supported(t.tag); locale != "" {
+			return locale
+		}
+	}
+	return Supported(fallback)
+}
+
+// Message returns the message of an error code in a locale. It reports false
+// for the default locale and codes the catalog has no entry for, in which
+// case the original message stands.
+func Message(locale, code string) (string, bool) {
+	message, ok := catalog[locale][code]
+	return message, ok
+}
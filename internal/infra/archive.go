@@ -0,0 +1,160 @@
+package infra
+
+import (
+	"archive/tar"
+	"archive/zip"
+	"bytes"
+	"compress/gzip"
+	"errors"
+	"io"
+	"time"
+)
+
+const (
+	// MaxArchiveEntries is the number of entries listed before a listing is truncated.
+	MaxArchiveEntries = 1000
+	// maxGzipScan bounds how much of a gzip stream is decompressed to size or list it.
+	maxGzipScan = 256 << 20
+)
+
+// ArchiveListing describes the entries of an archive without extracting them.
+type ArchiveListing struct {
+	Format       string // zip, tar, tar.gz or gzip
+	Entries      []ArchiveEntry
+	TotalEntries int  // Entries seen, including those past MaxArchiveEntries
+	Truncated    bool // Listing stopped early, at the entry limit or scan limit
+	Err          error
+}
+
+// ArchiveEntry is a single file or directory in an archive.
+type ArchiveEntry struct {
+	Name           string
+	Size           int64
+	CompressedSize *int64 // Zip only
+	Dir            bool
+	Modified       *time.Time
+}
+
+// ListArchive lists the entries of a zip, tar or gzip body identified by its
+// magic bytes. It returns nil for other content.
+func ListArchive(data []byte) *ArchiveListing {
+	switch {
+	case bytes.HasPrefix(data, []byte("PK\x03\x04")), bytes.HasPrefix(data, []byte("PK\x05\x06")):
+		return listZip(data)
+	case bytes.HasPrefix(data, []byte{0x1f, 0x8b}):
+		return listGzip(data)
+	case isTar(data):
+		listing := &ArchiveListing{Format: "tar"}
+		listTar(listing, bytes.NewReader(data))
+		return listing
+	}
+	return nil
+}
+
+func isTar(data []byte) bool {
+	return len(data) >= 262 && bytes.Equal(data[257:262], []byte("ustar"))
+}
+
+func (l *ArchiveListing) add(entry ArchiveEntry) {
+	l.TotalEntries++
+	if len(l.Entries) < MaxArchiveEntries {
+		l.Entries = append(l.Entries, entry)
+	} else {
+		l.Truncated = true
+	}
+}
+
+func listZip(data []byte) *ArchiveListing {
+	listing := &ArchiveListing{Format: "zip"}
+	r, err := zip.NewReader(bytes.NewReader(data), int64(len(data)))
+	if err != nil {
+		listing.Err = err
+		return listing
+	}
+
+	for _, f := range r.File {
+		compressed := int64(f.CompressedSize64)
+		entry := ArchiveEntry{
+			Name:           f.Name,
+			Size:           int64(f.UncompressedSize64),
+			CompressedSize: &compressed,
+			Dir:            f.FileInfo().IsDir(),
+		}
+		if modified := f.Modified; !modified.IsZero() {
+			entry.Modified = &modified
+		}
+		listing.add(entry)
+	}
+	return listing
+}
+
+func listGzip(data []byte) *ArchiveListing {
+	listing := &ArchiveListing{Format: "gzip"}
+	zr, err := gzip.NewReader(bytes.NewReader(data))
+	if err != nil {
+		listing.Err = err
+		return listing
+	}
+	defer zr.Close()
+
+	// Peek far enough to recognize a tar header
+	limited := io.LimitReader(zr, maxGzipScan)
+	head := make([]byte, 512)
+	n, err := io.ReadFull(limited, head)
+	if err != nil && !errors.Is(err, io.ErrUnexpectedEOF) && !errors.Is(err, io.EOF) {
+		listing.Err = err
+		return listing
+	}
+	head = head[:n]
+	stream := io.MultiReader(bytes.NewReader(head), limited)
+
+	if isTar(head) {
+		listing.Format = "tar.gz"
+		listTar(listing, stream)
+		return listing
+	}
+
+	// A plain gzip file holds a single member named in its header
+	size, err := io.Copy(io.Discard, stream)
+	if err != nil {
+		listing.Err = err
+	}
+	if size == maxGzipScan {
+		listing.Truncated = true
+	}
+	entry := ArchiveEntry{Name: zr.Name, Size: size}
+	if !zr.ModTime.IsZero() {
+		modified := zr.ModTime
+		entry.Modified = &modified
+	}
+	listing.add(entry)
+	return listing
+}
+
+func listTar(listing *ArchiveListing, r io.Reader) {
+	tr := tar.NewReader(r)
+	for {
+		hdr, err := tr.Next()
+		if err == io.EOF {
+			return
+		}
+		if err != nil {
+			listing.Err = err
+			return
+		}
+
+		switch hdr.Typeflag {
+		case tar.TypeXGlobalHeader, tar.TypeXHeader, tar.TypeGNULongName, tar.TypeGNULongLink:
+			continue
+		}
+		entry := ArchiveEntry{
+			Name: hdr.Name,
+			Size: hdr.Size,
+			Dir:  hdr.Typeflag == tar.TypeDir,
+		}
+		if modified := hdr.ModTime; !modified.IsZero() {
+			entry.Modified = &modified
+		}
+		listing.add(entry)
+	}
+}
@@ -0,0 +1,355 @@
+package infra
+
+import "strings"
+
+// MaxBeautifySize limits the bodies that are beautified.
+const MaxBeautifySize = 2 << 20
+
+// formatter writes indented lines.
+type formatter struct {
+	out       strings.Builder
+	indent    int
+	lineStart bool
+	last      byte // Last non-space byte written
+}
+
+func newFormatter(size int) *formatter {
+	f := &formatter{lineStart: true}
+	f.out.Grow(size + size/4)
+	return f
+}
+
+func (f *formatter) newline() {
+	if !f.lineStart {
+		f.out.WriteByte('\n')
+		f.lineStart = true
+	}
+}
+
+func (f *formatter) write(s string) {
+	if s == "" {
+		return
+	}
+	if f.lineStart {
+		f.out.WriteString(strings.Repeat("  ", f.indent))
+		f.lineStart = false
+	}
+	f.out.WriteString(s)
+	if t := strings.TrimRight(s, " "); t != "" {
+		f.last = t[len(t)-1]
+	}
+}
+
+func (f *formatter) space() {
+	if !f.lineStart && f.last != ' ' {
+		f.out.WriteByte(' ')
+	}
+}
+
+func (f *formatter) dedent() {
+	if f.indent > 0 {
+		f.indent--
+	}
+}
+
+// BeautifyJS reformats minified JavaScript with one statement per line and
+// indented blocks. Only whitespace outside strings, template literals,
+// comments and regular expressions is changed.
+func BeautifyJS(src string) string {
+	f := newFormatter(len(src))
+	parens := 0
+	prevWord := ""
+
+	for i := 0; i < len(src); {
+		c := src[i]
+		switch {
+		case c == '"' || c == '\'' || c == '`':
+			end := scanQuoted(src, i)
+			f.write(src[i:end])
+			prevWord = ""
+			i = end
+
+		case strings.HasPrefix(src[i:], "//"):
+			end := strings.IndexByte(src[i:], '\n')
+			if end < 0 {
+				end = len(src) - i
+			}
+			f.space()
+			f.write(src[i : i+end])
+			f.newline()
+			i += end
+
+		case strings.HasPrefix(src[i:], "/*"):
+			end := commentEnd(src, i)
+			f.write(src[i:end])
+			i = end
+
+		case c == '/' && regexAllowed(f.last, prevWord):
+			end := scanRegex(src, i)
+			f.write(src[i:end])
+			prevWord = ""
+			i = end
+
+		case isSpace(c):
+			j := i
+			hasNewline := false
+			for j < len(src) && isSpace(src[j]) {
+				hasNewline = hasNewline || src[j] == '\n'
+				j++
+			}
+			if hasNewline && parens == 0 {
+				f.newline()
+			} else {
+				f.space()
+			}
+			i = j
+
+		case c == '{':
+			if isWordByte(f.last) || f.last == ')' {
+				f.space()
+			}
+			f.write("{")
+			f.indent++
+			f.newline()
+			prevWord = ""
+			i++
+
+		case c == '}':
+			f.dedent()
+			f.newline()
+			f.write("}")
+			prevWord = ""
+			i++
+
+			// Keep "} else", "})" and "};" together
+			j := skipSpaces(src, i)
+			next := word(src, j)
+			switch {
+			case j < len(src) && strings.IndexByte(");,.]", src[j]) >= 0:
+			case next == "else" || next == "catch" || next == "finally" || next == "while":
+				f.write(" ")
+			default:
+				f.newline()
+			}
+			i = j
+
+		case c == ';':
+			f.write(";")
+			if parens == 0 {
+				f.newline()
+			} else if i+1 < len(src) && !isSpace(src[i+1]) {
+				f.write(" ")
+			}
+			prevWord = ""
+			i++
+
+		case c == '(' || c == '[':
+			parens++
+			f.write(src[i : i+1])
+			prevWord = ""
+			i++
+
+		case c == ')' || c == ']':
+			if parens > 0 {
+				parens--
+			}
+			f.write(src[i : i+1])
+			prevWord = ""
+			i++
+
+		case c == ',':
+			f.write(",")
+			if i+1 < len(src) && !isSpace(src[i+1]) {
+				f.write(" ")
+			}
+			prevWord = ""
+			i++
+
+		case isWordByte(c):
+			w := word(src, i)
+			f.write(w)
+			prevWord = w
+			i += len(w)
+
+		default:
+			f.write(src[i : i+1])
+			prevWord = ""
+			i++
+		}
+	}
+
+	return strings.TrimRight(f.out.String(), "\n ") + "\n"
+}
+
+// BeautifyCSS reformats minified CSS with one declaration per line and
+// indented rule blocks.
+func BeautifyCSS(src string) string {
+	f := newFormatter(len(src))
+	parens := 0
+
+	for i := 0; i < len(src); {
+		c := src[i]
+		switch {
+		case c == '"' || c == '\'':
+			end := scanQuoted(src, i)
+			f.write(src[i:end])
+			i = end
+
+		case strings.HasPrefix(src[i:], "/*"):
+			end := commentEnd(src, i)
+			f.write(src[i:end])
+			f.newline()
+			i = end
+
+		case isSpace(c):
+			for i < len(src) && isSpace(src[i]) {
+				i++
+			}
+			f.space()
+
+		case c == '{':
+			f.space()
+			f.write("{")
+			f.indent++
+			f.newline()
+			i++
+
+		case c == '}':
+			f.dedent()
+			f.newline()
+			f.write("}")
+			f.newline()
+			i++
+
+		case c == ';' && parens == 0:
+			f.write(";")
+			f.newline()
+			i++
+
+		case c == '(':
+			parens++
+			f.write("(")
+			i++
+
+		case c == ')':
+			if parens > 0 {
+				parens--
+			}
+			f.write(")")
+			i++
+
+		default:
+			f.write(src[i : i+1])
+			i++
+		}
+	}
+
+	return strings.TrimRight(f.out.String(), "\n ") + "\n"
+}
+
+// commentEnd returns the end of the block comment starting at i.
+func commentEnd(src string, i int) int {
+	end := strings.Index(src[i+2:], "*/")
+	if end < 0 {
+		return len(src)
+	}
+	return i + 2 + end + 2
+}
+
+// scanQuoted returns the end of the string or template literal starting at i.
+func scanQuoted(src string, i int) int {
+	quote := src[i]
+	for j := i + 1; j < len(src); j++ {
+		switch {
+		case src[j] == '\\':
+			j++
+		case src[j] == quote:
+			return j + 1
+		case quote == '`' && src[j] == '$' && j+1 < len(src) && src[j+1] == '{':
+			// Skip the template substitution, including nested braces
+			depth := 0
+			for j++; j < len(src); j++ {
+				if src[j] == '{' {
+					depth++
+				} else if src[j] == '}' {
+					depth--
+					if depth == 0 {
+						break
+					}
+				}
+			}
+		}
+	}
+	return len(src)
+}
+
+// scanRegex returns the end of the regular expression literal starting at i,
+// including its flags.
+func scanRegex(src string, i int) int {
+	inClass := false
+	j := i + 1
+	for ; j < len(src); j++ {
+		c := src[j]
+		if c == '\\' {
+			j++
+			continue
+		}
+		if c == '\n' {
+			return j
+		}
+		if c == '[' {
+			inClass = true
+		} else if c == ']' {
+			inClass = false
+		} else if c == '/' && !inClass {
+			j++
+			break
+		}
+	}
+	for j < len(src) && isWordByte(src[j]) {
+		j++
+	}
+	if j > len(src) {
+		j = len(src)
+	}
+	return j
+}
+
+// regexAllowed reports whether a slash after the given context starts a
+// regular expression rather than a division.
+func regexAllowed(last byte, prevWord string) bool {
+	switch prevWord {
+	case "return", "typeof", "instanceof", "in", "of", "new", "delete", "void", "throw", "case", "do", "else", "yield", "await":
+		return true
+	case "":
+	default:
+		return false
+	}
+	if last == 0 {
+		return true
+	}
+	return strings.IndexByte("(,=:[!&|?{};+-*%<>~^", last) >= 0
+}
+
+func skipSpaces(src string, i int) int {
+	for i < len(src) && isSpace(src[i]) {
+		i++
+	}
+	return i
+}
+
+func word(src string, i int) string {
+	j := i
+	for j < len(src) && isWordByte(src[j]) {
+		j++
+	}
+	return src[i:j]
+}
+
+func isSpace(c byte) bool {
+	return c == ' ' || c == '\t' || c == '\n' || c == '\r'
+}
+
+func isWordByte(c byte) bool {
+	return c == '_' || c == '$' || (c >= '0' && c <= '9') || (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || c >= 0x80
+}
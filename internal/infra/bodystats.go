@@ -0,0 +1,223 @@
+package infra
+
+import (
+	"bytes"
+	"encoding/json"
+	"errors"
+	"io"
+	"math"
+	"mime"
+	"mime/multipart"
+	"sort"
+	"strings"
+)
+
+const (
+	// MaxStatsKeys is the number of JSON keys and multipart content types
+	// reported, most frequent first.
+	MaxStatsKeys = 20
+	// maxDistinctKeys bounds the JSON keys counted, so bodies with generated
+	// keys do not grow the histogram without limit.
+	maxDistinctKeys = 10000
+)
+
+// BodyStatistics summarizes a body too large to display usefully.
+type BodyStatistics struct {
+	Bytes       int
+	Entropy     float64 // Shannon entropy in bits per byte; near 8 for compressed or encrypted data
+	Lines       int     // Text bodies only
+	LongestLine int     // Text bodies only, in bytes
+	JSON        *JSONStatistics
+	Multipart   *MultipartStatistics
+}
+
+// JSONStatistics counts the structure of a JSON body.
+type JSONStatistics struct {
+	Objects      int
+	Arrays       int
+	MaxDepth     int
+	Keys         []Count // Most frequent keys
+	DistinctKeys int
+	Complete     bool // The whole body parsed; a truncated body stops early
+}
+
+// MultipartStatistics counts the parts of a multipart body by content type.
+type MultipartStatistics struct {
+	Parts        int
+	ContentTypes []Count // Most frequent part content types
+	Complete     bool    // The closing boundary was reached
+}
+
+// Count is a value and how often it occurred, with the bytes it covered
+// where that applies.
+type Count struct {
+	Value string
+	Count int
+	Bytes int
+}
+
+// ComputeBodyStatistics summarizes a body. Line counts are computed for
+// text bodies, key histograms for JSON and part histograms for multipart.
+func ComputeBodyStatistics(data []byte, contentType string, text bool) *BodyStatistics {
+	stats := &BodyStatistics{Bytes: len(data), Entropy: entropy(data)}
+	if text {
+		stats.Lines, stats.LongestLine = lineStatistics(data)
+	}
+
+	mediaType, params, _ := mime.ParseMediaType(contentType)
+	switch {
+	case strings.HasPrefix(mediaType, "multipart/") && params["boundary"] != "":
+		stats.Multipart = multipartStatistics(data, params["boundary"])
+	case strings.Contains(mediaType, "json"), text && looksLikeJSON(data):
+		stats.JSON = jsonStatistics(data)
+	}
+	return stats
+}
+
+func entropy(data []byte) float64 {
+	if len(data) == 0 {
+		return 0
+	}
+	var histogram [256]int
+	for _, b := range data {
+		histogram[b]++
+	}
+	total := float64(len(data))
+	bits := 0.0
+	for _, n := range histogram {
+		if n > 0 {
+			p := float64(n) / total
+			bits -= p * math.Log2(p)
+		}
+	}
+	return math.Round(bits*1000) / 1000
+}
+
+func lineStatistics(data []byte) (lines, longest int) {
+	for len(data) > 0 {
+		line := data
+		if i := bytes.IndexByte(data, '\n'); i >= 0 {
+			line, data = data[:i], data[i+1:]
+		} else {
+			data = nil
+		}
+		lines++
+		longest = max(longest, len(bytes.TrimSuffix(line, []byte("\r"))))
+	}
+	return lines, longest
+}
+
+func looksLikeJSON(data []byte) bool {
+	trimmed := bytes.TrimLeft(data, " \t\r\n")
+	return len(trimmed) > 0 && (trimmed[0] == '{' || trimmed[0] == '[')
+}
+
+// jsonStatistics tokenizes a JSON body without building it in memory.
+func jsonStatistics(data []byte) *JSONStatistics {
+	stats := &JSONStatistics{}
+	keys := map[string]int{}
+
+	// For each open container, whether it is an object whose next token is a key
+	type frame struct{ object, key bool }
+	var stack []frame
+	decoder := json.NewDecoder(bytes.NewReader(data))
+	for {
+		token, err := decoder.Token()
+		if errors.Is(err, io.EOF) {
+			stats.Complete = len(stack) == 0
+			break
+		}
+		if err != nil {
+			break
+		}
+
+		var top *frame
+		if len(stack) > 0 {
+			top = &stack[len(stack)-1]
+		}
+		if delim, ok := token.(json.Delim); ok && (delim == '}' || delim == ']') {
+			stack = stack[:len(stack)-1]
+			continue
+		}
+		if key, ok := token.(string); ok && top != nil && top.key {
+			if _, seen := keys[key]; seen || len(keys) < maxDistinctKeys {
+				keys[key]++
+			}
+			top.key = false
+			continue
+		}
+		// Any other token is a value; an object expects a key after it
+		if top != nil && top.object {
+			top.key = true
+		}
+		if delim, ok := token.(json.Delim); ok {
+			if delim == '{' {
+				stats.Objects++
+			} else {
+				stats.Arrays++
+			}
+			stack = append(stack, frame{object: delim == '{', key: delim == '{'})
+			stats.MaxDepth = max(stats.MaxDepth, len(stack))
+		}
+	}
+
+	stats.DistinctKeys = len(keys)
+	for key, n := range keys {
+		stats.Keys = append(stats.Keys, Count{Value: key, Count: n})
+	}
+	stats.Keys = topCounts(stats.Keys)
+	return stats
+}
+
+func multipartStatistics(data []byte, boundary string) *MultipartStatistics {
+	stats := &MultipartStatistics{}
+	types := map[string]*Count{}
+	reader := multipart.NewReader(bytes.NewReader(data), boundary)
+	for {
+		part, err := reader.NextPart()
+		if errors.Is(err, io.EOF) {
+			stats.Complete = true
+			break
+		}
+		if err != nil {
+			break
+		}
+		contentType := part.Header.Get("Content-Type")
+		if mediaType, _, err := mime.ParseMediaType(contentType); err == nil {
+			contentType = mediaType
+		}
+		if contentType == "" {
+			// The default of RFC 2046
+			contentType = "text/plain"
+		}
+		size, _ := io.Copy(io.Discard, part)
+		stats.Parts++
+		count := types[contentType]
+		if count == nil {
+			count = &Count{Value: contentType}
+			types[contentType] = count
+		}
+		count.Count++
+		count.Bytes += int(size)
+	}
+
+	for _, count := range types {
+		stats.ContentTypes = append(stats.ContentTypes, *count)
+	}
+	stats.ContentTypes = topCounts(stats.ContentTypes)
+	return stats
+}
+
+// topCounts sorts counts by frequency, then value, and keeps the first MaxStatsKeys.
+func topCounts(counts []Count) []Count {
+	sort.Slice(counts, func(i, j int) bool {
+		if counts[i].Count != counts[j].Count {
+			return counts[i].Count > counts[j].Count
+		}
+		return counts[i].Value < counts[j].Value
+	})
+	if len(counts) > MaxStatsKeys {
+		counts = counts[:MaxStatsKeys]
+	}
+	return counts
+}
@@ -4,7 +4,10 @@ import (
 	"bytes"
 	"compress/flate"
 	"compress/gzip"
+	"errors"
+	"fmt"
 	"io"
+	"strings"
 
 	"github.com/andybalholm/brotli"
 )
@@ -14,72 +17,135 @@ type DecompressResult struct {
 	Data             []byte
 	CompressedSize   int
 	DecompressedSize int
+	Layers           []DecompressLayer // Decoding steps in the order they were applied
+	Truncated        bool              // Output stopped at the limit, or the input was cut short
+}
+
+// DecompressLayer describes a single decoding step of a chained content-encoding.
+type DecompressLayer struct {
+	Encoding   string
+	InputSize  int
+	OutputSize int
+}
+
+// ParseEncodings splits a Content-Encoding header into normalized codings.
+// Whitespace, parameters and identity codings are dropped.
+func ParseEncodings(header string) []string {
+	var encodings []string
+	for _, part := range strings.Split(header, ",") {
+		encoding, _, _ := strings.Cut(part, ";")
+		encoding = strings.ToLower(strings.TrimSpace(encoding))
+		switch encoding {
+		case "", "identity":
+			continue
+		case "x-gzip":
+			encoding = "gzip"
+		}
+		encodings = append(encodings, encoding)
+	}
+	return encodings
 }
 
 // Decompress decompresses data based on the content-encoding.
+// Multiple codings are listed in the order they were applied, so they are decoded in reverse.
+// Decoding stops at the first unknown coding and returns the data decoded so far.
 func Decompress(data []byte, encoding string) (*DecompressResult, error) {
-	switch encoding {
-	case "gzip":
-		return decompressGzip(data)
-	case "deflate":
-		return decompressDeflate(data)
-	case "br":
-		return decompressBrotli(data)
-	default:
-		// No compression or unknown encoding - return as-is
-		return &DecompressResult{
-			Data:             data,
-			CompressedSize:   len(data),
-			DecompressedSize: len(data),
-		}, nil
-	}
+	return DecompressLimit(data, encoding, 0, false)
 }
 
-func decompressGzip(data []byte) (*DecompressResult, error) {
-	reader, err := gzip.NewReader(bytes.NewReader(data))
-	if err != nil {
-		return nil, err
+// DecompressLimit decompresses like Decompress, keeping at most limit bytes of
+// output (0 = unlimited). When partial is set the data is the start of a
+// truncated body, and decoding keeps what the available input yields.
+func DecompressLimit(data []byte, encoding string, limit int, partial bool) (*DecompressResult, error) {
+	result := &DecompressResult{
+		Data:           data,
+		CompressedSize: len(data),
+		Truncated:      partial,
 	}
-	defer reader.Close()
 
-	decompressed, err := io.ReadAll(reader)
-	if err != nil {
-		return nil, err
+	encodings := ParseEncodings(encoding)
+	for i := len(encodings) - 1; i >= 0; i-- {
+		// Nothing to decode, e.g. the body of a HEAD response
+		if len(result.Data) == 0 {
+			break
+		}
+		decoded, ok, err := decodeLayer(result.Data, encodings[i], limit)
+		if partial && errors.Is(err, io.ErrUnexpectedEOF) {
+			err = nil
+		}
+		if err != nil {
+			return nil, fmt.Errorf("%s: %w", encodings[i], err)
+		}
+		if !ok {
+			// Unknown encoding - return as-is
+			break
+		}
+		if limit > 0 && len(decoded) > limit {
+			decoded = decoded[:limit]
+			result.Truncated = true
+		}
+
+		result.Layers = append(result.Layers, DecompressLayer{
+			Encoding:   encodings[i],
+			InputSize:  len(result.Data),
+			OutputSize: len(decoded),
+		})
+		result.Data = decoded
 	}
 
-	return &DecompressResult{
-		Data:             decompressed,
-		CompressedSize:   len(data),
-		DecompressedSize: len(decompressed),
-	}, nil
+	result.DecompressedSize = len(result.Data)
+	return result, nil
 }
 
-func decompressDeflate(data []byte) (*DecompressResult, error) {
-	reader := flate.NewReader(bytes.NewReader(data))
-	defer reader.Close()
-
-	decompressed, err := io.ReadAll(reader)
-	if err != nil {
-		return nil, err
+// DecodeReader returns a reader removing the codings of a Content-Encoding
+// header as it is read, for bodies too large to decompress in memory.
+// Decoding stops at the first unknown coding, like Decompress.
+func DecodeReader(r io.Reader, encoding string) (io.Reader, error) {
+	encodings := ParseEncodings(encoding)
+	for i := len(encodings) - 1; i >= 0; i-- {
+		switch encodings[i] {
+		case "gzip":
+			zr, err := gzip.NewReader(r)
+			if err != nil {
+				return nil, fmt.Errorf("gzip: %w", err)
+			}
+			r = zr
+		case "deflate":
+			r = flate.NewReader(r)
+		case "br":
+			r = brotli.NewReader(r)
+		default:
+			return r, nil
+		}
 	}
-
-	return &DecompressResult{
-		Data:             decompressed,
-		CompressedSize:   len(data),
-		DecompressedSize: len(decompressed),
-	}, nil
+	return r, nil
 }
 
-func decompressBrotli(data []byte) (*DecompressResult, error) {
-	reader := brotli.NewReader(bytes.NewReader(data))
-	decompressed, err := io.ReadAll(reader)
-	if err != nil {
-		return nil, err
+// decodeLayer removes a single coding, reading at most limit+1 bytes of
+// output (0 = unlimited). ok is false for unsupported codings.
+func decodeLayer(data []byte, encoding string, limit int) (decoded []byte, ok bool, err error) {
+	var reader io.Reader
+	switch encoding {
+	case "gzip":
+		zr, err := gzip.NewReader(bytes.NewReader(data))
+		if err != nil {
+			return nil, true, err
+		}
+		defer zr.Close()
+		reader = zr
+	case "deflate":
+		fr := flate.NewReader(bytes.NewReader(data))
+		defer fr.Close()
+		reader = fr
+	case "br":
+		reader = brotli.NewReader(bytes.NewReader(data))
+	default:
+		return nil, false, nil
 	}
-
-	return &DecompressResult{
-		Data:             decompressed,
-		CompressedSize:   len(data),
-		DecompressedSize: len(decompressed),
-	}, nil
+	// One byte past the limit tells a body at the limit from a longer one
+	if limit > 0 {
+		reader = io.LimitReader(reader, int64(limit)+1)
+	}
+	decoded, err = io.ReadAll(reader)
+	return decoded, true, err
 }
@@ -0,0 +1,55 @@
+package infra_test
+
+import (
+	"bytes"
+	"compress/flate"
+	"compress/gzip"
+	"io"
+	"testing"
+
+	"github.com/andybalholm/brotli"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+// maxDecompressed bounds the output while fuzzing, as the body limit does.
+const maxDecompressed = 1 << 20
+
+// compress encodes data with a writer from newWriter.
+func compress(data []byte, newWriter func(io.Writer) io.WriteCloser) []byte {
+	var buf bytes.Buffer
+	w := newWriter(&buf)
+	w.Write(data)
+	w.Close()
+	return buf.Bytes()
+}
+
+func FuzzDecompressLimit(f *testing.F) {
+	body := []byte(`{"items":["alpha","beta","gamma","delta"]}`)
+	gzipped := compress(body, func(w io.Writer) io.WriteCloser { return gzip.NewWriter(w) })
+	deflated := compress(body, func(w io.Writer) io.WriteCloser {
+		fw, _ := flate.NewWriter(w, flate.DefaultCompression)
+		return fw
+	})
+	brotlied := compress(body, func(w io.Writer) io.WriteCloser { return brotli.NewWriter(w) })
+
+	f.Add(gzipped, "gzip", false)
+	f.Add(gzipped[:len(gzipped)/2], "gzip", true)
+	f.Add(deflated, "deflate", false)
+	f.Add(brotlied, "br", false)
+	f.Add(compress(gzipped, func(w io.Writer) io.WriteCloser { return brotli.NewWriter(w) }), "gzip, br", false)
+	f.Add(body, "identity", false)
+	f.Add(body, "x-gzip", true)
+	f.Add([]byte{}, "gzip", false)
+
+	f.Fuzz(func(t *testing.T, data []byte, encoding string, partial bool) {
+		result, err := infra.DecompressLimit(data, encoding, maxDecompressed, partial)
+		if err != nil {
+			return
+		}
+		// Undecoded input is returned as it came
+		if len(result.Layers) > 0 && len(result.Data) > maxDecompressed {
+			t.Fatalf("decoded %d bytes, over the limit of %d", len(result.Data), maxDecompressed)
+		}
+	})
+}
@@ -2,8 +2,15 @@
 package infra
 
 import (
+	"bufio"
 	"context"
+	"errors"
+	"fmt"
 	"net"
+	"net/url"
+	"os"
+	"strings"
+	"sync"
 	"time"
 )
 
@@ -11,6 +18,7 @@ import (
 type DNSResult struct {
 	IPs        []net.IP
 	DurationMs uint64
+	Resolver   string // "system" or the nameserver that answered; empty for IP literals
 }
 
 // ResolveDNS resolves a hostname to IP addresses with timing.
@@ -41,5 +49,186 @@ func ResolveDNS(ctx context.Context, host string) (*DNSResult, error) {
 	return &DNSResult{
 		IPs:        ips,
 		DurationMs: uint64(time.Since(start).Milliseconds()),
+		Resolver:   "system",
 	}, nil
 }
+
+// Nameserver is a DNS server and the transport used to query it.
+type Nameserver struct {
+	Protocol string // udp (retried over tcp when truncated), tcp, tls or https
+	Address  string // host:port, or the endpoint URL for https
+}
+
+// ParseNameserver reads a nameserver such as "1.1.1.1", "1.1.1.1:5353",
+// "tcp://1.1.1.1", "tls://dns.quad9.net" or "https://dns.google/dns-query".
+func ParseNameserver(spec string) (Nameserver, error) {
+	spec = strings.TrimSpace(spec)
+	protocol, rest, ok := strings.Cut(spec, "://")
+	if !ok {
+		protocol, rest = "udp", spec
+	}
+
+	switch protocol {
+	case "https":
+		u, err := url.Parse(spec)
+		if err != nil || u.Host == "" {
+			return Nameserver{}, fmt.Errorf("invalid DNS-over-HTTPS URL %q", spec)
+		}
+		return Nameserver{Protocol: protocol, Address: spec}, nil
+	case "udp", "tcp", "tls":
+		port := "53"
+		if protocol == "tls" {
+			port = "853"
+		}
+		host, p, err := net.SplitHostPort(rest)
+		if err != nil {
+			host, p = strings.Trim(rest, "[]"), port
+		}
+		// DNS-over-TLS needs a name to verify, plain DNS cannot resolve one first
+		if host == "" || (protocol != "tls" && net.ParseIP(host) == nil) {
+			return Nameserver{}, fmt.Errorf("nameserver %q must be an IP address", spec)
+		}
+		return Nameserver{Protocol: protocol, Address: net.JoinHostPort(host, p)}, nil
+	}
+	return Nameserver{}, fmt.Errorf("unsupported nameserver protocol %q", protocol)
+}
+
+// String returns the nameserver in the form ParseNameserver accepts.
+func (n Nameserver) String() string {
+	if n.Protocol == "https" {
+		return n.Address
+	}
+	return n.Protocol + "://" + n.Address
+}
+
+// Host returns the hostname or IP of the nameserver.
+func (n Nameserver) Host() string {
+	if n.Protocol == "https" {
+		if u, err := url.Parse(n.Address); err == nil {
+			return u.Hostname()
+		}
+	}
+	host, _, _ := net.SplitHostPort(n.Address)
+	return host
+}
+
+// SystemNameservers returns the nameservers of /etc/resolv.conf. It returns
+// nil where the file does not exist, as on Windows.
+func SystemNameservers() []Nameserver {
+	file, err := os.Open("/etc/resolv.conf")
+	if err != nil {
+		return nil
+	}
+	defer file.Close()
+
+	var servers []Nameserver
+	scanner := bufio.NewScanner(file)
+	for scanner.Scan() {
+		fields := strings.Fields(scanner.Text())
+		if len(fields) < 2 || fields[0] != "nameserver" {
+			continue
+		}
+		// Drop an IPv6 zone, which resolv.conf may append
+		host, _, _ := strings.Cut(fields[1], "%")
+		if net.ParseIP(host) != nil {
+			servers = append(servers, Nameserver{Protocol: "udp", Address: net.JoinHostPort(host, "53")})
+		}
+	}
+	return servers
+}
+
+// Resolver resolves hostnames through explicit nameservers, tried in order
+// until one answers. A nil Resolver uses the system resolver.
+type Resolver struct {
+	Nameservers []Nameserver
+}
+
+// NewResolver parses nameservers as accepted by ParseNameserver. It returns
+// nil for an empty list.
+func NewResolver(specs []string) (*Resolver, error) {
+	if len(specs) == 0 {
+		return nil, nil
+	}
+	r := &Resolver{}
+	for _, spec := range specs {
+		ns, err := ParseNameserver(spec)
+		if err != nil {
+			return nil, err
+		}
+		r.Nameservers = append(r.Nameservers, ns)
+	}
+	return r, nil
+}
+
+// Custom reports whether r bypasses the system resolver.
+func (r *Resolver) Custom() bool {
+	return r != nil && len(r.Nameservers) > 0
+}
+
+// Resolve resolves a hostname to IP addresses with timing, querying A and
+// AAAA records concurrently.
+func (r *Resolver) Resolve(ctx context.Context, host string) (*DNSResult, error) {
+	if !r.Custom() {
+		return ResolveDNS(ctx, host)
+	}
+	if ip := net.ParseIP(host); ip != nil {
+		return &DNSResult{IPs: []net.IP{ip}}, nil
+	}
+
+	start := time.Now()
+	var lastErr error
+	for _, ns := range r.Nameservers {
+		ips, err := lookupAddresses(ctx, ns, host)
+		if err != nil {
+			// A name that does not exist won't exist on the next server either
+			var dnsErr *net.DNSError
+			if errors.As(err, &dnsErr) && dnsErr.IsNotFound {
+				return nil, err
+			}
+			lastErr = err
+			continue
+		}
+		return &DNSResult{
+			IPs:        ips,
+			DurationMs: uint64(time.Since(start).Milliseconds()),
+			Resolver:   ns.String(),
+		}, nil
+	}
+	return nil, lastErr
+}
+
+// lookupAddresses queries one nameserver for the A and AAAA records of host,
+// IPv4 addresses first.
+func lookupAddresses(ctx context.Context, ns Nameserver, host string) ([]net.IP, error) {
+	types := []string{"A", "AAAA"}
+	queries := make([]*DNSQuery, len(types))
+	errs := make([]error, len(types))
+	var wg sync.WaitGroup
+	for i, recordType := range types {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			queries[i], errs[i] = LookupRecord(ctx, ns, host, recordType)
+		}()
+	}
+	wg.Wait()
+
+	var ips []net.IP
+	for i, query := range queries {
+		if errs[i] != nil {
+			return nil, fmt.Errorf("%s: %w", ns, errs[i])
+		}
+		if query.RCode != "NOERROR" && query.RCode != "NXDOMAIN" {
+			return nil, fmt.Errorf("%s answered %s", ns, query.RCode)
+		}
+		for _, record := range query.Records {
+			if record.Type == "A" || record.Type == "AAAA" {
+				ips = append(ips, net.ParseIP(record.Value))
+			}
+		}
+	}
+	if len(ips) == 0 {
+		return nil, &net.DNSError{Err: "no addresses found", Name: host, Server: ns.String(), IsNotFound: true}
+	}
+	return ips, nil
+}
@@ -0,0 +1,323 @@
+package infra
+
+import (
+	"bytes"
+	"context"
+	"crypto/tls"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"io"
+	"math/rand/v2"
+	"net"
+	"net/http"
+	"strconv"
+	"strings"
+	"time"
+
+	"golang.org/x/net/dns/dnsmessage"
+)
+
+// Types that are not among those dnsmessage knows.
+const (
+	typeRRSIG dnsmessage.Type = 46
+	typeCAA   dnsmessage.Type = 257
+)
+
+// DNSRecordTypes lists the record types LookupRecord supports, in the order
+// they are usually shown.
+var DNSRecordTypes = []string{"A", "AAAA", "CNAME", "MX", "TXT", "NS", "CAA"}
+
+var recordTypes = map[string]dnsmessage.Type{
+	"A":     dnsmessage.TypeA,
+	"AAAA":  dnsmessage.TypeAAAA,
+	"CNAME": dnsmessage.TypeCNAME,
+	"MX":    dnsmessage.TypeMX,
+	"TXT":   dnsmessage.TypeTXT,
+	"NS":    dnsmessage.TypeNS,
+	"CAA":   typeCAA,
+}
+
+var rcodeNames = map[dnsmessage.RCode]string{
+	dnsmessage.RCodeSuccess:        "NOERROR",
+	dnsmessage.RCodeFormatError:    "FORMERR",
+	dnsmessage.RCodeServerFailure:  "SERVFAIL",
+	dnsmessage.RCodeNameError:      "NXDOMAIN",
+	dnsmessage.RCodeNotImplemented: "NOTIMP",
+	dnsmessage.RCodeRefused:        "REFUSED",
+}
+
+// DNSRecord is a resource record of an answer in presentation form.
+type DNSRecord struct {
+	Name  string
+	Type  string
+	TTL   uint32
+	Value string
+}
+
+// DNSQuery is the outcome of querying a nameserver for one record type.
+type DNSQuery struct {
+	Type          string
+	Server        string // Nameserver that answered
+	Transport     string // Protocol of the nameserver, or tcp after a truncated udp reply
+	DurationMs    uint64
+	RCode         string
+	Authoritative bool
+	AuthenticData bool // The resolver validated the answer with DNSSEC
+	Records       []DNSRecord
+	Signatures    []DNSSignature // RRSIG records of the answer; only sent for DNSSEC queries
+}
+
+// queryFlags are the DNSSEC bits of a query.
+type queryFlags struct {
+	dnssecOK         bool // Ask for RRSIG records
+	checkingDisabled bool // Ask a validating resolver to answer even when validation fails
+}
+
+// LookupRecord queries a nameserver directly for the records of one type,
+// retrying over TCP when a UDP reply is truncated. Answers are returned as-is,
+// so an A query for an alias includes the CNAME records leading to the addresses.
+func LookupRecord(ctx context.Context, ns Nameserver, name, recordType string) (*DNSQuery, error) {
+	return lookupRecord(ctx, ns, name, recordType, queryFlags{})
+}
+
+func lookupRecord(ctx context.Context, ns Nameserver, name, recordType string, flags queryFlags) (*DNSQuery, error) {
+	qtype, ok := recordTypes[strings.ToUpper(recordType)]
+	if !ok {
+		return nil, fmt.Errorf("unsupported record type %q", recordType)
+	}
+	if !strings.HasSuffix(name, ".") {
+		name += "."
+	}
+	qname, err := dnsmessage.NewName(name)
+	if err != nil {
+		return nil, fmt.Errorf("invalid name: %w", err)
+	}
+
+	// DNS-over-HTTPS uses ID 0 so replies can be cached by URL
+	var id uint16
+	if ns.Protocol != "https" {
+		id = uint16(rand.Uint32())
+	}
+	msg, err := buildQuery(id, qname, qtype, flags)
+	if err != nil {
+		return nil, err
+	}
+
+	query := &DNSQuery{Type: strings.ToUpper(recordType), Server: ns.String(), Transport: ns.Protocol}
+	start := time.Now()
+	reply, err := exchange(ctx, ns, id, msg)
+	if err == nil && ns.Protocol == "udp" && len(reply) > 2 && reply[2]&0x02 != 0 {
+		query.Transport = "tcp"
+		reply, err = exchange(ctx, Nameserver{Protocol: "tcp", Address: ns.Address}, id, msg)
+	}
+	query.DurationMs = uint64(time.Since(start).Milliseconds())
+	if err != nil {
+		return query, err
+	}
+
+	var p dnsmessage.Parser
+	header, err := p.Start(reply)
+	if err != nil {
+		return query, fmt.Errorf("invalid reply: %w", err)
+	}
+	query.RCode = rcodeName(header.RCode)
+	query.Authoritative = header.Authoritative
+	query.AuthenticData = header.AuthenticData
+	query.Records = []DNSRecord{}
+	if err := p.SkipAllQuestions(); err != nil {
+		return query, fmt.Errorf("invalid reply: %w", err)
+	}
+	for {
+		rh, err := p.AnswerHeader()
+		if errors.Is(err, dnsmessage.ErrSectionDone) {
+			break
+		}
+		if err != nil {
+			return query, fmt.Errorf("invalid reply: %w", err)
+		}
+		if rh.Type == typeRRSIG {
+			r, err := p.UnknownResource()
+			if err != nil {
+				return query, fmt.Errorf("invalid RRSIG record: %w", err)
+			}
+			signature, err := parseSignature(r.Data)
+			if err != nil {
+				return query, fmt.Errorf("invalid RRSIG record: %w", err)
+			}
+			query.Signatures = append(query.Signatures, signature)
+			continue
+		}
+		value, err := recordValue(&p, rh)
+		if err != nil {
+			return query, fmt.Errorf("invalid %s record: %w", typeName(rh.Type), err)
+		}
+		query.Records = append(query.Records, DNSRecord{
+			Name:  rh.Name.String(),
+			Type:  typeName(rh.Type),
+			TTL:   rh.TTL,
+			Value: value,
+		})
+	}
+	return query, nil
+}
+
+// buildQuery encodes a recursive query, advertising a 4096 byte EDNS0
+// buffer so large TXT sets rarely need the TCP retry.
+func buildQuery(id uint16, name dnsmessage.Name, qtype dnsmessage.Type, flags queryFlags) ([]byte, error) {
+	b := dnsmessage.NewBuilder(make([]byte, 0, 512), dnsmessage.Header{ID: id, RecursionDesired: true, CheckingDisabled: flags.checkingDisabled})
+	b.EnableCompression()
+	if err := b.StartQuestions(); err != nil {
+		return nil, err
+	}
+	if err := b.Question(dnsmessage.Question{Name: name, Type: qtype, Class: dnsmessage.ClassINET}); err != nil {
+		return nil, err
+	}
+	if err := b.StartAdditionals(); err != nil {
+		return nil, err
+	}
+	var opt dnsmessage.ResourceHeader
+	if err := opt.SetEDNS0(4096, dnsmessage.RCodeSuccess, flags.dnssecOK); err != nil {
+		return nil, err
+	}
+	if err := b.OPTResource(opt, dnsmessage.OPTResource{}); err != nil {
+		return nil, err
+	}
+	return b.Finish()
+}
+
+// exchange sends msg to a nameserver and reads the reply with the matching ID.
+func exchange(ctx context.Context, ns Nameserver, id uint16, msg []byte) ([]byte, error) {
+	if ns.Protocol == "https" {
+		return exchangeHTTPS(ctx, ns.Address, msg)
+	}
+
+	var conn net.Conn
+	var err error
+	switch ns.Protocol {
+	case "tls":
+		dialer := &tls.Dialer{Config: &tls.Config{ServerName: ns.Host()}}
+		conn, err = dialer.DialContext(ctx, "tcp", ns.Address)
+	default:
+		var dialer net.Dialer
+		conn, err = dialer.DialContext(ctx, ns.Protocol, ns.Address)
+	}
+	if err != nil {
+		return nil, err
+	}
+	defer conn.Close()
+	if deadline, ok := ctx.Deadline(); ok {
+		conn.SetDeadline(deadline)
+	}
+
+	if ns.Protocol != "udp" {
+		// Stream transports prefix each message with its length
+		framed := binary.BigEndian.AppendUint16(nil, uint16(len(msg)))
+		if _, err := conn.Write(append(framed, msg...)); err != nil {
+			return nil, err
+		}
+		var length [2]byte
+		if _, err := io.ReadFull(conn, length[:]); err != nil {
+			return nil, err
+		}
+		reply := make([]byte, binary.BigEndian.Uint16(length[:]))
+		if _, err := io.ReadFull(conn, reply); err != nil {
+			return nil, err
+		}
+		return reply, nil
+	}
+
+	if _, err := conn.Write(msg); err != nil {
+		return nil, err
+	}
+	buf := make([]byte, 65535)
+	for {
+		n, err := conn.Read(buf)
+		if err != nil {
+			return nil, err
+		}
+		// Ignore stray datagrams, such as late replies to an earlier query
+		if n >= 12 && binary.BigEndian.Uint16(buf) == id {
+			return buf[:n], nil
+		}
+	}
+}
+
+// exchangeHTTPS posts msg to a DNS-over-HTTPS endpoint (RFC 8484).
+func exchangeHTTPS(ctx context.Context, endpoint string, msg []byte) ([]byte, error) {
+	req, err := http.NewRequestWithContext(ctx, http.MethodPost, endpoint, bytes.NewReader(msg))
+	if err != nil {
+		return nil, err
+	}
+	req.Header.Set("Content-Type", "application/dns-message")
+	req.Header.Set("Accept", "application/dns-message")
+
+	resp, err := http.DefaultClient.Do(req)
+	if err != nil {
+		return nil, err
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode != http.StatusOK {
+		return nil, fmt.Errorf("DNS-over-HTTPS endpoint returned %s", resp.Status)
+	}
+	return io.ReadAll(io.LimitReader(resp.Body, 65535))
+}
+
+// recordValue formats the data of a record as it appears in a zone file.
+func recordValue(p *dnsmessage.Parser, rh dnsmessage.ResourceHeader) (string, error) {
+	switch rh.Type {
+	case dnsmessage.TypeA:
+		r, err := p.AResource()
+		return net.IP(r.A[:]).String(), err
+	case dnsmessage.TypeAAAA:
+		r, err := p.AAAAResource()
+		return net.IP(r.AAAA[:]).String(), err
+	case dnsmessage.TypeCNAME:
+		r, err := p.CNAMEResource()
+		return r.CNAME.String(), err
+	case dnsmessage.TypeMX:
+		r, err := p.MXResource()
+		return fmt.Sprintf("%d %s", r.Pref, r.MX.String()), err
+	case dnsmessage.TypeNS:
+		r, err := p.NSResource()
+		return r.NS.String(), err
+	case dnsmessage.TypeTXT:
+		// Long values such as SPF are split into strings meant to be joined
+		r, err := p.TXTResource()
+		return strings.Join(r.TXT, ""), err
+	}
+
+	r, err := p.UnknownResource()
+	if err != nil {
+		return "", err
+	}
+	if rh.Type == typeCAA {
+		return caaValue(r.Data)
+	}
+	return fmt.Sprintf(`\# %d %x`, len(r.Data), r.Data), nil
+}
+
+// caaValue formats CAA record data (RFC 8659) as flags, tag and quoted value.
+func caaValue(data []byte) (string, error) {
+	if len(data) < 2 || len(data) < 2+int(data[1]) {
+		return "", errors.New("truncated CAA record")
+	}
+	tag := string(data[2 : 2+int(data[1])])
+	return fmt.Sprintf("%d %s %s", data[0], tag, strconv.Quote(string(data[2+int(data[1]):]))), nil
+}
+
+func typeName(t dnsmessage.Type) string {
+	for name, known := range recordTypes {
+		if known == t {
+			return name
+		}
+	}
+	return "TYPE" + strconv.Itoa(int(t))
+}
+
+func rcodeName(code dnsmessage.RCode) string {
+	if name, ok := rcodeNames[code]; ok {
+		return name
+	}
+	return "RCODE" + strconv.Itoa(int(code))
+}
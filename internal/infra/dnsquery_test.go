@@ -0,0 +1,320 @@
+package infra_test
+
+import (
+	"context"
+	"encoding/binary"
+	"io"
+	"net"
+	"net/http"
+	"net/http/httptest"
+	"reflect"
+	"strings"
+	"testing"
+	"time"
+
+	"golang.org/x/net/dns/dnsmessage"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+// dnsAnswer answers a query the way a resolver with a few fixed zones would:
+// example.com. has a record of every supported type, the *.example. names
+// exercise DNSSEC, and other names do not exist. Truncated replies carry no
+// records, as servers send when the answer exceeds a datagram.
+func dnsAnswer(t *testing.T, query []byte, truncated bool) []byte {
+	var p dnsmessage.Parser
+	header, err := p.Start(query)
+	if err != nil {
+		t.Errorf("server got an invalid query: %v", err)
+		return nil
+	}
+	q, err := p.Question()
+	if err != nil {
+		t.Errorf("server got a query without a question: %v", err)
+		return nil
+	}
+
+	reply := dnsmessage.Header{ID: header.ID, Response: true, Authoritative: true, RecursionDesired: header.RecursionDesired, Truncated: truncated}
+	rh := dnsmessage.ResourceHeader{Name: q.Name, Class: dnsmessage.ClassINET, TTL: 300}
+	var answers []func(*dnsmessage.Builder) error
+	address := func(b *dnsmessage.Builder) error {
+		return b.AResource(rh, dnsmessage.AResource{A: [4]byte{192, 0, 2, 1}})
+	}
+	signature := func(b *dnsmessage.Builder) error {
+		data := []byte{0, 1, 13, 2, 0, 0, 1, 44, 0, 0, 7, 208, 0, 0, 3, 232, 48, 57, 7, 'e', 'x', 'a', 'm', 'p', 'l', 'e', 0, 's', 'i', 'g'}
+		return b.UnknownResource(rh, dnsmessage.UnknownResource{Type: 46, Data: data})
+	}
+	switch name := q.Name.String(); {
+	case truncated:
+	case name == "example.com.":
+		answers = append(answers, map[dnsmessage.Type]func(*dnsmessage.Builder) error{
+			dnsmessage.TypeA: address,
+			dnsmessage.TypeAAAA: func(b *dnsmessage.Builder) error {
+				return b.AAAAResource(rh, dnsmessage.AAAAResource{AAAA: [16]byte{0x20, 0x01, 0x0d, 0xb8, 15: 1}})
+			},
+			dnsmessage.TypeCNAME: func(b *dnsmessage.Builder) error {
+				return b.CNAMEResource(rh, dnsmessage.CNAMEResource{CNAME: dnsmessage.MustNewName("www.example.net.")})
+			},
+			dnsmessage.TypeMX: func(b *dnsmessage.Builder) error {
+				return b.MXResource(rh, dnsmessage.MXResource{Pref: 10, MX: dnsmessage.MustNewName("mail.example.com.")})
+			},
+			dnsmessage.TypeTXT: func(b *dnsmessage.Builder) error {
+				return b.TXTResource(rh, dnsmessage.TXTResource{TXT: []string{"v=spf1 ", "-all"}})
+			},
+			dnsmessage.TypeNS: func(b *dnsmessage.Builder) error {
+				return b.NSResource(rh, dnsmessage.NSResource{NS: dnsmessage.MustNewName("ns1.example.com.")})
+			},
+			257: func(b *dnsmessage.Builder) error {
+				return b.UnknownResource(rh, dnsmessage.UnknownResource{Type: 257, Data: []byte("\x00\x05issueletsencrypt.org")})
+			},
+		}[q.Type])
+	case name == "unsigned.example.":
+		answers = append(answers, address)
+	case name == "signed.example.":
+		answers = append(answers, address, signature)
+	case name == "secure.example.":
+		reply.AuthenticData = true
+		answers = append(answers, address, signature)
+	case name == "bogus.example." && !header.CheckingDisabled:
+		reply.RCode = dnsmessage.RCodeServerFailure
+	case name == "bogus.example.":
+		answers = append(answers, address, signature)
+	default:
+		reply.RCode = dnsmessage.RCodeNameError
+	}
+
+	b := dnsmessage.NewBuilder(nil, reply)
+	b.StartQuestions()
+	b.Question(q)
+	b.StartAnswers()
+	for _, answer := range answers {
+		if err := answer(&b); err != nil {
+			t.Errorf("building the answer: %v", err)
+		}
+	}
+	msg, err := b.Finish()
+	if err != nil {
+		t.Errorf("building the answer: %v", err)
+	}
+	return msg
+}
+
+// startDNS serves dnsAnswer over UDP and TCP on one port of the loopback
+// address and returns that address.
+func startDNS(t *testing.T, truncateUDP bool) string {
+	listener, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		t.Fatal(err)
+	}
+	packets, err := net.ListenPacket("udp", listener.Addr().String())
+	if err != nil {
+		listener.Close()
+		t.Skipf("UDP port of %s is taken: %v", listener.Addr(), err)
+	}
+	t.Cleanup(func() {
+		listener.Close()
+		packets.Close()
+	})
+
+	go func() {
+		buf := make([]byte, 65535)
+		for {
+			n, addr, err := packets.ReadFrom(buf)
+			if err != nil {
+				return
+			}
+			packets.WriteTo(dnsAnswer(t, buf[:n], truncateUDP), addr)
+		}
+	}()
+	go func() {
+		for {
+			conn, err := listener.Accept()
+			if err != nil {
+				return
+			}
+			go func() {
+				defer conn.Close()
+				var length [2]byte
+				if _, err := io.ReadFull(conn, length[:]); err != nil {
+					return
+				}
+				query := make([]byte, binary.BigEndian.Uint16(length[:]))
+				if _, err := io.ReadFull(conn, query); err != nil {
+					return
+				}
+				reply := dnsAnswer(t, query, false)
+				conn.Write(append(binary.BigEndian.AppendUint16(nil, uint16(len(reply))), reply...))
+			}()
+		}
+	}()
+	return listener.Addr().String()
+}
+
+// startDoH serves dnsAnswer as a DNS-over-HTTPS endpoint, without TLS.
+func startDoH(t *testing.T) string {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.Method != http.MethodPost || r.Header.Get("Content-Type") != "application/dns-message" {
+			http.Error(w, "expected a POSTed DNS message", http.StatusBadRequest)
+			return
+		}
+		query, _ := io.ReadAll(r.Body)
+		w.Header().Set("Content-Type", "application/dns-message")
+		w.Write(dnsAnswer(t, query, false))
+	}))
+	t.Cleanup(server.Close)
+	return server.URL + "/dns-query"
+}
+
+func lookupContext(t *testing.T) context.Context {
+	ctx, cancel := context.WithTimeout(context.Background(), 5*time.Second)
+	t.Cleanup(cancel)
+	return ctx
+}
+
+func TestParseNameserver(t *testing.T) {
+	tests := []struct {
+		spec string
+		want infra.Nameserver
+		err  bool
+	}{
+		{"1.1.1.1", infra.Nameserver{Protocol: "udp", Address: "1.1.1.1:53"}, false},
+		{" 1.1.1.1:5353 ", infra.Nameserver{Protocol: "udp", Address: "1.1.1.1:5353"}, false},
+		{"tcp://1.1.1.1", infra.Nameserver{Protocol: "tcp", Address: "1.1.1.1:53"}, false},
+		{"[2606:4700::1111]", infra.Nameserver{Protocol: "udp", Address: "[2606:4700::1111]:53"}, false},
+		{"udp://[2606:4700::1111]:5353", infra.Nameserver{Protocol: "udp", Address: "[2606:4700::1111]:5353"}, false},
+		{"tls://dns.quad9.net", infra.Nameserver{Protocol: "tls", Address: "dns.quad9.net:853"}, false},
+		{"https://dns.google/dns-query", infra.Nameserver{Protocol: "https", Address: "https://dns.google/dns-query"}, false},
+		{"dns.google", infra.Nameserver{}, true},
+		{"tcp://", infra.Nameserver{}, true},
+		{"https:///dns-query", infra.Nameserver{}, true},
+		{"quic://1.1.1.1", infra.Nameserver{}, true},
+	}
+	for _, tt := range tests {
+		t.Run(tt.spec, func(t *testing.T) {
+			got, err := infra.ParseNameserver(tt.spec)
+			if (err != nil) != tt.err {
+				t.Fatalf("err = %v, want an error: %v", err, tt.err)
+			}
+			if got != tt.want {
+				t.Errorf("ParseNameserver = %+v, want %+v", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestLookupRecord(t *testing.T) {
+	ns := infra.Nameserver{Protocol: "udp", Address: startDNS(t, false)}
+
+	tests := []struct {
+		recordType string
+		value      string
+	}{
+		{"A", "192.0.2.1"},
+		{"AAAA", "2001:db8::1"},
+		{"CNAME", "www.example.net."},
+		{"MX", "10 mail.example.com."},
+		{"TXT", "v=spf1 -all"},
+		{"NS", "ns1.example.com."},
+		{"caa", `0 issue "letsencrypt.org"`},
+	}
+	for _, tt := range tests {
+		t.Run(tt.recordType, func(t *testing.T) {
+			query, err := infra.LookupRecord(lookupContext(t), ns, "example.com", tt.recordType)
+			if err != nil {
+				t.Fatal(err)
+			}
+			want := []infra.DNSRecord{{Name: "example.com.", Type: strings.ToUpper(tt.recordType), TTL: 300, Value: tt.value}}
+			if query.RCode != "NOERROR" || !query.Authoritative || !reflect.DeepEqual(query.Records, want) {
+				t.Errorf("LookupRecord = %s %+v, want NOERROR %+v from an authoritative server", query.RCode, query.Records, want)
+			}
+		})
+	}
+}
+
+func TestLookupRecordTransports(t *testing.T) {
+	address := startDNS(t, false)
+	tests := []struct {
+		name      string
+		ns        infra.Nameserver
+		transport string
+	}{
+		{"udp", infra.Nameserver{Protocol: "udp", Address: address}, "udp"},
+		{"tcp", infra.Nameserver{Protocol: "tcp", Address: address}, "tcp"},
+		{"truncated udp", infra.Nameserver{Protocol: "udp", Address: startDNS(t, true)}, "tcp"},
+		{"https", infra.Nameserver{Protocol: "https", Address: startDoH(t)}, "https"},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			query, err := infra.LookupRecord(lookupContext(t), tt.ns, "example.com.", "A")
+			if err != nil {
+				t.Fatal(err)
+			}
+			if query.Transport != tt.transport {
+				t.Errorf("Transport = %q, want %q", query.Transport, tt.transport)
+			}
+			if len(query.Records) != 1 || query.Records[0].Value != "192.0.2.1" {
+				t.Errorf("Records = %+v, want the A record", query.Records)
+			}
+		})
+	}
+}
+
+func TestLookupRecordErrors(t *testing.T) {
+	ns := infra.Nameserver{Protocol: "udp", Address: startDNS(t, false)}
+
+	query, err := infra.LookupRecord(lookupContext(t), ns, "missing.example.com", "A")
+	if err != nil {
+		t.Fatal(err)
+	}
+	if query.RCode != "NXDOMAIN" || len(query.Records) != 0 {
+		t.Errorf("LookupRecord = %s %+v, want NXDOMAIN without records", query.RCode, query.Records)
+	}
+
+	if _, err := infra.LookupRecord(lookupContext(t), ns, "example.com", "SOA"); err == nil {
+		t.Error("an unsupported record type was queried")
+	}
+
+	// DNS-over-TLS verifies the certificate of the nameserver
+	untrusted := httptest.NewTLSServer(http.NotFoundHandler())
+	defer untrusted.Close()
+	dot := infra.Nameserver{Protocol: "tls", Address: untrusted.Listener.Addr().String()}
+	if _, err := infra.LookupRecord(lookupContext(t), dot, "example.com", "A"); err == nil {
+		t.Error("DNS-over-TLS accepted an untrusted certificate")
+	}
+}
+
+func TestCheckDNSSEC(t *testing.T) {
+	ns := infra.Nameserver{Protocol: "tcp", Address: startDNS(t, false)}
+	signature := infra.DNSSignature{
+		TypeCovered: "A",
+		Algorithm:   "ECDSAP256SHA256",
+		Signer:      "example.",
+		KeyTag:      12345,
+		Inception:   1000,
+		Expiration:  2000,
+	}
+
+	tests := []struct {
+		host   string
+		status string
+	}{
+		{"unsigned.example", infra.DNSSECInsecure},
+		{"signed.example", infra.DNSSECUnvalidated},
+		{"secure.example", infra.DNSSECSecure},
+		{"bogus.example", infra.DNSSECBogus},
+	}
+	for _, tt := range tests {
+		t.Run(tt.host, func(t *testing.T) {
+			result, err := infra.CheckDNSSEC(lookupContext(t), ns, tt.host)
+			if err != nil {
+				t.Fatal(err)
+			}
+			if result.Status != tt.status {
+				t.Errorf("Status = %q, want %q", result.Status, tt.status)
+			}
+			if tt.status != infra.DNSSECInsecure && !reflect.DeepEqual(result.Signatures, []infra.DNSSignature{signature}) {
+				t.Errorf("Signatures = %+v, want %+v", result.Signatures, signature)
+			}
+		})
+	}
+}
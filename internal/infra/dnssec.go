@@ -0,0 +1,190 @@
+package infra
+
+import (
+	"context"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"strconv"
+	"strings"
+
+	"golang.org/x/net/dns/dnsmessage"
+)
+
+// DNSSEC statuses reported by CheckDNSSEC.
+const (
+	DNSSECSecure      = "secure"      // The resolver validated the answer
+	DNSSECInsecure    = "insecure"    // The answer is not signed
+	DNSSECUnvalidated = "unvalidated" // Signed, but the resolver does not validate
+	DNSSECBogus       = "bogus"       // Signed, but the signatures do not validate
+)
+
+var dnssecAlgorithms = map[uint8]string{
+	5:  "RSASHA1",
+	7:  "RSASHA1-NSEC3-SHA1",
+	8:  "RSASHA256",
+	10: "RSASHA512",
+	13: "ECDSAP256SHA256",
+	14: "ECDSAP384SHA384",
+	15: "ED25519",
+	16: "ED448",
+}
+
+// DNSSignature is an RRSIG record (RFC 4034) of an answer.
+type DNSSignature struct {
+	TypeCovered string
+	Algorithm   string // e.g. ECDSAP256SHA256
+	Signer      string // Zone that signed the records
+	KeyTag      uint16 // Identifies the DNSKEY of the signer
+	Inception   uint64 // Unix seconds
+	Expiration  uint64 // Unix seconds
+}
+
+// DNSSECResult is the DNSSEC status of the addresses of a name.
+type DNSSECResult struct {
+	Status     string
+	Signatures []DNSSignature
+}
+
+// CheckDNSSEC asks a nameserver for the A records of host with the DNSSEC OK
+// bit set and reports whether it validated them. The status is only as
+// trustworthy as the path to the nameserver.
+func CheckDNSSEC(ctx context.Context, ns Nameserver, host string) (*DNSSECResult, error) {
+	query, err := lookupRecord(ctx, ns, host, "A", queryFlags{dnssecOK: true})
+	if err != nil {
+		return nil, err
+	}
+	result := &DNSSECResult{Signatures: query.Signatures}
+	switch {
+	case query.AuthenticData:
+		result.Status = DNSSECSecure
+	case query.RCode == "SERVFAIL":
+		// Validating resolvers fail bogus answers; with checking disabled
+		// they return them, which tells a failed validation from an outage
+		retry, err := lookupRecord(ctx, ns, host, "A", queryFlags{dnssecOK: true, checkingDisabled: true})
+		if err != nil || retry.RCode != "NOERROR" || len(retry.Signatures) == 0 {
+			return nil, fmt.Errorf("the nameserver answered %s", query.RCode)
+		}
+		result.Status = DNSSECBogus
+		result.Signatures = retry.Signatures
+	case len(query.Signatures) > 0:
+		result.Status = DNSSECUnvalidated
+	default:
+		result.Status = DNSSECInsecure
+	}
+	return result, nil
+}
+
+// parseSignature decodes the data of an RRSIG record.
+func parseSignature(data []byte) (DNSSignature, error) {
+	if len(data) < 18 {
+		return DNSSignature{}, errors.New("truncated")
+	}
+	signer, err := wireName(data[18:])
+	if err != nil {
+		return DNSSignature{}, err
+	}
+	algorithm, ok := dnssecAlgorithms[data[2]]
+	if !ok {
+		algorithm = strconv.Itoa(int(data[2]))
+	}
+	return DNSSignature{
+		TypeCovered: typeName(dnsmessage.Type(binary.BigEndian.Uint16(data))),
+		Algorithm:   algorithm,
+		Signer:      signer,
+		KeyTag:      binary.BigEndian.Uint16(data[16:]),
+		Expiration:  uint64(binary.BigEndian.Uint32(data[8:])),
+		Inception:   uint64(binary.BigEndian.Uint32(data[12:])),
+	}, nil
+}
+
+// wireName decodes an uncompressed domain name, as RRSIG records carry.
+func wireName(data []byte) (string, error) {
+	var labels []string
+	for len(data) > 0 {
+		n := int(data[0])
+		if n == 0 {
+			return strings.Join(labels, ".") + ".", nil
+		}
+		if n > 63 || len(data) < 1+n {
+			return "", errors.New("invalid signer name")
+		}
+		labels = append(labels, string(data[1:1+n]))
+		data = data[1+n:]
+	}
+	return "", errors.New("invalid signer name")
+}
+
+// CAAPolicy is the CAA record set (RFC 8659) that governs certificate
+// issuance for a host.
+type CAAPolicy struct {
+	Domain    string // Where the records were found, climbing from the host; empty when none apply and any CA may issue
+	Records   []DNSRecord
+	Issue     []string // Values of issue properties, e.g. "letsencrypt.org"; ";" forbids issuance
+	IssueWild []string // Values of issuewild properties, which override issue for wildcards
+	IODEF     []string // Where CAs report refused requests
+	Critical  []string // Tags flagged critical that CAs must understand to issue
+}
+
+// LookupCAA finds the CAA records relevant to host, climbing from the host
+// towards its top-level domain until a name has any. CNAMEs in the answers
+// are followed by the nameserver.
+func LookupCAA(ctx context.Context, ns Nameserver, host string) (*CAAPolicy, error) {
+	policy := &CAAPolicy{Records: []DNSRecord{}}
+	name := strings.TrimSuffix(host, ".")
+	for name != "" {
+		query, err := LookupRecord(ctx, ns, name, "CAA")
+		if err != nil {
+			return nil, err
+		}
+		if query.RCode != "NOERROR" && query.RCode != "NXDOMAIN" {
+			return nil, fmt.Errorf("the nameserver answered %s for %s", query.RCode, name)
+		}
+		for _, record := range query.Records {
+			if record.Type == "CAA" {
+				policy.Records = append(policy.Records, record)
+			}
+		}
+		if len(policy.Records) > 0 {
+			policy.Domain = name
+			break
+		}
+		_, name, _ = strings.Cut(name, ".")
+	}
+
+	for _, record := range policy.Records {
+		critical, tag, value, ok := splitCAA(record.Value)
+		if !ok {
+			continue
+		}
+		switch strings.ToLower(tag) {
+		case "issue":
+			policy.Issue = append(policy.Issue, value)
+		case "issuewild":
+			policy.IssueWild = append(policy.IssueWild, value)
+		case "iodef":
+			policy.IODEF = append(policy.IODEF, value)
+		default:
+			if critical {
+				policy.Critical = append(policy.Critical, tag)
+			}
+		}
+	}
+	return policy, nil
+}
+
+// splitCAA reads a CAA value as formatted by caaValue.
+func splitCAA(s string) (critical bool, tag, value string, ok bool) {
+	flags, rest, _ := strings.Cut(s, " ")
+	tag, quoted, _ := strings.Cut(rest, " ")
+	n, err := strconv.Atoi(flags)
+	if err != nil {
+		return false, "", "", false
+	}
+	value, err = strconv.Unquote(quoted)
+	if err != nil {
+		return false, "", "", false
+	}
+	// The issuer critical flag is the high bit
+	return n&0x80 != 0, tag, value, true
+}
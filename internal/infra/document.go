@@ -0,0 +1,370 @@
+package infra
+
+import (
+	"archive/zip"
+	"bytes"
+	"compress/zlib"
+	"encoding/xml"
+	"io"
+	"regexp"
+	"strconv"
+	"strings"
+	"unicode/utf16"
+)
+
+// maxObjectStream bounds the decompressed size of a single PDF object stream.
+const maxObjectStream = 16 << 20
+
+// DocumentInfo is metadata extracted from a PDF or Office Open XML document.
+type DocumentInfo struct {
+	Format    string // pdf, docx, xlsx or pptx
+	Version   string // PDF version or generating application version
+	Pages     *int
+	Sheets    *int
+	Slides    *int
+	Title     string
+	Author    string
+	Subject   string
+	Creator   string // Authoring application
+	Producer  string
+	Created   string
+	Modified  string
+	Encrypted bool
+	Metadata  map[string]string // Remaining metadata fields by name
+	Err       error
+}
+
+// ExtractDocumentInfo reads document metadata from a PDF or OOXML body.
+// It returns nil for other content.
+func ExtractDocumentInfo(data []byte) *DocumentInfo {
+	switch {
+	case bytes.HasPrefix(data, []byte("%PDF-")):
+		return pdfInfo(data)
+	case bytes.HasPrefix(data, []byte("PK\x03\x04")):
+		return ooxmlInfo(data)
+	}
+	return nil
+}
+
+var (
+	pdfVersionPattern = regexp.MustCompile(`^%PDF-(\d\.\d)`)
+	pdfPagePattern    = regexp.MustCompile(`/Type\s*/Page[^s]`)
+	pdfInfoRefPattern = regexp.MustCompile(`/Info\s+(\d+)\s+(\d+)\s+R`)
+	pdfObjStmPattern  = regexp.MustCompile(`/Type\s*/ObjStm`)
+)
+
+// pdfInfoKeys lists the Info dictionary entries that are read.
+var pdfInfoKeys = []string{"Title", "Author", "Subject", "Keywords", "Creator", "Producer", "CreationDate", "ModDate", "Trapped"}
+
+func pdfInfo(data []byte) *DocumentInfo {
+	info := &DocumentInfo{Format: "pdf", Metadata: map[string]string{}}
+	if m := pdfVersionPattern.FindSubmatch(data); m != nil {
+		info.Version = string(m[1])
+	}
+	info.Encrypted = bytes.Contains(data, []byte("/Encrypt"))
+
+	// Objects may be packed into compressed object streams
+	content := data
+	if streams := pdfObjectStreams(data); len(streams) > 0 {
+		content = bytes.Join(append([][]byte{data}, streams...), []byte("\n"))
+	}
+
+	pages := len(pdfPagePattern.FindAllIndex(content, -1))
+	if pages > 0 {
+		info.Pages = &pages
+	}
+
+	dict := pdfInfoDict(content)
+	if dict == nil {
+		return info
+	}
+	for _, key := range pdfInfoKeys {
+		value, ok := pdfDictString(dict, key)
+		if !ok {
+			continue
+		}
+		switch key {
+		case "Title":
+			info.Title = value
+		case "Author":
+			info.Author = value
+		case "Subject":
+			info.Subject = value
+		case "Creator":
+			info.Creator = value
+		case "Producer":
+			info.Producer = value
+		case "CreationDate":
+			info.Created = value
+		case "ModDate":
+			info.Modified = value
+		default:
+			info.Metadata[key] = value
+		}
+	}
+	return info
+}
+
+// pdfInfoDict returns the body of the document information dictionary.
+func pdfInfoDict(content []byte) []byte {
+	matches := pdfInfoRefPattern.FindAllSubmatch(content, -1)
+	if len(matches) == 0 {
+		return nil
+	}
+	// The last trailer wins in incrementally updated files
+	ref := matches[len(matches)-1]
+	header := regexp.MustCompile(`(?:^|\s)` + string(ref[1]) + `\s+` + string(ref[2]) + `\s+obj\s*<<`)
+	loc := header.FindIndex(content)
+	if loc == nil {
+		// Objects inside object streams have no obj header; fall back to the first Info-like dictionary
+		i := bytes.Index(content, []byte("/Producer"))
+		if i < 0 {
+			return nil
+		}
+		start := bytes.LastIndex(content[:i], []byte("<<"))
+		if start < 0 {
+			return nil
+		}
+		return content[start:]
+	}
+	return content[loc[1]:]
+}
+
+// pdfDictString reads a string value of key from a dictionary body.
+func pdfDictString(dict []byte, key string) (string, bool) {
+	end := bytes.Index(dict, []byte(">>"))
+	if end < 0 {
+		end = len(dict)
+	}
+	i := bytes.Index(dict[:end], []byte("/"+key))
+	if i < 0 {
+		return "", false
+	}
+	rest := bytes.TrimLeft(dict[i+len(key)+1:], " \t\r\n")
+	if len(rest) == 0 {
+		return "", false
+	}
+
+	switch rest[0] {
+	case '(':
+		return decodePDFText(pdfLiteralString(rest)), true
+	case '<':
+		closing := bytes.IndexByte(rest, '>')
+		if closing < 0 {
+			return "", false
+		}
+		raw := make([]byte, 0, closing/2)
+		hexDigits := bytes.Map(func(r rune) rune {
+			if strings.ContainsRune("0123456789abcdefABCDEF", r) {
+				return r
+			}
+			return -1
+		}, rest[1:closing])
+		for j := 0; j+1 < len(hexDigits); j += 2 {
+			b, _ := strconv.ParseUint(string(hexDigits[j:j+2]), 16, 8)
+			raw = append(raw, byte(b))
+		}
+		return decodePDFText(raw), true
+	}
+	return "", false
+}
+
+// pdfLiteralString decodes a parenthesized string, honoring nesting and escapes.
+func pdfLiteralString(src []byte) []byte {
+	var out []byte
+	depth := 0
+	for i := 0; i < len(src); i++ {
+		c := src[i]
+		switch {
+		case c == '\\' && i+1 < len(src):
+			i++
+			switch e := src[i]; e {
+			case 'n':
+				out = append(out, '\n')
+			case 'r':
+				out = append(out, '\r')
+			case 't':
+				out = append(out, '\t')
+			case 'b':
+				out = append(out, '\b')
+			case 'f':
+				out = append(out, '\f')
+			case '\r', '\n':
+				// Line continuation
+			default:
+				if e >= '0' && e <= '7' {
+					j := i
+					for j < len(src) && j < i+3 && src[j] >= '0' && src[j] <= '7' {
+						j++
+					}
+					n, _ := strconv.ParseUint(string(src[i:j]), 8, 8)
+					out = append(out, byte(n))
+					i = j - 1
+				} else {
+					out = append(out, e)
+				}
+			}
+		case c == '(':
+			if depth > 0 {
+				out = append(out, c)
+			}
+			depth++
+		case c == ')':
+			depth--
+			if depth == 0 {
+				return out
+			}
+			out = append(out, c)
+		default:
+			out = append(out, c)
+		}
+	}
+	return out
+}
+
+// decodePDFText decodes UTF-16BE strings marked with a byte order mark; others
+// are PDFDocEncoding, treated as Latin-1.
+func decodePDFText(raw []byte) string {
+	if len(raw) >= 2 && raw[0] == 0xfe && raw[1] == 0xff {
+		units := make([]uint16, 0, len(raw)/2)
+		for i := 2; i+1 < len(raw); i += 2 {
+			units = append(units, uint16(raw[i])<<8|uint16(raw[i+1]))
+		}
+		return string(utf16.Decode(units))
+	}
+	runes := make([]rune, len(raw))
+	for i, b := range raw {
+		runes[i] = rune(b)
+	}
+	return string(runes)
+}
+
+// pdfObjectStreams inflates the compressed object streams of a PDF.
+func pdfObjectStreams(data []byte) [][]byte {
+	var streams [][]byte
+	for _, loc := range pdfObjStmPattern.FindAllIndex(data, -1) {
+		start := bytes.Index(data[loc[1]:], []byte("stream"))
+		if start < 0 {
+			continue
+		}
+		start += loc[1] + len("stream")
+		for start < len(data) && (data[start] == '\r' || data[start] == '\n') {
+			start++
+		}
+		zr, err := zlib.NewReader(bytes.NewReader(data[start:]))
+		if err != nil {
+			continue
+		}
+		stream, _ := io.ReadAll(io.LimitReader(zr, maxObjectStream))
+		zr.Close()
+		streams = append(streams, stream)
+	}
+	return streams
+}
+
+// ooxmlCore is docProps/core.xml.
+type ooxmlCore struct {
+	Title          string `xml:"title"`
+	Subject        string `xml:"subject"`
+	Creator        string `xml:"creator"`
+	Keywords       string `xml:"keywords"`
+	Description    string `xml:"description"`
+	LastModifiedBy string `xml:"lastModifiedBy"`
+	Revision       string `xml:"revision"`
+	Created        string `xml:"created"`
+	Modified       string `xml:"modified"`
+	Category       string `xml:"category"`
+}
+
+// ooxmlApp is docProps/app.xml.
+type ooxmlApp struct {
+	Application string `xml:"Application"`
+	AppVersion  string `xml:"AppVersion"`
+	Company     string `xml:"Company"`
+	Pages       *int   `xml:"Pages"`
+	Words       *int   `xml:"Words"`
+	Slides      *int   `xml:"Slides"`
+}
+
+func ooxmlInfo(data []byte) *DocumentInfo {
+	r, err := zip.NewReader(bytes.NewReader(data), int64(len(data)))
+	if err != nil {
+		return nil
+	}
+
+	files := make(map[string]*zip.File, len(r.File))
+	sheets := 0
+	for _, f := range r.File {
+		files[f.Name] = f
+		if strings.HasPrefix(f.Name, "xl/worksheets/") && strings.HasSuffix(f.Name, ".xml") {
+			sheets++
+		}
+	}
+
+	info := &DocumentInfo{Metadata: map[string]string{}}
+	switch {
+	case files["word/document.xml"] != nil:
+		info.Format = "docx"
+	case files["xl/workbook.xml"] != nil:
+		info.Format = "xlsx"
+		info.Sheets = &sheets
+	case files["ppt/presentation.xml"] != nil:
+		info.Format = "pptx"
+	default:
+		return nil
+	}
+
+	var core ooxmlCore
+	if err := readZipXML(files["docProps/core.xml"], &core); err != nil {
+		info.Err = err
+	}
+	info.Title = core.Title
+	info.Author = core.Creator
+	info.Subject = core.Subject
+	info.Created = core.Created
+	info.Modified = core.Modified
+	for key, value := range map[string]string{
+		"Keywords":       core.Keywords,
+		"Description":    core.Description,
+		"LastModifiedBy": core.LastModifiedBy,
+		"Revision":       core.Revision,
+		"Category":       core.Category,
+	} {
+		if value != "" {
+			info.Metadata[key] = value
+		}
+	}
+
+	var app ooxmlApp
+	if err := readZipXML(files["docProps/app.xml"], &app); err != nil && info.Err == nil {
+		info.Err = err
+	}
+	info.Creator = app.Application
+	info.Version = app.AppVersion
+	if app.Company != "" {
+		info.Metadata["Company"] = app.Company
+	}
+	if app.Words != nil {
+		info.Metadata["Words"] = strconv.Itoa(*app.Words)
+	}
+	if info.Format == "docx" {
+		info.Pages = app.Pages
+	}
+	if info.Format == "pptx" {
+		info.Slides = app.Slides
+	}
+	return info
+}
+
+// readZipXML decodes an XML part of a zip archive. Missing parts are ignored.
+func readZipXML(f *zip.File, v interface{}) error {
+	if f == nil {
+		return nil
+	}
+	rc, err := f.Open()
+	if err != nil {
+		return err
+	}
+	defer rc.Close()
+	return xml.NewDecoder(io.LimitReader(rc, maxObjectStream)).Decode(v)
+}
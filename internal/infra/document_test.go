@@ -0,0 +1,161 @@
+package infra_test
+
+import (
+	"archive/zip"
+	"bytes"
+	"compress/zlib"
+	"reflect"
+	"testing"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+// zipFiles builds a zip archive of the named files.
+func zipFiles(t *testing.T, files map[string]string) []byte {
+	var buf bytes.Buffer
+	w := zip.NewWriter(&buf)
+	for name, content := range files {
+		f, err := w.Create(name)
+		if err != nil {
+			t.Fatal(err)
+		}
+		f.Write([]byte(content))
+	}
+	if err := w.Close(); err != nil {
+		t.Fatal(err)
+	}
+	return buf.Bytes()
+}
+
+func TestExtractDocumentInfo(t *testing.T) {
+	pdf := []byte(`%PDF-1.7
+1 0 obj
+<< /Type /Catalog /Pages 2 0 R >>
+endobj
+2 0 obj
+<< /Type /Pages /Kids [3 0 R 4 0 R] /Count 2 >>
+endobj
+3 0 obj
+<< /Type /Page /Parent 2 0 R >>
+endobj
+4 0 obj
+<< /Type/Page /Parent 2 0 R >>
+endobj
+5 0 obj
+<< /Title (Annual \(draft\) report) /Author <FEFF004A006F0073006500E9> /Producer (Tommie\040PDF)
+/CreationDate (D:20240101120000Z) /Keywords (a\nb) >>
+endobj
+trailer
+<< /Root 1 0 R /Info 5 0 R >>
+%%EOF`)
+
+	var packed bytes.Buffer
+	zw := zlib.NewWriter(&packed)
+	zw.Write([]byte("8 0 9 24 << /Type /Page >> << /Title (Packed) /Producer (Writer) >>"))
+	zw.Close()
+	objStm := append([]byte("%PDF-1.5\n7 0 obj\n<< /Type /ObjStm /N 2 /First 9 /Filter /FlateDecode >>\nstream\n"), packed.Bytes()...)
+	objStm = append(objStm, "\nendstream\nendobj\ntrailer\n<< /Root 1 0 R /Info 9 0 R >>\n%%EOF"...)
+
+	const core = `<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties"
+		xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/">
+		<dc:title>Plan</dc:title><dc:creator>Ann</dc:creator><cp:keywords>q1</cp:keywords>
+		<dcterms:created>2024-01-01T00:00:00Z</dcterms:created></cp:coreProperties>`
+
+	tests := []struct {
+		name string
+		data []byte
+		want infra.DocumentInfo
+		err  bool
+	}{
+		{"pdf", pdf, infra.DocumentInfo{
+			Format:   "pdf",
+			Version:  "1.7",
+			Pages:    intPtr(2),
+			Title:    "Annual (draft) report",
+			Author:   "Josée",
+			Producer: "Tommie PDF",
+			Created:  "D:20240101120000Z",
+			Metadata: map[string]string{"Keywords": "a\nb"},
+		}, false},
+		{"pdf object stream", objStm, infra.DocumentInfo{
+			Format:   "pdf",
+			Version:  "1.5",
+			Pages:    intPtr(1),
+			Title:    "Packed",
+			Producer: "Writer",
+			Metadata: map[string]string{},
+		}, false},
+		{"encrypted pdf", []byte("%PDF-1.4\ntrailer\n<< /Encrypt 6 0 R >>"), infra.DocumentInfo{
+			Format:    "pdf",
+			Version:   "1.4",
+			Encrypted: true,
+			Metadata:  map[string]string{},
+		}, false},
+		{"docx", zipFiles(t, map[string]string{
+			"word/document.xml": "<w:document/>",
+			"docProps/core.xml": core,
+			"docProps/app.xml":  "<Properties><Application>Word</Application><AppVersion>16.0000</AppVersion><Pages>3</Pages><Words>120</Words><Company>Acme</Company></Properties>",
+		}), infra.DocumentInfo{
+			Format:   "docx",
+			Version:  "16.0000",
+			Pages:    intPtr(3),
+			Title:    "Plan",
+			Author:   "Ann",
+			Creator:  "Word",
+			Created:  "2024-01-01T00:00:00Z",
+			Metadata: map[string]string{"Keywords": "q1", "Words": "120", "Company": "Acme"},
+		}, false},
+		{"xlsx", zipFiles(t, map[string]string{
+			"xl/workbook.xml":          "<workbook/>",
+			"xl/worksheets/sheet1.xml": "<worksheet/>",
+			"xl/worksheets/sheet2.xml": "<worksheet/>",
+		}), infra.DocumentInfo{
+			Format:   "xlsx",
+			Sheets:   intPtr(2),
+			Metadata: map[string]string{},
+		}, false},
+		{"pptx", zipFiles(t, map[string]string{
+			"ppt/presentation.xml": "<presentation/>",
+			"docProps/app.xml":     "<Properties><Slides>5</Slides><Pages>9</Pages></Properties>",
+		}), infra.DocumentInfo{
+			Format:   "pptx",
+			Slides:   intPtr(5),
+			Metadata: map[string]string{},
+		}, false},
+		{"broken core properties", zipFiles(t, map[string]string{
+			"word/document.xml": "<w:document/>",
+			"docProps/core.xml": "<coreProperties",
+		}), infra.DocumentInfo{
+			Format:   "docx",
+			Metadata: map[string]string{},
+		}, true},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			info := infra.ExtractDocumentInfo(tt.data)
+			if info == nil {
+				t.Fatal("ExtractDocumentInfo = nil, want metadata")
+			}
+			if (info.Err != nil) != tt.err {
+				t.Errorf("Err = %v, want an error: %v", info.Err, tt.err)
+			}
+			got := *info
+			got.Err = nil
+			if !reflect.DeepEqual(got, tt.want) {
+				t.Errorf("ExtractDocumentInfo = %+v, want %+v", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestExtractDocumentInfoOtherContent(t *testing.T) {
+	for name, data := range map[string][]byte{
+		"text":        []byte("hello, world"),
+		"zip":         zipFiles(t, map[string]string{"readme.txt": "hello"}),
+		"corrupt zip": []byte("PK\x03\x04 not really a zip"),
+	} {
+		if info := infra.ExtractDocumentInfo(data); info != nil {
+			t.Errorf("%s: ExtractDocumentInfo = %+v, want nil", name, info)
+		}
+	}
+}
@@ -0,0 +1,133 @@
+package infra
+
+import (
+	"strconv"
+	"strings"
+)
+
+// MaxSPFLookups is the number of DNS-querying terms an SPF evaluation may
+// use (RFC 7208 section 4.6.4).
+const MaxSPFLookups = 10
+
+// SPFRecord is a parsed SPF policy (RFC 7208).
+type SPFRecord struct {
+	Terms    []SPFTerm
+	All      string   // Qualifier and mechanism of the final all, e.g. "-all"; empty without one
+	Redirect string   // Domain of a redirect= modifier
+	Includes []string // Domains of include: mechanisms
+	Lookups  int      // Terms of this record that query DNS; included records add their own
+}
+
+// SPFTerm is a mechanism or modifier of an SPF record.
+type SPFTerm struct {
+	Qualifier string // +, -, ~ or ?; empty for modifiers
+	Name      string // e.g. ip4, include, all, or a modifier such as redirect
+	Value     string
+	Modifier  bool
+}
+
+// ParseSPF parses the text of a record starting with v=spf1. It returns
+// false for other records.
+func ParseSPF(text string) (*SPFRecord, bool) {
+	fields := strings.Fields(text)
+	if len(fields) == 0 || !strings.EqualFold(fields[0], "v=spf1") {
+		return nil, false
+	}
+	record := &SPFRecord{}
+	for _, field := range fields[1:] {
+		term := SPFTerm{}
+		if name, value, ok := strings.Cut(field, "="); ok && !strings.ContainsAny(name, ":/") {
+			term.Name, term.Value, term.Modifier = strings.ToLower(name), value, true
+		} else {
+			term.Qualifier = "+"
+			if strings.ContainsAny(field[:1], "+-~?") {
+				term.Qualifier, field = field[:1], field[1:]
+			}
+			name, value, ok := strings.Cut(field, ":")
+			if !ok {
+				name, value, _ = strings.Cut(field, "/")
+				if value != "" {
+					value = "/" + value
+				}
+			}
+			term.Name, term.Value = strings.ToLower(name), value
+		}
+
+		switch term.Name {
+		case "include":
+			record.Includes = append(record.Includes, term.Value)
+			record.Lookups++
+		case "a", "mx", "ptr", "exists":
+			record.Lookups++
+		case "redirect":
+			record.Redirect = term.Value
+			record.Lookups++
+		case "all":
+			record.All = term.Qualifier + "all"
+		}
+		record.Terms = append(record.Terms, term)
+	}
+	return record, true
+}
+
+// DMARCRecord is a parsed DMARC policy (RFC 7489).
+type DMARCRecord struct {
+	Policy          string   // p: none, quarantine or reject
+	SubdomainPolicy string   // sp; defaults to the policy
+	Percent         int      // pct: share of failing mail the policy applies to
+	DKIMAlignment   string   // adkim: r (relaxed) or s (strict)
+	SPFAlignment    string   // aspf: r (relaxed) or s (strict)
+	Aggregate       []string // rua: where aggregate reports go
+	Forensic        []string // ruf: where failure reports go
+	Tags            map[string]string
+}
+
+// ParseDMARC parses the text of a record starting with v=DMARC1. It returns
+// false for other records.
+func ParseDMARC(text string) (*DMARCRecord, bool) {
+	tags := map[string]string{}
+	for i, part := range strings.Split(text, ";") {
+		name, value, _ := strings.Cut(strings.TrimSpace(part), "=")
+		name, value = strings.ToLower(strings.TrimSpace(name)), strings.TrimSpace(value)
+		if i == 0 && (name != "v" || value != "DMARC1") {
+			return nil, false
+		}
+		if name != "" {
+			tags[name] = value
+		}
+	}
+
+	record := &DMARCRecord{
+		Policy:          strings.ToLower(tags["p"]),
+		SubdomainPolicy: strings.ToLower(tags["sp"]),
+		Percent:         100,
+		DKIMAlignment:   "r",
+		SPFAlignment:    "r",
+		Aggregate:       splitURIs(tags["rua"]),
+		Forensic:        splitURIs(tags["ruf"]),
+		Tags:            tags,
+	}
+	if record.SubdomainPolicy == "" {
+		record.SubdomainPolicy = record.Policy
+	}
+	if n, err := strconv.Atoi(tags["pct"]); err == nil {
+		record.Percent = n
+	}
+	if v := strings.ToLower(tags["adkim"]); v != "" {
+		record.DKIMAlignment = v
+	}
+	if v := strings.ToLower(tags["aspf"]); v != "" {
+		record.SPFAlignment = v
+	}
+	return record, true
+}
+
+func splitURIs(value string) []string {
+	var uris []string
+	for _, uri := range strings.Split(value, ",") {
+		if uri = strings.TrimSpace(uri); uri != "" {
+			uris = append(uris, uri)
+		}
+	}
+	return uris
+}
@@ -0,0 +1,284 @@
+package infra
+
+import (
+	"bufio"
+	"bytes"
+	"encoding/xml"
+	"fmt"
+	"io"
+	"strings"
+	"time"
+	"unicode/utf8"
+)
+
+// MaxFeedItems is the number of feed items included in a preview.
+const MaxFeedItems = 100
+
+// Feed is a parsed RSS, RDF or Atom feed.
+type Feed struct {
+	Format      string // rss, rdf or atom
+	Version     string
+	Title       string
+	Link        string
+	Description string
+	Updated     string // RFC 3339 when parseable
+	Items       []FeedItem
+	TotalItems  int
+	Warnings    []string
+}
+
+// FeedItem is a single entry of a feed.
+type FeedItem struct {
+	ID        string
+	Title     string
+	Link      string
+	Published string // RFC 3339 when parseable
+}
+
+// rssDocument covers RSS 0.9x/2.0 and RSS 1.0 (RDF).
+type rssDocument struct {
+	XMLName xml.Name
+	Version string     `xml:"version,attr"`
+	Channel rssChannel `xml:"channel"`
+	Items   []rssItem  `xml:"item"` // RDF places items beside the channel
+}
+
+type rssChannel struct {
+	Title         string    `xml:"title"`
+	Link          string    `xml:"link"`
+	Description   string    `xml:"description"`
+	LastBuildDate string    `xml:"lastBuildDate"`
+	PubDate       string    `xml:"pubDate"`
+	DCDate        string    `xml:"http://purl.org/dc/elements/1.1/ date"`
+	Items         []rssItem `xml:"item"`
+}
+
+type rssItem struct {
+	Title   string `xml:"title"`
+	Link    string `xml:"link"`
+	GUID    string `xml:"guid"`
+	PubDate string `xml:"pubDate"`
+	DCDate  string `xml:"http://purl.org/dc/elements/1.1/ date"`
+	About   string `xml:"about,attr"`
+}
+
+type atomDocument struct {
+	Title    string      `xml:"title"`
+	Subtitle string      `xml:"subtitle"`
+	ID       string      `xml:"id"`
+	Updated  string      `xml:"updated"`
+	Links    []atomLink  `xml:"link"`
+	Entries  []atomEntry `xml:"entry"`
+}
+
+type atomLink struct {
+	Href string `xml:"href,attr"`
+	Rel  string `xml:"rel,attr"`
+}
+
+type atomEntry struct {
+	ID        string     `xml:"id"`
+	Title     string     `xml:"title"`
+	Links     []atomLink `xml:"link"`
+	Published string     `xml:"published"`
+	Updated   string     `xml:"updated"`
+}
+
+// feedDateLayouts are the date formats seen in RSS feeds, which nominally use RFC 822.
+var feedDateLayouts = []string{
+	time.RFC1123Z,
+	time.RFC1123,
+	"Mon, 2 Jan 2006 15:04:05 -0700",
+	"Mon, 2 Jan 2006 15:04:05 MST",
+	"2 Jan 2006 15:04:05 -0700",
+	time.RFC822Z,
+	time.RFC822,
+	time.RFC3339,
+	time.RFC3339Nano,
+	"2006-01-02T15:04:05",
+	"2006-01-02",
+}
+
+// ParseFeed parses an RSS, RDF or Atom document. It returns nil when the body
+// is not a feed.
+func ParseFeed(body []byte) *Feed {
+	root := xmlRoot(body)
+	switch root.Local {
+	case "rss", "RDF":
+		return parseRSS(body, root.Local)
+	case "feed":
+		if root.Space == "http://www.w3.org/2005/Atom" || root.Space == "" {
+			return parseAtom(body)
+		}
+	}
+	return nil
+}
+
+// xmlRoot returns the name of the document element.
+func xmlRoot(body []byte) xml.Name {
+	decoder := newXMLDecoder(body)
+	for {
+		tok, err := decoder.Token()
+		if err != nil {
+			return xml.Name{}
+		}
+		if start, ok := tok.(xml.StartElement); ok {
+			return start.Name
+		}
+	}
+}
+
+func newXMLDecoder(body []byte) *xml.Decoder {
+	decoder := xml.NewDecoder(bytes.NewReader(body))
+	decoder.Strict = false
+	decoder.CharsetReader = xmlCharsetReader
+	return decoder
+}
+
+// xmlCharsetReader decodes the single-byte charsets common in feeds and SOAP services.
+func xmlCharsetReader(charset string, input io.Reader) (io.Reader, error) {
+	switch strings.ToLower(charset) {
+	case "utf-8", "utf8", "us-ascii", "ascii":
+		return input, nil
+	case "iso-8859-1", "latin1", "windows-1252":
+		return latin1Reader{bufio.NewReader(input)}, nil
+	}
+	return nil, fmt.Errorf("unsupported charset %s", charset)
+}
+
+// latin1Reader transcodes ISO-8859-1 to UTF-8.
+type latin1Reader struct {
+	r *bufio.Reader
+}
+
+func (l latin1Reader) Read(p []byte) (int, error) {
+	n := 0
+	for n+utf8.UTFMax <= len(p) {
+		b, err := l.r.ReadByte()
+		if err != nil {
+			if n > 0 {
+				return n, nil
+			}
+			return 0, err
+		}
+		n += utf8.EncodeRune(p[n:], rune(b))
+	}
+	return n, nil
+}
+
+func parseRSS(body []byte, root string) *Feed {
+	feed := &Feed{Format: "rss"}
+	var doc rssDocument
+	if err := newXMLDecoder(body).Decode(&doc); err != nil {
+		feed.Warnings = append(feed.Warnings, fmt.Sprintf("Invalid XML: %v", err))
+		return feed
+	}
+
+	feed.Version = doc.Version
+	items := doc.Channel.Items
+	if root == "RDF" {
+		feed.Format = "rdf"
+		feed.Version = "1.0"
+		items = append(items, doc.Items...)
+	}
+	feed.Title = strings.TrimSpace(doc.Channel.Title)
+	feed.Link = strings.TrimSpace(doc.Channel.Link)
+	feed.Description = strings.TrimSpace(doc.Channel.Description)
+	feed.Updated = feed.normalizeDate(firstNonEmpty(doc.Channel.LastBuildDate, doc.Channel.PubDate, doc.Channel.DCDate), "channel")
+
+	if feed.Title == "" {
+		feed.Warnings = append(feed.Warnings, "Channel has no title")
+	}
+	feed.TotalItems = len(items)
+	for i, item := range items {
+		if i == MaxFeedItems {
+			break
+		}
+		entry := FeedItem{
+			ID:    strings.TrimSpace(firstNonEmpty(item.GUID, item.About)),
+			Title: strings.TrimSpace(item.Title),
+			Link:  strings.TrimSpace(item.Link),
+		}
+		entry.Published = feed.normalizeDate(firstNonEmpty(item.PubDate, item.DCDate), fmt.Sprintf("item %d", i+1))
+		feed.checkItem(i, entry)
+		feed.Items = append(feed.Items, entry)
+	}
+	return feed
+}
+
+func parseAtom(body []byte) *Feed {
+	feed := &Feed{Format: "atom", Version: "1.0"}
+	var doc atomDocument
+	if err := newXMLDecoder(body).Decode(&doc); err != nil {
+		feed.Warnings = append(feed.Warnings, fmt.Sprintf("Invalid XML: %v", err))
+		return feed
+	}
+
+	feed.Title = strings.TrimSpace(doc.Title)
+	feed.Link = atomHref(doc.Links)
+	feed.Description = strings.TrimSpace(doc.Subtitle)
+	feed.Updated = feed.normalizeDate(doc.Updated, "feed")
+
+	if feed.Title == "" {
+		feed.Warnings = append(feed.Warnings, "Feed has no title")
+	}
+	if doc.ID == "" {
+		feed.Warnings = append(feed.Warnings, "Feed has no id")
+	}
+	feed.TotalItems = len(doc.Entries)
+	for i, e := range doc.Entries {
+		if i == MaxFeedItems {
+			break
+		}
+		entry := FeedItem{
+			ID:    strings.TrimSpace(e.ID),
+			Title: strings.TrimSpace(e.Title),
+			Link:  atomHref(e.Links),
+		}
+		entry.Published = feed.normalizeDate(firstNonEmpty(e.Published, e.Updated), fmt.Sprintf("entry %d", i+1))
+		feed.checkItem(i, entry)
+		feed.Items = append(feed.Items, entry)
+	}
+	return feed
+}
+
+// atomHref returns the alternate link, which is the default relation.
+func atomHref(links []atomLink) string {
+	for _, l := range links {
+		if l.Rel == "" || l.Rel == "alternate" {
+			return strings.TrimSpace(l.Href)
+		}
+	}
+	return ""
+}
+
+func (f *Feed) checkItem(i int, item FeedItem) {
+	if item.Title == "" && item.Link == "" {
+		f.Warnings = append(f.Warnings, fmt.Sprintf("Item %d has neither title nor link", i+1))
+	}
+}
+
+// normalizeDate converts a feed date to RFC 3339, recording a warning when it
+// cannot be parsed.
+func (f *Feed) normalizeDate(value, where string) string {
+	value = strings.TrimSpace(value)
+	if value == "" {
+		return ""
+	}
+	for _, layout := range feedDateLayouts {
+		if t, err := time.Parse(layout, value); err == nil {
+			return t.Format(time.RFC3339)
+		}
+	}
+	f.Warnings = append(f.Warnings, fmt.Sprintf("Unparseable date in %s: %q", where, value))
+	return value
+}
+
+func firstNonEmpty(values ...string) string {
+	for _, v := range values {
+		if strings.TrimSpace(v) != "" {
+			return v
+		}
+	}
+	return ""
+}
@@ -0,0 +1,312 @@
+package infra
+
+import (
+	"encoding/json"
+	"errors"
+	"strconv"
+	"strings"
+)
+
+// IntrospectionQuery fetches everything needed to print a schema as SDL.
+const IntrospectionQuery = `query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types { ...FullType }
+    directives { name description isRepeatable locations args { ...InputValue } }
+  }
+}
+
+fragment FullType on __Type {
+  kind
+  name
+  description
+  fields(includeDeprecated: true) {
+    name
+    description
+    args { ...InputValue }
+    type { ...TypeRef }
+    isDeprecated
+    deprecationReason
+  }
+  inputFields { ...InputValue }
+  interfaces { ...TypeRef }
+  enumValues(includeDeprecated: true) { name description isDeprecated deprecationReason }
+  possibleTypes { ...TypeRef }
+}
+
+fragment InputValue on __InputValue {
+  name
+  description
+  type { ...TypeRef }
+  defaultValue
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType { kind name ofType { kind name ofType { kind name ofType { kind name ofType { kind name ofType { kind name ofType { kind name } } } } } } }
+}`
+
+// GraphQLSchema is a schema rebuilt from an introspection result.
+type GraphQLSchema struct {
+	SDL              string
+	QueryType        string
+	MutationType     string
+	SubscriptionType string
+	Types            int // Named types excluding introspection and built-in scalars
+}
+
+type introspection struct {
+	Schema struct {
+		QueryType        *namedRef       `json:"queryType"`
+		MutationType     *namedRef       `json:"mutationType"`
+		SubscriptionType *namedRef       `json:"subscriptionType"`
+		Types            []fullType      `json:"types"`
+		Directives       []directiveType `json:"directives"`
+	} `json:"__schema"`
+}
+
+type namedRef struct {
+	Name string `json:"name"`
+}
+
+type fullType struct {
+	Kind          string       `json:"kind"`
+	Name          string       `json:"name"`
+	Description   string       `json:"description"`
+	Fields        []fieldType  `json:"fields"`
+	InputFields   []inputValue `json:"inputFields"`
+	Interfaces    []typeRef    `json:"interfaces"`
+	EnumValues    []enumValue  `json:"enumValues"`
+	PossibleTypes []typeRef    `json:"possibleTypes"`
+}
+
+type fieldType struct {
+	Name              string       `json:"name"`
+	Description       string       `json:"description"`
+	Args              []inputValue `json:"args"`
+	Type              typeRef      `json:"type"`
+	IsDeprecated      bool         `json:"isDeprecated"`
+	DeprecationReason string       `json:"deprecationReason"`
+}
+
+type inputValue struct {
+	Name         string  `json:"name"`
+	Description  string  `json:"description"`
+	Type         typeRef `json:"type"`
+	DefaultValue *string `json:"defaultValue"`
+}
+
+type enumValue struct {
+	Name              string `json:"name"`
+	Description       string `json:"description"`
+	IsDeprecated      bool   `json:"isDeprecated"`
+	DeprecationReason string `json:"deprecationReason"`
+}
+
+type directiveType struct {
+	Name         string       `json:"name"`
+	Description  string       `json:"description"`
+	IsRepeatable bool         `json:"isRepeatable"`
+	Locations    []string     `json:"locations"`
+	Args         []inputValue `json:"args"`
+}
+
+type typeRef struct {
+	Kind   string   `json:"kind"`
+	Name   string   `json:"name"`
+	OfType *typeRef `json:"ofType"`
+}
+
+// String renders a type reference such as [String!]!.
+func (t typeRef) String() string {
+	switch {
+	case t.Kind == "NON_NULL" && t.OfType != nil:
+		return t.OfType.String() + "!"
+	case t.Kind == "LIST" && t.OfType != nil:
+		return "[" + t.OfType.String() + "]"
+	}
+	return t.Name
+}
+
+var builtinScalars = map[string]bool{"String": true, "Int": true, "Float": true, "Boolean": true, "ID": true}
+
+var builtinDirectives = map[string]bool{"include": true, "skip": true, "deprecated": true, "specifiedBy": true, "oneOf": true}
+
+// defaultDeprecationReason is implied by a bare @deprecated.
+const defaultDeprecationReason = "No longer supported"
+
+// ParseIntrospection prints the data of an introspection query response,
+// the object holding __schema, as SDL.
+func ParseIntrospection(data []byte) (*GraphQLSchema, error) {
+	var result introspection
+	if err := json.Unmarshal(data, &result); err != nil {
+		return nil, err
+	}
+	s := result.Schema
+	if s.QueryType == nil && len(s.Types) == 0 {
+		return nil, errors.New("response contains no __schema")
+	}
+
+	schema := &GraphQLSchema{}
+	var blocks []string
+
+	// The schema block is only needed when root types have non-default names
+	roots := []struct {
+		operation, fallback string
+		ref                 *namedRef
+		name                *string
+	}{
+		{"query", "Query", s.QueryType, &schema.QueryType},
+		{"mutation", "Mutation", s.MutationType, &schema.MutationType},
+		{"subscription", "Subscription", s.SubscriptionType, &schema.SubscriptionType},
+	}
+	var rootLines []string
+	custom := false
+	for _, root := range roots {
+		if root.ref == nil {
+			continue
+		}
+		*root.name = root.ref.Name
+		rootLines = append(rootLines, "  "+root.operation+": "+root.ref.Name)
+		custom = custom || root.ref.Name != root.fallback
+	}
+	if custom {
+		blocks = append(blocks, "schema {\n"+strings.Join(rootLines, "\n")+"\n}")
+	}
+
+	for _, d := range s.Directives {
+		if builtinDirectives[d.Name] {
+			continue
+		}
+		block := description(d.Description, "") + "directive @" + d.Name + printArgs(d.Args, "")
+		if d.IsRepeatable {
+			block += " repeatable"
+		}
+		blocks = append(blocks, block+" on "+strings.Join(d.Locations, " | "))
+	}
+
+	for _, t := range s.Types {
+		if strings.HasPrefix(t.Name, "__") || (t.Kind == "SCALAR" && builtinScalars[t.Name]) {
+			continue
+		}
+		schema.Types++
+		blocks = append(blocks, printType(t))
+	}
+
+	schema.SDL = strings.Join(blocks, "\n\n") + "\n"
+	return schema, nil
+}
+
+// printType renders a named type definition.
+func printType(t fullType) string {
+	head := description(t.Description, "")
+	switch t.Kind {
+	case "SCALAR":
+		return head + "scalar " + t.Name
+	case "UNION":
+		members := make([]string, len(t.PossibleTypes))
+		for i, p := range t.PossibleTypes {
+			members[i] = p.String()
+		}
+		return head + "union " + t.Name + " = " + strings.Join(members, " | ")
+	case "ENUM":
+		var lines []string
+		for _, v := range t.EnumValues {
+			lines = append(lines, description(v.Description, "  ")+"  "+v.Name+deprecated(v.IsDeprecated, v.DeprecationReason))
+		}
+		return head + "enum " + t.Name + printBlock(lines)
+	case "INPUT_OBJECT":
+		var lines []string
+		for _, f := range t.InputFields {
+			lines = append(lines, description(f.Description, "  ")+"  "+printInputValue(f))
+		}
+		return head + "input " + t.Name + printBlock(lines)
+	}
+
+	keyword := "type"
+	if t.Kind == "INTERFACE" {
+		keyword = "interface"
+	}
+	head += keyword + " " + t.Name
+	if len(t.Interfaces) > 0 {
+		names := make([]string, len(t.Interfaces))
+		for i, iface := range t.Interfaces {
+			names[i] = iface.String()
+		}
+		head += " implements " + strings.Join(names, " & ")
+	}
+	var lines []string
+	for _, f := range t.Fields {
+		line := "  " + f.Name + printArgs(f.Args, "  ") + ": " + f.Type.String() + deprecated(f.IsDeprecated, f.DeprecationReason)
+		lines = append(lines, description(f.Description, "  ")+line)
+	}
+	return head + printBlock(lines)
+}
+
+// printArgs renders an argument list, one per line when any is described.
+func printArgs(args []inputValue, indent string) string {
+	if len(args) == 0 {
+		return ""
+	}
+	described := false
+	for _, a := range args {
+		described = described || a.Description != ""
+	}
+	parts := make([]string, len(args))
+	if !described {
+		for i, a := range args {
+			parts[i] = printInputValue(a)
+		}
+		return "(" + strings.Join(parts, ", ") + ")"
+	}
+	for i, a := range args {
+		parts[i] = description(a.Description, indent+"  ") + indent + "  " + printInputValue(a)
+	}
+	return "(\n" + strings.Join(parts, "\n") + "\n" + indent + ")"
+}
+
+func printInputValue(v inputValue) string {
+	s := v.Name + ": " + v.Type.String()
+	if v.DefaultValue != nil {
+		s += " = " + *v.DefaultValue
+	}
+	return s
+}
+
+func printBlock(lines []string) string {
+	if len(lines) == 0 {
+		return ""
+	}
+	return " {\n" + strings.Join(lines, "\n") + "\n}"
+}
+
+func deprecated(isDeprecated bool, reason string) string {
+	if !isDeprecated {
+		return ""
+	}
+	if reason == "" || reason == defaultDeprecationReason {
+		return " @deprecated"
+	}
+	return " @deprecated(reason: " + strconv.Quote(reason) + ")"
+}
+
+// description renders a description as a block string on its own lines.
+func description(text, indent string) string {
+	if text == "" {
+		return ""
+	}
+	text = strings.ReplaceAll(text, `"""`, `\"""`)
+	if !strings.Contains(text, "\n") && len(text) < 70 {
+		return indent + `"""` + text + `"""` + "\n"
+	}
+	lines := strings.Split(text, "\n")
+	for i, line := range lines {
+		if line != "" {
+			lines[i] = indent + line
+		}
+	}
+	return indent + `"""` + "\n" + strings.Join(lines, "\n") + "\n" + indent + `"""` + "\n"
+}
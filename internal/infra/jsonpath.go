@@ -0,0 +1,301 @@
+package infra
+
+import (
+	"errors"
+	"fmt"
+	"sort"
+	"strconv"
+	"strings"
+)
+
+// JSONPath is a compiled JSONPath expression. It supports the root $, child
+// access as .name or ['name'], indexes (negative from the end), slices
+// [start:end], unions [0,2] or ['a','b'], wildcards .* and [*] and recursive
+// descent ..name. Filter expressions are not supported. A path without a
+// leading $ is taken relative to the root, so data.items[0] works too.
+type JSONPath struct {
+	source string
+	steps  []pathStep
+}
+
+type pathStep struct {
+	recursive bool     // ..: applies to the value and all its descendants
+	wildcard  bool     // * : every member or element
+	names     []string // Object members
+	indexes   []int    // Array elements; negative counts from the end
+	slice     *[2]*int // [start:end], either may be open
+}
+
+// CompileJSONPath parses a JSONPath expression.
+func CompileJSONPath(expr string) (*JSONPath, error) {
+	src := strings.TrimSpace(expr)
+	if src == "" {
+		return nil, errors.New("empty JSONPath")
+	}
+	p := &JSONPath{source: src}
+	rest := src
+	if strings.HasPrefix(rest, "$") {
+		rest = rest[1:]
+	} else if rest[0] != '.' && rest[0] != '[' {
+		rest = "." + rest
+	}
+
+	for rest != "" {
+		var step pathStep
+		switch {
+		case strings.HasPrefix(rest, ".."):
+			step.recursive = true
+			rest = rest[2:]
+			if strings.HasPrefix(rest, "[") {
+				var err error
+				if rest, err = parseBracket(rest, &step); err != nil {
+					return nil, fmt.Errorf("%s: %w", src, err)
+				}
+				p.steps = append(p.steps, step)
+				continue
+			}
+			name, remaining := cutName(rest)
+			if name == "" {
+				return nil, fmt.Errorf("%s: expected a name after ..", src)
+			}
+			step.setName(name)
+			rest = remaining
+		case strings.HasPrefix(rest, "."):
+			name, remaining := cutName(rest[1:])
+			if name == "" {
+				return nil, fmt.Errorf("%s: expected a name after .", src)
+			}
+			step.setName(name)
+			rest = remaining
+		case strings.HasPrefix(rest, "["):
+			var err error
+			if rest, err = parseBracket(rest, &step); err != nil {
+				return nil, fmt.Errorf("%s: %w", src, err)
+			}
+		default:
+			return nil, fmt.Errorf("%s: unexpected %q", src, rest[:1])
+		}
+		p.steps = append(p.steps, step)
+	}
+	return p, nil
+}
+
+// String returns the expression as written.
+func (p *JSONPath) String() string {
+	return p.source
+}
+
+func (s *pathStep) setName(name string) {
+	if name == "*" {
+		s.wildcard = true
+	} else {
+		s.names = []string{name}
+	}
+}
+
+// cutName splits a member name off the start of s; names end at . or [.
+func cutName(s string) (name, rest string) {
+	end := strings.IndexAny(s, ".[")
+	if end < 0 {
+		return s, ""
+	}
+	return s[:end], s[end:]
+}
+
+// parseBracket parses a [...] selector at the start of s.
+func parseBracket(s string, step *pathStep) (string, error) {
+	body, rest, err := cutBracket(s)
+	if err != nil {
+		return "", err
+	}
+	body = strings.TrimSpace(body)
+	switch {
+	case body == "*":
+		step.wildcard = true
+	case strings.HasPrefix(body, "?"):
+		return "", errors.New("filter expressions are not supported")
+	case strings.HasPrefix(body, "'") || strings.HasPrefix(body, `"`):
+		for _, part := range splitUnion(body) {
+			name, err := unquoteName(part)
+			if err != nil {
+				return "", err
+			}
+			step.names = append(step.names, name)
+		}
+	case strings.Contains(body, ":"):
+		startText, endText, _ := strings.Cut(body, ":")
+		endText, _, _ = strings.Cut(endText, ":")
+		var bounds [2]*int
+		for i, text := range []string{startText, endText} {
+			if text = strings.TrimSpace(text); text == "" {
+				continue
+			}
+			n, err := strconv.Atoi(text)
+			if err != nil {
+				return "", fmt.Errorf("invalid slice bound %q", text)
+			}
+			bounds[i] = &n
+		}
+		step.slice = &bounds
+	default:
+		for _, part := range splitUnion(body) {
+			n, err := strconv.Atoi(strings.TrimSpace(part))
+			if err != nil {
+				return "", fmt.Errorf("invalid index %q", part)
+			}
+			step.indexes = append(step.indexes, n)
+		}
+	}
+	return rest, nil
+}
+
+// cutBracket returns the contents of the bracket opening s and what follows
+// it, honoring quotes.
+func cutBracket(s string) (body, rest string, err error) {
+	var quote byte
+	for i := 1; i < len(s); i++ {
+		c := s[i]
+		switch {
+		case quote != 0 && c == '\\':
+			i++
+		case quote != 0 && c == quote:
+			quote = 0
+		case quote == 0 && (c == '\'' || c == '"'):
+			quote = c
+		case quote == 0 && c == ']':
+			return s[1:i], s[i+1:], nil
+		}
+	}
+	return "", "", errors.New("unclosed [")
+}
+
+// splitUnion splits the comma-separated parts of a bracket, honoring quotes.
+func splitUnion(body string) []string {
+	var parts []string
+	var quote byte
+	start := 0
+	for i := 0; i < len(body); i++ {
+		c := body[i]
+		switch {
+		case quote != 0 && c == '\\':
+			i++
+		case quote != 0 && c == quote:
+			quote = 0
+		case quote == 0 && (c == '\'' || c == '"'):
+			quote = c
+		case quote == 0 && c == ',':
+			parts = append(parts, strings.TrimSpace(body[start:i]))
+			start = i + 1
+		}
+	}
+	return append(parts, strings.TrimSpace(body[start:]))
+}
+
+func unquoteName(s string) (string, error) {
+	if len(s) < 2 || s[0] != s[len(s)-1] || (s[0] != '\'' && s[0] != '"') {
+		return "", fmt.Errorf("invalid member name %s", s)
+	}
+	var b strings.Builder
+	for i := 1; i < len(s)-1; i++ {
+		if s[i] == '\\' && i+1 < len(s)-1 {
+			i++
+		}
+		b.WriteByte(s[i])
+	}
+	return b.String(), nil
+}
+
+// Find returns the values the path selects in a decoded JSON document, in
+// document order with object members sorted by name.
+func (p *JSONPath) Find(root interface{}) []interface{} {
+	current := []interface{}{root}
+	for _, step := range p.steps {
+		var next []interface{}
+		for _, value := range current {
+			if step.recursive {
+				for _, v := range descendants(value) {
+					next = step.apply(v, next)
+				}
+				continue
+			}
+			next = step.apply(value, next)
+		}
+		current = next
+	}
+	return current
+}
+
+// apply appends the children of value the step selects to out.
+func (s pathStep) apply(value interface{}, out []interface{}) []interface{} {
+	switch v := value.(type) {
+	case map[string]interface{}:
+		if s.wildcard {
+			for _, key := range sortedMembers(v) {
+				out = append(out, v[key])
+			}
+		}
+		for _, name := range s.names {
+			if child, ok := v[name]; ok {
+				out = append(out, child)
+			}
+		}
+	case []interface{}:
+		switch {
+		case s.wildcard:
+			out = append(out, v...)
+		case s.slice != nil:
+			start, end := 0, len(v)
+			if b := s.slice[0]; b != nil {
+				start = clampIndex(*b, len(v))
+			}
+			if b := s.slice[1]; b != nil {
+				end = clampIndex(*b, len(v))
+			}
+			for i := start; i < end; i++ {
+				out = append(out, v[i])
+			}
+		default:
+			for _, i := range s.indexes {
+				if i < 0 {
+					i += len(v)
+				}
+				if i >= 0 && i < len(v) {
+					out = append(out, v[i])
+				}
+			}
+		}
+	}
+	return out
+}
+
+func clampIndex(i, length int) int {
+	if i < 0 {
+		i += length
+	}
+	return min(max(i, 0), length)
+}
+
+// descendants returns value and every value nested in it, depth first.
+func descendants(value interface{}) []interface{} {
+	out := []interface{}{value}
+	switch v := value.(type) {
+	case map[string]interface{}:
+		for _, key := range sortedMembers(v) {
+			out = append(out, descendants(v[key])...)
+		}
+	case []interface{}:
+		for _, item := range v {
+			out = append(out, descendants(item)...)
+		}
+	}
+	return out
+}
+
+func sortedMembers(m map[string]interface{}) []string {
+	keys := make([]string, 0, len(m))
+	for key := range m {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys)
+	return keys
+}
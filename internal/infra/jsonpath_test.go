@@ -0,0 +1,99 @@
+package infra_test
+
+import (
+	"encoding/json"
+	"testing"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+const jsonPathDocument = `{
+	"store": {
+		"book": [
+			{"title": "A", "price": 8},
+			{"title": "B", "price": 12},
+			{"title": "C", "price": 20}
+		],
+		"bicycle": {"price": 19}
+	},
+	"x.y": 1
+}`
+
+func TestJSONPath(t *testing.T) {
+	var document interface{}
+	if err := json.Unmarshal([]byte(jsonPathDocument), &document); err != nil {
+		t.Fatal(err)
+	}
+
+	tests := []struct {
+		expr string
+		want string // JSON of the values found
+	}{
+		{"$", jsonPathCompact(t, jsonPathDocument)},
+		{"$.store.book[0].title", `["A"]`},
+		{"store.book[-1].title", `["C"]`},
+		{"$['store'][\"bicycle\"].price", `[19]`},
+		{"$['x.y']", `[1]`},
+		{"$.store.book[0,2].title", `["A","C"]`},
+		{"$.store.book[1:].title", `["B","C"]`},
+		{"$.store.book[:-1].price", `[8,12]`},
+		{"$.store.book[-10:10].title", `["A","B","C"]`},
+		{"$.store.book[*].price", `[8,12,20]`},
+		{"$.store.bicycle.*", `[19]`},
+		{"$..price", `[19,8,12,20]`},
+		{"$..[0].title", `["A"]`},
+		{"$.store.book[5]", `[]`},
+		{"$.missing.title", `[]`},
+		{"$.store.book.title", `[]`},
+	}
+	for _, tt := range tests {
+		t.Run(tt.expr, func(t *testing.T) {
+			path, err := infra.CompileJSONPath(tt.expr)
+			if err != nil {
+				t.Fatal(err)
+			}
+			found := path.Find(document)
+			if found == nil {
+				found = []interface{}{}
+			}
+			got, _ := json.Marshal(found)
+			if string(got) != tt.want {
+				t.Errorf("Find = %s, want %s", got, tt.want)
+			}
+			if path.String() != tt.expr {
+				t.Errorf("String() = %q, want %q", path.String(), tt.expr)
+			}
+		})
+	}
+}
+
+func TestJSONPathErrors(t *testing.T) {
+	for _, expr := range []string{
+		"",
+		"  ",
+		"$.",
+		"$..",
+		"$x",
+		"$.store[",
+		"$['store]",
+		"$[a]",
+		"$[1:x]",
+		"$[?(@.price > 10)]",
+	} {
+		t.Run(expr, func(t *testing.T) {
+			if _, err := infra.CompileJSONPath(expr); err == nil {
+				t.Errorf("CompileJSONPath(%q) succeeded, want an error", expr)
+			}
+		})
+	}
+}
+
+// jsonPathCompact returns a JSON document as a one-element result.
+func jsonPathCompact(t *testing.T, document string) string {
+	var value interface{}
+	if err := json.Unmarshal([]byte(document), &value); err != nil {
+		t.Fatal(err)
+	}
+	data, _ := json.Marshal([]interface{}{value})
+	return string(data)
+}
@@ -0,0 +1,186 @@
+package infra
+
+import (
+	"bytes"
+	"crypto"
+	"crypto/ecdsa"
+	"crypto/ed25519"
+	"crypto/hmac"
+	"crypto/rsa"
+	"crypto/x509"
+	"encoding/base64"
+	"encoding/json"
+	"encoding/pem"
+	"errors"
+	"fmt"
+	"math/big"
+	"regexp"
+	"strings"
+
+	// Registers the SHA-2 hashes used by crypto.Hash.New
+	_ "crypto/sha256"
+	_ "crypto/sha512"
+)
+
+// jwtPattern matches a compact JWS whose header is a JSON object, which
+// base64url-encodes to a leading "eyJ".
+var jwtPattern = regexp.MustCompile(`^eyJ[A-Za-z0-9_-]*\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]*$`)
+
+// JWT is a decoded JSON Web Token (RFC 7519) in compact JWS form.
+type JWT struct {
+	Header       map[string]interface{}
+	Claims       map[string]interface{}
+	Algorithm    string // alg header, e.g. HS256
+	SigningInput []byte // Encoded header and payload, as signed
+	Signature    []byte
+}
+
+// LooksLikeJWT reports whether s has the shape of a compact JWS.
+func LooksLikeJWT(s string) bool {
+	return len(s) < 16<<10 && jwtPattern.MatchString(s)
+}
+
+// ParseJWT decodes the header and claims of a token without verifying it.
+func ParseJWT(token string) (*JWT, error) {
+	parts := strings.Split(strings.TrimSpace(token), ".")
+	if len(parts) == 5 {
+		return nil, errors.New("token is encrypted (JWE) and cannot be decoded")
+	}
+	if len(parts) != 3 {
+		return nil, fmt.Errorf("token has %d parts, want 3", len(parts))
+	}
+
+	t := &JWT{SigningInput: []byte(parts[0] + "." + parts[1])}
+	if err := decodeJWTPart(parts[0], &t.Header); err != nil {
+		return nil, fmt.Errorf("invalid header: %w", err)
+	}
+	if err := decodeJWTPart(parts[1], &t.Claims); err != nil {
+		return nil, fmt.Errorf("invalid claims: %w", err)
+	}
+	signature, err := base64.RawURLEncoding.DecodeString(parts[2])
+	if err != nil {
+		return nil, fmt.Errorf("invalid signature encoding: %w", err)
+	}
+	t.Signature = signature
+	t.Algorithm, _ = t.Header["alg"].(string)
+	return t, nil
+}
+
+func decodeJWTPart(part string, v interface{}) error {
+	// Some issuers pad despite RFC 7515
+	data, err := base64.RawURLEncoding.DecodeString(strings.TrimRight(part, "="))
+	if err != nil {
+		return err
+	}
+	decoder := json.NewDecoder(bytes.NewReader(data))
+	decoder.UseNumber()
+	return decoder.Decode(v)
+}
+
+// Verify checks the signature of a token with key: the secret for HMAC
+// algorithms, or a PEM public key or certificate for RSA, ECDSA and EdDSA.
+func (t *JWT) Verify(key []byte) error {
+	alg := t.Algorithm
+	if strings.EqualFold(alg, "none") || alg == "" {
+		return errors.New("token is unsigned (alg none)")
+	}
+
+	var hash crypto.Hash
+	switch {
+	case strings.HasSuffix(alg, "256"):
+		hash = crypto.SHA256
+	case strings.HasSuffix(alg, "384"):
+		hash = crypto.SHA384
+	case strings.HasSuffix(alg, "512"):
+		hash = crypto.SHA512
+	}
+
+	switch {
+	case strings.HasPrefix(alg, "HS") && hash != 0:
+		mac := hmac.New(hash.New, key)
+		mac.Write(t.SigningInput)
+		if !hmac.Equal(mac.Sum(nil), t.Signature) {
+			return errors.New("signature does not match the secret")
+		}
+		return nil
+	case alg == "EdDSA":
+		public, err := parsePublicKey(key)
+		if err != nil {
+			return err
+		}
+		edKey, ok := public.(ed25519.PublicKey)
+		if !ok {
+			return fmt.Errorf("EdDSA needs an Ed25519 key, got %T", public)
+		}
+		if !ed25519.Verify(edKey, t.SigningInput, t.Signature) {
+			return errors.New("signature does not match the key")
+		}
+		return nil
+	case hash == 0:
+		return fmt.Errorf("unsupported algorithm %s", alg)
+	}
+
+	public, err := parsePublicKey(key)
+	if err != nil {
+		return err
+	}
+	digest := hash.New()
+	digest.Write(t.SigningInput)
+	sum := digest.Sum(nil)
+
+	switch alg[:2] {
+	case "RS", "PS":
+		rsaKey, ok := public.(*rsa.PublicKey)
+		if !ok {
+			return fmt.Errorf("%s needs an RSA key, got %T", alg, public)
+		}
+		if alg[:2] == "PS" {
+			err = rsa.VerifyPSS(rsaKey, hash, sum, t.Signature, &rsa.PSSOptions{SaltLength: rsa.PSSSaltLengthEqualsHash})
+		} else {
+			err = rsa.VerifyPKCS1v15(rsaKey, hash, sum, t.Signature)
+		}
+		if err != nil {
+			return errors.New("signature does not match the key")
+		}
+		return nil
+	case "ES":
+		ecKey, ok := public.(*ecdsa.PublicKey)
+		if !ok {
+			return fmt.Errorf("%s needs an ECDSA key, got %T", alg, public)
+		}
+		// JWS signatures are r and s concatenated, not ASN.1 (RFC 7518, section 3.4)
+		size := (ecKey.Curve.Params().BitSize + 7) / 8
+		if len(t.Signature) != 2*size {
+			return fmt.Errorf("signature is %d bytes, want %d", len(t.Signature), 2*size)
+		}
+		r := new(big.Int).SetBytes(t.Signature[:size])
+		s := new(big.Int).SetBytes(t.Signature[size:])
+		if !ecdsa.Verify(ecKey, sum, r, s) {
+			return errors.New("signature does not match the key")
+		}
+		return nil
+	}
+	return fmt.Errorf("unsupported algorithm %s", alg)
+}
+
+// parsePublicKey reads a PEM public key, PKCS #1 RSA public key or certificate.
+func parsePublicKey(key []byte) (crypto.PublicKey, error) {
+	block, _ := pem.Decode(key)
+	if block == nil {
+		return nil, errors.New("key is not PEM encoded")
+	}
+	switch block.Type {
+	case "PUBLIC KEY":
+		return x509.ParsePKIXPublicKey(block.Bytes)
+	case "RSA PUBLIC KEY":
+		return x509.ParsePKCS1PublicKey(block.Bytes)
+	case "CERTIFICATE":
+		cert, err := x509.ParseCertificate(block.Bytes)
+		if err != nil {
+			return nil, err
+		}
+		return cert.PublicKey, nil
+	default:
+		return nil, fmt.Errorf("unsupported PEM block %q; use a public key or certificate", block.Type)
+	}
+}
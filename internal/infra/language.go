@@ -0,0 +1,225 @@
+package infra
+
+import (
+	"bytes"
+	"html"
+	"regexp"
+	"strings"
+	"unicode"
+	"unicode/utf8"
+)
+
+const (
+	// maxLanguageSample is the number of text bytes examined for language detection.
+	maxLanguageSample = 64 << 10
+	// minLanguageWords is the fewest words needed for a stopword-based guess.
+	minLanguageWords = 20
+)
+
+// stopwords are frequent function words of languages written in Latin script.
+var stopwords = map[string][]string{
+	"en": {"the", "and", "of", "to", "in", "is", "that", "for", "it", "with", "as", "was", "on", "are", "be", "this", "by", "you", "not", "or", "have", "from"},
+	"de": {"der", "die", "und", "das", "ist", "nicht", "ich", "zu", "den", "mit", "sich", "des", "auf", "für", "ein", "eine", "dem", "auch", "es", "von", "wird"},
+	"fr": {"le", "la", "les", "et", "des", "est", "un", "une", "du", "que", "pour", "dans", "en", "pas", "qui", "sur", "au", "avec", "ce", "il", "sont"},
+	"es": {"el", "la", "de", "que", "y", "en", "los", "del", "se", "las", "por", "un", "para", "con", "no", "una", "su", "al", "es", "lo", "como"},
+	"it": {"il", "di", "che", "la", "e", "per", "un", "in", "non", "una", "sono", "del", "della", "con", "si", "le", "da", "al", "gli", "è"},
+	"nl": {"de", "het", "een", "en", "van", "is", "dat", "op", "te", "in", "niet", "zijn", "voor", "met", "die", "ook", "er", "aan", "om", "wordt"},
+	"pt": {"o", "a", "de", "que", "e", "do", "da", "em", "um", "para", "é", "com", "não", "uma", "os", "no", "se", "na", "por", "mais", "dos", "as"},
+	"sv": {"och", "att", "det", "som", "en", "på", "är", "av", "för", "med", "till", "den", "har", "de", "inte", "om", "ett", "jag", "var"},
+	"da": {"og", "i", "at", "det", "er", "en", "til", "på", "som", "de", "med", "for", "af", "ikke", "den", "har", "et", "der", "jeg"},
+	"pl": {"i", "w", "na", "nie", "z", "się", "do", "że", "to", "jest", "o", "jak", "po", "ale", "co", "od", "za", "tak", "przez"},
+}
+
+// scriptLanguages maps writing systems used by a single main language.
+var scriptLanguages = []struct {
+	table *unicode.RangeTable
+	lang  string
+}{
+	{unicode.Greek, "el"},
+	{unicode.Arabic, "ar"},
+	{unicode.Hebrew, "he"},
+	{unicode.Hangul, "ko"},
+	{unicode.Thai, "th"},
+	{unicode.Devanagari, "hi"},
+}
+
+var (
+	nonTextPattern = regexp.MustCompile(`(?is)<(script|style|noscript)\b.*?</(script|style|noscript)>|<!--.*?-->`)
+	tagPattern     = regexp.MustCompile(`(?s)<[^>]*>`)
+	htmlLangRegexp = regexp.MustCompile(`(?is)<html\b[^>]*\blang\s*=\s*["']?([A-Za-z-]+)`)
+	metaCharset    = regexp.MustCompile(`(?is)<meta\b[^>]*charset\s*=\s*["']?([\w-]+)`)
+	xmlEncoding    = regexp.MustCompile(`^<\?xml[^>]*encoding\s*=\s*["']([\w-]+)["']`)
+)
+
+// LanguageGuess is the detected natural language of a text.
+type LanguageGuess struct {
+	Language   string  // ISO 639-1 code
+	Confidence float64 // 0 to 1
+}
+
+// DetectLanguage guesses the natural language of a text or HTML body. It
+// returns nil when there is too little text to decide.
+func DetectLanguage(body string, isHTML bool) *LanguageGuess {
+	if len(body) > maxLanguageSample {
+		body = body[:maxLanguageSample]
+	}
+	if isHTML {
+		body = nonTextPattern.ReplaceAllString(body, " ")
+		body = html.UnescapeString(tagPattern.ReplaceAllString(body, " "))
+	}
+
+	if guess := detectByScript(body); guess != nil {
+		return guess
+	}
+	return detectByStopwords(body)
+}
+
+// HTMLLang returns the lang attribute of the html element.
+func HTMLLang(body string) string {
+	if m := htmlLangRegexp.FindStringSubmatch(body); m != nil {
+		return m[1]
+	}
+	return ""
+}
+
+// detectByScript recognizes languages by their writing system.
+func detectByScript(text string) *LanguageGuess {
+	var letters, latin, cyrillic, han, kana int
+	counts := make([]int, len(scriptLanguages))
+	ukrainian := false
+	for _, r := range text {
+		if !unicode.IsLetter(r) {
+			continue
+		}
+		letters++
+		switch {
+		case unicode.Is(unicode.Latin, r):
+			latin++
+		case unicode.Is(unicode.Cyrillic, r):
+			cyrillic++
+			ukrainian = ukrainian || strings.ContainsRune("іїєґІЇЄҐ", r)
+		case unicode.Is(unicode.Hiragana, r), unicode.Is(unicode.Katakana, r):
+			kana++
+		case unicode.Is(unicode.Han, r):
+			han++
+		default:
+			for i, s := range scriptLanguages {
+				if unicode.Is(s.table, r) {
+					counts[i]++
+					break
+				}
+			}
+		}
+	}
+	if letters < minLanguageWords || latin*2 > letters {
+		return nil
+	}
+
+	share := func(n int) float64 { return float64(n) / float64(letters) }
+	switch {
+	case kana > 0 && kana+han > letters/2:
+		return &LanguageGuess{Language: "ja", Confidence: share(kana + han)}
+	case han > letters/2:
+		return &LanguageGuess{Language: "zh", Confidence: share(han)}
+	case cyrillic > letters/2:
+		lang := "ru"
+		if ukrainian {
+			lang = "uk"
+		}
+		return &LanguageGuess{Language: lang, Confidence: share(cyrillic)}
+	}
+	for i, s := range scriptLanguages {
+		if counts[i] > letters/2 {
+			return &LanguageGuess{Language: s.lang, Confidence: share(counts[i])}
+		}
+	}
+	return nil
+}
+
+// detectByStopwords scores Latin-script languages by their most common words.
+func detectByStopwords(text string) *LanguageGuess {
+	words := strings.FieldsFunc(strings.ToLower(text), func(r rune) bool {
+		return !unicode.IsLetter(r)
+	})
+	if len(words) < minLanguageWords {
+		return nil
+	}
+
+	scores := make(map[string]int, len(stopwords))
+	total := 0
+	for lang, list := range stopwords {
+		set := make(map[string]bool, len(list))
+		for _, w := range list {
+			set[w] = true
+		}
+		for _, w := range words {
+			if set[w] {
+				scores[lang]++
+				total++
+			}
+		}
+	}
+
+	best, bestScore := "", 0
+	for lang, score := range scores {
+		if score > bestScore || (score == bestScore && lang < best) {
+			best, bestScore = lang, score
+		}
+	}
+	// Require stopwords to make up a meaningful share of the text
+	if bestScore*20 < len(words) {
+		return nil
+	}
+	return &LanguageGuess{Language: best, Confidence: float64(bestScore) / float64(total)}
+}
+
+// CharsetInfo describes the declared and observed character encoding of a body.
+type CharsetInfo struct {
+	Header    string // charset parameter of Content-Type
+	Document  string // meta charset or XML declaration
+	BOM       string // utf-8, utf-16le or utf-16be
+	ValidUTF8 bool
+	ASCII     bool // Only 7-bit bytes, compatible with any ASCII-based charset
+}
+
+// InspectCharset compares a body's declared encodings with its bytes.
+func InspectCharset(body []byte, contentType string) CharsetInfo {
+	info := CharsetInfo{
+		ValidUTF8: utf8.Valid(body),
+		ASCII:     isASCII(body),
+	}
+	for _, param := range strings.Split(contentType, ";")[1:] {
+		key, value, ok := strings.Cut(strings.TrimSpace(param), "=")
+		if ok && strings.EqualFold(key, "charset") {
+			info.Header = strings.ToLower(strings.Trim(value, `"`))
+		}
+	}
+
+	head := body
+	if len(head) > 4096 {
+		head = head[:4096]
+	}
+	switch {
+	case bytes.HasPrefix(head, []byte{0xef, 0xbb, 0xbf}):
+		info.BOM = "utf-8"
+	case bytes.HasPrefix(head, []byte{0xff, 0xfe}):
+		info.BOM = "utf-16le"
+	case bytes.HasPrefix(head, []byte{0xfe, 0xff}):
+		info.BOM = "utf-16be"
+	}
+	if m := xmlEncoding.FindSubmatch(bytes.TrimPrefix(head, []byte{0xef, 0xbb, 0xbf})); m != nil {
+		info.Document = strings.ToLower(string(m[1]))
+	} else if m := metaCharset.FindSubmatch(head); m != nil {
+		info.Document = strings.ToLower(string(m[1]))
+	}
+	return info
+}
+
+func isASCII(data []byte) bool {
+	for _, b := range data {
+		if b >= 0x80 {
+			return false
+		}
+	}
+	return true
+}
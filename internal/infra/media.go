@@ -0,0 +1,662 @@
+package infra
+
+import (
+	"bytes"
+	"encoding/binary"
+	"fmt"
+	"math"
+)
+
+// MediaMetadata describes an audio or video container read from its headers.
+type MediaMetadata struct {
+	Format   string   // mp4, mov, m4a, mp3, wav, flac, ogg, webm or matroska
+	Duration *float64 // Seconds
+	Bitrate  *int     // Average bits per second over the whole file
+	Tracks   []MediaTrack
+	Err      error
+}
+
+// MediaTrack is a single audio or video stream.
+type MediaTrack struct {
+	Type       string // audio or video
+	Codec      string
+	Width      *int
+	Height     *int
+	SampleRate *int
+	Channels   *int
+}
+
+// ProbeMedia reads container metadata from an audio or video body identified
+// by its magic bytes. It returns nil for other content.
+func ProbeMedia(data []byte) *MediaMetadata {
+	var m *MediaMetadata
+	switch {
+	case len(data) >= 12 && string(data[4:8]) == "ftyp" && !imageBrands[string(data[8:12])]:
+		m = probeMP4(data)
+	case len(data) >= 12 && string(data[:4]) == "RIFF" && string(data[8:12]) == "WAVE":
+		m = probeWAV(data)
+	case bytes.HasPrefix(data, []byte("fLaC")):
+		m = probeFLAC(data)
+	case bytes.HasPrefix(data, []byte("OggS")):
+		m = probeOgg(data)
+	case bytes.HasPrefix(data, []byte{0x1a, 0x45, 0xdf, 0xa3}):
+		m = probeMatroska(data)
+	case bytes.HasPrefix(data, []byte("ID3")) || mp3FrameAt(data, 0):
+		m = probeMP3(data)
+	default:
+		return nil
+	}
+
+	if m.Bitrate == nil && m.Duration != nil && *m.Duration > 0 {
+		bitrate := int(float64(len(data)) * 8 / *m.Duration)
+		m.Bitrate = &bitrate
+	}
+	return m
+}
+
+// imageBrands are ISO base media brands used by still images rather than media.
+var imageBrands = map[string]bool{
+	"heic": true, "heix": true, "heif": true, "mif1": true, "msf1": true, "avif": true, "avis": true,
+}
+
+func intPtr(v int) *int {
+	return &v
+}
+
+func seconds(v float64) *float64 {
+	if v <= 0 || math.IsInf(v, 0) || math.IsNaN(v) {
+		return nil
+	}
+	return &v
+}
+
+// --- ISO base media (MP4, MOV, M4A) ---
+
+// mp4Box is a box header within its parent's payload.
+type mp4Box struct {
+	kind    string
+	payload []byte
+}
+
+// mp4Boxes splits a payload into its child boxes.
+func mp4Boxes(data []byte) []mp4Box {
+	var boxes []mp4Box
+	for len(data) >= 8 {
+		size := uint64(binary.BigEndian.Uint32(data))
+		kind := string(data[4:8])
+		header := uint64(8)
+		switch size {
+		case 0:
+			size = uint64(len(data))
+		case 1:
+			if len(data) < 16 {
+				return boxes
+			}
+			size = binary.BigEndian.Uint64(data[8:])
+			header = 16
+		}
+		if size < header || size > uint64(len(data)) {
+			// Truncated box: keep what is available
+			size = uint64(len(data))
+		}
+		boxes = append(boxes, mp4Box{kind: kind, payload: data[header:size]})
+		data = data[size:]
+	}
+	return boxes
+}
+
+func mp4Find(data []byte, path ...string) []byte {
+	for _, kind := range path {
+		found := false
+		for _, box := range mp4Boxes(data) {
+			if box.kind == kind {
+				data, found = box.payload, true
+				break
+			}
+		}
+		if !found {
+			return nil
+		}
+	}
+	return data
+}
+
+// mp4Duration reads the timescale and duration of an mvhd or mdhd payload.
+func mp4Duration(payload []byte) float64 {
+	if len(payload) < 4 {
+		return 0
+	}
+	var timescale uint32
+	var duration uint64
+	if payload[0] == 1 {
+		if len(payload) < 32 {
+			return 0
+		}
+		timescale = binary.BigEndian.Uint32(payload[20:])
+		duration = binary.BigEndian.Uint64(payload[24:])
+	} else {
+		if len(payload) < 20 {
+			return 0
+		}
+		timescale = binary.BigEndian.Uint32(payload[12:])
+		duration = uint64(binary.BigEndian.Uint32(payload[16:]))
+	}
+	if timescale == 0 || duration == math.MaxUint32 || duration == math.MaxUint64 {
+		return 0
+	}
+	return float64(duration) / float64(timescale)
+}
+
+func probeMP4(data []byte) *MediaMetadata {
+	m := &MediaMetadata{Format: "mp4"}
+	switch string(data[8:12]) {
+	case "qt  ":
+		m.Format = "mov"
+	case "M4A ", "M4B ":
+		m.Format = "m4a"
+	}
+
+	moov := mp4Find(data, "moov")
+	if moov == nil {
+		m.Err = fmt.Errorf("no moov box in the available data")
+		return m
+	}
+	if mvhd := mp4Find(moov, "mvhd"); mvhd != nil {
+		m.Duration = seconds(mp4Duration(mvhd))
+	}
+
+	for _, box := range mp4Boxes(moov) {
+		if box.kind != "trak" {
+			continue
+		}
+		track := MediaTrack{}
+		switch hdlr := mp4Find(box.payload, "mdia", "hdlr"); {
+		case len(hdlr) >= 12 && string(hdlr[8:12]) == "vide":
+			track.Type = "video"
+		case len(hdlr) >= 12 && string(hdlr[8:12]) == "soun":
+			track.Type = "audio"
+		default:
+			continue
+		}
+
+		stsd := mp4Find(box.payload, "mdia", "minf", "stbl", "stsd")
+		if len(stsd) >= 8 {
+			entries := mp4Boxes(stsd[8:])
+			if len(entries) > 0 {
+				entry := entries[0]
+				track.Codec = entry.kind
+				p := entry.payload
+				if track.Type == "video" && len(p) >= 28 {
+					track.Width = intPtr(int(binary.BigEndian.Uint16(p[24:])))
+					track.Height = intPtr(int(binary.BigEndian.Uint16(p[26:])))
+				}
+				if track.Type == "audio" && len(p) >= 28 {
+					track.Channels = intPtr(int(binary.BigEndian.Uint16(p[16:])))
+					track.SampleRate = intPtr(int(binary.BigEndian.Uint32(p[24:]) >> 16))
+				}
+			}
+		}
+		m.Tracks = append(m.Tracks, track)
+	}
+	return m
+}
+
+// --- MPEG audio (MP3) ---
+
+var mp3Bitrates = map[[2]int][]int{
+	{1, 1}: {0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448},
+	{1, 2}: {0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384},
+	{1, 3}: {0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320},
+	{2, 1}: {0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256},
+	{2, 2}: {0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160},
+	{2, 3}: {0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160},
+}
+
+var mp3SampleRates = map[int][]int{
+	1:  {44100, 48000, 32000},
+	2:  {22050, 24000, 16000},
+	25: {11025, 12000, 8000},
+}
+
+// mp3Frame is a decoded MPEG audio frame header.
+type mp3Frame struct {
+	version    int // 1, 2 or 25 for MPEG 2.5
+	layer      int
+	bitrate    int // Bits per second
+	sampleRate int
+	mono       bool
+}
+
+func mp3FrameAt(data []byte, i int) bool {
+	_, ok := parseMP3Frame(data, i)
+	return ok
+}
+
+func parseMP3Frame(data []byte, i int) (mp3Frame, bool) {
+	if i+4 > len(data) || data[i] != 0xff || data[i+1]&0xe0 != 0xe0 {
+		return mp3Frame{}, false
+	}
+	var f mp3Frame
+	switch (data[i+1] >> 3) & 3 {
+	case 3:
+		f.version = 1
+	case 2:
+		f.version = 2
+	case 0:
+		f.version = 25
+	default:
+		return f, false
+	}
+	switch (data[i+1] >> 1) & 3 {
+	case 3:
+		f.layer = 1
+	case 2:
+		f.layer = 2
+	case 1:
+		f.layer = 3
+	default:
+		return f, false
+	}
+
+	bitrateIndex := int(data[i+2] >> 4)
+	rateIndex := int(data[i+2]>>2) & 3
+	if bitrateIndex == 0 || bitrateIndex == 15 || rateIndex == 3 {
+		return f, false
+	}
+	table := f.version
+	if table == 25 {
+		table = 2
+	}
+	f.bitrate = mp3Bitrates[[2]int{table, f.layer}][bitrateIndex] * 1000
+	f.sampleRate = mp3SampleRates[f.version][rateIndex]
+	f.mono = data[i+3]>>6 == 3
+	return f, true
+}
+
+func (f mp3Frame) samplesPerFrame() int {
+	switch {
+	case f.layer == 1:
+		return 384
+	case f.layer == 3 && f.version != 1:
+		return 576
+	}
+	return 1152
+}
+
+func probeMP3(data []byte) *MediaMetadata {
+	m := &MediaMetadata{Format: "mp3"}
+
+	// Skip an ID3v2 tag
+	start := 0
+	if bytes.HasPrefix(data, []byte("ID3")) && len(data) >= 10 {
+		size := int(data[6]&0x7f)<<21 | int(data[7]&0x7f)<<14 | int(data[8]&0x7f)<<7 | int(data[9]&0x7f)
+		start = 10 + size
+		if data[5]&0x10 != 0 {
+			start += 10
+		}
+	}
+
+	// Find the first frame header
+	frameAt := -1
+	for i := start; i+4 <= len(data) && i < start+64<<10; i++ {
+		if mp3FrameAt(data, i) {
+			frameAt = i
+			break
+		}
+	}
+	if frameAt < 0 {
+		m.Err = fmt.Errorf("no MPEG audio frame found")
+		return m
+	}
+	f, _ := parseMP3Frame(data, frameAt)
+
+	channels := 2
+	if f.mono {
+		channels = 1
+	}
+	track := MediaTrack{
+		Type:       "audio",
+		Codec:      fmt.Sprintf("mp%d", f.layer),
+		SampleRate: intPtr(f.sampleRate),
+		Channels:   intPtr(channels),
+	}
+	m.Tracks = []MediaTrack{track}
+
+	// A Xing or Info header carries the frame count of VBR files
+	sideInfo := 32
+	switch {
+	case f.version == 1 && f.mono:
+		sideInfo = 17
+	case f.version != 1 && !f.mono:
+		sideInfo = 17
+	case f.version != 1 && f.mono:
+		sideInfo = 9
+	}
+	xing := frameAt + 4 + sideInfo
+	if xing+12 <= len(data) {
+		tag := string(data[xing : xing+4])
+		flags := binary.BigEndian.Uint32(data[xing+4:])
+		if (tag == "Xing" || tag == "Info") && flags&1 != 0 {
+			frames := binary.BigEndian.Uint32(data[xing+8:])
+			m.Duration = seconds(float64(frames) * float64(f.samplesPerFrame()) / float64(f.sampleRate))
+			return m
+		}
+	}
+
+	// Constant bitrate: estimate from the audio size
+	m.Bitrate = intPtr(f.bitrate)
+	m.Duration = seconds(float64(len(data)-frameAt) * 8 / float64(f.bitrate))
+	return m
+}
+
+// --- WAV ---
+
+func probeWAV(data []byte) *MediaMetadata {
+	m := &MediaMetadata{Format: "wav"}
+	track := MediaTrack{Type: "audio"}
+	var byteRate uint32
+	var dataSize uint32
+
+	for i := 12; i+8 <= len(data); {
+		id := string(data[i : i+4])
+		size := binary.LittleEndian.Uint32(data[i+4:])
+		body := data[i+8:]
+		if uint64(size) < uint64(len(body)) {
+			body = body[:size]
+		}
+		switch id {
+		case "fmt ":
+			if len(body) >= 16 {
+				format := binary.LittleEndian.Uint16(body)
+				switch format {
+				case 1, 0xfffe:
+					track.Codec = "pcm"
+				case 3:
+					track.Codec = "pcm_float"
+				default:
+					track.Codec = fmt.Sprintf("wav format 0x%04x", format)
+				}
+				track.Channels = intPtr(int(binary.LittleEndian.Uint16(body[2:])))
+				track.SampleRate = intPtr(int(binary.LittleEndian.Uint32(body[4:])))
+				byteRate = binary.LittleEndian.Uint32(body[8:])
+			}
+		case "data":
+			dataSize = size
+		}
+		// Chunks are padded to an even size
+		i += 8 + int(size) + int(size&1)
+	}
+
+	m.Tracks = []MediaTrack{track}
+	if byteRate > 0 {
+		m.Bitrate = intPtr(int(byteRate) * 8)
+		m.Duration = seconds(float64(dataSize) / float64(byteRate))
+	}
+	return m
+}
+
+// --- FLAC ---
+
+func probeFLAC(data []byte) *MediaMetadata {
+	m := &MediaMetadata{Format: "flac"}
+	// STREAMINFO is always the first metadata block
+	if len(data) < 8+18 || data[4]&0x7f != 0 {
+		m.Err = fmt.Errorf("missing STREAMINFO block")
+		return m
+	}
+	info := data[8+10:]
+	packed := binary.BigEndian.Uint64(info)
+	sampleRate := int(packed >> 44)
+	channels := int(packed>>41&0x7) + 1
+	totalSamples := packed & 0xfffffffff
+
+	m.Tracks = []MediaTrack{{
+		Type:       "audio",
+		Codec:      "flac",
+		SampleRate: intPtr(sampleRate),
+		Channels:   intPtr(channels),
+	}}
+	if sampleRate > 0 {
+		m.Duration = seconds(float64(totalSamples) / float64(sampleRate))
+	}
+	return m
+}
+
+// --- Ogg (Vorbis, Opus, Theora) ---
+
+func probeOgg(data []byte) *MediaMetadata {
+	m := &MediaMetadata{Format: "ogg"}
+	if len(data) < 27 {
+		m.Err = fmt.Errorf("truncated Ogg page")
+		return m
+	}
+	segments := int(data[26])
+	packet := data[min(27+segments, len(data)):]
+
+	var rate float64
+	var preSkip uint64
+	track := MediaTrack{}
+	switch {
+	case bytes.HasPrefix(packet, []byte("OpusHead")) && len(packet) >= 16:
+		track = MediaTrack{
+			Type:       "audio",
+			Codec:      "opus",
+			Channels:   intPtr(int(packet[9])),
+			SampleRate: intPtr(int(binary.LittleEndian.Uint32(packet[12:]))),
+		}
+		// Opus granule positions always count 48 kHz samples
+		rate = 48000
+		preSkip = uint64(binary.LittleEndian.Uint16(packet[10:]))
+	case bytes.HasPrefix(packet, []byte("\x01vorbis")) && len(packet) >= 28:
+		sampleRate := int(binary.LittleEndian.Uint32(packet[12:]))
+		track = MediaTrack{
+			Type:       "audio",
+			Codec:      "vorbis",
+			Channels:   intPtr(int(packet[11])),
+			SampleRate: intPtr(sampleRate),
+		}
+		rate = float64(sampleRate)
+		if nominal := int32(binary.LittleEndian.Uint32(packet[20:])); nominal > 0 {
+			m.Bitrate = intPtr(int(nominal))
+		}
+	case bytes.HasPrefix(packet, []byte("\x80theora")):
+		track = MediaTrack{Type: "video", Codec: "theora"}
+	case bytes.HasPrefix(packet, []byte("\x7fFLAC")):
+		track = MediaTrack{Type: "audio", Codec: "flac"}
+	default:
+		m.Err = fmt.Errorf("unknown Ogg stream")
+		return m
+	}
+	m.Tracks = []MediaTrack{track}
+
+	// The granule position of the last page gives the stream length
+	if last := bytes.LastIndex(data, []byte("OggS")); rate > 0 && last >= 0 && last+14 <= len(data) {
+		granule := binary.LittleEndian.Uint64(data[last+6:])
+		if granule != math.MaxUint64 && granule > preSkip {
+			m.Duration = seconds(float64(granule-preSkip) / rate)
+		}
+	}
+	return m
+}
+
+// --- Matroska and WebM ---
+
+const (
+	ebmlHeader         = 0x1a45dfa3
+	ebmlDocType        = 0x4282
+	mkvSegment         = 0x18538067
+	mkvInfo            = 0x1549a966
+	mkvTimecodeScale   = 0x2ad7b1
+	mkvDuration        = 0x4489
+	mkvTracks          = 0x1654ae6b
+	mkvTrackEntry      = 0xae
+	mkvTrackType       = 0x83
+	mkvCodecID         = 0x86
+	mkvVideo           = 0xe0
+	mkvPixelWidth      = 0xb0
+	mkvPixelHeight     = 0xba
+	mkvAudio           = 0xe1
+	mkvSamplingFreq    = 0xb5
+	mkvChannels        = 0x9f
+	mkvCluster         = 0x1f43b675
+)
+
+// ebmlElement is an element within its parent's payload.
+type ebmlElement struct {
+	id      uint64
+	payload []byte
+}
+
+// ebmlVint reads a variable-length integer. Element IDs keep their length marker.
+func ebmlVint(data []byte, keepMarker bool) (uint64, int) {
+	if len(data) == 0 || data[0] == 0 {
+		return 0, 0
+	}
+	length := 1
+	for mask := byte(0x80); data[0]&mask == 0; mask >>= 1 {
+		length++
+	}
+	if length > len(data) {
+		return 0, 0
+	}
+	value := uint64(data[0])
+	if !keepMarker {
+		value &= uint64(0xff >> length)
+	}
+	allOnes := value == uint64(0xff>>length)
+	for _, b := range data[1:length] {
+		value = value<<8 | uint64(b)
+		allOnes = allOnes && b == 0xff
+	}
+	if !keepMarker && allOnes {
+		return math.MaxUint64, length
+	}
+	return value, length
+}
+
+// ebmlElements splits a payload into elements. Elements of unknown size, such
+// as live segments, extend to the end of the payload.
+func ebmlElements(data []byte) []ebmlElement {
+	var elements []ebmlElement
+	for len(data) > 0 {
+		id, n := ebmlVint(data, true)
+		if n == 0 {
+			break
+		}
+		size, m := ebmlVint(data[n:], false)
+		if m == 0 {
+			break
+		}
+		start := n + m
+		end := len(data)
+		if size != math.MaxUint64 && uint64(start)+size <= uint64(len(data)) {
+			end = start + int(size)
+		}
+		elements = append(elements, ebmlElement{id: id, payload: data[start:end]})
+		if id == mkvCluster {
+			break
+		}
+		data = data[end:]
+	}
+	return elements
+}
+
+func ebmlUint(payload []byte) uint64 {
+	var v uint64
+	for _, b := range payload {
+		v = v<<8 | uint64(b)
+	}
+	return v
+}
+
+func ebmlFloat(payload []byte) float64 {
+	switch len(payload) {
+	case 4:
+		return float64(math.Float32frombits(binary.BigEndian.Uint32(payload)))
+	case 8:
+		return math.Float64frombits(binary.BigEndian.Uint64(payload))
+	}
+	return 0
+}
+
+func probeMatroska(data []byte) *MediaMetadata {
+	m := &MediaMetadata{Format: "matroska"}
+
+	for _, top := range ebmlElements(data) {
+		switch top.id {
+		case ebmlHeader:
+			for _, e := range ebmlElements(top.payload) {
+				if e.id == ebmlDocType && string(bytes.TrimRight(e.payload, "\x00")) == "webm" {
+					m.Format = "webm"
+				}
+			}
+		case mkvSegment:
+			probeSegment(m, top.payload)
+		}
+	}
+	return m
+}
+
+func probeSegment(m *MediaMetadata, segment []byte) {
+	for _, e := range ebmlElements(segment) {
+		switch e.id {
+		case mkvInfo:
+			scale := uint64(1000000)
+			var duration float64
+			for _, field := range ebmlElements(e.payload) {
+				switch field.id {
+				case mkvTimecodeScale:
+					scale = ebmlUint(field.payload)
+				case mkvDuration:
+					duration = ebmlFloat(field.payload)
+				}
+			}
+			m.Duration = seconds(duration * float64(scale) / 1e9)
+
+		case mkvTracks:
+			for _, entry := range ebmlElements(e.payload) {
+				if entry.id == mkvTrackEntry {
+					if track, ok := matroskaTrack(entry.payload); ok {
+						m.Tracks = append(m.Tracks, track)
+					}
+				}
+			}
+		}
+	}
+}
+
+func matroskaTrack(entry []byte) (MediaTrack, bool) {
+	var track MediaTrack
+	for _, field := range ebmlElements(entry) {
+		switch field.id {
+		case mkvTrackType:
+			switch ebmlUint(field.payload) {
+			case 1:
+				track.Type = "video"
+			case 2:
+				track.Type = "audio"
+			}
+		case mkvCodecID:
+			track.Codec = string(bytes.TrimRight(field.payload, "\x00"))
+		case mkvVideo:
+			for _, v := range ebmlElements(field.payload) {
+				switch v.id {
+				case mkvPixelWidth:
+					track.Width = intPtr(int(ebmlUint(v.payload)))
+				case mkvPixelHeight:
+					track.Height = intPtr(int(ebmlUint(v.payload)))
+				}
+			}
+		case mkvAudio:
+			for _, a := range ebmlElements(field.payload) {
+				switch a.id {
+				case mkvSamplingFreq:
+					track.SampleRate = intPtr(int(ebmlFloat(a.payload)))
+				case mkvChannels:
+					track.Channels = intPtr(int(ebmlUint(a.payload)))
+				}
+			}
+		}
+	}
+	return track, track.Type != ""
+}
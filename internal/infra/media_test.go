@@ -0,0 +1,173 @@
+package infra_test
+
+import (
+	"bytes"
+	"encoding/binary"
+	"math"
+	"reflect"
+	"testing"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+func intPtr(v int) *int { return &v }
+
+// join concatenates byte slices and strings.
+func join(parts ...interface{}) []byte {
+	var buf bytes.Buffer
+	for _, part := range parts {
+		switch p := part.(type) {
+		case string:
+			buf.WriteString(p)
+		case []byte:
+			buf.Write(p)
+		default:
+			binary.Write(&buf, binary.BigEndian, p)
+		}
+	}
+	return buf.Bytes()
+}
+
+func le32(v uint32) []byte { return binary.LittleEndian.AppendUint32(nil, v) }
+func le16(v uint16) []byte { return binary.LittleEndian.AppendUint16(nil, v) }
+
+// mp4Box encodes an ISO base media box.
+func mp4Box(kind string, payload ...interface{}) []byte {
+	body := join(payload...)
+	return join(uint32(8+len(body)), kind, body)
+}
+
+// ebml encodes a Matroska element with a one- or two-byte size.
+func ebml(id uint32, payload ...interface{}) []byte {
+	body := join(payload...)
+	idBytes := bytes.TrimLeft(binary.BigEndian.AppendUint32(nil, id), "\x00")
+	if len(body) < 127 {
+		return join(idBytes, []byte{0x80 | byte(len(body))}, body)
+	}
+	return join(idBytes, []byte{0x40 | byte(len(body)>>8), byte(len(body))}, body)
+}
+
+func TestProbeMedia(t *testing.T) {
+	wav := join("RIFF", le32(36), "WAVE",
+		"fmt ", le32(16), le16(1), le16(2), le32(44100), le32(176400), le16(4), le16(16),
+		"data", le32(176400*2))
+
+	flac := join("fLaC", []byte{0x80, 0, 0, 34},
+		make([]byte, 10), uint64(44100)<<44|uint64(1)<<41|uint64(15)<<36|88200, make([]byte, 16))
+
+	mp3 := append([]byte{0xff, 0xfb, 0x90, 0x00}, make([]byte, 15996)...)
+	vbr := append([]byte{0xff, 0xfb, 0x90, 0x00}, make([]byte, 32)...)
+	vbr = append(vbr, join("Xing", uint32(1), uint32(441), make([]byte, 400))...)
+	id3 := append(join("ID3", []byte{4, 0, 0, 0, 0, 0, 10}, make([]byte, 10)), mp3...)
+
+	stsd := func(entry []byte) []byte {
+		return mp4Box("stsd", uint32(0), uint32(1), entry)
+	}
+	trak := func(handler string, entry []byte) []byte {
+		return mp4Box("trak", mp4Box("mdia",
+			mp4Box("hdlr", uint32(0), uint32(0), handler, make([]byte, 12)),
+			mp4Box("minf", mp4Box("stbl", stsd(entry)))))
+	}
+	video := mp4Box("avc1", make([]byte, 24), uint16(1920), uint16(1080), make([]byte, 50))
+	audio := mp4Box("mp4a", make([]byte, 16), uint16(2), uint16(16), make([]byte, 4), uint32(48000)<<16)
+	mvhd := mp4Box("mvhd", uint32(0), uint32(0), uint32(0), uint32(1000), uint32(5000), make([]byte, 80))
+	mp4 := join(mp4Box("ftyp", "isom", uint32(512), "isomavc1"), mp4Box("moov", mvhd, trak("vide", video), trak("soun", audio)))
+	m4a := join(mp4Box("ftyp", "M4A ", uint32(0)), mp4Box("moov", mvhd, trak("soun", audio)))
+
+	oggPage := func(granule uint64, segments []byte, packet []byte) []byte {
+		return join("OggS", []byte{0, 2}, le32(uint32(granule)), le32(uint32(granule>>32)), make([]byte, 12),
+			[]byte{byte(len(segments))}, segments, packet)
+	}
+	opusHead := join("OpusHead", []byte{1, 2}, le16(312), le32(48000), make([]byte, 3))
+	ogg := join(oggPage(0, []byte{19}, opusHead), oggPage(3*48000+312, nil, nil))
+
+	webm := join(
+		ebml(0x1a45dfa3, ebml(0x4282, "webm")),
+		ebml(0x18538067,
+			ebml(0x1549a966, ebml(0x2ad7b1, []byte{0x0f, 0x42, 0x40}), ebml(0x4489, math.Float64bits(2500))),
+			ebml(0x1654ae6b,
+				ebml(0xae, ebml(0x83, []byte{1}), ebml(0x86, "V_VP9"),
+					ebml(0xe0, ebml(0xb0, uint16(1280)), ebml(0xba, uint16(720)))),
+				ebml(0xae, ebml(0x83, []byte{2}), ebml(0x86, "A_OPUS"),
+					ebml(0xe1, ebml(0xb5, math.Float64bits(48000)), ebml(0x9f, []byte{2}))))))
+
+	tests := []struct {
+		name     string
+		data     []byte
+		format   string
+		duration float64 // 0 when unknown
+		bitrate  int     // 0 to skip
+		tracks   []infra.MediaTrack
+		err      bool
+	}{
+		{"wav", wav, "wav", 2, 1411200, []infra.MediaTrack{
+			{Type: "audio", Codec: "pcm", SampleRate: intPtr(44100), Channels: intPtr(2)},
+		}, false},
+		{"flac", flac, "flac", 2, 0, []infra.MediaTrack{
+			{Type: "audio", Codec: "flac", SampleRate: intPtr(44100), Channels: intPtr(2)},
+		}, false},
+		{"mp3 cbr", mp3, "mp3", 1, 128000, []infra.MediaTrack{
+			{Type: "audio", Codec: "mp3", SampleRate: intPtr(44100), Channels: intPtr(2)},
+		}, false},
+		{"mp3 xing", vbr, "mp3", 11.52, 0, []infra.MediaTrack{
+			{Type: "audio", Codec: "mp3", SampleRate: intPtr(44100), Channels: intPtr(2)},
+		}, false},
+		{"mp3 id3", id3, "mp3", 1, 128000, []infra.MediaTrack{
+			{Type: "audio", Codec: "mp3", SampleRate: intPtr(44100), Channels: intPtr(2)},
+		}, false},
+		{"mp4", mp4, "mp4", 5, 0, []infra.MediaTrack{
+			{Type: "video", Codec: "avc1", Width: intPtr(1920), Height: intPtr(1080)},
+			{Type: "audio", Codec: "mp4a", SampleRate: intPtr(48000), Channels: intPtr(2)},
+		}, false},
+		{"m4a", m4a, "m4a", 5, 0, []infra.MediaTrack{
+			{Type: "audio", Codec: "mp4a", SampleRate: intPtr(48000), Channels: intPtr(2)},
+		}, false},
+		{"mp4 without moov", mp4Box("ftyp", "isom", uint32(0)), "mp4", 0, 0, nil, true},
+		{"ogg opus", ogg, "ogg", 3, 0, []infra.MediaTrack{
+			{Type: "audio", Codec: "opus", SampleRate: intPtr(48000), Channels: intPtr(2)},
+		}, false},
+		{"webm", webm, "webm", 2.5, 0, []infra.MediaTrack{
+			{Type: "video", Codec: "V_VP9", Width: intPtr(1280), Height: intPtr(720)},
+			{Type: "audio", Codec: "A_OPUS", SampleRate: intPtr(48000), Channels: intPtr(2)},
+		}, false},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			m := infra.ProbeMedia(tt.data)
+			if m == nil {
+				t.Fatal("ProbeMedia = nil, want metadata")
+			}
+			if m.Format != tt.format {
+				t.Errorf("Format = %q, want %q", m.Format, tt.format)
+			}
+			if (m.Err != nil) != tt.err {
+				t.Errorf("Err = %v, want an error: %v", m.Err, tt.err)
+			}
+			switch {
+			case tt.duration == 0 && m.Duration != nil:
+				t.Errorf("Duration = %v, want none", *m.Duration)
+			case tt.duration != 0 && (m.Duration == nil || math.Abs(*m.Duration-tt.duration) > 1e-6):
+				t.Errorf("Duration = %v, want %v", m.Duration, tt.duration)
+			}
+			if tt.bitrate != 0 && (m.Bitrate == nil || *m.Bitrate != tt.bitrate) {
+				t.Errorf("Bitrate = %v, want %d", m.Bitrate, tt.bitrate)
+			}
+			if !reflect.DeepEqual(m.Tracks, tt.tracks) {
+				t.Errorf("Tracks = %+v, want %+v", m.Tracks, tt.tracks)
+			}
+		})
+	}
+}
+
+func TestProbeMediaOtherContent(t *testing.T) {
+	for name, data := range map[string][]byte{
+		"text":  []byte("hello, world"),
+		"empty": nil,
+		"heic":  mp4Box("ftyp", "heic", uint32(0), "mif1heic"),
+		"png":   []byte("\x89PNG\r\n\x1a\n"),
+	} {
+		if m := infra.ProbeMedia(data); m != nil {
+			t.Errorf("%s: ProbeMedia = %+v, want nil", name, m)
+		}
+	}
+}
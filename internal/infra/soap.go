@@ -0,0 +1,141 @@
+package infra
+
+import (
+	"encoding/xml"
+	"strings"
+)
+
+const (
+	soap11Namespace = "http://schemas.xmlsoap.org/soap/envelope/"
+	soap12Namespace = "http://www.w3.org/2003/05/soap-envelope"
+
+	// maxFaultDetail bounds the raw fault detail kept in a SOAPMessage.
+	maxFaultDetail = 4 << 10
+)
+
+// SOAPMessage describes a SOAP envelope.
+type SOAPMessage struct {
+	Version            string // 1.1 or 1.2
+	HasHeader          bool
+	Operation          string // Local name of the first Body element
+	OperationNamespace string
+	Fault              *SOAPFault
+}
+
+// SOAPFault holds the fault fields of either SOAP version. SOAP 1.1
+// faultcode, faultstring and faultactor map to Code, Reason and Role.
+type SOAPFault struct {
+	Code     string
+	Subcodes []string // SOAP 1.2 only
+	Reason   string
+	Role     string
+	Node     string // SOAP 1.2 only
+	Detail   string // Raw XML of the detail element
+}
+
+type soapEnvelope struct {
+	XMLName xml.Name
+	Header  *struct{} `xml:"Header"`
+	Body    soapBody  `xml:"Body"`
+}
+
+type soapBody struct {
+	Fault    *soapFault    `xml:"Fault"`
+	Elements []soapElement `xml:",any"`
+}
+
+type soapElement struct {
+	XMLName xml.Name
+}
+
+type soapFault struct {
+	// SOAP 1.1
+	FaultCode   string     `xml:"faultcode"`
+	FaultString string     `xml:"faultstring"`
+	FaultActor  string     `xml:"faultactor"`
+	Detail11    soapDetail `xml:"detail"`
+	// SOAP 1.2
+	Code     soapCode   `xml:"Code"`
+	Reason   []soapText `xml:"Reason>Text"`
+	Role     string     `xml:"Role"`
+	Node     string     `xml:"Node"`
+	Detail12 soapDetail `xml:"Detail"`
+}
+
+type soapCode struct {
+	Value   string    `xml:"Value"`
+	Subcode *soapCode `xml:"Subcode"`
+}
+
+type soapText struct {
+	Lang string `xml:"lang,attr"`
+	Text string `xml:",chardata"`
+}
+
+type soapDetail struct {
+	Inner string `xml:",innerxml"`
+}
+
+// ParseSOAP parses a SOAP 1.1 or 1.2 envelope. It returns nil when the body
+// is not one.
+func ParseSOAP(body []byte) *SOAPMessage {
+	var version string
+	switch root := xmlRoot(body); {
+	case root.Local != "Envelope":
+		return nil
+	case root.Space == soap11Namespace:
+		version = "1.1"
+	case root.Space == soap12Namespace:
+		version = "1.2"
+	default:
+		return nil
+	}
+
+	var envelope soapEnvelope
+	if err := newXMLDecoder(body).Decode(&envelope); err != nil {
+		return nil
+	}
+
+	msg := &SOAPMessage{Version: version, HasHeader: envelope.Header != nil}
+	if len(envelope.Body.Elements) > 0 {
+		msg.Operation = envelope.Body.Elements[0].XMLName.Local
+		msg.OperationNamespace = envelope.Body.Elements[0].XMLName.Space
+	}
+	if f := envelope.Body.Fault; f != nil {
+		msg.Operation = "Fault"
+		msg.OperationNamespace = envelope.XMLName.Space
+		msg.Fault = convertFault(f)
+	}
+	return msg
+}
+
+func convertFault(f *soapFault) *SOAPFault {
+	fault := &SOAPFault{
+		Code:   strings.TrimSpace(f.FaultCode),
+		Reason: strings.TrimSpace(f.FaultString),
+		Role:   strings.TrimSpace(firstNonEmpty(f.FaultActor, f.Role)),
+		Node:   strings.TrimSpace(f.Node),
+		Detail: strings.TrimSpace(firstNonEmpty(f.Detail11.Inner, f.Detail12.Inner)),
+	}
+
+	if fault.Code == "" {
+		fault.Code = strings.TrimSpace(f.Code.Value)
+		for sub := f.Code.Subcode; sub != nil; sub = sub.Subcode {
+			fault.Subcodes = append(fault.Subcodes, strings.TrimSpace(sub.Value))
+		}
+	}
+	if fault.Reason == "" && len(f.Reason) > 0 {
+		// Prefer English when the reason is given in several languages
+		fault.Reason = strings.TrimSpace(f.Reason[0].Text)
+		for _, text := range f.Reason {
+			if strings.HasPrefix(strings.ToLower(text.Lang), "en") {
+				fault.Reason = strings.TrimSpace(text.Text)
+				break
+			}
+		}
+	}
+	if len(fault.Detail) > maxFaultDetail {
+		fault.Detail = fault.Detail[:maxFaultDetail]
+	}
+	return fault
+}
@@ -1,24 +1,84 @@
 package infra
 
 import (
+	"crypto/ecdsa"
+	"crypto/rsa"
+	"crypto/sha256"
 	"crypto/tls"
 	"crypto/x509"
+	"errors"
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
 	"time"
 )
 
 // CertInfo contains TLS certificate information.
 type CertInfo struct {
-	Protocol  string
-	Cipher    string
-	Issuer    string
-	Subject   string
-	ValidFrom uint64
-	ValidTo   uint64
-	SANs      []string // Subject Alternative Names (DNS names and IP addresses)
+	Protocol   string
+	Cipher     string
+	Issuer     string
+	Subject    string
+	ValidFrom  uint64
+	ValidTo    uint64
+	SANs       []string // Subject Alternative Names (DNS names and IP addresses)
+	Chain      []ChainCert
+	Validation CertValidation
 }
 
-// ExtractCertInfo extracts certificate information from a TLS connection state.
-func ExtractCertInfo(state *tls.ConnectionState) *CertInfo {
+// CertValidation holds the outcome of validating the presented certificate.
+type CertValidation struct {
+	HostnameMatch bool
+	Expired       bool
+	NotYetValid   bool
+	ChainVerified bool
+	Errors        []string
+	Warnings      []string
+}
+
+// Valid reports whether validation produced no errors.
+func (v CertValidation) Valid() bool {
+	return len(v.Errors) == 0
+}
+
+// ChainCert describes a single certificate of the chain presented by the server.
+type ChainCert struct {
+	Subject      string
+	Issuer       string
+	ValidFrom    uint64
+	ValidTo      uint64
+	KeyAlgorithm string
+	Fingerprint  string // SHA-256 of the DER encoding
+	SerialNumber string
+}
+
+// certExpiryWarning is how close to expiry a certificate triggers a warning.
+const certExpiryWarning = 30 * 24 * time.Hour
+
+// errUnverifiedChain is the chain outcome of a handshake that verified no chain.
+var errUnverifiedChain = errors.New("no verified chain")
+
+// ExtractCertInfo extracts certificate information from a TLS connection state
+// and validates the leaf certificate against the requested host. The chain
+// counts as verified when crypto/tls verified it during the handshake.
+func ExtractCertInfo(state *tls.ConnectionState, host string) *CertInfo {
+	var chainErr error
+	if state != nil && len(state.VerifiedChains) == 0 {
+		chainErr = errUnverifiedChain
+	}
+	return extractCertInfo(state, host, chainErr)
+}
+
+// VerifyCertInfo is ExtractCertInfo for a handshake that skipped the
+// verification of crypto/tls: it verifies the presented chain against roots
+// itself, or the system roots when nil, so every reason a certificate fails
+// is reported rather than only the first.
+func VerifyCertInfo(state *tls.ConnectionState, host string, roots *x509.CertPool) *CertInfo {
+	return extractCertInfo(state, host, verifyChain(state.PeerCertificates, roots))
+}
+
+func extractCertInfo(state *tls.ConnectionState, host string, chainErr error) *CertInfo {
 	if state == nil {
 		return nil
 	}
@@ -36,17 +96,86 @@ func ExtractCertInfo(state *tls.ConnectionState) *CertInfo {
 		info.ValidFrom = uint64(cert.NotBefore.Unix())
 		info.ValidTo = uint64(cert.NotAfter.Unix())
 		info.SANs = extractSANs(cert)
+		info.Validation = validateCert(cert, host, chainErr)
+	} else {
+		info.Validation.Errors = []string{"server presented no certificate"}
+	}
+
+	// Describe the full presented chain, leaf first
+	for _, cert := range state.PeerCertificates {
+		info.Chain = append(info.Chain, describeCert(cert))
 	}
 
 	return info
 }
 
+// verifyChain verifies the presented chain, leaf first, as crypto/tls does
+// for a client: the rest of the chain serves as intermediates.
+func verifyChain(chain []*x509.Certificate, roots *x509.CertPool) error {
+	if len(chain) == 0 {
+		return errors.New("server presented no certificate")
+	}
+	intermediates := x509.NewCertPool()
+	for _, cert := range chain[1:] {
+		intermediates.AddCert(cert)
+	}
+	_, err := chain[0].Verify(x509.VerifyOptions{Roots: roots, Intermediates: intermediates})
+	return err
+}
+
 // IsCertValid checks if a certificate is currently valid.
 func IsCertValid(validFrom, validTo uint64) bool {
 	now := uint64(time.Now().Unix())
 	return now >= validFrom && now <= validTo
 }
 
+// validateCert checks hostname, validity period and key strength of a leaf certificate.
+// chainErr is the outcome of verifying the chain, nil when it was verified.
+func validateCert(cert *x509.Certificate, host string, chainErr error) CertValidation {
+	v := CertValidation{ChainVerified: chainErr == nil}
+	now := time.Now()
+
+	if err := cert.VerifyHostname(host); err == nil {
+		v.HostnameMatch = true
+	} else {
+		v.Errors = append(v.Errors, fmt.Sprintf("hostname mismatch: %v", err))
+	}
+
+	switch {
+	case now.Before(cert.NotBefore):
+		v.NotYetValid = true
+		v.Errors = append(v.Errors, fmt.Sprintf("certificate is not valid before %s", cert.NotBefore.UTC().Format(time.RFC3339)))
+	case now.After(cert.NotAfter):
+		v.Expired = true
+		v.Errors = append(v.Errors, fmt.Sprintf("certificate expired at %s", cert.NotAfter.UTC().Format(time.RFC3339)))
+	case cert.NotAfter.Sub(now) < certExpiryWarning:
+		days := int(cert.NotAfter.Sub(now).Hours() / 24)
+		v.Warnings = append(v.Warnings, fmt.Sprintf("certificate expires in %d days", days))
+	}
+
+	switch {
+	case errors.Is(chainErr, errUnverifiedChain):
+		v.Errors = append(v.Errors, "certificate chain was not verified against a trusted root")
+	case chainErr != nil:
+		v.Errors = append(v.Errors, fmt.Sprintf("certificate chain was not verified against a trusted root: %v", chainErr))
+	}
+
+	if key, ok := cert.PublicKey.(*rsa.PublicKey); ok && key.N.BitLen() < 2048 {
+		v.Warnings = append(v.Warnings, fmt.Sprintf("weak RSA key size: %d bits", key.N.BitLen()))
+	}
+
+	switch cert.SignatureAlgorithm {
+	case x509.SHA1WithRSA, x509.ECDSAWithSHA1, x509.MD5WithRSA:
+		v.Warnings = append(v.Warnings, fmt.Sprintf("weak signature algorithm: %s", cert.SignatureAlgorithm))
+	}
+
+	if cert.Subject.String() == cert.Issuer.String() {
+		v.Warnings = append(v.Warnings, "certificate is self-signed")
+	}
+
+	return v
+}
+
 // ParseCertificate parses a DER-encoded X.509 certificate.
 func ParseCertificate(der []byte) (*CertInfo, error) {
 	cert, err := x509.ParseCertificate(der)
@@ -60,9 +189,60 @@ func ParseCertificate(der []byte) (*CertInfo, error) {
 		ValidFrom: uint64(cert.NotBefore.Unix()),
 		ValidTo:   uint64(cert.NotAfter.Unix()),
 		SANs:      extractSANs(cert),
+		Chain:     []ChainCert{describeCert(cert)},
 	}, nil
 }
 
+// LoadCertPool returns the system root pool extended with the PEM certificates at path.
+// The path may be a single PEM file or a directory of PEM files. An empty path returns
+// nil, which makes crypto/tls fall back to the system roots.
+func LoadCertPool(path string) (*x509.CertPool, error) {
+	if path == "" {
+		return nil, nil
+	}
+
+	pool, err := x509.SystemCertPool()
+	if err != nil {
+		pool = x509.NewCertPool()
+	}
+
+	info, err := os.Stat(path)
+	if err != nil {
+		return nil, fmt.Errorf("failed to read CA bundle: %w", err)
+	}
+
+	files := []string{path}
+	if info.IsDir() {
+		entries, err := os.ReadDir(path)
+		if err != nil {
+			return nil, fmt.Errorf("failed to read CA bundle directory: %w", err)
+		}
+		files = files[:0]
+		for _, entry := range entries {
+			if !entry.IsDir() {
+				files = append(files, filepath.Join(path, entry.Name()))
+			}
+		}
+	}
+
+	added := 0
+	for _, file := range files {
+		data, err := os.ReadFile(file)
+		if err != nil {
+			return nil, fmt.Errorf("failed to read CA bundle: %w", err)
+		}
+		if pool.AppendCertsFromPEM(data) {
+			added++
+		}
+	}
+
+	if added == 0 {
+		return nil, fmt.Errorf("no PEM certificates found in %s", path)
+	}
+
+	return pool, nil
+}
+
 // tlsVersionString returns a human-readable TLS version string.
 func tlsVersionString(version uint16) string {
 	switch version {
@@ -86,6 +266,42 @@ func extractCN(dn string) string {
 	return dn
 }
 
+// describeCert builds the chain entry for a certificate.
+func describeCert(cert *x509.Certificate) ChainCert {
+	fingerprint := sha256.Sum256(cert.Raw)
+
+	return ChainCert{
+		Subject:      extractCN(cert.Subject.String()),
+		Issuer:       extractCN(cert.Issuer.String()),
+		ValidFrom:    uint64(cert.NotBefore.Unix()),
+		ValidTo:      uint64(cert.NotAfter.Unix()),
+		KeyAlgorithm: keyAlgorithmString(cert),
+		Fingerprint:  colonHex(fingerprint[:]),
+		SerialNumber: colonHex(cert.SerialNumber.Bytes()),
+	}
+}
+
+// keyAlgorithmString returns the public key algorithm including key size or curve.
+func keyAlgorithmString(cert *x509.Certificate) string {
+	switch key := cert.PublicKey.(type) {
+	case *rsa.PublicKey:
+		return fmt.Sprintf("RSA %d", key.N.BitLen())
+	case *ecdsa.PublicKey:
+		return fmt.Sprintf("ECDSA %s", key.Curve.Params().Name)
+	default:
+		return cert.PublicKeyAlgorithm.String()
+	}
+}
+
+// colonHex formats bytes as colon-separated uppercase hex (AB:CD:EF).
+func colonHex(data []byte) string {
+	parts := make([]string, len(data))
+	for i, b := range data {
+		parts[i] = fmt.Sprintf("%02X", b)
+	}
+	return strings.Join(parts, ":")
+}
+
 // extractSANs extracts Subject Alternative Names from a certificate.
 // Returns DNS names and IP addresses as strings.
 func extractSANs(cert *x509.Certificate) []string {
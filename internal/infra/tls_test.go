@@ -0,0 +1,23 @@
+package infra_test
+
+import (
+	"net/http/httptest"
+	"testing"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+func FuzzParseCertificate(f *testing.F) {
+	server := httptest.NewTLSServer(nil)
+	der := server.Certificate().Raw
+	server.Close()
+
+	f.Add(der)
+	f.Add(der[:len(der)/2])
+	f.Add([]byte{0x30, 0x82, 0x01, 0x00})
+	f.Add([]byte{})
+
+	f.Fuzz(func(t *testing.T, data []byte) {
+		infra.ParseCertificate(data)
+	})
+}
@@ -0,0 +1,147 @@
+package monitors
+
+import (
+	"errors"
+	"fmt"
+	"net/url"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/collections"
+	"zone.digit.tommie/internal/proxy"
+	"zone.digit.tommie/internal/storage"
+)
+
+// Executor executes the request of a check, such as HTTPService.ExecuteIn.
+type Executor func(store storage.Store, request proxy.ProxyRequest) proxy.ProxyResponse
+
+// Check executes the request of a monitor once and stores the result.
+// A saved request deleted since the monitor was defined fails the check.
+func Check(store storage.Store, id string, execute Executor) (*Result, error) {
+	_, result, _, err := check(store, id, execute)
+	return result, err
+}
+
+// check is Check, also returning the monitor and the event the result
+// causes, if any.
+func check(store storage.Store, id string, execute Executor) (*Monitor, *Result, *Event, error) {
+	m, err := Get(store, id)
+	if err != nil {
+		return nil, nil, nil, err
+	}
+	previous, err := Results(store, id, 0, 1)
+	if err != nil {
+		return nil, nil, nil, err
+	}
+	result := Result{Timestamp: time.Now().UTC()}
+	saved, err := savedRequest(store, *m)
+	var validation *ValidationError
+	switch {
+	case errors.As(err, &validation):
+		result.ErrorCode = "NOT_FOUND"
+		result.Error = validation.Message
+	case err != nil:
+		return nil, nil, nil, err
+	default:
+		result = checkResult(result.Timestamp, execute(store, monitorRequest(*m, *saved)))
+	}
+	if err := AddResult(store, id, result); err != nil {
+		return nil, nil, nil, err
+	}
+	var last *Result
+	if len(previous.Results) > 0 {
+		last = &previous.Results[0]
+	}
+	return m, &result, transition(*m, last, result), nil
+}
+
+// checkResult summarizes a response. Without assertions or tests, any status
+// below 400 counts as up.
+func checkResult(timestamp time.Time, response proxy.ProxyResponse) Result {
+	result := Result{Timestamp: timestamp}
+	if !response.Success {
+		result.ErrorCode = response.Error.Code
+		result.Error = response.Error.Message
+		return result
+	}
+	data := response.Data
+	result.Status = data.Status
+	result.Latency = data.Timing.Total
+
+	checked := false
+	for _, assertion := range data.Assertions {
+		checked = true
+		if !assertion.Passed {
+			result.Failures = append(result.Failures, assertionFailure(assertion))
+		}
+	}
+	if data.Scripts != nil && data.Scripts.Test != nil {
+		test := data.Scripts.Test
+		checked = checked || len(test.Assertions) > 0
+		for _, assertion := range test.Assertions {
+			if !assertion.Passed {
+				result.Failures = append(result.Failures, "Test "+assertion.Name+" failed")
+			}
+		}
+		if test.Error != nil {
+			result.Failures = append(result.Failures, "Test script failed: "+test.Error.String())
+		}
+	}
+	if !checked && data.Status >= 400 {
+		result.Failures = append(result.Failures, fmt.Sprintf("Status %d", data.Status))
+	}
+	result.Up = len(result.Failures) == 0
+	return result
+}
+
+func assertionFailure(assertion proxy.AssertionResult) string {
+	label := assertion.Type
+	if assertion.Name != nil {
+		label = *assertion.Name
+	}
+	if assertion.Message != nil {
+		return label + ": " + *assertion.Message
+	}
+	return label + " failed"
+}
+
+// monitorRequest builds the request of a check from a saved request.
+func monitorRequest(m Monitor, saved collections.SavedRequest) proxy.ProxyRequest {
+	request := proxy.ProxyRequest{
+		Method:      saved.Method,
+		URL:         saved.URL,
+		Headers:     map[string]string{},
+		Body:        saved.Body,
+		Environment: m.Environment,
+	}
+	for name, value := range saved.Headers {
+		request.Headers[name] = value
+	}
+	if auth := saved.Auth; auth != nil {
+		switch auth.Type {
+		case "basic", "bearer":
+			request.Auth = &proxy.AuthOptions{Type: auth.Type, Username: auth.Username, Password: auth.Password, Token: auth.Token}
+		case "apiKey":
+			if auth.In == "query" {
+				separator := "?"
+				if strings.Contains(request.URL, "?") {
+					separator = "&"
+				}
+				request.URL += separator + url.QueryEscape(auth.Key) + "=" + url.QueryEscape(auth.Value)
+			} else {
+				request.Headers[auth.Key] = auth.Value
+			}
+		}
+	}
+	if saved.Scripts != nil {
+		request.Scripts = &proxy.RequestScripts{PreRequest: saved.Scripts.PreRequest, Test: saved.Scripts.Test}
+	}
+	for _, assertion := range saved.Assertions {
+		request.Assertions = append(request.Assertions, proxy.Assertion(assertion))
+	}
+	request.Assertions = append(request.Assertions, m.Assertions...)
+	for _, extraction := range saved.Extract {
+		request.Extract = append(request.Extract, proxy.Extraction(extraction))
+	}
+	return request
+}
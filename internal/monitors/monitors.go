@@ -0,0 +1,195 @@
+// Package monitors keeps scheduled checks of saved requests, and the results
+// of those checks, in a storage.Store.
+package monitors
+
+import (
+	"crypto/rand"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"sort"
+	"strings"
+	"sync"
+	"time"
+
+	"zone.digit.tommie/internal/collections"
+	"zone.digit.tommie/internal/proxy"
+	"zone.digit.tommie/internal/storage"
+)
+
+// StoreName is the storage store holding monitors, keyed by monitor ID.
+const StoreName = "monitors"
+
+const (
+	// MinInterval is the shortest time between the checks of a monitor.
+	MinInterval = 10 * time.Second
+	// MaxInterval is the longest time between the checks of a monitor.
+	MaxInterval = 24 * time.Hour
+)
+
+// ErrNotFound is returned when a monitor does not exist.
+var ErrNotFound = errors.New("not found")
+
+// ValidationError reports an invalid monitor, such as one without a name or
+// referring to a request that does not exist.
+type ValidationError struct {
+	Message string
+}
+
+func (e *ValidationError) Error() string {
+	return e.Message
+}
+
+// Monitor checks a saved request at a fixed interval.
+type Monitor struct {
+	ID            string            `json:"id"`
+	Name          string            `json:"name"`
+	CollectionID  string            `json:"collectionId"`
+	RequestID     string            `json:"requestId"`             // Saved request executed on each check, as it is at that time
+	Environment   *string           `json:"environment,omitempty"` // Resolves the {{variables}} of the request
+	Interval      int               `json:"interval"`              // Seconds between checks
+	Assertions    []proxy.Assertion `json:"assertions,omitempty"`  // Checked in addition to those of the saved request
+	Enabled       bool              `json:"enabled"`
+	Notifications *Notifications    `json:"notifications,omitempty"` // Where to report that the monitor went down or recovered
+	Created       time.Time         `json:"created"`
+	Updated       time.Time         `json:"updated"`
+}
+
+// mu serializes read-modify-write cycles on monitors.
+var mu sync.Mutex
+
+// List returns all monitors sorted by name.
+func List(store storage.Store) ([]Monitor, error) {
+	ids, err := store.Keys(StoreName)
+	if err != nil {
+		return nil, err
+	}
+	monitors := []Monitor{}
+	for _, id := range ids {
+		m, err := Get(store, id)
+		if err != nil {
+			return nil, err
+		}
+		monitors = append(monitors, *m)
+	}
+	sort.Slice(monitors, func(i, j int) bool {
+		return strings.ToLower(monitors[i].Name) < strings.ToLower(monitors[j].Name)
+	})
+	return monitors, nil
+}
+
+// Get returns a monitor.
+func Get(store storage.Store, id string) (*Monitor, error) {
+	value, err := store.Get(StoreName, id)
+	if err != nil {
+		return nil, err
+	}
+	if value == nil {
+		return nil, ErrNotFound
+	}
+	var m Monitor
+	if err := json.Unmarshal([]byte(*value), &m); err != nil {
+		return nil, fmt.Errorf("corrupt monitor %s: %w", id, err)
+	}
+	return &m, nil
+}
+
+// Create stores a new monitor, assigning its ID.
+func Create(store storage.Store, m Monitor) (*Monitor, error) {
+	if err := validate(store, &m); err != nil {
+		return nil, err
+	}
+	suffix := make([]byte, 8)
+	if _, err := rand.Read(suffix); err != nil {
+		return nil, err
+	}
+	m.ID = hex.EncodeToString(suffix)
+	m.Created = time.Now().UTC()
+
+	mu.Lock()
+	defer mu.Unlock()
+	if err := save(store, &m); err != nil {
+		return nil, err
+	}
+	return &m, nil
+}
+
+// Update replaces the definition of a monitor, keeping its results.
+func Update(store storage.Store, id string, m Monitor) (*Monitor, error) {
+	if err := validate(store, &m); err != nil {
+		return nil, err
+	}
+
+	mu.Lock()
+	defer mu.Unlock()
+	existing, err := Get(store, id)
+	if err != nil {
+		return nil, err
+	}
+	m.ID = existing.ID
+	m.Created = existing.Created
+	if err := save(store, &m); err != nil {
+		return nil, err
+	}
+	return &m, nil
+}
+
+// Delete removes a monitor and its results.
+func Delete(store storage.Store, id string) error {
+	mu.Lock()
+	defer mu.Unlock()
+	if err := store.Remove(StoreName, id); err != nil {
+		return err
+	}
+	return clearResults(store, id)
+}
+
+// IntervalDuration returns the time between the checks of the monitor.
+func (m Monitor) IntervalDuration() time.Duration {
+	return time.Duration(m.Interval) * time.Second
+}
+
+func validate(store storage.Store, m *Monitor) error {
+	m.Name = strings.TrimSpace(m.Name)
+	if m.Name == "" {
+		return &ValidationError{Message: "Name is required"}
+	}
+	if interval := m.IntervalDuration(); interval < MinInterval || interval > MaxInterval {
+		return &ValidationError{Message: fmt.Sprintf("Interval must be between %d and %d seconds",
+			int(MinInterval.Seconds()), int(MaxInterval.Seconds()))}
+	}
+	if err := validateNotifications(m.Notifications); err != nil {
+		return err
+	}
+	if _, err := savedRequest(store, *m); err != nil {
+		return err
+	}
+	return nil
+}
+
+// savedRequest returns the saved request a monitor checks.
+func savedRequest(store storage.Store, m Monitor) (*collections.SavedRequest, error) {
+	c, err := collections.Get(store, m.CollectionID)
+	if errors.Is(err, collections.ErrNotFound) {
+		return nil, &ValidationError{Message: "Collection not found"}
+	}
+	if err != nil {
+		return nil, err
+	}
+	for i := range c.Requests {
+		if c.Requests[i].ID == m.RequestID {
+			return &c.Requests[i], nil
+		}
+	}
+	return nil, &ValidationError{Message: "Request not found in the collection"}
+}
+
+func save(store storage.Store, m *Monitor) error {
+	m.Updated = time.Now().UTC()
+	data, err := json.Marshal(m)
+	if err != nil {
+		return err
+	}
+	return store.Set(StoreName, m.ID, string(data))
+}
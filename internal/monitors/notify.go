@@ -0,0 +1,157 @@
+package monitors
+
+import (
+	"encoding/json"
+	"fmt"
+	"net/url"
+	"strconv"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/proxy"
+)
+
+// Webhook payload formats.
+const (
+	FormatGeneric = "generic" // The event as JSON, or the payload template
+	FormatSlack   = "slack"   // {"text": message}
+	FormatDiscord = "discord" // {"content": message}
+)
+
+// Notifications configure how a monitor reports that it went down or
+// recovered. A failing monitor notifies once, not on every failed check.
+type Notifications struct {
+	WebhookURL string  `json:"webhookUrl,omitempty"` // Receives a POST for each event
+	Format     string  `json:"format,omitempty"`     // generic (default), slack or discord
+	Payload    *string `json:"payload,omitempty"`    // Generic JSON template; see payloadFields for its {{placeholders}}
+	OnRecovery bool    `json:"onRecovery,omitempty"` // Also notify when a monitor is up again
+	Desktop    bool    `json:"desktop,omitempty"`    // Raise a desktop notification; desktop app only
+}
+
+// Event is a change of state of a monitor.
+type Event struct {
+	MonitorID string `json:"monitorId"`
+	Name      string `json:"name"`
+	Down      bool   `json:"down"` // False when the monitor recovered
+	Message   string `json:"message"`
+	Result    Result `json:"result"`
+}
+
+// transition returns the event a result causes, or nil when the state of
+// the monitor did not change. A first check only notifies when it fails.
+func transition(m Monitor, previous *Result, current Result) *Event {
+	wasUp := previous == nil || previous.Up
+	if wasUp == current.Up {
+		return nil
+	}
+	event := &Event{MonitorID: m.ID, Name: m.Name, Down: !current.Up, Result: current}
+	if event.Down {
+		event.Message = fmt.Sprintf("%s is down: %s", m.Name, failureReason(current))
+	} else {
+		event.Message = fmt.Sprintf("%s is up again (%d in %d ms)", m.Name, current.Status, current.Latency)
+	}
+	return event
+}
+
+func failureReason(result Result) string {
+	switch {
+	case result.Error != "":
+		return result.Error
+	case len(result.Failures) > 0:
+		return strings.Join(result.Failures, "; ")
+	}
+	return "check failed"
+}
+
+// wantsEvent reports whether the notifications cover an event.
+func (n *Notifications) wantsEvent(event Event) bool {
+	return n != nil && (event.Down || n.OnRecovery)
+}
+
+// webhookRequest builds the request posting an event to the webhook.
+func (n *Notifications) webhookRequest(event Event) (proxy.ProxyRequest, error) {
+	var body []byte
+	var err error
+	switch n.Format {
+	case FormatSlack:
+		body, err = json.Marshal(map[string]string{"text": event.Message})
+	case FormatDiscord:
+		body, err = json.Marshal(map[string]string{"content": event.Message})
+	default:
+		if n.Payload != nil {
+			body, err = expandPayload(*n.Payload, event)
+		} else {
+			body, err = json.Marshal(event)
+		}
+	}
+	if err != nil {
+		return proxy.ProxyRequest{}, err
+	}
+	text := string(body)
+	return proxy.ProxyRequest{
+		Method:  "POST",
+		URL:     n.WebhookURL,
+		Headers: map[string]string{"Content-Type": "application/json"},
+		Body:    &text,
+	}, nil
+}
+
+// payloadFields are the values a payload template can reference.
+func payloadFields(event Event) map[string]string {
+	state := "up"
+	if event.Down {
+		state = "down"
+	}
+	return map[string]string{
+		"id":        event.MonitorID,
+		"name":      event.Name,
+		"state":     state,
+		"message":   event.Message,
+		"status":    strconv.Itoa(int(event.Result.Status)),
+		"latency":   strconv.FormatUint(event.Result.Latency, 10),
+		"error":     failureReason(event.Result),
+		"timestamp": event.Result.Timestamp.Format(time.RFC3339),
+	}
+}
+
+// expandPayload replaces the {{placeholders}} of a payload template with
+// JSON-escaped values, to be written inside strings, and checks that the
+// result is JSON.
+func expandPayload(template string, event Event) ([]byte, error) {
+	payload := template
+	for name, value := range payloadFields(event) {
+		quoted, _ := json.Marshal(value)
+		payload = strings.ReplaceAll(payload, "{{"+name+"}}", string(quoted[1:len(quoted)-1]))
+	}
+	if !json.Valid([]byte(payload)) {
+		return nil, fmt.Errorf("payload is not valid JSON once expanded")
+	}
+	return []byte(payload), nil
+}
+
+func validateNotifications(n *Notifications) error {
+	if n == nil {
+		return nil
+	}
+	switch n.Format {
+	case "", FormatGeneric, FormatSlack, FormatDiscord:
+	default:
+		return &ValidationError{Message: fmt.Sprintf("Unknown notification format %q; expected generic, slack or discord", n.Format)}
+	}
+	if n.WebhookURL != "" {
+		u, err := url.Parse(n.WebhookURL)
+		if err != nil || (u.Scheme != "http" && u.Scheme != "https") || u.Host == "" {
+			return &ValidationError{Message: "Webhook URL must be an http or https URL"}
+		}
+	}
+	if n.Payload != nil {
+		if n.Format != "" && n.Format != FormatGeneric {
+			return &ValidationError{Message: "A payload template only applies to the generic format"}
+		}
+		sample := Event{Name: "monitor", Down: true, Message: "monitor is down", Result: Result{Timestamp: time.Now()}}
+		if _, err := expandPayload(*n.Payload, sample); err != nil {
+			return &ValidationError{Message: "Invalid payload template: " + err.Error()}
+		}
+	}
+	return nil
+}
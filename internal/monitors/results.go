@@ -0,0 +1,219 @@
+package monitors
+
+import (
+	"encoding/json"
+	"fmt"
+	"sort"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/stats"
+	"zone.digit.tommie/internal/storage"
+)
+
+// ResultStoreName is the storage store holding the results of checks, keyed
+// by monitor ID and time so the keys of a monitor sort chronologically.
+const ResultStoreName = "monitor-results"
+
+// MaxResults is the number of results kept per monitor; older ones are dropped.
+const MaxResults = 10000
+
+// DefaultPageSize is the number of results returned when a query sets no limit.
+const DefaultPageSize = 100
+
+// UptimeWindows are the periods uptime is reported for.
+var UptimeWindows = []time.Duration{24 * time.Hour, 7 * 24 * time.Hour, 30 * 24 * time.Hour}
+
+// Result is the outcome of one check.
+type Result struct {
+	Timestamp time.Time `json:"timestamp"`
+	Up        bool      `json:"up"`               // A response arrived and every assertion and test passed
+	Status    uint16    `json:"status,omitempty"` // Zero when the request failed
+	Latency   uint64    `json:"latency"`          // Total time in milliseconds
+	ErrorCode string    `json:"errorCode,omitempty"`
+	Error     string    `json:"error,omitempty"`
+	Failures  []string  `json:"failures,omitempty"` // Assertions and tests that failed
+}
+
+// ResultPage is a slice of the results of a monitor, newest first.
+type ResultPage struct {
+	Results []Result `json:"results"`
+	Total   int      `json:"total"`
+	Offset  int      `json:"offset"`
+	Limit   int      `json:"limit"`
+}
+
+// Uptime summarizes the checks of a monitor over a period.
+type Uptime struct {
+	Window         string     `json:"window"` // e.g. 24h
+	Checks         int        `json:"checks"`
+	Up             int        `json:"up"`
+	Uptime         *float64   `json:"uptime,omitempty"`         // Percentage of checks up; absent without checks
+	AverageLatency *uint64    `json:"averageLatency,omitempty"` // Milliseconds, over checks that got a response
+	P95Latency     *uint64    `json:"p95Latency,omitempty"`
+	LastFailure    *time.Time `json:"lastFailure,omitempty"`
+}
+
+// AddResult stores the result of a check and drops results beyond MaxResults.
+func AddResult(store storage.Store, id string, result Result) error {
+	data, err := json.Marshal(result)
+	if err != nil {
+		return err
+	}
+	if err := store.Set(ResultStoreName, resultKey(id, result.Timestamp), string(data)); err != nil {
+		return err
+	}
+	keys, err := resultKeys(store, id)
+	if err != nil {
+		return err
+	}
+	for len(keys) > MaxResults {
+		if err := store.Remove(ResultStoreName, keys[0]); err != nil {
+			return err
+		}
+		keys = keys[1:]
+	}
+	return nil
+}
+
+// Results returns a page of the results of a monitor, newest first.
+func Results(store storage.Store, id string, offset, limit int) (*ResultPage, error) {
+	keys, err := resultKeys(store, id)
+	if err != nil {
+		return nil, err
+	}
+	if limit <= 0 {
+		limit = DefaultPageSize
+	}
+	offset = max(offset, 0)
+	page := &ResultPage{Results: []Result{}, Total: len(keys), Offset: offset, Limit: limit}
+	for i := len(keys) - 1 - offset; i >= 0 && len(page.Results) < limit; i-- {
+		result, err := getResult(store, keys[i])
+		if err != nil {
+			return nil, err
+		}
+		if result == nil {
+			continue
+		}
+		page.Results = append(page.Results, *result)
+	}
+	return page, nil
+}
+
+// Stats reports the uptime of a monitor over each of the UptimeWindows
+// ending at now.
+func Stats(store storage.Store, id string, now time.Time) ([]Uptime, error) {
+	keys, err := resultKeys(store, id)
+	if err != nil {
+		return nil, err
+	}
+	oldest := now.Add(-UptimeWindows[len(UptimeWindows)-1])
+	var results []Result
+	for i := len(keys) - 1; i >= 0; i-- {
+		result, err := getResult(store, keys[i])
+		if err != nil {
+			return nil, err
+		}
+		if result == nil {
+			continue
+		}
+		if result.Timestamp.Before(oldest) {
+			break
+		}
+		results = append(results, *result)
+	}
+
+	stats := make([]Uptime, len(UptimeWindows))
+	for i, window := range UptimeWindows {
+		stats[i] = uptime(results, now.Add(-window))
+		stats[i].Window = formatWindow(window)
+	}
+	return stats, nil
+}
+
+// uptime summarizes the results since start; results are newest first.
+func uptime(results []Result, start time.Time) Uptime {
+	var u Uptime
+	var latencies []uint64
+	var total uint64
+	for _, result := range results {
+		if result.Timestamp.Before(start) {
+			break
+		}
+		u.Checks++
+		if result.Up {
+			u.Up++
+		} else if u.LastFailure == nil {
+			timestamp := result.Timestamp
+			u.LastFailure = &timestamp
+		}
+		if result.Status != 0 {
+			latencies = append(latencies, result.Latency)
+			total += result.Latency
+		}
+	}
+	if u.Checks > 0 {
+		percentage := float64(u.Up) * 100 / float64(u.Checks)
+		u.Uptime = &percentage
+	}
+	if len(latencies) > 0 {
+		average := total / uint64(len(latencies))
+		u.AverageLatency = &average
+		p95 := stats.Summarize(latencies).P95
+		u.P95Latency = &p95
+	}
+	return u
+}
+
+func formatWindow(d time.Duration) string {
+	if d%(24*time.Hour) == 0 {
+		return fmt.Sprintf("%dd", d/(24*time.Hour))
+	}
+	return fmt.Sprintf("%dh", d/time.Hour)
+}
+
+func resultKey(id string, t time.Time) string {
+	return fmt.Sprintf("%s/%019d", id, t.UnixNano())
+}
+
+// resultKeys returns the keys of the results of a monitor, oldest first.
+func resultKeys(store storage.Store, id string) ([]string, error) {
+	all, err := store.Keys(ResultStoreName)
+	if err != nil {
+		return nil, err
+	}
+	var keys []string
+	for _, key := range all {
+		if strings.HasPrefix(key, id+"/") {
+			keys = append(keys, key)
+		}
+	}
+	sort.Strings(keys)
+	return keys, nil
+}
+
+// getResult returns a result, or nil if it was dropped since its key was listed.
+func getResult(store storage.Store, key string) (*Result, error) {
+	value, err := store.Get(ResultStoreName, key)
+	if err != nil || value == nil {
+		return nil, err
+	}
+	var result Result
+	if err := json.Unmarshal([]byte(*value), &result); err != nil {
+		return nil, fmt.Errorf("corrupt monitor result %s: %w", key, err)
+	}
+	return &result, nil
+}
+
+func clearResults(store storage.Store, id string) error {
+	keys, err := resultKeys(store, id)
+	if err != nil {
+		return err
+	}
+	for _, key := range keys {
+		if err := store.Remove(ResultStoreName, key); err != nil {
+			return err
+		}
+	}
+	return nil
+}
@@ -0,0 +1,168 @@
+package monitors
+
+import (
+	"context"
+	"log"
+	"strings"
+	"sync"
+	"time"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+// Scheduler runs the checks of enabled monitors in the background. Monitors
+// of every namespace of the root store are scheduled, so each user's checks
+// run against their own collections and environments.
+type Scheduler struct {
+	root    storage.Store
+	execute Executor
+
+	mu       sync.Mutex
+	running  map[monitorKey]context.CancelFunc
+	stopped  bool
+	onNotify func(namespace string, event Event)
+}
+
+type monitorKey struct {
+	namespace string // Empty for the root store
+	id        string
+}
+
+// NewScheduler creates a scheduler executing checks with execute.
+func NewScheduler(root storage.Store, execute Executor) *Scheduler {
+	return &Scheduler{root: root, execute: execute, running: map[monitorKey]context.CancelFunc{}}
+}
+
+// Start schedules the enabled monitors found in the root store and in every
+// namespace of it, returning how many were scheduled.
+func (s *Scheduler) Start() (int, error) {
+	stores, err := s.root.Stores()
+	if err != nil {
+		return 0, err
+	}
+	scheduled := 0
+	for _, name := range stores {
+		namespace, ok := strings.CutSuffix(name, StoreName)
+		if !ok || (namespace != "" && !strings.HasSuffix(namespace, "/")) {
+			continue
+		}
+		namespace = strings.TrimSuffix(namespace, "/")
+		monitors, err := List(s.store(namespace))
+		if err != nil {
+			return scheduled, err
+		}
+		for _, m := range monitors {
+			if m.Enabled {
+				s.Schedule(namespace, m)
+				scheduled++
+			}
+		}
+	}
+	return scheduled, nil
+}
+
+// Stop cancels all checks. Checks in progress finish in the background.
+func (s *Scheduler) Stop() {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	for key, cancel := range s.running {
+		cancel()
+		delete(s.running, key)
+	}
+	s.stopped = true
+}
+
+// Schedule (re)starts the checks of a monitor after it was created or
+// changed, first checking it right away. Disabled monitors are unscheduled.
+func (s *Scheduler) Schedule(namespace string, m Monitor) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	key := monitorKey{namespace, m.ID}
+	if cancel, ok := s.running[key]; ok {
+		cancel()
+		delete(s.running, key)
+	}
+	if !m.Enabled || s.stopped {
+		return
+	}
+	ctx, cancel := context.WithCancel(context.Background())
+	s.running[key] = cancel
+	go s.run(ctx, namespace, m.ID, m.IntervalDuration())
+}
+
+// Unschedule stops the checks of a monitor.
+func (s *Scheduler) Unschedule(namespace, id string) {
+	s.Schedule(namespace, Monitor{ID: id})
+}
+
+// OnNotify sets a function receiving the events of monitors with desktop
+// notifications enabled.
+func (s *Scheduler) OnNotify(fn func(namespace string, event Event)) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	s.onNotify = fn
+}
+
+// Check checks a monitor once, like the scheduled checks, and sends the
+// notifications its result causes.
+func (s *Scheduler) Check(namespace, id string) (*Result, error) {
+	m, result, event, err := check(s.store(namespace), id, s.execute)
+	if err != nil {
+		return nil, err
+	}
+	if event != nil && m.Notifications.wantsEvent(*event) {
+		go s.notify(namespace, *m.Notifications, *event)
+	}
+	return result, nil
+}
+
+// notify posts an event to the webhook of a monitor and passes it on to
+// OnNotify. Webhooks go through the executor, so the proxy's destination
+// policy applies to them, but without a store they leave no history.
+func (s *Scheduler) notify(namespace string, n Notifications, event Event) {
+	if n.Desktop {
+		s.mu.Lock()
+		fn := s.onNotify
+		s.mu.Unlock()
+		if fn != nil {
+			fn(namespace, event)
+		}
+	}
+	if n.WebhookURL == "" {
+		return
+	}
+	request, err := n.webhookRequest(event)
+	if err != nil {
+		log.Printf("Monitor %s notification failed: %v", event.MonitorID, err)
+		return
+	}
+	response := s.execute(nil, request)
+	switch {
+	case !response.Success:
+		log.Printf("Monitor %s webhook failed: %s", event.MonitorID, response.Error.Message)
+	case response.Data.Status >= 400:
+		log.Printf("Monitor %s webhook returned status %d", event.MonitorID, response.Data.Status)
+	}
+}
+
+func (s *Scheduler) run(ctx context.Context, namespace, id string, interval time.Duration) {
+	ticker := time.NewTicker(interval)
+	defer ticker.Stop()
+	for {
+		if _, err := s.Check(namespace, id); err != nil {
+			log.Printf("Monitor %s check failed: %v", id, err)
+		}
+		select {
+		case <-ctx.Done():
+			return
+		case <-ticker.C:
+		}
+	}
+}
+
+func (s *Scheduler) store(namespace string) storage.Store {
+	if namespace == "" {
+		return s.root
+	}
+	return storage.NewNamespaced(s.root, namespace)
+}
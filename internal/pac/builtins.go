@@ -0,0 +1,141 @@
+package pac
+
+import (
+	"context"
+	"encoding/binary"
+	"fmt"
+	"net"
+	"regexp"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/script"
+)
+
+// dnsTimeout bounds each lookup made by dnsResolve, isResolvable and isInNet.
+const dnsTimeout = 2 * time.Second
+
+// now is the clock of the date and time helpers.
+var now = time.Now
+
+// builtins returns the standard PAC helper functions implemented in Go.
+func builtins() map[string]script.Function {
+	return map[string]script.Function{
+		"isPlainHostName": func(args []interface{}) (interface{}, error) {
+			return !strings.Contains(argString(args, 0), "."), nil
+		},
+		"dnsDomainIs": func(args []interface{}) (interface{}, error) {
+			host := strings.ToLower(argString(args, 0))
+			domain := strings.ToLower(argString(args, 1))
+			return strings.HasSuffix(host, domain), nil
+		},
+		"localHostOrDomainIs": func(args []interface{}) (interface{}, error) {
+			host := strings.ToLower(argString(args, 0))
+			hostdom := strings.ToLower(argString(args, 1))
+			if host == hostdom {
+				return true, nil
+			}
+			return !strings.Contains(host, ".") && strings.HasPrefix(hostdom, host+"."), nil
+		},
+		"dnsDomainLevels": func(args []interface{}) (interface{}, error) {
+			return float64(strings.Count(argString(args, 0), ".")), nil
+		},
+		"shExpMatch": func(args []interface{}) (interface{}, error) {
+			return shExpMatch(argString(args, 0), argString(args, 1)), nil
+		},
+		"isResolvable": func(args []interface{}) (interface{}, error) {
+			return resolve(argString(args, 0)) != nil, nil
+		},
+		"dnsResolve": func(args []interface{}) (interface{}, error) {
+			ip := resolve(argString(args, 0))
+			if ip == nil {
+				return nil, nil
+			}
+			return ip.String(), nil
+		},
+		"isInNet": func(args []interface{}) (interface{}, error) {
+			ip := resolve(argString(args, 0))
+			pattern := net.ParseIP(argString(args, 1)).To4()
+			mask := net.ParseIP(argString(args, 2)).To4()
+			if ip == nil || pattern == nil || mask == nil {
+				return false, nil
+			}
+			m := net.IPMask(mask)
+			return ip.Mask(m).Equal(pattern.Mask(m)), nil
+		},
+		"myIpAddress": func(args []interface{}) (interface{}, error) {
+			return myIPAddress(), nil
+		},
+		"convert_addr": func(args []interface{}) (interface{}, error) {
+			ip := net.ParseIP(argString(args, 0)).To4()
+			if ip == nil {
+				return float64(0), nil
+			}
+			return float64(binary.BigEndian.Uint32(ip)), nil
+		},
+		"alert": func(args []interface{}) (interface{}, error) {
+			return nil, nil
+		},
+		"__pacNow": func(args []interface{}) (interface{}, error) {
+			return float64(now().UnixMilli()), nil
+		},
+	}
+}
+
+// argString returns argument i as a string, with missing, null and
+// undefined arguments as the empty string.
+func argString(args []interface{}, i int) string {
+	if i >= len(args) || args[i] == nil {
+		return ""
+	}
+	if s, ok := args[i].(string); ok {
+		return s
+	}
+	return fmt.Sprint(args[i])
+}
+
+// shExpMatch matches a shell expression where * and ? are the only wildcards.
+func shExpMatch(str, pattern string) bool {
+	expr := regexp.QuoteMeta(pattern)
+	expr = strings.ReplaceAll(expr, `\*`, ".*")
+	expr = strings.ReplaceAll(expr, `\?`, ".")
+	re, err := regexp.Compile("^" + expr + "$")
+	if err != nil {
+		return false
+	}
+	return re.MatchString(str)
+}
+
+// resolve returns the first IPv4 address of host, or nil.
+func resolve(host string) net.IP {
+	if ip := net.ParseIP(host); ip != nil {
+		return ip.To4()
+	}
+
+	ctx, cancel := context.WithTimeout(context.Background(), dnsTimeout)
+	defer cancel()
+	addrs, err := net.DefaultResolver.LookupIPAddr(ctx, host)
+	if err != nil {
+		return nil
+	}
+	for _, addr := range addrs {
+		if ip4 := addr.IP.To4(); ip4 != nil {
+			return ip4
+		}
+	}
+	return nil
+}
+
+// myIPAddress returns the local address used for outbound traffic.
+func myIPAddress() string {
+	// Connecting a UDP socket selects a route without sending packets
+	conn, err := net.Dial("udp4", "192.0.2.1:80")
+	if err != nil {
+		return "127.0.0.1"
+	}
+	defer conn.Close()
+	if addr, ok := conn.LocalAddr().(*net.UDPAddr); ok {
+		return addr.IP.String()
+	}
+	return "127.0.0.1"
+}
@@ -0,0 +1,171 @@
+// Package pac evaluates proxy auto-config (PAC) scripts on the embedded
+// JavaScript engine of package script, with the standard PAC helpers,
+// including the date and time helpers weekdayRange, dateRange and timeRange.
+//
+// Each evaluation runs in a runtime of its own, taken from a pool, so
+// requests resolving hosts through dnsResolve or isInNet do not wait on
+// each other.
+package pac
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"os"
+	"strings"
+	"sync"
+	"time"
+
+	"zone.digit.tommie/internal/script"
+)
+
+// maxScriptSize limits the size of a PAC file.
+const maxScriptSize = 1 << 20
+
+// limits bound an evaluation. The timeout leaves room for several DNS
+// lookups, each bounded by dnsTimeout.
+var limits = script.Limits{
+	MaxSourceBytes: maxScriptSize,
+	MaxCallDepth:   1000,
+	MaxMemoryBytes: 64 << 20,
+	MaxOutputBytes: 64 << 10,
+	Timeout:        10 * time.Second,
+}
+
+// Script is a parsed PAC file. It is safe for concurrent use.
+type Script struct {
+	program  *script.Program
+	runtimes sync.Pool // Of *script.Runtime with the script loaded
+}
+
+// Parse compiles a PAC script and runs its top-level statements.
+func Parse(src string) (*Script, error) {
+	program, err := script.Compile(src, limits)
+	if err != nil {
+		return nil, fmt.Errorf("PAC syntax error: %w", err)
+	}
+
+	s := &Script{program: program}
+	r, err := s.newRuntime()
+	if err != nil {
+		return nil, err
+	}
+	if !r.Defined("FindProxyForURL") {
+		return nil, errors.New("PAC script does not define FindProxyForURL")
+	}
+	s.runtimes.Put(r)
+	return s, nil
+}
+
+// newRuntime creates a runtime with the PAC helpers and runs the script in it.
+func (s *Script) newRuntime() (*script.Runtime, error) {
+	r := script.NewRuntime(limits)
+	for name, fn := range builtins() {
+		if err := r.Define(name, fn); err != nil {
+			return nil, err
+		}
+	}
+	if _, err := r.Run(context.Background(), prelude); err != nil {
+		return nil, fmt.Errorf("PAC evaluation error: %w", err)
+	}
+	if _, err := r.Run(context.Background(), s.program); err != nil {
+		return nil, fmt.Errorf("PAC evaluation error: %w", err)
+	}
+	return r, nil
+}
+
+// Load reads a PAC script from an http(s) URL, a file:// URL or a file path.
+func Load(location string) (*Script, error) {
+	var src []byte
+	var err error
+
+	switch {
+	case strings.HasPrefix(location, "http://"), strings.HasPrefix(location, "https://"):
+		src, err = fetch(location)
+	default:
+		src, err = os.ReadFile(strings.TrimPrefix(location, "file://"))
+	}
+	if err != nil {
+		return nil, fmt.Errorf("failed to load PAC file %s: %w", location, err)
+	}
+	return Parse(string(src))
+}
+
+func fetch(location string) ([]byte, error) {
+	client := &http.Client{Timeout: 10 * time.Second}
+	resp, err := client.Get(location)
+	if err != nil {
+		return nil, err
+	}
+	defer resp.Body.Close()
+
+	if resp.StatusCode != http.StatusOK {
+		return nil, fmt.Errorf("unexpected status %s", resp.Status)
+	}
+	return io.ReadAll(io.LimitReader(resp.Body, maxScriptSize))
+}
+
+// FindProxyForURL calls the script's FindProxyForURL function and returns its result.
+func (s *Script) FindProxyForURL(url, host string) (string, error) {
+	r, ok := s.runtimes.Get().(*script.Runtime)
+	if !ok {
+		var err error
+		if r, err = s.newRuntime(); err != nil {
+			return "", err
+		}
+	}
+
+	result, err := r.Call(context.Background(), "FindProxyForURL", url, host)
+	if err != nil {
+		// The globals of an interrupted script may be left half-updated
+		return "", fmt.Errorf("PAC evaluation error: %w", err)
+	}
+	s.runtimes.Put(r)
+
+	switch result := result.(type) {
+	case nil:
+		return "", errors.New("PAC evaluation error: FindProxyForURL returned no value")
+	case string:
+		return result, nil
+	default:
+		return "", fmt.Errorf("PAC evaluation error: FindProxyForURL returned %v instead of a string", result)
+	}
+}
+
+// Directive is one entry of a FindProxyForURL result, e.g. "PROXY host:8080".
+type Directive struct {
+	Type string // DIRECT, PROXY, HTTP, HTTPS, SOCKS, SOCKS4 or SOCKS5
+	Host string // host:port, empty for DIRECT
+}
+
+// ParseResult splits a FindProxyForURL result into its directives.
+func ParseResult(result string) ([]Directive, error) {
+	var directives []Directive
+	for _, entry := range strings.Split(result, ";") {
+		fields := strings.Fields(entry)
+		if len(fields) == 0 {
+			continue
+		}
+		d := Directive{Type: strings.ToUpper(fields[0])}
+		switch d.Type {
+		case "DIRECT":
+			if len(fields) != 1 {
+				return nil, fmt.Errorf("invalid PAC directive %q", strings.TrimSpace(entry))
+			}
+		case "PROXY", "HTTP", "HTTPS", "SOCKS", "SOCKS4", "SOCKS5":
+			if len(fields) != 2 {
+				return nil, fmt.Errorf("invalid PAC directive %q", strings.TrimSpace(entry))
+			}
+			d.Host = fields[1]
+		default:
+			return nil, fmt.Errorf("unknown PAC directive %q", fields[0])
+		}
+		directives = append(directives, d)
+	}
+	if len(directives) == 0 {
+		return nil, errors.New("empty PAC result")
+	}
+	return directives, nil
+}
@@ -0,0 +1,239 @@
+package pac
+
+import (
+	"reflect"
+	"strings"
+	"sync"
+	"testing"
+	"time"
+)
+
+// evaluate returns the string value of a JavaScript expression evaluated
+// within FindProxyForURL.
+func evaluate(t *testing.T, expr string) string {
+	t.Helper()
+	s, err := Parse("function FindProxyForURL(url, host) { return String(" + expr + ") }")
+	if err != nil {
+		t.Fatalf("Parse: %v", err)
+	}
+	result, err := s.FindProxyForURL("http://example.com/", "example.com")
+	if err != nil {
+		t.Fatalf("FindProxyForURL: %v", err)
+	}
+	return result
+}
+
+func TestBuiltins(t *testing.T) {
+	tests := []struct {
+		expr string
+		want string
+	}{
+		{`isPlainHostName("www")`, "true"},
+		{`isPlainHostName("www.netscape.com")`, "false"},
+		{`dnsDomainIs("www.netscape.com", ".netscape.com")`, "true"},
+		{`dnsDomainIs("www", ".netscape.com")`, "false"},
+		{`localHostOrDomainIs("www.netscape.com", "www.netscape.com")`, "true"},
+		{`localHostOrDomainIs("www", "www.netscape.com")`, "true"},
+		{`localHostOrDomainIs("www.mcom.com", "www.netscape.com")`, "false"},
+		{`dnsDomainLevels("www.netscape.com")`, "2"},
+		{`shExpMatch("http://home.netscape.com/people/ari/index.html", "*/ari/*")`, "true"},
+		{`shExpMatch("http://home.netscape.com/people/montulli/index.html", "*/ari/*")`, "false"},
+		{`shExpMatch("www.example.com", "www.?xample.*")`, "true"},
+		{`isInNet("198.95.249.79", "198.95.249.79", "255.255.255.255")`, "true"},
+		{`isInNet("198.95.1.1", "198.95.0.0", "255.255.0.0")`, "true"},
+		{`isInNet("10.0.0.1", "198.95.0.0", "255.255.0.0")`, "false"},
+		{`dnsResolve("127.0.0.1")`, "127.0.0.1"},
+		{`isResolvable("127.0.0.1")`, "true"},
+		{`convert_addr("104.16.41.2")`, "1745889538"},
+		{`dnsDomainIs(undefined, "")`, "true"},
+	}
+	for _, tt := range tests {
+		if got := evaluate(t, tt.expr); got != tt.want {
+			t.Errorf("%s: got %s, want %s", tt.expr, got, tt.want)
+		}
+	}
+}
+
+func TestDateTimeHelpers(t *testing.T) {
+	// Wednesday 17 January 2024, 14:30:15 UTC
+	fixed := time.Date(2024, time.January, 17, 14, 30, 15, 0, time.UTC)
+	now = func() time.Time { return fixed }
+	t.Cleanup(func() { now = time.Now })
+
+	tests := []struct {
+		expr string
+		want string
+	}{
+		{`weekdayRange("WED", "GMT")`, "true"},
+		{`weekdayRange("MON", "FRI", "GMT")`, "true"},
+		{`weekdayRange("SAT", "GMT")`, "false"},
+		{`weekdayRange("FRI", "MON", "GMT")`, "false"},
+		{`weekdayRange("SAT", "THU", "GMT")`, "true"},
+		{`weekdayRange("XYZ", "GMT")`, "false"},
+		{`dateRange(17, "GMT")`, "true"},
+		{`dateRange(18, 31, "GMT")`, "false"},
+		{`dateRange("JAN", "GMT")`, "true"},
+		{`dateRange("JAN", "MAR", "GMT")`, "true"},
+		{`dateRange("NOV", "FEB", "GMT")`, "true"},
+		{`dateRange(2023, "GMT")`, "false"},
+		{`dateRange(2020, 2030, "GMT")`, "true"},
+		{`dateRange(1, "JAN", 15, "JAN", "GMT")`, "false"},
+		{`dateRange(15, "DEC", 20, "JAN", "GMT")`, "true"},
+		{`dateRange("DEC", 2023, "JAN", 2024, "GMT")`, "true"},
+		{`dateRange(1, "DEC", 2023, 16, "JAN", 2024, "GMT")`, "false"},
+		{`dateRange(1, "DEC", 2023, 17, "JAN", 2024, "GMT")`, "true"},
+		{`dateRange(1, "JAN", "GMT")`, "false"},
+		{`timeRange(14, "GMT")`, "true"},
+		{`timeRange(9, 17, "GMT")`, "true"},
+		{`timeRange(22, 6, "GMT")`, "false"},
+		{`timeRange(12, 6, "GMT")`, "true"},
+		{`timeRange(14, 30, 14, 31, "GMT")`, "true"},
+		{`timeRange(14, 0, 14, 29, "GMT")`, "false"},
+		{`timeRange(14, 30, 0, 14, 30, 10, "GMT")`, "false"},
+		{`timeRange(14, 30, 0, 14, 30, 20, "GMT")`, "true"},
+		{`timeRange("noon", "GMT")`, "false"},
+	}
+	for _, tt := range tests {
+		if got := evaluate(t, tt.expr); got != tt.want {
+			t.Errorf("%s: got %s, want %s", tt.expr, got, tt.want)
+		}
+	}
+}
+
+func TestFindProxyForURL(t *testing.T) {
+	src := `
+var direct = ["localhost", /\.internal$/];
+
+function FindProxyForURL(url, host) {
+	for (var i = 0; i < direct.length; i++) {
+		var rule = direct[i];
+		if (typeof rule === "string" ? host === rule : rule.test(host)) {
+			return "DIRECT";
+		}
+	}
+	if (url.substring(0, 6) === "https:") {
+		return "HTTPS secure.example.com:443; DIRECT";
+	}
+	return "PROXY proxy.example.com:8080";
+}`
+	s, err := Parse(src)
+	if err != nil {
+		t.Fatalf("Parse: %v", err)
+	}
+
+	tests := []struct {
+		url, host string
+		want      string
+	}{
+		{"http://localhost/", "localhost", "DIRECT"},
+		{"http://wiki.internal/", "wiki.internal", "DIRECT"},
+		{"https://example.com/", "example.com", "HTTPS secure.example.com:443; DIRECT"},
+		{"http://example.com/", "example.com", "PROXY proxy.example.com:8080"},
+	}
+	for _, tt := range tests {
+		got, err := s.FindProxyForURL(tt.url, tt.host)
+		if err != nil {
+			t.Fatalf("FindProxyForURL(%s): %v", tt.url, err)
+		}
+		if got != tt.want {
+			t.Errorf("FindProxyForURL(%s): got %q, want %q", tt.url, got, tt.want)
+		}
+	}
+}
+
+func TestFindProxyForURLConcurrent(t *testing.T) {
+	// A slow evaluation, as one waiting on DNS, must not hold up others
+	s, err := Parse(`
+function FindProxyForURL(url, host) {
+	if (host === "slow") {
+		var end = Date.now() + 500;
+		while (Date.now() < end) {}
+	}
+	return "DIRECT";
+}`)
+	if err != nil {
+		t.Fatalf("Parse: %v", err)
+	}
+
+	var wg sync.WaitGroup
+	wg.Add(1)
+	go func() {
+		defer wg.Done()
+		s.FindProxyForURL("http://slow/", "slow")
+	}()
+	time.Sleep(50 * time.Millisecond)
+
+	started := time.Now()
+	if _, err := s.FindProxyForURL("http://fast/", "fast"); err != nil {
+		t.Fatal(err)
+	}
+	if elapsed := time.Since(started); elapsed > 250*time.Millisecond {
+		t.Errorf("took %s while another evaluation was running", elapsed)
+	}
+	wg.Wait()
+}
+
+func TestParseErrors(t *testing.T) {
+	tests := []struct {
+		name string
+		src  string
+		want string
+	}{
+		{"syntax", "function FindProxyForURL(url, host) { return ", "PAC syntax error"},
+		{"missing function", "var proxy = 'DIRECT';", "does not define FindProxyForURL"},
+		{"top-level exception", "missing(); function FindProxyForURL() { return 'DIRECT' }", "PAC evaluation error"},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			_, err := Parse(tt.src)
+			if err == nil || !strings.Contains(err.Error(), tt.want) {
+				t.Errorf("got %v, want an error containing %q", err, tt.want)
+			}
+		})
+	}
+}
+
+func TestFindProxyForURLErrors(t *testing.T) {
+	tests := []struct {
+		name string
+		body string
+		want string
+	}{
+		{"no value", "", "returned no value"},
+		{"not a string", "return 1", "instead of a string"},
+		{"exception", "throw new Error('unreachable')", "Error: unreachable"},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			s, err := Parse("function FindProxyForURL(url, host) { " + tt.body + " }")
+			if err != nil {
+				t.Fatalf("Parse: %v", err)
+			}
+			_, err = s.FindProxyForURL("http://example.com/", "example.com")
+			if err == nil || !strings.Contains(err.Error(), tt.want) {
+				t.Errorf("got %v, want an error containing %q", err, tt.want)
+			}
+		})
+	}
+}
+
+func TestParseResult(t *testing.T) {
+	got, err := ParseResult("PROXY proxy.example.com:8080; socks5 socks.example.com:1080;DIRECT")
+	if err != nil {
+		t.Fatal(err)
+	}
+	want := []Directive{
+		{Type: "PROXY", Host: "proxy.example.com:8080"},
+		{Type: "SOCKS5", Host: "socks.example.com:1080"},
+		{Type: "DIRECT"},
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Errorf("got %+v, want %+v", got, want)
+	}
+
+	for _, result := range []string{"", " ; ", "PROXY", "DIRECT host:1", "FTP host:21"} {
+		if _, err := ParseResult(result); err == nil {
+			t.Errorf("ParseResult(%q): got no error", result)
+		}
+	}
+}
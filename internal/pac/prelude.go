@@ -0,0 +1,135 @@
+package pac
+
+import "zone.digit.tommie/internal/script"
+
+// preludeSrc defines the date and time helpers of the PAC standard in
+// JavaScript. Each takes an optional trailing "GMT" argument; without it the
+// local time zone applies. Ranges that wrap around, such as
+// weekdayRange("FRI", "MON"), are supported.
+const preludeSrc = `
+var __pacDays = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+var __pacMonths = ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+
+function __pacArgs(args) {
+	var list = Array.prototype.slice.call(args);
+	var gmt = list.length > 0 && list[list.length - 1] === "GMT";
+	if (gmt) {
+		list.pop();
+	}
+	var date = new Date(__pacNow());
+	return {
+		list: list,
+		day: gmt ? date.getUTCDate() : date.getDate(),
+		weekday: gmt ? date.getUTCDay() : date.getDay(),
+		month: gmt ? date.getUTCMonth() : date.getMonth(),
+		year: gmt ? date.getUTCFullYear() : date.getFullYear(),
+		seconds: gmt
+			? date.getUTCHours() * 3600 + date.getUTCMinutes() * 60 + date.getUTCSeconds()
+			: date.getHours() * 3600 + date.getMinutes() * 60 + date.getSeconds()
+	};
+}
+
+function __pacInRange(value, from, to) {
+	return from <= to ? from <= value && value <= to : value >= from || value <= to;
+}
+
+function weekdayRange() {
+	var now = __pacArgs(arguments);
+	if (now.list.length < 1 || now.list.length > 2) {
+		return false;
+	}
+	var from = __pacDays.indexOf(String(now.list[0]).toUpperCase());
+	var to = now.list.length > 1 ? __pacDays.indexOf(String(now.list[1]).toUpperCase()) : from;
+	if (from < 0 || to < 0) {
+		return false;
+	}
+	return __pacInRange(now.weekday, from, to);
+}
+
+// __pacDate reads day, month and year fields into a key that orders dates
+// by the fields present, and a mask naming those fields.
+function __pacDate(fields) {
+	var date = {key: 0, mask: ""};
+	for (var i = 0; i < fields.length; i++) {
+		var field = fields[i];
+		var month = __pacMonths.indexOf(String(field).toUpperCase());
+		if (month >= 0) {
+			date.key += month * 100;
+			date.mask += "m";
+		} else if (typeof field === "number" && field >= 1 && field <= 31) {
+			date.key += field;
+			date.mask += "d";
+		} else if (typeof field === "number" && field > 31) {
+			date.key += field * 10000;
+			date.mask += "y";
+		} else {
+			return null;
+		}
+	}
+	return date;
+}
+
+function dateRange() {
+	var now = __pacArgs(arguments);
+	var n = now.list.length;
+	if (n !== 1 && n !== 2 && n !== 4 && n !== 6) {
+		return false;
+	}
+	var half = n === 1 ? 1 : n / 2;
+	var from = __pacDate(now.list.slice(0, half));
+	var to = n === 1 ? from : __pacDate(now.list.slice(half));
+	if (from === null || to === null || from.mask !== to.mask) {
+		return false;
+	}
+	var today = 0;
+	if (from.mask.indexOf("d") >= 0) {
+		today += now.day;
+	}
+	if (from.mask.indexOf("m") >= 0) {
+		today += now.month * 100;
+	}
+	if (from.mask.indexOf("y") >= 0) {
+		today += now.year * 10000;
+	}
+	return __pacInRange(today, from.key, to.key);
+}
+
+function timeRange() {
+	var now = __pacArgs(arguments);
+	var t = now.list;
+	var from, to;
+	switch (t.length) {
+	case 1:
+		from = t[0] * 3600;
+		to = t[0] * 3600 + 3599;
+		break;
+	case 2:
+		from = t[0] * 3600;
+		to = t[1] * 3600 + 3599;
+		break;
+	case 4:
+		from = t[0] * 3600 + t[1] * 60;
+		to = t[2] * 3600 + t[3] * 60 + 59;
+		break;
+	case 6:
+		from = t[0] * 3600 + t[1] * 60 + t[2];
+		to = t[3] * 3600 + t[4] * 60 + t[5];
+		break;
+	default:
+		return false;
+	}
+	if (isNaN(from) || isNaN(to)) {
+		return false;
+	}
+	return __pacInRange(now.seconds, from, to);
+}
+`
+
+// prelude is preludeSrc compiled once for all runtimes.
+var prelude = func() *script.Program {
+	program, err := script.Compile(preludeSrc, limits)
+	if err != nil {
+		panic(err)
+	}
+	return program
+}()
@@ -0,0 +1,222 @@
+// Package plugins runs WebAssembly plugins that process requests and
+// responses, such as custom auth signers, body decoders and analyzers, on the
+// embedded wazero runtime.
+//
+// A plugin is a .wasm module exporting its memory, an allocator and one or
+// both hooks:
+//
+//	alloc(size i32) i32                  reserves size bytes for the input
+//	on_request(ptr i32, len i32) i64     processes a request
+//	on_response(ptr i32, len i32) i64    processes a response
+//
+// The host writes the JSON input of a hook into memory reserved with alloc
+// and calls the hook, which returns the address of its JSON output in the
+// upper 32 bits and its length in the lower ones, or 0 for no output.
+//
+// Every call runs in a fresh instance of the module, so plugins keep no
+// state between calls. WASI is available without its capabilities: no
+// files, environment variables or arguments, and output is discarded. Only
+// the clocks and random numbers are real. Plugins cannot reach the network,
+// and each call is bounded in memory and time.
+package plugins
+
+import (
+	"context"
+	"crypto/rand"
+	"errors"
+	"fmt"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+	"time"
+
+	"github.com/tetratelabs/wazero"
+	"github.com/tetratelabs/wazero/api"
+	"github.com/tetratelabs/wazero/imports/wasi_snapshot_preview1"
+)
+
+// Hooks a plugin may export.
+const (
+	OnRequest  = "on_request"
+	OnResponse = "on_response"
+)
+
+// Limits bound the resources of a plugin call.
+type Limits struct {
+	MemoryPages uint32        // Linear memory in 64 KiB pages
+	Timeout     time.Duration // Wall-clock time per call
+}
+
+// DefaultLimits allow 16 MiB of memory and a second per call.
+var DefaultLimits = Limits{
+	MemoryPages: 256,
+	Timeout:     time.Second,
+}
+
+// Host holds the plugins loaded from a directory. It is safe for concurrent use.
+type Host struct {
+	runtime wazero.Runtime
+	limits  Limits
+	plugins []*Plugin
+}
+
+// Plugin is a compiled plugin module.
+type Plugin struct {
+	Name string // File name without .wasm
+
+	host   *Host
+	module wazero.CompiledModule
+	hooks  map[string]bool
+}
+
+// Load compiles every .wasm file of dir, in order of name.
+func Load(ctx context.Context, dir string, limits Limits) (*Host, error) {
+	entries, err := os.ReadDir(dir)
+	if err != nil {
+		return nil, fmt.Errorf("failed to read plugin directory: %w", err)
+	}
+
+	config := wazero.NewRuntimeConfig().
+		WithMemoryLimitPages(limits.MemoryPages).
+		WithCloseOnContextDone(true)
+	h := &Host{runtime: wazero.NewRuntimeWithConfig(ctx, config), limits: limits}
+	if _, err := wasi_snapshot_preview1.Instantiate(ctx, h.runtime); err != nil {
+		h.Close(ctx)
+		return nil, err
+	}
+
+	sort.Slice(entries, func(i, j int) bool { return entries[i].Name() < entries[j].Name() })
+	for _, entry := range entries {
+		if entry.IsDir() || filepath.Ext(entry.Name()) != ".wasm" {
+			continue
+		}
+		binary, err := os.ReadFile(filepath.Join(dir, entry.Name()))
+		if err != nil {
+			h.Close(ctx)
+			return nil, fmt.Errorf("failed to read plugin %s: %w", entry.Name(), err)
+		}
+		plugin, err := h.compile(ctx, strings.TrimSuffix(entry.Name(), ".wasm"), binary)
+		if err != nil {
+			h.Close(ctx)
+			return nil, err
+		}
+		h.plugins = append(h.plugins, plugin)
+	}
+	return h, nil
+}
+
+// compile compiles a module and checks that it implements the plugin interface.
+func (h *Host) compile(ctx context.Context, name string, binary []byte) (*Plugin, error) {
+	module, err := h.runtime.CompileModule(ctx, binary)
+	if err != nil {
+		return nil, fmt.Errorf("invalid plugin %s: %w", name, err)
+	}
+	p := &Plugin{Name: name, host: h, module: module, hooks: make(map[string]bool)}
+
+	if _, ok := module.ExportedMemories()["memory"]; !ok {
+		return nil, fmt.Errorf("invalid plugin %s: memory is not exported", name)
+	}
+	functions := module.ExportedFunctions()
+	if !signature(functions["alloc"], []api.ValueType{api.ValueTypeI32}, []api.ValueType{api.ValueTypeI32}) {
+		return nil, fmt.Errorf("invalid plugin %s: alloc(i32) i32 is not exported", name)
+	}
+	for _, hook := range []string{OnRequest, OnResponse} {
+		definition, ok := functions[hook]
+		if !ok {
+			continue
+		}
+		if !signature(definition, []api.ValueType{api.ValueTypeI32, api.ValueTypeI32}, []api.ValueType{api.ValueTypeI64}) {
+			return nil, fmt.Errorf("invalid plugin %s: %s must be (i32, i32) i64", name, hook)
+		}
+		p.hooks[hook] = true
+	}
+	if len(p.hooks) == 0 {
+		return nil, fmt.Errorf("invalid plugin %s: exports neither %s nor %s", name, OnRequest, OnResponse)
+	}
+	return p, nil
+}
+
+func signature(definition api.FunctionDefinition, params, results []api.ValueType) bool {
+	if definition == nil {
+		return false
+	}
+	return string(definition.ParamTypes()) == string(params) && string(definition.ResultTypes()) == string(results)
+}
+
+// Plugins returns the plugins loaded, in order of name.
+func (h *Host) Plugins() []*Plugin {
+	if h == nil {
+		return nil
+	}
+	return h.plugins
+}
+
+// Close releases the runtime and the compiled plugins.
+func (h *Host) Close(ctx context.Context) error {
+	return h.runtime.Close(ctx)
+}
+
+// Has reports whether the plugin exports a hook.
+func (p *Plugin) Has(hook string) bool {
+	return p.hooks[hook]
+}
+
+// Call runs a hook of the plugin in a fresh instance with input as its JSON
+// input, returning its output; nil when it returned none.
+func (p *Plugin) Call(ctx context.Context, hook string, input []byte) ([]byte, error) {
+	if !p.hooks[hook] {
+		return nil, fmt.Errorf("plugin %s does not export %s", p.Name, hook)
+	}
+	ctx, cancel := context.WithTimeout(ctx, p.host.limits.Timeout)
+	defer cancel()
+
+	output, err := p.call(ctx, hook, input)
+	if err != nil && errors.Is(ctx.Err(), context.DeadlineExceeded) {
+		return nil, fmt.Errorf("plugin %s exceeded the time limit of %s", p.Name, p.host.limits.Timeout)
+	}
+	if err != nil {
+		return nil, fmt.Errorf("plugin %s failed in %s: %w", p.Name, hook, err)
+	}
+	return output, nil
+}
+
+func (p *Plugin) call(ctx context.Context, hook string, input []byte) ([]byte, error) {
+	// An anonymous instance, so calls may run concurrently
+	config := wazero.NewModuleConfig().
+		WithName("").
+		WithStartFunctions("_initialize").
+		WithSysWalltime().
+		WithSysNanotime().
+		WithRandSource(rand.Reader)
+	instance, err := p.host.runtime.InstantiateModule(ctx, p.module, config)
+	if err != nil {
+		return nil, err
+	}
+	defer instance.Close(context.Background())
+
+	results, err := instance.ExportedFunction("alloc").Call(ctx, uint64(len(input)))
+	if err != nil {
+		return nil, err
+	}
+	ptr := uint32(results[0])
+	memory := instance.Memory()
+	if !memory.Write(ptr, input) {
+		return nil, fmt.Errorf("alloc returned %d, outside memory for %d bytes", ptr, len(input))
+	}
+
+	results, err = instance.ExportedFunction(hook).Call(ctx, uint64(ptr), uint64(len(input)))
+	if err != nil {
+		return nil, err
+	}
+	if results[0] == 0 {
+		return nil, nil
+	}
+	outPtr, outLen := uint32(results[0]>>32), uint32(results[0])
+	output, ok := memory.Read(outPtr, outLen)
+	if !ok {
+		return nil, fmt.Errorf("output of %d bytes at %d is outside memory", outLen, outPtr)
+	}
+	// The view dies with the instance
+	return append([]byte(nil), output...), nil
+}
@@ -0,0 +1,147 @@
+package plugins_test
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"sync"
+	"testing"
+	"time"
+
+	"zone.digit.tommie/internal/plugins"
+	"zone.digit.tommie/internal/testkit"
+)
+
+// load writes modules to a directory, by file name, and loads it.
+func load(t *testing.T, limits plugins.Limits, modules map[string][]byte) (*plugins.Host, error) {
+	t.Helper()
+	dir := t.TempDir()
+	for name, module := range modules {
+		if err := os.WriteFile(filepath.Join(dir, name), module, 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+	host, err := plugins.Load(context.Background(), dir, limits)
+	if err == nil {
+		t.Cleanup(func() { host.Close(context.Background()) })
+	}
+	return host, err
+}
+
+func TestCall(t *testing.T) {
+	host, err := load(t, plugins.DefaultLimits, map[string][]byte{
+		"b-echo.wasm":     testkit.Plugin(1, testkit.WasmHook{Name: plugins.OnRequest, Echo: true}),
+		"a-analyzer.wasm": testkit.Plugin(1, testkit.WasmHook{Name: plugins.OnResponse, Output: `{"annotations":{"ok":"yes"}}`}, testkit.WasmHook{Name: plugins.OnRequest}),
+		"README.md":       []byte("not a plugin"),
+	})
+	if err != nil {
+		t.Fatalf("Load: %v", err)
+	}
+
+	loaded := host.Plugins()
+	if len(loaded) != 2 || loaded[0].Name != "a-analyzer" || loaded[1].Name != "b-echo" {
+		t.Fatalf("got %d plugins, want a-analyzer and b-echo in order", len(loaded))
+	}
+	analyzer, echo := loaded[0], loaded[1]
+	if !analyzer.Has(plugins.OnResponse) || !analyzer.Has(plugins.OnRequest) || echo.Has(plugins.OnResponse) {
+		t.Error("Has does not report the hooks exported")
+	}
+
+	output, err := echo.Call(context.Background(), plugins.OnRequest, []byte(`{"request":{}}`))
+	if err != nil || string(output) != `{"request":{}}` {
+		t.Errorf("echo: got %q, %v", output, err)
+	}
+	output, err = analyzer.Call(context.Background(), plugins.OnResponse, []byte(`{}`))
+	if err != nil || string(output) != `{"annotations":{"ok":"yes"}}` {
+		t.Errorf("analyzer: got %q, %v", output, err)
+	}
+	if output, err := analyzer.Call(context.Background(), plugins.OnRequest, []byte(`{}`)); err != nil || output != nil {
+		t.Errorf("no output: got %q, %v", output, err)
+	}
+	if _, err := echo.Call(context.Background(), plugins.OnResponse, nil); err == nil {
+		t.Error("got no error calling a hook that is not exported")
+	}
+}
+
+func TestCallConcurrent(t *testing.T) {
+	host, err := load(t, plugins.DefaultLimits, map[string][]byte{
+		"echo.wasm": testkit.Plugin(1, testkit.WasmHook{Name: plugins.OnRequest, Echo: true}),
+	})
+	if err != nil {
+		t.Fatalf("Load: %v", err)
+	}
+	echo := host.Plugins()[0]
+
+	var wg sync.WaitGroup
+	for i := 0; i < 8; i++ {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			input := strings.Repeat("x", 100*(i+1))
+			if output, err := echo.Call(context.Background(), plugins.OnRequest, []byte(input)); err != nil || string(output) != input {
+				t.Errorf("call %d: got %d bytes, %v", i, len(output), err)
+			}
+		}()
+	}
+	wg.Wait()
+}
+
+func TestCallErrors(t *testing.T) {
+	limits := plugins.DefaultLimits
+	limits.Timeout = 50 * time.Millisecond
+	host, err := load(t, limits, map[string][]byte{
+		"spin.wasm": testkit.Plugin(1, testkit.WasmHook{Name: plugins.OnRequest, Spin: true}),
+		"trap.wasm": testkit.Plugin(1, testkit.WasmHook{Name: plugins.OnRequest, Trap: true}),
+	})
+	if err != nil {
+		t.Fatalf("Load: %v", err)
+	}
+	spin, trap := host.Plugins()[0], host.Plugins()[1]
+
+	started := time.Now()
+	if _, err := spin.Call(context.Background(), plugins.OnRequest, nil); err == nil || !strings.Contains(err.Error(), "time limit") {
+		t.Errorf("spin: got %v, want the time limit exceeded", err)
+	}
+	if elapsed := time.Since(started); elapsed > time.Second {
+		t.Errorf("took %s to stop after 50ms", elapsed)
+	}
+	if _, err := trap.Call(context.Background(), plugins.OnRequest, nil); err == nil || !strings.Contains(err.Error(), "plugin trap failed in on_request") {
+		t.Errorf("trap: got %v", err)
+	}
+
+	// An input larger than the memory of the plugin
+	echo, err := load(t, plugins.DefaultLimits, map[string][]byte{
+		"echo.wasm": testkit.Plugin(1, testkit.WasmHook{Name: plugins.OnRequest, Echo: true}),
+	})
+	if err != nil {
+		t.Fatalf("Load: %v", err)
+	}
+	if _, err := echo.Plugins()[0].Call(context.Background(), plugins.OnRequest, make([]byte, 64<<10)); err == nil {
+		t.Error("got no error for an input outside memory")
+	}
+}
+
+func TestLoadErrors(t *testing.T) {
+	tests := []struct {
+		name   string
+		module []byte
+		want   string
+	}{
+		{"not wasm", []byte("not a module"), "invalid plugin broken"},
+		{"no hooks", testkit.Plugin(1), "exports neither"},
+		{"memory over the limit", testkit.Plugin(plugins.DefaultLimits.MemoryPages+1, testkit.WasmHook{Name: plugins.OnRequest}), "invalid plugin broken"},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			_, err := load(t, plugins.DefaultLimits, map[string][]byte{"broken.wasm": tt.module})
+			if err == nil || !strings.Contains(err.Error(), tt.want) {
+				t.Errorf("got %v, want an error containing %q", err, tt.want)
+			}
+		})
+	}
+
+	if _, err := plugins.Load(context.Background(), filepath.Join(t.TempDir(), "missing"), plugins.DefaultLimits); err == nil {
+		t.Error("got no error for a missing directory")
+	}
+}
@@ -0,0 +1,154 @@
+// Package protocol guards the JSON contract between the proxy service and
+// the frontend and browser extension. It snapshots the shape of
+// ProxyResponse and the serialized form of representative responses, and
+// reports a change unless proxy.ProtocolVersion was bumped with it.
+//
+// The snapshots live in snapshots/ and are checked by cmd/protocheck, which
+// the Makefile and Docker build run before compiling:
+//
+//	go run ./cmd/protocheck           # fail when the protocol changed or no snapshots exist
+//	go run ./cmd/protocheck -update   # record the snapshots after a bump
+package protocol
+
+import (
+	"bytes"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"os"
+	"path/filepath"
+	"reflect"
+	"sort"
+	"strconv"
+	"strings"
+
+	"zone.digit.tommie/internal/proxy"
+)
+
+// versionFile holds the protocol version the snapshots were recorded for.
+const versionFile = "VERSION"
+
+// shapeFile lists every field ProxyResponse can serialize.
+const shapeFile = "shape.txt"
+
+// ErrChanged is returned by Check when the snapshots differ from the
+// current protocol.
+var ErrChanged = errors.New("protocol changed")
+
+// ErrNoSnapshots is returned by Check when no snapshots were recorded.
+var ErrNoSnapshots = errors.New("no protocol snapshots")
+
+// Snapshots returns the snapshot files for the current protocol by name.
+func Snapshots() (map[string][]byte, error) {
+	files := map[string][]byte{
+		versionFile: []byte(strconv.Itoa(proxy.ProtocolVersion) + "\n"),
+		shapeFile:   []byte(strings.Join(Shape(reflect.TypeOf(proxy.ProxyResponse{})), "\n") + "\n"),
+	}
+	for name, response := range Variants() {
+		data, err := json.MarshalIndent(response, "", "  ")
+		if err != nil {
+			return nil, fmt.Errorf("serialize %s: %w", name, err)
+		}
+		files[name+".json"] = append(data, '\n')
+	}
+	return files, nil
+}
+
+// Result describes how the recorded snapshots compare to the current protocol.
+type Result struct {
+	Recorded int      // Version the snapshots were recorded for, 0 when none were
+	Changed  []string // Snapshot files that differ, are new or were removed
+}
+
+// Compare compares the snapshots in dir with the current protocol.
+func Compare(dir string) (Result, error) {
+	var result Result
+	current, err := Snapshots()
+	if err != nil {
+		return result, err
+	}
+	if data, err := os.ReadFile(filepath.Join(dir, versionFile)); err == nil {
+		result.Recorded, _ = strconv.Atoi(strings.TrimSpace(string(data)))
+	} else if !errors.Is(err, os.ErrNotExist) {
+		return result, err
+	}
+
+	for name, data := range current {
+		recorded, err := os.ReadFile(filepath.Join(dir, name))
+		if err != nil && !errors.Is(err, os.ErrNotExist) {
+			return result, err
+		}
+		if name != versionFile && !bytes.Equal(recorded, data) {
+			result.Changed = append(result.Changed, name)
+		}
+	}
+	entries, err := os.ReadDir(dir)
+	if err != nil && !errors.Is(err, os.ErrNotExist) {
+		return result, err
+	}
+	for _, entry := range entries {
+		if _, ok := current[entry.Name()]; !ok && !entry.IsDir() {
+			result.Changed = append(result.Changed, entry.Name())
+		}
+	}
+	sort.Strings(result.Changed)
+	return result, nil
+}
+
+// Check fails with ErrChanged when the protocol differs from the snapshots
+// in dir while proxy.ProtocolVersion still matches them, or when the version
+// was bumped without recording new snapshots. Missing snapshots fail with
+// ErrNoSnapshots, so a wrong directory cannot pass the check.
+func Check(dir string) (Result, error) {
+	result, err := Compare(dir)
+	if err != nil {
+		return result, err
+	}
+	if result.Recorded == 0 {
+		return result, fmt.Errorf("%w in %s; record them with -update", ErrNoSnapshots, dir)
+	}
+	if result.Recorded != proxy.ProtocolVersion {
+		return result, fmt.Errorf("%w: snapshots are for version %d but ProtocolVersion is %d; record them with -update",
+			ErrChanged, result.Recorded, proxy.ProtocolVersion)
+	}
+	if len(result.Changed) > 0 {
+		return result, fmt.Errorf("%w without bumping ProtocolVersion (%d): %s",
+			ErrChanged, proxy.ProtocolVersion, strings.Join(result.Changed, ", "))
+	}
+	return result, nil
+}
+
+// Update records the current protocol in dir. It refuses to replace
+// snapshots of the same version that differ, so a change always comes with
+// a bump of proxy.ProtocolVersion.
+func Update(dir string) (Result, error) {
+	result, err := Compare(dir)
+	if err != nil {
+		return result, err
+	}
+	if result.Recorded == proxy.ProtocolVersion && len(result.Changed) > 0 {
+		return result, fmt.Errorf("%w: bump ProtocolVersion before recording new snapshots: %s",
+			ErrChanged, strings.Join(result.Changed, ", "))
+	}
+
+	current, err := Snapshots()
+	if err != nil {
+		return result, err
+	}
+	if err := os.MkdirAll(dir, 0o755); err != nil {
+		return result, err
+	}
+	for _, name := range result.Changed {
+		if _, ok := current[name]; !ok {
+			if err := os.Remove(filepath.Join(dir, name)); err != nil {
+				return result, err
+			}
+		}
+	}
+	for name, data := range current {
+		if err := os.WriteFile(filepath.Join(dir, name), data, 0o644); err != nil {
+			return result, err
+		}
+	}
+	return result, nil
+}
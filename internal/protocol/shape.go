@@ -0,0 +1,111 @@
+package protocol
+
+import (
+	"encoding"
+	"encoding/json"
+	"reflect"
+	"strings"
+)
+
+var (
+	jsonMarshaler = reflect.TypeOf((*json.Marshaler)(nil)).Elem()
+	textMarshaler = reflect.TypeOf((*encoding.TextMarshaler)(nil)).Elem()
+)
+
+// Shape lists every field the JSON encoding of t can contain, one
+// "path: type" line per field in declaration order. Optional fields, those
+// with omitempty or behind a pointer, end their name with "?"; array
+// elements are written as [] and map values as {}.
+//
+//	data.status: number
+//	data.headers[].name: string
+//	data.timing.dns?: number
+func Shape(t reflect.Type) []string {
+	var lines []string
+	walkShape(t, "", map[reflect.Type]bool{}, &lines)
+	return lines
+}
+
+func walkShape(t reflect.Type, path string, visiting map[reflect.Type]bool, lines *[]string) {
+	for t.Kind() == reflect.Pointer {
+		t = t.Elem()
+	}
+	if kind := shapeKind(t); kind != "" {
+		*lines = append(*lines, path+": "+kind)
+		return
+	}
+
+	switch t.Kind() {
+	case reflect.Slice, reflect.Array:
+		walkShape(t.Elem(), path+"[]", visiting, lines)
+	case reflect.Map:
+		walkShape(t.Elem(), path+"{}", visiting, lines)
+	case reflect.Struct:
+		if visiting[t] {
+			// Recursive types are listed once, at their first occurrence
+			*lines = append(*lines, path+": "+t.Name())
+			return
+		}
+		visiting[t] = true
+		defer delete(visiting, t)
+		if path != "" {
+			*lines = append(*lines, path+": object")
+		}
+		walkFields(t, path, visiting, lines)
+	default:
+		*lines = append(*lines, path+": "+t.Kind().String())
+	}
+}
+
+func walkFields(t reflect.Type, path string, visiting map[reflect.Type]bool, lines *[]string) {
+	for i := 0; i < t.NumField(); i++ {
+		field := t.Field(i)
+		tag := field.Tag.Get("json")
+		if !field.IsExported() || tag == "-" {
+			continue
+		}
+		name, options, _ := strings.Cut(tag, ",")
+		if field.Anonymous && name == "" && field.Type.Kind() == reflect.Struct {
+			walkFields(field.Type, path, visiting, lines)
+			continue
+		}
+		if name == "" {
+			name = field.Name
+		}
+		if field.Type.Kind() == reflect.Pointer || strings.Contains(options, "omitempty") {
+			name += "?"
+		}
+		if path != "" {
+			name = path + "." + name
+		}
+		walkShape(field.Type, name, visiting, lines)
+	}
+}
+
+// shapeKind names types encoded as a JSON scalar, or returns "". Types
+// with their own MarshalJSON are opaque "json".
+func shapeKind(t reflect.Type) string {
+	if t.Implements(textMarshaler) || reflect.PointerTo(t).Implements(textMarshaler) {
+		return "string"
+	}
+	if t.Implements(jsonMarshaler) || reflect.PointerTo(t).Implements(jsonMarshaler) {
+		return "json"
+	}
+	switch t.Kind() {
+	case reflect.Bool:
+		return "boolean"
+	case reflect.Int, reflect.Int8, reflect.Int16, reflect.Int32, reflect.Int64,
+		reflect.Uint, reflect.Uint8, reflect.Uint16, reflect.Uint32, reflect.Uint64,
+		reflect.Float32, reflect.Float64:
+		return "number"
+	case reflect.String:
+		return "string"
+	case reflect.Interface:
+		return "any"
+	case reflect.Slice:
+		if t.Elem().Kind() == reflect.Uint8 {
+			return "base64"
+		}
+	}
+	return ""
+}
@@ -0,0 +1,182 @@
+package protocol
+
+import "zone.digit.tommie/internal/proxy"
+
+func ptr[T any](v T) *T {
+	return &v
+}
+
+// Variants returns representative responses by snapshot name. Their values
+// are fixed, so only a change to the types changes their serialization.
+func Variants() map[string]proxy.ProxyResponse {
+	return map[string]proxy.ProxyResponse{
+		"success":     successVariant(),
+		"error":       errorVariant(),
+		"binary":      binaryVariant(),
+		"redirected":  redirectedVariant(),
+		"tls":         tlsVariant(),
+		"certificate": certificateVariant(),
+	}
+}
+
+func successVariant() proxy.ProxyResponse {
+	body := `{"id":1,"name":"example"}`
+	return proxy.ProxyResponse{
+		Success: true,
+		Data: &proxy.ResponseData{
+			Status:     200,
+			StatusText: "OK",
+			Headers: []proxy.HeaderField{
+				{Name: "Content-Type", Value: "application/json"},
+				{Name: "Content-Length", Value: "25"},
+			},
+			RequestMethod:  ptr("GET"),
+			RequestHeaders: map[string]string{"Accept": "application/json"},
+			Body:           body,
+			Size:           len(body),
+			ContentLength:  ptr(int64(len(body))),
+			Timing: proxy.TimingInfo{
+				Total:    42,
+				DNS:      ptr(uint64(3)),
+				TCP:      ptr(uint64(8)),
+				TTFB:     ptr(uint64(27)),
+				Download: ptr(uint64(4)),
+			},
+			URL: "http://example.com/items/1",
+			SizeBreakdown: &proxy.SizeBreakdown{
+				Headers: 72,
+				Body:    len(body),
+				Total:   72 + len(body),
+			},
+			ServerIP:   ptr("93.184.216.34"),
+			Protocol:   ptr("HTTP/1.1"),
+			Connection: ptr("keep-alive"),
+			Hostname:   ptr("example.com"),
+			Port:       ptr("80"),
+		},
+	}
+}
+
+func errorVariant() proxy.ProxyResponse {
+	return proxy.NewErrorResponse("Request failed: dial tcp 192.0.2.1:443: connect: connection refused", "REQUEST_FAILED")
+}
+
+func binaryVariant() proxy.ProxyResponse {
+	return proxy.ProxyResponse{
+		Success: true,
+		Data: &proxy.ResponseData{
+			Status:     200,
+			StatusText: "OK",
+			Headers: []proxy.HeaderField{
+				{Name: "Content-Type", Value: "image/png"},
+			},
+			BodyBase64:  ptr("iVBORw0KGgo="),
+			BodyHexdump: ptr("00000000  89 50 4e 47 0d 0a 1a 0a                           |.PNG....|\n"),
+			IsBinary:    true,
+			Size:        8,
+			Timing:      proxy.TimingInfo{Total: 12},
+			URL:         "http://example.com/logo.png",
+		},
+	}
+}
+
+func redirectedVariant() proxy.ProxyResponse {
+	return proxy.ProxyResponse{
+		Success: true,
+		Data: &proxy.ResponseData{
+			Status:     200,
+			StatusText: "OK",
+			Headers: []proxy.HeaderField{
+				{Name: "Content-Type", Value: "text/plain"},
+			},
+			Body:       "ok",
+			Size:       2,
+			Timing:     proxy.TimingInfo{Total: 64},
+			URL:        "https://example.com/ok",
+			Redirected: true,
+			RedirectChain: []proxy.RedirectHop{
+				{
+					URL:      "http://example.com/old",
+					Method:   "GET",
+					Status:   301,
+					Duration: 20,
+					Headers:  map[string]string{"location": "https://example.com/new"},
+				},
+				{
+					URL:      "https://example.com/new",
+					Method:   "GET",
+					Status:   302,
+					Duration: 18,
+					Headers:  map[string]string{"location": "/ok"},
+					ServerIP: ptr("93.184.216.34"),
+					Protocol: ptr("HTTP/2"),
+				},
+			},
+		},
+	}
+}
+
+func tlsVariant() proxy.ProxyResponse {
+	return proxy.ProxyResponse{
+		Success: true,
+		Data: &proxy.ResponseData{
+			Status:     204,
+			StatusText: "No Content",
+			Headers:    []proxy.HeaderField{},
+			Timing: proxy.TimingInfo{
+				Total: 95,
+				DNS:   ptr(uint64(5)),
+				TCP:   ptr(uint64(14)),
+				TLS:   ptr(uint64(31)),
+				TTFB:  ptr(uint64(44)),
+			},
+			URL: "https://example.com/",
+			TLS: &proxy.TLSInfo{
+				Protocol:  ptr("TLS 1.3"),
+				Cipher:    ptr("TLS_AES_128_GCM_SHA256"),
+				Issuer:    ptr("CN=Example CA"),
+				Subject:   ptr("CN=example.com"),
+				ValidFrom: ptr(uint64(1704067200000)),
+				ValidTo:   ptr(uint64(1735689599000)),
+				Valid:     ptr(true),
+				SANs:      []string{"example.com", "www.example.com"},
+				Chain: []proxy.CertificateInfo{
+					{
+						Subject:      "CN=example.com",
+						Issuer:       "CN=Example CA",
+						ValidFrom:    1704067200000,
+						ValidTo:      1735689599000,
+						KeyAlgorithm: "ECDSA P-256",
+						Fingerprint:  "5f1c0e0d5c4a8b3e2f6d7a9b0c1d2e3f4a5b6c7d8e9f0a1b2c3d4e5f6a7b8c9d",
+						SerialNumber: "0a1b2c3d",
+					},
+				},
+				Validation: &proxy.CertValidation{
+					HostnameMatch: true,
+					ChainVerified: true,
+				},
+			},
+			Protocol: ptr("HTTP/2"),
+		},
+	}
+}
+
+func certificateVariant() proxy.ProxyResponse {
+	message := "hostname mismatch: x509: certificate is valid for example.com, www.example.com, not api.example.org"
+	response := proxy.NewErrorResponse("Request failed: certificate rejected: "+message, "CERT_INVALID")
+	response.Error.TLS = &proxy.TLSInfo{
+		Protocol:  ptr("TLS 1.3"),
+		Cipher:    ptr("TLS_AES_128_GCM_SHA256"),
+		Issuer:    ptr("CN=Example CA"),
+		Subject:   ptr("CN=example.com"),
+		ValidFrom: ptr(uint64(1704067200000)),
+		ValidTo:   ptr(uint64(1735689599000)),
+		Valid:     ptr(false),
+		SANs:      []string{"example.com", "www.example.com"},
+		Validation: &proxy.CertValidation{
+			ChainVerified: true,
+			Errors:        []string{message},
+		},
+	}
+	return response
+}
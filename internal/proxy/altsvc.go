@@ -0,0 +1,68 @@
+package proxy
+
+import (
+	"strconv"
+	"strings"
+)
+
+// parseAltSvc parses an Alt-Svc header (RFC 7838) into its advertised alternatives.
+// The special value "clear" yields no entries.
+func parseAltSvc(header string) []AltSvcEntry {
+	var entries []AltSvcEntry
+	for _, part := range splitQuoted(header, ',') {
+		part = strings.TrimSpace(part)
+		if part == "" || part == "clear" {
+			continue
+		}
+
+		params := splitQuoted(part, ';')
+		protocol, authority, ok := strings.Cut(strings.TrimSpace(params[0]), "=")
+		if !ok {
+			continue
+		}
+
+		entry := AltSvcEntry{
+			Protocol:  strings.TrimSpace(protocol),
+			Authority: strings.Trim(strings.TrimSpace(authority), `"`),
+		}
+		for _, param := range params[1:] {
+			name, value, _ := strings.Cut(strings.TrimSpace(param), "=")
+			if strings.EqualFold(name, "ma") {
+				if ma, err := strconv.ParseUint(strings.Trim(value, `"`), 10, 64); err == nil {
+					entry.MaxAge = &ma
+				}
+			}
+		}
+		entries = append(entries, entry)
+	}
+	return entries
+}
+
+// offersHTTP3 reports whether any alternative is an HTTP/3 (or draft) protocol.
+func offersHTTP3(entries []AltSvcEntry) bool {
+	for _, entry := range entries {
+		if entry.Protocol == "h3" || strings.HasPrefix(entry.Protocol, "h3-") {
+			return true
+		}
+	}
+	return false
+}
+
+// splitQuoted splits s on sep, ignoring separators inside double quotes.
+func splitQuoted(s string, sep byte) []string {
+	var parts []string
+	inQuotes := false
+	start := 0
+	for i := 0; i < len(s); i++ {
+		switch s[i] {
+		case '"':
+			inQuotes = !inQuotes
+		case sep:
+			if !inQuotes {
+				parts = append(parts, s[start:i])
+				start = i + 1
+			}
+		}
+	}
+	return append(parts, s[start:])
+}
@@ -0,0 +1,99 @@
+package proxy
+
+import (
+	"fmt"
+	"strings"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+// charsetAliases normalizes charset labels that name the same encoding.
+var charsetAliases = map[string]string{
+	"utf8":       "utf-8",
+	"latin1":     "windows-1252",
+	"iso-8859-1": "windows-1252",
+	"us-ascii":   "windows-1252",
+	"ascii":      "windows-1252",
+}
+
+// analyzeBody derives language and charset heuristics from a text body.
+func analyzeBody(raw []byte, body, contentType, contentLanguage string) *ResponseAnalysis {
+	ct := strings.ToLower(contentType)
+	analysis := &ResponseAnalysis{Charset: analyzeCharset(raw, contentType)}
+
+	isHTML := strings.Contains(ct, "html")
+	if isHTML || strings.Contains(ct, "text/plain") || strings.Contains(ct, "markdown") || strings.Contains(ct, "xml") {
+		analysis.Language = analyzeLanguage(body, isHTML, contentLanguage)
+	}
+	return analysis
+}
+
+func analyzeLanguage(body string, isHTML bool, contentLanguage string) *LanguageAnalysis {
+	result := &LanguageAnalysis{}
+	for _, tag := range strings.Split(contentLanguage, ",") {
+		if tag = strings.TrimSpace(tag); tag != "" {
+			result.Declared = append(result.Declared, tag)
+		}
+	}
+	if isHTML {
+		result.DocumentLang = optionalString(infra.HTMLLang(body))
+	}
+
+	guess := infra.DetectLanguage(body, isHTML)
+	if guess == nil {
+		return result
+	}
+	result.Detected = &guess.Language
+	result.Confidence = &guess.Confidence
+
+	declared := result.Declared
+	if len(declared) == 0 && result.DocumentLang != nil {
+		declared = []string{*result.DocumentLang}
+	}
+	if len(declared) > 0 {
+		matches := false
+		for _, tag := range declared {
+			primary, _, _ := strings.Cut(strings.ToLower(tag), "-")
+			matches = matches || primary == guess.Language
+		}
+		result.Matches = &matches
+	}
+	return result
+}
+
+func analyzeCharset(raw []byte, contentType string) *CharsetAnalysis {
+	info := infra.InspectCharset(raw, contentType)
+	result := &CharsetAnalysis{
+		Header:    optionalString(info.Header),
+		Document:  optionalString(info.Document),
+		BOM:       optionalString(info.BOM),
+		ValidUTF8: info.ValidUTF8,
+		ASCII:     info.ASCII,
+	}
+
+	header, document := normalizeCharset(info.Header), normalizeCharset(info.Document)
+	if header != "" && document != "" && header != document {
+		result.Warnings = append(result.Warnings, fmt.Sprintf("Content-Type declares %s but the document declares %s", info.Header, info.Document))
+	}
+	declared := header
+	if declared == "" {
+		declared = document
+	}
+	switch {
+	case info.BOM != "" && declared != "" && !strings.HasPrefix(info.BOM, declared):
+		result.Warnings = append(result.Warnings, fmt.Sprintf("Byte order mark indicates %s but %s is declared", info.BOM, declared))
+	case declared == "utf-8" && !info.ValidUTF8:
+		result.Warnings = append(result.Warnings, "Declared as UTF-8 but the body is not valid UTF-8")
+	case declared == "" && info.BOM == "" && !info.ASCII:
+		result.Warnings = append(result.Warnings, "No charset declared for a body with non-ASCII bytes")
+	}
+	result.Consistent = len(result.Warnings) == 0
+	return result
+}
+
+func normalizeCharset(label string) string {
+	if alias, ok := charsetAliases[label]; ok {
+		return alias
+	}
+	return label
+}
@@ -0,0 +1,176 @@
+package proxy
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"reflect"
+	"regexp"
+	"strconv"
+	"strings"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+// Assertion types.
+const (
+	AssertStatus       = "status"       // value: a status code or class such as "2xx"
+	AssertHeader       = "header"       // header: name; value: regular expression (omitted: present)
+	AssertJSONPath     = "jsonPath"     // path: JSONPath; value: expected JSON value (omitted: exists)
+	AssertBodyContains = "bodyContains" // value: text the body contains
+	AssertResponseTime = "responseTime" // value: maximum total time in milliseconds
+)
+
+// evaluateAssertions checks the assertions of a request against its
+// response. Invalid assertions fail with a message rather than failing the
+// request.
+func evaluateAssertions(assertions []Assertion, data *ResponseData) []AssertionResult {
+	results := make([]AssertionResult, len(assertions))
+	var parsed interface{}
+	parsedOK := false
+	for i, a := range assertions {
+		result := AssertionResult{Type: a.Type, Name: a.Name}
+		fail := func(format string, args ...interface{}) {
+			result.Message = strPtr(fmt.Sprintf(format, args...))
+		}
+
+		switch a.Type {
+		case AssertStatus:
+			result.Actual = data.Status
+			var expected interface{}
+			if err := json.Unmarshal(a.Value, &expected); err != nil {
+				fail("value must be a status code or class such as \"2xx\"")
+				break
+			}
+			code := strconv.Itoa(int(data.Status))
+			switch e := expected.(type) {
+			case float64:
+				result.Passed = int(e) == int(data.Status)
+			case string:
+				e = strings.ToLower(e)
+				if len(e) == 3 && strings.HasSuffix(e, "xx") {
+					result.Passed = code[0] == e[0]
+				} else {
+					result.Passed = code == e
+				}
+			default:
+				fail("value must be a status code or class such as \"2xx\"")
+			}
+			if result.Message == nil && !result.Passed {
+				fail("Expected status %v, got %d", expected, data.Status)
+			}
+
+		case AssertHeader:
+			if a.Header == "" {
+				fail("header is required")
+				break
+			}
+			var values []string
+			for _, field := range data.Headers {
+				if strings.EqualFold(field.Name, a.Header) {
+					values = append(values, field.Value)
+				}
+			}
+			if len(values) == 0 {
+				fail("Header %s is missing", a.Header)
+				break
+			}
+			value := strings.Join(values, ", ")
+			result.Actual = value
+			if len(a.Value) == 0 {
+				result.Passed = true
+				break
+			}
+			var pattern string
+			if err := json.Unmarshal(a.Value, &pattern); err != nil {
+				fail("value must be a regular expression")
+				break
+			}
+			re, err := regexp.Compile(pattern)
+			if err != nil {
+				fail("Invalid regular expression: %v", err)
+				break
+			}
+			if result.Passed = re.MatchString(value); !result.Passed {
+				fail("Header %s does not match %s", a.Header, pattern)
+			}
+
+		case AssertJSONPath:
+			path, err := infra.CompileJSONPath(a.Path)
+			if err != nil {
+				fail("Invalid JSONPath: %v", err)
+				break
+			}
+			if !parsedOK {
+				decoder := json.NewDecoder(strings.NewReader(data.Body))
+				if err := decoder.Decode(&parsed); err != nil || data.IsBinary {
+					fail("The body is not JSON")
+					break
+				}
+				parsedOK = true
+			}
+			matches := path.Find(parsed)
+			if len(matches) == 0 {
+				fail("%s matches nothing", a.Path)
+				break
+			}
+			actual := interface{}(matches)
+			if len(matches) == 1 {
+				actual = matches[0]
+			}
+			result.Actual = actual
+			if len(a.Value) == 0 {
+				result.Passed = true
+				break
+			}
+			var expected interface{}
+			if err := json.Unmarshal(a.Value, &expected); err != nil {
+				fail("value must be JSON")
+				break
+			}
+			if result.Passed = reflect.DeepEqual(actual, expected); !result.Passed {
+				fail("Expected %s to equal %s", a.Path, compactJSON(a.Value))
+			}
+
+		case AssertBodyContains:
+			var text string
+			if err := json.Unmarshal(a.Value, &text); err != nil || text == "" {
+				fail("value must be a non-empty string")
+				break
+			}
+			body := data.Body
+			if data.IsBinary {
+				fail("The body is binary")
+				break
+			}
+			if result.Passed = strings.Contains(body, text); !result.Passed {
+				fail("The body does not contain %q", text)
+			}
+
+		case AssertResponseTime:
+			result.Actual = data.Timing.Total
+			var limit float64
+			if err := json.Unmarshal(a.Value, &limit); err != nil || limit < 0 {
+				fail("value must be a number of milliseconds")
+				break
+			}
+			if result.Passed = float64(data.Timing.Total) <= limit; !result.Passed {
+				fail("Took %d ms, more than %v ms", data.Timing.Total, limit)
+			}
+
+		default:
+			fail("Unknown assertion type %q; expected status, header, jsonPath, bodyContains or responseTime", a.Type)
+		}
+		results[i] = result
+	}
+	return results
+}
+
+// compactJSON renders raw JSON on one line for messages.
+func compactJSON(raw json.RawMessage) string {
+	var b bytes.Buffer
+	if json.Compact(&b, raw) != nil {
+		return string(raw)
+	}
+	return b.String()
+}
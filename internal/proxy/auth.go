@@ -0,0 +1,302 @@
+package proxy
+
+import (
+	"crypto/md5"
+	"crypto/rand"
+	"crypto/sha256"
+	"crypto/sha512"
+	"encoding/base64"
+	"encoding/hex"
+	"fmt"
+	"hash"
+	"io"
+	"net/http"
+	"strings"
+)
+
+// Authentication schemes of AuthOptions.Type.
+const (
+	AuthBasic     = "basic"
+	AuthBearer    = "bearer"
+	AuthDigest    = "digest"
+	AuthNTLM      = "ntlm"
+	AuthNegotiate = "negotiate"
+)
+
+// digestAuthAlgorithms maps the Digest algorithms in order of preference to
+// their hash (RFC 7616, section 3.4.2).
+var digestAuthAlgorithms = []struct {
+	name string
+	hash func() hash.Hash
+}{
+	{"SHA-512-256", sha512.New512_256},
+	{"SHA-256", sha256.New},
+	{"MD5", md5.New},
+}
+
+// authHeaders returns headers with the Authorization header of a Basic or
+// Bearer scheme set, replacing any given. Digest, NTLM and Negotiate need a
+// challenge first and leave headers unchanged.
+func (a *AuthOptions) authHeaders(headers map[string]string) (map[string]string, error) {
+	var value string
+	switch strings.ToLower(a.Type) {
+	case AuthBasic:
+		value = "Basic " + base64.StdEncoding.EncodeToString([]byte(a.Username+":"+a.Password))
+	case AuthBearer:
+		if a.Token == "" {
+			return nil, fmt.Errorf("bearer auth requires a token")
+		}
+		value = "Bearer " + a.Token
+	case AuthDigest:
+		if a.Username == "" {
+			return nil, fmt.Errorf("digest auth requires a username")
+		}
+		return headers, nil
+	case AuthNTLM, AuthNegotiate:
+		// Without a username the current user's credentials are used
+		return headers, nil
+	default:
+		return nil, fmt.Errorf("unknown auth type %q: use basic, bearer, digest, ntlm or negotiate", a.Type)
+	}
+
+	authed := withoutHeaders(headers, []string{"Authorization"})
+	authed["Authorization"] = value
+	return authed, nil
+}
+
+// digestChallenge is a Digest WWW-Authenticate challenge.
+type digestChallenge struct {
+	realm     string
+	nonce     string
+	opaque    string
+	algorithm string // As offered, e.g. SHA-256-sess
+	qop       string // auth, auth-int or empty for RFC 2069 servers
+	userhash  bool
+	hash      func() hash.Hash
+	session   bool
+}
+
+// parseDigestChallenge picks the Digest challenge with the strongest
+// supported algorithm, or returns nil when none is offered.
+func parseDigestChallenge(values []string) *digestChallenge {
+	var best *digestChallenge
+	bestRank := len(digestAuthAlgorithms)
+	for _, value := range values {
+		for _, params := range splitChallenges(value, "Digest") {
+			c := &digestChallenge{
+				realm:     params["realm"],
+				nonce:     params["nonce"],
+				opaque:    params["opaque"],
+				algorithm: params["algorithm"],
+				userhash:  strings.EqualFold(params["userhash"], "true"),
+			}
+			if c.nonce == "" {
+				continue
+			}
+			if c.algorithm == "" {
+				c.algorithm = "MD5"
+			}
+			base, session := strings.CutSuffix(strings.ToUpper(c.algorithm), "-SESS")
+			c.session = session
+
+			rank := -1
+			for i, algorithm := range digestAuthAlgorithms {
+				if algorithm.name == base {
+					rank = i
+					c.hash = algorithm.hash
+				}
+			}
+			if rank < 0 || rank >= bestRank {
+				continue
+			}
+
+			// Prefer auth over auth-int, which needs the whole body hashed
+			for _, qop := range strings.Split(params["qop"], ",") {
+				qop = strings.TrimSpace(strings.ToLower(qop))
+				if qop == "auth" || qop == "auth-int" && c.qop == "" {
+					c.qop = qop
+				}
+			}
+			if params["qop"] != "" && c.qop == "" {
+				continue
+			}
+			best, bestRank = c, rank
+		}
+	}
+	return best
+}
+
+// splitChallenges returns the parameters of each challenge of a scheme in a
+// WWW-Authenticate value, which may list several challenges.
+func splitChallenges(value, scheme string) []map[string]string {
+	var challenges []map[string]string
+	var current map[string]string
+	for len(value) > 0 {
+		value = strings.TrimLeft(value, " \t,")
+		token := value
+		if i := strings.IndexAny(value, " \t,="); i >= 0 {
+			token = value[:i]
+		}
+		if token == "" {
+			break
+		}
+		rest := strings.TrimLeft(value[len(token):], " \t")
+
+		// A token not followed by = starts a new challenge
+		if !strings.HasPrefix(rest, "=") {
+			current = nil
+			if strings.EqualFold(token, scheme) {
+				current = map[string]string{}
+				challenges = append(challenges, current)
+			}
+			value = rest
+			continue
+		}
+
+		rest = strings.TrimLeft(rest[1:], " \t")
+		var param string
+		if strings.HasPrefix(rest, `"`) {
+			param, rest = readQuoted(rest[1:])
+		} else {
+			end := strings.IndexAny(rest, ", \t")
+			if end < 0 {
+				end = len(rest)
+			}
+			param, rest = rest[:end], rest[end:]
+		}
+		if current != nil {
+			current[strings.ToLower(token)] = param
+		}
+		value = rest
+	}
+	return challenges
+}
+
+// readQuoted reads a quoted string up to its closing quote, returning the
+// unescaped content and the remainder after the quote.
+func readQuoted(s string) (string, string) {
+	var b strings.Builder
+	for i := 0; i < len(s); i++ {
+		switch s[i] {
+		case '\\':
+			if i+1 < len(s) {
+				i++
+				b.WriteByte(s[i])
+			}
+		case '"':
+			return b.String(), s[i+1:]
+		default:
+			b.WriteByte(s[i])
+		}
+	}
+	return b.String(), ""
+}
+
+// digestAuthorization computes the Authorization header answering a challenge
+// with the client nonce cnonce (RFC 7616, section 3.4).
+func digestAuthorization(c *digestChallenge, username, password, method, uri, cnonce string, body []byte) string {
+	h := func(parts ...string) string {
+		digest := c.hash()
+		io.WriteString(digest, strings.Join(parts, ":"))
+		return hex.EncodeToString(digest.Sum(nil))
+	}
+	const nc = "00000001"
+
+	ha1 := h(username, c.realm, password)
+	if c.session {
+		ha1 = h(ha1, c.nonce, cnonce)
+	}
+	ha2 := h(method, uri)
+	if c.qop == "auth-int" {
+		ha2 = h(method, uri, h(string(body)))
+	}
+	response := h(ha1, c.nonce, ha2)
+	if c.qop != "" {
+		response = h(ha1, c.nonce, nc, cnonce, c.qop, ha2)
+	}
+
+	user := username
+	if c.userhash {
+		user = h(username, c.realm)
+	}
+	params := []string{
+		fmt.Sprintf("username=%q", user),
+		fmt.Sprintf("realm=%q", c.realm),
+		fmt.Sprintf("nonce=%q", c.nonce),
+		fmt.Sprintf("uri=%q", uri),
+		"algorithm=" + c.algorithm,
+		fmt.Sprintf("response=%q", response),
+	}
+	if c.opaque != "" {
+		params = append(params, fmt.Sprintf("opaque=%q", c.opaque))
+	}
+	if c.qop != "" {
+		params = append(params, "qop="+c.qop, "nc="+nc, fmt.Sprintf("cnonce=%q", cnonce))
+	}
+	if c.userhash {
+		params = append(params, "userhash=true")
+	}
+	return "Digest " + strings.Join(params, ", ")
+}
+
+// digestAuth answers a 401 Digest challenge by resending the request with
+// credentials. It returns the response to use, the challenged response
+// (drained and closed) and the Authorization header sent. The challenged
+// response is nil when no Digest challenge applies. It answers any origin,
+// so callers only use it for the origin the credentials were given for.
+func digestAuth(client *http.Client, resp *http.Response, newRequest func() (*http.Request, error), auth *AuthOptions, body []byte) (*http.Response, *http.Response, string, error) {
+	if resp.StatusCode != http.StatusUnauthorized {
+		return resp, nil, "", nil
+	}
+	challenge := parseDigestChallenge(resp.Header.Values("WWW-Authenticate"))
+	if challenge == nil {
+		return resp, nil, "", nil
+	}
+
+	req, err := newRequest()
+	if err != nil {
+		return nil, nil, "", err
+	}
+	cnonce := make([]byte, 16)
+	if _, err := rand.Read(cnonce); err != nil {
+		return nil, nil, "", err
+	}
+	authorization := digestAuthorization(challenge, auth.Username, auth.Password, req.Method, req.URL.RequestURI(), hex.EncodeToString(cnonce), body)
+
+	// Drain so the answer reuses the connection
+	io.Copy(io.Discard, resp.Body)
+	resp.Body.Close()
+
+	req.Header.Set("Authorization", authorization)
+	answered, err := client.Do(req)
+	if err != nil {
+		return nil, nil, "", err
+	}
+	return answered, resp, authorization, nil
+}
+
+// negotiatorFactory picks how Negotiate and NTLM challenges of host are
+// answered: with NTLM tokens computed from the request's credentials, with
+// the current user's credentials, or not at all (nil). The current user's
+// are only sent when integrated auth is enabled and the request asks for
+// them, with ntlm or negotiate auth without a username, or host is one of
+// IntegratedAuthHosts. Either answers any host, so server challenges are
+// only answered for the origin of the request.
+func (s *HTTPService) negotiatorFactory(auth *AuthOptions, host string) func(scheme, host string) (negotiator, error) {
+	requested := false
+	if auth != nil {
+		switch strings.ToLower(auth.Type) {
+		case AuthNTLM, AuthNegotiate:
+			if auth.Username != "" {
+				return func(scheme, host string) (negotiator, error) {
+					return newNTLMNegotiator(auth.Username, auth.Password, s.clock.Now()), nil
+				}
+			}
+			requested = true
+		}
+	}
+	if s.opts.IntegratedAuth && (requested || matchesHost(s.opts.IntegratedAuthHosts, host)) {
+		return newNegotiator
+	}
+	return nil
+}
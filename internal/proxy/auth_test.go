@@ -0,0 +1,168 @@
+package proxy
+
+import (
+	"net/http"
+	"net/http/httptest"
+	"net/url"
+	"strings"
+	"testing"
+)
+
+// Challenge of the examples in RFC 7616, section 3.9.1.
+const (
+	digestTestNonce  = "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v"
+	digestTestOpaque = "FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS"
+	digestTestCnonce = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ"
+)
+
+func TestParseDigestChallenge(t *testing.T) {
+	type challenge struct {
+		realm, nonce, opaque, algorithm, qop string
+		session, userhash                    bool
+	}
+	rfcChallenge := func(algorithm string) string {
+		return `Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=` + algorithm +
+			`, nonce="` + digestTestNonce + `", opaque="` + digestTestOpaque + `"`
+	}
+	tests := []struct {
+		name   string
+		values []string
+		want   *challenge
+	}{
+		{"defaults", []string{`Digest realm="r", nonce="n"`}, &challenge{realm: "r", nonce: "n", algorithm: "MD5"}},
+		{
+			"strongest of several headers",
+			[]string{rfcChallenge("MD5"), rfcChallenge("SHA-256")},
+			&challenge{realm: "http-auth@example.org", nonce: digestTestNonce, opaque: digestTestOpaque, algorithm: "SHA-256", qop: "auth"},
+		},
+		{
+			"after another scheme",
+			[]string{`Basic realm="basic", Digest realm="r", nonce=n, algorithm=md5-sess, qop="auth-int", userhash=true`},
+			&challenge{realm: "r", nonce: "n", algorithm: "md5-sess", qop: "auth-int", session: true, userhash: true},
+		},
+		{"escaped quotes", []string{`Digest realm="say \"hi\"", nonce="n"`}, &challenge{realm: `say "hi"`, nonce: "n", algorithm: "MD5"}},
+		{"unsupported algorithm", []string{`Digest realm="r", nonce="n", algorithm=SHA-1`}, nil},
+		{"unsupported qop", []string{`Digest realm="r", nonce="n", qop="auth-conf"`}, nil},
+		{"no nonce", []string{`Digest realm="r"`}, nil},
+		{"other schemes", []string{`Basic realm="r"`, `Bearer realm="r"`}, nil},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			c := parseDigestChallenge(tt.values)
+			if c == nil || tt.want == nil {
+				if (c == nil) != (tt.want == nil) {
+					t.Fatalf("parseDigestChallenge = %+v, want %+v", c, tt.want)
+				}
+				return
+			}
+			got := challenge{c.realm, c.nonce, c.opaque, c.algorithm, c.qop, c.session, c.userhash}
+			if got != *tt.want {
+				t.Errorf("parseDigestChallenge = %+v, want %+v", got, *tt.want)
+			}
+		})
+	}
+}
+
+func TestDigestAuthorization(t *testing.T) {
+	tests := []struct {
+		name      string
+		challenge string
+		password  string
+		method    string
+		uri       string
+		body      string
+		cnonce    string
+		want      string
+	}{
+		{
+			"RFC 7616 MD5",
+			`Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=MD5, nonce="` + digestTestNonce + `", opaque="` + digestTestOpaque + `"`,
+			"Circle of Life", "GET", "/dir/index.html", "", digestTestCnonce,
+			`Digest username="Mufasa", realm="http-auth@example.org", nonce="` + digestTestNonce + `", uri="/dir/index.html", algorithm=MD5, ` +
+				`response="8ca523f5e9506fed4657c9700eebdbec", opaque="` + digestTestOpaque + `", qop=auth, nc=00000001, cnonce="` + digestTestCnonce + `"`,
+		},
+		{
+			"RFC 7616 SHA-256",
+			`Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=SHA-256, nonce="` + digestTestNonce + `", opaque="` + digestTestOpaque + `"`,
+			"Circle of Life", "GET", "/dir/index.html", "", digestTestCnonce,
+			`Digest username="Mufasa", realm="http-auth@example.org", nonce="` + digestTestNonce + `", uri="/dir/index.html", algorithm=SHA-256, ` +
+				`response="753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1", opaque="` + digestTestOpaque + `", qop=auth, nc=00000001, cnonce="` + digestTestCnonce + `"`,
+		},
+		{
+			// RFC 2069, section 2.4
+			"without qop",
+			`Digest realm="testrealm@host.com", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093"`,
+			"Circle Of Life", "GET", "/dir/index.html", "", "unused",
+			`Digest username="Mufasa", realm="testrealm@host.com", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093", uri="/dir/index.html", algorithm=MD5, ` +
+				`response="670fd8c2df070c60b045671b8b24ff02"`,
+		},
+		{
+			"session, auth-int and userhash",
+			`Digest realm="api", nonce="abc", algorithm=SHA-256-sess, qop="auth-int", userhash=true`,
+			"Circle of Life", "POST", "/items", "hello", "0a4f113b",
+			`Digest username="da5f86cb719898ca182fa7815b21337a611fdccceef385e5aa68dc13148a0d32", realm="api", nonce="abc", uri="/items", algorithm=SHA-256-sess, ` +
+				`response="fca114a17700f6f1f57cca65b04cb3d9a3dde6bd9a4816b8f8f426c107166feb", qop=auth-int, nc=00000001, cnonce="0a4f113b", userhash=true`,
+		},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			c := parseDigestChallenge([]string{tt.challenge})
+			if c == nil {
+				t.Fatal("the challenge was not parsed")
+			}
+			if got := digestAuthorization(c, "Mufasa", tt.password, tt.method, tt.uri, tt.cnonce, []byte(tt.body)); got != tt.want {
+				t.Errorf("digestAuthorization =\n%s\nwant\n%s", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestDigestAuth(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if strings.HasPrefix(r.Header.Get("Authorization"), `Digest username="Mufasa"`) {
+			return
+		}
+		w.Header().Set("WWW-Authenticate", r.URL.Query().Get("challenge"))
+		w.WriteHeader(http.StatusUnauthorized)
+	}))
+	defer server.Close()
+	auth := &AuthOptions{Type: AuthDigest, Username: "Mufasa", Password: "Circle of Life"}
+
+	tests := []struct {
+		name      string
+		challenge string
+		status    int
+		answered  bool
+	}{
+		{"digest", `Digest realm="r", nonce="n", qop="auth"`, http.StatusOK, true},
+		{"basic", `Basic realm="r"`, http.StatusUnauthorized, false},
+		{"unsupported algorithm", `Digest realm="r", nonce="n", algorithm=SHA-1`, http.StatusUnauthorized, false},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			target := server.URL + "/dir?challenge=" + url.QueryEscape(tt.challenge)
+			newRequest := func() (*http.Request, error) {
+				return http.NewRequest(http.MethodGet, target, nil)
+			}
+			req, _ := newRequest()
+			resp, err := server.Client().Do(req)
+			if err != nil {
+				t.Fatal(err)
+			}
+			answered, challenged, authorization, err := digestAuth(server.Client(), resp, newRequest, auth, nil)
+			if err != nil {
+				t.Fatal(err)
+			}
+			defer answered.Body.Close()
+			if answered.StatusCode != tt.status {
+				t.Errorf("status = %d, want %d", answered.StatusCode, tt.status)
+			}
+			if (challenged != nil) != tt.answered || (authorization != "") != tt.answered {
+				t.Errorf("challenge answered with %q, want it answered: %v", authorization, tt.answered)
+			}
+			if tt.answered && !strings.Contains(authorization, `uri="`+req.URL.RequestURI()+`"`) {
+				t.Errorf("Authorization %q does not cover %s", authorization, req.URL.RequestURI())
+			}
+		})
+	}
+}
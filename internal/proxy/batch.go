@@ -0,0 +1,130 @@
+package proxy
+
+import (
+	"context"
+	"sync"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+const (
+	// DefaultBatchConcurrency is the number of batch requests in flight at once.
+	DefaultBatchConcurrency = 4
+	// MaxBatchConcurrency caps the requested concurrency.
+	MaxBatchConcurrency = 16
+	// MaxBatchRequests is the number of requests accepted in one batch.
+	MaxBatchRequests = 500
+)
+
+// MaxRequests returns the number of requests the batch sends.
+func (b BatchRequest) MaxRequests() int {
+	return len(b.Requests)
+}
+
+// LimitRequests cuts the batch down to its first n requests.
+func (b *BatchRequest) LimitRequests(n int) {
+	if n < len(b.Requests) {
+		b.Requests = b.Requests[:max(n, 0)]
+	}
+}
+
+// ExecuteBatch runs the requests of a batch with bounded concurrency, or one
+// at a time when the batch is chained. Results keep the order of the requests.
+// Requests not yet sent when ctx is cancelled fail without being sent.
+func (s *HTTPService) ExecuteBatch(ctx context.Context, store storage.Store, batch BatchRequest) BatchResponse {
+	concurrency := DefaultBatchConcurrency
+	if batch.Concurrency != nil && *batch.Concurrency > 0 {
+		concurrency = min(*batch.Concurrency, MaxBatchConcurrency)
+	}
+
+	started := s.clock.Now()
+	results := make([]ProxyResponse, len(batch.Requests))
+	if batch.Chain {
+		s.executeChain(ctx, store, batch.Requests, results)
+	} else {
+		slots := make(chan struct{}, concurrency)
+		var wg sync.WaitGroup
+		for i, request := range batch.Requests {
+			wg.Add(1)
+			slots <- struct{}{}
+			go func() {
+				defer wg.Done()
+				defer func() { <-slots }()
+				results[i] = s.ExecuteContext(ctx, store, request)
+			}()
+		}
+		wg.Wait()
+	}
+
+	stats := BatchStats{
+		Total:     len(results),
+		TotalTime: uint64(since(s.clock, started).Milliseconds()),
+	}
+	var requestTime uint64
+	var timings timingSamples
+	for _, response := range results {
+		if !response.Success {
+			stats.Failed++
+			continue
+		}
+		stats.Succeeded++
+		if status := response.Data.Status; status >= 400 {
+			stats.ErrorStatus++
+		}
+		requestTime += response.Data.Timing.Total
+		timings.add(response.Data.Timing)
+	}
+	if stats.Succeeded > 0 {
+		stats.AverageTime = requestTime / uint64(stats.Succeeded)
+	}
+	stats.Latency = timings.latency()
+	stats.Phases = timings.timingPhases()
+	return BatchResponse{Results: results, Stats: stats}
+}
+
+// executeChain runs requests one at a time. Variables a request extracts or
+// its scripts set become template variables of the requests after it, so
+// chaining works without an environment; a request's own variables win.
+func (s *HTTPService) executeChain(ctx context.Context, store storage.Store, requests []ProxyRequest, results []ProxyResponse) {
+	chained := map[string]string{}
+	for i, request := range requests {
+		if len(chained) > 0 {
+			variables := make(map[string]string, len(chained)+len(request.Variables))
+			for name, value := range chained {
+				variables[name] = value
+			}
+			for name, value := range request.Variables {
+				variables[name] = value
+			}
+			request.Variables = variables
+		}
+		results[i] = s.ExecuteContext(ctx, store, request)
+		if !results[i].Success {
+			continue
+		}
+
+		data := results[i].Data
+		if data.Scripts != nil {
+			for _, run := range []*ScriptRun{data.Scripts.PreRequest, data.Scripts.Test} {
+				if run != nil {
+					chainVariables(chained, run.Variables)
+				}
+			}
+		}
+		for _, extracted := range data.Extracted {
+			if extracted.Value != nil {
+				chained[extracted.Variable] = *extracted.Value
+			}
+		}
+	}
+}
+
+func chainVariables(chained map[string]string, variables map[string]*string) {
+	for name, value := range variables {
+		if value == nil {
+			delete(chained, name)
+		} else {
+			chained[name] = *value
+		}
+	}
+}
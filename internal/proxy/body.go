@@ -0,0 +1,149 @@
+package proxy
+
+import (
+	"bytes"
+	"encoding/base64"
+	"errors"
+	"fmt"
+	"mime/multipart"
+	"net/http"
+	"net/textproto"
+	"strings"
+	"unicode/utf8"
+)
+
+// Request body encodings reported in ResponseData.RequestBodyEncoding.
+const (
+	BodyEncodingText      = "text"
+	BodyEncodingBase64    = "base64"
+	BodyEncodingMultipart = "multipart"
+)
+
+// requestBody is the raw body of a request together with how it was produced.
+type requestBody struct {
+	raw         []byte
+	encoding    string // Empty when the request has no body
+	contentType string // Set for generated bodies, overriding the request header
+	rendered    string // Readable rendering of generated bodies with binary content elided
+}
+
+// encodeBody builds the raw request body from Body, BodyBase64, FormData or
+// GraphQL, which are mutually exclusive.
+func (r ProxyRequest) encodeBody() (*requestBody, error) {
+	set := 0
+	for _, present := range []bool{r.Body != nil, r.BodyBase64 != nil, r.FormData != nil, r.GraphQL != nil} {
+		if present {
+			set++
+		}
+	}
+	if set > 1 {
+		return nil, errors.New("body, bodyBase64, formData and graphql are mutually exclusive")
+	}
+
+	switch {
+	case r.BodyBase64 != nil:
+		raw, err := base64.StdEncoding.DecodeString(*r.BodyBase64)
+		if err != nil {
+			return nil, fmt.Errorf("invalid bodyBase64: %w", err)
+		}
+		return &requestBody{raw: raw, encoding: BodyEncodingBase64}, nil
+	case r.Body != nil:
+		return &requestBody{raw: []byte(*r.Body), encoding: BodyEncodingText}, nil
+	case r.FormData != nil:
+		return encodeMultipart(r.FormData)
+	case r.GraphQL != nil:
+		return encodeGraphQL(r.GraphQL)
+	}
+	return &requestBody{}, nil
+}
+
+var quoteEscaper = strings.NewReplacer("\\", "\\\\", `"`, "\\\"")
+
+// encodeMultipart renders form parts as multipart/form-data with a generated boundary.
+func encodeMultipart(parts []FormPart) (*requestBody, error) {
+	var raw, rendered bytes.Buffer
+	w := multipart.NewWriter(&raw)
+	rw := multipart.NewWriter(&rendered)
+	if err := rw.SetBoundary(w.Boundary()); err != nil {
+		return nil, err
+	}
+
+	for i, part := range parts {
+		if part.Name == "" {
+			return nil, fmt.Errorf("formData[%d]: name is required", i)
+		}
+		if (part.Value == nil) == (part.FileBase64 == nil) {
+			return nil, fmt.Errorf("formData[%d]: exactly one of value and fileBase64 is required", i)
+		}
+
+		header := make(textproto.MIMEHeader)
+		var content []byte
+		if part.FileBase64 != nil {
+			decoded, err := base64.StdEncoding.DecodeString(*part.FileBase64)
+			if err != nil {
+				return nil, fmt.Errorf("formData[%d]: invalid fileBase64: %w", i, err)
+			}
+			content = decoded
+
+			filename := part.Name
+			if part.Filename != nil {
+				filename = *part.Filename
+			}
+			contentType := "application/octet-stream"
+			if part.ContentType != nil {
+				contentType = *part.ContentType
+			}
+			header.Set("Content-Disposition", fmt.Sprintf(`form-data; name="%s"; filename="%s"`,
+				quoteEscaper.Replace(part.Name), quoteEscaper.Replace(filename)))
+			header.Set("Content-Type", contentType)
+		} else {
+			content = []byte(*part.Value)
+			header.Set("Content-Disposition", fmt.Sprintf(`form-data; name="%s"`, quoteEscaper.Replace(part.Name)))
+			if part.ContentType != nil {
+				header.Set("Content-Type", *part.ContentType)
+			}
+		}
+
+		pw, err := w.CreatePart(header)
+		if err != nil {
+			return nil, err
+		}
+		pw.Write(content)
+
+		rpw, err := rw.CreatePart(header)
+		if err != nil {
+			return nil, err
+		}
+		if utf8.Valid(content) {
+			rpw.Write(content)
+		} else {
+			fmt.Fprintf(rpw, "<%d bytes of binary data>", len(content))
+		}
+	}
+
+	if err := w.Close(); err != nil {
+		return nil, err
+	}
+	rw.Close()
+
+	return &requestBody{
+		raw:         raw.Bytes(),
+		encoding:    BodyEncodingMultipart,
+		contentType: w.FormDataContentType(),
+		rendered:    rendered.String(),
+	}, nil
+}
+
+// renderRequest formats a request as it is written on an HTTP/1.1 connection.
+func renderRequest(req *http.Request, contentLength int, body string) string {
+	var b strings.Builder
+	fmt.Fprintf(&b, "%s %s HTTP/1.1\r\n", req.Method, req.URL.RequestURI())
+	fmt.Fprintf(&b, "Host: %s\r\n", req.URL.Host)
+	if contentLength > 0 {
+		fmt.Fprintf(&b, "Content-Length: %d\r\n", contentLength)
+	}
+	req.Header.Write(&b)
+	b.WriteString("\r\n")
+	b.WriteString(body)
+	return b.String()
+}
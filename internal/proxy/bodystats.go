@@ -0,0 +1,56 @@
+package proxy
+
+import "zone.digit.tommie/internal/infra"
+
+// DefaultBodyStatsThreshold is the body size from which statistics are
+// computed when no threshold is given.
+const DefaultBodyStatsThreshold = 1 << 20
+
+// bodyStats summarizes a body of at least threshold bytes, or nil for
+// smaller bodies.
+func bodyStats(data []byte, contentType string, isBinary bool, threshold *int) *BodyStats {
+	n := DefaultBodyStatsThreshold
+	if threshold != nil {
+		n = *threshold
+	}
+	if n <= 0 || len(data) < n {
+		return nil
+	}
+
+	computed := infra.ComputeBodyStatistics(data, contentType, !isBinary)
+	stats := &BodyStats{Bytes: computed.Bytes, Entropy: computed.Entropy}
+	if !isBinary {
+		stats.Lines = &computed.Lines
+		stats.LongestLine = &computed.LongestLine
+	}
+	if j := computed.JSON; j != nil {
+		stats.JSON = &JSONBodyStats{
+			Objects:      j.Objects,
+			Arrays:       j.Arrays,
+			MaxDepth:     j.MaxDepth,
+			Keys:         statCounts(j.Keys, false),
+			DistinctKeys: j.DistinctKeys,
+			Complete:     j.Complete,
+		}
+	}
+	if m := computed.Multipart; m != nil {
+		stats.Multipart = &MultipartStats{
+			Parts:        m.Parts,
+			ContentTypes: statCounts(m.ContentTypes, true),
+			Complete:     m.Complete,
+		}
+	}
+	return stats
+}
+
+func statCounts(counts []infra.Count, withBytes bool) []StatCount {
+	result := make([]StatCount, len(counts))
+	for i, c := range counts {
+		result[i] = StatCount{Value: c.Value, Count: c.Count}
+		if withBytes {
+			bytes := c.Bytes
+			result[i].Bytes = &bytes
+		}
+	}
+	return result
+}
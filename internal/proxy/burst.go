@@ -0,0 +1,147 @@
+package proxy
+
+import (
+	"context"
+	"sync"
+	"time"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+const (
+	// DefaultBursts is the number of bursts sent when probing a rate limit.
+	DefaultBursts = 3
+	// DefaultBurstSize is the number of requests in each burst.
+	DefaultBurstSize = 10
+	// DefaultBurstIntervalMS is the pause between bursts in milliseconds.
+	DefaultBurstIntervalMS = 1000
+	// MaxBurstSize caps the requests sent at once.
+	MaxBurstSize = 100
+	// MaxBurstIntervalMS caps the pause between bursts.
+	MaxBurstIntervalMS = 60_000
+)
+
+// shape returns the number of bursts to send and their size.
+func (b BurstRequest) shape() (bursts, size int) {
+	size = min(positiveOr(b.BurstSize, DefaultBurstSize), MaxBurstSize)
+	bursts = min(positiveOr(b.Bursts, DefaultBursts), max(MaxBatchRequests/size, 1))
+	return bursts, size
+}
+
+// MaxRequests returns the number of requests the bursts send at most.
+func (b BurstRequest) MaxRequests() int {
+	bursts, size := b.shape()
+	return bursts * size
+}
+
+// LimitRequests cuts the bursts down to at most n requests, dropping whole
+// bursts first.
+func (b *BurstRequest) LimitRequests(n int) {
+	bursts, size := b.shape()
+	if n >= bursts*size {
+		return
+	}
+	size = max(min(size, n), 1)
+	bursts = max(n/size, 1)
+	b.Bursts, b.BurstSize = &bursts, &size
+}
+
+// ExecuteBurst sends a request in timed bursts and records when the server
+// starts answering 429, along with the rate limit headers of every response.
+// The total number of requests is capped at MaxBatchRequests. No further
+// bursts are sent once ctx is cancelled.
+func (s *HTTPService) ExecuteBurst(ctx context.Context, store storage.Store, burst BurstRequest) BurstResponse {
+	bursts, size := burst.shape()
+	interval := DefaultBurstIntervalMS
+	if burst.IntervalMS != nil && *burst.IntervalMS >= 0 {
+		interval = min(*burst.IntervalMS, MaxBurstIntervalMS)
+	}
+
+	started := s.clock.Now()
+	result := BurstResponse{Samples: []BurstSample{}}
+	for b := 0; b < bursts; b++ {
+		if b > 0 && !sleepContext(ctx, time.Duration(interval)*time.Millisecond) {
+			break
+		}
+
+		samples := make([]BurstSample, size)
+		var wg sync.WaitGroup
+		for i := range samples {
+			wg.Add(1)
+			samples[i] = BurstSample{
+				Burst:    b + 1,
+				Sequence: b*size + i + 1,
+				SentAt:   uint64(since(s.clock, started).Milliseconds()),
+			}
+			go func(sample *BurstSample) {
+				defer wg.Done()
+				resp := s.ExecuteContext(ctx, store, burst.Request)
+				if !resp.Success {
+					sample.Error = strPtr(resp.Error.Message)
+					return
+				}
+				sample.Status = &resp.Data.Status
+				sample.Time = resp.Data.Timing.Total
+				sample.RateLimit = resp.Data.RateLimit
+			}(&samples[i])
+		}
+		wg.Wait()
+
+		limited := result.addBurst(samples)
+		if limited && burst.StopWhenLimited {
+			break
+		}
+	}
+
+	result.TotalTime = uint64(since(s.clock, started).Milliseconds())
+	return result
+}
+
+// addBurst records the samples of one burst and reports whether any was rate limited.
+func (r *BurstResponse) addBurst(samples []BurstSample) bool {
+	limited := false
+	for i := range samples {
+		sample := &samples[i]
+		r.Samples = append(r.Samples, *sample)
+
+		if sample.Status == nil {
+			r.Failed++
+			continue
+		}
+		if *sample.Status != 429 {
+			r.Succeeded++
+		} else {
+			r.Limited++
+			limited = true
+			if r.FirstLimited == nil || sample.Sequence < *r.FirstLimited {
+				sequence := sample.Sequence
+				r.FirstLimited = &sequence
+			}
+		}
+
+		if rl := sample.RateLimit; rl != nil {
+			r.ObservedLimit = maxInt(r.ObservedLimit, rl.Limit)
+			r.ObservedWindow = maxInt(r.ObservedWindow, rl.Window)
+			r.ObservedWindow = maxInt(r.ObservedWindow, rl.Reset)
+			if *sample.Status == 429 && r.RetryAfter == nil {
+				r.RetryAfter = rl.RetryAfter
+			}
+		}
+	}
+	return limited
+}
+
+func positiveOr(value *int, fallback int) int {
+	if value == nil || *value <= 0 {
+		return fallback
+	}
+	return *value
+}
+
+// maxInt returns the larger of two optional values.
+func maxInt(a, b *int) *int {
+	if a == nil || (b != nil && *b > *a) {
+		return b
+	}
+	return a
+}
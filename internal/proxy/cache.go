@@ -0,0 +1,224 @@
+package proxy
+
+import (
+	"context"
+	"fmt"
+	"net/http"
+	"strconv"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+// maxHeuristicFreshness caps the freshness a cache may assume from
+// Last-Modified alone.
+const maxHeuristicFreshness = 24 * time.Hour
+
+// heuristicStatuses may be cached without explicit freshness (RFC 9110, section 15.1).
+var heuristicStatuses = map[int]bool{
+	200: true, 203: true, 204: true, 206: true, 300: true, 301: true, 308: true,
+	404: true, 405: true, 410: true, 414: true, 501: true,
+}
+
+// analyzeCache explains how HTTP caches treat a response (RFC 9111): whether
+// it may be stored, for how long it is fresh, and how it can be revalidated.
+// headers holds the first value of each header, keyed by lowercase name;
+// authorized tells whether the request carried an Authorization header.
+func analyzeCache(method string, authorized bool, status int, headers map[string]string, now time.Time) *CacheAnalysis {
+	directives := parseCacheControl(headers["cache-control"])
+	_, noStore := directives["no-store"]
+	_, noCache := directives["no-cache"]
+	_, private := directives["private"]
+	_, public := directives["public"]
+	_, mustRevalidate := directives["must-revalidate"]
+	_, immutable := directives["immutable"]
+
+	analysis := &CacheAnalysis{
+		Directives:     directives,
+		NoStore:        noStore,
+		NoCache:        noCache || headers["pragma"] == "no-cache" && headers["cache-control"] == "",
+		Private:        private,
+		Public:         public,
+		MustRevalidate: mustRevalidate,
+		Immutable:      immutable,
+		ETag:           optionalString(headers["etag"]),
+		LastModified:   optionalString(headers["last-modified"]),
+		Vary:           optionalString(headers["vary"]),
+		Notes:          []string{},
+	}
+	if analysis.ETag != nil {
+		weak := strings.HasPrefix(*analysis.ETag, "W/")
+		analysis.WeakETag = &weak
+	}
+
+	date := now
+	if parsed, err := http.ParseTime(headers["date"]); err == nil {
+		date = parsed
+	}
+
+	// Explicit freshness from max-age, else Expires relative to Date
+	lifetime := -1
+	if seconds, ok := directiveSeconds(directives, "max-age"); ok {
+		lifetime = seconds
+		analysis.FreshnessSource = "max-age"
+	} else if expires := headers["expires"]; expires != "" {
+		analysis.FreshnessSource = "Expires"
+		lifetime = 0 // An invalid date means already expired
+		if parsed, err := http.ParseTime(expires); err == nil {
+			lifetime = max(int(parsed.Sub(date).Seconds()), 0)
+		}
+	}
+	if seconds, ok := directiveSeconds(directives, "s-maxage"); ok {
+		analysis.SharedLifetime = &seconds
+	}
+
+	method = strings.ToUpper(method)
+	cacheableMethod := method == "GET" || method == "HEAD" || method == ""
+	explicit := lifetime >= 0 || public || analysis.SharedLifetime != nil
+	analysis.Cacheable = cacheableMethod && !noStore && (heuristicStatuses[status] || explicit)
+	// Shared caches only reuse authorized responses that explicitly allow it (RFC 9111, section 3.5)
+	analysis.SharedCacheable = analysis.Cacheable && !private &&
+		(!authorized || public || mustRevalidate || analysis.SharedLifetime != nil)
+
+	if lifetime < 0 && analysis.Cacheable && analysis.LastModified != nil {
+		if modified, err := http.ParseTime(*analysis.LastModified); err == nil && date.After(modified) {
+			lifetime = int(min(date.Sub(modified)/10, maxHeuristicFreshness).Seconds())
+			analysis.Heuristic = true
+			analysis.FreshnessSource = "heuristic"
+		}
+	}
+	if lifetime >= 0 && analysis.Cacheable {
+		analysis.FreshnessLifetime = &lifetime
+	}
+
+	// Age received plus the time since the response was generated (RFC 9111, section 4.2.3)
+	age := 0
+	if value, err := strconv.Atoi(strings.TrimSpace(headers["age"])); err == nil && value >= 0 {
+		age = value
+		analysis.AgeHeader = &value
+	}
+	age = max(age, int(now.Sub(date).Seconds()))
+	analysis.Age = age
+	if analysis.FreshnessLifetime != nil {
+		remaining := *analysis.FreshnessLifetime - age
+		fresh := remaining > 0 && !analysis.NoCache
+		analysis.Fresh = &fresh
+		analysis.RemainingFreshness = &remaining
+	}
+	if seconds, ok := directiveSeconds(directives, "stale-while-revalidate"); ok {
+		analysis.StaleWhileRevalidate = &seconds
+	}
+	if seconds, ok := directiveSeconds(directives, "stale-if-error"); ok {
+		analysis.StaleIfError = &seconds
+	}
+
+	analysis.notes(cacheableMethod, status)
+	return analysis
+}
+
+func (a *CacheAnalysis) notes(cacheableMethod bool, status int) {
+	note := func(format string, args ...interface{}) {
+		a.Notes = append(a.Notes, fmt.Sprintf(format, args...))
+	}
+	switch {
+	case !cacheableMethod:
+		note("Responses to this method are not reused by caches")
+	case a.NoStore:
+		note("no-store: caches must not keep the response")
+	case !a.Cacheable:
+		note("Status %d is not cacheable without explicit freshness", status)
+	case a.Heuristic:
+		note("No explicit freshness; caches may assume %ds from Last-Modified (10%% of its age)", *a.FreshnessLifetime)
+	case a.FreshnessLifetime == nil:
+		note("No explicit freshness or Last-Modified; caches must revalidate every use")
+	}
+	if a.NoCache && a.Cacheable {
+		note("no-cache: stored copies must be revalidated before each use")
+	}
+	if a.Private {
+		note("private: only the browser cache may store it, not shared caches or CDNs")
+	}
+	if a.Cacheable && a.ETag == nil && a.LastModified == nil {
+		note("No ETag or Last-Modified; stale copies cannot be revalidated and are fetched again in full")
+	}
+	if a.Vary != nil && strings.TrimSpace(*a.Vary) == "*" {
+		note("Vary: * makes every request a cache miss")
+	}
+	if a.Immutable && a.FreshnessLifetime != nil {
+		note("immutable: browsers skip revalidation while fresh, even on reload")
+	}
+}
+
+// parseCacheControl reads the directives of a Cache-Control header with
+// lowercase names. Directives without an argument have an empty value.
+func parseCacheControl(header string) map[string]string {
+	directives := map[string]string{}
+	for _, part := range strings.Split(header, ",") {
+		name, value, _ := strings.Cut(strings.TrimSpace(part), "=")
+		name = strings.ToLower(strings.TrimSpace(name))
+		if name != "" {
+			directives[name] = strings.Trim(strings.TrimSpace(value), `"`)
+		}
+	}
+	return directives
+}
+
+func directiveSeconds(directives map[string]string, name string) (int, bool) {
+	value, ok := directives[name]
+	if !ok {
+		return 0, false
+	}
+	seconds, err := strconv.Atoi(value)
+	if err != nil || seconds < 0 {
+		return 0, false
+	}
+	return seconds, true
+}
+
+// revalidateCache re-sends a request with the validators of its response,
+// as a cache would to reuse a stale copy, and measures the outcome. Only the
+// method, headers, credentials and connection settings are re-sent, and the
+// conditional request stays out of the history and sessions.
+func (s *HTTPService) revalidateCache(ctx context.Context, store storage.Store, request ProxyRequest, finalURL string, original *ResponseData, analysis *CacheAnalysis) *CacheRevalidation {
+	revalidation := &CacheRevalidation{Sent: map[string]string{}}
+	if analysis.ETag != nil {
+		revalidation.Sent["If-None-Match"] = *analysis.ETag
+	}
+	if analysis.LastModified != nil {
+		revalidation.Sent["If-Modified-Since"] = *analysis.LastModified
+	}
+	if len(revalidation.Sent) == 0 {
+		revalidation.Error = strPtr("The response has no ETag or Last-Modified to revalidate with")
+		return revalidation
+	}
+	headers := withoutHeaders(request.Headers, []string{"If-None-Match", "If-Modified-Since"})
+	for key, value := range revalidation.Sent {
+		headers[key] = value
+	}
+
+	resp := s.execute(ctx, store, ProxyRequest{
+		Method:   request.Method,
+		URL:      finalURL,
+		Headers:  headers,
+		Auth:     request.Auth,
+		Timeout:  request.Timeout,
+		PinToken: request.PinToken,
+	}, nil)
+	if !resp.Success {
+		revalidation.Error = strPtr(resp.Error.Message)
+		return revalidation
+	}
+
+	revalidation.Status = &resp.Data.Status
+	revalidation.NotModified = resp.Data.Status == http.StatusNotModified
+	revalidation.Time = &resp.Data.Timing.Total
+	revalidation.Size = resp.Data.Size
+	if revalidation.NotModified {
+		revalidation.TimeSaved = int64(original.Timing.Total) - int64(resp.Data.Timing.Total)
+		revalidation.BytesSaved = original.Size - resp.Data.Size
+	} else if resp.Data.Status == original.Status {
+		revalidation.Error = strPtr(fmt.Sprintf("Server answered %d with the full body instead of 304 Not Modified", resp.Data.Status))
+	}
+	return revalidation
+}
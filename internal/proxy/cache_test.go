@@ -0,0 +1,44 @@
+package proxy_test
+
+import (
+	"net/http"
+	"testing"
+
+	"zone.digit.tommie/internal/proxy"
+	"zone.digit.tommie/internal/testkit"
+)
+
+func TestRevalidate(t *testing.T) {
+	target := testkit.Start()
+	defer target.Close()
+	target.Handle("/cached", func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("ETag", `"v1"`)
+		if r.Header.Get("If-None-Match") == `"v1"` {
+			w.WriteHeader(http.StatusNotModified)
+			return
+		}
+		w.Write([]byte("cached body"))
+	})
+
+	data := success(t, target.Execute(proxy.ProxyRequest{
+		URL:        "/cached",
+		Auth:       &proxy.AuthOptions{Type: proxy.AuthBasic, Username: "user", Password: "secret"},
+		Revalidate: true,
+	}))
+	revalidation := data.CacheAnalysis.Revalidation
+	if revalidation == nil || !revalidation.NotModified || revalidation.BytesSaved != len("cached body") {
+		t.Fatalf("got revalidation %+v, want a 304 saving the body", revalidation)
+	}
+
+	requests := target.Requests()
+	if len(requests) != 2 {
+		t.Fatalf("target got %d requests, want 2", len(requests))
+	}
+	conditional := requests[1]
+	if conditional.Method != http.MethodGet || conditional.Header.Get("If-None-Match") != `"v1"` {
+		t.Errorf("conditional request %s with If-None-Match %q, want GET with \"v1\"", conditional.Method, conditional.Header.Get("If-None-Match"))
+	}
+	if conditional.Header.Get("Authorization") != requests[0].Header.Get("Authorization") {
+		t.Error("the conditional request did not carry the request's credentials")
+	}
+}
@@ -0,0 +1,101 @@
+package proxy
+
+import (
+	"bytes"
+	"encoding/base64"
+	"net"
+	"net/http"
+	"sync"
+	"unicode/utf8"
+)
+
+// DefaultRawCaptureLimit is the number of request bytes kept when no limit is given.
+const DefaultRawCaptureLimit = 64 << 10
+
+// wireCapture records the plaintext bytes written to a hop's connections.
+type wireCapture struct {
+	mu    sync.Mutex
+	buf   bytes.Buffer
+	limit int
+	total int
+}
+
+func newWireCapture(limit *int) *wireCapture {
+	c := &wireCapture{limit: DefaultRawCaptureLimit}
+	if limit != nil && *limit >= 0 {
+		c.limit = *limit
+	}
+	return c
+}
+
+func (c *wireCapture) record(p []byte) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+
+	c.total += len(p)
+	if room := c.limit - c.buf.Len(); room > 0 {
+		if len(p) > room {
+			p = p[:room]
+		}
+		c.buf.Write(p)
+	}
+}
+
+// result returns the captured bytes, or a reconstruction of req when nothing
+// readable was captured (encrypted tunnels and binary HTTP/2 framing).
+func (c *wireCapture) result(req *http.Request, body []byte, http2 bool) *RawRequest {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+
+	if c.total > 0 && !http2 {
+		return newRawRequest(c.buf.Bytes(), c.total, false)
+	}
+
+	rendered := []byte(renderRequest(req, len(body), string(body)))
+	size := len(rendered)
+	if len(rendered) > c.limit {
+		rendered = rendered[:c.limit]
+	}
+	return newRawRequest(rendered, size, true)
+}
+
+func newRawRequest(data []byte, size int, reconstructed bool) *RawRequest {
+	raw := &RawRequest{
+		Size:          size,
+		Truncated:     len(data) < size,
+		Reconstructed: reconstructed,
+	}
+	if utf8.Valid(data) {
+		raw.Data = string(data)
+		raw.Encoding = BodyEncodingText
+	} else {
+		raw.Data = base64.StdEncoding.EncodeToString(data)
+		raw.Encoding = BodyEncodingBase64
+	}
+	return raw
+}
+
+// recordingConn records the request bytes written to a connection and the
+// response head read back from it.
+type recordingConn struct {
+	net.Conn
+	capture *wireCapture // Nil unless raw capture was requested
+	head    *headRecorder
+}
+
+// Write writes to the connection, starting a new response head recording.
+func (c *recordingConn) Write(p []byte) (int, error) {
+	n, err := c.Conn.Write(p)
+	c.head.reset()
+	if c.capture != nil {
+		c.capture.record(p[:n])
+	}
+	return n, err
+}
+
+// Read reads from the connection, recording the response head.
+func (c *recordingConn) Read(p []byte) (int, error) {
+	n, err := c.Conn.Read(p)
+	c.head.record(p[:n])
+	return n, err
+}
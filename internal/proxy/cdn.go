@@ -0,0 +1,165 @@
+package proxy
+
+import (
+	"net"
+	"sort"
+	"strings"
+)
+
+// Signal weights used to score CDN evidence.
+const (
+	weightStrong = 3 // Provider-specific header
+	weightMedium = 2 // Server/Via banner or published IP range
+	weightWeak   = 1 // Certificate issuer
+)
+
+// cdnSignature lists the evidence identifying a CDN or hosting provider.
+type cdnSignature struct {
+	provider string
+	headers  []string          // Headers only this provider sets
+	contains map[string]string // Header whose lowercased value contains a marker
+	issuers  []string          // Certificate issuer substrings
+	networks []*net.IPNet      // Published edge ranges
+}
+
+var cdnSignatures = []cdnSignature{
+	{
+		provider: "Cloudflare",
+		headers:  []string{"cf-ray", "cf-cache-status"},
+		contains: map[string]string{"server": "cloudflare"},
+		issuers:  []string{"Cloudflare"},
+		networks: mustParseCIDRs(
+			"173.245.48.0/20", "103.21.244.0/22", "103.22.200.0/22", "103.31.4.0/22",
+			"141.101.64.0/18", "108.162.192.0/18", "190.93.240.0/20", "188.114.96.0/20",
+			"197.234.240.0/22", "198.41.128.0/17", "162.158.0.0/15", "104.16.0.0/13",
+			"104.24.0.0/14", "172.64.0.0/13", "131.0.72.0/22", "2400:cb00::/32",
+			"2606:4700::/32", "2803:f800::/32", "2405:b500::/32", "2405:8100::/32",
+			"2a06:98c0::/29", "2c0f:f248::/32",
+		),
+	},
+	{
+		provider: "Fastly",
+		headers:  []string{"x-fastly-request-id", "fastly-debug-digest"},
+		contains: map[string]string{"x-served-by": "cache-", "via": "varnish"},
+		networks: mustParseCIDRs(
+			"23.235.32.0/20", "43.249.72.0/22", "103.244.50.0/24", "103.245.222.0/23",
+			"103.245.224.0/24", "104.156.80.0/20", "140.248.64.0/18", "140.248.128.0/17",
+			"146.75.0.0/17", "151.101.0.0/16", "157.52.64.0/18", "167.82.0.0/17",
+			"167.82.128.0/20", "167.82.160.0/20", "167.82.224.0/20", "172.111.64.0/18",
+			"185.31.16.0/22", "199.27.72.0/21", "199.232.0.0/16", "2a04:4e40::/32",
+			"2a04:4e42::/32",
+		),
+	},
+	{
+		provider: "Akamai",
+		headers:  []string{"akamai-grn", "x-akamai-transformed", "x-akamai-request-id", "akamai-cache-status"},
+		contains: map[string]string{"server": "akamai", "x-cache": "akamai"},
+	},
+	{
+		provider: "Amazon CloudFront",
+		headers:  []string{"x-amz-cf-id", "x-amz-cf-pop"},
+		contains: map[string]string{"server": "cloudfront", "via": "cloudfront", "x-cache": "cloudfront"},
+		issuers:  []string{"Amazon"},
+	},
+	{
+		provider: "Google Cloud CDN",
+		contains: map[string]string{"via": "google", "server": "google frontend"},
+		issuers:  []string{"Google Trust Services"},
+	},
+	{
+		provider: "Azure Front Door",
+		headers:  []string{"x-azure-ref"},
+		issuers:  []string{"Microsoft"},
+	},
+	{
+		provider: "Vercel",
+		headers:  []string{"x-vercel-id", "x-vercel-cache"},
+		contains: map[string]string{"server": "vercel"},
+	},
+	{
+		provider: "Netlify",
+		headers:  []string{"x-nf-request-id"},
+		contains: map[string]string{"server": "netlify"},
+	},
+}
+
+func mustParseCIDRs(cidrs ...string) []*net.IPNet {
+	networks := make([]*net.IPNet, 0, len(cidrs))
+	for _, cidr := range cidrs {
+		_, network, err := net.ParseCIDR(cidr)
+		if err != nil {
+			panic("invalid CDN range " + cidr)
+		}
+		networks = append(networks, network)
+	}
+	return networks
+}
+
+// detectServedBy infers the CDN or provider serving a response from its
+// headers, certificate issuer and server IP. It returns nil without evidence.
+func detectServedBy(headers map[string]string, issuer string, serverIP string) *ServedBy {
+	ip := net.ParseIP(serverIP)
+
+	var best *ServedBy
+	bestScore := 0
+	for _, sig := range cdnSignatures {
+		score := 0
+		var evidence []string
+
+		for _, name := range sig.headers {
+			if _, ok := headers[name]; ok {
+				score += weightStrong
+				evidence = append(evidence, name+" header")
+			}
+		}
+
+		// Sorted so evidence is reported in a stable order
+		names := make([]string, 0, len(sig.contains))
+		for name := range sig.contains {
+			names = append(names, name)
+		}
+		sort.Strings(names)
+		for _, name := range names {
+			if strings.Contains(strings.ToLower(headers[name]), sig.contains[name]) {
+				score += weightMedium
+				evidence = append(evidence, name+": "+headers[name])
+			}
+		}
+
+		if ip != nil {
+			for _, network := range sig.networks {
+				if network.Contains(ip) {
+					score += weightMedium
+					evidence = append(evidence, "IP "+serverIP+" in "+network.String())
+					break
+				}
+			}
+		}
+
+		for _, marker := range sig.issuers {
+			if strings.Contains(issuer, marker) {
+				score += weightWeak
+				evidence = append(evidence, "certificate issued by "+issuer)
+				break
+			}
+		}
+
+		if score > bestScore {
+			bestScore = score
+			best = &ServedBy{Provider: sig.provider, Evidence: evidence}
+		}
+	}
+
+	if best == nil {
+		return nil
+	}
+	switch {
+	case bestScore >= weightStrong:
+		best.Confidence = "high"
+	case bestScore >= weightMedium:
+		best.Confidence = "medium"
+	default:
+		best.Confidence = "low"
+	}
+	return best
+}
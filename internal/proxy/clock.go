@@ -0,0 +1,58 @@
+package proxy
+
+import (
+	"sync"
+	"time"
+)
+
+// Clock tells the time for timing measurements. The service uses the system
+// clock unless Options.Clock replaces it, e.g. with a MockClock to produce
+// deterministic or simulated timings.
+type Clock interface {
+	Now() time.Time
+}
+
+// SystemClock is the wall clock.
+var SystemClock Clock = systemClock{}
+
+type systemClock struct{}
+
+func (systemClock) Now() time.Time { return time.Now() }
+
+// MockClock is a Clock that only moves when told to. It is safe for
+// concurrent use.
+type MockClock struct {
+	mu  sync.Mutex
+	now time.Time
+}
+
+// NewMockClock returns a clock stopped at start.
+func NewMockClock(start time.Time) *MockClock {
+	return &MockClock{now: start}
+}
+
+// Now returns the current time of the clock.
+func (c *MockClock) Now() time.Time {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	return c.now
+}
+
+// Advance moves the clock forward by d.
+func (c *MockClock) Advance(d time.Duration) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	c.now = c.now.Add(d)
+}
+
+// Set moves the clock to t.
+func (c *MockClock) Set(t time.Time) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	c.now = t
+}
+
+// since returns the time elapsed on clock since start.
+func since(clock Clock, start time.Time) time.Duration {
+	return clock.Now().Sub(start)
+}
@@ -0,0 +1,56 @@
+package proxy
+
+import (
+	"fmt"
+	"net/http"
+	"strconv"
+	"time"
+)
+
+// ClockSkewWarning is the skew, beyond the measurement uncertainty, reported as significant.
+const ClockSkewWarning = 5 * time.Second
+
+// clockSkew estimates how far the server clock is ahead of the local clock
+// from the Date header, assuming the server produced it halfway between
+// sending the request and receiving the first byte. Cached responses are
+// corrected by their Age. It returns nil without a usable Date header.
+func clockSkew(headers map[string]string, timing *DetailedTiming) *ClockSkewInfo {
+	if timing.RequestStart == nil || timing.TTFB == nil {
+		return nil
+	}
+	serverTime, err := http.ParseTime(headers["date"])
+	if err != nil {
+		return nil
+	}
+
+	info := &ClockSkewInfo{ServerDate: serverTime.UTC().Format(time.RFC3339)}
+	if age, err := strconv.Atoi(headers["age"]); err == nil && age > 0 {
+		// Date is when the origin produced the response, not when the cache served it
+		serverTime = serverTime.Add(time.Duration(age) * time.Second)
+		info.Age = &age
+	}
+
+	sent, received := *timing.RequestStart, *timing.TTFB
+	rtt := received.Sub(sent)
+	local := sent.Add(rtt / 2)
+
+	// Date is truncated to whole seconds, so the server time lies within the following second
+	skew := serverTime.Add(500 * time.Millisecond).Sub(local)
+	uncertainty := rtt/2 + 500*time.Millisecond
+
+	info.LocalTime = local.UTC().Format(time.RFC3339Nano)
+	info.Skew = skew.Milliseconds()
+	info.Uncertainty = uint64(uncertainty.Milliseconds())
+
+	magnitude := skew.Abs()
+	if magnitude-uncertainty >= ClockSkewWarning {
+		info.Significant = true
+		direction := "ahead of"
+		if skew < 0 {
+			direction = "behind"
+		}
+		info.Message = strPtr(fmt.Sprintf("Server clock is about %s %s the local clock; tokens may appear expired or not yet valid",
+			magnitude.Round(time.Second), direction))
+	}
+	return info
+}
@@ -0,0 +1,187 @@
+package proxy
+
+import (
+	"sort"
+	"strconv"
+	"strings"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+// ToCurl renders the request as a copy-pasteable curl command that
+// reproduces how the executor sends it: redirects are followed, the timeout
+// applies to the whole transfer and responses are decompressed.
+func (r ProxyRequest) ToCurl() string {
+	args := []string{"curl"}
+	var prefix string
+
+	hasBody := r.Body != nil || r.BodyBase64 != nil || len(r.FormData) > 0 || r.GraphQL != nil
+	method := strings.ToUpper(r.Method)
+	if method == "" && r.GraphQL != nil {
+		method = "POST"
+	} else if method == "" {
+		method = "GET"
+	}
+	if !(method == "GET" && !hasBody) && !(method == "POST" && hasBody) {
+		args = append(args, "-X", method)
+	}
+
+	// Redirects
+	args = append(args, "-L", "--max-redirs", strconv.Itoa(MaxRedirects))
+	if r.StrictRedirects {
+		args = append(args, "--post301", "--post302", "--post303")
+	}
+	if r.ForwardCredentials {
+		args = append(args, "--location-trusted")
+	}
+
+	timeoutMS := uint64(DefaultTimeoutMS)
+	if r.Timeout != nil {
+		timeoutMS = *r.Timeout
+	}
+	args = append(args, "--max-time", strconv.FormatFloat(float64(timeoutMS)/1000, 'f', -1, 64))
+
+	if policy := r.Retry; policy != nil {
+		attempts := min(positiveOr(policy.MaxAttempts, DefaultRetryAttempts), MaxRetryAttempts)
+		args = append(args, "--retry", strconv.Itoa(attempts-1))
+		// curl takes whole seconds and otherwise doubles its delay from one second
+		if policy.Backoff != nil && *policy.Backoff == "constant" {
+			delay := positiveOr(policy.DelayMS, DefaultRetryDelayMS)
+			args = append(args, "--retry-delay", strconv.Itoa((delay+999)/1000))
+		}
+	}
+
+	// curl has no DNS-over-TLS, so only plain and DNS-over-HTTPS nameservers carry over
+	var dnsServers []string
+	dohURL := ""
+	for _, spec := range r.DNSServers {
+		ns, err := infra.ParseNameserver(spec)
+		switch {
+		case err != nil:
+		case ns.Protocol == "udp":
+			dnsServers = append(dnsServers, ns.Address)
+		case ns.Protocol == "https" && dohURL == "":
+			dohURL = ns.Address
+		}
+	}
+	if len(dnsServers) > 0 {
+		args = append(args, "--dns-servers", strings.Join(dnsServers, ","))
+	}
+	if dohURL != "" {
+		args = append(args, "--doh-url", shellQuote(dohURL))
+	}
+
+	if version, err := parseHTTPVersion(r.HTTPVersion); err == nil {
+		switch version {
+		case httpVersion10:
+			args = append(args, "--http1.0")
+		case httpVersion11:
+			args = append(args, "--http1.1")
+		case httpVersion2:
+			args = append(args, "--http2")
+		case httpVersion3:
+			args = append(args, "--http3-only")
+		}
+	}
+
+	// Headers in a stable order
+	names := make([]string, 0, len(r.Headers))
+	acceptEncoding := false
+	for name := range r.Headers {
+		names = append(names, name)
+		acceptEncoding = acceptEncoding || strings.EqualFold(name, "Accept-Encoding")
+	}
+	sort.Strings(names)
+	for _, name := range names {
+		// The multipart boundary is generated by curl and GraphQL is always JSON
+		if (len(r.FormData) > 0 || r.GraphQL != nil) && strings.EqualFold(name, "Content-Type") {
+			continue
+		}
+		// Auth options replace any Authorization header given
+		if r.Auth != nil && strings.EqualFold(name, "Authorization") {
+			continue
+		}
+		args = append(args, "-H", shellQuote(name+": "+r.Headers[name]))
+	}
+	if r.GraphQL != nil {
+		args = append(args, "-H", shellQuote("Content-Type: application/json"))
+	}
+	if a := r.Auth; a != nil {
+		switch strings.ToLower(a.Type) {
+		case AuthBasic:
+			args = append(args, "-u", shellQuote(a.Username+":"+a.Password))
+		case AuthDigest:
+			args = append(args, "--digest", "-u", shellQuote(a.Username+":"+a.Password))
+		case AuthNTLM:
+			args = append(args, "--ntlm", "-u", shellQuote(a.Username+":"+a.Password))
+		case AuthNegotiate:
+			// -u : makes curl use the current user's Kerberos credentials
+			args = append(args, "--negotiate", "-u", shellQuote(a.Username+":"+a.Password))
+		case AuthBearer:
+			args = append(args, "-H", shellQuote("Authorization: Bearer "+a.Token))
+		}
+	}
+	if !acceptEncoding {
+		args = append(args, "--compressed")
+	}
+
+	switch {
+	case len(r.FormData) > 0:
+		for _, part := range r.FormData {
+			args = append(args, "-F", shellQuote(curlFormPart(part)))
+		}
+	case r.BodyBase64 != nil:
+		// Binary bodies are piped in so they survive the shell untouched
+		prefix = "printf '%s' " + shellQuote(*r.BodyBase64) + " | base64 -d | "
+		args = append(args, "--data-binary", "@-")
+	case r.Body != nil:
+		args = append(args, "--data-raw", shellQuote(*r.Body))
+	case r.GraphQL != nil:
+		if body, err := encodeGraphQL(r.GraphQL); err == nil {
+			args = append(args, "--data-raw", shellQuote(string(body.raw)))
+		}
+	}
+
+	args = append(args, shellQuote(r.URL))
+	return prefix + strings.Join(args, " ")
+}
+
+// curlFormPart renders a multipart field for curl's -F option. File parts
+// reference a local file of the same name, since content cannot be inlined.
+func curlFormPart(part FormPart) string {
+	if part.FileBase64 == nil {
+		value := ""
+		if part.Value != nil {
+			value = *part.Value
+		}
+		// A leading @ or < would make curl read a file
+		if strings.HasPrefix(value, "@") || strings.HasPrefix(value, "<") {
+			return part.Name + "=\"" + strings.ReplaceAll(value, "\"", "\\\"") + "\""
+		}
+		return part.Name + "=" + value
+	}
+
+	filename := part.Name
+	if part.Filename != nil {
+		filename = *part.Filename
+	}
+	spec := part.Name + "=@" + filename
+	if part.ContentType != nil {
+		spec += ";type=" + *part.ContentType
+	}
+	return spec
+}
+
+// shellQuote quotes a value for POSIX shells. Variable references placed by
+// ToRedactedCurl are left for the shell to expand.
+func shellQuote(s string) string {
+	if strings.Contains(s, redactSentinel) {
+		return quoteWithVariables(s)
+	}
+	if s != "" && strings.IndexFunc(s, func(r rune) bool {
+		return !(r >= 'a' && r <= 'z' || r >= 'A' && r <= 'Z' || r >= '0' && r <= '9' || strings.ContainsRune("-_./:=@%+,", r))
+	}) < 0 {
+		return s
+	}
+	return "'" + strings.ReplaceAll(s, "'", `'\''`) + "'"
+}
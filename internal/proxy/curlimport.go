@@ -0,0 +1,675 @@
+package proxy
+
+import (
+	"encoding/base64"
+	"errors"
+	"fmt"
+	"net/url"
+	"path"
+	"strconv"
+	"strings"
+	"unicode/utf8"
+)
+
+// curlArgOptions are the long options that consume the following word.
+var curlArgOptions = wordSet(`request header data data-raw data-binary data-ascii
+	data-urlencode form form-string user cookie user-agent referer max-time url
+	oauth2-bearer max-redirs output proxy proxy-user write-out cert key cacert
+	connect-timeout retry resolve connect-to cookie-jar config interface limit-rate
+	dns-servers doh-url retry-delay`)
+
+// curlShortOptions maps single-letter options to their long names.
+var curlShortOptions = map[byte]string{
+	'X': "request", 'H': "header", 'd': "data", 'F': "form", 'u': "user", 'b': "cookie",
+	'A': "user-agent", 'e': "referer", 'm': "max-time", 'I': "head", 'G': "get",
+	'k': "insecure", 'L': "location", 's': "silent", 'S': "show-error", 'v': "verbose",
+	'i': "include", 'f': "fail", 'g': "globoff", 'N': "no-buffer", 'o': "output",
+	'O': "remote-name", 'x': "proxy", 'w': "write-out", 'E': "cert", 'U': "proxy-user",
+	'c': "cookie-jar", 'K': "config", '0': "http1.0",
+}
+
+// curlNoopOptions only affect curl's own output or are the executor's default.
+var curlNoopOptions = wordSet(`location compressed max-redirs silent show-error
+	verbose include fail globoff no-buffer progress-bar no-progress-meter`)
+
+func wordSet(words string) map[string]bool {
+	set := make(map[string]bool)
+	for _, w := range strings.Fields(words) {
+		set[w] = true
+	}
+	return set
+}
+
+// curlParser accumulates the request described by curl options.
+type curlParser struct {
+	readFile   func(name string) ([]byte, error)
+	stdin      []byte
+	request    ProxyRequest
+	method     string
+	head       bool
+	get        bool
+	data       [][]byte
+	binaryData bool
+	warnings   []string
+}
+
+// ParseCurl converts a curl command line into a request. Files referenced
+// with @file are read through readFile; a nil readFile leaves them out with a
+// warning. The base64 pipe produced by ToCurl is understood as stdin.
+func ParseCurl(command string, readFile func(name string) ([]byte, error)) (*CurlImport, error) {
+	segments, err := shellWords(command)
+	if err != nil {
+		return nil, err
+	}
+
+	p := &curlParser{readFile: readFile, request: ProxyRequest{Headers: map[string]string{}}}
+	curlAt := -1
+	for i, words := range segments {
+		if len(words) > 0 && isCurlBinary(words[0]) {
+			curlAt = i
+			break
+		}
+	}
+	if curlAt < 0 {
+		return nil, errors.New("not a curl command")
+	}
+	if curlAt > 0 {
+		if stdin, ok := base64Pipe(segments[:curlAt]); ok {
+			p.stdin = stdin
+		} else {
+			p.warn("Commands piped into curl are ignored")
+		}
+	}
+	if curlAt < len(segments)-1 {
+		p.warn("Commands after curl are ignored")
+	}
+
+	if err := p.parseArgs(segments[curlAt][1:]); err != nil {
+		return nil, err
+	}
+	if err := p.finish(); err != nil {
+		return nil, err
+	}
+	return &CurlImport{Request: p.request, Warnings: p.warnings}, nil
+}
+
+func isCurlBinary(word string) bool {
+	name := strings.ToLower(path.Base(strings.ReplaceAll(word, `\`, "/")))
+	return name == "curl" || name == "curl.exe"
+}
+
+// base64Pipe recognizes `printf '%s' <base64> | base64 -d` ahead of curl.
+func base64Pipe(segments [][]string) ([]byte, bool) {
+	if len(segments) != 2 {
+		return nil, false
+	}
+	printf, decode := segments[0], segments[1]
+	if len(printf) != 3 || printf[0] != "printf" || printf[1] != "%s" {
+		return nil, false
+	}
+	if len(decode) != 2 || decode[0] != "base64" || (decode[1] != "-d" && decode[1] != "--decode") {
+		return nil, false
+	}
+	data, err := base64.StdEncoding.DecodeString(printf[2])
+	return data, err == nil
+}
+
+func (p *curlParser) warn(format string, args ...interface{}) {
+	p.warnings = append(p.warnings, fmt.Sprintf(format, args...))
+}
+
+func (p *curlParser) parseArgs(args []string) error {
+	for i := 0; i < len(args); i++ {
+		arg := args[i]
+
+		// value returns the option argument, attached or in the next word
+		value := func(name, attached string) (string, error) {
+			if attached != "" {
+				return attached, nil
+			}
+			if i+1 >= len(args) {
+				return "", fmt.Errorf("option %s requires a value", name)
+			}
+			i++
+			return args[i], nil
+		}
+
+		switch {
+		case strings.HasPrefix(arg, "--") && len(arg) > 2:
+			name := arg[2:]
+			var v string
+			if curlArgOptions[name] {
+				var err error
+				if v, err = value(arg, ""); err != nil {
+					return err
+				}
+			}
+			if err := p.apply(name, v); err != nil {
+				return err
+			}
+
+		case strings.HasPrefix(arg, "-") && len(arg) > 1:
+			// Short options may be combined, and the last may carry its value
+			for j := 1; j < len(arg); j++ {
+				name, ok := curlShortOptions[arg[j]]
+				if !ok {
+					p.warn("Ignored unsupported option -%c", arg[j])
+					continue
+				}
+				var v string
+				if curlArgOptions[name] {
+					var err error
+					if v, err = value("-"+string(arg[j]), arg[j+1:]); err != nil {
+						return err
+					}
+					j = len(arg)
+				}
+				if err := p.apply(name, v); err != nil {
+					return err
+				}
+			}
+
+		default:
+			p.setURL(arg)
+		}
+	}
+	return nil
+}
+
+// apply handles a single option by its long name.
+func (p *curlParser) apply(name, value string) error {
+	switch name {
+	case "request":
+		p.method = strings.ToUpper(value)
+	case "head":
+		p.head = true
+	case "get":
+		p.get = true
+	case "url":
+		p.setURL(value)
+	case "header":
+		p.addHeader(value)
+	case "user-agent":
+		p.setHeader("User-Agent", value)
+	case "referer":
+		p.setHeader("Referer", strings.TrimSuffix(value, ";auto"))
+	case "cookie":
+		if !strings.Contains(value, "=") {
+			p.warn("Cookie file %s is ignored", value)
+			return nil
+		}
+		if existing, ok := p.header("Cookie"); ok && existing != "" {
+			value = existing + "; " + value
+		}
+		p.setHeader("Cookie", value)
+	case "user":
+		if !strings.Contains(value, ":") {
+			p.warn("No password given for user %s; an empty password is used", value)
+			value += ":"
+		}
+		p.setHeader("Authorization", "Basic "+base64.StdEncoding.EncodeToString([]byte(value)))
+	case "oauth2-bearer":
+		p.setHeader("Authorization", "Bearer "+value)
+
+	case "data", "data-ascii":
+		return p.addData(value, true, false)
+	case "data-raw":
+		p.data = append(p.data, []byte(value))
+	case "data-binary":
+		return p.addData(value, false, true)
+	case "data-urlencode":
+		return p.addURLEncoded(value)
+	case "form":
+		return p.addForm(value, false)
+	case "form-string":
+		return p.addForm(value, true)
+
+	case "max-time":
+		seconds, err := strconv.ParseFloat(value, 64)
+		if err != nil || seconds < 0 {
+			return fmt.Errorf("invalid --max-time value %q", value)
+		}
+		timeout := uint64(seconds * 1000)
+		p.request.Timeout = &timeout
+	case "http1.0":
+		p.request.HTTPVersion = strPtr("1.0")
+	case "http1.1":
+		p.request.HTTPVersion = strPtr("1.1")
+	case "http2", "http2-prior-knowledge":
+		p.request.HTTPVersion = strPtr("2")
+	case "http3", "http3-only":
+		p.request.HTTPVersion = strPtr("3")
+	case "post301", "post302", "post303":
+		p.request.StrictRedirects = true
+	case "location-trusted":
+		p.request.ForwardCredentials = true
+	case "dns-servers":
+		for _, server := range strings.Split(value, ",") {
+			p.request.DNSServers = append(p.request.DNSServers, strings.TrimSpace(server))
+		}
+	case "doh-url":
+		p.request.DNSServers = append(p.request.DNSServers, value)
+	case "retry":
+		n, err := strconv.Atoi(value)
+		if err != nil || n < 0 {
+			return fmt.Errorf("invalid --retry value %q", value)
+		}
+		attempts := n + 1
+		p.retry().MaxAttempts = &attempts
+	case "retry-delay":
+		seconds, err := strconv.ParseFloat(value, 64)
+		if err != nil || seconds < 0 {
+			return fmt.Errorf("invalid --retry-delay value %q", value)
+		}
+		delay := int(seconds * 1000)
+		p.retry().DelayMS = &delay
+		p.retry().Backoff = strPtr("constant")
+	case "retry-connrefused":
+		p.retry().RetryOn = append(p.retry().RetryOn, "connect")
+	case "retry-all-errors":
+		p.retry().RetryOn = append(p.retry().RetryOn, "4xx", "5xx", "dns", "connect")
+	case "insecure":
+		p.warn("Certificate verification cannot be disabled; -k is ignored")
+
+	default:
+		if curlNoopOptions[name] {
+			return nil
+		}
+		p.warn("Ignored unsupported option --%s", name)
+	}
+	return nil
+}
+
+// retry returns the retry policy of the request, starting from what curl
+// treats as transient: timeouts and 408, 429, 500, 502, 503 and 504, for any
+// method.
+func (p *curlParser) retry() *RetryPolicy {
+	if p.request.Retry == nil {
+		p.request.Retry = &RetryPolicy{
+			RetryOn:     []string{"408", "429", "500", "502", "503", "504", "timeout"},
+			RetryUnsafe: true,
+		}
+	}
+	return p.request.Retry
+}
+
+func (p *curlParser) setURL(value string) {
+	if p.request.URL != "" {
+		p.warn("Only the first URL is imported; %s is ignored", value)
+		return
+	}
+	p.request.URL = value
+}
+
+// addHeader parses a -H value. "Name;" sends an empty header and "Name:"
+// removes one, as in curl.
+func (p *curlParser) addHeader(value string) {
+	if name, ok := strings.CutSuffix(value, ";"); ok && !strings.Contains(name, ":") {
+		p.setHeader(strings.TrimSpace(name), "")
+		return
+	}
+	name, v, ok := strings.Cut(value, ":")
+	if !ok {
+		p.warn("Ignored malformed header %q", value)
+		return
+	}
+	name = strings.TrimSpace(name)
+	v = strings.TrimSpace(v)
+	if v == "" {
+		p.deleteHeader(name)
+		return
+	}
+	p.setHeader(name, v)
+}
+
+// header looks a header up case-insensitively.
+func (p *curlParser) header(name string) (string, bool) {
+	for k, v := range p.request.Headers {
+		if strings.EqualFold(k, name) {
+			return v, true
+		}
+	}
+	return "", false
+}
+
+func (p *curlParser) setHeader(name, value string) {
+	p.deleteHeader(name)
+	p.request.Headers[name] = value
+}
+
+func (p *curlParser) deleteHeader(name string) {
+	for k := range p.request.Headers {
+		if strings.EqualFold(k, name) {
+			delete(p.request.Headers, k)
+		}
+	}
+}
+
+// file resolves an @name reference, with @- meaning stdin.
+func (p *curlParser) file(name string) ([]byte, bool) {
+	if name == "-" {
+		if p.stdin == nil {
+			p.warn("Body read from stdin is not available")
+			return nil, false
+		}
+		return p.stdin, true
+	}
+	if p.readFile == nil {
+		p.warn("File %s cannot be read here and is left out", name)
+		return nil, false
+	}
+	data, err := p.readFile(name)
+	if err != nil {
+		p.warn("Failed to read %s: %v", name, err)
+		return nil, false
+	}
+	return data, true
+}
+
+// addData appends a -d style value. Text data has newlines stripped from
+// files, as curl does.
+func (p *curlParser) addData(value string, stripNewlines, binary bool) error {
+	name, ok := strings.CutPrefix(value, "@")
+	if !ok {
+		p.data = append(p.data, []byte(value))
+		return nil
+	}
+	data, ok := p.file(name)
+	if !ok {
+		return nil
+	}
+	if stripNewlines {
+		data = []byte(strings.NewReplacer("\r", "", "\n", "").Replace(string(data)))
+	}
+	p.data = append(p.data, data)
+	p.binaryData = p.binaryData || binary
+	return nil
+}
+
+// addURLEncoded handles the content, =content, name=content, @file and
+// name@file forms of --data-urlencode.
+func (p *curlParser) addURLEncoded(value string) error {
+	var name, content string
+	if i := strings.IndexAny(value, "=@"); i >= 0 {
+		name = value[:i]
+		if value[i] == '@' {
+			data, ok := p.file(value[i+1:])
+			if !ok {
+				return nil
+			}
+			content = string(data)
+		} else {
+			content = value[i+1:]
+		}
+	} else {
+		content = value
+	}
+
+	encoded := url.QueryEscape(content)
+	if name != "" {
+		encoded = name + "=" + encoded
+	}
+	p.data = append(p.data, []byte(encoded))
+	return nil
+}
+
+// addForm parses a -F value: name=value, name=@file;type=...;filename=...
+// or name=<file. Literal values are taken verbatim.
+func (p *curlParser) addForm(value string, literal bool) error {
+	name, v, ok := strings.Cut(value, "=")
+	if !ok {
+		return fmt.Errorf("invalid form field %q", value)
+	}
+	part := FormPart{Name: name}
+
+	switch {
+	case literal:
+		part.Value = strPtr(v)
+	case strings.HasPrefix(v, `"`):
+		part.Value = strPtr(unquoteFormValue(v))
+	case strings.HasPrefix(v, "@"):
+		fields := strings.Split(v[1:], ";")
+		data, ok := p.file(fields[0])
+		if !ok {
+			return nil
+		}
+		part.FileBase64 = strPtr(base64.StdEncoding.EncodeToString(data))
+		part.Filename = strPtr(path.Base(strings.ReplaceAll(fields[0], `\`, "/")))
+		for _, field := range fields[1:] {
+			key, attr, _ := strings.Cut(field, "=")
+			switch strings.TrimSpace(key) {
+			case "type":
+				part.ContentType = strPtr(attr)
+			case "filename":
+				part.Filename = strPtr(attr)
+			}
+		}
+	case strings.HasPrefix(v, "<"):
+		data, ok := p.file(strings.SplitN(v[1:], ";", 2)[0])
+		if !ok {
+			return nil
+		}
+		part.Value = strPtr(string(data))
+	default:
+		part.Value = strPtr(strings.SplitN(v, ";type=", 2)[0])
+	}
+
+	p.request.FormData = append(p.request.FormData, part)
+	return nil
+}
+
+// unquoteFormValue strips the double quotes curl accepts around form values.
+func unquoteFormValue(v string) string {
+	var b strings.Builder
+	for i := 1; i < len(v); i++ {
+		switch {
+		case v[i] == '\\' && i+1 < len(v):
+			i++
+			b.WriteByte(v[i])
+		case v[i] == '"':
+			return b.String()
+		default:
+			b.WriteByte(v[i])
+		}
+	}
+	return b.String()
+}
+
+// finish resolves the method, URL and body once all options are known.
+func (p *curlParser) finish() error {
+	if p.request.URL == "" {
+		return errors.New("no URL found in curl command")
+	}
+	if !strings.Contains(p.request.URL, "://") {
+		p.request.URL = "http://" + p.request.URL
+	}
+	if len(p.request.FormData) > 0 && len(p.data) > 0 {
+		return errors.New("form and data options cannot be combined")
+	}
+
+	body := []byte(strings.Join(byteStrings(p.data), "&"))
+	hasData := len(p.data) > 0
+	if p.get && hasData {
+		separator := "?"
+		if strings.Contains(p.request.URL, "?") {
+			separator = "&"
+		}
+		p.request.URL += separator + string(body)
+		hasData = false
+	}
+
+	switch {
+	case p.method != "":
+		p.request.Method = p.method
+	case p.head:
+		p.request.Method = "HEAD"
+	case hasData || len(p.request.FormData) > 0:
+		p.request.Method = "POST"
+	default:
+		p.request.Method = "GET"
+	}
+
+	if hasData {
+		if p.binaryData || !utf8.Valid(body) {
+			p.request.BodyBase64 = strPtr(base64.StdEncoding.EncodeToString(body))
+		} else {
+			p.request.Body = strPtr(string(body))
+		}
+		if _, ok := p.header("Content-Type"); !ok {
+			p.setHeader("Content-Type", "application/x-www-form-urlencoded")
+		}
+	}
+	return nil
+}
+
+func byteStrings(data [][]byte) []string {
+	out := make([]string, len(data))
+	for i, d := range data {
+		out[i] = string(d)
+	}
+	return out
+}
+
+// shellWords splits a POSIX shell command line into words, grouped by
+// pipeline segment. Single, double and $'...' quoting, backslash escapes and
+// line continuations are supported.
+func shellWords(command string) ([][]string, error) {
+	var segments [][]string
+	var words []string
+	var word strings.Builder
+	inWord := false
+
+	flush := func() {
+		if inWord {
+			words = append(words, word.String())
+			word.Reset()
+			inWord = false
+		}
+	}
+
+	for i := 0; i < len(command); i++ {
+		c := command[i]
+		switch {
+		case c == '\\' && i+1 < len(command):
+			i++
+			if command[i] == '\n' {
+				continue
+			}
+			if command[i] == '\r' && i+1 < len(command) && command[i+1] == '\n' {
+				i++
+				continue
+			}
+			word.WriteByte(command[i])
+			inWord = true
+
+		case c == ' ' || c == '\t' || c == '\n' || c == '\r':
+			flush()
+
+		case c == '|':
+			flush()
+			segments = append(segments, words)
+			words = nil
+
+		case c == '\'':
+			end := strings.IndexByte(command[i+1:], '\'')
+			if end < 0 {
+				return nil, errors.New("unterminated single quote")
+			}
+			word.WriteString(command[i+1 : i+1+end])
+			i += end + 1
+			inWord = true
+
+		case c == '$' && i+1 < len(command) && command[i+1] == '\'':
+			n, err := readANSIQuoted(command[i+2:], &word)
+			if err != nil {
+				return nil, err
+			}
+			i += n + 1
+			inWord = true
+
+		case c == '"':
+			n, err := readDoubleQuoted(command[i+1:], &word)
+			if err != nil {
+				return nil, err
+			}
+			i += n
+			inWord = true
+
+		default:
+			word.WriteByte(c)
+			inWord = true
+		}
+	}
+	flush()
+	return append(segments, words), nil
+}
+
+// readDoubleQuoted reads up to the closing quote and returns the bytes consumed.
+func readDoubleQuoted(s string, word *strings.Builder) (int, error) {
+	for i := 0; i < len(s); i++ {
+		switch c := s[i]; {
+		case c == '"':
+			return i + 1, nil
+		case c == '\\' && i+1 < len(s) && strings.IndexByte("$`\"\\\n", s[i+1]) >= 0:
+			i++
+			if s[i] != '\n' {
+				word.WriteByte(s[i])
+			}
+		default:
+			word.WriteByte(c)
+		}
+	}
+	return 0, errors.New("unterminated double quote")
+}
+
+// readANSIQuoted reads a $'...' string, as produced by browser "Copy as cURL",
+// and returns the bytes consumed.
+func readANSIQuoted(s string, word *strings.Builder) (int, error) {
+	for i := 0; i < len(s); i++ {
+		c := s[i]
+		if c == '\'' {
+			return i + 1, nil
+		}
+		if c != '\\' || i+1 >= len(s) {
+			word.WriteByte(c)
+			continue
+		}
+
+		i++
+		switch s[i] {
+		case 'n':
+			word.WriteByte('\n')
+		case 't':
+			word.WriteByte('\t')
+		case 'r':
+			word.WriteByte('\r')
+		case 'e', 'E':
+			word.WriteByte(0x1b)
+		case 'x':
+			n, digits := parseHexEscape(s[i+1:], 2)
+			word.WriteByte(byte(n))
+			i += digits
+		case 'u', 'U':
+			maxDigits := 4
+			if s[i] == 'U' {
+				maxDigits = 8
+			}
+			n, digits := parseHexEscape(s[i+1:], maxDigits)
+			word.WriteRune(rune(n))
+			i += digits
+		default:
+			word.WriteByte(s[i])
+		}
+	}
+	return 0, errors.New("unterminated $' quote")
+}
+
+// parseHexEscape reads up to maxDigits hex digits.
+func parseHexEscape(s string, maxDigits int) (uint64, int) {
+	digits := 0
+	for digits < maxDigits && digits < len(s) && strings.IndexByte("0123456789abcdefABCDEF", s[digits]) >= 0 {
+		digits++
+	}
+	n, _ := strconv.ParseUint(s[:digits], 16, 32)
+	return n, digits
+}
@@ -0,0 +1,26 @@
+package proxy_test
+
+import (
+	"errors"
+	"testing"
+
+	"zone.digit.tommie/internal/proxy"
+)
+
+func FuzzParseCurl(f *testing.F) {
+	f.Add(`curl https://example.com/`)
+	f.Add(`curl -X POST -H 'Content-Type: application/json' -d '{"name":"example"}' https://example.com/items`)
+	f.Add(`curl -sSL -u user:secret --compressed "https://example.com/a b?q=1"`)
+	f.Add(`curl -F 'file=@photo.png;type=image/png' -F name=example https://example.com/upload`)
+	f.Add(`echo ZXhhbXBsZQ== | base64 -d | curl --data-binary @- https://example.com/`)
+	f.Add("curl 'https://example.com/' \\\n  -H $'X-Quote: \\'a\\''")
+	f.Add(`curl "unterminated`)
+
+	// Files referenced with @file do not exist
+	readFile := func(name string) ([]byte, error) {
+		return nil, errors.New("no files while fuzzing")
+	}
+	f.Fuzz(func(t *testing.T, command string) {
+		proxy.ParseCurl(command, readFile)
+	})
+}
@@ -0,0 +1,211 @@
+package proxy
+
+import (
+	"bytes"
+	"context"
+	"encoding/csv"
+	"encoding/json"
+	"fmt"
+	"io"
+	"sort"
+	"strconv"
+	"strings"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+// MaxDataRunRows is the number of data rows accepted in one run.
+const MaxDataRunRows = 1000
+
+// MaxRequests returns the number of requests the run sends at most: one per
+// row, or one for data that fails before any is sent.
+func (run DataRunRequest) MaxRequests() int {
+	rows, _ := parseDataRows(run.Data, run.format())
+	count := min(len(rows), MaxDataRunRows)
+	if run.MaxRows != nil && *run.MaxRows >= 0 {
+		count = min(count, *run.MaxRows)
+	}
+	return max(count, 1)
+}
+
+// LimitRequests cuts the run down to the first n rows of the data.
+func (run *DataRunRequest) LimitRequests(n int) {
+	if run.MaxRows == nil || *run.MaxRows < 0 || n < *run.MaxRows {
+		run.MaxRows = &n
+	}
+}
+
+func (run DataRunRequest) format() string {
+	if run.Format == nil {
+		return ""
+	}
+	return strings.ToLower(*run.Format)
+}
+
+// ExecuteDataRun executes a templated request once per row of a CSV or JSON
+// data set, with the row's columns as template variables, and aggregates the
+// outcome. Rows run with the bounded concurrency of a batch, and those not
+// yet sent are skipped once ctx is cancelled.
+func (s *HTTPService) ExecuteDataRun(ctx context.Context, store storage.Store, run DataRunRequest) DataRunResponse {
+	rows, err := parseDataRows(run.Data, run.format())
+	if err != nil {
+		return dataRunError(err.Error(), "INVALID_DATA")
+	}
+	if len(rows) == 0 {
+		return dataRunError("Data has no rows", "INVALID_DATA")
+	}
+	if len(rows) > MaxDataRunRows {
+		return dataRunError(fmt.Sprintf("Data exceeds %d rows", MaxDataRunRows), "DATA_TOO_LARGE")
+	}
+	if run.MaxRows != nil && *run.MaxRows >= 0 && *run.MaxRows < len(rows) {
+		rows = rows[:*run.MaxRows]
+	}
+
+	requests := make([]ProxyRequest, len(rows))
+	for i, row := range rows {
+		request := run.Request
+		// Row values take precedence over the template's own variables
+		request.Variables = make(map[string]string, len(run.Request.Variables)+len(row))
+		for name, value := range run.Request.Variables {
+			request.Variables[name] = value
+		}
+		for name, value := range row {
+			request.Variables[name] = value
+		}
+		requests[i] = request
+	}
+
+	batch := s.ExecuteBatch(ctx, store, BatchRequest{Requests: requests, Concurrency: run.Concurrency})
+
+	result := DataRunResponse{
+		Success:  true,
+		Columns:  dataColumns(rows),
+		Rows:     make([]DataRunRow, len(rows)),
+		Stats:    batch.Stats,
+		Statuses: map[string]int{},
+	}
+	for i, response := range batch.Results {
+		row := DataRunRow{Row: i + 1, Variables: rows[i]}
+		if response.Success {
+			data := response.Data
+			row.URL = data.URL
+			row.Status = &data.Status
+			row.Time = &data.Timing.Total
+			row.Size = data.Size
+			result.Statuses[strconv.Itoa(int(data.Status))]++
+		} else {
+			row.Error = strPtr(response.Error.Message)
+			row.Code = strPtr(response.Error.Code)
+			result.Statuses["error"]++
+		}
+		if run.IncludeResponses {
+			row.Response = &batch.Results[i]
+		}
+		result.Rows[i] = row
+	}
+	return result
+}
+
+// parseDataRows reads rows of named values from CSV with a header line or a
+// JSON array of objects. An empty format detects JSON by its leading bracket.
+func parseDataRows(data, format string) ([]map[string]string, error) {
+	if format == "" {
+		format = "csv"
+		if strings.HasPrefix(strings.TrimSpace(data), "[") {
+			format = "json"
+		}
+	}
+	switch format {
+	case "csv":
+		return parseCSVRows(data)
+	case "json":
+		return parseJSONRows(data)
+	}
+	return nil, fmt.Errorf("unknown data format %q: use csv or json", format)
+}
+
+func parseCSVRows(data string) ([]map[string]string, error) {
+	reader := csv.NewReader(strings.NewReader(strings.TrimPrefix(data, "\ufeff")))
+	reader.TrimLeadingSpace = true
+	header, err := reader.Read()
+	if err == io.EOF {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("invalid CSV: %v", err)
+	}
+	for i, name := range header {
+		header[i] = strings.TrimSpace(name)
+	}
+
+	var rows []map[string]string
+	for {
+		record, err := reader.Read()
+		if err == io.EOF {
+			return rows, nil
+		}
+		if err != nil {
+			return nil, fmt.Errorf("invalid CSV: %v", err)
+		}
+		row := make(map[string]string, len(header))
+		for i, name := range header {
+			row[name] = record[i]
+		}
+		rows = append(rows, row)
+		if len(rows) > MaxDataRunRows {
+			return rows, nil
+		}
+	}
+}
+
+// parseJSONRows reads an array of objects. Values other than strings are
+// used in their JSON form, and null as an empty string.
+func parseJSONRows(data string) ([]map[string]string, error) {
+	var objects []map[string]json.RawMessage
+	if err := json.Unmarshal([]byte(data), &objects); err != nil {
+		return nil, fmt.Errorf("invalid JSON rows: %v", err)
+	}
+
+	rows := make([]map[string]string, len(objects))
+	for i, object := range objects {
+		row := make(map[string]string, len(object))
+		for name, raw := range object {
+			var text string
+			switch {
+			case bytes.Equal(raw, []byte("null")):
+			case json.Unmarshal(raw, &text) == nil:
+			default:
+				var compact bytes.Buffer
+				json.Compact(&compact, raw)
+				text = compact.String()
+			}
+			row[name] = text
+		}
+		rows[i] = row
+	}
+	return rows, nil
+}
+
+// dataColumns returns the variable names of the rows, sorted.
+func dataColumns(rows []map[string]string) []string {
+	seen := map[string]bool{}
+	var columns []string
+	for _, row := range rows {
+		for name := range row {
+			if !seen[name] {
+				seen[name] = true
+				columns = append(columns, name)
+			}
+		}
+	}
+	sort.Strings(columns)
+	return columns
+}
+
+func dataRunError(message, code string) DataRunResponse {
+	return DataRunResponse{
+		Rows:     []DataRunRow{},
+		Statuses: map[string]int{},
+		Error:    &ErrorData{Message: message, Code: code},
+	}
+}
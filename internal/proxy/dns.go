@@ -0,0 +1,151 @@
+package proxy
+
+import (
+	"context"
+	"net"
+	"strings"
+	"sync"
+	"time"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+// DNSLookupTimeout bounds each query of a DNS lookup unless the request sets a timeout.
+const DNSLookupTimeout = 5 * time.Second
+
+// LookupDNS queries a nameserver for each requested record type of a name,
+// concurrently, so name resolution can be debugged without an HTTP request.
+func (s *HTTPService) LookupDNS(request DNSLookupRequest) DNSLookupResponse {
+	name := strings.TrimSpace(request.Name)
+	if name == "" {
+		return dnsLookupError(name, "Name is required", "INVALID_REQUEST")
+	}
+	types := request.Types
+	if len(types) == 0 {
+		types = infra.DNSRecordTypes
+	}
+
+	server, lookupErr := s.lookupNameserver(request.Server)
+	if lookupErr != nil {
+		return dnsLookupError(name, lookupErr.Message, lookupErr.Code)
+	}
+	timeout := lookupTimeout(request.Timeout)
+
+	result := DNSLookupResponse{Success: true, Name: name, Server: server.String(), Queries: make([]DNSQueryResult, len(types))}
+	var wg sync.WaitGroup
+	for i, recordType := range types {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			ctx, cancel := context.WithTimeout(context.Background(), timeout)
+			defer cancel()
+			result.Queries[i] = dnsQueryResult(infra.LookupRecord(ctx, server, name, recordType))
+			result.Queries[i].Type = strings.ToUpper(recordType)
+			result.Queries[i].Server = server.String()
+		}()
+	}
+	wg.Wait()
+
+	for _, query := range result.Queries {
+		if query.Error != nil {
+			result.Success = false
+		}
+	}
+	return result
+}
+
+// lookupNameserver returns the nameserver a lookup queries: the one given,
+// otherwise that of the configured resolver.
+func (s *HTTPService) lookupNameserver(server *string) (infra.Nameserver, *ErrorData) {
+	if server != nil && *server != "" {
+		ns, err := infra.ParseNameserver(*server)
+		if err != nil {
+			return infra.Nameserver{}, &ErrorData{Message: err.Error(), Code: "INVALID_REQUEST"}
+		}
+		if err := s.checkNameservers([]infra.Nameserver{ns}); err != nil {
+			return infra.Nameserver{}, &ErrorData{Message: err.Error(), Code: "BLOCKED_BY_POLICY"}
+		}
+		return ns, nil
+	}
+	ns, ok := queryNameserver(s.opts.Resolver)
+	if !ok {
+		return infra.Nameserver{}, &ErrorData{Message: "No system nameserver found; set server", Code: "NO_NAMESERVER"}
+	}
+	return ns, nil
+}
+
+// lookupTimeout returns the timeout of each query of a lookup.
+func lookupTimeout(ms *uint64) time.Duration {
+	if ms != nil && *ms > 0 {
+		return time.Duration(*ms) * time.Millisecond
+	}
+	return DNSLookupTimeout
+}
+
+// queryNameserver returns the nameserver to query directly for records: the
+// first of resolver when it is custom, otherwise the first system nameserver.
+func queryNameserver(resolver *infra.Resolver) (infra.Nameserver, bool) {
+	if resolver.Custom() {
+		return resolver.Nameservers[0], true
+	}
+	servers := infra.SystemNameservers()
+	if len(servers) == 0 {
+		return infra.Nameserver{}, false
+	}
+	return servers[0], true
+}
+
+func dnsQueryResult(query *infra.DNSQuery, err error) DNSQueryResult {
+	result := DNSQueryResult{Records: []DNSRecord{}}
+	if query != nil {
+		result.Time = query.DurationMs
+		result.Transport = query.Transport
+		result.RCode = query.RCode
+		result.Authoritative = query.Authoritative
+		for _, record := range query.Records {
+			result.Records = append(result.Records, DNSRecord(record))
+		}
+	}
+	if err != nil {
+		result.Error = strPtr(err.Error())
+	}
+	return result
+}
+
+func dnsLookupError(name, message, code string) DNSLookupResponse {
+	return DNSLookupResponse{Name: name, Queries: []DNSQueryResult{}, Error: &ErrorData{Message: message, Code: code}}
+}
+
+// requestResolver returns the resolver of a request: its own nameservers when
+// given, otherwise the configured resolver.
+func (s *HTTPService) requestResolver(servers []string) (*infra.Resolver, error) {
+	if len(servers) == 0 {
+		return s.opts.Resolver, nil
+	}
+	resolver, err := infra.NewResolver(servers)
+	if err != nil {
+		return nil, err
+	}
+	if err := s.checkNameservers(resolver.Nameservers); err != nil {
+		return nil, err
+	}
+	return resolver, nil
+}
+
+// checkNameservers applies the destination policy to nameservers chosen by a
+// request, which would otherwise let it send traffic to blocked addresses.
+func (s *HTTPService) checkNameservers(servers []infra.Nameserver) error {
+	for _, ns := range servers {
+		host := ns.Host()
+		if ip := net.ParseIP(host); ip != nil {
+			if err := s.policy.Load().checkIP(ip); err != nil {
+				return err
+			}
+			continue
+		}
+		if err := s.policy.Load().checkHost(host); err != nil {
+			return err
+		}
+	}
+	return nil
+}
@@ -0,0 +1,60 @@
+package proxy
+
+import (
+	"context"
+	"net"
+	"sync"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+// dnsSecurity looks up the CAA policy and DNSSEC status of a host through
+// the nameserver of the request, concurrently. IP addresses have neither.
+func dnsSecurity(resolver *infra.Resolver, host string) *DNSSecurityInfo {
+	if net.ParseIP(host) != nil {
+		return nil
+	}
+	ns, ok := queryNameserver(resolver)
+	if !ok {
+		return &DNSSecurityInfo{Error: strPtr("No system nameserver found; set dnsServers")}
+	}
+	info := &DNSSecurityInfo{Server: ns.String()}
+
+	ctx, cancel := context.WithTimeout(context.Background(), DNSLookupTimeout)
+	defer cancel()
+	var wg sync.WaitGroup
+	wg.Add(2)
+	go func() {
+		defer wg.Done()
+		policy, err := infra.LookupCAA(ctx, ns, host)
+		if err != nil {
+			info.CAA = &CAAInfo{Records: []DNSRecord{}, Error: strPtr(err.Error())}
+			return
+		}
+		info.CAA = &CAAInfo{
+			Domain:    optionalString(policy.Domain),
+			Issue:     policy.Issue,
+			IssueWild: policy.IssueWild,
+			IODEF:     policy.IODEF,
+			Critical:  policy.Critical,
+			Records:   make([]DNSRecord, len(policy.Records)),
+		}
+		for i, record := range policy.Records {
+			info.CAA.Records[i] = DNSRecord(record)
+		}
+	}()
+	go func() {
+		defer wg.Done()
+		result, err := infra.CheckDNSSEC(ctx, ns, host)
+		if err != nil {
+			info.DNSSEC = &DNSSECInfo{Signatures: []DNSSignature{}, Error: strPtr(err.Error())}
+			return
+		}
+		info.DNSSEC = &DNSSECInfo{Status: result.Status, Signatures: make([]DNSSignature, len(result.Signatures))}
+		for i, signature := range result.Signatures {
+			info.DNSSEC.Signatures[i] = DNSSignature(signature)
+		}
+	}()
+	wg.Wait()
+	return info
+}
@@ -0,0 +1,301 @@
+package proxy
+
+import (
+	"crypto/rand"
+	"crypto/sha256"
+	"encoding/hex"
+	"errors"
+	"fmt"
+	"io"
+	"mime"
+	"net/http"
+	"net/url"
+	"os"
+	"path"
+	"path/filepath"
+	"sync"
+	"time"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+const (
+	// DownloadRetention is how long a temporary download stays retrievable.
+	DownloadRetention = time.Hour
+	// DownloadURLPrefix is where the server serves temporary downloads by ID.
+	DownloadURLPrefix = "/api/downloads/"
+	// throughputInterval is the initial spacing of throughput samples.
+	throughputInterval = 250 * time.Millisecond
+	// maxThroughputSamples bounds the samples of a download; longer downloads
+	// are sampled less often.
+	maxThroughputSamples = 240
+)
+
+// DownloadedFile is a temporary download kept for retrieval.
+type DownloadedFile struct {
+	Path        string
+	FileName    string
+	ContentType string
+	created     time.Time
+}
+
+// downloadStore keeps the temporary files of downloads without a path.
+type downloadStore struct {
+	dir string
+
+	mu    sync.Mutex
+	files map[string]*DownloadedFile
+}
+
+func newDownloadStore(dir string) *downloadStore {
+	if dir == "" {
+		dir = filepath.Join(os.TempDir(), "tommie-downloads")
+	}
+	return &downloadStore{dir: dir, files: make(map[string]*DownloadedFile)}
+}
+
+// create opens a new temporary file and drops downloads past their retention.
+func (d *downloadStore) create() (string, *os.File, error) {
+	d.prune()
+	if err := os.MkdirAll(d.dir, 0o700); err != nil {
+		return "", nil, err
+	}
+	buf := make([]byte, 16)
+	if _, err := rand.Read(buf); err != nil {
+		return "", nil, err
+	}
+	id := hex.EncodeToString(buf)
+	file, err := os.OpenFile(filepath.Join(d.dir, id), os.O_WRONLY|os.O_CREATE|os.O_EXCL, 0o600)
+	return id, file, err
+}
+
+func (d *downloadStore) add(id string, file *DownloadedFile) {
+	d.mu.Lock()
+	defer d.mu.Unlock()
+	file.created = time.Now()
+	d.files[id] = file
+}
+
+// prune removes expired downloads, including files left by an earlier run.
+func (d *downloadStore) prune() {
+	d.mu.Lock()
+	defer d.mu.Unlock()
+	for id, file := range d.files {
+		if time.Since(file.created) > DownloadRetention {
+			os.Remove(file.Path)
+			delete(d.files, id)
+		}
+	}
+	entries, _ := os.ReadDir(d.dir)
+	for _, entry := range entries {
+		info, err := entry.Info()
+		if _, kept := d.files[entry.Name()]; kept || err != nil {
+			continue
+		}
+		if time.Since(info.ModTime()) > DownloadRetention {
+			os.Remove(filepath.Join(d.dir, entry.Name()))
+		}
+	}
+}
+
+// Download returns a temporary download by ID, or false when it does not
+// exist or has expired.
+func (s *HTTPService) Download(id string) (*DownloadedFile, bool) {
+	s.downloads.mu.Lock()
+	defer s.downloads.mu.Unlock()
+	file, ok := s.downloads.files[id]
+	if !ok || time.Since(file.created) > DownloadRetention {
+		return nil, false
+	}
+	return file, true
+}
+
+// saveDownload streams a response body to a file, decoding its content
+// codings, instead of holding it in memory.
+func (s *HTTPService) saveDownload(options *DownloadOptions, requestURL string, resp *http.Response) (*DownloadInfo, error) {
+	if limit := s.opts.MaxDownloadBytes; limit > 0 && resp.ContentLength > int64(limit) {
+		return nil, &downloadTooLargeError{limit: limit}
+	}
+
+	var id string
+	var file *os.File
+	var err error
+	if options.Path != nil && *options.Path != "" {
+		if !s.opts.AllowDownloadPath {
+			return nil, fmt.Errorf("download paths are only available in the desktop app")
+		}
+		if !filepath.IsAbs(*options.Path) {
+			return nil, fmt.Errorf("download path must be absolute")
+		}
+		file, err = os.Create(*options.Path)
+	} else {
+		id, file, err = s.downloads.create()
+	}
+	if err != nil {
+		return nil, err
+	}
+
+	// Both the received and the decoded bytes are bounded, so a small
+	// compressed body cannot unpack to fill the disk
+	limit := int64(s.opts.MaxDownloadBytes)
+	wire := &downloadReader{r: resp.Body, limit: limit, egress: s.egress}
+	body, err := infra.DecodeReader(wire, resp.Header.Get("Content-Encoding"))
+	if err != nil {
+		file.Close()
+		os.Remove(file.Name())
+		return nil, err
+	}
+	body = &downloadReader{r: body, limit: limit}
+	hash := sha256.New()
+	meter := newThroughputMeter()
+	written, err := io.Copy(io.MultiWriter(file, hash, meter), body)
+	if closeErr := file.Close(); err == nil {
+		err = closeErr
+	}
+	if err != nil {
+		os.Remove(file.Name())
+		return nil, err
+	}
+
+	duration := time.Since(meter.started)
+	info := &DownloadInfo{
+		FileName:   downloadFileName(resp.Header.Get("Content-Disposition"), requestURL),
+		Size:       written,
+		SHA256:     hex.EncodeToString(hash.Sum(nil)),
+		Duration:   uint64(duration.Milliseconds()),
+		Throughput: meter.finish(),
+	}
+	if duration > 0 {
+		info.BytesPerSecond = float64(written) / duration.Seconds()
+	}
+	if id == "" {
+		info.Path = strPtr(file.Name())
+	} else {
+		s.downloads.add(id, &DownloadedFile{Path: file.Name(), FileName: info.FileName, ContentType: resp.Header.Get("Content-Type")})
+		info.ID = id
+		info.URL = strPtr(DownloadURLPrefix + id)
+		// The desktop app may open the temporary file itself
+		if s.opts.AllowDownloadPath {
+			info.Path = strPtr(file.Name())
+		}
+	}
+	return info, nil
+}
+
+// downloadErrorResponse reports why a download could not be saved.
+func downloadErrorResponse(err error) ProxyResponse {
+	var tooLarge *downloadTooLargeError
+	var exceeded *egressExceededError
+	switch {
+	case errors.As(err, &tooLarge):
+		return NewErrorResponse(fmt.Sprintf("Download exceeds the limit of %d bytes", tooLarge.limit), "DOWNLOAD_TOO_LARGE")
+	case errors.As(err, &exceeded):
+		return NewErrorResponse(exceeded.Error(), "QUOTA_EXCEEDED")
+	}
+	return NewErrorResponse(fmt.Sprintf("Failed to save download: %v", err), "DOWNLOAD_ERROR")
+}
+
+// downloadTooLargeError fails a download over Options.MaxDownloadBytes.
+type downloadTooLargeError struct {
+	limit int
+}
+
+func (e *downloadTooLargeError) Error() string {
+	return fmt.Sprintf("download exceeds %d bytes", e.limit)
+}
+
+// downloadReader fails a download once more than limit bytes were read
+// through it (0 = unlimited), and charges them to the egress budget as they
+// arrive when one is given.
+type downloadReader struct {
+	r      io.Reader
+	limit  int64
+	read   int64
+	egress *egressBudget
+}
+
+func (d *downloadReader) Read(p []byte) (int, error) {
+	if d.egress != nil {
+		if budget := d.egress.exhausted(); budget != "" {
+			return 0, &egressExceededError{budget: budget}
+		}
+	}
+	n, err := d.r.Read(p)
+	d.read += int64(n)
+	if d.egress != nil {
+		d.egress.record(n)
+	}
+	if d.limit > 0 && d.read > d.limit {
+		return n, &downloadTooLargeError{limit: int(d.limit)}
+	}
+	return n, err
+}
+
+// downloadFileName suggests a file name from Content-Disposition or the last
+// segment of the URL path.
+func downloadFileName(disposition, requestURL string) string {
+	if _, params, err := mime.ParseMediaType(disposition); err == nil {
+		if name := filepath.Base(params["filename"]); params["filename"] != "" && name != "." && name != string(filepath.Separator) {
+			return name
+		}
+	}
+	if u, err := url.Parse(requestURL); err == nil {
+		if name := path.Base(u.Path); name != "/" && name != "." {
+			return name
+		}
+	}
+	return "download"
+}
+
+// throughputMeter samples the bytes written over time.
+type throughputMeter struct {
+	started  time.Time
+	interval time.Duration
+	last     time.Time
+	lastSize int64
+	size     int64
+	samples  []ThroughputSample
+}
+
+func newThroughputMeter() *throughputMeter {
+	now := time.Now()
+	return &throughputMeter{started: now, interval: throughputInterval, last: now}
+}
+
+func (m *throughputMeter) Write(p []byte) (int, error) {
+	m.size += int64(len(p))
+	if now := time.Now(); now.Sub(m.last) >= m.interval {
+		m.sample(now)
+	}
+	return len(p), nil
+}
+
+func (m *throughputMeter) sample(now time.Time) {
+	elapsed := now.Sub(m.last)
+	m.samples = append(m.samples, ThroughputSample{
+		Offset:         uint64(now.Sub(m.started).Milliseconds()),
+		Bytes:          m.size,
+		BytesPerSecond: float64(m.size-m.lastSize) / elapsed.Seconds(),
+	})
+	m.last, m.lastSize = now, m.size
+	// Halve the samples kept and sample half as often from here on
+	if len(m.samples) >= maxThroughputSamples {
+		kept := m.samples[:0]
+		for i := 1; i < len(m.samples); i += 2 {
+			kept = append(kept, m.samples[i])
+		}
+		m.samples = kept
+		m.interval *= 2
+	}
+}
+
+// finish takes a final sample and returns them all.
+func (m *throughputMeter) finish() []ThroughputSample {
+	if now := time.Now(); m.size > m.lastSize && now.After(m.last) {
+		m.sample(now)
+	}
+	if m.samples == nil {
+		return []ThroughputSample{}
+	}
+	return m.samples
+}
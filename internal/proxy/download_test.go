@@ -0,0 +1,58 @@
+package proxy_test
+
+import (
+	"bytes"
+	"compress/gzip"
+	"net/http"
+	"testing"
+
+	"zone.digit.tommie/internal/proxy"
+	"zone.digit.tommie/internal/testkit"
+)
+
+func TestDownload(t *testing.T) {
+	target := testkit.Start()
+	defer target.Close()
+	service := target.Service(proxy.Options{DownloadDir: t.TempDir(), MaxDownloadBytes: 1 << 20})
+
+	data := success(t, service.Execute(target.Request(proxy.ProxyRequest{URL: "/bytes/100000", Download: &proxy.DownloadOptions{}})))
+	if data.Download == nil || data.Download.Size != 100000 || data.Download.URL == nil {
+		t.Fatalf("got download %+v, want 100000 bytes served from a URL", data.Download)
+	}
+	if _, ok := service.Download(data.Download.ID); !ok {
+		t.Error("the download cannot be retrieved by its ID")
+	}
+}
+
+func TestDownloadLimits(t *testing.T) {
+	target := testkit.Start()
+	defer target.Close()
+
+	// 16 MiB of zeros compress to a few KiB
+	var bomb bytes.Buffer
+	writer := gzip.NewWriter(&bomb)
+	writer.Write(make([]byte, 16<<20))
+	writer.Close()
+	target.Handle("/bomb", func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Encoding", "gzip")
+		w.Write(bomb.Bytes())
+	})
+
+	tests := []struct {
+		name string
+		opts proxy.Options
+		url  string
+		code string
+	}{
+		{"received", proxy.Options{MaxDownloadBytes: 64 << 10}, "/bytes/100000", "DOWNLOAD_TOO_LARGE"},
+		{"decoded", proxy.Options{MaxDownloadBytes: 1 << 20}, "/bomb", "DOWNLOAD_TOO_LARGE"},
+		{"egress budget", proxy.Options{EgressBytesPerMinute: 1000}, "/slow?bytes=100000&chunk=10000&interval=1", "QUOTA_EXCEEDED"},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			tt.opts.DownloadDir = t.TempDir()
+			service := target.Service(tt.opts)
+			failure(t, service.Execute(target.Request(proxy.ProxyRequest{URL: tt.url, Download: &proxy.DownloadOptions{}})), tt.code)
+		})
+	}
+}
@@ -0,0 +1,91 @@
+package proxy
+
+import (
+	"fmt"
+	"sync"
+	"time"
+)
+
+// egressExceededError fails a transfer that ran out of an egress budget.
+type egressExceededError struct {
+	budget string // per-minute or daily
+}
+
+func (e *egressExceededError) Error() string {
+	return fmt.Sprintf("Egress %s byte budget exceeded", e.budget)
+}
+
+// egressBudget enforces a global byte budget across all executions of a service.
+// Budgets use fixed windows: the current minute and the current UTC day.
+type egressBudget struct {
+	perMinute int // Guarded by mu, as setLimits may change it
+	perDay    int
+
+	mu          sync.Mutex
+	minuteStart time.Time
+	minuteUsed  int
+	dayStart    time.Time
+	dayUsed     int
+}
+
+func newEgressBudget(perMinute, perDay int) *egressBudget {
+	return &egressBudget{
+		perMinute: perMinute,
+		perDay:    perDay,
+	}
+}
+
+// roll resets windows that have elapsed. Callers must hold mu.
+func (b *egressBudget) roll(now time.Time) {
+	if minute := now.Truncate(time.Minute); !minute.Equal(b.minuteStart) {
+		b.minuteStart = minute
+		b.minuteUsed = 0
+	}
+	if day := now.UTC().Truncate(24 * time.Hour); !day.Equal(b.dayStart) {
+		b.dayStart = day
+		b.dayUsed = 0
+	}
+}
+
+// exhausted returns a description of the exhausted budget, or "" if bytes may still be sent.
+func (b *egressBudget) exhausted() string {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+
+	if b.perMinute <= 0 && b.perDay <= 0 {
+		return ""
+	}
+
+	b.roll(time.Now())
+	if b.perMinute > 0 && b.minuteUsed >= b.perMinute {
+		return "per-minute"
+	}
+	if b.perDay > 0 && b.dayUsed >= b.perDay {
+		return "daily"
+	}
+	return ""
+}
+
+// record adds transferred bytes to the current windows.
+func (b *egressBudget) record(bytes int) {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+
+	if b.perMinute <= 0 && b.perDay <= 0 {
+		return
+	}
+
+	b.roll(time.Now())
+	b.minuteUsed += bytes
+	b.dayUsed += bytes
+}
+
+// setLimits replaces the budgets. Bytes already counted in the current
+// windows still count against the new budgets.
+func (b *egressBudget) setLimits(perMinute, perDay int) {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+
+	b.perMinute = perMinute
+	b.perDay = perDay
+}
@@ -0,0 +1,208 @@
+package proxy
+
+import (
+	"context"
+	"fmt"
+	"sort"
+	"strconv"
+	"strings"
+	"sync"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+// LookupEmailPosture fetches the MX records of a domain, its SPF record and
+// its DMARC record, and summarizes how mail from and to it is protected.
+func (s *HTTPService) LookupEmailPosture(request EmailPostureRequest) EmailPostureResponse {
+	domain := strings.TrimSuffix(strings.TrimSpace(request.Domain), ".")
+	if domain == "" {
+		return emailPostureError(domain, "Domain is required", "INVALID_REQUEST")
+	}
+	server, lookupErr := s.lookupNameserver(request.Server)
+	if lookupErr != nil {
+		return emailPostureError(domain, lookupErr.Message, lookupErr.Code)
+	}
+	timeout := lookupTimeout(request.Timeout)
+
+	// MX and TXT of the domain, then TXT of its DMARC name
+	names := []string{domain, domain, "_dmarc." + domain}
+	types := []string{"MX", "TXT", "TXT"}
+	queries := make([]*infra.DNSQuery, len(names))
+	result := EmailPostureResponse{
+		Success:  true,
+		Domain:   domain,
+		Server:   server.String(),
+		MX:       []MXHost{},
+		Findings: []EmailFinding{},
+		Queries:  make([]DNSQueryResult, len(names)),
+	}
+	var wg sync.WaitGroup
+	for i := range names {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			ctx, cancel := context.WithTimeout(context.Background(), timeout)
+			defer cancel()
+			query, err := infra.LookupRecord(ctx, server, names[i], types[i])
+			if err == nil {
+				queries[i] = query
+			}
+			result.Queries[i] = dnsQueryResult(query, err)
+			result.Queries[i].Type = types[i]
+			result.Queries[i].Server = server.String()
+		}()
+	}
+	wg.Wait()
+	for _, query := range result.Queries {
+		if query.Error != nil {
+			result.Success = false
+		}
+	}
+
+	finding := func(severity, format string, args ...interface{}) {
+		result.Findings = append(result.Findings, EmailFinding{Severity: severity, Message: fmt.Sprintf(format, args...)})
+	}
+
+	if mx := queries[0]; mx != nil {
+		for _, record := range recordsOfType(mx, "MX") {
+			preference, host, _ := strings.Cut(record.Value, " ")
+			n, _ := strconv.Atoi(preference)
+			result.MX = append(result.MX, MXHost{Preference: n, Host: host})
+		}
+		sort.SliceStable(result.MX, func(i, j int) bool { return result.MX[i].Preference < result.MX[j].Preference })
+		// RFC 7505: a single "0 ." record declares that the domain accepts no mail
+		result.NullMX = len(result.MX) == 1 && result.MX[0].Host == "."
+		if len(result.MX) == 0 {
+			finding("info", "No MX records; mail is delivered to the A/AAAA address of the domain, if any")
+		}
+	}
+
+	if txt := queries[1]; txt != nil {
+		var spfRecords []string
+		for _, record := range recordsOfType(txt, "TXT") {
+			if _, ok := infra.ParseSPF(record.Value); ok {
+				spfRecords = append(spfRecords, record.Value)
+			}
+		}
+		switch len(spfRecords) {
+		case 0:
+			finding("warning", "No SPF record; receivers cannot tell which servers may send mail for %s", domain)
+		case 1:
+			result.SPF = spfInfo(spfRecords[0])
+			spfFindings(result.SPF, result.NullMX, finding)
+		default:
+			finding("error", "%d SPF records; receivers treat this as a permanent error", len(spfRecords))
+		}
+	}
+
+	if txt := queries[2]; txt != nil {
+		var dmarcRecords []string
+		for _, record := range recordsOfType(txt, "TXT") {
+			if _, ok := infra.ParseDMARC(record.Value); ok {
+				dmarcRecords = append(dmarcRecords, record.Value)
+			}
+		}
+		switch len(dmarcRecords) {
+		case 0:
+			finding("warning", "No DMARC record at _dmarc.%s; spoofed mail is not rejected", domain)
+		case 1:
+			result.DMARC = dmarcInfo(dmarcRecords[0])
+			dmarcFindings(result.DMARC, finding)
+		default:
+			finding("error", "%d DMARC records; receivers ignore DMARC for the domain", len(dmarcRecords))
+		}
+	}
+	return result
+}
+
+func recordsOfType(query *infra.DNSQuery, recordType string) []infra.DNSRecord {
+	var records []infra.DNSRecord
+	for _, record := range query.Records {
+		if record.Type == recordType {
+			records = append(records, record)
+		}
+	}
+	return records
+}
+
+func spfInfo(text string) *SPFInfo {
+	record, _ := infra.ParseSPF(text)
+	info := &SPFInfo{
+		Record:   text,
+		All:      optionalString(record.All),
+		Redirect: optionalString(record.Redirect),
+		Includes: record.Includes,
+		Lookups:  record.Lookups,
+		Terms:    make([]SPFTerm, len(record.Terms)),
+	}
+	for i, term := range record.Terms {
+		info.Terms[i] = SPFTerm(term)
+	}
+	return info
+}
+
+func spfFindings(spf *SPFInfo, nullMX bool, finding func(severity, format string, args ...interface{})) {
+	switch {
+	case spf.All == nil && spf.Redirect == nil:
+		finding("warning", "SPF has no all mechanism; unlisted senders get a neutral result")
+	case spf.All != nil && *spf.All == "+all":
+		finding("error", "SPF ends in +all, which lets any server send mail for the domain")
+	case spf.All != nil && *spf.All == "?all":
+		finding("warning", "SPF ends in ?all, which gives unlisted senders a neutral result")
+	case spf.All != nil && *spf.All == "~all":
+		finding("info", "SPF ends in ~all; unlisted senders soft-fail rather than fail")
+	}
+	if spf.Lookups > infra.MaxSPFLookups {
+		finding("error", "SPF needs %d DNS lookups before following includes; more than %d is a permanent error", spf.Lookups, infra.MaxSPFLookups)
+	} else if len(spf.Includes) > 0 {
+		finding("info", "SPF uses %d of %d DNS lookups before following its includes", spf.Lookups, infra.MaxSPFLookups)
+	}
+	if nullMX && (spf.All == nil || *spf.All != "-all" || len(spf.Terms) > 1) {
+		finding("info", "The domain accepts no mail; \"v=spf1 -all\" also declares that it sends none")
+	}
+}
+
+func dmarcInfo(text string) *DMARCInfo {
+	record, _ := infra.ParseDMARC(text)
+	return &DMARCInfo{
+		Record:          text,
+		Policy:          record.Policy,
+		SubdomainPolicy: record.SubdomainPolicy,
+		Percent:         record.Percent,
+		DKIMAlignment:   record.DKIMAlignment,
+		SPFAlignment:    record.SPFAlignment,
+		Aggregate:       record.Aggregate,
+		Forensic:        record.Forensic,
+	}
+}
+
+func dmarcFindings(dmarc *DMARCInfo, finding func(severity, format string, args ...interface{})) {
+	switch dmarc.Policy {
+	case "reject":
+	case "quarantine":
+		finding("info", "DMARC quarantines failing mail rather than rejecting it")
+	case "none":
+		finding("warning", "DMARC policy is none; failing mail is only reported")
+	default:
+		finding("error", "DMARC has no valid p= policy; receivers ignore the record")
+	}
+	if dmarc.Percent < 100 {
+		finding("warning", "DMARC applies to %d%% of failing mail only", dmarc.Percent)
+	}
+	if dmarc.SubdomainPolicy == "none" && dmarc.Policy != "none" {
+		finding("warning", "DMARC subdomain policy is none; subdomains can be spoofed")
+	}
+	if len(dmarc.Aggregate) == 0 {
+		finding("info", "DMARC has no rua address; no aggregate reports are sent")
+	}
+}
+
+func emailPostureError(domain, message, code string) EmailPostureResponse {
+	return EmailPostureResponse{
+		Domain:   domain,
+		MX:       []MXHost{},
+		Findings: []EmailFinding{},
+		Queries:  []DNSQueryResult{},
+		Error:    &ErrorData{Message: message, Code: code},
+	}
+}
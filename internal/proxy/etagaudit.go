@@ -0,0 +1,145 @@
+package proxy
+
+import (
+	"crypto/sha256"
+	"encoding/hex"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+const (
+	// DefaultETagAuditRequests is the number of times a URL is fetched by an ETag audit.
+	DefaultETagAuditRequests = 3
+	// MaxETagAuditRequests caps the fetches of an ETag audit.
+	MaxETagAuditRequests = 10
+)
+
+// MaxRequests returns the number of requests the audit sends at most: its
+// fetches and the conditional request.
+func (a ETagAuditRequest) MaxRequests() int {
+	return min(positiveOr(a.Requests, DefaultETagAuditRequests), MaxETagAuditRequests) + 1
+}
+
+// ExecuteETagAudit fetches a request several times and reports whether its
+// ETag and Last-Modified validators stay stable while the content does,
+// then checks that the server answers a conditional request with 304.
+func (s *HTTPService) ExecuteETagAudit(store storage.Store, audit ETagAuditRequest) ETagAuditResponse {
+	count := min(positiveOr(audit.Requests, DefaultETagAuditRequests), MaxETagAuditRequests)
+	interval := 0
+	if audit.IntervalMS != nil && *audit.IntervalMS > 0 {
+		interval = min(*audit.IntervalMS, MaxBurstIntervalMS)
+	}
+
+	result := ETagAuditResponse{Samples: []ETagSample{}, Findings: []string{}}
+	for i := 0; i < count; i++ {
+		if i > 0 && interval > 0 {
+			time.Sleep(time.Duration(interval) * time.Millisecond)
+		}
+		sample := ETagSample{Sequence: i + 1}
+		resp := s.ExecuteIn(store, audit.Request)
+		if !resp.Success {
+			sample.Error = strPtr(resp.Error.Message)
+		} else {
+			content := resp.Data.Body
+			if resp.Data.BodyBase64 != nil {
+				content = *resp.Data.BodyBase64
+			}
+			sum := sha256.Sum256([]byte(content))
+			sample.Status = &resp.Data.Status
+			sample.ETag = optionalString(headerValue(resp.Data.Headers, "ETag"))
+			sample.LastModified = optionalString(headerValue(resp.Data.Headers, "Last-Modified"))
+			sample.BodyHash = strPtr(hex.EncodeToString(sum[:]))
+			sample.Size = resp.Data.Size
+		}
+		result.Samples = append(result.Samples, sample)
+	}
+
+	result.analyze()
+	if last := result.lastSample(); last != nil && last.ETag != nil {
+		s.revalidate(store, audit.Request, *last.ETag, &result)
+	}
+	return result
+}
+
+// analyze compares the validators of the successful samples.
+func (r *ETagAuditResponse) analyze() {
+	var ok []ETagSample
+	for _, sample := range r.Samples {
+		if sample.Status != nil {
+			ok = append(ok, sample)
+		}
+	}
+	if len(ok) < 2 {
+		r.Findings = append(r.Findings, "Fewer than two successful responses; stability could not be compared")
+		return
+	}
+
+	contentStable, etagStable, modifiedStable := true, true, true
+	hasETag, hasModified := false, false
+	for _, sample := range ok {
+		contentStable = contentStable && *sample.BodyHash == *ok[0].BodyHash
+		etagStable = etagStable && equalOptional(sample.ETag, ok[0].ETag)
+		modifiedStable = modifiedStable && equalOptional(sample.LastModified, ok[0].LastModified)
+		hasETag = hasETag || sample.ETag != nil
+		hasModified = hasModified || sample.LastModified != nil
+		if sample.ETag != nil && strings.HasPrefix(*sample.ETag, "W/") {
+			r.WeakETag = true
+		}
+	}
+	r.ContentStable = contentStable
+	if hasETag {
+		r.ETagStable = &etagStable
+	}
+	if hasModified {
+		r.LastModifiedStable = &modifiedStable
+	}
+
+	switch {
+	case !hasETag && !hasModified:
+		r.Findings = append(r.Findings, "No ETag or Last-Modified; responses cannot be revalidated")
+	case contentStable && hasETag && !etagStable:
+		r.Findings = append(r.Findings, "ETag changes although the content is identical; caches refetch the full body every time")
+	case !contentStable && hasETag && etagStable && !r.WeakETag:
+		r.Findings = append(r.Findings, "Strong ETag stays the same while the content changes; caches may serve stale content")
+	}
+	if contentStable && hasModified && !modifiedStable {
+		r.Findings = append(r.Findings, "Last-Modified changes although the content is identical; it is likely the response time")
+	}
+	if !contentStable {
+		r.Findings = append(r.Findings, "Content differs between requests")
+	}
+}
+
+// revalidate sends the request with If-None-Match and records whether the server answered 304.
+func (s *HTTPService) revalidate(store storage.Store, request ProxyRequest, etag string, r *ETagAuditResponse) {
+	headers := make(map[string]string, len(request.Headers)+1)
+	for key, value := range request.Headers {
+		if !strings.EqualFold(key, "If-None-Match") && !strings.EqualFold(key, "If-Modified-Since") {
+			headers[key] = value
+		}
+	}
+	headers["If-None-Match"] = etag
+	request.Headers = headers
+
+	resp := s.ExecuteIn(store, request)
+	if !resp.Success {
+		return
+	}
+	revalidated := resp.Data.Status == 304
+	r.RevalidationStatus = &resp.Data.Status
+	r.Revalidated = &revalidated
+	if !revalidated && r.ETagStable != nil && *r.ETagStable {
+		r.Findings = append(r.Findings, "Server ignores If-None-Match even though the ETag is stable")
+	}
+}
+
+func (r *ETagAuditResponse) lastSample() *ETagSample {
+	for i := len(r.Samples) - 1; i >= 0; i-- {
+		if r.Samples[i].Status != nil {
+			return &r.Samples[i]
+		}
+	}
+	return nil
+}
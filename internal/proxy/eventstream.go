@@ -0,0 +1,148 @@
+package proxy
+
+import (
+	"bufio"
+	"bytes"
+	"errors"
+	"io"
+	"net"
+	"strconv"
+	"strings"
+	"sync/atomic"
+	"time"
+)
+
+const (
+	// DefaultMaxEvents is the number of events read from an event stream.
+	DefaultMaxEvents = 100
+	// MaxEvents caps the events read from an event stream.
+	MaxEvents = 10_000
+	// DefaultEventStreamMS is how long an event stream is read in milliseconds.
+	DefaultEventStreamMS = 10_000
+)
+
+// isEventStream reports whether a content type is text/event-stream.
+func isEventStream(contentType string) bool {
+	mediaType, _, _ := strings.Cut(contentType, ";")
+	return strings.EqualFold(strings.TrimSpace(mediaType), "text/event-stream")
+}
+
+// readEventStream reads server-sent events until the stream ends or a limit
+// is reached, passing each to onEvent as it arrives. It returns the raw bytes
+// read and the events. The request timeout still bounds the whole stream.
+func readEventStream(body io.ReadCloser, opts *EventStreamOptions, onEvent func(ServerSentEvent)) ([]byte, *EventStreamInfo) {
+	maxEvents, maxDuration := DefaultMaxEvents, DefaultEventStreamMS
+	if opts != nil {
+		maxEvents = min(positiveOr(opts.MaxEvents, DefaultMaxEvents), MaxEvents)
+		maxDuration = positiveOr(opts.MaxDurationMS, DefaultEventStreamMS)
+	}
+
+	started := time.Now()
+	var expired atomic.Bool
+	timer := time.AfterFunc(time.Duration(maxDuration)*time.Millisecond, func() {
+		expired.Store(true)
+		body.Close()
+	})
+	defer timer.Stop()
+
+	var raw bytes.Buffer
+	reader := bufio.NewReader(io.TeeReader(body, &raw))
+	info := &EventStreamInfo{Events: []ServerSentEvent{}}
+	parser := &eventParser{}
+	last := started
+
+	for {
+		line, err := reader.ReadString('\n')
+		if err != nil {
+			// An incomplete event at the end of the stream is discarded
+			var netErr net.Error
+			switch {
+			case expired.Load():
+				info.StoppedReason = "maxDuration"
+			case errors.Is(err, io.EOF):
+				info.StoppedReason = "ended"
+			case errors.As(err, &netErr) && netErr.Timeout():
+				info.StoppedReason = "timeout"
+			default:
+				info.StoppedReason = "error"
+				info.Error = strPtr(err.Error())
+			}
+			break
+		}
+
+		event, ok := parser.feed(strings.TrimSuffix(strings.TrimSuffix(line, "\n"), "\r"))
+		if !ok {
+			continue
+		}
+		now := time.Now()
+		event.Seq = len(info.Events) + 1
+		event.Offset = uint64(now.Sub(started).Milliseconds())
+		event.Latency = uint64(now.Sub(last).Milliseconds())
+		last = now
+		info.Events = append(info.Events, event)
+		if onEvent != nil {
+			onEvent(event)
+		}
+		if len(info.Events) >= maxEvents {
+			info.StoppedReason = "maxEvents"
+			break
+		}
+	}
+
+	info.Duration = uint64(time.Since(started).Milliseconds())
+	info.LastEventID = optionalString(parser.lastID)
+	info.Retry = parser.retry
+	return raw.Bytes(), info
+}
+
+// eventParser interprets event stream lines as specified by the HTML
+// standard's server-sent events section.
+type eventParser struct {
+	eventType string
+	data      []string
+	hasData   bool
+	lastID    string
+	retry     *int
+}
+
+// feed processes one line and returns the event a blank line dispatches.
+func (p *eventParser) feed(line string) (ServerSentEvent, bool) {
+	if line == "" {
+		defer func() { p.eventType, p.data, p.hasData = "", nil, false }()
+		if !p.hasData {
+			return ServerSentEvent{}, false
+		}
+		event := ServerSentEvent{
+			Event: p.eventType,
+			Data:  strings.Join(p.data, "\n"),
+			ID:    optionalString(p.lastID),
+		}
+		if event.Event == "" {
+			event.Event = "message"
+		}
+		return event, true
+	}
+	if strings.HasPrefix(line, ":") {
+		// Comment, often used as a keep-alive
+		return ServerSentEvent{}, false
+	}
+
+	field, value, _ := strings.Cut(line, ":")
+	value = strings.TrimPrefix(value, " ")
+	switch field {
+	case "event":
+		p.eventType = value
+	case "data":
+		p.data = append(p.data, value)
+		p.hasData = true
+	case "id":
+		if !strings.ContainsRune(value, 0) {
+			p.lastID = value
+		}
+	case "retry":
+		if n, err := strconv.Atoi(value); err == nil && n >= 0 {
+			p.retry = &n
+		}
+	}
+	return ServerSentEvent{}, false
+}
@@ -1,8 +1,9 @@
 package proxy
 
 import (
+	"bytes"
 	"context"
-	"crypto/tls"
+	"errors"
 	"fmt"
 	"io"
 	"net"
@@ -11,7 +12,10 @@ import (
 	"strings"
 	"time"
 
+	"zone.digit.tommie/internal/cookies"
+	"zone.digit.tommie/internal/hosts"
 	"zone.digit.tommie/internal/infra"
+	"zone.digit.tommie/internal/storage"
 )
 
 const (
@@ -139,30 +143,238 @@ func (c *requestContext) addr() string {
 	return net.JoinHostPort(c.host, c.port)
 }
 
-// ExecuteRequest executes an HTTP request with detailed timing.
-func ExecuteRequest(request ProxyRequest) ProxyResponse {
-	timing := NewDetailedTiming()
+// Execute executes an HTTP request with detailed timing, keeping per-user
+// state such as cookie jars in the service's default store.
+func (s *HTTPService) Execute(request ProxyRequest) ProxyResponse {
+	return s.ExecuteIn(s.opts.Store, request)
+}
+
+// ExecuteIn executes an HTTP request with detailed timing, keeping per-user
+// state such as cookie jars and history in store. A nil store disables that state.
+func (s *HTTPService) ExecuteIn(store storage.Store, request ProxyRequest) ProxyResponse {
+	return s.ExecuteContext(context.Background(), store, request)
+}
+
+// ExecuteContext is ExecuteIn that gives up when ctx is cancelled.
+func (s *HTTPService) ExecuteContext(ctx context.Context, store storage.Store, request ProxyRequest) ProxyResponse {
+	return s.ExecuteStream(ctx, store, request, nil)
+}
+
+// ExecuteStream is ExecuteContext that also passes each server-sent event
+// of a text/event-stream response to onEvent as it arrives.
+func (s *HTTPService) ExecuteStream(ctx context.Context, store storage.Store, request ProxyRequest, onEvent func(ServerSentEvent)) ProxyResponse {
+	started := s.clock.Now()
+	response, executed := s.run(ctx, store, request, onEvent)
+	if executed != nil {
+		s.recordHistory(store, *executed, response, started)
+		s.recordSession(store, *executed, response, started)
+	}
+	return response
+}
+
+// run executes a request with its scripts, template, assertions and
+// extraction, returning the response and the request as it was sent. The
+// request is nil when the execution failed before sending it.
+func (s *HTTPService) run(ctx context.Context, store storage.Store, request ProxyRequest, onEvent func(ServerSentEvent)) (ProxyResponse, *ProxyRequest) {
+	if ctx.Err() != nil {
+		return NewErrorResponse("Request cancelled", "CANCELLED"), nil
+	}
+	var scripts ScriptResults
+	if request.Scripts != nil && strings.TrimSpace(request.Scripts.PreRequest) != "" {
+		preRequest, err := s.runPreRequestScript(store, &request)
+		if err != nil {
+			return environmentError(err), nil
+		}
+		if preRequest.Error != nil {
+			return NewErrorResponse("Pre-request script failed: "+preRequest.Error.String(), preRequest.Error.Code), nil
+		}
+		scripts.PreRequest = preRequest
+	}
+	unresolved := request
+	request, template, err := resolveTemplate(store, request)
+	if err != nil {
+		return environmentError(err), nil
+	}
+	s.interceptRequest(&request)
+	pluginRuns, failed := s.runRequestPlugins(ctx, &request)
+	if failed != nil {
+		return NewErrorResponse(fmt.Sprintf("Plugin %s failed: %s", failed.Plugin, *failed.Error), "PLUGIN_ERROR"), nil
+	}
+
+	if request.CaptureFixture && s.opts.FixtureDir == "" {
+		return NewErrorResponse("Fixture capture is not enabled; set FIXTURE_DIR", "INVALID_REQUEST"), nil
+	}
+
+	var response ProxyResponse
+	if s.simulator != nil {
+		response = s.simulator.Execute(request)
+	} else {
+		response = s.executeWithRetry(ctx, store, request, onEvent)
+	}
+	s.interceptResponse(request, &response)
+	if response.Success {
+		pluginRuns = append(pluginRuns, s.runResponsePlugins(ctx, request, response.Data)...)
+		response.Data.Plugins = pluginRuns
+	}
+	if template != nil && response.Success {
+		response.Data.Template = template
+	}
+	if response.Success && unresolved.Scripts != nil && strings.TrimSpace(unresolved.Scripts.Test) != "" {
+		scripts.Test = s.runTestScript(store, unresolved, request, response.Data)
+	}
+	if response.Success && len(request.Assertions) > 0 {
+		response.Data.Assertions = evaluateAssertions(request.Assertions, response.Data)
+	}
+	if response.Success && len(request.Extract) > 0 {
+		response.Data.Extracted = extractVariables(store, unresolved.Environment, request.Extract, response.Data)
+	}
+	if response.Success && scripts != (ScriptResults{}) {
+		response.Data.Scripts = &scripts
+	}
+	if request.CaptureFixture {
+		s.captureFixture(request, &response)
+	}
+	return response, &request
+}
+
+func (s *HTTPService) execute(ctx context.Context, store storage.Store, request ProxyRequest, onEvent func(ServerSentEvent)) ProxyResponse {
+	timing := NewDetailedTimingWithClock(s.clock)
 
 	// Parse initial URL
-	ctx, err := newRequestContext(request.URL)
+	target, err := newRequestContext(request.URL)
 	if err != nil {
 		return NewErrorResponse(err.Error(), "INVALID_URL")
 	}
 
+	reqBody, err := request.encodeBody()
+	if err != nil {
+		return NewErrorResponse(err.Error(), "INVALID_BODY")
+	}
+
+	// Bodies held in memory count against the service's in-flight ceiling
+	memory := s.memory.reserve()
+	defer memory.release()
+	if err := memory.grow(len(reqBody.raw)); err != nil {
+		response, _ := busyResponse(err)
+		return response
+	}
+	if request.GraphQL != nil && request.Method == "" {
+		request.Method = "POST"
+	}
+	if request.Auth != nil {
+		headers, err := request.Auth.authHeaders(request.Headers)
+		if err != nil {
+			return NewErrorResponse(err.Error(), "INVALID_AUTH")
+		}
+		if s.negotiatorFactory(request.Auth, "") == nil && (strings.EqualFold(request.Auth.Type, AuthNegotiate) || strings.EqualFold(request.Auth.Type, AuthNTLM)) {
+			return NewErrorResponse(fmt.Sprintf("%s auth without a username needs integrated authentication, available in the desktop app", strings.ToLower(request.Auth.Type)), "INVALID_AUTH")
+		}
+		request.Headers = headers
+	}
+	maxBodyBytes := positiveOr(s.opts.MaxBodyBytes, DefaultMaxBodyBytes)
+	if request.MaxBodyBytes != nil {
+		if *request.MaxBodyBytes <= 0 {
+			return NewErrorResponse("maxBodyBytes must be positive", "INVALID_REQUEST")
+		}
+		maxBodyBytes = min(*request.MaxBodyBytes, maxBodyBytes)
+	}
+
+	// Attach the named cookie jar
+	var jar *cookies.Jar
+	var jarInfo *CookieJarInfo
+	if request.CookieJar != nil && *request.CookieJar != "" {
+		if store == nil {
+			return NewErrorResponse("Cookie jars are not available", "COOKIE_JAR_ERROR")
+		}
+		jar, err = cookies.Load(store, *request.CookieJar)
+		if err != nil {
+			return NewErrorResponse(fmt.Sprintf("Failed to load cookie jar: %v", err), "COOKIE_JAR_ERROR")
+		}
+		jarInfo = &CookieJarInfo{Name: *request.CookieJar}
+	}
+	cookieCtx := cookies.RequestContext{Navigation: true}
+	if request.BrowserContext != nil {
+		cookieCtx.Navigation = request.BrowserContext.Navigation
+		if request.BrowserContext.Initiator != "" {
+			initiator, err := url.Parse(request.BrowserContext.Initiator)
+			if err != nil || initiator.Host == "" {
+				return NewErrorResponse(fmt.Sprintf("Invalid initiator: %s", request.BrowserContext.Initiator), "INVALID_BROWSER_CONTEXT")
+			}
+			cookieCtx.Initiator = initiator
+		}
+	}
+
+	// Validate the requested protocol version
+	version, err := parseHTTPVersion(request.HTTPVersion)
+	if err != nil {
+		return NewErrorResponse(fmt.Sprintf("Unknown HTTP version: %s", *request.HTTPVersion), "INVALID_HTTP_VERSION")
+	}
+	preferIP, err := parsePreferIP(request.PreferIP)
+	if err != nil {
+		return NewErrorResponse(err.Error(), "INVALID_REQUEST")
+	}
+
 	timeoutMS := DefaultTimeoutMS
 	if request.Timeout != nil {
 		timeoutMS = int(*request.Timeout)
 	}
 	timeout := time.Duration(timeoutMS) * time.Millisecond
 
+	// Join or start a DNS pin session
+	var pinToken string
+	var pins map[string]string
+	if request.PinDNS || request.PinToken != nil {
+		token := ""
+		if request.PinToken != nil {
+			token = *request.PinToken
+		}
+		if pinToken, pins, err = s.pins.session(token); err != nil {
+			return NewErrorResponse(fmt.Sprintf("Failed to start a DNS pin session: %v", err), "INTERNAL_ERROR")
+		}
+	}
+
+	if err := s.policy.Load().checkHost(target.host); err != nil {
+		return NewErrorResponse(err.Error(), "BLOCKED_BY_POLICY")
+	}
+	resolver, err := s.requestResolver(request.DNSServers)
+	if err != nil {
+		var policyErr *PolicyError
+		if errors.As(err, &policyErr) {
+			return NewErrorResponse(err.Error(), "BLOCKED_BY_POLICY")
+		}
+		return NewErrorResponse(fmt.Sprintf("Invalid dnsServers: %v", err), "INVALID_REQUEST")
+	}
+
+	// Host overrides take precedence over pins and DNS on every hop
+	var overrides map[string]string
+	if store != nil {
+		if overrides, err = hosts.Map(store); err != nil {
+			return NewErrorResponse(fmt.Sprintf("Failed to load host overrides: %v", err), "STORAGE_ERROR")
+		}
+	}
+
 	// DNS Resolution
 	timing.StartDNS()
-	dnsResult, err := infra.ResolveDNS(context.Background(), ctx.host)
-	if err != nil {
-		return NewErrorResponse(fmt.Sprintf("DNS lookup failed: %v", err), "DNS_ERROR")
+	var dnsResult *infra.DNSResult
+	pinnedIP, dnsPinned := pins[target.host]
+	overrideIP, overridden := overrides[hosts.Normalize(target.host)]
+	if overridden {
+		dnsPinned = false
+		dnsResult = &infra.DNSResult{IPs: []net.IP{net.ParseIP(overrideIP)}, Resolver: "override"}
+	} else if dnsPinned {
+		dnsResult = &infra.DNSResult{IPs: []net.IP{net.ParseIP(pinnedIP)}, Resolver: "pinned"}
+	} else {
+		dnsResult, err = resolver.Resolve(ctx, target.host)
+		if err != nil {
+			return NewErrorResponse(fmt.Sprintf("DNS lookup failed: %v", err), "DNS_ERROR")
+		}
 	}
 	timing.EndDNS()
 
+	if err := s.policy.Load().checkIPs(dnsResult.IPs); err != nil {
+		return NewErrorResponse(err.Error(), "BLOCKED_BY_POLICY")
+	}
+
 	var serverIP string
 	var resolvedIPs []string
 	for _, ip := range dnsResult.IPs {
@@ -171,6 +383,23 @@ func ExecuteRequest(request ProxyRequest) ProxyResponse {
 	if len(resolvedIPs) > 0 {
 		serverIP = resolvedIPs[0]
 	}
+	if pinToken != "" && !dnsPinned && !overridden && serverIP != "" {
+		s.pins.pin(pinToken, target.host, serverIP)
+		pins[target.host] = serverIP
+	}
+	// Overrides reach the dial through the pins, which are never saved back
+	for host, address := range overrides {
+		if pins == nil {
+			pins = make(map[string]string)
+		}
+		pins[host] = address
+	}
+	if overridden {
+		pins[target.host] = overrideIP
+	}
+	// The dial falls back across the addresses already resolved rather than
+	// resolving again, which would also bypass a custom resolver
+	addresses := map[string][]net.IP{target.host: dnsResult.IPs}
 
 	// Track redirect chain
 	var redirectChain []RedirectHop
@@ -183,68 +412,86 @@ func ExecuteRequest(request ProxyRequest) ProxyResponse {
 	}
 
 	var requestBodySize *int
-	if request.Body != nil {
-		size := len(*request.Body)
+	if reqBody.encoding != "" {
+		size := len(reqBody.raw)
 		requestBodySize = &size
 	}
 
+	// Redirects may change the method and drop the body
+	method := request.Method
+	sendHeaders := request.Headers
+
 	isFirstRequest := true
 
 	for {
-		hopStart := time.Now()
-
-		// Create HTTP client with custom transport for timing
-		transport := &http.Transport{
-			DialContext: func(dialCtx context.Context, network, addr string) (net.Conn, error) {
-				if isFirstRequest {
-					timing.StartTCP()
-				}
-				dialer := &net.Dialer{Timeout: timeout}
-				conn, err := dialer.DialContext(dialCtx, network, addr)
-				if isFirstRequest && err == nil {
-					timing.EndTCP()
-				}
-				return conn, err
-			},
-			TLSClientConfig: &tls.Config{
-				InsecureSkipVerify: false,
-			},
-			TLSHandshakeTimeout: timeout,
-			DisableCompression:  false,
+		if budget := s.egress.exhausted(); budget != "" {
+			return NewErrorResponse(fmt.Sprintf("Egress %s byte budget exceeded", budget), "QUOTA_EXCEEDED")
 		}
 
-		// Capture TLS info
-		if ctx.isHTTPS {
-			transport.DialTLSContext = func(dialCtx context.Context, network, addr string) (net.Conn, error) {
-				if isFirstRequest {
-					timing.StartTCP()
-				}
-				dialer := &net.Dialer{Timeout: timeout}
-				conn, err := dialer.DialContext(dialCtx, network, addr)
-				if err != nil {
-					return nil, err
-				}
-				if isFirstRequest {
-					timing.EndTCP()
-					timing.StartTLS()
-				}
+		hopStart := s.clock.Now()
 
-				tlsConn := tls.Client(conn, &tls.Config{
-					ServerName: ctx.host,
-				})
-				if err := tlsConn.HandshakeContext(dialCtx); err != nil {
-					conn.Close()
-					return nil, err
-				}
-				if isFirstRequest {
-					timing.EndTLS()
-					state := tlsConn.ConnectionState()
-					tlsInfo = infra.ExtractCertInfo(&state)
-				}
-				return tlsConn, nil
+		// Redirect targets go through the same resolver as the initial host
+		_, pinned := pins[target.host]
+		_, resolved := addresses[target.host]
+		if !isFirstRequest && resolver.Custom() && !pinned && !resolved {
+			result, err := resolver.Resolve(ctx, target.host)
+			if err != nil {
+				return NewErrorResponse(fmt.Sprintf("DNS lookup failed: %v", err), "DNS_ERROR")
+			}
+			addresses[target.host] = result.IPs
+		}
+
+		// Redirect targets are checked like the initial host
+		if !isFirstRequest && s.policy.Load() != nil {
+			if err := s.checkRedirectTarget(target.host, pins, addresses); err != nil {
+				return NewErrorResponse(err.Error(), "BLOCKED_BY_POLICY")
 			}
 		}
 
+		// Decide whether this hop goes through an upstream proxy
+		proxyURL, proxyDecision, err := s.upstream.decide(target.url)
+		if err != nil {
+			return NewErrorResponse(err.Error(), "PROXY_CONFIG_ERROR")
+		}
+
+		// The first hop records into the overall timing, redirects get their own
+		hopTiming := timing
+		if !isFirstRequest {
+			hopTiming = NewDetailedTimingWithClock(s.clock)
+		}
+
+		// Create HTTP client with custom transport for timing
+		dialer := &hopDialer{
+			timing:     hopTiming,
+			timeout:    timeout,
+			pins:       pins,
+			serverName: target.host,
+			rootCAs:    s.opts.RootCAs,
+			nextProtos: version.alpnProtocols(),
+			secure:     target.isHTTPS,
+			head:       &headRecorder{},
+			addresses:  addresses,
+			preferIP:   preferIP,
+		}
+		// A proxy resolves the destination itself, so only direct dials are checked
+		if proxyURL == nil {
+			dialer.policy = s.policy.Load()
+		}
+		if request.CaptureRaw {
+			dialer.capture = newWireCapture(request.CaptureRawLimit)
+		}
+		transport, err := newRoundTripper(dialer, version, proxyURL, target.isHTTPS)
+		if err != nil {
+			return NewErrorResponse(err.Error(), "UNSUPPORTED_PROTOCOL")
+		}
+		// Each QUIC connection holds a UDP socket until its transport is closed
+		if closer, ok := transport.(io.Closer); ok {
+			defer closer.Close()
+		}
+		if t, ok := transport.(*http.Transport); ok && s.opts.IntegratedAuth && proxyURL != nil {
+			t.GetProxyConnectHeader = negotiateProxyConnectHeader
+		}
+
 		client := &http.Client{
 			Transport: transport,
 			Timeout:   timeout,
@@ -254,51 +501,204 @@ func ExecuteRequest(request ProxyRequest) ProxyResponse {
 			},
 		}
 
-		// Build request
-		var bodyReader io.Reader
-		if request.Body != nil {
-			bodyReader = strings.NewReader(*request.Body)
+		// Build request; integrated auth resends it once per handshake leg
+		newRequest := func() (*http.Request, error) {
+			var bodyReader io.Reader
+			if reqBody.encoding != "" {
+				bodyReader = bytes.NewReader(reqBody.raw)
+			}
+
+			httpReq, err := http.NewRequestWithContext(ctx, method, target.url, bodyReader)
+			if err != nil {
+				return nil, err
+			}
+
+			// Set headers
+			for key, value := range sendHeaders {
+				httpReq.Header.Set(key, value)
+			}
+
+			// Generated bodies carry their own content type, e.g. the multipart boundary
+			if reqBody.contentType != "" {
+				httpReq.Header.Set("Content-Type", reqBody.contentType)
+			}
+
+			// Jar cookies are appended to any Cookie header set explicitly
+			if jar != nil {
+				cookieCtx.Method = httpReq.Method
+				sent, decisions := jar.Evaluate(httpReq.URL, cookieCtx)
+				jarInfo.Sent = nil
+				for _, c := range sent {
+					httpReq.AddCookie(c)
+					jarInfo.Sent = append(jarInfo.Sent, c.Name)
+				}
+				jarInfo.Decisions = cookieDecisions(decisions)
+			}
+
+			// Add accept-encoding if not set
+			if httpReq.Header.Get("Accept-Encoding") == "" {
+				httpReq.Header.Set("Accept-Encoding", "gzip, deflate, br")
+			}
+			return httpReq, nil
 		}
 
-		httpReq, err := http.NewRequest(request.Method, ctx.url, bodyReader)
+		httpReq, err := newRequest()
 		if err != nil {
 			return NewErrorResponse(fmt.Sprintf("Failed to create request: %v", err), "REQUEST_BUILD_ERROR")
 		}
 
-		// Set headers
-		for key, value := range request.Headers {
-			httpReq.Header.Set(key, value)
-		}
-
-		// Add accept-encoding if not set
-		if httpReq.Header.Get("Accept-Encoding") == "" {
-			httpReq.Header.Set("Accept-Encoding", "gzip, deflate, br")
+		var renderedRequest *string
+		if reqBody.rendered != "" {
+			rendered := renderRequest(httpReq, len(reqBody.raw), reqBody.rendered)
+			renderedRequest = &rendered
 		}
 
-		if isFirstRequest {
-			timing.StartRequest()
-		}
+		hopTiming.StartRequest()
 
 		// Execute request
 		resp, err := client.Do(httpReq)
+		var policyErr *PolicyError
+		if errors.As(err, &policyErr) {
+			return NewErrorResponse(policyErr.Error(), "BLOCKED_BY_POLICY")
+		}
+		var certErr *CertificateError
+		if errors.As(err, &certErr) {
+			response := NewErrorResponse(fmt.Sprintf("Request failed: %v", certErr), "CERT_INVALID")
+			response.Error.TLS = buildTLSInfo(certErr.Info)
+			return response
+		}
 		if err != nil {
-			return NewErrorResponse(fmt.Sprintf("Request failed: %v", err), "REQUEST_FAILED")
+			return NewErrorResponse(fmt.Sprintf("Request failed: %v%s", err, attemptSummary(dialer.connectionAttempts())), "REQUEST_FAILED")
 		}
 
-		if isFirstRequest {
-			timing.MarkTTFB()
+		// Authentication challenges are recorded as hops to the same URL
+		recordChallenge := func(challenged *http.Response, message string) {
+			challengeHeaders := make(map[string]string)
+			for key, values := range challenged.Header {
+				challengeHeaders[strings.ToLower(key)] = values[0]
+			}
+			redirectChain = append(redirectChain, RedirectHop{
+				URL:      target.url,
+				Method:   method,
+				Status:   uint16(challenged.StatusCode),
+				Duration: uint64(since(s.clock, hopStart).Milliseconds()),
+				Headers:  challengeHeaders,
+				Message:  strPtr(message),
+				Proxy:    proxyDecision,
+				ServerIP: optionalString(dialer.remoteIP),
+				Protocol: strPtr(challenged.Proto),
+			})
+		}
+
+		// Credentials answer the challenges of the origin they were given for,
+		// not those of a redirect target, unless they are forwarded
+		answerChallenges := request.ForwardCredentials || sameOrigin(request.URL, target.url)
+
+		var authInfo *IntegratedAuthInfo
+		newNeg := s.negotiatorFactory(request.Auth, target.host)
+		if newNeg == nil && s.opts.IntegratedAuth && proxyURL != nil && resp.StatusCode == http.StatusProxyAuthRequired {
+			// The configured proxy is trusted with the current user's credentials
+			newNeg = newNegotiator
+		}
+		if newNeg != nil && !answerChallenges && resp.StatusCode == http.StatusUnauthorized {
+			// 407s still go to integratedAuth, as the proxy is not chosen by the redirect
+			if scheme := preferredScheme(resp.Header.Values("WWW-Authenticate")); scheme != "" {
+				recordChallenge(resp, scheme+" challenge not answered: credentials are not sent to another origin")
+			}
+		} else if newNeg != nil {
+			resp, authInfo, err = integratedAuth(client, resp, newRequest, target.host, proxyURL, newNeg, func(challenged *http.Response, scheme string) {
+				recordChallenge(challenged, scheme+" challenge answered")
+			})
+			if err != nil {
+				return NewErrorResponse(fmt.Sprintf("Request failed: %v", err), "REQUEST_FAILED")
+			}
+		}
+		digest := request.Auth != nil && strings.EqualFold(request.Auth.Type, AuthDigest)
+		if digest && !answerChallenges && resp.StatusCode == http.StatusUnauthorized && parseDigestChallenge(resp.Header.Values("WWW-Authenticate")) != nil {
+			recordChallenge(resp, "Digest challenge not answered: credentials are not sent to another origin")
+		}
+		if digest && answerChallenges {
+			answered, challenged, authorization, err := digestAuth(client, resp, newRequest, request.Auth, reqBody.raw)
+			if err != nil {
+				return NewErrorResponse(fmt.Sprintf("Request failed: %v", err), "REQUEST_FAILED")
+			}
+			if challenged != nil {
+				recordChallenge(challenged, "Digest challenge answered with credentials")
+				resp = answered
+				requestHeaders = withoutHeaders(requestHeaders, []string{"Authorization"})
+				requestHeaders["Authorization"] = authorization
+			}
+		}
+
+		hopTiming.MarkTTFB()
+
+		tlsInfo = dialer.tlsInfo
+
+		// Tunneled connections bypass the TLS dialer, so capture from the response
+		if tlsInfo == nil && resp.TLS != nil {
+			tlsInfo = infra.ExtractCertInfo(resp.TLS, target.host)
+		}
+		// Through a proxy the connection ends at the proxy, not the origin
+		if dialer.remoteIP != "" && proxyURL == nil {
+			serverIP = dialer.remoteIP
+		}
+
+		if version == httpVersion2 && resp.ProtoMajor != 2 {
+			resp.Body.Close()
+			return NewErrorResponse(fmt.Sprintf("Server did not negotiate HTTP/2 (got %s)", resp.Proto), "PROTOCOL_NOT_NEGOTIATED")
 		}
 
 		// Read response
 		timing.StartDownload()
-		bodyBytes, err := io.ReadAll(resp.Body)
+		if hopTiming != timing {
+			hopTiming.StartDownload()
+		}
+		// Event streams never end on their own, so they are read up to a limit
+		var bodyBytes []byte
+		var bodyTruncated bool
+		var eventStream *EventStreamInfo
+		var download *DownloadInfo
+		redirect := resp.StatusCode >= 300 && resp.StatusCode < 400 && resp.Header.Get("Location") != ""
+		if request.Download != nil && !redirect {
+			// Downloads go to disk instead of memory, bounded by their own limit
+			download, err = s.saveDownload(request.Download, target.url, resp)
+			if err != nil {
+				resp.Body.Close()
+				return downloadErrorResponse(err)
+			}
+		} else if isEventStream(resp.Header.Get("Content-Type")) {
+			bodyBytes, eventStream = readEventStream(memory.reader(resp.Body), request.EventStream, onEvent)
+		} else {
+			bodyBytes, bodyTruncated, err = readBody(memory.reader(resp.Body), maxBodyBytes)
+		}
 		resp.Body.Close()
+		if response, busy := busyResponse(err); busy {
+			return response
+		}
 		if err != nil {
 			return NewErrorResponse(fmt.Sprintf("Failed to read body: %v", err), "BODY_READ_ERROR")
 		}
 		timing.EndDownload()
+		if hopTiming != timing {
+			hopTiming.EndDownload()
+		}
+
+		// Downloads were charged to the egress budget as they streamed
+		downloaded := 0
+		if download != nil {
+			downloaded = int(download.Size)
+		}
+		s.egress.record(len(reqBody.raw) + len(bodyBytes))
 
 		// Get headers
+		if jar != nil {
+			received := resp.Cookies()
+			jar.SetCookies(httpReq.URL, received)
+			for _, c := range received {
+				jarInfo.Received = append(jarInfo.Received, c.Name)
+			}
+		}
+
 		headers := make(map[string]string)
 		for key, values := range resp.Header {
 			if len(values) > 0 {
@@ -306,53 +706,226 @@ func ExecuteRequest(request ProxyRequest) ProxyResponse {
 			}
 		}
 
+		// Keep the wire order and casing when the head was recorded
+		headerFields := headerFieldsFromHeader(resp.Header, resp.ProtoMajor == 2)
+		rawHead := dialer.head.responseHead(resp)
+		if rawHead != nil {
+			headerFields = parseHeaderFields(rawHead)
+		}
+
 		httpVersion = resp.Proto
 
 		// Check for redirect
 		if resp.StatusCode >= 300 && resp.StatusCode < 400 {
 			location := resp.Header.Get("Location")
 			if location != "" {
-				hopDuration := uint64(time.Since(hopStart).Milliseconds())
-				currentURL := ctx.url
-				nextURL := ctx.updateFromRedirect(location)
+				hopDuration := uint64(since(s.clock, hopStart).Milliseconds())
+				currentURL := target.url
+				nextURL := target.updateFromRedirect(location)
+				nextMethod, keepBody := redirectMethod(resp.StatusCode, method, request.StrictRedirects)
+				bodyDropped := !keepBody && reqBody.encoding != ""
 
+				hopTimingInfo := hopTiming.ToTimingInfo()
 				redirectChain = append(redirectChain, RedirectHop{
-					URL:      currentURL,
-					Status:   uint16(resp.StatusCode),
-					Duration: hopDuration,
-					Headers:  headers,
-					Message:  strPtr(fmt.Sprintf("Redirect to: %s", nextURL)),
+					URL:                  currentURL,
+					Method:               method,
+					Status:               uint16(resp.StatusCode),
+					Duration:             hopDuration,
+					Headers:              headers,
+					Message:              strPtr(redirectMessage(nextURL, method, nextMethod, bodyDropped)),
+					Proxy:                proxyDecision,
+					ServerIP:             strPtr(serverIP),
+					Protocol:             strPtr(resp.Proto),
+					TLS:                  buildTLSInfo(tlsInfo),
+					Timing:               &hopTimingInfo,
+					CredentialsForwarded: credentialsSent(request.Headers, sendHeaders),
+					ConnectionAttempts:   dialer.connectionAttempts(),
 				})
 
 				if len(redirectChain) >= MaxRedirects {
 					return NewErrorResponse("Too many redirects", "TOO_MANY_REDIRECTS")
 				}
 
+				method = nextMethod
+				if !keepBody {
+					reqBody = requestBody{}
+					sendHeaders = withoutBodyHeaders(sendHeaders)
+				}
+				// Once dropped, credentials stay dropped even if a later hop returns to the origin
+				if !request.ForwardCredentials && !sameOrigin(currentURL, nextURL) {
+					sendHeaders = withoutHeaders(sendHeaders, credentialHeaders)
+				}
+
 				isFirstRequest = false
 				continue
 			}
 		}
 
+		if jar != nil {
+			if err := cookies.Save(store, jarInfo.Name, jar); err != nil {
+				return NewErrorResponse(fmt.Sprintf("Failed to save cookie jar: %v", err), "COOKIE_JAR_ERROR")
+			}
+			jarInfo.Stored = len(jar.Cookies)
+		}
+
+		var rawRequest *RawRequest
+		if dialer.capture != nil {
+			rawRequest = dialer.capture.result(resp.Request, reqBody.raw, resp.ProtoMajor == 2)
+		}
+
 		// Build response
-		return buildResponse(responseBuildParams{
-			status:          uint16(resp.StatusCode),
-			headers:         headers,
-			bodyBytes:       bodyBytes,
-			timing:          timing,
-			finalURL:        ctx.url,
-			redirectChain:   redirectChain,
-			tlsInfo:         tlsInfo,
-			httpVersion:     httpVersion,
-			serverIP:        serverIP,
-			requestHeaders:  requestHeaders,
-			requestBodySize: requestBodySize,
-			hostname:        ctx.host,
-			port:            ctx.port,
-			resolvedIPs:     resolvedIPs,
+		response := buildResponse(responseBuildParams{
+			status:              uint16(resp.StatusCode),
+			headers:             headers,
+			bodyBytes:           bodyBytes,
+			bodyTruncated:       bodyTruncated,
+			contentLength:       resp.ContentLength,
+			maxBodyBytes:        maxBodyBytes,
+			integrity:           request.Integrity,
+			timing:              timing,
+			finalURL:            target.url,
+			redirectChain:       redirectChain,
+			tlsInfo:             tlsInfo,
+			httpVersion:         httpVersion,
+			serverIP:            serverIP,
+			requestMethod:       method,
+			requestHeaders:      requestHeaders,
+			requestBodySize:     requestBodySize,
+			requestBodyEncoding: reqBody.encoding,
+			renderedRequest:     renderedRequest,
+			cookieJar:           jarInfo,
+			rawRequest:          rawRequest,
+			headerFields:        headerFields,
+			rawHead:             rawHead,
+			captureRaw:          request.CaptureRaw,
+			beautify:            request.Beautify,
+			hexdumpBytes:        request.HexdumpBytes,
+			bodyStatsThreshold:  request.BodyStatsThreshold,
+			hostname:            target.host,
+			port:                target.port,
+			resolvedIPs:         resolvedIPs,
+			pinToken:            pinToken,
+			dnsPinned:           dnsPinned,
+			proxyDecision:       proxyDecision,
+			integratedAuth:      authInfo,
+			trailers:            headerFieldsFromHeader(resp.Trailer, resp.ProtoMajor == 2),
 		})
+
+		if request.ProbeRange && response.Success {
+			if media := response.Data.MediaInfo; media != nil && media.Range != nil {
+				s.probeRange(store, request, target.url, media.Range)
+			}
+		}
+		if request.FetchSourceMap && response.Success {
+			if ref := sourceMapReference(headers, response.Data.Body); ref != "" {
+				response.Data.SourceMap = s.fetchSourceMap(store, request, target.url, ref)
+			}
+		}
+		if request.DNSSecurity && response.Success {
+			response.Data.DNSSecurity = dnsSecurity(resolver, target.host)
+		}
+		if request.Paginate != nil && response.Success {
+			response.Data.Pagination = s.paginate(store, request, target.url, response.Data)
+		}
+		if request.GraphQL != nil && response.Success {
+			response.Data.GraphQL = parseGraphQLResponse(response.Data.Body)
+		}
+		if eventStream != nil && response.Success {
+			response.Data.EventStream = eventStream
+		}
+		if download != nil && response.Success {
+			response.Data.Download = download
+			response.Data.Size = downloaded
+			response.Data.BodyBase64 = nil
+			response.Data.Integrity = nil
+			if sizes := response.Data.SizeBreakdown; sizes != nil {
+				sizes.Body = downloaded
+				sizes.Total = sizes.Headers + downloaded
+			}
+		}
+		if response.Success {
+			response.Data.ClockSkew = clockSkew(headers, hopTiming)
+			response.Data.Cookies = parseSetCookies(response.Data.Headers, target.url, s.clock.Now())
+			response.Data.JWTs = findJWTs(sendHeaders, response.Data, request.JWTKey, s.clock.Now())
+			authorized := false
+			for key := range sendHeaders {
+				authorized = authorized || strings.EqualFold(key, "Authorization")
+			}
+			response.Data.CacheAnalysis = analyzeCache(method, authorized, int(response.Data.Status), headers, s.clock.Now())
+			if request.Revalidate && download == nil {
+				response.Data.CacheAnalysis.Revalidation = s.revalidateCache(ctx, store, request, target.url, response.Data, response.Data.CacheAnalysis)
+			}
+		}
+		if response.Success {
+			response.Data.Resolver = optionalString(dnsResult.Resolver)
+			response.Data.ConnectionAttempts = dialer.connectionAttempts()
+			forwarded := credentialsSent(request.Headers, sendHeaders)
+			response.Data.CredentialsForwarded = forwarded
+			response.Data.SecurityAnalysis = &SecurityAnalysis{
+				Redirects: analyzeRedirects(redirectChain, target.url, forwarded),
+				Headers:   analyzeSecurityHeaders(headers, target.url),
+			}
+		}
+		return response
+	}
+}
+
+// readBody reads a response body, keeping at most limit bytes. It reports
+// whether the body was longer and stops reading there, leaving the rest
+// unread rather than buffering it.
+func readBody(body io.Reader, limit int) ([]byte, bool, error) {
+	data, err := io.ReadAll(io.LimitReader(body, int64(limit)+1))
+	if len(data) > limit {
+		return data[:limit], true, nil
+	}
+	return data, false, err
+}
+
+// checkRedirectTarget applies the destination policy to a redirect target,
+// resolving it unless an address is pinned or already resolved.
+func (s *HTTPService) checkRedirectTarget(host string, pins map[string]string, addresses map[string][]net.IP) error {
+	if err := s.policy.Load().checkHost(host); err != nil {
+		return err
+	}
+	if pinned, ok := pins[host]; ok {
+		return s.policy.Load().checkIP(net.ParseIP(pinned))
 	}
+	if ips, ok := addresses[host]; ok {
+		return s.policy.Load().checkIPs(ips)
+	}
+	result, err := infra.ResolveDNS(context.Background(), host)
+	if err != nil {
+		// Left to the dial to report
+		return nil
+	}
+	return s.policy.Load().checkIPs(result.IPs)
+}
+
+// cookieDecisions converts jar decisions to their protocol representation.
+func cookieDecisions(decisions []cookies.Decision) []CookieDecision {
+	result := make([]CookieDecision, len(decisions))
+	for i, d := range decisions {
+		result[i] = CookieDecision{
+			Name:   d.Name,
+			Domain: d.Domain,
+			Path:   d.Path,
+			Sent:   d.Sent,
+		}
+		if d.Reason != "" {
+			result[i].Reason = strPtr(d.Reason)
+		}
+	}
+	return result
 }
 
 func strPtr(s string) *string {
 	return &s
 }
+
+// optionalString returns nil for an empty string.
+func optionalString(s string) *string {
+	if s == "" {
+		return nil
+	}
+	return &s
+}
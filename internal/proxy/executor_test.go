@@ -0,0 +1,92 @@
+package proxy_test
+
+import (
+	"strings"
+	"testing"
+	"time"
+
+	"zone.digit.tommie/internal/proxy"
+	"zone.digit.tommie/internal/testkit"
+)
+
+// success fails the test unless the response succeeded, returning its data.
+func success(t *testing.T, response proxy.ProxyResponse) *proxy.ResponseData {
+	t.Helper()
+	if !response.Success {
+		t.Fatalf("request failed: %s (%s)", response.Error.Message, response.Error.Code)
+	}
+	return response.Data
+}
+
+// failure fails the test unless the response failed with code.
+func failure(t *testing.T, response proxy.ProxyResponse, code string) *proxy.ErrorData {
+	t.Helper()
+	if response.Success {
+		t.Fatalf("got status %d, want %s", response.Data.Status, code)
+	}
+	if response.Error.Code != code {
+		t.Fatalf("got %s (%s), want %s", response.Error.Code, response.Error.Message, code)
+	}
+	return response.Error
+}
+
+func TestTimeout(t *testing.T) {
+	target := testkit.Start()
+	defer target.Close()
+
+	timeout := uint64(100)
+	started := time.Now()
+	response := target.Execute(proxy.ProxyRequest{URL: "/delay/5000", Timeout: &timeout})
+	failed := failure(t, response, "REQUEST_FAILED")
+	if !strings.Contains(strings.ToLower(failed.Message), "timeout") {
+		t.Errorf("got %q, want a timeout", failed.Message)
+	}
+	if elapsed := time.Since(started); elapsed > 2*time.Second {
+		t.Errorf("took %s to time out after 100ms", elapsed)
+	}
+}
+
+func TestDecompression(t *testing.T) {
+	target := testkit.Start()
+	defer target.Close()
+
+	for _, encoding := range []string{"gzip", "deflate", "br"} {
+		t.Run(encoding, func(t *testing.T) {
+			data := success(t, target.Execute(proxy.ProxyRequest{URL: "/" + encoding}))
+			if !strings.Contains(data.Body, `"encoding":"`+encoding+`"`) {
+				t.Errorf("got body %q, want it decoded", data.Body)
+			}
+			sizes := data.SizeBreakdown
+			if sizes == nil || sizes.Encoding == nil || *sizes.Encoding != encoding {
+				t.Fatalf("got size breakdown %+v, want encoding %s", sizes, encoding)
+			}
+			if sizes.Uncompressed == nil || *sizes.Uncompressed != len(data.Body) {
+				t.Errorf("got uncompressed size %v, want %d", sizes.Uncompressed, len(data.Body))
+			}
+		})
+	}
+}
+
+func TestCertificateValidation(t *testing.T) {
+	target := testkit.StartTLS()
+	defer target.Close()
+
+	data := success(t, target.Execute(proxy.ProxyRequest{URL: "/ok"}))
+	if data.TLS == nil || data.TLS.Validation == nil || !data.TLS.Validation.ChainVerified {
+		t.Errorf("got TLS %+v, want a verified chain", data.TLS)
+	}
+
+	// The system roots do not trust the target
+	response := proxy.NewHTTPService(proxy.Options{}).Execute(target.Request(proxy.ProxyRequest{URL: "/ok"}))
+	failed := failure(t, response, "CERT_INVALID")
+	if failed.TLS == nil || failed.TLS.Validation == nil {
+		t.Fatalf("got no certificate details with %q", failed.Message)
+	}
+	validation := failed.TLS.Validation
+	if validation.ChainVerified || !validation.HostnameMatch || len(validation.Errors) == 0 {
+		t.Errorf("got validation %+v, want only the chain rejected", validation)
+	}
+	if len(target.Requests()) != 1 {
+		t.Errorf("target received %d requests, want the rejected one not sent", len(target.Requests()))
+	}
+}
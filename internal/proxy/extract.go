@@ -0,0 +1,84 @@
+package proxy
+
+import (
+	"encoding/json"
+	"strings"
+
+	"zone.digit.tommie/internal/infra"
+	"zone.digit.tommie/internal/storage"
+)
+
+// extractVariables reads the extractions of a request from its JSON response
+// body and saves the values found to the request's environment. A path
+// matching one value stores it as a string, like a script variable; several
+// matches are stored as a JSON array.
+func extractVariables(store storage.Store, environment *string, extractions []Extraction, data *ResponseData) []ExtractionResult {
+	results := make([]ExtractionResult, len(extractions))
+	var parsed interface{}
+	parseErr := "The body is binary"
+	if !data.IsBinary {
+		parseErr = ""
+		if err := json.NewDecoder(strings.NewReader(data.Body)).Decode(&parsed); err != nil {
+			parseErr = "The body is not JSON"
+		}
+	}
+
+	variables := map[string]*string{}
+	for i, e := range extractions {
+		result := ExtractionResult{Variable: e.Variable, Path: e.Path}
+		path, err := infra.CompileJSONPath(e.Path)
+		switch {
+		case strings.TrimSpace(e.Variable) == "":
+			result.Error = strPtr("variable is required")
+		case err != nil:
+			result.Error = strPtr("Invalid JSONPath: " + err.Error())
+		case parseErr != "":
+			result.Error = strPtr(parseErr)
+		default:
+			matches := path.Find(parsed)
+			switch len(matches) {
+			case 0:
+				result.Error = strPtr(e.Path + " matches nothing")
+			case 1:
+				result.Value = strPtr(scriptString(matches[0]))
+			default:
+				result.Value = strPtr(scriptString(matches))
+			}
+		}
+		if result.Value != nil {
+			variables[e.Variable] = result.Value
+		}
+		results[i] = result
+	}
+
+	if len(variables) == 0 {
+		return results
+	}
+	if err := saveVariables(store, environment, variables); err != nil {
+		message := "Failed to save variable: " + err.Error()
+		for i := range results {
+			if results[i].Value != nil {
+				results[i].Error = &message
+			}
+		}
+	}
+	return results
+}
+
+// EvaluateJSONPath applies a JSONPath expression to a JSON document, for
+// trying out the paths of extractions and assertions.
+func (s *HTTPService) EvaluateJSONPath(request JSONPathRequest) JSONPathResponse {
+	path, err := infra.CompileJSONPath(request.Path)
+	if err != nil {
+		return JSONPathResponse{Error: &ErrorData{Message: "Invalid JSONPath: " + err.Error(), Code: "INVALID_REQUEST"}}
+	}
+	var document interface{}
+	if err := json.Unmarshal([]byte(request.JSON), &document); err != nil {
+		return JSONPathResponse{Error: &ErrorData{Message: "Invalid JSON: " + err.Error(), Code: "INVALID_REQUEST"}}
+	}
+	matches := path.Find(document)
+	if matches == nil {
+		matches = []interface{}{}
+	}
+	return JSONPathResponse{Success: true, Matches: matches}
+}
@@ -0,0 +1,80 @@
+package proxy
+
+import (
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"log"
+	"net/url"
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// maxFixtureSlug bounds the readable part of a fixture file name.
+const maxFixtureSlug = 80
+
+// captureFixture writes the full response of a request as indented JSON to
+// the fixture directory and records the file name on the response. Names
+// derive from the request, so capturing the same request again replaces
+// the previous fixture. Failures are logged and leave the response as is.
+func (s *HTTPService) captureFixture(request ProxyRequest, response *ProxyResponse) {
+	data, err := json.MarshalIndent(response, "", "  ")
+	if err == nil {
+		err = os.MkdirAll(s.opts.FixtureDir, 0o755)
+	}
+	name := fixtureName(request)
+	if err == nil {
+		err = os.WriteFile(filepath.Join(s.opts.FixtureDir, name), append(data, '\n'), 0o644)
+	}
+	if err != nil {
+		log.Printf("Failed to capture fixture: %v", err)
+		return
+	}
+
+	if response.Success {
+		response.Data.Fixture = &name
+	} else {
+		response.Error.Fixture = &name
+	}
+}
+
+// fixtureName names the fixture of a request as method-host-path-hash.json.
+// The hash covers the method, URL and body, telling apart requests that
+// differ only in their query or body.
+func fixtureName(request ProxyRequest) string {
+	method := strings.ToUpper(request.Method)
+	if method == "" {
+		method = "GET"
+	}
+	readable := method
+	if u, err := url.Parse(request.URL); err == nil {
+		readable += " " + u.Host + " " + u.Path
+	}
+
+	var slug strings.Builder
+	dash := false
+	for _, r := range strings.ToLower(readable) {
+		if r >= 'a' && r <= 'z' || r >= '0' && r <= '9' || r == '.' {
+			slug.WriteRune(r)
+			dash = false
+		} else if !dash {
+			slug.WriteByte('-')
+			dash = true
+		}
+	}
+	readable = strings.Trim(slug.String(), "-.")
+	if len(readable) > maxFixtureSlug {
+		readable = strings.TrimRight(readable[:maxFixtureSlug], "-.")
+	}
+
+	h := sha256.New()
+	fmt.Fprintf(h, "%s\n%s\n", method, request.URL)
+	if request.Body != nil {
+		h.Write([]byte(*request.Body))
+	} else if request.BodyBase64 != nil {
+		h.Write([]byte(*request.BodyBase64))
+	}
+	return fmt.Sprintf("%s-%s.json", readable, hex.EncodeToString(h.Sum(nil))[:8])
+}
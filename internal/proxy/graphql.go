@@ -0,0 +1,89 @@
+package proxy
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"time"
+
+	"zone.digit.tommie/internal/infra"
+	"zone.digit.tommie/internal/storage"
+)
+
+// encodeGraphQL builds the JSON POST body of a GraphQL operation.
+func encodeGraphQL(operation *GraphQLRequest) (*requestBody, error) {
+	if operation.Query == "" {
+		return nil, errors.New("graphql query is required")
+	}
+	raw, err := json.Marshal(operation)
+	if err != nil {
+		return nil, fmt.Errorf("invalid graphql variables: %w", err)
+	}
+	return &requestBody{raw: raw, encoding: BodyEncodingText, contentType: "application/json"}, nil
+}
+
+// parseGraphQLResponse separates the data and errors of a GraphQL response
+// body and measures how long decoding took.
+func parseGraphQLResponse(body string) *GraphQLInfo {
+	started := time.Now()
+	var payload struct {
+		Data       interface{}            `json:"data"`
+		Errors     []GraphQLError         `json:"errors"`
+		Extensions map[string]interface{} `json:"extensions"`
+	}
+	err := json.Unmarshal([]byte(body), &payload)
+	info := &GraphQLInfo{ParseTime: uint64(time.Since(started).Microseconds())}
+	if err != nil {
+		info.ParseError = strPtr(err.Error())
+		return info
+	}
+
+	info.Data = payload.Data
+	info.Errors = payload.Errors
+	info.Extensions = payload.Extensions
+	info.HasData = payload.Data != nil
+	info.Partial = info.HasData && len(payload.Errors) > 0
+	return info
+}
+
+// IntrospectGraphQL runs the introspection query against the endpoint of
+// request and returns the schema printed as SDL. Any body or operation set
+// on request is replaced.
+func (s *HTTPService) IntrospectGraphQL(store storage.Store, request ProxyRequest) GraphQLSchemaResponse {
+	request.Method = "POST"
+	request.Body, request.BodyBase64, request.FormData = nil, nil, nil
+	request.GraphQL = &GraphQLRequest{Query: infra.IntrospectionQuery, OperationName: strPtr("IntrospectionQuery")}
+	request.Paginate = nil
+
+	response := s.ExecuteIn(store, request)
+	if !response.Success {
+		return GraphQLSchemaResponse{Error: response.Error}
+	}
+	result := GraphQLSchemaResponse{Status: response.Data.Status}
+	graphql := response.Data.GraphQL
+	switch {
+	case graphql.ParseError != nil:
+		result.Error = &ErrorData{Message: fmt.Sprintf("Response is not GraphQL (status %d): %s", response.Data.Status, *graphql.ParseError), Code: "INVALID_GRAPHQL_RESPONSE"}
+		return result
+	case !graphql.HasData && len(graphql.Errors) > 0:
+		// Typically introspection being disabled
+		result.Error = &ErrorData{Message: graphql.Errors[0].Message, Code: "INTROSPECTION_FAILED"}
+		return result
+	}
+
+	data, _ := json.Marshal(graphql.Data)
+	schema, err := infra.ParseIntrospection(data)
+	if err != nil {
+		result.Error = &ErrorData{Message: fmt.Sprintf("Invalid introspection result: %v", err), Code: "INTROSPECTION_FAILED"}
+		return result
+	}
+	result.Success = true
+	result.Schema = &GraphQLSchema{
+		SDL:              schema.SDL,
+		QueryType:        optionalString(schema.QueryType),
+		MutationType:     optionalString(schema.MutationType),
+		SubscriptionType: optionalString(schema.SubscriptionType),
+		Types:            schema.Types,
+	}
+	return result
+}
@@ -0,0 +1,174 @@
+package proxy
+
+import (
+	"bytes"
+	"compress/gzip"
+	"encoding/base64"
+	"fmt"
+	"io"
+	"strconv"
+	"strings"
+
+	"zone.digit.tommie/internal/grpc"
+	"zone.digit.tommie/internal/storage"
+)
+
+// ExecuteGRPC makes a unary gRPC call, or a gRPC-Web call over HTTP/1.1,
+// through the regular request pipeline. Messages are encoded and decoded
+// with the descriptor when one is given, otherwise payloads are raw.
+func (s *HTTPService) ExecuteGRPC(store storage.Store, call GRPCRequest) GRPCResponse {
+	name := strings.Trim(call.Method, "/")
+	if name == "" || !strings.Contains(name, "/") {
+		return grpcError("Method must be package.Service/Method", "INVALID_REQUEST")
+	}
+
+	var method *grpc.Method
+	if call.Descriptor != nil {
+		data, err := base64.StdEncoding.DecodeString(*call.Descriptor)
+		if err != nil {
+			return grpcError(fmt.Sprintf("Invalid descriptor: %v", err), "INVALID_DESCRIPTOR")
+		}
+		registry, err := grpc.ParseDescriptorSet(data)
+		if err != nil {
+			return grpcError(fmt.Sprintf("Invalid descriptor: %v", err), "INVALID_DESCRIPTOR")
+		}
+		var ok bool
+		if method, ok = registry.Method(name); !ok {
+			return grpcError(fmt.Sprintf("Method %s is not in the descriptor set", name), "METHOD_NOT_FOUND")
+		}
+		if method.ClientStreaming || method.ServerStreaming {
+			return grpcError(fmt.Sprintf("Method %s is streaming; only unary calls are supported", name), "UNSUPPORTED_METHOD")
+		}
+	}
+
+	var payload []byte
+	switch {
+	case call.PayloadBase64 != nil:
+		var err error
+		if payload, err = base64.StdEncoding.DecodeString(*call.PayloadBase64); err != nil {
+			return grpcError(fmt.Sprintf("Invalid payloadBase64: %v", err), "INVALID_REQUEST")
+		}
+	case method != nil:
+		var err error
+		if payload, err = grpc.Marshal(method.Input, call.Message); err != nil {
+			return grpcError(fmt.Sprintf("Failed to encode message: %v", err), "INVALID_MESSAGE")
+		}
+	case call.Message != nil:
+		return grpcError("A descriptor is required to encode message", "INVALID_REQUEST")
+	}
+
+	headers := make(map[string]string, len(call.Metadata)+4)
+	for key, value := range call.Metadata {
+		headers[key] = value
+	}
+	headers["grpc-accept-encoding"] = "gzip"
+	if call.Timeout != nil {
+		headers["grpc-timeout"] = strconv.FormatUint(*call.Timeout, 10) + "m"
+	}
+	version := "HTTP/2"
+	if call.Web {
+		version = "HTTP/1.1"
+		headers["Content-Type"] = "application/grpc-web+proto"
+		headers["X-Grpc-Web"] = "1"
+	} else {
+		headers["Content-Type"] = "application/grpc"
+		headers["TE"] = "trailers"
+	}
+
+	body := base64.StdEncoding.EncodeToString(grpc.EncodeFrame(payload))
+	resp := s.ExecuteIn(store, ProxyRequest{
+		Method:      "POST",
+		URL:         strings.TrimRight(call.URL, "/") + "/" + name,
+		Headers:     headers,
+		BodyBase64:  &body,
+		Timeout:     call.Timeout,
+		HTTPVersion: &version,
+	})
+	if !resp.Success {
+		return GRPCResponse{Messages: []GRPCMessage{}, RequestSize: len(payload), Error: resp.Error}
+	}
+
+	result := GRPCResponse{Messages: []GRPCMessage{}, RequestSize: len(payload), HTTP: resp.Data}
+	raw := []byte(resp.Data.Body)
+	if resp.Data.BodyBase64 != nil {
+		raw, _ = base64.StdEncoding.DecodeString(*resp.Data.BodyBase64)
+	}
+	frames, err := grpc.ParseFrames(raw)
+	if err != nil {
+		result.FrameError = strPtr(err.Error())
+	}
+
+	// Trailers-only responses carry the status in the headers
+	status := resp.Data.Headers
+	if len(resp.Data.Trailers) > 0 {
+		status = resp.Data.Trailers
+		result.Trailers = resp.Data.Trailers
+	}
+	encoding := headerValue(resp.Data.Headers, "grpc-encoding")
+	for _, frame := range frames {
+		if frame.Trailer {
+			for key, values := range grpc.ParseWebTrailers(frame.Payload) {
+				for _, value := range values {
+					result.Trailers = append(result.Trailers, HeaderField{Name: strings.ToLower(key), Value: value})
+				}
+			}
+			status = result.Trailers
+			continue
+		}
+		result.Messages = append(result.Messages, decodeGRPCMessage(frame, encoding, method))
+	}
+
+	if code, err := strconv.Atoi(headerValue(status, "grpc-status")); err == nil {
+		result.Status = &code
+		result.StatusName = strPtr(grpc.StatusName(code))
+		result.Success = code == 0
+	}
+	if message := headerValue(status, "grpc-message"); message != "" {
+		result.StatusMessage = strPtr(grpc.DecodeMessage(message))
+	}
+	if result.Status == nil {
+		result.Error = &ErrorData{Message: fmt.Sprintf("Response has no grpc-status (HTTP %d)", resp.Data.Status), Code: "MISSING_GRPC_STATUS"}
+	}
+	return result
+}
+
+// decodeGRPCMessage decompresses and decodes a response frame.
+func decodeGRPCMessage(frame grpc.Frame, encoding string, method *grpc.Method) GRPCMessage {
+	message := GRPCMessage{
+		Size:          len(frame.Payload),
+		Compressed:    frame.Compressed,
+		PayloadBase64: base64.StdEncoding.EncodeToString(frame.Payload),
+	}
+	payload := frame.Payload
+	if frame.Compressed {
+		if encoding != "gzip" {
+			message.DecodeError = strPtr(fmt.Sprintf("Unsupported grpc-encoding %q", encoding))
+			return message
+		}
+		zr, err := gzip.NewReader(bytes.NewReader(payload))
+		if err == nil {
+			payload, err = io.ReadAll(zr)
+		}
+		if err != nil {
+			message.DecodeError = strPtr(fmt.Sprintf("Failed to decompress message: %v", err))
+			return message
+		}
+	}
+
+	if method == nil {
+		message.Fields = grpc.DecodeRaw(payload)
+		return message
+	}
+	decoded, err := grpc.Unmarshal(method.Output, payload)
+	if err != nil {
+		message.DecodeError = strPtr(err.Error())
+		message.Fields = grpc.DecodeRaw(payload)
+		return message
+	}
+	message.Message = decoded
+	return message
+}
+
+func grpcError(message, code string) GRPCResponse {
+	return GRPCResponse{Messages: []GRPCMessage{}, Error: &ErrorData{Message: message, Code: code}}
+}
@@ -0,0 +1,216 @@
+package proxy
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"net"
+	"strings"
+	"syscall"
+	"time"
+)
+
+// connectionAttemptDelay is how long an attempt runs alone before the next
+// address is tried alongside it (RFC 8305).
+const connectionAttemptDelay = 250 * time.Millisecond
+
+// parsePreferIP normalizes the preferIp field of a request.
+func parsePreferIP(value *string) (string, error) {
+	if value == nil {
+		return "auto", nil
+	}
+	switch prefer := strings.ToLower(strings.TrimSpace(*value)); prefer {
+	case "", "auto":
+		return "auto", nil
+	case "v4", "v6":
+		return prefer, nil
+	}
+	return "", fmt.Errorf("unknown preferIp %q: use v4, v6 or auto", *value)
+}
+
+// sortAddresses orders addresses for connection racing, alternating between
+// families and starting with the preferred one. Auto starts with IPv6.
+func sortAddresses(ips []net.IP, prefer string) []net.IP {
+	var v4, v6 []net.IP
+	for _, ip := range ips {
+		if ip.To4() != nil {
+			v4 = append(v4, ip)
+		} else {
+			v6 = append(v6, ip)
+		}
+	}
+	first, second := v6, v4
+	if prefer == "v4" {
+		first, second = v4, v6
+	}
+
+	sorted := make([]net.IP, 0, len(ips))
+	for i := 0; i < len(first) || i < len(second); i++ {
+		if i < len(first) {
+			sorted = append(sorted, first[i])
+		}
+		if i < len(second) {
+			sorted = append(sorted, second[i])
+		}
+	}
+	return sorted
+}
+
+func ipFamily(ip net.IP) string {
+	if ip.To4() != nil {
+		return "v4"
+	}
+	return "v6"
+}
+
+// connect dials addr, racing the addresses of its host as Happy Eyeballs
+// (RFC 8305) does, and records every attempt.
+func (d *hopDialer) connect(ctx context.Context, dialer *net.Dialer, network, addr string) (net.Conn, error) {
+	host, port, err := net.SplitHostPort(addr)
+	if err != nil {
+		return dialer.DialContext(ctx, network, addr)
+	}
+	ips, err := d.candidates(ctx, host)
+	if err != nil {
+		return nil, err
+	}
+	return d.race(ctx, dialer, network, sortAddresses(ips, d.preferIP), port)
+}
+
+// candidates returns the addresses to try for host: the answer already
+// resolved by the executor when there is one, otherwise a fresh lookup.
+func (d *hopDialer) candidates(ctx context.Context, host string) ([]net.IP, error) {
+	if ip := net.ParseIP(host); ip != nil {
+		return []net.IP{ip}, nil
+	}
+	if ips := d.addresses[host]; len(ips) > 0 {
+		return ips, nil
+	}
+	return net.DefaultResolver.LookupIP(ctx, "ip", host)
+}
+
+// race starts an attempt per address, each connectionAttemptDelay after the
+// previous one or as soon as it fails, and keeps the first that connects.
+func (d *hopDialer) race(ctx context.Context, dialer *net.Dialer, network string, ips []net.IP, port string) (net.Conn, error) {
+	if len(ips) == 0 {
+		return nil, fmt.Errorf("no addresses to connect to")
+	}
+	ctx, cancel := context.WithCancel(ctx)
+	defer cancel()
+
+	type result struct {
+		index    int
+		conn     net.Conn
+		err      error
+		duration time.Duration
+	}
+	results := make(chan result, len(ips))
+	attempts := make([]ConnectionAttempt, 0, len(ips))
+	started := time.Now()
+	launch := func() {
+		index, ip := len(attempts), ips[len(attempts)]
+		attempts = append(attempts, ConnectionAttempt{
+			Address: ip.String(),
+			Family:  ipFamily(ip),
+			Start:   uint64(time.Since(started).Milliseconds()),
+		})
+		go func() {
+			attemptStart := time.Now()
+			conn, err := dialer.DialContext(ctx, network, net.JoinHostPort(ip.String(), port))
+			results <- result{index, conn, err, time.Since(attemptStart)}
+		}()
+	}
+
+	launch()
+	pending := 1
+	timer := time.NewTimer(connectionAttemptDelay)
+	defer timer.Stop()
+
+	var winner net.Conn
+	var lastErr error
+	for pending > 0 {
+		select {
+		case r := <-results:
+			pending--
+			attempt := &attempts[r.index]
+			attempt.Duration = uint64(r.duration.Milliseconds())
+			switch {
+			case r.err == nil && winner == nil:
+				winner = r.conn
+				attempt.Outcome = "connected"
+				cancel()
+				continue
+			case r.err == nil:
+				// Lost the race by a hair
+				r.conn.Close()
+				attempt.Outcome = "canceled"
+				continue
+			case winner != nil:
+				attempt.Outcome = "canceled"
+				continue
+			}
+			attempt.Outcome = attemptOutcome(r.err)
+			attempt.Error = strPtr(r.err.Error())
+			lastErr = r.err
+			// A failure starts the next attempt without waiting for the delay
+			if len(attempts) < len(ips) {
+				launch()
+				pending++
+				timer.Reset(connectionAttemptDelay)
+			}
+		case <-timer.C:
+			if winner == nil && len(attempts) < len(ips) {
+				launch()
+				pending++
+				timer.Reset(connectionAttemptDelay)
+			}
+		}
+	}
+
+	d.mu.Lock()
+	d.attempts = append(d.attempts, attempts...)
+	d.mu.Unlock()
+	if winner == nil {
+		return nil, lastErr
+	}
+	return winner, nil
+}
+
+// attemptOutcome classifies why an attempt failed, so a refused address can
+// be told apart from one that is unreachable or never answered.
+func attemptOutcome(err error) string {
+	var netErr net.Error
+	switch {
+	case errors.Is(err, syscall.ECONNREFUSED):
+		return "refused"
+	case errors.Is(err, syscall.EHOSTUNREACH), errors.Is(err, syscall.ENETUNREACH):
+		return "unreachable"
+	case errors.As(err, &netErr) && netErr.Timeout():
+		return "timeout"
+	}
+	return "failed"
+}
+
+// connectionAttempts returns the attempts recorded by the dialer, or nil
+// when it never dialed, as when a connection was reused.
+func (d *hopDialer) connectionAttempts() []ConnectionAttempt {
+	d.mu.Lock()
+	defer d.mu.Unlock()
+	if len(d.attempts) == 0 {
+		return nil
+	}
+	return append([]ConnectionAttempt(nil), d.attempts...)
+}
+
+// attemptSummary lists the addresses tried for an error message when there
+// was more than one.
+func attemptSummary(attempts []ConnectionAttempt) string {
+	if len(attempts) < 2 {
+		return ""
+	}
+	tried := make([]string, len(attempts))
+	for i, attempt := range attempts {
+		tried[i] = attempt.Address + " " + attempt.Outcome
+	}
+	return " (tried " + strings.Join(tried, ", ") + ")"
+}
@@ -0,0 +1,139 @@
+package proxy
+
+import (
+	"bytes"
+	"net/http"
+	"sort"
+	"strconv"
+	"strings"
+	"sync"
+)
+
+// maxResponseHeadBytes bounds the recorded response head.
+const maxResponseHeadBytes = 64 << 10
+
+var headTerminator = []byte("\r\n\r\n")
+
+// headRecorder keeps the head of the latest final (non-1xx) response read
+// from a connection. Writing a request restarts the recording.
+type headRecorder struct {
+	mu   sync.Mutex
+	buf  bytes.Buffer
+	head []byte
+	done bool
+}
+
+func (h *headRecorder) reset() {
+	h.mu.Lock()
+	defer h.mu.Unlock()
+	h.buf.Reset()
+	h.head = nil
+	h.done = false
+}
+
+func (h *headRecorder) record(p []byte) {
+	h.mu.Lock()
+	defer h.mu.Unlock()
+
+	if h.done || len(p) == 0 {
+		return
+	}
+	if room := maxResponseHeadBytes - h.buf.Len(); len(p) > room {
+		p = p[:room]
+		h.done = true
+	}
+	h.buf.Write(p)
+
+	for {
+		data := h.buf.Bytes()
+		end := bytes.Index(data, headTerminator)
+		if end < 0 {
+			return
+		}
+		head := data[:end+len(headTerminator)]
+
+		// Skip interim responses such as 100 Continue
+		if bytes.HasPrefix(head, []byte("HTTP/1.")) && len(head) > 9 && head[9] == '1' {
+			h.buf.Next(len(head))
+			continue
+		}
+
+		h.head = append([]byte(nil), head...)
+		h.done = true
+		return
+	}
+}
+
+// responseHead returns the head recorded for resp, or nil when it was not
+// recorded (HTTP/2, tunneled TLS) or belongs to another response.
+func (h *headRecorder) responseHead(resp *http.Response) []byte {
+	if h == nil {
+		return nil
+	}
+	h.mu.Lock()
+	defer h.mu.Unlock()
+
+	if h.head == nil {
+		return nil
+	}
+	statusLine, _, _ := bytes.Cut(h.head, []byte("\r\n"))
+	fields := strings.Fields(string(statusLine))
+	if len(fields) < 2 || fields[1] != strconv.Itoa(resp.StatusCode) {
+		return nil
+	}
+	return h.head
+}
+
+// parseHeaderFields returns the header fields of a raw response head in wire
+// order with their original casing.
+func parseHeaderFields(head []byte) []HeaderField {
+	lines := strings.Split(strings.TrimRight(string(head), "\r\n"), "\r\n")
+
+	var fields []HeaderField
+	for _, line := range lines[1:] {
+		// Obsolete line folding continues the previous value
+		if (strings.HasPrefix(line, " ") || strings.HasPrefix(line, "\t")) && len(fields) > 0 {
+			fields[len(fields)-1].Value += " " + strings.TrimSpace(line)
+			continue
+		}
+		name, value, ok := strings.Cut(line, ":")
+		if !ok {
+			continue
+		}
+		fields = append(fields, HeaderField{Name: name, Value: strings.TrimSpace(value)})
+	}
+	return fields
+}
+
+// headerFieldsFromHeader lists every value of h when the wire order is not
+// known. Names are sorted; HTTP/2 names are lowercase as sent.
+func headerFieldsFromHeader(h http.Header, http2 bool) []HeaderField {
+	names := make([]string, 0, len(h))
+	for name := range h {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+
+	var fields []HeaderField
+	for _, name := range names {
+		wireName := name
+		if http2 {
+			wireName = strings.ToLower(name)
+		}
+		for _, value := range h[name] {
+			fields = append(fields, HeaderField{Name: wireName, Value: value})
+		}
+	}
+	return fields
+}
+
+// headerValue returns the last value of the named header field, or "".
+func headerValue(fields []HeaderField, name string) string {
+	value := ""
+	for _, h := range fields {
+		if strings.EqualFold(h.Name, name) {
+			value = h.Value
+		}
+	}
+	return value
+}
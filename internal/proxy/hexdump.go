@@ -0,0 +1,26 @@
+package proxy
+
+import "encoding/hex"
+
+const (
+	// DefaultHexdumpBytes is the number of body bytes dumped when no limit is given.
+	DefaultHexdumpBytes = 512
+	// MaxHexdumpBytes caps the requested hex dump length.
+	MaxHexdumpBytes = 64 << 10
+)
+
+// bodyHexdump renders the first bytes of a binary body as offset, hex and ASCII columns.
+func bodyHexdump(data []byte, limit *int) *string {
+	n := DefaultHexdumpBytes
+	if limit != nil {
+		n = min(max(*limit, 0), MaxHexdumpBytes)
+	}
+	if n == 0 || len(data) == 0 {
+		return nil
+	}
+	if len(data) > n {
+		data = data[:n]
+	}
+	dump := hex.Dump(data)
+	return &dump
+}
@@ -0,0 +1,69 @@
+package proxy
+
+import (
+	"log"
+	"net/url"
+	"time"
+
+	"zone.digit.tommie/internal/history"
+	"zone.digit.tommie/internal/storage"
+)
+
+// HistoryRetention returns the history limits of the service, or nil when history is disabled.
+func (s *HTTPService) HistoryRetention() *history.Retention {
+	return s.opts.History
+}
+
+// recordHistory stores a summary of an executed request in store, with its
+// response body when it is text.
+func (s *HTTPService) recordHistory(store storage.Store, request ProxyRequest, response ProxyResponse, started time.Time) {
+	if store == nil || s.opts.History == nil {
+		return
+	}
+
+	entry := history.Entry{
+		Timestamp: started,
+		Method:    request.Method,
+		URL:       request.URL,
+	}
+	if parsed, err := url.Parse(request.URL); err == nil {
+		entry.Host = parsed.Hostname()
+	}
+
+	var body string
+	if response.Success {
+		data := response.Data
+		if !data.IsBinary {
+			body = data.Body
+		}
+		entry.Status = data.Status
+		entry.ResponseSize = data.Size
+		entry.Redirects = len(data.RedirectChain)
+		if data.Redirected {
+			entry.FinalURL = data.URL
+		}
+		if data.RequestBodySize != nil {
+			entry.RequestSize = *data.RequestBodySize
+		}
+		if data.Protocol != nil {
+			entry.Protocol = *data.Protocol
+		}
+		entry.Timing = history.Timing{
+			Total:    data.Timing.Total,
+			DNS:      data.Timing.DNS,
+			TCP:      data.Timing.TCP,
+			TLS:      data.Timing.TLS,
+			TTFB:     data.Timing.TTFB,
+			Download: data.Timing.Download,
+		}
+	} else {
+		entry.ErrorCode = response.Error.Code
+		entry.Error = response.Error.Message
+		entry.Timing.Total = uint64(since(s.clock, started).Milliseconds())
+	}
+
+	// History is best effort and never fails the request
+	if err := history.Add(store, entry, body, *s.opts.History); err != nil {
+		log.Printf("Failed to record history: %v", err)
+	}
+}
@@ -0,0 +1,321 @@
+package proxy
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"sort"
+	"strconv"
+	"strings"
+
+	"zone.digit.tommie/internal/environments"
+	"zone.digit.tommie/internal/script"
+	"zone.digit.tommie/internal/storage"
+)
+
+// runPreRequestScript runs the pre-request script of a request and applies
+// the changes it returns. Only failures to load the environment are returned
+// as errors; script failures are reported in the run.
+func (s *HTTPService) runPreRequestScript(store storage.Store, request *ProxyRequest) (*ScriptRun, error) {
+	variables, err := templateVariables(store, *request)
+	if err != nil {
+		return nil, err
+	}
+	globals := map[string]interface{}{
+		"request": scriptRequest(*request),
+		"vars":    variables,
+	}
+
+	run, value := s.runHook(request.Scripts.PreRequest, globals)
+	if run.Error != nil {
+		return run, nil
+	}
+	changes, ok := value.(map[string]interface{})
+	if value != nil && !ok {
+		run.Error = &ScriptError{Message: "The pre-request script must return an object or null", Code: "SCRIPT_ERROR"}
+		return run, nil
+	}
+
+	for _, field := range sortedKeys(changes) {
+		value := changes[field]
+		switch field {
+		case "method":
+			request.Method = strings.ToUpper(scriptString(value))
+		case "url":
+			request.URL = scriptString(value)
+		case "body":
+			if value == nil {
+				request.Body = nil
+			} else {
+				request.Body = strPtr(scriptString(value))
+			}
+		case "headers":
+			headers, ok := value.(map[string]interface{})
+			if !ok {
+				run.Error = &ScriptError{Message: "headers must be an object", Code: "SCRIPT_ERROR"}
+				return run, nil
+			}
+			request.Headers = withScriptHeaders(request.Headers, headers)
+		case "vars":
+		default:
+			run.Error = &ScriptError{Message: fmt.Sprintf("Unknown field %q; expected method, url, headers, body or vars", field), Code: "SCRIPT_ERROR"}
+			return run, nil
+		}
+	}
+	if !setScriptVariables(store, request, changes["vars"], run) {
+		return run, nil
+	}
+
+	// Variables set apply to the templates of this request too
+	if len(run.Variables) > 0 {
+		inline := make(map[string]string, len(request.Variables)+len(run.Variables))
+		for name, value := range request.Variables {
+			inline[name] = value
+		}
+		for name, value := range run.Variables {
+			if value == nil {
+				delete(inline, name)
+			} else {
+				inline[name] = *value
+			}
+		}
+		request.Variables = inline
+	}
+	return run, nil
+}
+
+// runTestScript runs the test script of a request over its response.
+// template is the request before its templates were resolved.
+func (s *HTTPService) runTestScript(store storage.Store, template, sent ProxyRequest, response *ResponseData) *ScriptRun {
+	variables, err := templateVariables(store, template)
+	if err != nil {
+		return &ScriptRun{Error: &ScriptError{Message: err.Error(), Code: "STORAGE_ERROR"}}
+	}
+	globals := map[string]interface{}{
+		"request": scriptRequest(sent),
+		"vars":    variables,
+	}
+	setResponseGlobals(globals, response)
+
+	run, value := s.runHook(template.Scripts.Test, globals)
+	if run.Error != nil {
+		return run
+	}
+	result, ok := value.(map[string]interface{})
+	if value != nil && !ok {
+		run.Error = &ScriptError{Message: "The test script must return an object or null", Code: "SCRIPT_ERROR"}
+		return run
+	}
+	for field := range result {
+		if field != "tests" && field != "vars" {
+			run.Error = &ScriptError{Message: fmt.Sprintf("Unknown field %q; expected tests or vars", field), Code: "SCRIPT_ERROR"}
+			return run
+		}
+	}
+
+	assertions, err := scriptAssertions(result["tests"])
+	if err != nil {
+		run.Error = &ScriptError{Message: err.Error(), Code: "SCRIPT_ERROR"}
+		return run
+	}
+	run.Assertions = assertions
+	for _, assertion := range assertions {
+		if assertion.Passed {
+			run.Passed++
+		} else {
+			run.Failed++
+		}
+	}
+	setScriptVariables(store, &template, result["vars"], run)
+	return run
+}
+
+// runHook evaluates a script, reporting its cost in the returned run.
+func (s *HTTPService) runHook(src string, globals map[string]interface{}) (*ScriptRun, interface{}) {
+	started := s.clock.Now()
+	result, err := script.Eval(context.Background(), src, globals, script.DefaultLimits)
+	run := &ScriptRun{Duration: uint64(since(s.clock, started).Milliseconds())}
+	if err != nil {
+		run.Error = &ScriptError{Message: err.Error(), Code: "SCRIPT_ERROR"}
+		var scriptErr *script.Error
+		if errors.As(err, &scriptErr) {
+			run.Error.Message = scriptErr.Message
+			if scriptErr.Pos != nil {
+				run.Error.Line = &scriptErr.Pos.Line
+				run.Error.Column = &scriptErr.Pos.Column
+			}
+		}
+		return run, nil
+	}
+	return run, result.Value
+}
+
+// setScriptVariables records the variables a script set in its run and saves
+// them to the request's environment. It reports whether that succeeded.
+func setScriptVariables(store storage.Store, request *ProxyRequest, value interface{}, run *ScriptRun) bool {
+	if value == nil {
+		return true
+	}
+	vars, ok := value.(map[string]interface{})
+	if !ok {
+		run.Error = &ScriptError{Message: "vars must be an object", Code: "SCRIPT_ERROR"}
+		return false
+	}
+	if len(vars) == 0 {
+		return true
+	}
+	run.Variables = make(map[string]*string, len(vars))
+	for name, v := range vars {
+		if v == nil {
+			run.Variables[name] = nil
+		} else {
+			run.Variables[name] = strPtr(scriptString(v))
+		}
+	}
+
+	if err := saveVariables(store, request.Environment, run.Variables); err != nil {
+		run.Error = &ScriptError{Message: "Failed to save variables: " + err.Error(), Code: "STORAGE_ERROR"}
+		return false
+	}
+	return true
+}
+
+// saveVariables sets variables in an environment, removing those that are
+// nil. Without an environment or store there is nothing to save to.
+func saveVariables(store storage.Store, environment *string, variables map[string]*string) error {
+	if environment == nil || store == nil {
+		return nil
+	}
+	env, err := environments.Load(store, *environment)
+	if err != nil {
+		return err
+	}
+	if env == nil {
+		return errEnvironmentNotFound(*environment)
+	}
+	for name, value := range variables {
+		if value == nil {
+			delete(env.Variables, name)
+		} else {
+			env.Variables[name] = *value
+		}
+	}
+	_, err = environments.Save(store, env.Name, env.Variables)
+	return err
+}
+
+// scriptRequest exposes a request to a script.
+func scriptRequest(request ProxyRequest) map[string]interface{} {
+	headers := make(map[string]interface{}, len(request.Headers))
+	for name, value := range request.Headers {
+		headers[name] = value
+	}
+	var body interface{}
+	if request.Body != nil {
+		body = *request.Body
+	}
+	return map[string]interface{}{
+		"method":  request.Method,
+		"url":     request.URL,
+		"headers": headers,
+		"body":    body,
+	}
+}
+
+// withScriptHeaders returns a copy of headers with those a script set. Names
+// match case-insensitively and null values remove a header.
+func withScriptHeaders(headers map[string]string, changes map[string]interface{}) map[string]string {
+	result := make(map[string]string, len(headers)+len(changes))
+	for name, value := range headers {
+		result[name] = value
+	}
+	for _, name := range sortedKeys(changes) {
+		for existing := range result {
+			if strings.EqualFold(existing, name) {
+				delete(result, existing)
+			}
+		}
+		if value := changes[name]; value != nil {
+			result[name] = scriptString(value)
+		}
+	}
+	return result
+}
+
+// scriptAssertions reads the tests a test script returned: an object mapping
+// names to booleans or {pass, message}, or an array of {name, pass, message}.
+func scriptAssertions(value interface{}) ([]ScriptAssertion, error) {
+	var assertions []ScriptAssertion
+	switch tests := value.(type) {
+	case nil:
+	case map[string]interface{}:
+		for _, name := range sortedKeys(tests) {
+			assertion, err := scriptAssertion(name, tests[name])
+			if err != nil {
+				return nil, err
+			}
+			assertions = append(assertions, assertion)
+		}
+	case []interface{}:
+		for i, test := range tests {
+			fields, _ := test.(map[string]interface{})
+			name, ok := fields["name"].(string)
+			if !ok {
+				return nil, fmt.Errorf("tests[%d] must be an object with a name", i)
+			}
+			assertion, err := scriptAssertion(name, test)
+			if err != nil {
+				return nil, err
+			}
+			assertions = append(assertions, assertion)
+		}
+	default:
+		return nil, errors.New("tests must be an object or an array")
+	}
+	return assertions, nil
+}
+
+func scriptAssertion(name string, value interface{}) (ScriptAssertion, error) {
+	switch v := value.(type) {
+	case bool:
+		return ScriptAssertion{Name: name, Passed: v}, nil
+	case map[string]interface{}:
+		pass, ok := v["pass"].(bool)
+		if !ok {
+			return ScriptAssertion{}, fmt.Errorf("test %q must have a boolean pass", name)
+		}
+		assertion := ScriptAssertion{Name: name, Passed: pass}
+		if message, ok := v["message"]; ok && message != nil {
+			assertion.Message = strPtr(scriptString(message))
+		}
+		return assertion, nil
+	}
+	return ScriptAssertion{}, fmt.Errorf("test %q must be a boolean or {pass, message}", name)
+}
+
+// scriptString converts a script value to the string form of a header,
+// body or variable. Objects and arrays become JSON.
+func scriptString(value interface{}) string {
+	switch v := value.(type) {
+	case nil:
+		return ""
+	case string:
+		return v
+	case float64:
+		return strconv.FormatFloat(v, 'f', -1, 64)
+	case bool:
+		return strconv.FormatBool(v)
+	}
+	data, _ := json.Marshal(value)
+	return string(data)
+}
+
+func sortedKeys(m map[string]interface{}) []string {
+	keys := make([]string, 0, len(m))
+	for key := range m {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys)
+	return keys
+}
@@ -0,0 +1,77 @@
+package proxy
+
+import (
+	"context"
+	"crypto/tls"
+	"fmt"
+	"net"
+
+	"github.com/quic-go/quic-go"
+	"github.com/quic-go/quic-go/http3"
+)
+
+// newHTTP3Transport builds an HTTP/3 transport whose QUIC connections are
+// opened by dialer. Certificates are verified as over TCP.
+func newHTTP3Transport(dialer *hopDialer) *http3.Transport {
+	return &http3.Transport{
+		TLSClientConfig: dialer.tlsConfig(),
+		QUICConfig:      &quic.Config{HandshakeIdleTimeout: dialer.timeout},
+		Dial:            dialer.dialQUIC,
+	}
+}
+
+// dialQUIC opens a QUIC connection and waits for its handshake, which
+// carries the TLS handshake, recording both as the QUIC phase. Addresses are
+// tried in order of preference until one connects.
+func (d *hopDialer) dialQUIC(ctx context.Context, addr string, tlsConf *tls.Config, conf *quic.Config) (quic.EarlyConnection, error) {
+	host, port, err := net.SplitHostPort(pinnedAddr(d.pins, addr))
+	if err != nil {
+		return nil, err
+	}
+	ips, err := d.candidates(ctx, host)
+	if err != nil {
+		return nil, err
+	}
+	if len(ips) == 0 {
+		return nil, fmt.Errorf("no addresses to connect to")
+	}
+
+	for _, ip := range sortAddresses(ips, d.preferIP) {
+		if err = d.policy.checkIP(ip); err != nil {
+			return nil, err
+		}
+		var conn quic.EarlyConnection
+		if conn, err = d.handshakeQUIC(ctx, net.JoinHostPort(ip.String(), port), tlsConf, conf); err == nil {
+			d.remoteIP = ip.String()
+			return conn, nil
+		}
+		// A rejected certificate is rejected on every address
+		if d.tlsInfo != nil && !d.tlsInfo.Validation.Valid() {
+			return nil, &CertificateError{Info: d.tlsInfo}
+		}
+		if ctx.Err() != nil {
+			break
+		}
+	}
+	return nil, err
+}
+
+// handshakeQUIC dials addr and waits until the handshake completes.
+func (d *hopDialer) handshakeQUIC(ctx context.Context, addr string, tlsConf *tls.Config, conf *quic.Config) (quic.EarlyConnection, error) {
+	d.timing.StartQUIC()
+	conn, err := quic.DialAddrEarly(ctx, addr, tlsConf, conf)
+	if err != nil {
+		return nil, err
+	}
+	// An early connection may return before the handshake has finished
+	select {
+	case <-conn.HandshakeComplete():
+	case <-conn.Context().Done():
+		return nil, context.Cause(conn.Context())
+	case <-ctx.Done():
+		conn.CloseWithError(0, "")
+		return nil, ctx.Err()
+	}
+	d.timing.EndQUIC()
+	return conn, nil
+}
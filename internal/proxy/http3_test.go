@@ -0,0 +1,49 @@
+package proxy_test
+
+import (
+	"testing"
+
+	"zone.digit.tommie/internal/proxy"
+	"zone.digit.tommie/internal/testkit"
+)
+
+func TestHTTP3(t *testing.T) {
+	target := testkit.StartHTTP3()
+	defer target.Close()
+
+	data := success(t, target.Execute(proxy.ProxyRequest{URL: "/ok"}))
+	if !data.HTTP3Offered {
+		t.Errorf("got Alt-Svc %+v, want HTTP/3 offered", data.AltSvc)
+	}
+
+	version := "3"
+	data = success(t, target.Execute(proxy.ProxyRequest{URL: "/ok", HTTPVersion: &version}))
+	if data.Protocol == nil || *data.Protocol != "HTTP/3.0" {
+		t.Errorf("got protocol %v, want HTTP/3.0", data.Protocol)
+	}
+	if data.Body != "ok" {
+		t.Errorf("got body %q, want \"ok\"", data.Body)
+	}
+	if data.Timing.QUIC == nil || data.Timing.TCP != nil || data.Timing.TLS != nil {
+		t.Errorf("got timing %+v, want a QUIC handshake instead of TCP and TLS", data.Timing)
+	}
+	if data.TLS == nil || data.TLS.Validation == nil || !data.TLS.Validation.ChainVerified {
+		t.Errorf("got TLS %+v, want a verified chain", data.TLS)
+	}
+}
+
+func TestHTTP3Errors(t *testing.T) {
+	target := testkit.StartHTTP3()
+	defer target.Close()
+	version := "3"
+
+	// The system roots do not trust the target
+	response := proxy.NewHTTPService(proxy.Options{}).Execute(target.Request(proxy.ProxyRequest{URL: "/ok", HTTPVersion: &version}))
+	if failed := failure(t, response, "CERT_INVALID"); failed.TLS == nil {
+		t.Errorf("got no certificate details with %q", failed.Message)
+	}
+
+	plain := testkit.Start()
+	defer plain.Close()
+	failure(t, plain.Execute(proxy.ProxyRequest{URL: "/ok", HTTPVersion: &version}), "UNSUPPORTED_PROTOCOL")
+}
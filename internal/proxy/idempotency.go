@@ -0,0 +1,131 @@
+package proxy
+
+import (
+	"crypto/rand"
+	"encoding/json"
+	"fmt"
+	"strconv"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+const (
+	// DefaultIdempotencyHeader carries the key when no header name is given.
+	DefaultIdempotencyHeader = "Idempotency-Key"
+	// MaxIdempotencyDelayMS caps the pause between the two requests.
+	MaxIdempotencyDelayMS = 60_000
+)
+
+// idFields are the JSON fields compared to tell whether both responses
+// describe the same created resource.
+var idFields = []string{"id", "uuid", "data.id", "result.id", "object.id"}
+
+// replayHeaders are sent by servers that answered from their idempotency cache.
+var replayHeaders = wordSet("idempotent-replayed idempotency-replayed x-idempotent-replayed x-idempotency-replayed")
+
+// ExecuteIdempotencyCheck sends a request twice with the same idempotency key
+// and reports whether the server deduplicated the second one.
+func (s *HTTPService) ExecuteIdempotencyCheck(store storage.Store, check IdempotencyRequest) IdempotencyResponse {
+	header := DefaultIdempotencyHeader
+	if check.HeaderName != nil && *check.HeaderName != "" {
+		header = *check.HeaderName
+	}
+	key := ""
+	if check.Key != nil {
+		key = *check.Key
+	}
+	if key == "" {
+		key = newIdempotencyKey()
+	}
+
+	request := check.Request
+	headers := make(map[string]string, len(request.Headers)+1)
+	for name, value := range request.Headers {
+		if !strings.EqualFold(name, header) {
+			headers[name] = value
+		}
+	}
+	headers[header] = key
+	request.Headers = headers
+
+	result := IdempotencyResponse{Key: key, HeaderName: header}
+	result.First = s.ExecuteIn(store, request)
+	if check.DelayMS != nil && *check.DelayMS > 0 {
+		time.Sleep(time.Duration(min(*check.DelayMS, MaxIdempotencyDelayMS)) * time.Millisecond)
+	}
+	result.Second = s.ExecuteIn(store, request)
+
+	result.compare()
+	return result
+}
+
+// compare fills in the comparison of both responses and the verdict.
+func (r *IdempotencyResponse) compare() {
+	if !r.First.Success || !r.Second.Success {
+		r.Verdict = "error"
+		return
+	}
+	first, second := r.First.Data, r.Second.Data
+
+	r.SameStatus = first.Status == second.Status
+	r.SameBody = first.Body == second.Body && equalOptional(first.BodyBase64, second.BodyBase64)
+	r.FirstID = resourceID(first.Body)
+	r.SecondID = resourceID(second.Body)
+	for _, h := range second.Headers {
+		if replayHeaders[strings.ToLower(h.Name)] && strings.EqualFold(strings.TrimSpace(h.Value), "true") {
+			r.Replayed = true
+		}
+	}
+
+	switch {
+	case r.FirstID != nil && r.SecondID != nil && *r.FirstID != *r.SecondID:
+		// Two resources were created
+		r.Verdict = "duplicated"
+	case r.Replayed || (r.FirstID != nil && r.SecondID != nil) || (r.SameStatus && r.SameBody):
+		r.Verdict = "deduplicated"
+	case first.Status < 300 && (second.Status == 409 || second.Status == 422):
+		// Rejecting a retry is safe, though clients expect the original response
+		r.Verdict = "rejected"
+	default:
+		r.Verdict = "inconclusive"
+	}
+	r.Deduplicated = r.Verdict == "deduplicated" || r.Verdict == "rejected"
+}
+
+// resourceID returns the first identifier field of a JSON body.
+func resourceID(body string) *string {
+	var value interface{}
+	if json.Unmarshal([]byte(body), &value) != nil {
+		return nil
+	}
+	for _, field := range idFields {
+		switch id := jsonField(value, field).(type) {
+		case string:
+			if id != "" {
+				return &id
+			}
+		case float64:
+			s := strconv.FormatFloat(id, 'f', -1, 64)
+			return &s
+		}
+	}
+	return nil
+}
+
+func equalOptional(a, b *string) bool {
+	if a == nil || b == nil {
+		return a == b
+	}
+	return *a == *b
+}
+
+// newIdempotencyKey returns a random version 4 UUID.
+func newIdempotencyKey() string {
+	b := make([]byte, 16)
+	rand.Read(b)
+	b[6] = b[6]&0x0f | 0x40
+	b[8] = b[8]&0x3f | 0x80
+	return fmt.Sprintf("%x-%x-%x-%x-%x", b[0:4], b[4:6], b[6:8], b[8:10], b[10:])
+}
@@ -0,0 +1,142 @@
+package proxy
+
+import (
+	"context"
+	"encoding/base64"
+	"errors"
+	"io"
+	"net/http"
+	"net/url"
+	"strings"
+)
+
+// maxAuthLegs bounds the round trips of an integrated authentication handshake.
+const maxAuthLegs = 3
+
+// errIntegratedAuthUnsupported is returned where no platform security provider is available.
+var errIntegratedAuthUnsupported = errors.New("integrated Negotiate/NTLM authentication requires Windows SSPI")
+
+// negotiator produces the tokens of a Negotiate or NTLM handshake using the
+// credentials of the current user.
+type negotiator interface {
+	// step consumes the server challenge (nil on the first leg) and returns the next token.
+	step(challenge []byte) ([]byte, error)
+	close()
+}
+
+// integratedSchemes lists supported schemes in order of preference.
+var integratedSchemes = []string{"Negotiate", "NTLM"}
+
+// preferredScheme returns the most preferred integrated scheme offered by a challenge.
+func preferredScheme(challenges []string) string {
+	for _, scheme := range integratedSchemes {
+		for _, c := range challenges {
+			fields := strings.Fields(c)
+			if len(fields) > 0 && strings.EqualFold(fields[0], scheme) {
+				return scheme
+			}
+		}
+	}
+	return ""
+}
+
+// challengeToken extracts the base64 token following scheme, or nil if there is none.
+func challengeToken(challenges []string, scheme string) []byte {
+	for _, c := range challenges {
+		fields := strings.Fields(c)
+		if len(fields) != 2 || !strings.EqualFold(fields[0], scheme) {
+			continue
+		}
+		token, err := base64.StdEncoding.DecodeString(fields[1])
+		if err == nil {
+			return token
+		}
+	}
+	return nil
+}
+
+// integratedAuth answers a 401 or 407 Negotiate/NTLM challenge with the
+// tokens of negotiators made by newNeg: the current user's credentials via
+// newNegotiator, or explicit ones via newNTLMNegotiator. The handshake is
+// connection-oriented, so every leg drains the previous response to keep the
+// connection alive; onChallenge sees each challenged response first. When no
+// challenge applies, resp is returned unchanged with a nil report.
+func integratedAuth(client *http.Client, resp *http.Response, newRequest func() (*http.Request, error), host string, proxyURL *url.URL, newNeg func(scheme, host string) (negotiator, error), onChallenge func(challenged *http.Response, scheme string)) (*http.Response, *IntegratedAuthInfo, error) {
+	challengeHeader, authHeader, target, spnHost := "WWW-Authenticate", "Authorization", "server", host
+	switch {
+	case resp.StatusCode == http.StatusProxyAuthRequired && proxyURL != nil:
+		challengeHeader, authHeader, target, spnHost = "Proxy-Authenticate", "Proxy-Authorization", "proxy", proxyURL.Hostname()
+	case resp.StatusCode != http.StatusUnauthorized:
+		return resp, nil, nil
+	}
+
+	scheme := preferredScheme(resp.Header.Values(challengeHeader))
+	if scheme == "" {
+		return resp, nil, nil
+	}
+	info := &IntegratedAuthInfo{Scheme: scheme, Target: target}
+
+	neg, err := newNeg(scheme, spnHost)
+	if err != nil {
+		info.Error = strPtr(err.Error())
+		return resp, info, nil
+	}
+	defer neg.close()
+
+	status := resp.StatusCode
+	var challenge []byte
+	for info.Legs < maxAuthLegs {
+		token, err := neg.step(challenge)
+		if err != nil {
+			info.Error = strPtr(err.Error())
+			return resp, info, nil
+		}
+
+		onChallenge(resp, scheme)
+
+		// Drain so the next leg reuses the connection
+		io.Copy(io.Discard, resp.Body)
+		resp.Body.Close()
+
+		req, err := newRequest()
+		if err != nil {
+			return nil, info, err
+		}
+		req.Header.Set(authHeader, scheme+" "+base64.StdEncoding.EncodeToString(token))
+
+		resp, err = client.Do(req)
+		if err != nil {
+			return nil, info, err
+		}
+		info.Legs++
+
+		if resp.StatusCode != status {
+			info.Authenticated = true
+			return resp, info, nil
+		}
+		challenge = challengeToken(resp.Header.Values(challengeHeader), scheme)
+		if challenge == nil {
+			// Credentials were rejected
+			return resp, info, nil
+		}
+	}
+	return resp, info, nil
+}
+
+// negotiateProxyConnectHeader authenticates CONNECT tunnels with a single-leg
+// Negotiate (Kerberos) token. NTLM needs several legs on the tunnel
+// connection, which net/http does not expose, so tunnels fall back to
+// unauthenticated CONNECT when no token can be produced.
+func negotiateProxyConnectHeader(ctx context.Context, proxyURL *url.URL, target string) (http.Header, error) {
+	neg, err := newNegotiator("Negotiate", proxyURL.Hostname())
+	if err != nil {
+		return nil, nil
+	}
+	defer neg.close()
+
+	token, err := neg.step(nil)
+	if err != nil {
+		return nil, nil
+	}
+	return http.Header{"Proxy-Authorization": {"Negotiate " + base64.StdEncoding.EncodeToString(token)}}, nil
+}
@@ -0,0 +1,128 @@
+package proxy
+
+import (
+	"bytes"
+	"crypto/md5"
+	"crypto/sha1"
+	"crypto/sha256"
+	"crypto/sha512"
+	"encoding/base64"
+	"encoding/hex"
+	"hash"
+	"strings"
+)
+
+// digestAlgorithms maps the algorithm names of Digest, Content-Digest and
+// Subresource Integrity to their hash functions.
+var digestAlgorithms = map[string]func() hash.Hash{
+	"md5":     md5.New,
+	"sha":     sha1.New,
+	"sha1":    sha1.New,
+	"sha-1":   sha1.New,
+	"sha256":  sha256.New,
+	"sha-256": sha256.New,
+	"sha384":  sha512.New384,
+	"sha-384": sha512.New384,
+	"sha512":  sha512.New,
+	"sha-512": sha512.New,
+}
+
+// sriStrength orders the Subresource Integrity algorithms; only the
+// strongest one given is checked (W3C SRI, section 3.3.4).
+var sriStrength = map[string]int{"sha256": 1, "sha384": 2, "sha512": 3}
+
+// bodyIntegrity hashes a body as received and after decoding, and checks
+// the digests of the response headers and an expected SRI value. Header
+// digests cover the body as received, SRI the decoded body.
+func bodyIntegrity(encoded, decoded []byte, headers map[string]string, expected *string) *BodyIntegrity {
+	integrity := &BodyIntegrity{Decoded: bodyHashes(decoded)}
+	if !bytes.Equal(encoded, decoded) {
+		hashes := bodyHashes(encoded)
+		integrity.Encoded = &hashes
+	}
+
+	match := true
+	if value := headers["content-md5"]; value != "" {
+		check := checkDigest("Content-MD5", "md5", value, encoded)
+		match = match && check.Match
+		integrity.Checks = append(integrity.Checks, check)
+	}
+	for _, header := range []string{"Digest", "Content-Digest", "Repr-Digest"} {
+		for _, part := range strings.Split(headers[strings.ToLower(header)], ",") {
+			algorithm, value, ok := strings.Cut(strings.TrimSpace(part), "=")
+			algorithm = strings.ToLower(strings.TrimSpace(algorithm))
+			if !ok || digestAlgorithms[algorithm] == nil {
+				continue
+			}
+			// Structured fields wrap byte sequences in colons
+			check := checkDigest(header, algorithm, strings.Trim(strings.TrimSpace(value), ":"), encoded)
+			match = match && check.Match
+			integrity.Checks = append(integrity.Checks, check)
+		}
+	}
+
+	if expected != nil {
+		var checks []IntegrityCheck
+		strongest := 0
+		for _, token := range strings.Fields(*expected) {
+			// Options such as ?foo follow the value and are ignored
+			token, _, _ = strings.Cut(token, "?")
+			algorithm, value, ok := strings.Cut(token, "-")
+			algorithm = strings.ToLower(algorithm)
+			if !ok || sriStrength[algorithm] == 0 {
+				continue
+			}
+			checks = append(checks, checkDigest("integrity", algorithm, value, decoded))
+			strongest = max(strongest, sriStrength[algorithm])
+		}
+		// The body matches when any value of the strongest algorithm does
+		sriMatch := false
+		for _, check := range checks {
+			if sriStrength[check.Algorithm] == strongest {
+				sriMatch = sriMatch || check.Match
+			}
+		}
+		match = match && (sriMatch || len(checks) == 0)
+		integrity.Checks = append(integrity.Checks, checks...)
+	}
+
+	if len(integrity.Checks) > 0 {
+		integrity.Match = &match
+	}
+	return integrity
+}
+
+func bodyHashes(data []byte) BodyHashes {
+	md5Sum := md5.Sum(data)
+	sha1Sum := sha1.Sum(data)
+	sha256Sum := sha256.Sum256(data)
+	return BodyHashes{
+		MD5:    hex.EncodeToString(md5Sum[:]),
+		SHA1:   hex.EncodeToString(sha1Sum[:]),
+		SHA256: hex.EncodeToString(sha256Sum[:]),
+	}
+}
+
+// checkDigest compares an expected digest, in base64 or hex, with the digest
+// of data.
+func checkDigest(source, algorithm, expected string, data []byte) IntegrityCheck {
+	h := digestAlgorithms[algorithm]()
+	h.Write(data)
+	actual := h.Sum(nil)
+
+	want, err := base64.StdEncoding.DecodeString(expected)
+	if err != nil || len(want) != len(actual) {
+		if decoded, urlErr := base64.RawURLEncoding.DecodeString(strings.TrimRight(expected, "=")); urlErr == nil && len(decoded) == len(actual) {
+			want = decoded
+		} else if decoded, hexErr := hex.DecodeString(expected); hexErr == nil {
+			want = decoded
+		}
+	}
+	return IntegrityCheck{
+		Source:    source,
+		Algorithm: algorithm,
+		Expected:  expected,
+		Actual:    base64.StdEncoding.EncodeToString(actual),
+		Match:     bytes.Equal(want, actual),
+	}
+}
@@ -0,0 +1,89 @@
+package proxy
+
+import (
+	"maps"
+	"sync"
+)
+
+// Interceptor observes and rewrites the requests and responses of a service,
+// for embedders that inject default headers, redact secrets, record metrics
+// or rewrite URLs. Interceptors run in registration order, on every request
+// including those of batches, data runs and other follow-up requests.
+type Interceptor interface {
+	// OnRequest runs after templates are resolved and before the request is
+	// validated and sent. Its headers may be modified freely.
+	OnRequest(request *ProxyRequest)
+	// OnResponse runs on successful responses before they are recorded to
+	// history or captured as fixtures.
+	OnResponse(request ProxyRequest, response *ResponseData)
+}
+
+// InterceptorFuncs adapts functions to an Interceptor. Either may be nil.
+type InterceptorFuncs struct {
+	Request  func(request *ProxyRequest)
+	Response func(request ProxyRequest, response *ResponseData)
+}
+
+// OnRequest calls Request.
+func (f InterceptorFuncs) OnRequest(request *ProxyRequest) {
+	if f.Request != nil {
+		f.Request(request)
+	}
+}
+
+// OnResponse calls Response.
+func (f InterceptorFuncs) OnResponse(request ProxyRequest, response *ResponseData) {
+	if f.Response != nil {
+		f.Response(request, response)
+	}
+}
+
+// interceptorChain holds the interceptors of a service. It is safe for
+// concurrent use.
+type interceptorChain struct {
+	mu   sync.RWMutex
+	list []Interceptor
+}
+
+func (c *interceptorChain) add(interceptors ...Interceptor) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	c.list = append(c.list, interceptors...)
+}
+
+func (c *interceptorChain) snapshot() []Interceptor {
+	c.mu.RLock()
+	defer c.mu.RUnlock()
+	return c.list
+}
+
+// Use registers interceptors after those given in Options.Interceptors.
+func (s *HTTPService) Use(interceptors ...Interceptor) {
+	s.interceptors.add(interceptors...)
+}
+
+// interceptRequest runs the OnRequest hooks on a copy of the request's
+// headers, so callers' maps are never modified.
+func (s *HTTPService) interceptRequest(request *ProxyRequest) {
+	interceptors := s.interceptors.snapshot()
+	if len(interceptors) == 0 {
+		return
+	}
+	request.Headers = maps.Clone(request.Headers)
+	if request.Headers == nil {
+		request.Headers = map[string]string{}
+	}
+	for _, interceptor := range interceptors {
+		interceptor.OnRequest(request)
+	}
+}
+
+// interceptResponse runs the OnResponse hooks of a successful response.
+func (s *HTTPService) interceptResponse(request ProxyRequest, response *ProxyResponse) {
+	if !response.Success || response.Data == nil {
+		return
+	}
+	for _, interceptor := range s.interceptors.snapshot() {
+		interceptor.OnResponse(request, response.Data)
+	}
+}
@@ -0,0 +1,181 @@
+package proxy
+
+import (
+	"encoding/json"
+	"sort"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+// maxJWTs bounds the tokens annotated on one response.
+const maxJWTs = 10
+
+// maxJWTBodyDepth bounds the JSON nesting searched for tokens.
+const maxJWTBodyDepth = 8
+
+// DecodeJWT decodes a token and checks its time claims, verifying the
+// signature when a key is given.
+func (s *HTTPService) DecodeJWT(request JWTRequest) JWTResponse {
+	token := strings.TrimSpace(request.Token)
+	if scheme, rest, ok := strings.Cut(token, " "); ok && strings.EqualFold(scheme, "Bearer") {
+		token = strings.TrimSpace(rest)
+	}
+	if token == "" {
+		return JWTResponse{Error: &ErrorData{Message: "Token is required", Code: "INVALID_REQUEST"}}
+	}
+	info, err := inspectJWT(token, "input", request.Key, s.clock.Now())
+	if err != nil {
+		return JWTResponse{Error: &ErrorData{Message: "Invalid token: " + err.Error(), Code: "INVALID_JWT"}}
+	}
+	return JWTResponse{Success: true, Token: info}
+}
+
+// inspectJWT decodes a token found at source and evaluates it at now.
+func inspectJWT(token, source string, key *string, now time.Time) (*JWTInfo, error) {
+	parsed, err := infra.ParseJWT(token)
+	if err != nil {
+		return nil, err
+	}
+	info := &JWTInfo{
+		Source:    source,
+		Header:    parsed.Header,
+		Claims:    parsed.Claims,
+		Algorithm: parsed.Algorithm,
+		Warnings:  []string{},
+	}
+	warn := func(message string) {
+		info.Warnings = append(info.Warnings, message)
+	}
+
+	unix := now.Unix()
+	info.IssuedAt = numericDate(parsed.Claims, "iat", warn)
+	info.NotBefore = numericDate(parsed.Claims, "nbf", warn)
+	info.ExpiresAt = numericDate(parsed.Claims, "exp", warn)
+	if exp := info.ExpiresAt; exp != nil {
+		expiresIn := *exp - unix
+		info.ExpiresIn = &expiresIn
+		info.Expired = expiresIn <= 0
+	} else {
+		warn("No exp claim; the token never expires")
+	}
+	if nbf := info.NotBefore; nbf != nil {
+		info.NotYetValid = *nbf > unix
+	}
+	if iat := info.IssuedAt; iat != nil && *iat > unix+60 {
+		warn("iat is in the future; the issuer's clock may be ahead")
+	}
+	if strings.EqualFold(parsed.Algorithm, "none") {
+		warn("Unsigned token (alg none); servers must reject it")
+	}
+
+	if key != nil && *key != "" {
+		verified := true
+		if err := parsed.Verify([]byte(*key)); err != nil {
+			verified = false
+			info.VerifyError = strPtr(err.Error())
+		}
+		info.Verified = &verified
+	}
+	return info, nil
+}
+
+// numericDate reads a NumericDate claim in seconds, flagging values that are
+// not numbers.
+func numericDate(claims map[string]interface{}, name string, warn func(string)) *int64 {
+	value, ok := claims[name]
+	if !ok {
+		return nil
+	}
+	number, ok := value.(json.Number)
+	if !ok {
+		warn(name + " is not a number and is ignored")
+		return nil
+	}
+	seconds, err := number.Float64()
+	if err != nil {
+		warn(name + " is not a number and is ignored")
+		return nil
+	}
+	result := int64(seconds)
+	return &result
+}
+
+// findJWTs decodes the tokens in the request headers sent, and in the
+// headers, cookies and JSON body of a response. Each is verified with key
+// when given.
+func findJWTs(sent map[string]string, data *ResponseData, key *string, now time.Time) []JWTInfo {
+	var found []JWTInfo
+	seen := map[string]bool{}
+	add := func(value, source string) {
+		value = strings.TrimSpace(value)
+		if scheme, rest, ok := strings.Cut(value, " "); ok && strings.EqualFold(scheme, "Bearer") {
+			value = strings.TrimSpace(rest)
+		}
+		if len(found) >= maxJWTs || seen[value] || !infra.LooksLikeJWT(value) {
+			return
+		}
+		seen[value] = true
+		if info, err := inspectJWT(value, source, key, now); err == nil {
+			found = append(found, *info)
+		}
+	}
+
+	names := make([]string, 0, len(sent))
+	for name := range sent {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	for _, name := range names {
+		if strings.EqualFold(name, "Cookie") {
+			for _, pair := range strings.Split(sent[name], ";") {
+				cookie, value, _ := strings.Cut(strings.TrimSpace(pair), "=")
+				add(value, "request cookie "+cookie)
+			}
+			continue
+		}
+		add(sent[name], "request header "+name)
+	}
+
+	for _, field := range data.Headers {
+		if !strings.EqualFold(field.Name, "Set-Cookie") {
+			add(field.Value, "response header "+field.Name)
+		}
+	}
+	for _, cookie := range data.Cookies {
+		add(cookie.Value, "response cookie "+cookie.Name)
+	}
+
+	if !data.IsBinary && len(data.Body) > 0 && strings.Contains(data.Body, "eyJ") {
+		var body interface{}
+		if json.Unmarshal([]byte(data.Body), &body) == nil {
+			walkJWTBody(body, "body", 0, add)
+		}
+	}
+	return found
+}
+
+// walkJWTBody offers every string of a JSON value to add, with its path.
+func walkJWTBody(value interface{}, path string, depth int, add func(value, source string)) {
+	if depth > maxJWTBodyDepth {
+		return
+	}
+	switch v := value.(type) {
+	case string:
+		add(v, path)
+	case map[string]interface{}:
+		keys := make([]string, 0, len(v))
+		for key := range v {
+			keys = append(keys, key)
+		}
+		sort.Strings(keys)
+		for _, key := range keys {
+			walkJWTBody(v[key], path+"."+key, depth+1, add)
+		}
+	case []interface{}:
+		for _, item := range v {
+			walkJWTBody(item, path+"[]", depth+1, add)
+		}
+	}
+}
@@ -0,0 +1,66 @@
+package proxy_test
+
+import (
+	"testing"
+
+	"zone.digit.tommie/internal/proxy"
+)
+
+func intPtr(v int) *int { return &v }
+
+func TestBurstLimitRequests(t *testing.T) {
+	tests := []struct {
+		name              string
+		burst             proxy.BurstRequest
+		limit             int
+		wantMax, wantSent int
+	}{
+		{"defaults", proxy.BurstRequest{}, 100, 30, 30},
+		{"whole bursts", proxy.BurstRequest{}, 25, 30, 20},
+		{"less than a burst", proxy.BurstRequest{BurstSize: intPtr(50)}, 7, 150, 7},
+		{"capped at a batch", proxy.BurstRequest{Bursts: intPtr(100), BurstSize: intPtr(100)}, 1000, 500, 500},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := tt.burst.MaxRequests(); got != tt.wantMax {
+				t.Errorf("MaxRequests() = %d, want %d", got, tt.wantMax)
+			}
+			tt.burst.LimitRequests(tt.limit)
+			if got := tt.burst.MaxRequests(); got != tt.wantSent {
+				t.Errorf("after LimitRequests(%d): MaxRequests() = %d, want %d", tt.limit, got, tt.wantSent)
+			}
+		})
+	}
+}
+
+func TestDataRunLimitRequests(t *testing.T) {
+	run := proxy.DataRunRequest{Data: "id\n1\n2\n3\n4\n5"}
+	if got := run.MaxRequests(); got != 5 {
+		t.Fatalf("MaxRequests() = %d, want 5", got)
+	}
+	run.LimitRequests(2)
+	if got := run.MaxRequests(); got != 2 {
+		t.Errorf("after LimitRequests(2): MaxRequests() = %d, want 2", got)
+	}
+	// A larger limit does not lift a smaller one
+	run.LimitRequests(4)
+	if got := run.MaxRequests(); got != 2 {
+		t.Errorf("after LimitRequests(4): MaxRequests() = %d, want 2", got)
+	}
+
+	if got := (proxy.DataRunRequest{Data: "not, valid\n\"x"}).MaxRequests(); got != 1 {
+		t.Errorf("invalid data: MaxRequests() = %d, want 1", got)
+	}
+}
+
+func TestBatchLimitRequests(t *testing.T) {
+	batch := proxy.BatchRequest{Requests: make([]proxy.ProxyRequest, 10)}
+	batch.LimitRequests(3)
+	if got := batch.MaxRequests(); got != 3 {
+		t.Errorf("MaxRequests() = %d, want 3", got)
+	}
+	batch.LimitRequests(5)
+	if got := batch.MaxRequests(); got != 3 {
+		t.Errorf("a larger limit: MaxRequests() = %d, want 3", got)
+	}
+}
@@ -0,0 +1,164 @@
+package proxy
+
+import (
+	"context"
+	"strconv"
+	"sync"
+	"time"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+const (
+	// DefaultLoadTestIterations is the number of requests of a load test
+	// without iterations or a duration.
+	DefaultLoadTestIterations = 100
+	// MaxLoadTestIterations caps the requests of a load test.
+	MaxLoadTestIterations = 10_000
+	// DefaultLoadTestConcurrency is the number of requests in flight at once.
+	DefaultLoadTestConcurrency = 10
+	// MaxLoadTestConcurrency caps the requests in flight at once.
+	MaxLoadTestConcurrency = 100
+	// MaxLoadTestDuration caps how long a load test keeps sending requests.
+	MaxLoadTestDuration = 10 * time.Minute
+	// LoadTestProgressInterval is the time between progress reports.
+	LoadTestProgressInterval = 500 * time.Millisecond
+)
+
+// MaxRequests returns the number of requests the test sends at most.
+func (t LoadTestRequest) MaxRequests() int {
+	iterations := DefaultLoadTestIterations
+	if t.DurationMS != nil && *t.DurationMS > 0 {
+		iterations = MaxLoadTestIterations
+	}
+	return min(positiveOr(t.Iterations, iterations), MaxLoadTestIterations)
+}
+
+// ExecuteLoadTest sends a request repeatedly with fixed concurrency until
+// the iterations are done, the duration has passed or ctx is cancelled.
+// onProgress, when set, receives the aggregate stats so far at every
+// LoadTestProgressInterval. Only timings and sizes are kept: responses are
+// not returned, nor recorded in history or the active session.
+func (s *HTTPService) ExecuteLoadTest(ctx context.Context, store storage.Store, test LoadTestRequest, onProgress func(LoadTestResponse)) LoadTestResponse {
+	iterations := test.MaxRequests()
+	duration := MaxLoadTestDuration
+	if test.DurationMS != nil && *test.DurationMS > 0 {
+		duration = min(time.Duration(*test.DurationMS)*time.Millisecond, MaxLoadTestDuration)
+	}
+	concurrency := min(positiveOr(test.Concurrency, DefaultLoadTestConcurrency), MaxLoadTestConcurrency, iterations)
+
+	ctx, cancel := context.WithTimeout(ctx, duration)
+	defer cancel()
+	stats := newLoadTestStats(s.clock)
+
+	next := make(chan struct{})
+	go func() {
+		defer close(next)
+		for i := 0; i < iterations; i++ {
+			select {
+			case next <- struct{}{}:
+			case <-ctx.Done():
+				return
+			}
+		}
+	}()
+
+	var wg sync.WaitGroup
+	for i := 0; i < concurrency; i++ {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			for range next {
+				response, _ := s.run(ctx, store, test.Request, nil)
+				stats.add(response)
+			}
+		}()
+	}
+	done := make(chan struct{})
+	go func() {
+		wg.Wait()
+		close(done)
+	}()
+
+	ticker := time.NewTicker(LoadTestProgressInterval)
+	defer ticker.Stop()
+	for {
+		select {
+		case <-done:
+			result := stats.snapshot()
+			result.Done = true
+			return result
+		case <-ticker.C:
+			if onProgress != nil {
+				onProgress(stats.snapshot())
+			}
+		}
+	}
+}
+
+// loadTestStats collects the outcome of the requests of a load test.
+type loadTestStats struct {
+	clock   Clock
+	started time.Time
+
+	mu       sync.Mutex
+	result   LoadTestResponse
+	timings  timingSamples
+	failures int
+}
+
+func newLoadTestStats(clock Clock) *loadTestStats {
+	return &loadTestStats{
+		clock:   clock,
+		started: clock.Now(),
+		result:  LoadTestResponse{Statuses: map[string]int{}, Errors: map[string]int{}},
+	}
+}
+
+func (l *loadTestStats) add(response ProxyResponse) {
+	l.mu.Lock()
+	defer l.mu.Unlock()
+	l.result.Completed++
+	l.result.Bytes += response.TransferredBytes()
+	if !response.Success {
+		l.result.Failed++
+		l.failures++
+		l.result.Errors[response.Error.Code]++
+		return
+	}
+	data := response.Data
+	l.result.Succeeded++
+	l.result.Statuses[strconv.Itoa(int(data.Status))]++
+	if data.Status >= 400 {
+		l.result.ErrorStatus++
+		l.failures++
+	}
+	l.timings.add(data.Timing)
+}
+
+// snapshot returns the stats so far.
+func (l *loadTestStats) snapshot() LoadTestResponse {
+	l.mu.Lock()
+	defer l.mu.Unlock()
+	result := l.result
+	result.Statuses = make(map[string]int, len(l.result.Statuses))
+	for status, n := range l.result.Statuses {
+		result.Statuses[status] = n
+	}
+	result.Errors = make(map[string]int, len(l.result.Errors))
+	for code, n := range l.result.Errors {
+		result.Errors[code] = n
+	}
+
+	elapsed := since(l.clock, l.started)
+	result.Elapsed = uint64(elapsed.Milliseconds())
+	if elapsed > 0 {
+		result.RPS = float64(result.Completed) / elapsed.Seconds()
+	}
+	if result.Completed > 0 {
+		result.ErrorRate = float64(l.failures) * 100 / float64(result.Completed)
+	}
+	result.Latency = l.timings.latency()
+	result.Phases = l.timings.timingPhases()
+	return result
+}
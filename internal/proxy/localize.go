@@ -0,0 +1,96 @@
+package proxy
+
+import (
+	"reflect"
+
+	"zone.digit.tommie/internal/i18n"
+)
+
+var errorDataType = reflect.TypeOf(&ErrorData{})
+
+// Localized returns a copy of the error with its message translated to
+// locale, keeping the English message in Detail. The code never changes, and
+// errors without a translation are returned as is.
+func (e *ErrorData) Localized(locale string) *ErrorData {
+	if e == nil {
+		return nil
+	}
+	message, ok := i18n.Message(locale, e.Code)
+	if !ok {
+		return e
+	}
+	localized := *e
+	localized.Detail = &e.Message
+	localized.Message = message
+	return &localized
+}
+
+// Localize translates every error of a response in place, including those
+// of batch results and nested requests. v must be a pointer, e.g. to a
+// ProxyResponse or to an interface{} holding one. The errors themselves are replaced, not
+// modified, so responses sharing them are unaffected.
+func Localize(v interface{}, locale string) {
+	if locale == "" || locale == i18n.Default {
+		return
+	}
+	localizeValue(reflect.ValueOf(v), locale, 0)
+}
+
+// Localized returns a response with its errors translated as Localize does.
+func Localized[T any](v T, locale string) T {
+	Localize(&v, locale)
+	return v
+}
+
+// maxLocalizeDepth bounds the walk over self-referencing values.
+const maxLocalizeDepth = 16
+
+func localizeValue(v reflect.Value, locale string, depth int) {
+	if depth > maxLocalizeDepth {
+		return
+	}
+	switch v.Kind() {
+	case reflect.Pointer:
+		if !v.IsNil() {
+			localizeValue(v.Elem(), locale, depth+1)
+		}
+	case reflect.Interface:
+		if v.IsNil() {
+			return
+		}
+		elem := v.Elem()
+		if elem.Kind() == reflect.Pointer || !v.CanSet() {
+			localizeValue(elem, locale, depth+1)
+			return
+		}
+		// Values held by an interface are not addressable; localize a copy
+		copied := reflect.New(elem.Type()).Elem()
+		copied.Set(elem)
+		localizeValue(copied, locale, depth+1)
+		v.Set(copied)
+	case reflect.Struct:
+		for i := 0; i < v.NumField(); i++ {
+			field := v.Field(i)
+			if !field.CanSet() {
+				continue
+			}
+			if field.Type() == errorDataType {
+				if !field.IsNil() {
+					field.Set(reflect.ValueOf(field.Interface().(*ErrorData).Localized(locale)))
+				}
+				continue
+			}
+			localizeValue(field, locale, depth+1)
+		}
+	case reflect.Slice, reflect.Array:
+		// Bodies and other slices of scalars hold no errors
+		switch v.Type().Elem().Kind() {
+		case reflect.Struct, reflect.Pointer, reflect.Interface, reflect.Slice, reflect.Array:
+		default:
+			return
+		}
+		for i := 0; i < v.Len(); i++ {
+			localizeValue(v.Index(i), locale, depth+1)
+		}
+	}
+}
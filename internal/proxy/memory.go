@@ -0,0 +1,139 @@
+package proxy
+
+import (
+	"errors"
+	"io"
+	"sync"
+	"time"
+)
+
+var (
+	// errMemoryBusy is returned when memory did not free up within the wait.
+	errMemoryBusy = errors.New("in-flight memory limit reached")
+	// errMemoryExceeded is returned for a single execution larger than the ceiling.
+	errMemoryExceeded = errors.New("body exceeds the in-flight memory limit")
+)
+
+// memoryGuard bounds the approximate body memory held by concurrent
+// executions of a service. Executions that would cross the ceiling wait for
+// others to finish, then fail with BUSY.
+type memoryGuard struct {
+	limit int
+	wait  time.Duration
+
+	mu       sync.Mutex
+	used     int
+	active   int           // Executions holding a reservation
+	released chan struct{} // Closed and replaced whenever memory is released
+}
+
+func newMemoryGuard(limit int, wait time.Duration) *memoryGuard {
+	return &memoryGuard{limit: limit, wait: wait, released: make(chan struct{})}
+}
+
+// memoryReservation is the memory held by one execution.
+type memoryReservation struct {
+	guard *memoryGuard
+	held  int
+}
+
+// reserve starts accounting for an execution. Release it when done.
+func (g *memoryGuard) reserve() *memoryReservation {
+	g.mu.Lock()
+	g.active++
+	g.mu.Unlock()
+	return &memoryReservation{guard: g}
+}
+
+// grow accounts for n more bytes, waiting up to the guard's wait for room.
+func (r *memoryReservation) grow(n int) error {
+	g := r.guard
+	if g.limit <= 0 || n <= 0 {
+		return nil
+	}
+
+	deadline := time.Now().Add(g.wait)
+	for {
+		g.mu.Lock()
+		if r.held+n > g.limit {
+			g.mu.Unlock()
+			return errMemoryExceeded
+		}
+		if g.used+n <= g.limit {
+			g.used += n
+			r.held += n
+			g.mu.Unlock()
+			return nil
+		}
+		released := g.released
+		g.mu.Unlock()
+
+		remaining := time.Until(deadline)
+		if remaining <= 0 {
+			return errMemoryBusy
+		}
+		timer := time.NewTimer(remaining)
+		select {
+		case <-released:
+		case <-timer.C:
+		}
+		timer.Stop()
+	}
+}
+
+// release returns the memory held and wakes waiting executions.
+func (r *memoryReservation) release() {
+	g := r.guard
+	g.mu.Lock()
+	defer g.mu.Unlock()
+
+	g.active--
+	if r.held == 0 {
+		return
+	}
+	g.used -= r.held
+	r.held = 0
+	close(g.released)
+	g.released = make(chan struct{})
+}
+
+// usage returns the executions in progress and the body bytes they hold.
+func (g *memoryGuard) usage() (active, used int) {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	return g.active, g.used
+}
+
+// reader accounts for the bytes read from body.
+func (r *memoryReservation) reader(body io.Reader) io.Reader {
+	if r.guard.limit <= 0 {
+		return body
+	}
+	return &guardedReader{body: body, reservation: r}
+}
+
+type guardedReader struct {
+	body        io.Reader
+	reservation *memoryReservation
+}
+
+func (g *guardedReader) Read(p []byte) (int, error) {
+	n, err := g.body.Read(p)
+	if n > 0 {
+		if growErr := g.reservation.grow(n); growErr != nil {
+			return n, growErr
+		}
+	}
+	return n, err
+}
+
+// busyResponse converts a memory guard error to a BUSY response.
+func busyResponse(err error) (ProxyResponse, bool) {
+	switch {
+	case errors.Is(err, errMemoryExceeded):
+		return NewErrorResponse("Body exceeds the in-flight memory limit", "BUSY"), true
+	case errors.Is(err, errMemoryBusy):
+		return NewErrorResponse("Too many large transfers in progress; try again later", "BUSY"), true
+	}
+	return ProxyResponse{}, false
+}
@@ -0,0 +1,206 @@
+package proxy
+
+import (
+	"bytes"
+	"crypto/hmac"
+	"crypto/md5"
+	"crypto/rand"
+	"encoding/binary"
+	"errors"
+	"strings"
+	"time"
+	"unicode/utf16"
+
+	"golang.org/x/crypto/md4"
+)
+
+// NTLM message flags (MS-NLMP, section 2.2.2.5).
+const (
+	ntlmNegotiateUnicode    = 0x00000001
+	ntlmRequestTarget       = 0x00000004
+	ntlmNegotiateNTLM       = 0x00000200
+	ntlmAlwaysSign          = 0x00008000
+	ntlmExtendedSecurity    = 0x00080000
+	ntlmNegotiateTargetInfo = 0x00800000
+	ntlmNegotiate128        = 0x20000000
+	ntlmNegotiate56         = 0x80000000
+
+	ntlmClientFlags = ntlmNegotiateUnicode | ntlmRequestTarget | ntlmNegotiateNTLM | ntlmAlwaysSign |
+		ntlmExtendedSecurity | ntlmNegotiateTargetInfo | ntlmNegotiate128 | ntlmNegotiate56
+
+	// msvAvTimestamp is the AV_PAIR holding the server time in a challenge.
+	msvAvTimestamp = 7
+)
+
+var ntlmSignature = []byte("NTLMSSP\x00")
+
+// ntlmNegotiator answers NTLM challenges with NTLMv2 responses computed from
+// explicit credentials, so it works on every platform. Servers offering
+// Negotiate accept the same raw NTLM tokens; Kerberos needs SSPI.
+type ntlmNegotiator struct {
+	domain   string
+	user     string
+	password string
+	now      time.Time
+	legs     int
+}
+
+// newNTLMNegotiator takes a user as DOMAIN\user or as a user principal name
+// such as user@example.com.
+func newNTLMNegotiator(username, password string, now time.Time) *ntlmNegotiator {
+	n := &ntlmNegotiator{user: username, password: password, now: now}
+	if domain, user, ok := strings.Cut(username, `\`); ok {
+		n.domain, n.user = domain, user
+	}
+	return n
+}
+
+// step returns the NEGOTIATE message first and the AUTHENTICATE message
+// answering the server's CHALLENGE next.
+func (n *ntlmNegotiator) step(challenge []byte) ([]byte, error) {
+	n.legs++
+	if challenge == nil {
+		if n.legs > 1 {
+			return nil, errors.New("NTLM server sent no challenge")
+		}
+		return ntlmNegotiateMessage(), nil
+	}
+	return n.authenticate(challenge)
+}
+
+func (n *ntlmNegotiator) close() {}
+
+func ntlmNegotiateMessage() []byte {
+	msg := make([]byte, 32)
+	copy(msg, ntlmSignature)
+	binary.LittleEndian.PutUint32(msg[8:], 1)
+	binary.LittleEndian.PutUint32(msg[12:], ntlmClientFlags)
+	// Empty domain and workstation fields point past the header
+	binary.LittleEndian.PutUint32(msg[20:], 32)
+	binary.LittleEndian.PutUint32(msg[28:], 32)
+	return msg
+}
+
+// authenticate builds the AUTHENTICATE message (MS-NLMP, section 3.3.2).
+func (n *ntlmNegotiator) authenticate(challenge []byte) ([]byte, error) {
+	if len(challenge) < 32 || !bytes.Equal(challenge[:8], ntlmSignature) || binary.LittleEndian.Uint32(challenge[8:]) != 2 {
+		return nil, errors.New("malformed NTLM challenge")
+	}
+	flags := binary.LittleEndian.Uint32(challenge[20:]) & ntlmClientFlags
+	serverChallenge := challenge[24:32]
+	var targetInfo []byte
+	if len(challenge) >= 48 {
+		length := int(binary.LittleEndian.Uint16(challenge[40:]))
+		offset := int(binary.LittleEndian.Uint32(challenge[44:]))
+		if offset+length > len(challenge) {
+			return nil, errors.New("malformed NTLM challenge target info")
+		}
+		targetInfo = challenge[offset : offset+length]
+	}
+
+	clientChallenge := make([]byte, 8)
+	if _, err := rand.Read(clientChallenge); err != nil {
+		return nil, err
+	}
+
+	// The server time is preferred; it also means no LMv2 response is sent
+	timestamp, serverTime := ntlmTimestamp(targetInfo)
+	if !serverTime {
+		// FILETIME: 100 ns intervals since 1601
+		binary.LittleEndian.PutUint64(timestamp, uint64(n.now.UnixNano()/100+116444736000000000))
+	}
+
+	key := ntowfv2(n.user, n.domain, n.password)
+	ntResponse, lmResponse := ntlmv2Responses(key, serverChallenge, clientChallenge, timestamp, targetInfo, !serverTime)
+	return n.authenticateMessage(flags, lmResponse, ntResponse), nil
+}
+
+// ntlmv2Responses computes the NTLMv2 response and, when lm is set, the
+// LMv2 response; otherwise the LM response is zeroed (MS-NLMP, section 3.3.2).
+func ntlmv2Responses(key, serverChallenge, clientChallenge, timestamp, targetInfo []byte, lm bool) (ntResponse, lmResponse []byte) {
+	blob := make([]byte, 0, 28+len(targetInfo)+4)
+	blob = append(blob, 1, 1, 0, 0, 0, 0, 0, 0)
+	blob = append(blob, timestamp...)
+	blob = append(blob, clientChallenge...)
+	blob = append(blob, 0, 0, 0, 0)
+	blob = append(blob, targetInfo...)
+	blob = append(blob, 0, 0, 0, 0)
+	ntResponse = append(hmacMD5(key, serverChallenge, blob), blob...)
+
+	lmResponse = make([]byte, 24)
+	if lm {
+		lmResponse = append(hmacMD5(key, serverChallenge, clientChallenge), clientChallenge...)
+	}
+	return ntResponse, lmResponse
+}
+
+// authenticateMessage encodes an AUTHENTICATE message without version,
+// workstation or session key (MS-NLMP, section 2.2.1.3).
+func (n *ntlmNegotiator) authenticateMessage(flags uint32, lmResponse, ntResponse []byte) []byte {
+	fields := [][]byte{
+		lmResponse,
+		ntResponse,
+		utf16le(n.domain),
+		utf16le(n.user),
+		nil, // Workstation
+		nil, // Encrypted random session key; no key exchange
+	}
+	const headerSize = 64
+	msg := make([]byte, headerSize)
+	copy(msg, ntlmSignature)
+	binary.LittleEndian.PutUint32(msg[8:], 3)
+	offset := headerSize
+	for i, field := range fields {
+		pos := 12 + 8*i
+		binary.LittleEndian.PutUint16(msg[pos:], uint16(len(field)))
+		binary.LittleEndian.PutUint16(msg[pos+2:], uint16(len(field)))
+		binary.LittleEndian.PutUint32(msg[pos+4:], uint32(offset))
+		offset += len(field)
+	}
+	binary.LittleEndian.PutUint32(msg[60:], flags)
+	for _, field := range fields {
+		msg = append(msg, field...)
+	}
+	return msg
+}
+
+// ntlmTimestamp returns the MsvAvTimestamp of a challenge's target info, or
+// a zeroed buffer and false when the server sent none.
+func ntlmTimestamp(targetInfo []byte) ([]byte, bool) {
+	for rest := targetInfo; len(rest) >= 4; {
+		id := binary.LittleEndian.Uint16(rest)
+		length := int(binary.LittleEndian.Uint16(rest[2:]))
+		if len(rest) < 4+length {
+			break
+		}
+		if id == msvAvTimestamp && length == 8 {
+			return append([]byte(nil), rest[4:12]...), true
+		}
+		rest = rest[4+length:]
+	}
+	return make([]byte, 8), false
+}
+
+// ntowfv2 derives the NTLMv2 key from the password (MS-NLMP, section 3.3.2).
+func ntowfv2(user, domain, password string) []byte {
+	hash := md4.New()
+	hash.Write(utf16le(password))
+	return hmacMD5(hash.Sum(nil), utf16le(strings.ToUpper(user)+domain))
+}
+
+func hmacMD5(key []byte, data ...[]byte) []byte {
+	mac := hmac.New(md5.New, key)
+	for _, d := range data {
+		mac.Write(d)
+	}
+	return mac.Sum(nil)
+}
+
+func utf16le(s string) []byte {
+	units := utf16.Encode([]rune(s))
+	b := make([]byte, 2*len(units))
+	for i, u := range units {
+		binary.LittleEndian.PutUint16(b[2*i:], u)
+	}
+	return b
+}
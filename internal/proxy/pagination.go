@@ -0,0 +1,236 @@
+package proxy
+
+import (
+	"encoding/json"
+	"net/url"
+	"strconv"
+	"strings"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+const (
+	// DefaultMaxPages is the number of pages fetched when paginating.
+	DefaultMaxPages = 10
+	// MaxPages caps the requested number of pages.
+	MaxPages = 100
+)
+
+// nextURLFields are JSON paths commonly holding the URL of the next page.
+var nextURLFields = []string{"next", "links.next", "_links.next.href", "paging.next", "pagination.next"}
+
+// cursorFields are JSON paths commonly holding an opaque next-page cursor.
+var cursorFields = []string{
+	"next_cursor", "nextCursor", "meta.next_cursor", "meta.nextCursor",
+	"pagination.next_cursor", "response_metadata.next_cursor", "nextPageToken",
+}
+
+// itemFields are object fields commonly holding the items of a page.
+var itemFields = []string{"data", "items", "results", "records", "entries", "values"}
+
+// paginate follows next-page references from the first response and
+// aggregates per-page counts, sizes and timings.
+func (s *HTTPService) paginate(store storage.Store, request ProxyRequest, firstURL string, first *ResponseData) *PaginationInfo {
+	opts := request.Paginate
+	maxPages := DefaultMaxPages
+	if opts.MaxPages != nil && *opts.MaxPages > 0 {
+		maxPages = min(*opts.MaxPages, MaxPages)
+	}
+	cursorParam := "cursor"
+	if opts.CursorParam != nil && *opts.CursorParam != "" {
+		cursorParam = *opts.CursorParam
+	}
+
+	info := &PaginationInfo{}
+	info.addPage(firstURL, first)
+	seen := map[string]bool{firstURL: true}
+	pageURL, data := firstURL, first
+
+	for {
+		next, mode := nextPage(pageURL, firstURL, data, opts, cursorParam)
+		if next == "" {
+			info.Complete = true
+			break
+		}
+		if info.Mode == "" {
+			info.Mode = mode
+		}
+		if seen[next] {
+			info.StoppedReason = strPtr("Next page repeats " + next)
+			break
+		}
+		if len(info.Pages) >= maxPages {
+			info.StoppedReason = strPtr("Reached the limit of " + strconv.Itoa(maxPages) + " pages")
+			break
+		}
+		seen[next] = true
+
+		resp := s.ExecuteIn(store, ProxyRequest{
+			Method:      request.Method,
+			URL:         next,
+			Headers:     request.Headers,
+			Body:        request.Body,
+			BodyBase64:  request.BodyBase64,
+			Timeout:     request.Timeout,
+			PinToken:    request.PinToken,
+			HTTPVersion: request.HTTPVersion,
+			CookieJar:   request.CookieJar,
+		})
+		if !resp.Success {
+			info.Pages = append(info.Pages, PageInfo{URL: next, Error: strPtr(resp.Error.Message)})
+			info.StoppedReason = strPtr("Page " + strconv.Itoa(len(info.Pages)) + " failed")
+			break
+		}
+		info.addPage(next, resp.Data)
+		if resp.Data.Status >= 400 {
+			info.StoppedReason = strPtr("Page " + strconv.Itoa(len(info.Pages)) + " returned " + strconv.Itoa(int(resp.Data.Status)))
+			break
+		}
+		pageURL, data = next, resp.Data
+	}
+
+	info.TotalPages = len(info.Pages)
+	return info
+}
+
+func (p *PaginationInfo) addPage(pageURL string, data *ResponseData) {
+	page := PageInfo{
+		URL:    pageURL,
+		Status: &data.Status,
+		Time:   data.Timing.Total,
+		Items:  countItems(data.Body),
+	}
+	if data.SizeBreakdown != nil {
+		page.Size = data.SizeBreakdown.Total
+	}
+	p.Pages = append(p.Pages, page)
+	p.TotalSize += page.Size
+	p.TotalTime += page.Time
+	if page.Items != nil {
+		p.TotalItems += *page.Items
+	}
+}
+
+// nextPage returns the URL of the page after data and how it was found:
+// a Link header, a next URL in the body or a cursor in the body.
+func nextPage(pageURL, firstURL string, data *ResponseData, opts *PaginationOptions, cursorParam string) (string, string) {
+	for _, h := range data.Headers {
+		if strings.EqualFold(h.Name, "Link") {
+			if next := linkNext(h.Value); next != "" {
+				return resolveReference(pageURL, next), "link"
+			}
+		}
+	}
+
+	var body interface{}
+	if json.Unmarshal([]byte(data.Body), &body) != nil {
+		return "", ""
+	}
+
+	nextFields, cursors := nextURLFields, cursorFields
+	if opts.NextField != nil {
+		nextFields = []string{*opts.NextField}
+	}
+	if opts.CursorField != nil {
+		cursors = []string{*opts.CursorField}
+	}
+	for _, field := range nextFields {
+		if next, ok := jsonField(body, field).(string); ok && next != "" {
+			return resolveReference(pageURL, next), "nextUrl"
+		}
+	}
+	for _, field := range cursors {
+		var cursor string
+		switch v := jsonField(body, field).(type) {
+		case string:
+			cursor = v
+		case float64:
+			cursor = strconv.FormatFloat(v, 'f', -1, 64)
+		}
+		if cursor != "" {
+			return withQueryParam(firstURL, cursorParam, cursor), "cursor"
+		}
+	}
+	return "", ""
+}
+
+// linkNext extracts the rel="next" target of a Link header (RFC 8288).
+func linkNext(header string) string {
+	for _, link := range strings.Split(header, ",") {
+		target, params, ok := strings.Cut(link, ";")
+		if !ok {
+			continue
+		}
+		target = strings.TrimSpace(target)
+		if !strings.HasPrefix(target, "<") || !strings.HasSuffix(target, ">") {
+			continue
+		}
+		for _, param := range strings.Split(params, ";") {
+			name, value, _ := strings.Cut(param, "=")
+			if !strings.EqualFold(strings.TrimSpace(name), "rel") {
+				continue
+			}
+			for _, rel := range strings.Fields(strings.Trim(strings.TrimSpace(value), `"`)) {
+				if strings.EqualFold(rel, "next") {
+					return target[1 : len(target)-1]
+				}
+			}
+		}
+	}
+	return ""
+}
+
+// jsonField walks a dot-separated path through decoded JSON objects.
+func jsonField(value interface{}, path string) interface{} {
+	for _, key := range strings.Split(path, ".") {
+		obj, ok := value.(map[string]interface{})
+		if !ok {
+			return nil
+		}
+		value = obj[key]
+	}
+	return value
+}
+
+// countItems returns the length of a top-level JSON array, or of the first
+// array under a common items field.
+func countItems(body string) *int {
+	var value interface{}
+	if json.Unmarshal([]byte(body), &value) != nil {
+		return nil
+	}
+	if items, ok := value.([]interface{}); ok {
+		n := len(items)
+		return &n
+	}
+	for _, field := range itemFields {
+		if items, ok := jsonField(value, field).([]interface{}); ok {
+			n := len(items)
+			return &n
+		}
+	}
+	return nil
+}
+
+func resolveReference(base, ref string) string {
+	baseURL, err := url.Parse(base)
+	if err != nil {
+		return ref
+	}
+	resolved, err := baseURL.Parse(ref)
+	if err != nil {
+		return ref
+	}
+	return resolved.String()
+}
+
+func withQueryParam(rawURL, name, value string) string {
+	u, err := url.Parse(rawURL)
+	if err != nil {
+		return rawURL
+	}
+	query := u.Query()
+	query.Set(name, value)
+	u.RawQuery = query.Encode()
+	return u.String()
+}
@@ -0,0 +1,118 @@
+package proxy
+
+import (
+	"container/list"
+	"crypto/rand"
+	"encoding/hex"
+	"net"
+	"sync"
+	"time"
+)
+
+const (
+	// pinSessionTTL is how long an unused pin session is kept.
+	pinSessionTTL = time.Hour
+	// maxPinSessions caps the live pin sessions; the least recently used
+	// session is dropped to make room for a new one.
+	maxPinSessions = 10_000
+)
+
+// pinStore keeps per-session host to IP pins, so multi-request investigations
+// keep hitting the same backend node behind round-robin DNS.
+type pinStore struct {
+	mu       sync.Mutex
+	sessions map[string]*list.Element
+	recent   *list.List // Of *pinSession, most recently used first
+}
+
+type pinSession struct {
+	token    string
+	hosts    map[string]string
+	lastUsed time.Time
+}
+
+func newPinStore() *pinStore {
+	return &pinStore{sessions: make(map[string]*list.Element), recent: list.New()}
+}
+
+// session returns the token and a copy of the pins of a session.
+// A new session is created when the token is empty or unknown.
+func (p *pinStore) session(token string) (string, map[string]string, error) {
+	p.mu.Lock()
+	defer p.mu.Unlock()
+
+	p.evictLocked()
+
+	element, ok := p.sessions[token]
+	if ok {
+		p.recent.MoveToFront(element)
+	} else {
+		var err error
+		if token, err = newPinToken(); err != nil {
+			return "", nil, err
+		}
+		element = p.recent.PushFront(&pinSession{token: token, hosts: make(map[string]string)})
+		p.sessions[token] = element
+		for p.recent.Len() > maxPinSessions {
+			p.removeLocked(p.recent.Back())
+		}
+	}
+	session := element.Value.(*pinSession)
+	session.lastUsed = time.Now()
+
+	pins := make(map[string]string, len(session.hosts))
+	for host, ip := range session.hosts {
+		pins[host] = ip
+	}
+	return token, pins, nil
+}
+
+// pin records the IP a host is pinned to within a session.
+func (p *pinStore) pin(token, host, ip string) {
+	p.mu.Lock()
+	defer p.mu.Unlock()
+
+	if element, ok := p.sessions[token]; ok {
+		session := element.Value.(*pinSession)
+		session.hosts[host] = ip
+		session.lastUsed = time.Now()
+		p.recent.MoveToFront(element)
+	}
+}
+
+// evictLocked removes idle sessions, which are at the back of the recency
+// list. Callers must hold mu.
+func (p *pinStore) evictLocked() {
+	for element := p.recent.Back(); element != nil; element = p.recent.Back() {
+		if time.Since(element.Value.(*pinSession).lastUsed) <= pinSessionTTL {
+			return
+		}
+		p.removeLocked(element)
+	}
+}
+
+// removeLocked drops a session. Callers must hold mu.
+func (p *pinStore) removeLocked(element *list.Element) {
+	delete(p.sessions, element.Value.(*pinSession).token)
+	p.recent.Remove(element)
+}
+
+func newPinToken() (string, error) {
+	buf := make([]byte, 16)
+	if _, err := rand.Read(buf); err != nil {
+		return "", err
+	}
+	return hex.EncodeToString(buf), nil
+}
+
+// pinnedAddr rewrites a dial address to the pinned IP of its host, if any.
+func pinnedAddr(pins map[string]string, addr string) string {
+	host, port, err := net.SplitHostPort(addr)
+	if err != nil {
+		return addr
+	}
+	if ip, ok := pins[host]; ok {
+		return net.JoinHostPort(ip, port)
+	}
+	return addr
+}
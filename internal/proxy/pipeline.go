@@ -0,0 +1,312 @@
+package proxy
+
+import (
+	"bufio"
+	"bytes"
+	"context"
+	"errors"
+	"fmt"
+	"io"
+	"net"
+	"net/http"
+	"sort"
+	"strconv"
+	"strings"
+	"sync"
+	"time"
+)
+
+const (
+	// MaxPipelineRequests caps the requests pipelined on one connection.
+	MaxPipelineRequests = 20
+	// pipelineSequenceHeader numbers each pipelined request, so servers that
+	// echo request headers reveal which request a response answers.
+	pipelineSequenceHeader = "X-Pipeline-Sequence"
+)
+
+// MaxRequests returns the number of requests pipelined.
+func (p PipelineRequest) MaxRequests() int {
+	return len(p.Requests)
+}
+
+// LimitRequests cuts the pipeline down to its first n requests.
+func (p *PipelineRequest) LimitRequests(n int) {
+	if n < len(p.Requests) {
+		p.Requests = p.Requests[:max(n, 0)]
+	}
+}
+
+// ExecutePipeline writes several HTTP/1.1 requests back to back on one
+// connection without waiting for responses, then reads the responses in the
+// order they arrive. It is a diagnostic for legacy servers and intermediaries;
+// browsers never pipeline and many servers mishandle it. Requests bypass
+// templates, history and interceptors.
+func (s *HTTPService) ExecutePipeline(request PipelineRequest) PipelineResponse {
+	if len(request.Requests) == 0 {
+		return pipelineError("At least one request is required", "INVALID_REQUEST")
+	}
+	if len(request.Requests) > MaxPipelineRequests {
+		return pipelineError(fmt.Sprintf("At most %d requests can be pipelined", MaxPipelineRequests), "BATCH_TOO_LARGE")
+	}
+	ctx, err := newRequestContext(request.URL)
+	if err != nil {
+		return pipelineError(err.Error(), "INVALID_URL")
+	}
+	if scheme := strings.ToLower(strings.SplitN(request.URL, ":", 2)[0]); scheme != "http" && scheme != "https" {
+		return pipelineError("Pipelining needs an http or https URL", "INVALID_URL")
+	}
+
+	// Requests are serialized up front so invalid ones fail before dialing
+	hostHeader := ctx.host
+	if ctx.isHTTPS && ctx.port != "443" || !ctx.isHTTPS && ctx.port != "80" {
+		hostHeader = net.JoinHostPort(ctx.host, ctx.port)
+	}
+	wire := make([][]byte, len(request.Requests))
+	for i, r := range request.Requests {
+		if wire[i], err = r.serialize(hostHeader, i+1); err != nil {
+			return pipelineError(fmt.Sprintf("Request %d: %v", i+1, err), "INVALID_REQUEST")
+		}
+	}
+
+	timeout := time.Duration(DefaultTimeoutMS) * time.Millisecond
+	if request.Timeout != nil && *request.Timeout > 0 {
+		timeout = time.Duration(*request.Timeout) * time.Millisecond
+	}
+
+	if budget := s.egress.exhausted(); budget != "" {
+		return pipelineError(fmt.Sprintf("Egress %s byte budget exceeded", budget), "QUOTA_EXCEEDED")
+	}
+	if err := s.policy.Load().checkHost(ctx.host); err != nil {
+		return pipelineError(err.Error(), "BLOCKED_BY_POLICY")
+	}
+	proxyURL, _, err := s.upstream.decide(ctx.url)
+	if err != nil {
+		return pipelineError(err.Error(), "PROXY_CONFIG_ERROR")
+	}
+	if proxyURL != nil {
+		return pipelineError("Pipelining needs a direct connection, but the URL is routed through an upstream proxy", "UNSUPPORTED_PROTOCOL")
+	}
+
+	started := s.clock.Now()
+	dnsResult, err := s.opts.Resolver.Resolve(context.Background(), ctx.host)
+	if err != nil {
+		return pipelineError(fmt.Sprintf("DNS lookup failed: %v", err), "DNS_ERROR")
+	}
+	if err := s.policy.Load().checkIPs(dnsResult.IPs); err != nil {
+		return pipelineError(err.Error(), "BLOCKED_BY_POLICY")
+	}
+
+	dialer := &hopDialer{
+		timing:     NewDetailedTimingWithClock(s.clock),
+		timeout:    timeout,
+		serverName: ctx.host,
+		rootCAs:    s.opts.RootCAs,
+		nextProtos: httpVersion11.alpnProtocols(),
+		secure:     ctx.isHTTPS,
+		policy:     s.policy.Load(),
+		addresses:  map[string][]net.IP{ctx.host: dnsResult.IPs},
+	}
+	dialCtx, cancel := context.WithTimeout(context.Background(), timeout)
+	defer cancel()
+	dial := dialer.dial
+	if ctx.isHTTPS {
+		dial = dialer.dialTLS
+	}
+	conn, err := dial(dialCtx, "tcp", net.JoinHostPort(ctx.host, ctx.port))
+	var policyErr *PolicyError
+	if errors.As(err, &policyErr) {
+		return pipelineError(policyErr.Error(), "BLOCKED_BY_POLICY")
+	}
+	var certErr *CertificateError
+	if errors.As(err, &certErr) {
+		response := pipelineError(fmt.Sprintf("Connection failed: %v", certErr), "CERT_INVALID")
+		response.Error.TLS = buildTLSInfo(certErr.Info)
+		return response
+	}
+	if err != nil {
+		return pipelineError(fmt.Sprintf("Connection failed: %v", err), "REQUEST_FAILED")
+	}
+	defer conn.Close()
+	conn.SetDeadline(started.Add(timeout))
+
+	connected := s.clock.Now()
+	result := PipelineResponse{
+		Success:      true,
+		Experimental: true,
+		ServerIP:     optionalString(dialer.remoteIP),
+		TLS:          buildTLSInfo(dialer.tlsInfo),
+		Connect:      uint64(connected.Sub(started).Milliseconds()),
+		Results:      make([]PipelineResult, len(request.Requests)),
+		InOrder:      true,
+	}
+	for i, r := range request.Requests {
+		result.Results[i] = PipelineResult{Sequence: i + 1, Method: r.method(), Path: r.path()}
+	}
+	elapsed := func() uint64 {
+		return uint64(since(s.clock, connected).Milliseconds())
+	}
+
+	// Writes run concurrently with reads, so a server answering early
+	// cannot deadlock against unread responses. Only the writer touches
+	// SentAt, sent and written until it is done.
+	var wg sync.WaitGroup
+	sent, written := 0, 0
+	wg.Add(1)
+	go func() {
+		defer wg.Done()
+		for i, data := range wire {
+			if _, err := conn.Write(data); err != nil {
+				return
+			}
+			result.Results[i].SentAt = elapsed()
+			sent++
+			written += len(data)
+		}
+	}()
+
+	received := 0
+	reader := bufio.NewReader(conn)
+	for i := range result.Results {
+		r := &result.Results[i]
+		if _, err := reader.Peek(1); err != nil {
+			r.Error = strPtr(pipelineReadError(err))
+			break
+		}
+		firstByte := elapsed()
+		r.FirstByteAt = &firstByte
+
+		resp, err := http.ReadResponse(reader, &http.Request{Method: r.Method})
+		if err != nil {
+			r.Error = strPtr(fmt.Sprintf("Malformed response: %v", err))
+			break
+		}
+		size, err := io.Copy(io.Discard, resp.Body)
+		resp.Body.Close()
+		completed := elapsed()
+		r.CompletedAt = &completed
+		status := uint16(resp.StatusCode)
+		r.Status = &status
+		r.Headers = headerFieldsFromHeader(resp.Header, false)
+		r.Size = int(size)
+		received += int(size)
+		if echoed, err := strconv.Atoi(resp.Header.Get(pipelineSequenceHeader)); err == nil {
+			r.Echoed = &echoed
+			result.InOrder = result.InOrder && echoed == r.Sequence
+		}
+		if err != nil {
+			r.Error = strPtr(fmt.Sprintf("Body read failed: %v", err))
+			break
+		}
+		if resp.Close {
+			closed := i + 1
+			result.ClosedAfter = &closed
+			break
+		}
+	}
+	conn.Close()
+	wg.Wait()
+	s.egress.record(written + received)
+
+	for i := range result.Results {
+		r := &result.Results[i]
+		if i < sent && r.CompletedAt != nil && r.SentAt <= *r.CompletedAt {
+			latency := *r.CompletedAt - r.SentAt
+			r.Latency = &latency
+		}
+		if r.Status == nil && r.Error == nil {
+			if result.ClosedAfter != nil {
+				r.Error = strPtr("Not answered; the server closed the connection")
+			} else {
+				r.Error = strPtr("Not answered")
+			}
+		}
+		if r.Status != nil {
+			result.Answered++
+		}
+	}
+	result.Sent = sent
+	result.TotalTime = uint64(since(s.clock, started).Milliseconds())
+	return result
+}
+
+// serialize renders a pipelined request in HTTP/1.1 wire format.
+func (r PipelinedRequest) serialize(host string, sequence int) ([]byte, error) {
+	method := r.method()
+	if strings.ContainsAny(method, " \r\n") {
+		return nil, errors.New("invalid method")
+	}
+	path := r.path()
+	if strings.ContainsAny(path, " \r\n") || !strings.HasPrefix(path, "/") && path != "*" {
+		return nil, errors.New("path must start with / and contain no spaces")
+	}
+
+	var b bytes.Buffer
+	fmt.Fprintf(&b, "%s %s HTTP/1.1\r\n", method, path)
+	headers := http.Header{}
+	for name, value := range r.Headers {
+		if strings.ContainsAny(name+value, "\r\n") {
+			return nil, fmt.Errorf("header %s contains a line break", name)
+		}
+		headers.Set(name, value)
+	}
+	if headers.Get("Host") == "" {
+		headers.Set("Host", host)
+	}
+	// Framing is always ours; a wrong length would desynchronize the pipeline
+	headers.Del("Transfer-Encoding")
+	headers.Del("Content-Length")
+	if r.Body != nil {
+		headers.Set("Content-Length", strconv.Itoa(len(*r.Body)))
+	}
+	headers.Set(pipelineSequenceHeader, strconv.Itoa(sequence))
+
+	names := make([]string, 0, len(headers))
+	for name := range headers {
+		names = append(names, name)
+	}
+	// Host first, as on a regular request
+	sort.Slice(names, func(i, j int) bool {
+		if (names[i] == "Host") != (names[j] == "Host") {
+			return names[i] == "Host"
+		}
+		return names[i] < names[j]
+	})
+	for _, name := range names {
+		fmt.Fprintf(&b, "%s: %s\r\n", name, headers.Get(name))
+	}
+	b.WriteString("\r\n")
+	if r.Body != nil {
+		b.WriteString(*r.Body)
+	}
+	return b.Bytes(), nil
+}
+
+func (r PipelinedRequest) method() string {
+	if r.Method == "" {
+		return http.MethodGet
+	}
+	return strings.ToUpper(r.Method)
+}
+
+func (r PipelinedRequest) path() string {
+	if r.Path == "" {
+		return "/"
+	}
+	return r.Path
+}
+
+func pipelineReadError(err error) string {
+	var netErr net.Error
+	switch {
+	case errors.Is(err, io.EOF):
+		return "Not answered; the server closed the connection"
+	case errors.As(err, &netErr) && netErr.Timeout():
+		return "Not answered before the timeout"
+	default:
+		return fmt.Sprintf("Read failed: %v", err)
+	}
+}
+
+func pipelineError(message, code string) PipelineResponse {
+	return PipelineResponse{Experimental: true, Results: []PipelineResult{}, Error: &ErrorData{Message: message, Code: code}}
+}
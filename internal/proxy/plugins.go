@@ -0,0 +1,157 @@
+package proxy
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"strings"
+
+	"zone.digit.tommie/internal/plugins"
+)
+
+// pluginRequest is the input of on_request, and the request given to on_response.
+type pluginRequest struct {
+	Method  string            `json:"method"`
+	URL     string            `json:"url"`
+	Headers map[string]string `json:"headers"`
+	Body    *string           `json:"body,omitempty"`
+}
+
+// pluginResponse is the response given to on_response.
+type pluginResponse struct {
+	Status     uint16        `json:"status"`
+	Headers    []HeaderField `json:"headers"` // In wire order
+	Body       string        `json:"body"`
+	BodyBase64 *string       `json:"bodyBase64,omitempty"` // Binary bodies only
+}
+
+// pluginRequestChanges is the output of on_request; null headers are removed.
+type pluginRequestChanges struct {
+	Method  *string            `json:"method"`
+	URL     *string            `json:"url"`
+	Headers map[string]*string `json:"headers"`
+	Body    *string            `json:"body"`
+}
+
+// pluginResponseOutput is the output of on_response.
+type pluginResponseOutput struct {
+	Body        *string           `json:"body"` // Replaces the body, e.g. decoded
+	Annotations map[string]string `json:"annotations"`
+}
+
+// runRequestPlugins passes a request through the on_request hook of every
+// plugin in turn, applying the changes each returns. The run of a plugin
+// that failed is returned separately, as it fails the request.
+func (s *HTTPService) runRequestPlugins(ctx context.Context, request *ProxyRequest) ([]PluginRun, *PluginRun) {
+	var runs []PluginRun
+	for _, plugin := range s.opts.Plugins.Plugins() {
+		if !plugin.Has(plugins.OnRequest) {
+			continue
+		}
+		input, _ := json.Marshal(map[string]interface{}{"request": newPluginRequest(*request)})
+		run, output := s.callPlugin(ctx, plugin, plugins.OnRequest, input)
+		if run.Error != nil {
+			return runs, &run
+		}
+		if output != nil {
+			var changes pluginRequestChanges
+			if err := json.Unmarshal(output, &changes); err != nil {
+				run.Error = strPtr(fmt.Sprintf("invalid output: %v", err))
+				return runs, &run
+			}
+			applyPluginChanges(request, changes)
+		}
+		runs = append(runs, run)
+	}
+	return runs, nil
+}
+
+// runResponsePlugins passes a successful response through the on_response
+// hook of every plugin in turn. Failures are recorded in the runs.
+func (s *HTTPService) runResponsePlugins(ctx context.Context, request ProxyRequest, response *ResponseData) []PluginRun {
+	var runs []PluginRun
+	for _, plugin := range s.opts.Plugins.Plugins() {
+		if !plugin.Has(plugins.OnResponse) {
+			continue
+		}
+		input, _ := json.Marshal(map[string]interface{}{
+			"request":  newPluginRequest(request),
+			"response": newPluginResponse(response),
+		})
+		run, output := s.callPlugin(ctx, plugin, plugins.OnResponse, input)
+		if run.Error == nil && output != nil {
+			var result pluginResponseOutput
+			if err := json.Unmarshal(output, &result); err != nil {
+				run.Error = strPtr(fmt.Sprintf("invalid output: %v", err))
+			} else {
+				run.Annotations = result.Annotations
+				if result.Body != nil {
+					response.Body = *result.Body
+					response.BodyBase64 = nil
+					response.BodyHexdump = nil
+					response.IsBinary = false
+					run.BodyReplaced = true
+				}
+			}
+		}
+		runs = append(runs, run)
+	}
+	return runs
+}
+
+// callPlugin calls a hook, timing it.
+func (s *HTTPService) callPlugin(ctx context.Context, plugin *plugins.Plugin, hook string, input []byte) (PluginRun, []byte) {
+	started := s.clock.Now()
+	output, err := plugin.Call(ctx, hook, input)
+	run := PluginRun{
+		Plugin:   plugin.Name,
+		Hook:     hook,
+		Duration: uint64(since(s.clock, started).Milliseconds()),
+	}
+	if err != nil {
+		run.Error = strPtr(err.Error())
+	}
+	return run, output
+}
+
+func newPluginRequest(request ProxyRequest) pluginRequest {
+	return pluginRequest{
+		Method:  request.Method,
+		URL:     request.URL,
+		Headers: request.Headers,
+		Body:    request.Body,
+	}
+}
+
+func newPluginResponse(response *ResponseData) pluginResponse {
+	return pluginResponse{
+		Status:     response.Status,
+		Headers:    response.Headers,
+		Body:       response.Body,
+		BodyBase64: response.BodyBase64,
+	}
+}
+
+// applyPluginChanges applies the output of on_request to a request.
+func applyPluginChanges(request *ProxyRequest, changes pluginRequestChanges) {
+	if changes.Method != nil {
+		request.Method = strings.ToUpper(*changes.Method)
+	}
+	if changes.URL != nil {
+		request.URL = *changes.URL
+	}
+	if changes.Body != nil {
+		request.Body = changes.Body
+	}
+	if len(changes.Headers) > 0 {
+		headers := make(map[string]interface{}, len(changes.Headers))
+		for name, value := range changes.Headers {
+			if value == nil {
+				headers[name] = nil
+			} else {
+				headers[name] = *value
+			}
+		}
+		request.Headers = withScriptHeaders(request.Headers, headers)
+	}
+}
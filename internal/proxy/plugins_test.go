@@ -0,0 +1,88 @@
+package proxy_test
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"zone.digit.tommie/internal/plugins"
+	"zone.digit.tommie/internal/proxy"
+	"zone.digit.tommie/internal/testkit"
+)
+
+// pluginHost loads modules, by file name, as plugins.
+func pluginHost(t *testing.T, modules map[string][]byte) *plugins.Host {
+	t.Helper()
+	dir := t.TempDir()
+	for name, module := range modules {
+		if err := os.WriteFile(filepath.Join(dir, name), module, 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+	host, err := plugins.Load(context.Background(), dir, plugins.DefaultLimits)
+	if err != nil {
+		t.Fatalf("Load: %v", err)
+	}
+	t.Cleanup(func() { host.Close(context.Background()) })
+	return host
+}
+
+func TestPlugins(t *testing.T) {
+	target := testkit.Start()
+	defer target.Close()
+
+	host := pluginHost(t, map[string][]byte{
+		"a-signer.wasm":  testkit.Plugin(1, testkit.WasmHook{Name: plugins.OnRequest, Output: `{"headers":{"X-Signed":"yes"}}`}),
+		"b-decoder.wasm": testkit.Plugin(1, testkit.WasmHook{Name: plugins.OnResponse, Output: `{"body":"decoded","annotations":{"checked":"yes"}}`}),
+	})
+	service := target.Service(proxy.Options{Plugins: host})
+	data := success(t, service.Execute(target.Request(proxy.ProxyRequest{URL: "/bytes/64"})))
+
+	if got := target.Requests()[0].Header.Get("X-Signed"); got != "yes" {
+		t.Errorf("target got X-Signed %q, want the header added by the plugin", got)
+	}
+	if data.Body != "decoded" || data.IsBinary || data.BodyBase64 != nil {
+		t.Errorf("got body %q (binary %v), want the body replaced by the plugin", data.Body, data.IsBinary)
+	}
+	if len(data.Plugins) != 2 {
+		t.Fatalf("got %d plugin runs, want 2", len(data.Plugins))
+	}
+	signer, decoder := data.Plugins[0], data.Plugins[1]
+	if signer.Plugin != "a-signer" || signer.Hook != plugins.OnRequest || signer.Error != nil {
+		t.Errorf("got run %+v, want a-signer on_request", signer)
+	}
+	if decoder.Plugin != "b-decoder" || decoder.Hook != plugins.OnResponse || !decoder.BodyReplaced || decoder.Annotations["checked"] != "yes" {
+		t.Errorf("got run %+v, want b-decoder on_response with its annotations", decoder)
+	}
+}
+
+func TestPluginErrors(t *testing.T) {
+	target := testkit.Start()
+	defer target.Close()
+
+	// A failing request plugin fails the request before it is sent
+	host := pluginHost(t, map[string][]byte{
+		"signer.wasm": testkit.Plugin(1, testkit.WasmHook{Name: plugins.OnRequest, Trap: true}),
+	})
+	response := target.Service(proxy.Options{Plugins: host}).Execute(target.Request(proxy.ProxyRequest{URL: "/ok"}))
+	if failed := failure(t, response, "PLUGIN_ERROR"); !strings.Contains(failed.Message, "signer") {
+		t.Errorf("got %q, want the plugin named", failed.Message)
+	}
+	if len(target.Requests()) != 0 {
+		t.Error("the request was sent")
+	}
+
+	// A failing response plugin is recorded with the response
+	host = pluginHost(t, map[string][]byte{
+		"analyzer.wasm": testkit.Plugin(1, testkit.WasmHook{Name: plugins.OnResponse, Output: `not json`}),
+	})
+	data := success(t, target.Service(proxy.Options{Plugins: host}).Execute(target.Request(proxy.ProxyRequest{URL: "/ok"})))
+	if len(data.Plugins) != 1 || data.Plugins[0].Error == nil {
+		t.Fatalf("got runs %+v, want the failed analyzer", data.Plugins)
+	}
+	if data.Body != "ok" {
+		t.Errorf("got body %q, want the body untouched", data.Body)
+	}
+}
@@ -0,0 +1,200 @@
+package proxy
+
+import (
+	"fmt"
+	"net"
+	"strings"
+	"syscall"
+
+	"zone.digit.tommie/internal/config"
+)
+
+// metadataHosts are cloud instance metadata endpoints, by name and address.
+var metadataHosts = []string{
+	"169.254.169.254",
+	"169.254.170.2",
+	"100.100.100.200",
+	"fd00:ec2::254",
+	"metadata",
+	"metadata.google.internal",
+	"metadata.azure.internal",
+}
+
+// sharedAddressSpace is the carrier-grade NAT range (RFC 6598), not covered by net.IP.IsPrivate.
+var sharedAddressSpace = mustParseCIDR("100.64.0.0/10")
+
+// Policy restricts the destinations requests may reach. A nil Policy allows everything.
+type Policy struct {
+	BlockPrivate  bool         // Loopback, private, link-local and unspecified addresses
+	BlockMetadata bool         // Cloud metadata endpoints
+	Denied        []*net.IPNet // Additional blocked ranges
+	Allowed       []*net.IPNet // Ranges exempt from BlockPrivate and Denied
+	AllowedHosts  []string     // Hostname allowlist; a leading dot matches subdomains
+}
+
+// PolicyError reports a destination refused by the policy.
+type PolicyError struct {
+	Target string
+	Reason string
+}
+
+func (e *PolicyError) Error() string {
+	return fmt.Sprintf("Destination %s blocked by policy: %s", e.Target, e.Reason)
+}
+
+// NewPolicy builds the destination policy from the application configuration.
+// It returns nil when no restriction is configured.
+func NewPolicy(cfg *config.Config) (*Policy, error) {
+	if !cfg.BlockPrivateIPs && !cfg.BlockMetadata && len(cfg.DeniedCIDRs) == 0 && len(cfg.AllowedHosts) == 0 {
+		return nil, nil
+	}
+
+	p := &Policy{
+		BlockPrivate:  cfg.BlockPrivateIPs,
+		BlockMetadata: cfg.BlockMetadata,
+	}
+	for _, host := range cfg.AllowedHosts {
+		p.AllowedHosts = append(p.AllowedHosts, strings.ToLower(host))
+	}
+
+	var err error
+	if p.Denied, err = parseCIDRs(cfg.DeniedCIDRs); err != nil {
+		return nil, fmt.Errorf("invalid denied CIDR: %w", err)
+	}
+	if p.Allowed, err = parseCIDRs(cfg.AllowedCIDRs); err != nil {
+		return nil, fmt.Errorf("invalid allowed CIDR: %w", err)
+	}
+	return p, nil
+}
+
+// checkHost validates a hostname before it is resolved.
+func (p *Policy) checkHost(host string) error {
+	if p == nil {
+		return nil
+	}
+	host = strings.TrimSuffix(strings.ToLower(host), ".")
+
+	if p.BlockMetadata && isMetadataHost(host) {
+		return &PolicyError{Target: host, Reason: "cloud metadata endpoint"}
+	}
+	if len(p.AllowedHosts) > 0 && !p.hostAllowed(host) {
+		return &PolicyError{Target: host, Reason: "host not in allowlist"}
+	}
+	return nil
+}
+
+// checkIP validates an address a connection is about to be made to.
+func (p *Policy) checkIP(ip net.IP) error {
+	if p == nil {
+		return nil
+	}
+
+	if p.BlockMetadata && isMetadataHost(ip.String()) {
+		return &PolicyError{Target: ip.String(), Reason: "cloud metadata endpoint"}
+	}
+	if containsIP(p.Allowed, ip) {
+		return nil
+	}
+	if containsIP(p.Denied, ip) {
+		return &PolicyError{Target: ip.String(), Reason: "address in denied range"}
+	}
+	if p.BlockPrivate && isInternalIP(ip) {
+		return &PolicyError{Target: ip.String(), Reason: "private or internal address"}
+	}
+	return nil
+}
+
+// checkIPs validates every address a host resolved to, so a mix of public
+// and internal records cannot slip through.
+func (p *Policy) checkIPs(ips []net.IP) error {
+	for _, ip := range ips {
+		if err := p.checkIP(ip); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// control checks the address a socket connects to, catching DNS answers
+// that changed between resolution and dialing.
+func (p *Policy) control(network, address string, _ syscall.RawConn) error {
+	host, _, err := net.SplitHostPort(address)
+	if err != nil {
+		return err
+	}
+	ip := net.ParseIP(host)
+	if ip == nil {
+		return &PolicyError{Target: host, Reason: "unresolved address"}
+	}
+	return p.checkIP(ip)
+}
+
+func (p *Policy) hostAllowed(host string) bool {
+	return matchesHost(p.AllowedHosts, host)
+}
+
+// matchesHost reports whether host is one of patterns, ignoring case, where
+// a pattern with a leading dot also matches subdomains.
+func matchesHost(patterns []string, host string) bool {
+	for _, pattern := range patterns {
+		if strings.EqualFold(host, strings.TrimPrefix(pattern, ".")) {
+			return true
+		}
+		if strings.HasPrefix(pattern, ".") && len(host) > len(pattern) && strings.EqualFold(host[len(host)-len(pattern):], pattern) {
+			return true
+		}
+	}
+	return false
+}
+
+func isMetadataHost(host string) bool {
+	for _, h := range metadataHosts {
+		if host == h {
+			return true
+		}
+	}
+	return false
+}
+
+// isInternalIP reports whether ip is not publicly routable.
+func isInternalIP(ip net.IP) bool {
+	return ip.IsLoopback() || ip.IsPrivate() || ip.IsLinkLocalUnicast() || ip.IsLinkLocalMulticast() ||
+		ip.IsInterfaceLocalMulticast() || ip.IsUnspecified() || sharedAddressSpace.Contains(ip)
+}
+
+func containsIP(nets []*net.IPNet, ip net.IP) bool {
+	for _, n := range nets {
+		if n.Contains(ip) {
+			return true
+		}
+	}
+	return false
+}
+
+func parseCIDRs(values []string) ([]*net.IPNet, error) {
+	var nets []*net.IPNet
+	for _, value := range values {
+		// Bare addresses are treated as single-host ranges
+		if !strings.Contains(value, "/") {
+			if ip := net.ParseIP(value); ip != nil && ip.To4() != nil {
+				value += "/32"
+			} else {
+				value += "/128"
+			}
+		}
+		_, n, err := net.ParseCIDR(value)
+		if err != nil {
+			return nil, err
+		}
+		nets = append(nets, n)
+	}
+	return nets, nil
+}
+
+func mustParseCIDR(value string) *net.IPNet {
+	_, n, err := net.ParseCIDR(value)
+	if err != nil {
+		panic(err)
+	}
+	return n
+}
@@ -0,0 +1,52 @@
+package proxy
+
+import (
+	"fmt"
+	"strings"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+// probeRange requests the first two bytes of a media resource through the
+// executor and records whether the server answered with a matching partial response.
+func (s *HTTPService) probeRange(store storage.Store, request ProxyRequest, mediaURL string, support *RangeSupport) {
+	headers := make(map[string]string, len(request.Headers)+1)
+	for key, value := range request.Headers {
+		if !strings.EqualFold(key, "Range") {
+			headers[key] = value
+		}
+	}
+	headers["Range"] = "bytes=0-1"
+
+	resp := s.ExecuteIn(store, ProxyRequest{
+		Method:   "GET",
+		URL:      mediaURL,
+		Headers:  headers,
+		Timeout:  request.Timeout,
+		PinToken: request.PinToken,
+	})
+	verified := false
+	support.Verified = &verified
+	if !resp.Success {
+		support.Error = strPtr(resp.Error.Message)
+		return
+	}
+
+	support.ProbeStatus = &resp.Data.Status
+	contentRange := ""
+	for _, h := range resp.Data.Headers {
+		if strings.EqualFold(h.Name, "Content-Range") {
+			contentRange = h.Value
+		}
+	}
+	support.ContentRange = optionalString(contentRange)
+
+	switch {
+	case resp.Data.Status != 206:
+		support.Error = strPtr(fmt.Sprintf("Expected 206 Partial Content, got %d", resp.Data.Status))
+	case !strings.HasPrefix(contentRange, "bytes 0-1/"):
+		support.Error = strPtr(fmt.Sprintf("Unexpected Content-Range: %q", contentRange))
+	default:
+		verified = true
+	}
+}
@@ -0,0 +1,129 @@
+package proxy
+
+import (
+	"net/http"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// epochThreshold separates X-RateLimit-Reset values given as Unix
+// timestamps from those given as seconds remaining.
+const epochThreshold = 1_000_000_000
+
+// parseRateLimit reads the IETF RateLimit fields and the common
+// X-RateLimit-* variants. It returns nil when no such header is present.
+func parseRateLimit(headers []HeaderField, now time.Time) *RateLimitInfo {
+	info := &RateLimitInfo{}
+	found := false
+	for _, h := range headers {
+		name := strings.ToLower(h.Name)
+		value := strings.TrimSpace(h.Value)
+		switch name {
+		case "ratelimit-limit", "x-ratelimit-limit", "x-rate-limit-limit":
+			info.Limit = leadingInt(value)
+		case "ratelimit-remaining", "x-ratelimit-remaining", "x-rate-limit-remaining":
+			info.Remaining = leadingInt(value)
+		case "ratelimit-reset", "x-ratelimit-reset", "x-rate-limit-reset":
+			info.Reset = resetSeconds(value, now)
+		case "x-ratelimit-used":
+			info.Used = leadingInt(value)
+		case "ratelimit":
+			// Combined field: limit=100, remaining=50, reset=30 or "name";r=50;t=30
+			params := rateLimitParams(value)
+			info.Limit = firstInt(info.Limit, params["limit"])
+			info.Remaining = firstInt(info.Remaining, params["remaining"], params["r"])
+			info.Reset = firstInt(info.Reset, params["reset"], params["t"])
+		case "ratelimit-policy", "x-ratelimit-policy":
+			info.Policy = strPtr(value)
+			params := rateLimitParams(value)
+			info.Limit = firstInt(info.Limit, params["q"], params[""])
+			info.Window = firstInt(info.Window, params["w"])
+		case "retry-after":
+			info.RetryAfter = retryAfterSeconds(value, now)
+		default:
+			continue
+		}
+		if strings.HasPrefix(name, "x-") {
+			info.Source = "x-ratelimit"
+		} else if name != "retry-after" && info.Source == "" {
+			info.Source = "ietf"
+		}
+		found = true
+	}
+	if !found {
+		return nil
+	}
+	return info
+}
+
+// rateLimitParams collects the numeric parameters of a RateLimit or
+// RateLimit-Policy value. A bare leading number is stored under "".
+func rateLimitParams(value string) map[string]*int {
+	params := map[string]*int{}
+	for _, item := range strings.FieldsFunc(value, func(r rune) bool { return r == ',' || r == ';' }) {
+		key, v, ok := strings.Cut(strings.TrimSpace(item), "=")
+		if !ok {
+			if n := leadingInt(key); n != nil && params[""] == nil {
+				params[""] = n
+			}
+			continue
+		}
+		if n := leadingInt(strings.Trim(v, `"`)); n != nil {
+			params[strings.ToLower(strings.TrimSpace(key))] = n
+		}
+	}
+	return params
+}
+
+// resetSeconds converts a reset value to seconds from now, accepting both
+// delta seconds and Unix timestamps.
+func resetSeconds(value string, now time.Time) *int {
+	n, err := strconv.ParseFloat(value, 64)
+	if err != nil {
+		return nil
+	}
+	seconds := int(n)
+	if seconds >= epochThreshold {
+		seconds = max(int(time.Unix(int64(n), 0).Sub(now).Seconds()), 0)
+	}
+	return &seconds
+}
+
+// retryAfterSeconds parses Retry-After as delta seconds or an HTTP date.
+func retryAfterSeconds(value string, now time.Time) *int {
+	if n, err := strconv.Atoi(value); err == nil {
+		return &n
+	}
+	if t, err := http.ParseTime(value); err == nil {
+		seconds := max(int(t.Sub(now).Seconds()), 0)
+		return &seconds
+	}
+	return nil
+}
+
+// leadingInt parses the integer at the start of a value, ignoring trailing
+// parameters such as "100, 100;w=60".
+func leadingInt(value string) *int {
+	end := 0
+	for end < len(value) && value[end] >= '0' && value[end] <= '9' {
+		end++
+	}
+	if end == 0 {
+		return nil
+	}
+	n, err := strconv.Atoi(value[:end])
+	if err != nil {
+		return nil
+	}
+	return &n
+}
+
+func firstInt(values ...*int) *int {
+	for _, v := range values {
+		if v != nil {
+			return v
+		}
+	}
+	return nil
+}
@@ -0,0 +1,279 @@
+package proxy
+
+import (
+	"bytes"
+	"encoding/json"
+	"net/url"
+	"regexp"
+	"sort"
+	"strconv"
+	"strings"
+)
+
+// DefaultRedactedNames are the header, query parameter and body field names
+// whose values are replaced by variables in redacted exports. Matching
+// ignores case, and "-" and "_" are interchangeable.
+var DefaultRedactedNames = []string{
+	"Authorization", "Proxy-Authorization", "Cookie", "X-Api-Key", "Api-Key", "X-Auth-Token",
+	"X-Access-Token", "X-Csrf-Token", "X-Xsrf-Token", "Access-Token", "Refresh-Token", "Id-Token",
+	"Client-Secret", "Token", "Password", "Passwd", "Secret", "Key", "Signature", "Sig",
+}
+
+// redactSentinel delimits variable references in rendered arguments. NUL
+// cannot occur in a command-line argument, so it never clashes with values.
+const redactSentinel = "\x00"
+
+// redactedAuthScheme splits the scheme from Authorization values, so the
+// placeholder replaces only the credentials.
+var redactedAuthScheme = regexp.MustCompile(`^(?i)(Basic|Bearer|Digest|Negotiate|NTLM|Token|ApiKey)\s+`)
+
+// CurlExport is a curl command with its credentials replaced by shell variables.
+type CurlExport struct {
+	Command   string             `json:"command"`
+	Preamble  string             `json:"preamble"` // export lines declaring the variables, empty when nothing was redacted
+	Variables []RedactedVariable `json:"variables"`
+}
+
+// RedactedVariable is a shell variable standing in for a credential.
+type RedactedVariable struct {
+	Name   string `json:"name"`   // e.g. TOKEN
+	Source string `json:"source"` // What it replaced, e.g. "header Authorization"
+}
+
+// redactor replaces credentials of a request by variable references.
+type redactor struct {
+	names     map[string]bool
+	variables []RedactedVariable
+	used      map[string]bool
+}
+
+func newRedactor(names []string) *redactor {
+	r := &redactor{names: map[string]bool{}, used: map[string]bool{}}
+	for _, name := range names {
+		r.names[normalizeRedactedName(name)] = true
+	}
+	return r
+}
+
+func normalizeRedactedName(name string) string {
+	return strings.ReplaceAll(strings.ToLower(strings.TrimSpace(name)), "_", "-")
+}
+
+func (r *redactor) redacts(name string) bool {
+	return r.names[normalizeRedactedName(name)]
+}
+
+// variable declares a variable for a credential and returns its reference.
+func (r *redactor) variable(name, source string) string {
+	name = strings.Trim(strings.Map(func(c rune) rune {
+		switch {
+		case c >= 'a' && c <= 'z':
+			return c - 'a' + 'A'
+		case c >= 'A' && c <= 'Z', c >= '0' && c <= '9':
+			return c
+		default:
+			return '_'
+		}
+	}, name), "_")
+	if name == "" || name[0] >= '0' && name[0] <= '9' {
+		name = "SECRET_" + name
+	}
+	unique := name
+	for i := 2; r.used[unique]; i++ {
+		unique = name + "_" + strconv.Itoa(i)
+	}
+	r.used[unique] = true
+	r.variables = append(r.variables, RedactedVariable{Name: unique, Source: source})
+	return redactSentinel + unique + redactSentinel
+}
+
+// header redacts a header value, keeping an Authorization scheme in clear.
+func (r *redactor) header(name, value string) string {
+	if !r.redacts(name) || value == "" {
+		return value
+	}
+	if scheme := redactedAuthScheme.FindString(value); scheme != "" {
+		variable := "TOKEN"
+		if strings.EqualFold(strings.TrimSpace(scheme), "Basic") {
+			variable = "BASIC_AUTH"
+		}
+		if !strings.EqualFold(name, "Authorization") {
+			variable = name
+		}
+		return scheme + r.variable(variable, "header "+name)
+	}
+	return r.variable(name, "header "+name)
+}
+
+// url redacts the password and sensitive query parameters of a URL.
+func (r *redactor) url(raw string) string {
+	u, err := url.Parse(raw)
+	if err != nil {
+		return raw
+	}
+	changed := false
+	if password, ok := u.User.Password(); ok && password != "" {
+		u.User = url.UserPassword(u.User.Username(), r.variable("URL_PASSWORD", "URL password"))
+		changed = true
+	}
+	if u.RawQuery != "" {
+		pairs := strings.Split(u.RawQuery, "&")
+		for i, pair := range pairs {
+			key, value, _ := strings.Cut(pair, "=")
+			name, err := url.QueryUnescape(key)
+			if err != nil || value == "" || !r.redacts(name) {
+				continue
+			}
+			pairs[i] = key + "=" + r.variable(name, "query parameter "+name)
+			changed = true
+		}
+		u.RawQuery = strings.Join(pairs, "&")
+	}
+	if !changed {
+		return raw
+	}
+	// String escapes the sentinels of the password; restore them
+	return strings.ReplaceAll(u.String(), "%00", redactSentinel)
+}
+
+// jsonBody redacts sensitive string fields of a JSON body, at any depth.
+// Bodies that are not JSON are returned unchanged.
+func (r *redactor) jsonBody(body string) string {
+	// Numbers stay as written
+	decoder := json.NewDecoder(strings.NewReader(body))
+	decoder.UseNumber()
+	var value interface{}
+	if decoder.Decode(&value) != nil {
+		return body
+	}
+	changed := false
+	var walk func(v interface{}) interface{}
+	walk = func(v interface{}) interface{} {
+		switch v := v.(type) {
+		case map[string]interface{}:
+			keys := make([]string, 0, len(v))
+			for key := range v {
+				keys = append(keys, key)
+			}
+			sort.Strings(keys)
+			for _, key := range keys {
+				field := v[key]
+				if s, ok := field.(string); ok && s != "" && r.redacts(key) {
+					v[key] = r.variable(key, "body field "+key)
+					changed = true
+					continue
+				}
+				v[key] = walk(field)
+			}
+		case []interface{}:
+			for i, item := range v {
+				v[i] = walk(item)
+			}
+		}
+		return v
+	}
+	walk(value)
+	if !changed {
+		return body
+	}
+	var encoded bytes.Buffer
+	encoder := json.NewEncoder(&encoded)
+	encoder.SetEscapeHTML(false)
+	if err := encoder.Encode(value); err != nil {
+		return body
+	}
+	// The encoder escapes the sentinels; restore them
+	return strings.ReplaceAll(strings.TrimSuffix(encoded.String(), "\n"), `\u0000`, redactSentinel)
+}
+
+// RedactedCurl renders a request as a curl command with its credentials
+// replaced by shell variables, redacting DefaultRedactedNames and the names
+// added by Options.RedactNames.
+func (s *HTTPService) RedactedCurl(request ProxyRequest) CurlExport {
+	names := append(append([]string{}, DefaultRedactedNames...), *s.redactNames.Load()...)
+	return request.ToRedactedCurl(names)
+}
+
+// ToRedactedCurl renders the request like ToCurl with the values of
+// sensitive headers, query parameters, body fields and credentials replaced
+// by shell variables, so the command can be shared without leaking them.
+// names lists what to redact; nil uses DefaultRedactedNames.
+func (r ProxyRequest) ToRedactedCurl(names []string) CurlExport {
+	if names == nil {
+		names = DefaultRedactedNames
+	}
+	red := newRedactor(names)
+
+	redacted := r
+	redacted.URL = red.url(r.URL)
+	if len(r.Headers) > 0 {
+		// Sorted so repeated exports name their variables alike
+		headerNames := make([]string, 0, len(r.Headers))
+		for name := range r.Headers {
+			headerNames = append(headerNames, name)
+		}
+		sort.Strings(headerNames)
+		redacted.Headers = make(map[string]string, len(r.Headers))
+		for _, name := range headerNames {
+			redacted.Headers[name] = red.header(name, r.Headers[name])
+		}
+	}
+	if r.Body != nil {
+		body := red.jsonBody(*r.Body)
+		redacted.Body = &body
+	}
+	if len(r.FormData) > 0 {
+		redacted.FormData = make([]FormPart, len(r.FormData))
+		for i, part := range r.FormData {
+			if part.Value != nil && *part.Value != "" && part.FileBase64 == nil && red.redacts(part.Name) {
+				value := red.variable(part.Name, "form field "+part.Name)
+				part.Value = &value
+			}
+			redacted.FormData[i] = part
+		}
+	}
+	if a := r.Auth; a != nil {
+		auth := *a
+		if auth.Password != "" {
+			auth.Password = red.variable("PASSWORD", "auth password")
+		}
+		if auth.Token != "" {
+			auth.Token = red.variable("TOKEN", "auth token")
+		}
+		redacted.Auth = &auth
+	}
+
+	export := CurlExport{Command: redacted.ToCurl(), Variables: red.variables}
+	if export.Variables == nil {
+		export.Variables = []RedactedVariable{}
+	}
+	if len(red.variables) > 0 {
+		lines := []string{"# Set these before running the command"}
+		for _, v := range red.variables {
+			lines = append(lines, "export "+v.Name+"=''  # "+v.Source)
+		}
+		export.Preamble = strings.Join(lines, "\n")
+	}
+	return export
+}
+
+// quoteWithVariables double-quotes a value holding variable references, so
+// the shell expands them while the rest stays literal.
+func quoteWithVariables(s string) string {
+	var b strings.Builder
+	b.WriteByte('"')
+	for i, part := range strings.Split(s, redactSentinel) {
+		if i%2 == 1 {
+			b.WriteString("${" + part + "}")
+			continue
+		}
+		for _, c := range part {
+			if strings.ContainsRune("\"\\$`", c) {
+				b.WriteByte('\\')
+			}
+			b.WriteRune(c)
+		}
+	}
+	b.WriteByte('"')
+	return b.String()
+}
@@ -0,0 +1,120 @@
+package proxy
+
+import (
+	"fmt"
+	"net/http"
+	"net/url"
+	"strings"
+)
+
+// bodyHeaders describe a request body and are dropped along with it.
+var bodyHeaders = []string{"Content-Type", "Content-Length", "Content-Encoding", "Content-Language", "Content-Location"}
+
+// credentialHeaders are dropped on cross-origin redirects, as browsers do.
+var credentialHeaders = []string{"Authorization", "Cookie"}
+
+// redirectMethod returns the method used to follow a redirect and whether the
+// body is resent, following the Fetch standard. Strict mode keeps both unchanged.
+func redirectMethod(status int, method string, strict bool) (string, bool) {
+	if strict {
+		return method, true
+	}
+
+	switch status {
+	case http.StatusSeeOther:
+		if method == http.MethodHead {
+			return method, false
+		}
+		return http.MethodGet, false
+	case http.StatusMovedPermanently, http.StatusFound:
+		if method == http.MethodPost {
+			return http.MethodGet, false
+		}
+	}
+	return method, true
+}
+
+// ResolveRedirect returns the URL a redirect from base to location leads
+// to, as the executor follows it.
+func ResolveRedirect(base, location string) (string, error) {
+	ctx, err := newRequestContext(base)
+	if err != nil {
+		return "", err
+	}
+	return ctx.updateFromRedirect(location), nil
+}
+
+// redirectMessage describes a followed redirect and any change to the request.
+func redirectMessage(nextURL, method, nextMethod string, bodyDropped bool) string {
+	message := fmt.Sprintf("Redirect to: %s", nextURL)
+
+	var changes []string
+	if nextMethod != method {
+		changes = append(changes, fmt.Sprintf("%s changed to %s", method, nextMethod))
+	}
+	if bodyDropped {
+		changes = append(changes, "body dropped")
+	}
+	if len(changes) > 0 {
+		message += " (" + strings.Join(changes, ", ") + ")"
+	}
+	return message
+}
+
+// withoutBodyHeaders copies headers, leaving out those describing the body.
+func withoutBodyHeaders(headers map[string]string) map[string]string {
+	return withoutHeaders(headers, bodyHeaders)
+}
+
+// withoutHeaders copies headers, leaving out the given names.
+func withoutHeaders(headers map[string]string, names []string) map[string]string {
+	kept := make(map[string]string, len(headers))
+	for key, value := range headers {
+		if !hasHeader(names, key) {
+			kept[key] = value
+		}
+	}
+	return kept
+}
+
+// hasCredentials reports whether headers include Authorization or Cookie.
+func hasCredentials(headers map[string]string) bool {
+	for key := range headers {
+		if hasHeader(credentialHeaders, key) {
+			return true
+		}
+	}
+	return false
+}
+
+// credentialsSent reports whether the credentials given in original were
+// among the headers sent, or nil when there were none to send.
+func credentialsSent(original, sent map[string]string) *bool {
+	if !hasCredentials(original) {
+		return nil
+	}
+	forwarded := hasCredentials(sent)
+	return &forwarded
+}
+
+func hasHeader(names []string, key string) bool {
+	for _, name := range names {
+		if strings.EqualFold(key, name) {
+			return true
+		}
+	}
+	return false
+}
+
+// sameOrigin reports whether two URLs share scheme, host and port.
+func sameOrigin(a, b string) bool {
+	first, err := url.Parse(a)
+	if err != nil {
+		return false
+	}
+	second, err := url.Parse(b)
+	if err != nil {
+		return false
+	}
+	return origin(first) == origin(second)
+}
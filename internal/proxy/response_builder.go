@@ -12,20 +12,40 @@ import (
 
 // responseBuildParams contains parameters for building a proxy response.
 type responseBuildParams struct {
-	status          uint16
-	headers         map[string]string
-	bodyBytes       []byte
-	timing          *DetailedTiming
-	finalURL        string
-	redirectChain   []RedirectHop
-	tlsInfo         *infra.CertInfo
-	httpVersion     string
-	serverIP        string
-	requestHeaders  map[string]string
-	requestBodySize *int
-	hostname        string
-	port            string
-	resolvedIPs     []string
+	status              uint16
+	headers             map[string]string
+	bodyBytes           []byte
+	bodyTruncated       bool  // bodyBytes stopped at maxBodyBytes
+	contentLength       int64 // -1 when not announced
+	maxBodyBytes        int
+	integrity           *string
+	timing              *DetailedTiming
+	finalURL            string
+	redirectChain       []RedirectHop
+	tlsInfo             *infra.CertInfo
+	httpVersion         string
+	serverIP            string
+	requestMethod       string
+	requestHeaders      map[string]string
+	requestBodySize     *int
+	requestBodyEncoding string
+	renderedRequest     *string
+	cookieJar           *CookieJarInfo
+	rawRequest          *RawRequest
+	headerFields        []HeaderField
+	rawHead             []byte
+	captureRaw          bool
+	beautify            bool
+	hexdumpBytes        *int
+	bodyStatsThreshold  *int
+	hostname            string
+	port                string
+	resolvedIPs         []string
+	pinToken            string
+	dnsPinned           bool
+	proxyDecision       *ProxyDecision
+	integratedAuth      *IntegratedAuthInfo
+	trailers            []HeaderField
 }
 
 // isBinaryContent determines if response body is likely binary based on content-type.
@@ -65,7 +85,7 @@ func buildResponse(params responseBuildParams) ProxyResponse {
 
 	// Decompress if needed
 	compressedSize := len(params.bodyBytes)
-	decompressResult, err := infra.Decompress(params.bodyBytes, contentEncoding)
+	decompressResult, err := infra.DecompressLimit(params.bodyBytes, contentEncoding, params.maxBodyBytes, params.bodyTruncated)
 	if err != nil {
 		return NewErrorResponse(fmt.Sprintf("Decompression failed: %v", err), "DECOMPRESSION_ERROR")
 	}
@@ -75,18 +95,49 @@ func buildResponse(params responseBuildParams) ProxyResponse {
 	// Convert body
 	var body string
 	var bodyBase64 *string
+	var hexdump *string
+	var archive *ArchiveInfo
+	var media *MediaInfo
 	if isBinary {
 		b64 := base64.StdEncoding.EncodeToString(decompressed)
 		bodyBase64 = &b64
+		hexdump = bodyHexdump(decompressed, params.hexdumpBytes)
+		archive = buildArchiveInfo(infra.ListArchive(decompressed))
+		media = buildDocumentInfo(infra.ExtractDocumentInfo(decompressed))
+		if media == nil {
+			media = buildStreamInfo(infra.ProbeMedia(decompressed), params.headers["accept-ranges"])
+		}
 	} else {
 		body = string(decompressed)
 	}
 
+	var beautified *BeautifiedBody
+	if params.beautify && !isBinary {
+		beautified = beautifyBody(body, contentType)
+	}
+
+	var analysis *ResponseAnalysis
+	var feed *FeedPreview
+	var soap *SOAPInfo
+	if !isBinary && len(decompressed) > 0 {
+		analysis = analyzeBody(decompressed, body, contentType, params.headers["content-language"])
+		if isFeedContentType(contentType) {
+			feed = buildFeedPreview(infra.ParseFeed(decompressed))
+		}
+		// SOAP 1.1 uses text/xml and SOAP 1.2 application/soap+xml
+		if strings.Contains(strings.ToLower(contentType), "xml") {
+			soap = buildSOAPInfo(infra.ParseSOAP(decompressed))
+		}
+	}
+
 	// Calculate sizes
-	statusLine := fmt.Sprintf("%s %d %s", params.httpVersion, params.status, statustext.Get(int(params.status)))
-	headerSize := len(statusLine) + 2
-	for k, v := range params.headers {
-		headerSize += len(k) + 2 + len(v) + 2
+	headerSize := len(params.rawHead)
+	if params.rawHead == nil {
+		statusLine := fmt.Sprintf("%s %d %s", params.httpVersion, params.status, statustext.Get(int(params.status)))
+		headerSize = len(statusLine) + 2
+		for _, field := range params.headerFields {
+			headerSize += len(field.Name) + 2 + len(field.Value) + 2
+		}
 	}
 
 	var compressionRatio *float64
@@ -111,21 +162,12 @@ func buildResponse(params responseBuildParams) ProxyResponse {
 		Encoding:         encoding,
 		CompressionRatio: compressionRatio,
 	}
-
-	// Build TLS info
-	var tlsInfoData *TLSInfo
-	if params.tlsInfo != nil {
-		valid := infra.IsCertValid(params.tlsInfo.ValidFrom, params.tlsInfo.ValidTo)
-		tlsInfoData = &TLSInfo{
-			Protocol:  strPtr(params.tlsInfo.Protocol),
-			Cipher:    strPtr(params.tlsInfo.Cipher),
-			Issuer:    strPtr(params.tlsInfo.Issuer),
-			Subject:   strPtr(params.tlsInfo.Subject),
-			ValidFrom: &params.tlsInfo.ValidFrom,
-			ValidTo:   &params.tlsInfo.ValidTo,
-			Valid:     &valid,
-			SANs:      params.tlsInfo.SANs,
-		}
+	for _, layer := range decompressResult.Layers {
+		sizeBreakdown.EncodingLayers = append(sizeBreakdown.EncodingLayers, EncodingLayer{
+			Encoding:   layer.Encoding,
+			InputSize:  layer.InputSize,
+			OutputSize: layer.OutputSize,
+		})
 	}
 
 	serverSoftware := params.headers["server"]
@@ -157,41 +199,336 @@ func buildResponse(params responseBuildParams) ProxyResponse {
 	fromCache := false
 	resourceType := "fetch"
 
+	var pinTokenPtr *string
+	var dnsPinnedPtr *bool
+	if params.pinToken != "" {
+		pinTokenPtr = &params.pinToken
+		dnsPinnedPtr = &params.dnsPinned
+	}
+
+	var requestBodyEncodingPtr *string
+	if params.requestBodyEncoding != "" {
+		requestBodyEncodingPtr = &params.requestBodyEncoding
+	}
+
+	var rawHeadPtr *string
+	if params.captureRaw && params.rawHead != nil {
+		rawHead := string(params.rawHead)
+		rawHeadPtr = &rawHead
+	}
+
+	altSvc := parseAltSvc(params.headers["alt-svc"])
+
+	var issuer string
+	if params.tlsInfo != nil {
+		issuer = params.tlsInfo.Issuer
+	}
+	servedBy := detectServedBy(params.headers, issuer, params.serverIP)
+
+	// A truncated body cannot match its digests
+	var integrity *BodyIntegrity
+	if !decompressResult.Truncated {
+		integrity = bodyIntegrity(params.bodyBytes, decompressed, params.headers, params.integrity)
+	}
+
+	var contentLength *int64
+	if params.contentLength >= 0 {
+		contentLength = &params.contentLength
+	}
+
 	var redirectChainPtr []RedirectHop
 	if len(params.redirectChain) > 0 {
 		redirectChainPtr = params.redirectChain
 	}
 
+	// Digest challenges are hops to the same URL, not redirects
+	redirected := false
+	for _, hop := range params.redirectChain {
+		redirected = redirected || hop.Status >= 300 && hop.Status < 400
+	}
+
 	data := ResponseData{
-		Status:          params.status,
-		StatusText:      statustext.Get(int(params.status)),
-		Headers:         params.headers,
-		RequestHeaders:  params.requestHeaders,
-		Body:            body,
-		BodyBase64:      bodyBase64,
-		IsBinary:        isBinary,
-		Size:            bodySize,
-		Timing:          params.timing.ToTimingInfo(),
-		URL:             params.finalURL,
-		Redirected:      len(params.redirectChain) > 0,
-		RedirectChain:   redirectChainPtr,
-		TLS:             tlsInfoData,
-		SizeBreakdown:   sizeBreakdown,
-		ServerIP:        serverIPPtr,
-		Protocol:        &params.httpVersion,
-		FromCache:       &fromCache,
-		ResourceType:    &resourceType,
-		RequestBodySize: params.requestBodySize,
-		Connection:      connectionPtr,
-		ServerSoftware:  serverSoftwarePtr,
-		Hostname:        hostnamePtr,
-		Port:            portPtr,
-		ResolvedIPs:     params.resolvedIPs,
+		Status:              params.status,
+		StatusText:          statustext.Get(int(params.status)),
+		Headers:             params.headerFields,
+		RequestMethod:       strPtr(params.requestMethod),
+		RequestHeaders:      params.requestHeaders,
+		Body:                body,
+		BodyBase64:          bodyBase64,
+		BodyHexdump:         hexdump,
+		IsBinary:            isBinary,
+		Size:                bodySize,
+		BodyTruncated:       decompressResult.Truncated,
+		ContentLength:       contentLength,
+		Integrity:           integrity,
+		Timing:              params.timing.ToTimingInfo(),
+		URL:                 params.finalURL,
+		Redirected:          redirected,
+		RedirectChain:       redirectChainPtr,
+		TLS:                 buildTLSInfo(params.tlsInfo),
+		SizeBreakdown:       sizeBreakdown,
+		ServerIP:            serverIPPtr,
+		Protocol:            &params.httpVersion,
+		FromCache:           &fromCache,
+		ResourceType:        &resourceType,
+		RequestBodySize:     params.requestBodySize,
+		RequestBodyEncoding: requestBodyEncodingPtr,
+		RenderedRequest:     params.renderedRequest,
+		Connection:          connectionPtr,
+		ServerSoftware:      serverSoftwarePtr,
+		Hostname:            hostnamePtr,
+		Port:                portPtr,
+		ResolvedIPs:         params.resolvedIPs,
+		PinToken:            pinTokenPtr,
+		DNSPinned:           dnsPinnedPtr,
+		AltSvc:              altSvc,
+		HTTP3Offered:        offersHTTP3(altSvc),
+		ProxyDecision:       params.proxyDecision,
+		IntegratedAuth:      params.integratedAuth,
+		Trailers:            params.trailers,
+		ServedBy:            servedBy,
+		CookieJar:           params.cookieJar,
+		RawRequest:          params.rawRequest,
+		RawResponseHead:     rawHeadPtr,
+		Beautified:          beautified,
+		ArchiveInfo:         archive,
+		MediaInfo:           media,
+		Analysis:            analysis,
+		Feed:                feed,
+		SOAP:                soap,
+		RateLimit:           parseRateLimit(params.headerFields, time.Now()),
+		BodyStats:           bodyStats(decompressed, contentType, isBinary, params.bodyStatsThreshold),
 	}
 
+	data.Summary = summarize(&data)
 	return NewSuccessResponse(data)
 }
 
+// beautifyBody pretty-prints minified JavaScript and CSS bodies up to infra.MaxBeautifySize.
+func beautifyBody(body, contentType string) *BeautifiedBody {
+	if len(body) > infra.MaxBeautifySize {
+		return nil
+	}
+
+	ct := strings.ToLower(contentType)
+	var language, pretty string
+	switch {
+	case strings.Contains(ct, "javascript") || strings.Contains(ct, "ecmascript"):
+		language, pretty = "javascript", infra.BeautifyJS(body)
+	case strings.Contains(ct, "text/css"):
+		language, pretty = "css", infra.BeautifyCSS(body)
+	default:
+		return nil
+	}
+
+	return &BeautifiedBody{
+		Language:       language,
+		Body:           pretty,
+		OriginalLength: len(body),
+		Length:         len(pretty),
+	}
+}
+
+// buildArchiveInfo converts an archive listing to its protocol representation.
+func buildArchiveInfo(listing *infra.ArchiveListing) *ArchiveInfo {
+	if listing == nil {
+		return nil
+	}
+
+	info := &ArchiveInfo{
+		Format:       listing.Format,
+		Entries:      make([]ArchiveEntry, len(listing.Entries)),
+		TotalEntries: listing.TotalEntries,
+		Truncated:    listing.Truncated,
+	}
+	for i, e := range listing.Entries {
+		info.Entries[i] = ArchiveEntry{
+			Name:           e.Name,
+			Size:           e.Size,
+			CompressedSize: e.CompressedSize,
+			Dir:            e.Dir,
+		}
+		if e.Modified != nil {
+			modified := uint64(e.Modified.Unix())
+			info.Entries[i].Modified = &modified
+		}
+	}
+	if listing.Err != nil {
+		info.Error = strPtr(listing.Err.Error())
+	}
+	return info
+}
+
+// isFeedContentType reports whether a body may be a feed. Many feeds are served
+// as generic XML, so the document element decides.
+func isFeedContentType(contentType string) bool {
+	ct := strings.ToLower(contentType)
+	return strings.Contains(ct, "xml") || strings.Contains(ct, "rss") || strings.Contains(ct, "atom")
+}
+
+// buildFeedPreview converts a parsed feed to its protocol representation.
+func buildFeedPreview(feed *infra.Feed) *FeedPreview {
+	if feed == nil {
+		return nil
+	}
+
+	preview := &FeedPreview{
+		Format:      feed.Format,
+		Version:     optionalString(feed.Version),
+		Title:       feed.Title,
+		Link:        optionalString(feed.Link),
+		Description: optionalString(feed.Description),
+		Updated:     optionalString(feed.Updated),
+		Items:       make([]FeedItem, len(feed.Items)),
+		TotalItems:  feed.TotalItems,
+		Truncated:   len(feed.Items) < feed.TotalItems,
+		Warnings:    feed.Warnings,
+	}
+	for i, item := range feed.Items {
+		preview.Items[i] = FeedItem{
+			ID:        optionalString(item.ID),
+			Title:     item.Title,
+			Link:      optionalString(item.Link),
+			Published: optionalString(item.Published),
+		}
+	}
+	return preview
+}
+
+// buildSOAPInfo converts a parsed SOAP envelope to its protocol representation.
+func buildSOAPInfo(msg *infra.SOAPMessage) *SOAPInfo {
+	if msg == nil {
+		return nil
+	}
+
+	info := &SOAPInfo{
+		Version:            msg.Version,
+		HasHeader:          msg.HasHeader,
+		Operation:          optionalString(msg.Operation),
+		OperationNamespace: optionalString(msg.OperationNamespace),
+	}
+	if f := msg.Fault; f != nil {
+		info.Fault = &SOAPFault{
+			Code:     f.Code,
+			Subcodes: f.Subcodes,
+			Reason:   f.Reason,
+			Role:     optionalString(f.Role),
+			Node:     optionalString(f.Node),
+			Detail:   optionalString(f.Detail),
+		}
+	}
+	return info
+}
+
+// buildDocumentInfo converts document metadata to its protocol representation.
+func buildDocumentInfo(doc *infra.DocumentInfo) *MediaInfo {
+	if doc == nil {
+		return nil
+	}
+
+	info := &MediaInfo{
+		Format:    doc.Format,
+		Version:   optionalString(doc.Version),
+		Pages:     doc.Pages,
+		Sheets:    doc.Sheets,
+		Slides:    doc.Slides,
+		Title:     optionalString(doc.Title),
+		Author:    optionalString(doc.Author),
+		Subject:   optionalString(doc.Subject),
+		Creator:   optionalString(doc.Creator),
+		Producer:  optionalString(doc.Producer),
+		Created:   optionalString(doc.Created),
+		Modified:  optionalString(doc.Modified),
+		Encrypted: doc.Encrypted,
+	}
+	if len(doc.Metadata) > 0 {
+		info.Metadata = doc.Metadata
+	}
+	if doc.Err != nil {
+		info.Error = strPtr(doc.Err.Error())
+	}
+	return info
+}
+
+// buildStreamInfo converts audio or video metadata to its protocol
+// representation, including the advertised range support.
+func buildStreamInfo(meta *infra.MediaMetadata, acceptRanges string) *MediaInfo {
+	if meta == nil {
+		return nil
+	}
+
+	info := &MediaInfo{
+		Format:   meta.Format,
+		Duration: meta.Duration,
+		Bitrate:  meta.Bitrate,
+		Tracks:   make([]MediaTrack, len(meta.Tracks)),
+		Range: &RangeSupport{
+			AcceptRanges: optionalString(acceptRanges),
+			Advertised:   strings.Contains(strings.ToLower(acceptRanges), "bytes"),
+		},
+	}
+	for i, t := range meta.Tracks {
+		info.Tracks[i] = MediaTrack{
+			Type:       t.Type,
+			Codec:      t.Codec,
+			Width:      t.Width,
+			Height:     t.Height,
+			SampleRate: t.SampleRate,
+			Channels:   t.Channels,
+		}
+	}
+	if meta.Err != nil {
+		info.Error = strPtr(meta.Err.Error())
+	}
+	return info
+}
+
+// buildTLSInfo converts captured certificate details to their protocol representation.
+func buildTLSInfo(info *infra.CertInfo) *TLSInfo {
+	if info == nil {
+		return nil
+	}
+
+	validation := info.Validation
+	valid := validation.Valid()
+	return &TLSInfo{
+		Protocol:  strPtr(info.Protocol),
+		Cipher:    strPtr(info.Cipher),
+		Issuer:    strPtr(info.Issuer),
+		Subject:   strPtr(info.Subject),
+		ValidFrom: &info.ValidFrom,
+		ValidTo:   &info.ValidTo,
+		Valid:     &valid,
+		SANs:      info.SANs,
+		Chain:     buildCertChain(info.Chain),
+		Validation: &CertValidation{
+			HostnameMatch: validation.HostnameMatch,
+			Expired:       validation.Expired,
+			NotYetValid:   validation.NotYetValid,
+			ChainVerified: validation.ChainVerified,
+			Errors:        validation.Errors,
+			Warnings:      validation.Warnings,
+		},
+	}
+}
+
+// buildCertChain converts the captured certificate chain to its protocol representation.
+func buildCertChain(chain []infra.ChainCert) []CertificateInfo {
+	var result []CertificateInfo
+	for _, cert := range chain {
+		result = append(result, CertificateInfo{
+			Subject:      cert.Subject,
+			Issuer:       cert.Issuer,
+			ValidFrom:    cert.ValidFrom,
+			ValidTo:      cert.ValidTo,
+			KeyAlgorithm: cert.KeyAlgorithm,
+			Fingerprint:  cert.Fingerprint,
+			SerialNumber: cert.SerialNumber,
+		})
+	}
+	return result
+}
+
 // uint64Ptr creates a pointer to a uint64.
 func uint64Ptr(v uint64) *uint64 {
 	return &v
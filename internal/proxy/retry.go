@@ -0,0 +1,225 @@
+package proxy
+
+import (
+	"context"
+	"fmt"
+	"math/rand/v2"
+	"net/http"
+	"strconv"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+const (
+	// DefaultRetryAttempts is the number of attempts, including the first, when not given.
+	DefaultRetryAttempts = 3
+	// MaxRetryAttempts caps the attempts of a request.
+	MaxRetryAttempts = 10
+	// DefaultRetryDelayMS is the base delay between attempts in milliseconds.
+	DefaultRetryDelayMS = 500
+	// DefaultRetryMaxDelayMS caps a single delay, including one asked for by Retry-After.
+	DefaultRetryMaxDelayMS = 30_000
+)
+
+// defaultRetryOn is retried when a policy does not list its own conditions.
+var defaultRetryOn = []string{"5xx", "429", "dns", "connect", "timeout"}
+
+// retryErrorClasses are the failure classes a policy may list.
+var retryErrorClasses = wordSet("dns connect timeout busy")
+
+// idempotentMethods may be retried without retryUnsafe, as repeating them
+// has the same effect as sending them once (RFC 9110, section 9.2.2).
+var idempotentMethods = wordSet("GET HEAD PUT DELETE OPTIONS TRACE")
+
+// executeWithRetry executes a request, repeating it as its retry policy asks.
+func (s *HTTPService) executeWithRetry(ctx context.Context, store storage.Store, request ProxyRequest, onEvent func(ServerSentEvent)) ProxyResponse {
+	if request.Retry == nil {
+		return s.execute(ctx, store, request, onEvent)
+	}
+	policy := request.Retry
+	if err := policy.validate(); err != nil {
+		return NewErrorResponse(err.Error(), "INVALID_REQUEST")
+	}
+
+	attempts := min(positiveOr(policy.MaxAttempts, DefaultRetryAttempts), MaxRetryAttempts)
+	method := strings.ToUpper(request.Method)
+	if method == "" && request.GraphQL != nil {
+		method = http.MethodPost
+	} else if method == "" {
+		method = http.MethodGet
+	}
+	unsafe := !policy.RetryUnsafe && !idempotentMethods[method]
+
+	info := &RetryInfo{Attempts: []RetryAttempt{}}
+	var durations []uint64
+	var timings timingSamples
+	var response ProxyResponse
+	for n := 1; ; n++ {
+		started := time.Now()
+		response = s.execute(ctx, store, request, onEvent)
+		attempt := RetryAttempt{Attempt: n, Duration: uint64(time.Since(started).Milliseconds())}
+		durations = append(durations, attempt.Duration)
+		if response.Success {
+			attempt.Status = &response.Data.Status
+			timings.add(response.Data.Timing)
+		} else {
+			attempt.Error = strPtr(response.Error.Message)
+			attempt.Code = strPtr(response.Error.Code)
+		}
+
+		reason := policy.retryReason(response)
+		switch {
+		case reason == "":
+			info.Stopped = "not retryable"
+		case unsafe:
+			info.Stopped = fmt.Sprintf("%s requests are only retried with retryUnsafe", method)
+		case n >= attempts:
+			info.Stopped = "maxAttempts reached"
+		}
+		if info.Stopped != "" {
+			info.Attempts = append(info.Attempts, attempt)
+			break
+		}
+
+		delay, fromHeader := policy.delay(n, response)
+		if delay > time.Duration(positiveOr(policy.MaxDelayMS, DefaultRetryMaxDelayMS))*time.Millisecond {
+			info.Attempts = append(info.Attempts, attempt)
+			info.Stopped = fmt.Sprintf("Retry-After of %s exceeds maxDelayMs", delay)
+			break
+		}
+		attempt.Reason = strPtr(reason)
+		attempt.Delay = uint64Ptr(uint64(delay.Milliseconds()))
+		attempt.RetryAfter = fromHeader
+		info.Attempts = append(info.Attempts, attempt)
+		if !sleepContext(ctx, delay) {
+			info.Stopped = "cancelled"
+			break
+		}
+	}
+
+	if len(durations) > 1 {
+		info.Latency = latencyDistribution(durations)
+		info.Phases = timings.timingPhases()
+	}
+	if response.Success {
+		response.Data.Retry = info
+	} else {
+		response.Error.Retry = info
+	}
+	return response
+}
+
+// validate checks the retry conditions and backoff strategy of a policy.
+func (p *RetryPolicy) validate() error {
+	if p.Backoff != nil {
+		switch *p.Backoff {
+		case "constant", "linear", "exponential":
+		default:
+			return fmt.Errorf("unknown backoff %q: use constant, linear or exponential", *p.Backoff)
+		}
+	}
+	for _, condition := range p.RetryOn {
+		if _, _, ok := parseStatusCondition(condition); !ok && !retryErrorClasses[strings.ToLower(condition)] {
+			return fmt.Errorf("unknown retryOn condition %q: use a status such as 503, a class such as 5xx, or dns, connect, timeout or busy", condition)
+		}
+	}
+	return nil
+}
+
+// retryReason returns the condition a response matched, or "" when it
+// should not be retried.
+func (p *RetryPolicy) retryReason(response ProxyResponse) string {
+	conditions := p.RetryOn
+	if len(conditions) == 0 {
+		conditions = defaultRetryOn
+	}
+	class := ""
+	if !response.Success {
+		class = errorClass(response.Error)
+	}
+	for _, condition := range conditions {
+		condition = strings.ToLower(condition)
+		if !response.Success {
+			if condition == class {
+				return condition
+			}
+			continue
+		}
+		if low, high, ok := parseStatusCondition(condition); ok && int(response.Data.Status) >= low && int(response.Data.Status) <= high {
+			return condition
+		}
+	}
+	return ""
+}
+
+// parseStatusCondition reads a status code such as 503 or a class such as
+// 5xx as an inclusive range.
+func parseStatusCondition(condition string) (int, int, bool) {
+	condition = strings.ToLower(condition)
+	if len(condition) == 3 && strings.HasSuffix(condition, "xx") && condition[0] >= '1' && condition[0] <= '5' {
+		low := int(condition[0]-'0') * 100
+		return low, low + 99, true
+	}
+	if code, err := strconv.Atoi(condition); err == nil && code >= 100 && code <= 599 {
+		return code, code, true
+	}
+	return 0, 0, false
+}
+
+// errorClass sorts a failed execution into the classes a policy may retry.
+func errorClass(err *ErrorData) string {
+	switch err.Code {
+	case "DNS_ERROR":
+		return "dns"
+	case "BUSY":
+		return "busy"
+	case "REQUEST_FAILED":
+		message := strings.ToLower(err.Message)
+		if strings.Contains(message, "timeout") || strings.Contains(message, "deadline exceeded") {
+			return "timeout"
+		}
+		return "connect"
+	}
+	return ""
+}
+
+// delay returns how long to wait before attempt n+1, and whether a
+// Retry-After header asked for it.
+func (p *RetryPolicy) delay(n int, response ProxyResponse) (time.Duration, bool) {
+	if response.Success && (p.HonorRetryAfter == nil || *p.HonorRetryAfter) {
+		if value := headerValue(response.Data.Headers, "Retry-After"); value != "" {
+			if seconds := retryAfterSeconds(value, time.Now()); seconds != nil {
+				return time.Duration(*seconds) * time.Second, true
+			}
+		}
+	}
+
+	base := time.Duration(positiveOr(p.DelayMS, DefaultRetryDelayMS)) * time.Millisecond
+	delay := base
+	switch {
+	case p.Backoff != nil && *p.Backoff == "constant":
+	case p.Backoff != nil && *p.Backoff == "linear":
+		delay = base * time.Duration(n)
+	default:
+		delay = base << min(n-1, 16)
+	}
+	delay = min(delay, time.Duration(positiveOr(p.MaxDelayMS, DefaultRetryMaxDelayMS))*time.Millisecond)
+	if p.Jitter {
+		delay = delay/2 + rand.N(delay/2+1)
+	}
+	return delay, false
+}
+
+// sleepContext waits for d, reporting false when ctx is cancelled first.
+func sleepContext(ctx context.Context, d time.Duration) bool {
+	timer := time.NewTimer(d)
+	defer timer.Stop()
+	select {
+	case <-timer.C:
+		return true
+	case <-ctx.Done():
+		return false
+	}
+}
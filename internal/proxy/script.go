@@ -0,0 +1,109 @@
+package proxy
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/history"
+	"zone.digit.tommie/internal/script"
+	"zone.digit.tommie/internal/storage"
+)
+
+// EvaluateScript runs a sandboxed JavaScript over a stored response, for
+// ad-hoc transformations such as extracting or aggregating fields of a JSON
+// body. Scripts have no access to the network or the filesystem, and stop
+// when ctx is cancelled.
+func (s *HTTPService) EvaluateScript(ctx context.Context, store storage.Store, request ScriptRequest) ScriptResponse {
+	if strings.TrimSpace(request.Script) == "" {
+		return scriptError("Script is required", "INVALID_REQUEST", nil)
+	}
+	limits := script.DefaultLimits
+	if request.Timeout != nil && *request.Timeout > 0 {
+		limits.Timeout = min(time.Duration(*request.Timeout)*time.Millisecond, limits.Timeout)
+	}
+
+	globals := map[string]interface{}{
+		"response": nil,
+		"status":   nil,
+		"headers":  map[string]interface{}{},
+		"body":     nil,
+		"json":     nil,
+		"timing":   nil,
+		"entry":    nil,
+		"vars":     request.Variables,
+	}
+	if r := request.Response; r != nil {
+		setResponseGlobals(globals, r)
+	}
+	if request.HistoryID != nil {
+		entry, err := history.Get(store, *request.HistoryID)
+		if err != nil {
+			return scriptError(err.Error(), "STORAGE_ERROR", nil)
+		}
+		if entry == nil {
+			return scriptError("History entry not found", "HISTORY_NOT_FOUND", nil)
+		}
+		globals["entry"] = entry
+	}
+
+	started := time.Now()
+	result, err := script.Eval(ctx, request.Script, globals, limits)
+	if err != nil {
+		var scriptErr *script.Error
+		if errors.As(err, &scriptErr) {
+			return scriptError(scriptErr.Message, "SCRIPT_ERROR", scriptErr.Pos)
+		}
+		return scriptError(err.Error(), "SCRIPT_ERROR", nil)
+	}
+	return ScriptResponse{
+		Success:  true,
+		Result:   result.Value,
+		Duration: uint64(time.Since(started).Milliseconds()),
+	}
+}
+
+// setResponseGlobals exposes a response to a script as response, status,
+// headers, body, json and timing.
+func setResponseGlobals(globals map[string]interface{}, r *ResponseData) {
+	headers := make(map[string]interface{}, len(r.Headers))
+	for _, h := range r.Headers {
+		// Repeated headers are joined as they would be on one line
+		name := strings.ToLower(h.Name)
+		if prev, ok := headers[name].(string); ok {
+			headers[name] = prev + ", " + h.Value
+		} else {
+			headers[name] = h.Value
+		}
+	}
+	var parsed interface{}
+	if err := json.Unmarshal([]byte(r.Body), &parsed); err != nil {
+		parsed = nil
+	}
+	globals["response"] = r
+	globals["status"] = float64(r.Status)
+	globals["headers"] = headers
+	globals["body"] = r.Body
+	globals["json"] = parsed
+	globals["timing"] = r.Timing
+}
+
+// String formats the error with its position when known.
+func (e *ScriptError) String() string {
+	if e.Line == nil || e.Column == nil {
+		return e.Message
+	}
+	return fmt.Sprintf("%d:%d: %s", *e.Line, *e.Column, e.Message)
+}
+
+func scriptError(message, code string, pos *script.Pos) ScriptResponse {
+	response := ScriptResponse{Error: &ScriptError{Message: message, Code: code}}
+	if pos != nil {
+		response.Error.Line = &pos.Line
+		response.Error.Column = &pos.Column
+	}
+	return response
+}
@@ -0,0 +1,91 @@
+package proxy
+
+import (
+	"fmt"
+	"net/url"
+	"strings"
+)
+
+// analyzeRedirects flags risky redirects in a followed chain: downgrades from
+// https to http, credentials forwarded to or dropped for another origin, and
+// targets taken from the query string. finalForwarded is the credential state
+// of the final hop. It returns nil when nothing was found.
+func analyzeRedirects(chain []RedirectHop, finalURL string, finalForwarded *bool) []RedirectFinding {
+	var findings []RedirectFinding
+	for i, hop := range chain {
+		target, forwarded := finalURL, finalForwarded
+		if i+1 < len(chain) {
+			target, forwarded = chain[i+1].URL, chain[i+1].CredentialsForwarded
+		}
+		from, err := url.Parse(hop.URL)
+		if err != nil {
+			continue
+		}
+		to, err := url.Parse(target)
+		if err != nil {
+			continue
+		}
+
+		finding := RedirectFinding{Hop: i, From: hop.URL, To: target}
+		if from.Scheme == "https" && to.Scheme == "http" {
+			finding.Kind, finding.Severity = "downgrade", "high"
+			finding.Message = "Redirect from https to http exposes the request to interception"
+			findings = append(findings, finding)
+		}
+		if forwarded != nil && origin(from) != origin(to) {
+			switch {
+			case *forwarded:
+				finding.Kind, finding.Severity = "crossOriginAuth", "high"
+				finding.Message = fmt.Sprintf("Authorization and Cookie headers were forwarded to another origin (%s)", origin(to))
+				findings = append(findings, finding)
+			case hop.CredentialsForwarded != nil && *hop.CredentialsForwarded:
+				finding.Kind, finding.Severity = "authDropped", "low"
+				finding.Message = fmt.Sprintf("Authorization and Cookie headers were dropped for another origin (%s); it may reject the request", origin(to))
+				findings = append(findings, finding)
+			}
+		}
+		if param := reflectedTarget(from, to); param != "" {
+			finding.Kind, finding.Severity = "openRedirect", "medium"
+			finding.Parameter = &param
+			finding.Message = fmt.Sprintf("Redirect target on another host was taken from the %q query parameter", param)
+			findings = append(findings, finding)
+		}
+	}
+	return findings
+}
+
+// reflectedTarget returns the query parameter of from whose value is the
+// off-host URL being redirected to, the pattern of an open redirect.
+func reflectedTarget(from, to *url.URL) string {
+	if strings.EqualFold(from.Hostname(), to.Hostname()) {
+		return ""
+	}
+	for name, values := range from.Query() {
+		for _, value := range values {
+			// Scheme-relative values such as //evil.example also redirect off-host
+			if strings.HasPrefix(value, "//") {
+				value = to.Scheme + ":" + value
+			}
+			candidate, err := url.Parse(value)
+			if err != nil || candidate.Host == "" {
+				continue
+			}
+			if strings.EqualFold(candidate.Hostname(), to.Hostname()) {
+				return name
+			}
+		}
+	}
+	return ""
+}
+
+// origin returns the scheme, host and effective port of u.
+func origin(u *url.URL) string {
+	port := u.Port()
+	if port == "" {
+		port = "80"
+		if u.Scheme == "https" {
+			port = "443"
+		}
+	}
+	return strings.ToLower(u.Scheme + "://" + u.Hostname() + ":" + port)
+}
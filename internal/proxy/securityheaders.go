@@ -0,0 +1,244 @@
+package proxy
+
+import (
+	"fmt"
+	"net/url"
+	"strconv"
+	"strings"
+)
+
+// minHSTSMaxAge is the shortest HSTS lifetime not flagged, six months.
+const minHSTSMaxAge = 15768000
+
+// securityHeaderWeights are the points a missing or failing header costs;
+// a warning costs half.
+var securityHeaderWeights = map[string]int{
+	"Strict-Transport-Security":   25,
+	"Content-Security-Policy":     25,
+	"X-Frame-Options":             15,
+	"X-Content-Type-Options":      10,
+	"Referrer-Policy":             10,
+	"Permissions-Policy":          5,
+	"Access-Control-Allow-Origin": 10,
+}
+
+// analyzeSecurityHeaders grades the security headers of a response. headers
+// holds the first value of each header, keyed by lowercase name.
+func analyzeSecurityHeaders(headers map[string]string, finalURL string) *SecurityHeaderReport {
+	https := false
+	if u, err := url.Parse(finalURL); err == nil {
+		https = u.Scheme == "https"
+	}
+
+	csp := headers["content-security-policy"]
+	checks := []SecurityHeaderCheck{
+		checkHSTS(headers["strict-transport-security"], https),
+		checkCSP(csp, headers["content-security-policy-report-only"]),
+		checkFrameOptions(headers["x-frame-options"], cspDirective(csp, "frame-ancestors") != ""),
+		checkContentTypeOptions(headers["x-content-type-options"]),
+		checkReferrerPolicy(headers["referrer-policy"]),
+		checkPermissionsPolicy(headers["permissions-policy"], headers["feature-policy"]),
+	}
+	if cors, ok := checkCORS(headers); ok {
+		checks = append(checks, cors)
+	}
+
+	report := &SecurityHeaderReport{Score: 100, Checks: checks, Missing: []string{}}
+	for _, check := range checks {
+		switch check.Status {
+		case "fail":
+			report.Score -= securityHeaderWeights[check.Header]
+		case "warn":
+			report.Score -= securityHeaderWeights[check.Header] / 2
+		}
+		if !check.Present && check.Status != "info" {
+			report.Missing = append(report.Missing, check.Header)
+		}
+	}
+	report.Score = max(report.Score, 0)
+	report.Grade = securityGrade(report.Score)
+	return report
+}
+
+func securityGrade(score int) string {
+	switch {
+	case score >= 90:
+		return "A"
+	case score >= 80:
+		return "B"
+	case score >= 70:
+		return "C"
+	case score >= 60:
+		return "D"
+	}
+	return "F"
+}
+
+func headerCheck(header, value, status, message string) SecurityHeaderCheck {
+	return SecurityHeaderCheck{Header: header, Present: value != "", Value: optionalString(value), Status: status, Message: message}
+}
+
+func checkHSTS(value string, https bool) SecurityHeaderCheck {
+	const name = "Strict-Transport-Security"
+	if !https {
+		return headerCheck(name, value, "info", "Browsers ignore HSTS received over plain http")
+	}
+	if value == "" {
+		return headerCheck(name, value, "fail", "Missing; browsers may reach the site over plain http first")
+	}
+	maxAge := -1
+	var flags []string
+	for _, directive := range strings.Split(value, ";") {
+		key, arg, _ := strings.Cut(strings.TrimSpace(directive), "=")
+		switch strings.ToLower(key) {
+		case "max-age":
+			if n, err := strconv.Atoi(strings.Trim(arg, `"`)); err == nil {
+				maxAge = n
+			}
+		case "includesubdomains":
+			flags = append(flags, "includeSubDomains")
+		case "preload":
+			flags = append(flags, "preload")
+		}
+	}
+	switch {
+	case maxAge < 0:
+		return headerCheck(name, value, "fail", "No valid max-age directive")
+	case maxAge == 0:
+		return headerCheck(name, value, "warn", "max-age=0 removes the HSTS policy")
+	case maxAge < minHSTSMaxAge:
+		return headerCheck(name, value, "warn", fmt.Sprintf("max-age of %d seconds is under six months", maxAge))
+	}
+	message := fmt.Sprintf("max-age of %d days", maxAge/86400)
+	if len(flags) > 0 {
+		message += " with " + strings.Join(flags, " and ")
+	}
+	return headerCheck(name, value, "pass", message)
+}
+
+// cspDirective returns the value of a directive of a policy, or "".
+func cspDirective(policy, name string) string {
+	for _, directive := range strings.Split(policy, ";") {
+		fields := strings.Fields(directive)
+		if len(fields) > 0 && strings.EqualFold(fields[0], name) {
+			if len(fields) == 1 {
+				return " "
+			}
+			return strings.Join(fields[1:], " ")
+		}
+	}
+	return ""
+}
+
+func checkCSP(value, reportOnly string) SecurityHeaderCheck {
+	const name = "Content-Security-Policy"
+	if value == "" {
+		if reportOnly != "" {
+			return headerCheck(name, value, "warn", "Only Content-Security-Policy-Report-Only is set, which enforces nothing")
+		}
+		return headerCheck(name, value, "fail", "Missing; injected scripts are not restricted")
+	}
+	scripts := cspDirective(value, "script-src")
+	if scripts == "" {
+		scripts = cspDirective(value, "default-src")
+	}
+	if scripts == "" {
+		return headerCheck(name, value, "warn", "Neither script-src nor default-src restricts scripts")
+	}
+	var unsafe []string
+	for _, source := range []string{"'unsafe-inline'", "'unsafe-eval'", "*"} {
+		for _, field := range strings.Fields(scripts) {
+			if strings.EqualFold(field, source) {
+				unsafe = append(unsafe, source)
+			}
+		}
+	}
+	// Nonces and hashes make browsers ignore 'unsafe-inline'
+	if len(unsafe) > 0 && !(len(unsafe) == 1 && unsafe[0] == "'unsafe-inline'" &&
+		(strings.Contains(scripts, "'nonce-") || strings.Contains(scripts, "'sha"))) {
+		return headerCheck(name, value, "warn", "Scripts allow "+strings.Join(unsafe, ", "))
+	}
+	return headerCheck(name, value, "pass", "Scripts are restricted")
+}
+
+func checkFrameOptions(value string, frameAncestors bool) SecurityHeaderCheck {
+	const name = "X-Frame-Options"
+	switch strings.ToUpper(strings.TrimSpace(value)) {
+	case "DENY", "SAMEORIGIN":
+		return headerCheck(name, value, "pass", "Framing by other sites is blocked")
+	case "":
+		if frameAncestors {
+			return headerCheck(name, value, "info", "Not needed; the CSP frame-ancestors directive controls framing")
+		}
+		return headerCheck(name, value, "fail", "Missing, and no CSP frame-ancestors; the page can be framed for clickjacking")
+	}
+	if strings.HasPrefix(strings.ToUpper(value), "ALLOW-FROM") {
+		return headerCheck(name, value, "warn", "ALLOW-FROM is not supported by current browsers; use CSP frame-ancestors")
+	}
+	return headerCheck(name, value, "fail", "Unrecognized value; browsers ignore it")
+}
+
+func checkContentTypeOptions(value string) SecurityHeaderCheck {
+	const name = "X-Content-Type-Options"
+	if strings.EqualFold(strings.TrimSpace(value), "nosniff") {
+		return headerCheck(name, value, "pass", "MIME sniffing is disabled")
+	}
+	if value == "" {
+		return headerCheck(name, value, "fail", "Missing; browsers may sniff content as a different type")
+	}
+	return headerCheck(name, value, "fail", "Only nosniff is a valid value")
+}
+
+func checkReferrerPolicy(value string) SecurityHeaderCheck {
+	const name = "Referrer-Policy"
+	if value == "" {
+		return headerCheck(name, value, "warn", "Missing; browsers default to strict-origin-when-cross-origin")
+	}
+	// The last recognized policy of a list applies
+	policies := strings.Split(value, ",")
+	policy := strings.ToLower(strings.TrimSpace(policies[len(policies)-1]))
+	switch policy {
+	case "unsafe-url", "no-referrer-when-downgrade":
+		return headerCheck(name, value, "warn", policy+" sends full URLs to other origins")
+	}
+	return headerCheck(name, value, "pass", "Referrers to other origins are limited")
+}
+
+func checkPermissionsPolicy(value, featurePolicy string) SecurityHeaderCheck {
+	const name = "Permissions-Policy"
+	switch {
+	case value != "":
+		return headerCheck(name, value, "pass", "Browser features are restricted")
+	case featurePolicy != "":
+		return headerCheck(name, value, "warn", "Only the deprecated Feature-Policy header is set")
+	}
+	return headerCheck(name, value, "warn", "Missing; embedded content may use camera, microphone and similar features")
+}
+
+// checkCORS reviews the CORS headers of a response that sends any.
+func checkCORS(headers map[string]string) (SecurityHeaderCheck, bool) {
+	const name = "Access-Control-Allow-Origin"
+	origin := strings.TrimSpace(headers["access-control-allow-origin"])
+	if origin == "" {
+		return SecurityHeaderCheck{}, false
+	}
+	credentials := strings.EqualFold(strings.TrimSpace(headers["access-control-allow-credentials"]), "true")
+	varyOrigin := false
+	for _, field := range strings.Split(headers["vary"], ",") {
+		varyOrigin = varyOrigin || strings.EqualFold(strings.TrimSpace(field), "Origin") || strings.TrimSpace(field) == "*"
+	}
+
+	switch {
+	case origin == "*" && credentials:
+		return headerCheck(name, origin, "fail", "Wildcard origin with credentials; browsers reject it, and reflecting origins instead exposes credentialed data"), true
+	case origin == "*":
+		return headerCheck(name, origin, "info", "Any origin may read responses without credentials"), true
+	case origin == "null":
+		return headerCheck(name, origin, "warn", "The null origin is shared by sandboxed frames and local files"), true
+	case !varyOrigin:
+		return headerCheck(name, origin, "warn", "A specific origin without Vary: Origin lets caches serve it to other origins"), true
+	case credentials:
+		return headerCheck(name, origin, "info", "Credentialed requests allowed from "+origin), true
+	}
+	return headerCheck(name, origin, "pass", "Requests allowed from "+origin), true
+}
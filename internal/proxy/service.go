@@ -0,0 +1,240 @@
+package proxy
+
+import (
+	"context"
+	"crypto/x509"
+	"sync/atomic"
+	"time"
+
+	"golang.org/x/net/http/httpproxy"
+
+	"zone.digit.tommie/internal/config"
+	"zone.digit.tommie/internal/history"
+	"zone.digit.tommie/internal/infra"
+	"zone.digit.tommie/internal/pac"
+	"zone.digit.tommie/internal/plugins"
+	"zone.digit.tommie/internal/storage"
+)
+
+// Options configures an HTTPService.
+type Options struct {
+	// RootCAs is the set of trusted root certificates. Nil uses the system roots.
+	RootCAs *x509.CertPool
+	// EgressBytesPerMinute is the global byte budget per minute (0 = unlimited).
+	EgressBytesPerMinute int
+	// EgressBytesPerDay is the global byte budget per UTC day (0 = unlimited).
+	EgressBytesPerDay int
+	// UpstreamProxy routes requests through HTTP(S) proxies honoring NO_PROXY. Nil goes direct.
+	UpstreamProxy *httpproxy.Config
+	// PAC is a proxy auto-config script consulted per request. It takes precedence over UpstreamProxy.
+	PAC *pac.Script
+	// IntegratedAuth answers Negotiate/NTLM challenges from proxies, and from
+	// servers when a request asks for it or the server is one of
+	// IntegratedAuthHosts, with the logged-on user's credentials (Windows
+	// desktop only).
+	IntegratedAuth bool
+	// IntegratedAuthHosts are the intranet hosts answered with the logged-on
+	// user's credentials without the request asking; a leading dot also
+	// matches subdomains.
+	IntegratedAuthHosts []string
+	// Policy restricts the destinations requests may reach. Nil allows all.
+	Policy *Policy
+	// History bounds the request history recorded in the store. Nil disables history.
+	History *history.Retention
+	// Store keeps per-user state such as cookie jars for Execute. Nil disables it.
+	Store storage.Store
+	// Resolver resolves hostnames unless a request names its own nameservers. Nil uses the system resolver.
+	Resolver *infra.Resolver
+	// MaxInFlightBodyBytes bounds the request and response bodies held across
+	// concurrent executions (0 = unlimited). Executions over it fail with BUSY.
+	MaxInFlightBodyBytes int
+	// MaxBodyBytes is the response body kept per execution (0 = DefaultMaxBodyBytes).
+	// Reading stops at the limit and the response is marked truncated.
+	MaxBodyBytes int
+	// DownloadDir keeps temporary downloads ("" = a directory in os.TempDir).
+	DownloadDir string
+	// MaxDownloadBytes bounds a download, both as received and decoded
+	// (0 = unlimited). Downloads over it fail with DOWNLOAD_TOO_LARGE.
+	MaxDownloadBytes int
+	// AllowDownloadPath lets requests write downloads to a path of their choosing.
+	AllowDownloadPath bool
+	// FixtureDir receives the responses of requests sent with captureFixture ("" = disabled).
+	FixtureDir string
+	// BusyWait is how long an execution waits for in-flight memory to free up before failing.
+	BusyWait time.Duration
+	// Clock times executions (nil = SystemClock). A MockClock makes timings deterministic.
+	Clock Clock
+	// Simulation answers every request with a fabricated response instead of
+	// reaching the network (nil = disabled).
+	Simulation *SimulationOptions
+	// RedactNames adds header, query parameter and body field names to
+	// DefaultRedactedNames for redacted curl exports.
+	RedactNames []string
+	// Interceptors observe and rewrite every request and response; more can
+	// be added with Use.
+	Interceptors []Interceptor
+	// Plugins process every request and response after the interceptors (nil = none).
+	Plugins *plugins.Host
+}
+
+// OptionsFromConfig builds service options from the application configuration.
+func OptionsFromConfig(cfg *config.Config) (Options, error) {
+	rootCAs, err := infra.LoadCertPool(cfg.ExtraCABundle)
+	if err != nil {
+		return Options{}, err
+	}
+
+	var script *pac.Script
+	if cfg.PACURL != "" {
+		if script, err = pac.Load(cfg.PACURL); err != nil {
+			return Options{}, err
+		}
+	}
+
+	policy, err := NewPolicy(cfg)
+	if err != nil {
+		return Options{}, err
+	}
+
+	resolver, err := infra.NewResolver(cfg.DNSServers)
+	if err != nil {
+		return Options{}, err
+	}
+
+	var host *plugins.Host
+	if cfg.PluginDir != "" {
+		if host, err = plugins.Load(context.Background(), cfg.PluginDir, plugins.DefaultLimits); err != nil {
+			return Options{}, err
+		}
+	}
+
+	var simulation *SimulationOptions
+	if cfg.Simulate {
+		opts, err := ParseSimulationLatency(cfg.SimulateLatency)
+		if err != nil {
+			return Options{}, err
+		}
+		simulation = &opts
+	}
+
+	var retention *history.Retention
+	if cfg.HistoryEnabled {
+		retention = &history.Retention{
+			MaxEntries:   cfg.HistoryMaxEntries,
+			MaxAge:       time.Duration(cfg.HistoryMaxAgeDays) * 24 * time.Hour,
+			MaxBodyBytes: cfg.HistoryMaxBodyBytes,
+		}
+	}
+
+	return Options{
+		RootCAs:              rootCAs,
+		EgressBytesPerMinute: cfg.EgressBytesPerMinute,
+		EgressBytesPerDay:    cfg.EgressBytesPerDay,
+		UpstreamProxy: &httpproxy.Config{
+			HTTPProxy:  cfg.HTTPProxy,
+			HTTPSProxy: cfg.HTTPSProxy,
+			NoProxy:    cfg.NoProxy,
+		},
+		PAC:                  script,
+		Policy:               policy,
+		History:              retention,
+		Resolver:             resolver,
+		MaxInFlightBodyBytes: cfg.MaxInFlightBodyBytes,
+		MaxBodyBytes:         cfg.MaxBodyBytes,
+		DownloadDir:          cfg.DownloadDir,
+		MaxDownloadBytes:     cfg.MaxDownloadBytes,
+		FixtureDir:           cfg.FixtureDir,
+		BusyWait:             time.Duration(cfg.BusyWaitMS) * time.Millisecond,
+		Simulation:           simulation,
+		RedactNames:          cfg.RedactNames,
+		Plugins:              host,
+		IntegratedAuthHosts:  cfg.IntegratedAuthHosts,
+	}, nil
+}
+
+// DefaultMaxBodyBytes is the response body kept per execution unless configured.
+const DefaultMaxBodyBytes = 50 << 20
+
+// HTTPService executes proxy requests over the network.
+type HTTPService struct {
+	opts         Options
+	egress       *egressBudget
+	pins         *pinStore
+	upstream     *upstreamProxy
+	websockets   *webSocketSessions
+	memory       *memoryGuard
+	downloads    *downloadStore
+	clock        Clock
+	simulator    *SimulatedProxyService // Set in simulation mode
+	interceptors interceptorChain
+	policy       atomic.Pointer[Policy]   // Replaced by Reconfigure
+	redactNames  atomic.Pointer[[]string] // Replaced by Reconfigure
+}
+
+// NewHTTPService creates a new HTTPService with the given options.
+func NewHTTPService(opts Options) *HTTPService {
+	s := &HTTPService{
+		opts:       opts,
+		egress:     newEgressBudget(opts.EgressBytesPerMinute, opts.EgressBytesPerDay),
+		pins:       newPinStore(),
+		upstream:   newUpstreamProxy(opts.UpstreamProxy, opts.PAC),
+		websockets: &webSocketSessions{},
+		memory:     newMemoryGuard(opts.MaxInFlightBodyBytes, opts.BusyWait),
+		downloads:  newDownloadStore(opts.DownloadDir),
+		clock:      opts.Clock,
+	}
+	if s.clock == nil {
+		s.clock = SystemClock
+	}
+	s.interceptors.add(opts.Interceptors...)
+	if opts.Simulation != nil {
+		s.simulator = NewSimulatedProxyService(*opts.Simulation, s.clock)
+	}
+	s.policy.Store(opts.Policy)
+	s.redactNames.Store(&opts.RedactNames)
+	return s
+}
+
+// Reconfigure applies the settings of cfg that may change while running:
+// the destination policy, the egress budgets and the names redacted from
+// exports. Executions already past their checks are not affected.
+func (s *HTTPService) Reconfigure(cfg *config.Config) error {
+	policy, err := NewPolicy(cfg)
+	if err != nil {
+		return err
+	}
+	s.policy.Store(policy)
+	s.egress.setLimits(cfg.EgressBytesPerMinute, cfg.EgressBytesPerDay)
+	s.redactNames.Store(&cfg.RedactNames)
+	return nil
+}
+
+// defaultService is used by ExecuteRequest.
+var defaultService = NewHTTPService(Options{})
+
+// ExecuteRequest executes an HTTP request with detailed timing using default options.
+func ExecuteRequest(request ProxyRequest) ProxyResponse {
+	return defaultService.Execute(request)
+}
+
+// ServiceStats describes the work a service has in progress.
+type ServiceStats struct {
+	Executions           int `json:"executions"`           // Requests being executed
+	InFlightBodyBytes    int `json:"inFlightBodyBytes"`    // Body bytes held by those executions; only tracked with a limit
+	MaxInFlightBodyBytes int `json:"maxInFlightBodyBytes"` // 0 when unlimited
+	WebSocketSessions    int `json:"webSocketSessions"`    // Sessions kept, open or closed
+}
+
+// Stats returns the work the service has in progress.
+func (s *HTTPService) Stats() ServiceStats {
+	active, used := s.memory.usage()
+	s.websockets.mu.Lock()
+	sessions := len(s.websockets.sessions)
+	s.websockets.mu.Unlock()
+	return ServiceStats{
+		Executions:           active,
+		InFlightBodyBytes:    used,
+		MaxInFlightBodyBytes: s.opts.MaxInFlightBodyBytes,
+		WebSocketSessions:    sessions,
+	}
+}
@@ -0,0 +1,51 @@
+package proxy
+
+import (
+	"encoding/json"
+	"log"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/sessions"
+	"zone.digit.tommie/internal/storage"
+)
+
+// sessionExchange is the JSON shape of har.Exchange, in which sessions
+// record executed requests.
+type sessionExchange struct {
+	Request   ProxyRequest  `json:"request"`
+	Response  ProxyResponse `json:"response"`
+	StartedAt time.Time     `json:"startedAt"`
+}
+
+// recordSession adds an executed request to the session being recorded in
+// store, if any. Text bodies are cut at sessions.MaxBodyBytes; binary
+// ones beyond it are dropped.
+func (s *HTTPService) recordSession(store storage.Store, request ProxyRequest, response ProxyResponse, started time.Time) {
+	if store == nil {
+		return
+	}
+	if active, err := sessions.Active(store); err != nil || active == nil {
+		return
+	}
+	if response.Success {
+		data := *response.Data
+		if len(data.Body) > sessions.MaxBodyBytes {
+			data.Body = strings.ToValidUTF8(data.Body[:sessions.MaxBodyBytes], "")
+			data.BodyTruncated = true
+		}
+		if data.BodyBase64 != nil && len(*data.BodyBase64) > sessions.MaxBodyBytes {
+			data.BodyBase64 = nil
+			data.BodyTruncated = true
+		}
+		response.Data = &data
+	}
+	exchange, err := json.Marshal(sessionExchange{Request: request, Response: response, StartedAt: started.UTC()})
+	if err == nil {
+		err = sessions.Record(store, started, exchange)
+	}
+	// Sessions are best effort and never fail the request
+	if err != nil {
+		log.Printf("Failed to record session: %v", err)
+	}
+}
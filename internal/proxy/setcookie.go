@@ -0,0 +1,164 @@
+package proxy
+
+import (
+	"net/url"
+	"strconv"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/cookies"
+)
+
+// maxCookieLifetime is the longest lifetime browsers keep a cookie, in seconds.
+const maxCookieLifetime = 400 * 24 * 60 * 60
+
+// cookieTimeLayouts are the Expires formats seen in practice, tried in order.
+var cookieTimeLayouts = []string{
+	time.RFC1123,
+	"Mon, 02-Jan-2006 15:04:05 MST",
+	"Monday, 02-Jan-06 15:04:05 MST",
+	time.ANSIC,
+	"Mon, 02 Jan 06 15:04:05 MST",
+}
+
+// parseSetCookies parses every Set-Cookie header of a response, in order and
+// with duplicates, and flags attributes browsers reject or that weaken the
+// cookie.
+func parseSetCookies(fields []HeaderField, finalURL string, now time.Time) []ResponseCookie {
+	https := false
+	host := ""
+	if u, err := url.Parse(finalURL); err == nil {
+		https = u.Scheme == "https"
+		host = u.Hostname()
+	}
+
+	var parsed []ResponseCookie
+	for _, field := range fields {
+		if strings.EqualFold(field.Name, "Set-Cookie") {
+			parsed = append(parsed, parseSetCookie(field.Value, host, https, now))
+		}
+	}
+	return parsed
+}
+
+func parseSetCookie(raw, host string, https bool, now time.Time) ResponseCookie {
+	parts := strings.Split(raw, ";")
+	name, value, hasValue := strings.Cut(parts[0], "=")
+	c := ResponseCookie{
+		Name:     strings.TrimSpace(name),
+		Value:    strings.Trim(strings.TrimSpace(value), `"`),
+		Raw:      raw,
+		Session:  true,
+		Warnings: []string{},
+	}
+	warn := func(message string) {
+		c.Warnings = append(c.Warnings, message)
+	}
+	if !hasValue || c.Name == "" {
+		warn("No name=value pair; browsers ignore the cookie")
+	}
+
+	for _, part := range parts[1:] {
+		key, arg, _ := strings.Cut(strings.TrimSpace(part), "=")
+		arg = strings.TrimSpace(arg)
+		switch strings.ToLower(strings.TrimSpace(key)) {
+		case "domain":
+			c.Domain = optionalString(arg)
+		case "path":
+			c.Path = optionalString(arg)
+		case "expires":
+			c.Expires = optionalString(arg)
+			expires, ok := parseCookieTime(arg)
+			if !ok {
+				warn("Expires is not a valid date and is ignored")
+				continue
+			}
+			ms := uint64(max(expires.UnixMilli(), 0))
+			c.ExpiresAt = &ms
+		case "max-age":
+			seconds, err := strconv.Atoi(arg)
+			if err != nil {
+				warn("Max-Age is not a number and is ignored")
+				continue
+			}
+			c.MaxAge = &seconds
+		case "secure":
+			c.Secure = true
+		case "httponly":
+			c.HTTPOnly = true
+		case "samesite":
+			c.SameSite = optionalString(arg)
+			switch strings.ToLower(arg) {
+			case "strict", "lax", "none":
+			default:
+				warn("SameSite=" + arg + " is not Strict, Lax or None; browsers treat it as Lax")
+			}
+		case "partitioned":
+			c.Partitioned = true
+		case "priority":
+			c.Priority = optionalString(arg)
+		case "":
+		default:
+			warn("Unknown attribute " + strings.TrimSpace(key) + " is ignored")
+		}
+	}
+
+	// Max-Age takes precedence over Expires (RFC 6265, section 5.3)
+	switch {
+	case c.MaxAge != nil:
+		c.Session = false
+		c.Expired = *c.MaxAge <= 0
+		// Browsers cap lifetimes at 400 days
+		seconds := min(*c.MaxAge, maxCookieLifetime)
+		ms := uint64(now.Add(time.Duration(seconds) * time.Second).UnixMilli())
+		c.ExpiresAt = &ms
+	case c.ExpiresAt != nil:
+		c.Session = false
+		c.Expired = *c.ExpiresAt <= uint64(now.UnixMilli())
+	}
+
+	sameSiteNone := c.SameSite != nil && strings.EqualFold(*c.SameSite, "none")
+	switch {
+	case c.Secure && !https && host != "":
+		warn("Secure cookie set over plain http; browsers reject it")
+	case !c.Secure && https:
+		warn("Missing Secure on an https response; the cookie is also sent over plain http")
+	}
+	if sameSiteNone && !c.Secure {
+		warn("SameSite=None without Secure; browsers reject it")
+	}
+	if c.Partitioned && !c.Secure {
+		warn("Partitioned without Secure; browsers reject it")
+	}
+	if !c.HTTPOnly && !c.Expired {
+		warn("Missing HttpOnly; scripts can read the cookie")
+	}
+	if c.Domain != nil && host != "" && !cookies.DomainAllowed(host, *c.Domain) {
+		warn("Domain " + *c.Domain + " does not cover " + host + "; browsers reject the cookie")
+	}
+	if c.Path != nil && !strings.HasPrefix(*c.Path, "/") {
+		warn("Path does not start with /; browsers use the default path")
+	}
+
+	// Cookie name prefixes (RFC 6265bis, section 4.1.3)
+	switch {
+	case strings.HasPrefix(c.Name, "__Host-"):
+		if !c.Secure || c.Domain != nil || c.Path == nil || *c.Path != "/" {
+			warn("__Host- cookies need Secure, Path=/ and no Domain; browsers reject it")
+		}
+	case strings.HasPrefix(c.Name, "__Secure-"):
+		if !c.Secure {
+			warn("__Secure- cookies need Secure; browsers reject it")
+		}
+	}
+	return c
+}
+
+func parseCookieTime(value string) (time.Time, bool) {
+	for _, layout := range cookieTimeLayouts {
+		if t, err := time.Parse(layout, value); err == nil {
+			return t, true
+		}
+	}
+	return time.Time{}, false
+}
@@ -0,0 +1,381 @@
+package proxy
+
+import (
+	"crypto/sha256"
+	"encoding/json"
+	"fmt"
+	"hash/fnv"
+	"math/rand/v2"
+	"net/url"
+	"strconv"
+	"strings"
+	"time"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+// SimulationOptions sets the latencies of simulated responses. Each phase
+// varies by up to Jitter (a fraction) around its value.
+type SimulationOptions struct {
+	DNS      time.Duration
+	TCP      time.Duration
+	TLS      time.Duration
+	TTFB     time.Duration
+	Download time.Duration
+	Jitter   float64
+}
+
+// DefaultSimulation resembles a nearby server over a good connection.
+var DefaultSimulation = SimulationOptions{
+	DNS:      12 * time.Millisecond,
+	TCP:      24 * time.Millisecond,
+	TLS:      38 * time.Millisecond,
+	TTFB:     85 * time.Millisecond,
+	Download: 15 * time.Millisecond,
+	Jitter:   0.2,
+}
+
+// ParseSimulationLatency reads per-phase latencies in milliseconds, e.g.
+// "dns=12,tcp=24,tls=38,ttfb=85,download=15,jitter=0.2", over the defaults.
+func ParseSimulationLatency(entries []string) (SimulationOptions, error) {
+	opts := DefaultSimulation
+	for _, entry := range entries {
+		name, value, ok := strings.Cut(strings.TrimSpace(entry), "=")
+		if !ok {
+			return opts, fmt.Errorf("invalid simulation latency %q: want phase=ms", entry)
+		}
+		name = strings.ToLower(strings.TrimSpace(name))
+		if name == "jitter" {
+			jitter, err := strconv.ParseFloat(value, 64)
+			if err != nil || jitter < 0 || jitter > 1 {
+				return opts, fmt.Errorf("invalid simulation jitter %q: want 0 to 1", value)
+			}
+			opts.Jitter = jitter
+			continue
+		}
+		ms, err := strconv.Atoi(value)
+		if err != nil || ms < 0 {
+			return opts, fmt.Errorf("invalid simulation latency %q", entry)
+		}
+		d := time.Duration(ms) * time.Millisecond
+		switch name {
+		case "dns":
+			opts.DNS = d
+		case "tcp":
+			opts.TCP = d
+		case "tls":
+			opts.TLS = d
+		case "ttfb":
+			opts.TTFB = d
+		case "download":
+			opts.Download = d
+		default:
+			return opts, fmt.Errorf("unknown simulation phase %q: use dns, tcp, tls, ttfb, download or jitter", name)
+		}
+	}
+	return opts, nil
+}
+
+// SimulatedProxyService fabricates responses without network access, for
+// frontend demos, screenshots and offline development. The same request
+// always gets the same response and timings. Paths select the behavior,
+// like the routes of the test target:
+//
+//	/status/{code}   Empty response with the status
+//	/redirect/{n}    Chain of n redirects ending at /ok (?status=301|302|303|307|308)
+//	/delay/{ms}      Adds to the time to first byte
+//	/bytes/{n}       n bytes of binary data
+//	/fail            Connection refused
+//	anything else    200 with a JSON echo of the request
+type SimulatedProxyService struct {
+	opts  SimulationOptions
+	clock Clock
+}
+
+// NewSimulatedProxyService creates a simulator dating responses by clock.
+func NewSimulatedProxyService(opts SimulationOptions, clock Clock) *SimulatedProxyService {
+	if clock == nil {
+		clock = SystemClock
+	}
+	return &SimulatedProxyService{opts: opts, clock: clock}
+}
+
+// simulatedHop is the response fabricated for one URL.
+type simulatedHop struct {
+	status   int
+	location string
+	delay    time.Duration
+	headers  []HeaderField
+	body     []byte
+}
+
+// Execute fabricates the response to a request, following simulated redirects.
+func (s *SimulatedProxyService) Execute(request ProxyRequest) ProxyResponse {
+	ctx, err := newRequestContext(request.URL)
+	if err != nil {
+		return NewErrorResponse(err.Error(), "INVALID_URL")
+	}
+	reqBody, err := request.encodeBody()
+	if err != nil {
+		return NewErrorResponse(err.Error(), "INVALID_BODY")
+	}
+	method := request.Method
+	if method == "" {
+		method = "GET"
+	}
+
+	seed := fnv.New64a()
+	fmt.Fprintf(seed, "%s %s", method, request.URL)
+	rng := rand.New(rand.NewPCG(seed.Sum64(), 0))
+	jitter := func(d time.Duration) time.Duration {
+		return d + time.Duration(float64(d)*s.opts.Jitter*(rng.Float64()*2-1))
+	}
+
+	// Simulated time starts now and only moves by the simulated phases
+	clock := NewMockClock(s.clock.Now())
+	timing := NewDetailedTimingWithClock(clock)
+	var redirectChain []RedirectHop
+	previousHost := ""
+
+	for {
+		hopStart := clock.Now()
+		hopTiming := timing
+		if len(redirectChain) > 0 {
+			hopTiming = NewDetailedTimingWithClock(clock)
+		}
+
+		if previousHost == "" {
+			hopTiming.StartDNS()
+			clock.Advance(jitter(s.opts.DNS))
+			hopTiming.EndDNS()
+		}
+		// Redirects to the same host reuse the connection
+		if ctx.host != previousHost {
+			hopTiming.StartTCP()
+			clock.Advance(jitter(s.opts.TCP))
+			hopTiming.EndTCP()
+			if ctx.isHTTPS {
+				hopTiming.StartTLS()
+				clock.Advance(jitter(s.opts.TLS))
+				hopTiming.EndTLS()
+			}
+		}
+		previousHost = ctx.host
+
+		u, err := url.Parse(ctx.url)
+		if err != nil {
+			return NewErrorResponse(err.Error(), "INVALID_URL")
+		}
+		hop, ok := s.route(u, method, request.Headers, reqBody.raw)
+		if !ok {
+			return NewErrorResponse(fmt.Sprintf("Request failed: dial tcp %s: connect: connection refused",
+				simulatedIP(ctx.host)+":"+ctx.port), "REQUEST_FAILED")
+		}
+		hopTiming.StartRequest()
+		clock.Advance(jitter(s.opts.TTFB) + hop.delay)
+		hopTiming.MarkTTFB()
+
+		headers := make(map[string]string)
+		for _, h := range hop.headers {
+			headers[strings.ToLower(h.Name)] = h.Value
+		}
+		headers["date"] = clock.Now().UTC().Format(time.RFC1123)
+		hop.headers = append(hop.headers, HeaderField{Name: "Date", Value: headers["date"]})
+
+		var cert *infra.CertInfo
+		if ctx.isHTTPS {
+			cert = simulatedCertificate(ctx.host, clock.Now())
+		}
+		httpVersion := "HTTP/1.1"
+		if ctx.isHTTPS {
+			httpVersion = "HTTP/2.0"
+		}
+
+		if hop.location != "" {
+			if len(redirectChain) >= MaxRedirects {
+				return NewErrorResponse("Too many redirects", "TOO_MANY_REDIRECTS")
+			}
+			currentURL := ctx.url
+			nextURL := ctx.updateFromRedirect(hop.location)
+			nextMethod, keepBody := redirectMethod(hop.status, method, request.StrictRedirects)
+			hopTimingInfo := hopTiming.ToTimingInfo()
+			redirectChain = append(redirectChain, RedirectHop{
+				URL:      currentURL,
+				Method:   method,
+				Status:   uint16(hop.status),
+				Duration: uint64(since(clock, hopStart).Milliseconds()),
+				Headers:  headers,
+				Message:  strPtr(redirectMessage(nextURL, method, nextMethod, !keepBody && reqBody.encoding != "")),
+				ServerIP: strPtr(simulatedIP(u.Hostname())),
+				Protocol: strPtr(httpVersion),
+				TLS:      buildTLSInfo(cert),
+				Timing:   &hopTimingInfo,
+			})
+			method = nextMethod
+			if !keepBody {
+				reqBody = &requestBody{}
+			}
+			continue
+		}
+
+		timing.StartDownload()
+		if hopTiming != timing {
+			hopTiming.StartDownload()
+		}
+		clock.Advance(jitter(s.opts.Download))
+		timing.EndDownload()
+		if hopTiming != timing {
+			hopTiming.EndDownload()
+		}
+
+		var requestBodySize *int
+		if reqBody.encoding != "" {
+			size := len(reqBody.raw)
+			requestBodySize = &size
+		}
+		response := buildResponse(responseBuildParams{
+			status:              uint16(hop.status),
+			headers:             headers,
+			bodyBytes:           hop.body,
+			contentLength:       int64(len(hop.body)),
+			maxBodyBytes:        DefaultMaxBodyBytes,
+			integrity:           request.Integrity,
+			timing:              timing,
+			finalURL:            ctx.url,
+			redirectChain:       redirectChain,
+			tlsInfo:             cert,
+			httpVersion:         httpVersion,
+			serverIP:            simulatedIP(ctx.host),
+			requestMethod:       method,
+			requestHeaders:      request.Headers,
+			requestBodySize:     requestBodySize,
+			requestBodyEncoding: reqBody.encoding,
+			headerFields:        hop.headers,
+			beautify:            request.Beautify,
+			hexdumpBytes:        request.HexdumpBytes,
+			bodyStatsThreshold:  request.BodyStatsThreshold,
+			hostname:            ctx.host,
+			port:                ctx.port,
+			resolvedIPs:         []string{simulatedIP(ctx.host)},
+		})
+		if response.Success {
+			response.Data.SecurityAnalysis = &SecurityAnalysis{
+				Redirects: analyzeRedirects(redirectChain, ctx.url, nil),
+				Headers:   analyzeSecurityHeaders(headers, ctx.url),
+			}
+		}
+		return response
+	}
+}
+
+// route fabricates the response for a URL. It reports false for a failed connection.
+func (s *SimulatedProxyService) route(u *url.URL, method string, requestHeaders map[string]string, body []byte) (simulatedHop, bool) {
+	segments := strings.Split(strings.Trim(u.Path, "/"), "/")
+	arg := -1
+	if len(segments) == 2 {
+		if n, err := strconv.Atoi(segments[1]); err == nil && n >= 0 {
+			arg = n
+		}
+	}
+	hop := simulatedHop{
+		status: 200,
+		headers: []HeaderField{
+			{Name: "Server", Value: "simulated"},
+			{Name: "X-Simulated", Value: "true"},
+		},
+	}
+
+	switch {
+	case segments[0] == "fail":
+		return hop, false
+	case segments[0] == "status" && arg >= 100 && arg <= 999:
+		hop.status = arg
+	case segments[0] == "redirect" && arg >= 0:
+		hop.location = "/ok"
+		if arg > 0 {
+			hop.location = fmt.Sprintf("/redirect/%d", arg-1)
+			if u.RawQuery != "" {
+				hop.location += "?" + u.RawQuery
+			}
+		}
+		hop.status = 302
+		if status, err := strconv.Atoi(u.Query().Get("status")); err == nil && status >= 300 && status <= 399 {
+			hop.status = status
+		}
+		hop.headers = append(hop.headers, HeaderField{Name: "Location", Value: hop.location})
+	case segments[0] == "bytes" && arg >= 0:
+		hop.body = make([]byte, min(arg, DefaultMaxBodyBytes))
+		for i := range hop.body {
+			hop.body[i] = byte(i)
+		}
+		hop.headers = append(hop.headers, HeaderField{Name: "Content-Type", Value: "application/octet-stream"})
+	default:
+		if segments[0] == "delay" && arg >= 0 {
+			hop.delay = time.Duration(arg) * time.Millisecond
+		}
+		hop.body, _ = json.MarshalIndent(map[string]interface{}{
+			"simulated": true,
+			"method":    method,
+			"url":       u.String(),
+			"headers":   requestHeaders,
+			"body":      string(body),
+		}, "", "  ")
+		hop.headers = append(hop.headers, HeaderField{Name: "Content-Type", Value: "application/json"})
+	}
+	hop.headers = append(hop.headers, HeaderField{Name: "Content-Length", Value: strconv.Itoa(len(hop.body))})
+	return hop, true
+}
+
+// simulatedIP returns a stable documentation address (192.0.2.0/24) for a host.
+func simulatedIP(host string) string {
+	h := fnv.New32a()
+	h.Write([]byte(host))
+	return fmt.Sprintf("192.0.2.%d", 1+h.Sum32()%254)
+}
+
+// simulatedCertificate fabricates a valid certificate for host, issued 30
+// days before now and valid for 90 days.
+func simulatedCertificate(host string, now time.Time) *infra.CertInfo {
+	issued := now.Add(-30 * 24 * time.Hour).Truncate(24 * time.Hour)
+	expires := issued.Add(90 * 24 * time.Hour)
+	fingerprint := func(name string) string {
+		sum := sha256.Sum256([]byte(name))
+		parts := make([]string, len(sum))
+		for i, b := range sum {
+			parts[i] = fmt.Sprintf("%02X", b)
+		}
+		return strings.Join(parts, ":")
+	}
+
+	const issuer, root = "Simulated Issuing CA", "Simulated Root CA"
+	return &infra.CertInfo{
+		Protocol:  "TLS 1.3",
+		Cipher:    "TLS_AES_128_GCM_SHA256",
+		Issuer:    issuer,
+		Subject:   host,
+		ValidFrom: uint64(issued.Unix()),
+		ValidTo:   uint64(expires.Unix()),
+		SANs:      []string{host},
+		Chain: []infra.ChainCert{
+			{
+				Subject:      host,
+				Issuer:       issuer,
+				ValidFrom:    uint64(issued.Unix()),
+				ValidTo:      uint64(expires.Unix()),
+				KeyAlgorithm: "ECDSA P-256",
+				Fingerprint:  fingerprint(host),
+				SerialNumber: fingerprint(host + " serial")[:23],
+			},
+			{
+				Subject:      issuer,
+				Issuer:       root,
+				ValidFrom:    uint64(issued.AddDate(-2, 0, 0).Unix()),
+				ValidTo:      uint64(issued.AddDate(3, 0, 0).Unix()),
+				KeyAlgorithm: "RSA 2048",
+				Fingerprint:  fingerprint(issuer),
+				SerialNumber: fingerprint(issuer + " serial")[:23],
+			},
+		},
+		Validation: infra.CertValidation{HostnameMatch: true, ChainVerified: true},
+	}
+}
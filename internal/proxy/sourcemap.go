@@ -0,0 +1,144 @@
+package proxy
+
+import (
+	"encoding/base64"
+	"encoding/json"
+	"fmt"
+	"net/url"
+	"regexp"
+	"strings"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+// sourceMappingURLPattern matches the trailing source map comment of a script.
+var sourceMappingURLPattern = regexp.MustCompile(`//[#@]\s*sourceMappingURL=(\S+)\s*$`)
+
+// sourceMapFile holds the parts of a source map reported back.
+type sourceMapFile struct {
+	Version  int      `json:"version"`
+	File     string   `json:"file"`
+	Sources  []string `json:"sources"`
+	Sections []struct {
+		Map *sourceMapFile `json:"map"`
+	} `json:"sections"`
+}
+
+// sources lists the original files, flattening index maps.
+func (m *sourceMapFile) sources() []string {
+	sources := m.Sources
+	for _, section := range m.Sections {
+		if section.Map != nil {
+			sources = append(sources, section.Map.sources()...)
+		}
+	}
+	return sources
+}
+
+// sourceMapReference finds the source map of a JavaScript response, preferring
+// the SourceMap header over the sourceMappingURL comment.
+func sourceMapReference(headers map[string]string, body string) string {
+	if ref := headers["sourcemap"]; ref != "" {
+		return ref
+	}
+	if ref := headers["x-sourcemap"]; ref != "" {
+		return ref
+	}
+
+	ct := strings.ToLower(headers["content-type"])
+	if !strings.Contains(ct, "javascript") && !strings.Contains(ct, "ecmascript") {
+		return ""
+	}
+	if match := sourceMappingURLPattern.FindStringSubmatch(strings.TrimSpace(body)); match != nil {
+		return match[1]
+	}
+	return ""
+}
+
+// fetchSourceMap loads the source map referenced by a script through the
+// executor and reports whether it is available and which sources it lists.
+func (s *HTTPService) fetchSourceMap(store storage.Store, request ProxyRequest, scriptURL, ref string) *SourceMapInfo {
+	info := &SourceMapInfo{URL: ref}
+
+	var content []byte
+	if strings.HasPrefix(ref, "data:") {
+		info.Inline = true
+		data, err := decodeDataURL(ref)
+		if err != nil {
+			info.Error = strPtr(err.Error())
+			return info
+		}
+		content = data
+	} else {
+		base, err := url.Parse(scriptURL)
+		if err != nil {
+			info.Error = strPtr(fmt.Sprintf("invalid script URL: %v", err))
+			return info
+		}
+		mapURL, err := base.Parse(ref)
+		if err != nil {
+			info.Error = strPtr(fmt.Sprintf("invalid source map URL: %v", err))
+			return info
+		}
+		info.URL = mapURL.String()
+
+		resp := s.ExecuteIn(store, ProxyRequest{
+			Method:   "GET",
+			URL:      info.URL,
+			Headers:  map[string]string{"Accept": "application/json"},
+			Timeout:  request.Timeout,
+			PinToken: request.PinToken,
+		})
+		if !resp.Success {
+			info.Error = strPtr(resp.Error.Message)
+			return info
+		}
+		info.Status = &resp.Data.Status
+		if resp.Data.Status < 200 || resp.Data.Status >= 300 {
+			return info
+		}
+		content = []byte(resp.Data.Body)
+		if resp.Data.BodyBase64 != nil {
+			if content, err = base64.StdEncoding.DecodeString(*resp.Data.BodyBase64); err != nil {
+				info.Error = strPtr(fmt.Sprintf("invalid body encoding: %v", err))
+				return info
+			}
+		}
+	}
+
+	size := len(content)
+	info.Size = &size
+
+	var file sourceMapFile
+	if err := json.Unmarshal(content, &file); err != nil {
+		info.Error = strPtr(fmt.Sprintf("invalid source map: %v", err))
+		return info
+	}
+	info.Available = true
+	info.Version = file.Version
+	if file.File != "" {
+		info.File = strPtr(file.File)
+	}
+	info.Sources = file.sources()
+	return info
+}
+
+// decodeDataURL decodes the payload of an inline data: URL.
+func decodeDataURL(ref string) ([]byte, error) {
+	meta, payload, ok := strings.Cut(strings.TrimPrefix(ref, "data:"), ",")
+	if !ok {
+		return nil, fmt.Errorf("malformed data URL")
+	}
+	if strings.HasSuffix(meta, ";base64") {
+		data, err := base64.StdEncoding.DecodeString(payload)
+		if err != nil {
+			return nil, fmt.Errorf("invalid data URL: %w", err)
+		}
+		return data, nil
+	}
+	data, err := url.PathUnescape(payload)
+	if err != nil {
+		return nil, fmt.Errorf("invalid data URL: %w", err)
+	}
+	return []byte(data), nil
+}
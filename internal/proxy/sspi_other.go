@@ -0,0 +1,8 @@
+//go:build !windows
+
+package proxy
+
+// newNegotiator is unavailable without SSPI.
+func newNegotiator(scheme, host string) (negotiator, error) {
+	return nil, errIntegratedAuthUnsupported
+}
@@ -0,0 +1,141 @@
+//go:build windows
+
+package proxy
+
+import (
+	"fmt"
+	"syscall"
+	"unsafe"
+)
+
+var (
+	secur32                        = syscall.NewLazyDLL("secur32.dll")
+	procAcquireCredentialsHandleW  = secur32.NewProc("AcquireCredentialsHandleW")
+	procInitializeSecurityContextW = secur32.NewProc("InitializeSecurityContextW")
+	procDeleteSecurityContext      = secur32.NewProc("DeleteSecurityContext")
+	procFreeCredentialsHandle      = secur32.NewProc("FreeCredentialsHandle")
+	procFreeContextBuffer          = secur32.NewProc("FreeContextBuffer")
+)
+
+const (
+	secpkgCredOutbound   = 2
+	securityNativeDrep   = 0x10
+	secbufferVersion     = 0
+	secbufferToken       = 2
+	iscReqMutualAuth     = 0x2
+	iscReqAllocateMemory = 0x100
+	iscReqConnection     = 0x800
+	secEOK               = 0
+	secIContinueNeeded   = 0x00090312
+)
+
+// secHandle mirrors the SSPI SecHandle (CredHandle, CtxtHandle).
+type secHandle struct {
+	lower uintptr
+	upper uintptr
+}
+
+// secBuffer mirrors SecBuffer.
+type secBuffer struct {
+	size       uint32
+	bufferType uint32
+	buffer     *byte
+}
+
+// secBufferDesc mirrors SecBufferDesc.
+type secBufferDesc struct {
+	version uint32
+	count   uint32
+	buffers *secBuffer
+}
+
+// sspiNegotiator runs a Negotiate or NTLM handshake through SSPI with the
+// credentials of the logged-on Windows user.
+type sspiNegotiator struct {
+	cred   secHandle
+	ctx    secHandle
+	hasCtx bool
+	target *uint16
+}
+
+// newNegotiator acquires outbound credentials for the SSPI package named by scheme.
+func newNegotiator(scheme, host string) (negotiator, error) {
+	pkg, err := syscall.UTF16PtrFromString(scheme)
+	if err != nil {
+		return nil, err
+	}
+	target, err := syscall.UTF16PtrFromString("HTTP/" + host)
+	if err != nil {
+		return nil, err
+	}
+
+	n := &sspiNegotiator{target: target}
+	var expiry int64
+	status, _, _ := procAcquireCredentialsHandleW.Call(
+		0,
+		uintptr(unsafe.Pointer(pkg)),
+		secpkgCredOutbound,
+		0, 0, 0, 0,
+		uintptr(unsafe.Pointer(&n.cred)),
+		uintptr(unsafe.Pointer(&expiry)),
+	)
+	if status != secEOK {
+		return nil, fmt.Errorf("AcquireCredentialsHandle(%s) failed: 0x%08x", scheme, uint32(status))
+	}
+	return n, nil
+}
+
+// step calls InitializeSecurityContext with the server challenge.
+func (n *sspiNegotiator) step(challenge []byte) ([]byte, error) {
+	var input *secBufferDesc
+	if len(challenge) > 0 {
+		in := secBuffer{size: uint32(len(challenge)), bufferType: secbufferToken, buffer: &challenge[0]}
+		input = &secBufferDesc{version: secbufferVersion, count: 1, buffers: &in}
+	}
+	out := secBuffer{bufferType: secbufferToken}
+	output := secBufferDesc{version: secbufferVersion, count: 1, buffers: &out}
+
+	// The first call creates the context, later calls continue it
+	var current uintptr
+	if n.hasCtx {
+		current = uintptr(unsafe.Pointer(&n.ctx))
+	}
+
+	var attrs uint32
+	var expiry int64
+	status, _, _ := procInitializeSecurityContextW.Call(
+		uintptr(unsafe.Pointer(&n.cred)),
+		current,
+		uintptr(unsafe.Pointer(n.target)),
+		iscReqMutualAuth|iscReqAllocateMemory|iscReqConnection,
+		0,
+		securityNativeDrep,
+		uintptr(unsafe.Pointer(input)),
+		0,
+		uintptr(unsafe.Pointer(&n.ctx)),
+		uintptr(unsafe.Pointer(&output)),
+		uintptr(unsafe.Pointer(&attrs)),
+		uintptr(unsafe.Pointer(&expiry)),
+	)
+	if out.buffer != nil {
+		defer procFreeContextBuffer.Call(uintptr(unsafe.Pointer(out.buffer)))
+	}
+	if status != secEOK && status != secIContinueNeeded {
+		return nil, fmt.Errorf("InitializeSecurityContext failed: 0x%08x", uint32(status))
+	}
+	n.hasCtx = true
+
+	token := make([]byte, out.size)
+	if out.size > 0 {
+		copy(token, unsafe.Slice(out.buffer, out.size))
+	}
+	return token, nil
+}
+
+// close releases the security context and credentials.
+func (n *sspiNegotiator) close() {
+	if n.hasCtx {
+		procDeleteSecurityContext.Call(uintptr(unsafe.Pointer(&n.ctx)))
+	}
+	procFreeCredentialsHandle.Call(uintptr(unsafe.Pointer(&n.cred)))
+}
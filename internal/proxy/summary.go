@@ -0,0 +1,97 @@
+package proxy
+
+import (
+	"fmt"
+	"net/url"
+	"strings"
+)
+
+// summarize describes a response in one sentence for screen readers and
+// notifications, e.g. "GET example.com → 200 OK in 340 ms, 2 redirects to
+// www.example.com, TLS 1.3, 120 KB gzip".
+func summarize(data *ResponseData) string {
+	method := "GET"
+	if data.RequestMethod != nil {
+		method = *data.RequestMethod
+	}
+	host := summaryHost(data.URL)
+	finalHost := host
+
+	// Name the request as sent, before redirects changed it
+	redirects := 0
+	for i, hop := range data.RedirectChain {
+		if i == 0 {
+			host = summaryHost(hop.URL)
+			if hop.Method != "" {
+				method = hop.Method
+			}
+		}
+		if hop.Status >= 300 && hop.Status < 400 {
+			redirects++
+		}
+	}
+
+	var b strings.Builder
+	fmt.Fprintf(&b, "%s %s → %d", method, host, data.Status)
+	if data.StatusText != "" {
+		b.WriteString(" " + data.StatusText)
+	}
+	fmt.Fprintf(&b, " in %d ms", data.Timing.Total)
+
+	switch {
+	case redirects == 1 && finalHost != host:
+		fmt.Fprintf(&b, ", 1 redirect to %s", finalHost)
+	case redirects == 1:
+		b.WriteString(", 1 redirect")
+	case redirects > 1 && finalHost != host:
+		fmt.Fprintf(&b, ", %d redirects to %s", redirects, finalHost)
+	case redirects > 1:
+		fmt.Fprintf(&b, ", %d redirects", redirects)
+	}
+
+	if data.TLS != nil && data.TLS.Protocol != nil {
+		b.WriteString(", " + *data.TLS.Protocol)
+	}
+
+	// Bytes on the wire, with the coding that shrank them
+	if sizes := data.SizeBreakdown; sizes != nil && sizes.Compressed != nil && sizes.Encoding != nil {
+		fmt.Fprintf(&b, ", %s %s", formatSummaryBytes(*sizes.Compressed), *sizes.Encoding)
+	} else {
+		fmt.Fprintf(&b, ", %s", formatSummaryBytes(data.Size))
+	}
+	if data.BodyTruncated {
+		b.WriteString(" (truncated)")
+	}
+	return b.String()
+}
+
+// summaryHost returns the host of a URL with its port, or the URL itself
+// when it does not parse.
+func summaryHost(raw string) string {
+	u, err := url.Parse(raw)
+	if err != nil || u.Host == "" {
+		return raw
+	}
+	return u.Host
+}
+
+// formatSummaryBytes formats a byte count in binary units: 512 B, 1.5 KB, 120 KB.
+func formatSummaryBytes(n int) string {
+	units := []string{"KB", "MB", "GB"}
+	if n < 1024 {
+		return fmt.Sprintf("%d B", n)
+	}
+	value := float64(n)
+	unit := ""
+	for _, u := range units {
+		value /= 1024
+		unit = u
+		if value < 1024 {
+			break
+		}
+	}
+	if value < 10 {
+		return fmt.Sprintf("%.1f %s", value, unit)
+	}
+	return fmt.Sprintf("%.0f %s", value, unit)
+}
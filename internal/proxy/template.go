@@ -0,0 +1,102 @@
+package proxy
+
+import (
+	"errors"
+	"fmt"
+	"sort"
+
+	"zone.digit.tommie/internal/environments"
+	"zone.digit.tommie/internal/storage"
+)
+
+// errEnvironmentNotFound is returned when a request names an unknown environment.
+type errEnvironmentNotFound string
+
+func (e errEnvironmentNotFound) Error() string {
+	return fmt.Sprintf("Environment not found: %s", string(e))
+}
+
+// environmentError converts a failure to load a request's environment to a response.
+func environmentError(err error) ProxyResponse {
+	var notFound errEnvironmentNotFound
+	if errors.As(err, &notFound) {
+		return NewErrorResponse(notFound.Error(), "ENVIRONMENT_NOT_FOUND")
+	}
+	return NewErrorResponse(fmt.Sprintf("Failed to load environment: %v", err), "STORAGE_ERROR")
+}
+
+// resolveTemplate expands {{variable}} references in the URL, headers and
+// body of a request from its environment and inline variables, which take
+// precedence. Requests without either are returned unchanged with nil info.
+func resolveTemplate(store storage.Store, request ProxyRequest) (ProxyRequest, *TemplateInfo, error) {
+	if request.Environment == nil && len(request.Variables) == 0 {
+		return request, nil, nil
+	}
+
+	variables, err := templateVariables(store, request)
+	if err != nil {
+		return request, nil, err
+	}
+
+	unresolved := map[string]bool{}
+	expand := func(text string) string {
+		return environments.Expand(text, variables, unresolved)
+	}
+
+	resolved := request
+	resolved.Environment = nil
+	resolved.Variables = nil
+	resolved.URL = expand(request.URL)
+	resolved.Headers = make(map[string]string, len(request.Headers))
+	for name, value := range request.Headers {
+		resolved.Headers[expand(name)] = expand(value)
+	}
+	if request.Body != nil {
+		resolved.Body = strPtr(expand(*request.Body))
+	}
+	if len(request.FormData) > 0 {
+		resolved.FormData = make([]FormPart, len(request.FormData))
+		for i, part := range request.FormData {
+			if part.Value != nil {
+				part.Value = strPtr(expand(*part.Value))
+			}
+			resolved.FormData[i] = part
+		}
+	}
+
+	info := &TemplateInfo{
+		Environment: request.Environment,
+		Template:    TemplatedRequest{URL: request.URL, Headers: request.Headers, Body: request.Body},
+		Resolved:    TemplatedRequest{URL: resolved.URL, Headers: resolved.Headers, Body: resolved.Body},
+	}
+	for name := range unresolved {
+		info.Unresolved = append(info.Unresolved, name)
+	}
+	sort.Strings(info.Unresolved)
+	return resolved, info, nil
+}
+
+// templateVariables merges the variables of a request's environment with its
+// inline variables, which take precedence.
+func templateVariables(store storage.Store, request ProxyRequest) (map[string]string, error) {
+	variables := map[string]string{}
+	if request.Environment != nil {
+		var env *environments.Environment
+		if store != nil {
+			var err error
+			if env, err = environments.Load(store, *request.Environment); err != nil {
+				return nil, err
+			}
+		}
+		if env == nil {
+			return nil, errEnvironmentNotFound(*request.Environment)
+		}
+		for name, value := range env.Variables {
+			variables[name] = value
+		}
+	}
+	for name, value := range request.Variables {
+		variables[name] = value
+	}
+	return variables, nil
+}
@@ -10,23 +10,42 @@ type DetailedTiming struct {
 	TCPEnd        *time.Time
 	TLSStart      *time.Time
 	TLSEnd        *time.Time
+	QUICStart     *time.Time
+	QUICEnd       *time.Time
 	RequestStart  *time.Time
 	TTFB          *time.Time
 	DownloadStart *time.Time
 	DownloadEnd   *time.Time
 	TotalStart    time.Time
+
+	clock    Clock
+	tlsSteps tlsSteps // Steps observed between TLSStart and TLSEnd
 }
 
 // NewDetailedTiming creates a new DetailedTiming instance with the total timer started.
 func NewDetailedTiming() *DetailedTiming {
+	return NewDetailedTimingWithClock(SystemClock)
+}
+
+// NewDetailedTimingWithClock creates a DetailedTiming reading the time from
+// clock, with the total timer started.
+func NewDetailedTimingWithClock(clock Clock) *DetailedTiming {
 	return &DetailedTiming{
-		TotalStart: time.Now(),
+		TotalStart: clock.Now(),
+		clock:      clock,
+	}
+}
+
+func (t *DetailedTiming) now() time.Time {
+	if t.clock == nil {
+		return time.Now()
 	}
+	return t.clock.Now()
 }
 
 // ToTimingInfo converts the detailed timing measurements into a TimingInfo struct.
 func (t *DetailedTiming) ToTimingInfo() TimingInfo {
-	endTime := time.Now()
+	endTime := t.now()
 	if t.DownloadEnd != nil {
 		endTime = *t.DownloadEnd
 	}
@@ -50,6 +69,12 @@ func (t *DetailedTiming) ToTimingInfo() TimingInfo {
 	if t.TLSStart != nil && t.TLSEnd != nil {
 		tls := uint64(t.TLSEnd.Sub(*t.TLSStart).Milliseconds())
 		info.TLS = &tls
+		info.TLSSteps = t.tlsSteps.timeline(*t.TLSStart, *t.TLSEnd)
+	}
+
+	if t.QUICStart != nil && t.QUICEnd != nil {
+		quic := uint64(t.QUICEnd.Sub(*t.QUICStart).Milliseconds())
+		info.QUIC = &quic
 	}
 
 	if t.RequestStart != nil && t.TTFB != nil {
@@ -70,60 +95,72 @@ func (t *DetailedTiming) ToTimingInfo() TimingInfo {
 
 // StartDNS starts the DNS timing phase.
 func (t *DetailedTiming) StartDNS() {
-	now := time.Now()
+	now := t.now()
 	t.DNSStart = &now
 }
 
 // EndDNS ends the DNS timing phase.
 func (t *DetailedTiming) EndDNS() {
-	now := time.Now()
+	now := t.now()
 	t.DNSEnd = &now
 }
 
 // StartTCP starts the TCP connection timing phase.
 func (t *DetailedTiming) StartTCP() {
-	now := time.Now()
+	now := t.now()
 	t.TCPStart = &now
 }
 
 // EndTCP ends the TCP connection timing phase.
 func (t *DetailedTiming) EndTCP() {
-	now := time.Now()
+	now := t.now()
 	t.TCPEnd = &now
 }
 
 // StartTLS starts the TLS handshake timing phase.
 func (t *DetailedTiming) StartTLS() {
-	now := time.Now()
+	now := t.now()
 	t.TLSStart = &now
 }
 
 // EndTLS ends the TLS handshake timing phase.
 func (t *DetailedTiming) EndTLS() {
-	now := time.Now()
+	now := t.now()
 	t.TLSEnd = &now
 }
 
+// StartQUIC starts the QUIC handshake timing phase.
+func (t *DetailedTiming) StartQUIC() {
+	now := t.now()
+	t.QUICStart = &now
+}
+
+// EndQUIC ends the QUIC handshake timing phase.
+func (t *DetailedTiming) EndQUIC() {
+	now := t.now()
+	t.QUICEnd = &now
+}
+
 // StartRequest marks the start of sending the request.
 func (t *DetailedTiming) StartRequest() {
-	now := time.Now()
+	now := t.now()
 	t.RequestStart = &now
 }
 
 // MarkTTFB marks the time to first byte.
 func (t *DetailedTiming) MarkTTFB() {
-	now := time.Now()
+	now := t.now()
 	t.TTFB = &now
 }
 
 // StartDownload starts the download timing phase.
 func (t *DetailedTiming) StartDownload() {
-	now := time.Now()
+	now := t.now()
 	t.DownloadStart = &now
 }
 
 // EndDownload ends the download timing phase.
 func (t *DetailedTiming) EndDownload() {
-	now := time.Now()
+	now := t.now()
 	t.DownloadEnd = &now
 }
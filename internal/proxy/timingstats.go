@@ -0,0 +1,60 @@
+package proxy
+
+import "zone.digit.tommie/internal/stats"
+
+// timingSamples collects the timings of several executions.
+type timingSamples struct {
+	total  []uint64
+	phases [5][]uint64 // DNS, TCP, TLS, TTFB, download
+}
+
+func (t *timingSamples) add(timing TimingInfo) {
+	t.total = append(t.total, timing.Total)
+	for i, phase := range []*uint64{timing.DNS, timing.TCP, timing.TLS, timing.TTFB, timing.Download} {
+		if phase != nil {
+			t.phases[i] = append(t.phases[i], *phase)
+		}
+	}
+}
+
+// latency returns the distribution of the total times, nil without samples.
+func (t *timingSamples) latency() *LatencyDistribution {
+	return latencyDistribution(t.total)
+}
+
+// timingPhases returns the distribution of each phase, nil without samples.
+func (t *timingSamples) timingPhases() *TimingPhases {
+	if len(t.total) == 0 {
+		return nil
+	}
+	return &TimingPhases{
+		DNS:      latencyDistribution(t.phases[0]),
+		TCP:      latencyDistribution(t.phases[1]),
+		TLS:      latencyDistribution(t.phases[2]),
+		TTFB:     latencyDistribution(t.phases[3]),
+		Download: latencyDistribution(t.phases[4]),
+	}
+}
+
+// latencyDistribution summarizes timings in milliseconds; nil without any.
+func latencyDistribution(timings []uint64) *LatencyDistribution {
+	summary := stats.Summarize(timings)
+	if summary == nil {
+		return nil
+	}
+	distribution := &LatencyDistribution{
+		Samples:   summary.Count,
+		Min:       summary.Min,
+		Mean:      summary.Mean,
+		P50:       summary.P50,
+		P90:       summary.P90,
+		P95:       summary.P95,
+		P99:       summary.P99,
+		Max:       summary.Max,
+		Histogram: make([]HistogramBucket, len(summary.Histogram)),
+	}
+	for i, bucket := range summary.Histogram {
+		distribution.Histogram[i] = HistogramBucket(bucket)
+	}
+	return distribution
+}
@@ -0,0 +1,76 @@
+package proxy
+
+import (
+	"crypto/tls"
+	"net"
+	"time"
+)
+
+// tlsSteps records when the steps of a TLS handshake were observed.
+type tlsSteps struct {
+	clientHello  *time.Time
+	serverHello  *time.Time
+	certificates *time.Time
+}
+
+// timeline converts the steps to milliseconds since the handshake started.
+func (s tlsSteps) timeline(start, end time.Time) *TLSTimeline {
+	offset := func(at *time.Time) *uint64 {
+		if at == nil {
+			return nil
+		}
+		ms := uint64(at.Sub(start).Milliseconds())
+		return &ms
+	}
+	return &TLSTimeline{
+		ClientHello:  offset(s.clientHello),
+		ServerHello:  offset(s.serverHello),
+		Certificates: offset(s.certificates),
+		Complete:     uint64(end.Sub(start).Milliseconds()),
+	}
+}
+
+// handshakeConn observes the steps of a TLS client handshake that crypto/tls
+// does not expose: its first write is the ClientHello and its first read
+// returns the start of the server's reply, the ServerHello. Certificates are
+// marked through the VerifyConnection hook of config, before the hook it had.
+type handshakeConn struct {
+	net.Conn
+	timing *DetailedTiming
+	steps  tlsSteps
+}
+
+func newHandshakeConn(conn net.Conn, timing *DetailedTiming, config *tls.Config) *handshakeConn {
+	c := &handshakeConn{Conn: conn, timing: timing}
+	verify := config.VerifyConnection
+	config.VerifyConnection = func(state tls.ConnectionState) error {
+		// Also called on resumption, when no certificates were sent
+		if !state.DidResume {
+			now := timing.now()
+			c.steps.certificates = &now
+		}
+		if verify == nil {
+			return nil
+		}
+		return verify(state)
+	}
+	return c
+}
+
+func (c *handshakeConn) Write(p []byte) (int, error) {
+	n, err := c.Conn.Write(p)
+	if n > 0 && c.steps.clientHello == nil {
+		now := c.timing.now()
+		c.steps.clientHello = &now
+	}
+	return n, err
+}
+
+func (c *handshakeConn) Read(p []byte) (int, error) {
+	n, err := c.Conn.Read(p)
+	if n > 0 && c.steps.serverHello == nil {
+		now := c.timing.now()
+		c.steps.serverHello = &now
+	}
+	return n, err
+}
@@ -0,0 +1,321 @@
+package proxy
+
+import (
+	"bufio"
+	"context"
+	"crypto/tls"
+	"crypto/x509"
+	"errors"
+	"fmt"
+	"io"
+	"net"
+	"net/http"
+	"net/url"
+	"strings"
+	"sync"
+	"time"
+
+	"golang.org/x/net/http2"
+
+	"zone.digit.tommie/internal/infra"
+)
+
+// httpVersion identifies the protocol version forced for a request.
+type httpVersion int
+
+const (
+	httpVersionAuto httpVersion = iota
+	httpVersion10
+	httpVersion11
+	httpVersion2
+	httpVersion3
+)
+
+// parseHTTPVersion normalizes the httpVersion field of a request.
+func parseHTTPVersion(value *string) (httpVersion, error) {
+	if value == nil {
+		return httpVersionAuto, nil
+	}
+
+	switch strings.ToUpper(strings.TrimSpace(*value)) {
+	case "", "AUTO":
+		return httpVersionAuto, nil
+	case "HTTP/1.0", "1.0":
+		return httpVersion10, nil
+	case "HTTP/1.1", "1.1":
+		return httpVersion11, nil
+	case "HTTP/2", "HTTP/2.0", "H2", "2":
+		return httpVersion2, nil
+	case "HTTP/3", "HTTP/3.0", "H3", "3":
+		return httpVersion3, nil
+	default:
+		return httpVersionAuto, fmt.Errorf("unknown HTTP version: %s", *value)
+	}
+}
+
+// alpnProtocols returns the ALPN protocols to offer for a version.
+func (v httpVersion) alpnProtocols() []string {
+	switch v {
+	case httpVersion10, httpVersion11:
+		return []string{"http/1.1"}
+	case httpVersion2:
+		return []string{"h2"}
+	case httpVersion3:
+		return []string{"h3"}
+	default:
+		return nil
+	}
+}
+
+// hopDialer dials the connections of a single hop, recording timing and TLS details.
+type hopDialer struct {
+	timing     *DetailedTiming
+	timeout    time.Duration
+	pins       map[string]string
+	serverName string
+	rootCAs    *x509.CertPool
+	nextProtos []string
+	secure     bool          // Plain dials carry TLS, so only dialTLS may record
+	capture    *wireCapture  // Records plaintext writes when raw capture is requested
+	head       *headRecorder // Records the plaintext response head
+
+	tlsInfo  *infra.CertInfo // Captured by dialTLS
+	remoteIP string          // Address of the last connection opened
+	policy   *Policy         // Checked against each address dialed; nil when going through a proxy
+
+	addresses map[string][]net.IP // Resolved addresses by host, raced instead of resolving again
+	preferIP  string              // Family tried first: v4, v6 or auto
+
+	mu       sync.Mutex
+	attempts []ConnectionAttempt // Every address tried, across the connections of the hop
+}
+
+// dial opens a plain TCP connection.
+func (d *hopDialer) dial(ctx context.Context, network, addr string) (net.Conn, error) {
+	d.timing.StartTCP()
+	dialer := &net.Dialer{Timeout: d.timeout}
+	if d.policy != nil {
+		dialer.Control = d.policy.control
+	}
+	conn, err := d.connect(ctx, dialer, network, pinnedAddr(d.pins, addr))
+	if err != nil {
+		return nil, err
+	}
+	d.timing.EndTCP()
+	if host, _, err := net.SplitHostPort(conn.RemoteAddr().String()); err == nil {
+		d.remoteIP = host
+	}
+	if !d.secure {
+		return d.wrap(conn), nil
+	}
+	return conn, nil
+}
+
+// dialTLS opens a TCP connection and performs the TLS handshake.
+func (d *hopDialer) dialTLS(ctx context.Context, network, addr string) (net.Conn, error) {
+	conn, err := d.dial(ctx, network, addr)
+	if err != nil {
+		return nil, err
+	}
+	d.timing.StartTLS()
+
+	config := d.tlsConfig()
+	observed := newHandshakeConn(conn, d.timing, config)
+	tlsConn := tls.Client(observed, config)
+	if err := tlsConn.HandshakeContext(ctx); err != nil {
+		conn.Close()
+		return nil, err
+	}
+	state := tlsConn.ConnectionState()
+	d.timing.tlsSteps = observed.steps
+	d.timing.EndTLS()
+
+	// net/http only speaks HTTP/2 over a *tls.Conn, so h2 connections stay unwrapped
+	if state.NegotiatedProtocol != "h2" {
+		return d.wrap(tlsConn), nil
+	}
+	return tlsConn, nil
+}
+
+// wrap records the plaintext traffic of conn.
+func (d *hopDialer) wrap(conn net.Conn) net.Conn {
+	if d.head == nil {
+		return conn
+	}
+	return &recordingConn{Conn: conn, capture: d.capture, head: d.head}
+}
+
+// tlsConfig returns the configuration of handshakes with the origin. The
+// certificates are verified by verifyConnection instead of crypto/tls.
+func (d *hopDialer) tlsConfig() *tls.Config {
+	return &tls.Config{
+		ServerName:         d.serverName,
+		NextProtos:         d.nextProtos,
+		InsecureSkipVerify: true,
+		VerifyConnection:   d.verifyConnection,
+	}
+}
+
+// verifyConnection validates the certificates of a handshake and records
+// the outcome in tlsInfo, then fails the handshake with a *CertificateError
+// when they are not valid.
+func (d *hopDialer) verifyConnection(state tls.ConnectionState) error {
+	d.tlsInfo = infra.VerifyCertInfo(&state, d.serverName, d.rootCAs)
+	if !d.tlsInfo.Validation.Valid() {
+		return &CertificateError{Info: d.tlsInfo}
+	}
+	return nil
+}
+
+// CertificateError rejects a handshake whose certificate failed validation.
+// Info holds every reason it failed.
+type CertificateError struct {
+	Info *infra.CertInfo
+}
+
+func (e *CertificateError) Error() string {
+	return "certificate rejected: " + strings.Join(e.Info.Validation.Errors, "; ")
+}
+
+// newRoundTripper builds the transport for a hop according to the forced version.
+func newRoundTripper(dialer *hopDialer, version httpVersion, proxyURL *url.URL, isHTTPS bool) (http.RoundTripper, error) {
+	switch {
+	case version == httpVersion3:
+		if !isHTTPS {
+			return nil, errors.New("HTTP/3 needs an https URL")
+		}
+		if proxyURL != nil {
+			return nil, errors.New("HTTP/3 cannot be forced through an upstream proxy")
+		}
+		return newHTTP3Transport(dialer), nil
+
+	case version == httpVersion10:
+		if proxyURL != nil {
+			return nil, errors.New("HTTP/1.0 cannot be forced through an upstream proxy")
+		}
+		return &http10RoundTripper{dialer: dialer, isHTTPS: isHTTPS}, nil
+
+	case version == httpVersion2 && !isHTTPS:
+		// Cleartext HTTP/2 (h2c) with prior knowledge
+		if proxyURL != nil {
+			return nil, errors.New("cleartext HTTP/2 cannot be forced through an upstream proxy")
+		}
+		return &http2.Transport{
+			AllowHTTP: true,
+			DialTLSContext: func(ctx context.Context, network, addr string, _ *tls.Config) (net.Conn, error) {
+				return dialer.dial(ctx, network, addr)
+			},
+		}, nil
+	}
+
+	transport := &http.Transport{
+		DialContext: dialer.dial,
+		TLSClientConfig: &tls.Config{
+			InsecureSkipVerify: false,
+			RootCAs:            dialer.rootCAs,
+			NextProtos:         dialer.nextProtos,
+		},
+		TLSHandshakeTimeout: dialer.timeout,
+		DisableCompression:  false,
+		ForceAttemptHTTP2:   version == httpVersion2,
+	}
+	if proxyURL != nil {
+		transport.Proxy = http.ProxyURL(proxyURL)
+	}
+	if version == httpVersion11 {
+		// A non-nil empty map disables HTTP/2
+		transport.TLSNextProto = make(map[string]func(string, *tls.Conn) http.RoundTripper)
+	}
+
+	// Capture TLS info
+	if isHTTPS {
+		transport.DialTLSContext = dialer.dialTLS
+		// Tunnels through a proxy handshake with TLSClientConfig instead
+		transport.TLSClientConfig = dialer.tlsConfig()
+	}
+
+	return transport, nil
+}
+
+// http10RoundTripper sends requests with an HTTP/1.0 request line, which
+// net/http cannot do, over a fresh connection per request.
+type http10RoundTripper struct {
+	dialer  *hopDialer
+	isHTTPS bool
+}
+
+// RoundTrip implements http.RoundTripper.
+func (t *http10RoundTripper) RoundTrip(req *http.Request) (*http.Response, error) {
+	port := req.URL.Port()
+	if port == "" {
+		port = "80"
+		if t.isHTTPS {
+			port = "443"
+		}
+	}
+	addr := net.JoinHostPort(req.URL.Hostname(), port)
+
+	var conn net.Conn
+	var err error
+	if t.isHTTPS {
+		conn, err = t.dialer.dialTLS(req.Context(), "tcp", addr)
+	} else {
+		conn, err = t.dialer.dial(req.Context(), "tcp", addr)
+	}
+	if err != nil {
+		return nil, err
+	}
+	if deadline, ok := req.Context().Deadline(); ok {
+		conn.SetDeadline(deadline)
+	}
+
+	var body []byte
+	if req.Body != nil {
+		body, err = io.ReadAll(req.Body)
+		req.Body.Close()
+		if err != nil {
+			conn.Close()
+			return nil, err
+		}
+	}
+
+	host := req.Host
+	if host == "" {
+		host = req.URL.Host
+	}
+
+	w := bufio.NewWriter(conn)
+	fmt.Fprintf(w, "%s %s HTTP/1.0\r\n", req.Method, req.URL.RequestURI())
+	fmt.Fprintf(w, "Host: %s\r\n", host)
+	req.Header.Write(w)
+	if len(body) > 0 && req.Header.Get("Content-Length") == "" {
+		fmt.Fprintf(w, "Content-Length: %d\r\n", len(body))
+	}
+	w.WriteString("\r\n")
+	w.Write(body)
+	if err := w.Flush(); err != nil {
+		conn.Close()
+		return nil, err
+	}
+
+	resp, err := http.ReadResponse(bufio.NewReader(conn), req)
+	if err != nil {
+		conn.Close()
+		return nil, err
+	}
+	resp.Body = &connBody{ReadCloser: resp.Body, conn: conn}
+	return resp, nil
+}
+
+// connBody closes the underlying connection together with the response body.
+type connBody struct {
+	io.ReadCloser
+	conn net.Conn
+}
+
+// Close closes the body and its connection.
+func (b *connBody) Close() error {
+	err := b.ReadCloser.Close()
+	b.conn.Close()
+	return err
+}
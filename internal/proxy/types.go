@@ -1,94 +1,859 @@
 // Package proxy provides HTTP proxy functionality for the desktop app.
 package proxy
 
+import "encoding/json"
+
 // ProxyRequest represents an incoming proxy request from the frontend.
 type ProxyRequest struct {
-	Method  string            `json:"method"`
-	URL     string            `json:"url"`
-	Headers map[string]string `json:"headers"`
-	Body    *string           `json:"body,omitempty"`
-	Timeout *uint64           `json:"timeout,omitempty"` // Timeout in milliseconds
+	Method             string              `json:"method"`
+	URL                string              `json:"url"`
+	Headers            map[string]string   `json:"headers"`
+	Body               *string             `json:"body,omitempty"`
+	BodyBase64         *string             `json:"bodyBase64,omitempty"`         // Binary body; mutually exclusive with Body
+	FormData           []FormPart          `json:"formData,omitempty"`           // Multipart form; mutually exclusive with Body
+	Timeout            *uint64             `json:"timeout,omitempty"`            // Timeout in milliseconds
+	PinDNS             bool                `json:"pinDns,omitempty"`             // Pin resolved IPs in a new or existing session
+	PinToken           *string             `json:"pinToken,omitempty"`           // Session returned by a previous pinned request
+	HTTPVersion        *string             `json:"httpVersion,omitempty"`        // Protocol to force; empty negotiates automatically
+	CookieJar          *string             `json:"cookieJar,omitempty"`          // Named persistent cookie jar to send from and store into
+	BrowserContext     *BrowserContext     `json:"browserContext,omitempty"`     // Page issuing the request, for SameSite simulation
+	CaptureRaw         bool                `json:"captureRaw,omitempty"`         // Return the serialized request as sent on the wire
+	CaptureRawLimit    *int                `json:"captureRawLimit,omitempty"`    // Max raw bytes returned (default 64 KiB)
+	Beautify           bool                `json:"beautify,omitempty"`           // Pretty-print minified JavaScript/CSS bodies
+	StrictRedirects    bool                `json:"strictRedirects,omitempty"`    // Resend the original method and body on every redirect
+	ForwardCredentials bool                `json:"forwardCredentials,omitempty"` // Keep Authorization and Cookie headers on cross-origin redirects
+	HexdumpBytes       *int                `json:"hexdumpBytes,omitempty"`       // Binary body bytes included in bodyHexdump (default 512, 0 disables)
+	BodyStatsThreshold *int                `json:"bodyStatsThreshold,omitempty"` // Body bytes from which bodyStats are computed (default 1 MiB, 0 disables)
+	ProbeRange         bool                `json:"probeRange,omitempty"`         // Verify Range support of audio and video with a second request
+	FetchSourceMap     bool                `json:"fetchSourceMap,omitempty"`     // Fetch the source map a script references and list its sources
+	Paginate           *PaginationOptions  `json:"paginate,omitempty"`           // Follow next-page references and aggregate every page
+	Environment        *string             `json:"environment,omitempty"`        // Named variable set resolving {{variable}} in URL, headers and body
+	Variables          map[string]string   `json:"variables,omitempty"`          // Template variables overriding the environment
+	EventStream        *EventStreamOptions `json:"eventStream,omitempty"`        // Limits for reading a text/event-stream response
+	GraphQL            *GraphQLRequest     `json:"graphql,omitempty"`            // GraphQL operation sent as the JSON POST body; mutually exclusive with Body
+	DNSServers         []string            `json:"dnsServers,omitempty"`         // Nameservers resolving this request, e.g. 1.1.1.1, tls://1.1.1.1 or https://dns.google/dns-query
+	PreferIP           *string             `json:"preferIp,omitempty"`           // Address family tried first when connecting: v4, v6 or auto (default, IPv6 first)
+	Retry              *RetryPolicy        `json:"retry,omitempty"`              // Repeat the request when it fails or returns a retryable status
+	MaxBodyBytes       *int                `json:"maxBodyBytes,omitempty"`       // Response body bytes kept; may only lower the service limit (default 50 MiB)
+	Download           *DownloadOptions    `json:"download,omitempty"`           // Stream the final response body to a file instead of returning it
+	Integrity          *string             `json:"integrity,omitempty"`          // Expected Subresource Integrity hashes of the decoded body, e.g. "sha384-..."
+	CaptureFixture     bool                `json:"captureFixture,omitempty"`     // Write the full response as JSON to the fixture directory
+	Revalidate         bool                `json:"revalidate,omitempty"`         // Re-send the request with If-None-Match/If-Modified-Since to check for 304
+	Auth               *AuthOptions        `json:"auth,omitempty"`               // Credentials sent as the Authorization header, replacing any given
+	JWTKey             *string             `json:"jwtKey,omitempty"`             // HMAC secret or PEM public key verifying the JWTs found
+	Scripts            *RequestScripts     `json:"scripts,omitempty"`            // Sandboxed scripts run before the request and on its response
+	Assertions         []Assertion         `json:"assertions,omitempty"`         // Checks evaluated against the response
+	Extract            []Extraction        `json:"extract,omitempty"`            // Values of the JSON response saved as environment variables
+	DNSSecurity        bool                `json:"dnsSecurity,omitempty"`        // Look up the CAA policy and DNSSEC status of the final host
+}
+
+// AuthOptions computes the Authorization header of a request.
+type AuthOptions struct {
+	Type     string `json:"type"`               // basic, bearer, digest, ntlm or negotiate
+	Username string `json:"username,omitempty"` // DOMAIN\user or user@domain for NTLM; none sends the logged-on user's (desktop)
+	Password string `json:"password,omitempty"`
+	Token    string `json:"token,omitempty"` // Bearer token
+}
+
+// DownloadOptions writes a response body to a file.
+type DownloadOptions struct {
+	Path *string `json:"path,omitempty"` // Absolute destination (desktop only); otherwise a temporary file served from url
+}
+
+// DownloadInfo describes a response body written to a file.
+type DownloadInfo struct {
+	ID             string             `json:"id,omitempty"`   // Temporary download, kept for an hour
+	URL            *string            `json:"url,omitempty"`  // Where the server serves a temporary download
+	Path           *string            `json:"path,omitempty"` // File written; only reported by the desktop app
+	FileName       string             `json:"fileName"`       // Suggested by Content-Disposition or the URL
+	Size           int64              `json:"size"`           // Bytes written, after decoding content codings
+	SHA256         string             `json:"sha256"`
+	Duration       uint64             `json:"duration"`       // Milliseconds
+	BytesPerSecond float64            `json:"bytesPerSecond"` // Mean throughput
+	Throughput     []ThroughputSample `json:"throughput"`
+}
+
+// ThroughputSample is the progress of a download at a point in time.
+type ThroughputSample struct {
+	Offset         uint64  `json:"offset"`         // Milliseconds since the body started
+	Bytes          int64   `json:"bytes"`          // Bytes written so far
+	BytesPerSecond float64 `json:"bytesPerSecond"` // Since the previous sample
+}
+
+// RetryPolicy configures retrying a request. Only idempotent methods are
+// retried unless retryUnsafe is set.
+type RetryPolicy struct {
+	MaxAttempts     *int     `json:"maxAttempts,omitempty"`     // Attempts including the first (default 3, max 10)
+	Backoff         *string  `json:"backoff,omitempty"`         // constant, linear or exponential (default)
+	DelayMS         *int     `json:"delayMs,omitempty"`         // Base delay between attempts (default 500)
+	MaxDelayMS      *int     `json:"maxDelayMs,omitempty"`      // Cap on a single delay (default 30000); a longer Retry-After stops retrying
+	Jitter          bool     `json:"jitter,omitempty"`          // Randomize each computed delay between half and all of it
+	RetryOn         []string `json:"retryOn,omitempty"`         // Statuses (503), status classes (5xx) and failures (dns, connect, timeout, busy); default 5xx, 429, dns, connect, timeout
+	HonorRetryAfter *bool    `json:"honorRetryAfter,omitempty"` // Wait as long as a Retry-After header asks (default true)
+	RetryUnsafe     bool     `json:"retryUnsafe,omitempty"`     // Also retry POST, PATCH and other non-idempotent methods
+}
+
+// RetryInfo records every attempt of a request sent with a retry policy.
+type RetryInfo struct {
+	Attempts []RetryAttempt       `json:"attempts"`
+	Stopped  string               `json:"stopped"`           // Why no further attempt was made
+	Latency  *LatencyDistribution `json:"latency,omitempty"` // Durations of the attempts; with two or more
+	Phases   *TimingPhases        `json:"phases,omitempty"`  // Timings of the attempts that received a response; with two or more
+}
+
+// RetryAttempt is one execution of a retried request.
+type RetryAttempt struct {
+	Attempt    int     `json:"attempt"`
+	Status     *uint16 `json:"status,omitempty"`
+	Error      *string `json:"error,omitempty"`
+	Code       *string `json:"code,omitempty"`
+	Duration   uint64  `json:"duration"`
+	Reason     *string `json:"reason,omitempty"`     // Condition that caused the next attempt
+	Delay      *uint64 `json:"delay,omitempty"`      // Milliseconds waited before the next attempt
+	RetryAfter bool    `json:"retryAfter,omitempty"` // Whether the delay came from a Retry-After header
+}
+
+// GraphQLRequest is a GraphQL operation.
+type GraphQLRequest struct {
+	Query         string                 `json:"query"`
+	Variables     map[string]interface{} `json:"variables,omitempty"`
+	OperationName *string                `json:"operationName,omitempty"` // Operation to run when the query defines several
+}
+
+// EventStreamOptions bounds how much of a text/event-stream response is read.
+type EventStreamOptions struct {
+	MaxEvents     *int `json:"maxEvents,omitempty"`     // Events read before stopping (default 100, max 10000)
+	MaxDurationMS *int `json:"maxDurationMs,omitempty"` // Milliseconds read before stopping (default 10000); the timeout still applies
+}
+
+// PaginationOptions configures fetching all pages of a paginated endpoint.
+// Link headers are always followed; body fields are detected when not given.
+type PaginationOptions struct {
+	MaxPages    *int    `json:"maxPages,omitempty"`    // Pages fetched including the first (default 10, max 100)
+	NextField   *string `json:"nextField,omitempty"`   // Dot path of the next page URL in a JSON body
+	CursorField *string `json:"cursorField,omitempty"` // Dot path of the next cursor in a JSON body
+	CursorParam *string `json:"cursorParam,omitempty"` // Query parameter carrying the cursor (default "cursor")
+}
+
+// CurlImport is a request parsed from a curl command line.
+type CurlImport struct {
+	Request  ProxyRequest `json:"request"`
+	Warnings []string     `json:"warnings,omitempty"` // Options that were ignored or only partly applied
+}
+
+// BrowserContext describes the page issuing a request so cookie jars can
+// apply the SameSite rules a browser would.
+type BrowserContext struct {
+	Initiator  string `json:"initiator,omitempty"` // Origin of the issuing page; empty for a typed navigation
+	Navigation bool   `json:"navigation"`          // Top-level navigation rather than fetch or subresource
+}
+
+// FormPart is a text field or file of a multipart/form-data body.
+type FormPart struct {
+	Name        string  `json:"name"`
+	Value       *string `json:"value,omitempty"`       // Text field value
+	FileBase64  *string `json:"fileBase64,omitempty"`  // File content; makes this a file part
+	Filename    *string `json:"filename,omitempty"`    // Defaults to the field name
+	ContentType *string `json:"contentType,omitempty"` // Defaults to application/octet-stream for files
 }
 
 // TimingInfo contains detailed timing information for an HTTP request.
 type TimingInfo struct {
-	Total    uint64  `json:"total"`              // Total request time in milliseconds
-	DNS      *uint64 `json:"dns,omitempty"`      // DNS lookup time
-	TCP      *uint64 `json:"tcp,omitempty"`      // TCP connection time
-	TLS      *uint64 `json:"tls,omitempty"`      // TLS handshake time
-	TTFB     *uint64 `json:"ttfb,omitempty"`     // Time to first byte
-	Download *uint64 `json:"download,omitempty"` // Content download time
-	Blocked  *uint64 `json:"blocked,omitempty"`  // Time blocked/queued
+	Total    uint64       `json:"total"`              // Total request time in milliseconds
+	DNS      *uint64      `json:"dns,omitempty"`      // DNS lookup time
+	TCP      *uint64      `json:"tcp,omitempty"`      // TCP connection time
+	TLS      *uint64      `json:"tls,omitempty"`      // TLS handshake time
+	TLSSteps *TLSTimeline `json:"tlsSteps,omitempty"` // Steps of the TLS handshake
+	QUIC     *uint64      `json:"quic,omitempty"`     // QUIC handshake time including TLS; replaces TCP and TLS over HTTP/3
+	TTFB     *uint64      `json:"ttfb,omitempty"`     // Time to first byte
+	Download *uint64      `json:"download,omitempty"` // Content download time
+	Blocked  *uint64      `json:"blocked,omitempty"`  // Time blocked/queued
+}
+
+// TLSTimeline breaks a TLS handshake into the steps observable from the
+// client, in milliseconds since the handshake started. Steps not observed
+// are omitted.
+type TLSTimeline struct {
+	ClientHello  *uint64 `json:"clientHello,omitempty"`  // ClientHello sent
+	ServerHello  *uint64 `json:"serverHello,omitempty"`  // First bytes of the server's reply, which opens with its ServerHello
+	Certificates *uint64 `json:"certificates,omitempty"` // Server certificates received and verified; absent on session resumption
+	Complete     uint64  `json:"complete"`               // Handshake finished
 }
 
 // RedirectHop represents information about a redirect in the chain.
 type RedirectHop struct {
-	URL      string            `json:"url"`
-	Status   uint16            `json:"status"`
-	Duration uint64            `json:"duration"`
-	Headers  map[string]string `json:"headers,omitempty"`
-	Opaque   *bool             `json:"opaque,omitempty"`
-	Message  *string           `json:"message,omitempty"`
+	URL                  string              `json:"url"`
+	Method               string              `json:"method,omitempty"` // Method sent to this hop
+	Status               uint16              `json:"status"`
+	Duration             uint64              `json:"duration"`
+	Headers              map[string]string   `json:"headers,omitempty"`
+	Opaque               *bool               `json:"opaque,omitempty"`
+	Message              *string             `json:"message,omitempty"`
+	Proxy                *ProxyDecision      `json:"proxy,omitempty"`
+	ServerIP             *string             `json:"serverIp,omitempty"` // Address this hop connected to
+	Protocol             *string             `json:"protocol,omitempty"`
+	TLS                  *TLSInfo            `json:"tls,omitempty"`                  // Handshake of this hop's connection
+	Timing               *TimingInfo         `json:"timing,omitempty"`               // Phases of this hop; only the first includes DNS
+	CredentialsForwarded *bool               `json:"credentialsForwarded,omitempty"` // Whether the Authorization and Cookie headers given were sent to this hop
+	ConnectionAttempts   []ConnectionAttempt `json:"connectionAttempts,omitempty"`   // Addresses raced to connect to this hop
+}
+
+// ConnectionAttempt is one address tried while connecting, as raced by Happy Eyeballs.
+type ConnectionAttempt struct {
+	Address  string  `json:"address"`
+	Family   string  `json:"family"`   // v4 or v6
+	Start    uint64  `json:"start"`    // Milliseconds after the first attempt of the connection
+	Duration uint64  `json:"duration"` // Milliseconds until it connected, failed or was canceled
+	Outcome  string  `json:"outcome"`  // connected; refused, unreachable, timeout or failed; or canceled once another address connected
+	Error    *string `json:"error,omitempty"`
+}
+
+// ProxyDecision reports whether a request went direct or through an upstream proxy.
+type ProxyDecision struct {
+	Direct bool   `json:"direct"`
+	Proxy  string `json:"proxy,omitempty"` // Proxy URL with credentials redacted
+	Reason string `json:"reason"`
+	PAC    string `json:"pac,omitempty"` // Raw FindProxyForURL result when a PAC file decided
 }
 
 // TLSInfo contains TLS/SSL certificate information.
 type TLSInfo struct {
-	Protocol  *string  `json:"protocol,omitempty"`
-	Cipher    *string  `json:"cipher,omitempty"`
-	Issuer    *string  `json:"issuer,omitempty"`
-	Subject   *string  `json:"subject,omitempty"`
-	ValidFrom *uint64  `json:"validFrom,omitempty"`
-	ValidTo   *uint64  `json:"validTo,omitempty"`
-	Valid     *bool    `json:"valid,omitempty"`
-	SANs      []string `json:"san,omitempty"` // Subject Alternative Names
+	Protocol   *string           `json:"protocol,omitempty"`
+	Cipher     *string           `json:"cipher,omitempty"`
+	Issuer     *string           `json:"issuer,omitempty"`
+	Subject    *string           `json:"subject,omitempty"`
+	ValidFrom  *uint64           `json:"validFrom,omitempty"`
+	ValidTo    *uint64           `json:"validTo,omitempty"`
+	Valid      *bool             `json:"valid,omitempty"`
+	SANs       []string          `json:"san,omitempty"`   // Subject Alternative Names
+	Chain      []CertificateInfo `json:"chain,omitempty"` // Presented chain, leaf first
+	Validation *CertValidation   `json:"validation,omitempty"`
+}
+
+// CertValidation describes why a certificate is considered valid or not.
+type CertValidation struct {
+	HostnameMatch bool     `json:"hostnameMatch"`
+	Expired       bool     `json:"expired"`
+	NotYetValid   bool     `json:"notYetValid"`
+	ChainVerified bool     `json:"chainVerified"`
+	Errors        []string `json:"errors,omitempty"`
+	Warnings      []string `json:"warnings,omitempty"`
+}
+
+// CertificateInfo describes a single certificate of the presented chain.
+type CertificateInfo struct {
+	Subject      string `json:"subject"`
+	Issuer       string `json:"issuer"`
+	ValidFrom    uint64 `json:"validFrom"`
+	ValidTo      uint64 `json:"validTo"`
+	KeyAlgorithm string `json:"keyAlgorithm"`
+	Fingerprint  string `json:"fingerprintSha256"`
+	SerialNumber string `json:"serialNumber"`
 }
 
 // SizeBreakdown contains response size information.
 type SizeBreakdown struct {
-	Headers          int      `json:"headers"`
-	Body             int      `json:"body"`
-	Total            int      `json:"total"`
-	Compressed       *int     `json:"compressed,omitempty"`
-	Uncompressed     *int     `json:"uncompressed,omitempty"`
-	Encoding         *string  `json:"encoding,omitempty"`
-	CompressionRatio *float64 `json:"compressionRatio,omitempty"`
+	Headers          int             `json:"headers"`
+	Body             int             `json:"body"`
+	Total            int             `json:"total"`
+	Compressed       *int            `json:"compressed,omitempty"`
+	Uncompressed     *int            `json:"uncompressed,omitempty"`
+	Encoding         *string         `json:"encoding,omitempty"`
+	CompressionRatio *float64        `json:"compressionRatio,omitempty"`
+	EncodingLayers   []EncodingLayer `json:"encodingLayers,omitempty"` // Decoding steps for chained encodings
+}
+
+// EncodingLayer describes the sizes of a single content-encoding decoding step.
+type EncodingLayer struct {
+	Encoding   string `json:"encoding"`
+	InputSize  int    `json:"inputSize"`
+	OutputSize int    `json:"outputSize"`
 }
 
 // ResponseData contains successful response data matching extension protocol.
 type ResponseData struct {
-	Status          uint16            `json:"status"`
-	StatusText      string            `json:"statusText"`
-	Headers         map[string]string `json:"headers"`
-	RequestHeaders  map[string]string `json:"requestHeaders,omitempty"`
-	Body            string            `json:"body"`
-	BodyBase64      *string           `json:"bodyBase64,omitempty"`
-	IsBinary        bool              `json:"isBinary"`
-	Size            int               `json:"size"`
-	Timing          TimingInfo        `json:"timing"`
-	URL             string            `json:"url"`
-	Redirected      bool              `json:"redirected"`
-	RedirectChain   []RedirectHop     `json:"redirectChain,omitempty"`
-	TLS             *TLSInfo          `json:"tls,omitempty"`
-	SizeBreakdown   *SizeBreakdown    `json:"sizeBreakdown,omitempty"`
-	ServerIP        *string           `json:"serverIp,omitempty"`
-	Protocol        *string           `json:"protocol,omitempty"`
-	FromCache       *bool             `json:"fromCache,omitempty"`
-	ResourceType    *string           `json:"resourceType,omitempty"`
-	RequestBodySize *int              `json:"requestBodySize,omitempty"`
-	Connection      *string           `json:"connection,omitempty"`
-	ServerSoftware  *string           `json:"serverSoftware,omitempty"`
-	Hostname        *string           `json:"hostname,omitempty"`    // Hostname from URL
-	Port            *string           `json:"port,omitempty"`        // Port from URL
-	ResolvedIPs     []string          `json:"resolvedIps,omitempty"` // All resolved IP addresses
+	Status               uint16              `json:"status"`
+	StatusText           string              `json:"statusText"`
+	Headers              []HeaderField       `json:"headers"`                 // In wire order, duplicates preserved
+	RequestMethod        *string             `json:"requestMethod,omitempty"` // Method of the final request; redirects may change it
+	RequestHeaders       map[string]string   `json:"requestHeaders,omitempty"`
+	Body                 string              `json:"body"`
+	BodyBase64           *string             `json:"bodyBase64,omitempty"`
+	BodyHexdump          *string             `json:"bodyHexdump,omitempty"` // Hex and ASCII preview of the start of a binary body
+	IsBinary             bool                `json:"isBinary"`
+	Size                 int                 `json:"size"`
+	BodyTruncated        bool                `json:"bodyTruncated,omitempty"` // Body cut at maxBodyBytes; size counts the bytes kept
+	ContentLength        *int64              `json:"contentLength,omitempty"` // Body length announced by the server, before decoding
+	Timing               TimingInfo          `json:"timing"`
+	URL                  string              `json:"url"`
+	Redirected           bool                `json:"redirected"`
+	RedirectChain        []RedirectHop       `json:"redirectChain,omitempty"`
+	TLS                  *TLSInfo            `json:"tls,omitempty"`
+	DNSSecurity          *DNSSecurityInfo    `json:"dnsSecurity,omitempty"` // CAA policy and DNSSEC status of the host, when requested
+	SizeBreakdown        *SizeBreakdown      `json:"sizeBreakdown,omitempty"`
+	ServerIP             *string             `json:"serverIp,omitempty"`
+	Protocol             *string             `json:"protocol,omitempty"`
+	FromCache            *bool               `json:"fromCache,omitempty"`
+	ResourceType         *string             `json:"resourceType,omitempty"`
+	RequestBodySize      *int                `json:"requestBodySize,omitempty"`
+	RequestBodyEncoding  *string             `json:"requestBodyEncoding,omitempty"` // How the request body was supplied: text, base64 or multipart
+	RenderedRequest      *string             `json:"renderedRequest,omitempty"`     // Generated request (e.g. multipart) as sent, binary content elided
+	Connection           *string             `json:"connection,omitempty"`
+	ServerSoftware       *string             `json:"serverSoftware,omitempty"`
+	Hostname             *string             `json:"hostname,omitempty"`             // Hostname from URL
+	Port                 *string             `json:"port,omitempty"`                 // Port from URL
+	ResolvedIPs          []string            `json:"resolvedIps,omitempty"`          // All resolved IP addresses
+	PinToken             *string             `json:"pinToken,omitempty"`             // DNS pin session for follow-up requests
+	DNSPinned            *bool               `json:"dnsPinned,omitempty"`            // Whether DNS was answered from a pin
+	AltSvc               []AltSvcEntry       `json:"altSvc,omitempty"`               // Alternative services advertised by the server
+	HTTP3Offered         bool                `json:"http3Offered"`                   // Whether Alt-Svc advertises HTTP/3
+	ProxyDecision        *ProxyDecision      `json:"proxyDecision,omitempty"`        // Upstream proxy used for the final hop
+	IntegratedAuth       *IntegratedAuthInfo `json:"integratedAuth,omitempty"`       // Negotiate/NTLM handshake of the final hop
+	ServedBy             *ServedBy           `json:"servedBy,omitempty"`             // Heuristic CDN/provider detection
+	CookieJar            *CookieJarInfo      `json:"cookieJar,omitempty"`            // Cookie jar activity across all hops
+	RawRequest           *RawRequest         `json:"rawRequest,omitempty"`           // Final request as written to the connection
+	RawResponseHead      *string             `json:"rawResponseHead,omitempty"`      // Status line and headers as received (HTTP/1.x only)
+	Beautified           *BeautifiedBody     `json:"beautified,omitempty"`           // Pretty-printed body when beautify was requested
+	SourceMap            *SourceMapInfo      `json:"sourceMap,omitempty"`            // Source map referenced by a script when fetchSourceMap was requested
+	ArchiveInfo          *ArchiveInfo        `json:"archiveInfo,omitempty"`          // Entries of a zip, tar or gzip body
+	MediaInfo            *MediaInfo          `json:"mediaInfo,omitempty"`            // Metadata of a document, audio or video body
+	Analysis             *ResponseAnalysis   `json:"analysis,omitempty"`             // Heuristics derived from a text body
+	Feed                 *FeedPreview        `json:"feed,omitempty"`                 // Items of an RSS or Atom body
+	SOAP                 *SOAPInfo           `json:"soap,omitempty"`                 // Envelope and fault of a SOAP body
+	Pagination           *PaginationInfo     `json:"pagination,omitempty"`           // Every page fetched when paginate was requested
+	Template             *TemplateInfo       `json:"template,omitempty"`             // Templated request and its resolution when variables were used
+	RateLimit            *RateLimitInfo      `json:"rateLimit,omitempty"`            // Quota advertised by RateLimit or X-RateLimit-* headers
+	EventStream          *EventStreamInfo    `json:"eventStream,omitempty"`          // Events read from a text/event-stream body
+	ClockSkew            *ClockSkewInfo      `json:"clockSkew,omitempty"`            // Server clock offset estimated from the Date header
+	GraphQL              *GraphQLInfo        `json:"graphql,omitempty"`              // Data and errors of the response to a GraphQL operation
+	Trailers             []HeaderField       `json:"trailers,omitempty"`             // Trailer fields received after the body
+	SecurityAnalysis     *SecurityAnalysis   `json:"securityAnalysis,omitempty"`     // Security findings about the exchange
+	CredentialsForwarded *bool               `json:"credentialsForwarded,omitempty"` // Whether the Authorization and Cookie headers given reached the final hop
+	Resolver             *string             `json:"resolver,omitempty"`             // Resolver that answered for the initial host: system, pinned, override or a nameserver
+	ConnectionAttempts   []ConnectionAttempt `json:"connectionAttempts,omitempty"`   // Addresses raced to connect to the final hop
+	Retry                *RetryInfo          `json:"retry,omitempty"`                // Attempts made when a retry policy was given
+	Download             *DownloadInfo       `json:"download,omitempty"`             // File the body was written to when download was requested
+	Integrity            *BodyIntegrity      `json:"integrity,omitempty"`            // Hashes of the body and the digests it was checked against
+	Fixture              *string             `json:"fixture,omitempty"`              // File in the fixture directory the response was captured to
+	CacheAnalysis        *CacheAnalysis      `json:"cacheAnalysis,omitempty"`        // How HTTP caches may store and reuse the response
+	Cookies              []ResponseCookie    `json:"cookies,omitempty"`              // Set-Cookie headers of the final response, parsed
+	Summary              string              `json:"summary"`                        // One-sentence description for screen readers and notifications
+	JWTs                 []JWTInfo           `json:"jwts,omitempty"`                 // Tokens found in the request and response, decoded
+	BodyStats            *BodyStats          `json:"bodyStats,omitempty"`            // Statistics of a body too large to display usefully
+	Scripts              *ScriptResults      `json:"scripts,omitempty"`              // Outcome of the request's pre-request and test scripts
+	Assertions           []AssertionResult   `json:"assertions,omitempty"`           // Outcome of the request's assertions, in order
+	Extracted            []ExtractionResult  `json:"extracted,omitempty"`            // Outcome of the request's extractions, in order
+	Plugins              []PluginRun         `json:"plugins,omitempty"`              // Plugins run on the request and the response, in order
+}
+
+// JWTInfo is a decoded JSON Web Token. Signatures are only checked when a
+// key is given.
+type JWTInfo struct {
+	Source      string                 `json:"source"` // Where the token was found, e.g. "request header Authorization" or "body.access_token"
+	Header      map[string]interface{} `json:"header"`
+	Claims      map[string]interface{} `json:"claims"`
+	Algorithm   string                 `json:"algorithm"`
+	IssuedAt    *int64                 `json:"issuedAt,omitempty"`  // iat, Unix seconds
+	NotBefore   *int64                 `json:"notBefore,omitempty"` // nbf, Unix seconds
+	ExpiresAt   *int64                 `json:"expiresAt,omitempty"` // exp, Unix seconds
+	ExpiresIn   *int64                 `json:"expiresIn,omitempty"` // Seconds until exp, negative once expired
+	Expired     bool                   `json:"expired"`
+	NotYetValid bool                   `json:"notYetValid"`
+	Verified    *bool                  `json:"verified,omitempty"`    // Signature check result when a key was given
+	VerifyError *string                `json:"verifyError,omitempty"` // Why the signature did not verify
+	Warnings    []string               `json:"warnings"`
+}
+
+// ResponseCookie is a cookie set by a Set-Cookie header.
+type ResponseCookie struct {
+	Name        string   `json:"name"`
+	Value       string   `json:"value"`
+	Domain      *string  `json:"domain,omitempty"`
+	Path        *string  `json:"path,omitempty"`
+	Expires     *string  `json:"expires,omitempty"`   // As received
+	ExpiresAt   *uint64  `json:"expiresAt,omitempty"` // Unix milliseconds, from Max-Age or else Expires
+	MaxAge      *int     `json:"maxAge,omitempty"`
+	Secure      bool     `json:"secure"`
+	HTTPOnly    bool     `json:"httpOnly"`
+	SameSite    *string  `json:"sameSite,omitempty"`
+	Partitioned bool     `json:"partitioned"`
+	Priority    *string  `json:"priority,omitempty"`
+	Session     bool     `json:"session"` // No Expires or Max-Age; dropped when the browser closes
+	Expired     bool     `json:"expired"` // Already expired, which deletes a stored cookie
+	Raw         string   `json:"raw"`
+	Warnings    []string `json:"warnings"`
+}
+
+// CacheAnalysis explains the caching of a response from its Cache-Control,
+// Expires, ETag, Last-Modified and Age headers (RFC 9111). Lifetimes and
+// ages are in seconds.
+type CacheAnalysis struct {
+	Cacheable            bool               `json:"cacheable"`       // A cache may store the response
+	SharedCacheable      bool               `json:"sharedCacheable"` // Proxies and CDNs may store it too
+	Directives           map[string]string  `json:"directives"`      // Cache-Control directives by lowercase name
+	NoStore              bool               `json:"noStore"`
+	NoCache              bool               `json:"noCache"`
+	Private              bool               `json:"private"`
+	Public               bool               `json:"public"`
+	MustRevalidate       bool               `json:"mustRevalidate"`
+	Immutable            bool               `json:"immutable"`
+	FreshnessLifetime    *int               `json:"freshnessLifetime,omitempty"`  // How long the response is fresh after it was generated
+	FreshnessSource      string             `json:"freshnessSource,omitempty"`    // max-age, Expires or heuristic
+	Heuristic            bool               `json:"heuristic"`                    // Lifetime estimated as 10% of the time since Last-Modified
+	SharedLifetime       *int               `json:"sharedLifetime,omitempty"`     // s-maxage, overriding the lifetime for shared caches
+	AgeHeader            *int               `json:"ageHeader,omitempty"`          // Age reported by caches on the way
+	Age                  int                `json:"age"`                          // Current age, from Age and Date
+	RemainingFreshness   *int               `json:"remainingFreshness,omitempty"` // Negative once stale
+	Fresh                *bool              `json:"fresh,omitempty"`              // Absent without a freshness lifetime
+	StaleWhileRevalidate *int               `json:"staleWhileRevalidate,omitempty"`
+	StaleIfError         *int               `json:"staleIfError,omitempty"`
+	ETag                 *string            `json:"etag,omitempty"`
+	WeakETag             *bool              `json:"weakEtag,omitempty"`
+	LastModified         *string            `json:"lastModified,omitempty"`
+	Vary                 *string            `json:"vary,omitempty"`
+	Notes                []string           `json:"notes"`
+	Revalidation         *CacheRevalidation `json:"revalidation,omitempty"` // Conditional request made when revalidate was requested
+}
+
+// CacheRevalidation is the outcome of re-sending a request with the
+// validators of its response.
+type CacheRevalidation struct {
+	Sent        map[string]string `json:"sent"` // Conditional headers sent
+	Status      *uint16           `json:"status,omitempty"`
+	NotModified bool              `json:"notModified"`    // The server answered 304
+	Time        *uint64           `json:"time,omitempty"` // Total time of the conditional request in milliseconds
+	Size        int               `json:"size"`           // Body bytes of the conditional response
+	TimeSaved   int64             `json:"timeSaved"`      // Milliseconds faster than the full response, when 304
+	BytesSaved  int               `json:"bytesSaved"`     // Body bytes not transferred again, when 304
+	Error       *string           `json:"error,omitempty"`
+}
+
+// BodyIntegrity holds the hashes of a response body and the outcome of
+// checking the digests announced by the server or expected by the request.
+type BodyIntegrity struct {
+	Decoded BodyHashes       `json:"decoded"`           // Body after removing content codings
+	Encoded *BodyHashes      `json:"encoded,omitempty"` // Body as received, when it was encoded
+	Checks  []IntegrityCheck `json:"checks,omitempty"`
+	Match   *bool            `json:"match,omitempty"` // Every digest matched; absent when there was nothing to check
+}
+
+// BodyHashes are hex-encoded digests of a body.
+type BodyHashes struct {
+	MD5    string `json:"md5"`
+	SHA1   string `json:"sha1"`
+	SHA256 string `json:"sha256"`
+}
+
+// IntegrityCheck compares one expected digest with the body.
+type IntegrityCheck struct {
+	Source    string `json:"source"`    // Content-MD5, Digest, Content-Digest, Repr-Digest or integrity
+	Algorithm string `json:"algorithm"` // As named by the source, lowercased
+	Expected  string `json:"expected"`
+	Actual    string `json:"actual"` // Base64
+	Match     bool   `json:"match"`
+}
+
+// SecurityAnalysis collects security findings about a response and how it was reached.
+type SecurityAnalysis struct {
+	Redirects []RedirectFinding     `json:"redirects,omitempty"`
+	Headers   *SecurityHeaderReport `json:"headers,omitempty"` // Security headers of the final response
+}
+
+// SecurityHeaderReport grades the security headers of a response.
+type SecurityHeaderReport struct {
+	Grade   string                `json:"grade"` // A to F
+	Score   int                   `json:"score"` // 0 to 100
+	Checks  []SecurityHeaderCheck `json:"checks"`
+	Missing []string              `json:"missing"` // Recommended headers the response lacks
+}
+
+// SecurityHeaderCheck is the verdict on one security header.
+type SecurityHeaderCheck struct {
+	Header  string  `json:"header"`
+	Present bool    `json:"present"`
+	Value   *string `json:"value,omitempty"`
+	Status  string  `json:"status"` // pass, warn, fail or info
+	Message string  `json:"message"`
+}
+
+// RedirectFinding is a risky pattern in the redirect chain.
+type RedirectFinding struct {
+	Hop       int     `json:"hop"`      // Index in redirectChain of the redirecting response
+	Kind      string  `json:"kind"`     // downgrade, crossOriginAuth, authDropped or openRedirect
+	Severity  string  `json:"severity"` // high, medium or low
+	From      string  `json:"from"`
+	To        string  `json:"to"`
+	Parameter *string `json:"parameter,omitempty"` // Query parameter that chose the target, for openRedirect
+	Message   string  `json:"message"`
+}
+
+// GraphQLInfo separates the parts of a GraphQL response body.
+type GraphQLInfo struct {
+	Data       interface{}            `json:"data"`
+	Errors     []GraphQLError         `json:"errors,omitempty"`
+	Extensions map[string]interface{} `json:"extensions,omitempty"`
+	HasData    bool                   `json:"hasData"`
+	Partial    bool                   `json:"partial"`              // Data accompanied by errors
+	ParseTime  uint64                 `json:"parseTime"`            // Microseconds spent decoding the body
+	ParseError *string                `json:"parseError,omitempty"` // The body is not a JSON GraphQL response
+}
+
+// GraphQLError is an entry of the errors list of a GraphQL response.
+type GraphQLError struct {
+	Message    string                 `json:"message"`
+	Locations  []GraphQLLocation      `json:"locations,omitempty"`
+	Path       []interface{}          `json:"path,omitempty"` // Field names and list indices
+	Extensions map[string]interface{} `json:"extensions,omitempty"`
+}
+
+// GraphQLLocation points into the query document.
+type GraphQLLocation struct {
+	Line   int `json:"line"`
+	Column int `json:"column"`
+}
+
+// GraphQLSchemaResponse is the outcome of an introspection request.
+type GraphQLSchemaResponse struct {
+	Success bool           `json:"success"`
+	Status  uint16         `json:"status,omitempty"` // HTTP status of the introspection response
+	Schema  *GraphQLSchema `json:"schema,omitempty"`
+	Error   *ErrorData     `json:"error,omitempty"`
+}
+
+// GraphQLSchema is a schema fetched by introspection and printed as SDL.
+type GraphQLSchema struct {
+	SDL              string  `json:"sdl"`
+	QueryType        *string `json:"queryType,omitempty"`
+	MutationType     *string `json:"mutationType,omitempty"`
+	SubscriptionType *string `json:"subscriptionType,omitempty"`
+	Types            int     `json:"types"` // Named types excluding introspection types and built-in scalars
+}
+
+// ClockSkewInfo compares the server's Date header with the local clock.
+type ClockSkewInfo struct {
+	ServerDate  string  `json:"serverDate"`    // Date header as RFC 3339
+	LocalTime   string  `json:"localTime"`     // Local time estimated for when the server produced the response
+	Age         *int    `json:"age,omitempty"` // Seconds a cache held the response, added to the server date
+	Skew        int64   `json:"skew"`          // Milliseconds the server clock is ahead; negative when behind
+	Uncertainty uint64  `json:"uncertainty"`   // Milliseconds of error from latency and the Date resolution
+	Significant bool    `json:"significant"`   // Skew exceeds the uncertainty by at least 5 seconds
+	Message     *string `json:"message,omitempty"`
+}
+
+// EventStreamInfo holds the events read from a text/event-stream response.
+type EventStreamInfo struct {
+	Events        []ServerSentEvent `json:"events"`
+	StoppedReason string            `json:"stoppedReason"` // ended, maxEvents, maxDuration, timeout or error
+	Duration      uint64            `json:"duration"`      // Milliseconds the stream was read
+	LastEventID   *string           `json:"lastEventId,omitempty"`
+	Retry         *int              `json:"retry,omitempty"` // Reconnection delay requested by the server in milliseconds
+	Error         *string           `json:"error,omitempty"`
+}
+
+// ServerSentEvent is a single event dispatched by an event stream.
+type ServerSentEvent struct {
+	Seq     int     `json:"seq"`
+	ID      *string `json:"id,omitempty"` // Last event ID when the event was dispatched
+	Event   string  `json:"event"`        // Event type (default message)
+	Data    string  `json:"data"`
+	Offset  uint64  `json:"offset"`  // Milliseconds since the stream opened
+	Latency uint64  `json:"latency"` // Milliseconds since the previous event
+}
+
+// ResponseAnalysis holds heuristics derived from a text body.
+type ResponseAnalysis struct {
+	Language *LanguageAnalysis `json:"language,omitempty"`
+	Charset  *CharsetAnalysis  `json:"charset,omitempty"`
+}
+
+// LanguageAnalysis compares the detected natural language with the declared one.
+type LanguageAnalysis struct {
+	Detected     *string  `json:"detected,omitempty"` // ISO 639-1 code; nil when there is too little text
+	Confidence   *float64 `json:"confidence,omitempty"`
+	Declared     []string `json:"declared,omitempty"`     // Content-Language header values
+	DocumentLang *string  `json:"documentLang,omitempty"` // lang attribute of an HTML document
+	Matches      *bool    `json:"matches,omitempty"`      // Detected language is among the declared ones
+}
+
+// CharsetAnalysis compares the declared character encodings with the body bytes.
+type CharsetAnalysis struct {
+	Header     *string  `json:"header,omitempty"`   // charset of Content-Type
+	Document   *string  `json:"document,omitempty"` // meta charset or XML declaration
+	BOM        *string  `json:"bom,omitempty"`
+	ValidUTF8  bool     `json:"validUtf8"`
+	ASCII      bool     `json:"ascii"`
+	Consistent bool     `json:"consistent"`
+	Warnings   []string `json:"warnings,omitempty"`
+}
+
+// MediaInfo is metadata extracted from a document, audio or video body.
+type MediaInfo struct {
+	Format    string            `json:"format"`            // pdf, docx, xlsx, pptx, mp4, mov, m4a, mp3, wav, flac, ogg, webm or matroska
+	Version   *string           `json:"version,omitempty"` // PDF version or generating application version
+	Pages     *int              `json:"pages,omitempty"`
+	Sheets    *int              `json:"sheets,omitempty"`
+	Slides    *int              `json:"slides,omitempty"`
+	Title     *string           `json:"title,omitempty"`
+	Author    *string           `json:"author,omitempty"`
+	Subject   *string           `json:"subject,omitempty"`
+	Creator   *string           `json:"creator,omitempty"` // Authoring application
+	Producer  *string           `json:"producer,omitempty"`
+	Created   *string           `json:"created,omitempty"` // As stored in the document
+	Modified  *string           `json:"modified,omitempty"`
+	Encrypted bool              `json:"encrypted,omitempty"`
+	Metadata  map[string]string `json:"metadata,omitempty"` // Other embedded metadata fields
+	Duration  *float64          `json:"duration,omitempty"` // Seconds
+	Bitrate   *int              `json:"bitrate,omitempty"`  // Average bits per second
+	Tracks    []MediaTrack      `json:"tracks,omitempty"`
+	Range     *RangeSupport     `json:"range,omitempty"` // Byte range support, for seeking in audio and video
+	Error     *string           `json:"error,omitempty"`
+}
+
+// MediaTrack is an audio or video stream of a media container.
+type MediaTrack struct {
+	Type       string `json:"type"` // audio or video
+	Codec      string `json:"codec,omitempty"`
+	Width      *int   `json:"width,omitempty"`
+	Height     *int   `json:"height,omitempty"`
+	SampleRate *int   `json:"sampleRate,omitempty"`
+	Channels   *int   `json:"channels,omitempty"`
+}
+
+// RangeSupport reports whether a media resource can be requested in parts.
+type RangeSupport struct {
+	AcceptRanges *string `json:"acceptRanges,omitempty"` // Accept-Ranges header of the response
+	Advertised   bool    `json:"advertised"`             // Accept-Ranges lists bytes
+	Verified     *bool   `json:"verified,omitempty"`     // A probe request got a matching 206; set when probeRange was requested
+	ProbeStatus  *uint16 `json:"probeStatus,omitempty"`
+	ContentRange *string `json:"contentRange,omitempty"` // Content-Range of the probe response
+	Error        *string `json:"error,omitempty"`
+}
+
+// ArchiveInfo lists the entries of an archive body without extracting it.
+type ArchiveInfo struct {
+	Format       string         `json:"format"` // zip, tar, tar.gz or gzip
+	Entries      []ArchiveEntry `json:"entries"`
+	TotalEntries int            `json:"totalEntries"`
+	Truncated    bool           `json:"truncated"` // Not every entry is listed
+	Error        *string        `json:"error,omitempty"`
+}
+
+// ArchiveEntry is a file or directory inside an archive.
+type ArchiveEntry struct {
+	Name           string  `json:"name"`
+	Size           int64   `json:"size"`                     // Uncompressed size
+	CompressedSize *int64  `json:"compressedSize,omitempty"` // Zip only
+	Dir            bool    `json:"dir,omitempty"`
+	Modified       *uint64 `json:"modified,omitempty"` // Unix seconds
+}
+
+// BodyStats summarizes a large body, alongside the body itself.
+type BodyStats struct {
+	Bytes       int             `json:"bytes"`                 // Bytes analyzed; the kept body when it was truncated
+	Entropy     float64         `json:"entropy"`               // Shannon entropy in bits per byte; near 8 for compressed or encrypted data
+	Lines       *int            `json:"lines,omitempty"`       // Text bodies only
+	LongestLine *int            `json:"longestLine,omitempty"` // Text bodies only, in bytes
+	JSON        *JSONBodyStats  `json:"json,omitempty"`
+	Multipart   *MultipartStats `json:"multipart,omitempty"`
+}
+
+// JSONBodyStats counts the structure of a JSON body.
+type JSONBodyStats struct {
+	Objects      int         `json:"objects"`
+	Arrays       int         `json:"arrays"`
+	MaxDepth     int         `json:"maxDepth"`
+	Keys         []StatCount `json:"keys"` // Most frequent keys, at most 20
+	DistinctKeys int         `json:"distinctKeys"`
+	Complete     bool        `json:"complete"` // The whole body parsed; a truncated body stops early
+}
+
+// MultipartStats counts the parts of a multipart body by content type.
+type MultipartStats struct {
+	Parts        int         `json:"parts"`
+	ContentTypes []StatCount `json:"contentTypes"` // Most frequent part content types, at most 20
+	Complete     bool        `json:"complete"`     // The closing boundary was reached
+}
+
+// StatCount is a value and how often it occurred.
+type StatCount struct {
+	Value string `json:"value"`
+	Count int    `json:"count"`
+	Bytes *int   `json:"bytes,omitempty"` // Bytes covered, for multipart content types
+}
+
+// TemplateInfo shows a templated request next to the request that was sent.
+type TemplateInfo struct {
+	Environment *string          `json:"environment,omitempty"`
+	Template    TemplatedRequest `json:"template"`
+	Resolved    TemplatedRequest `json:"resolved"`
+	Unresolved  []string         `json:"unresolved,omitempty"` // Variables without a value, left as written
+}
+
+// TemplatedRequest holds the templatable parts of a request.
+type TemplatedRequest struct {
+	URL     string            `json:"url"`
+	Headers map[string]string `json:"headers"`
+	Body    *string           `json:"body,omitempty"`
+}
+
+// PaginationInfo aggregates the pages of a paginated endpoint.
+type PaginationInfo struct {
+	Mode          string     `json:"mode,omitempty"` // link, nextUrl or cursor; empty for a single page
+	Pages         []PageInfo `json:"pages"`
+	TotalPages    int        `json:"totalPages"`
+	TotalItems    int        `json:"totalItems"` // Sum of the pages' item counts
+	TotalSize     int        `json:"totalSize"`  // Header and body bytes of all pages
+	TotalTime     uint64     `json:"totalTime"`  // Sum of the page times in milliseconds
+	Complete      bool       `json:"complete"`   // The last page has no next reference
+	StoppedReason *string    `json:"stoppedReason,omitempty"`
+}
+
+// PageInfo describes one fetched page.
+type PageInfo struct {
+	URL    string  `json:"url"`
+	Status *uint16 `json:"status,omitempty"`
+	Items  *int    `json:"items,omitempty"` // Array length when the page is a JSON list
+	Size   int     `json:"size"`
+	Time   uint64  `json:"time"` // Milliseconds
+	Error  *string `json:"error,omitempty"`
+}
+
+// SOAPInfo describes a SOAP envelope. A fault is surfaced separately so it
+// does not have to be read out of the XML.
+type SOAPInfo struct {
+	Version            string     `json:"version"` // 1.1 or 1.2
+	HasHeader          bool       `json:"hasHeader"`
+	Operation          *string    `json:"operation,omitempty"` // First element of the Body
+	OperationNamespace *string    `json:"operationNamespace,omitempty"`
+	Fault              *SOAPFault `json:"fault,omitempty"`
+}
+
+// SOAPFault is a SOAP fault. SOAP 1.1 faultcode, faultstring and faultactor
+// map to code, reason and role.
+type SOAPFault struct {
+	Code     string   `json:"code"`
+	Subcodes []string `json:"subcodes,omitempty"` // SOAP 1.2 only
+	Reason   string   `json:"reason"`
+	Role     *string  `json:"role,omitempty"`
+	Node     *string  `json:"node,omitempty"`   // SOAP 1.2 only
+	Detail   *string  `json:"detail,omitempty"` // Raw XML of the detail element (max 4 KiB)
+}
+
+// FeedPreview summarizes an RSS, RDF or Atom feed.
+type FeedPreview struct {
+	Format      string     `json:"format"` // rss, rdf or atom
+	Version     *string    `json:"version,omitempty"`
+	Title       string     `json:"title"`
+	Link        *string    `json:"link,omitempty"`
+	Description *string    `json:"description,omitempty"`
+	Updated     *string    `json:"updated,omitempty"` // RFC 3339 when parseable
+	Items       []FeedItem `json:"items"`
+	TotalItems  int        `json:"totalItems"`
+	Truncated   bool       `json:"truncated"` // Not every item is listed
+	Warnings    []string   `json:"warnings,omitempty"`
+}
+
+// FeedItem is a single entry of a feed.
+type FeedItem struct {
+	ID        *string `json:"id,omitempty"` // guid, rdf:about or Atom id
+	Title     string  `json:"title"`
+	Link      *string `json:"link,omitempty"`
+	Published *string `json:"published,omitempty"` // RFC 3339 when parseable
+}
+
+// SourceMapInfo reports whether a script's source map can be retrieved.
+type SourceMapInfo struct {
+	URL       string   `json:"url"`    // Resolved map URL, or the data: URL of an inline map
+	Inline    bool     `json:"inline"` // Embedded as a data: URL
+	Available bool     `json:"available"`
+	Status    *uint16  `json:"status,omitempty"` // Status of the map request
+	Size      *int     `json:"size,omitempty"`
+	Version   int      `json:"version,omitempty"`
+	File      *string  `json:"file,omitempty"`
+	Sources   []string `json:"sources,omitempty"` // Original source files
+	Error     *string  `json:"error,omitempty"`
+}
+
+// BeautifiedBody is a pretty-printed JavaScript or CSS body.
+type BeautifiedBody struct {
+	Language       string `json:"language"` // javascript or css
+	Body           string `json:"body"`
+	OriginalLength int    `json:"originalLength"` // Length of the body as received (decompressed)
+	Length         int    `json:"length"`
+}
+
+// HeaderField is a single response header line.
+type HeaderField struct {
+	Name  string `json:"name"`
+	Value string `json:"value"`
+}
+
+// RawRequest is a request as serialized onto the connection.
+type RawRequest struct {
+	Data          string `json:"data"`          // Captured bytes, base64 encoded when not valid UTF-8
+	Encoding      string `json:"encoding"`      // text or base64
+	Size          int    `json:"size"`          // Total bytes written, including any truncated part
+	Truncated     bool   `json:"truncated"`     // Whether Data stops at the capture limit
+	Reconstructed bool   `json:"reconstructed"` // Rebuilt from the request because the wire bytes were HTTP/2 frames or tunneled TLS
+}
+
+// CookieJarInfo reports how a named cookie jar was used by a request.
+type CookieJarInfo struct {
+	Name      string           `json:"name"`
+	Sent      []string         `json:"sent"`                // Cookie names sent on the final hop
+	Received  []string         `json:"received"`            // Cookie names set by responses, including redirects
+	Stored    int              `json:"stored"`              // Cookies in the jar afterwards
+	Decisions []CookieDecision `json:"decisions,omitempty"` // Browser send decision per matching cookie on the final hop
+}
+
+// CookieDecision explains whether a browser would send a stored cookie.
+type CookieDecision struct {
+	Name   string  `json:"name"`
+	Domain string  `json:"domain"`
+	Path   string  `json:"path"`
+	Sent   bool    `json:"sent"`
+	Reason *string `json:"reason,omitempty"` // Why the cookie is withheld
+}
+
+// ServedBy is a heuristic guess of the CDN or provider that served a response.
+type ServedBy struct {
+	Provider   string   `json:"provider"`   // e.g. "Cloudflare"
+	Confidence string   `json:"confidence"` // high, medium or low
+	Evidence   []string `json:"evidence"`   // Signals that matched
+}
+
+// IntegratedAuthInfo reports a Negotiate or NTLM handshake performed with the current user's credentials.
+type IntegratedAuthInfo struct {
+	Scheme        string  `json:"scheme"` // Negotiate or NTLM
+	Target        string  `json:"target"` // server or proxy
+	Legs          int     `json:"legs"`   // Authenticated round trips sent
+	Authenticated bool    `json:"authenticated"`
+	Error         *string `json:"error,omitempty"`
+}
+
+// AltSvcEntry is a single alternative service advertised via Alt-Svc.
+type AltSvcEntry struct {
+	Protocol  string  `json:"protocol"`  // ALPN protocol ID, e.g. "h3"
+	Authority string  `json:"authority"` // Alternative host:port, host may be empty
+	MaxAge    *uint64 `json:"maxAge,omitempty"`
 }
 
 // ErrorData contains error information matching extension protocol.
 type ErrorData struct {
-	Message string  `json:"message"`
-	Code    string  `json:"code"`
-	Name    *string `json:"name,omitempty"`
+	Message string     `json:"message"`
+	Code    string     `json:"code"`
+	Name    *string    `json:"name,omitempty"`
+	Retry   *RetryInfo `json:"retry,omitempty"`   // Attempts made when a retry policy was given
+	Fixture *string    `json:"fixture,omitempty"` // File in the fixture directory the response was captured to
+	Detail  *string    `json:"detail,omitempty"`  // Original English message when Message was translated
+	TLS     *TLSInfo   `json:"tls,omitempty"`     // Certificate that failed validation, with every reason
 }
 
+// ProtocolVersion identifies the JSON shape of ProxyResponse shared with the
+// frontend and browser extension. Bump it whenever a field is added, renamed
+// or removed, and record the snapshots in internal/protocol.
+const ProtocolVersion = 17
+
 // ProxyResponse is the full proxy response matching extension protocol.
 type ProxyResponse struct {
 	Success bool          `json:"success"`
@@ -96,6 +861,631 @@ type ProxyResponse struct {
 	Error   *ErrorData    `json:"error,omitempty"`
 }
 
+// BatchRequest is a set of requests executed together.
+type BatchRequest struct {
+	Requests    []ProxyRequest `json:"requests"`
+	Concurrency *int           `json:"concurrency,omitempty"` // Requests in flight at once (default 4, max 16)
+	Chain       bool           `json:"chain,omitempty"`       // Run one at a time, passing the variables each request extracts or sets to the next
+}
+
+// BatchResponse holds the results of a batch in request order.
+type BatchResponse struct {
+	Results []ProxyResponse `json:"results"`
+	Stats   BatchStats      `json:"stats"`
+}
+
+// BatchStats aggregates the outcome of a batch.
+type BatchStats struct {
+	Total       int                  `json:"total"`
+	Succeeded   int                  `json:"succeeded"`         // Requests that received a response
+	Failed      int                  `json:"failed"`            // Requests that ended in an error
+	ErrorStatus int                  `json:"errorStatus"`       // Responses with a 4xx or 5xx status
+	TotalTime   uint64               `json:"totalTime"`         // Wall-clock time of the batch in milliseconds
+	AverageTime uint64               `json:"averageTime"`       // Mean time of the requests that received a response
+	Latency     *LatencyDistribution `json:"latency,omitempty"` // Total time of the requests that received a response
+	Phases      *TimingPhases        `json:"phases,omitempty"`
+}
+
+// DataRunRequest executes a templated request once per row of a data set.
+type DataRunRequest struct {
+	Request          ProxyRequest `json:"request"`                    // Template; {{column}} references take each row's values
+	Data             string       `json:"data"`                       // CSV with a header line, or a JSON array of objects
+	Format           *string      `json:"format,omitempty"`           // csv or json; detected from the data when omitted
+	Concurrency      *int         `json:"concurrency,omitempty"`      // Rows in flight at once (default 4, max 16)
+	IncludeResponses bool         `json:"includeResponses,omitempty"` // Return the full response of every row
+	MaxRows          *int         `json:"maxRows,omitempty"`          // Run only the first rows of the data
+}
+
+// DataRunResponse holds the outcome of every row of a data run.
+type DataRunResponse struct {
+	Success  bool           `json:"success"` // The data was valid and every row was executed
+	Columns  []string       `json:"columns,omitempty"`
+	Rows     []DataRunRow   `json:"rows"`
+	Stats    BatchStats     `json:"stats"`
+	Statuses map[string]int `json:"statuses"` // Rows per status code, and "error" for failed requests
+	Error    *ErrorData     `json:"error,omitempty"`
+}
+
+// DataRunRow is the outcome of the request for one row.
+type DataRunRow struct {
+	Row       int               `json:"row"` // 1-based, excluding a CSV header
+	Variables map[string]string `json:"variables"`
+	URL       string            `json:"url,omitempty"` // Final URL after redirects
+	Status    *uint16           `json:"status,omitempty"`
+	Time      *uint64           `json:"time,omitempty"` // Milliseconds
+	Size      int               `json:"size"`
+	Error     *string           `json:"error,omitempty"`
+	Code      *string           `json:"code,omitempty"`
+	Response  *ProxyResponse    `json:"response,omitempty"` // Set with includeResponses
+}
+
+// BurstRequest sends one request repeatedly in timed bursts to probe a rate limiter.
+type BurstRequest struct {
+	Request         ProxyRequest `json:"request"`
+	Bursts          *int         `json:"bursts,omitempty"`          // Number of bursts (default 3)
+	BurstSize       *int         `json:"burstSize,omitempty"`       // Requests sent at once per burst (default 10, max 100)
+	IntervalMS      *int         `json:"intervalMs,omitempty"`      // Pause between bursts in milliseconds (default 1000)
+	StopWhenLimited bool         `json:"stopWhenLimited,omitempty"` // Stop after the first burst that received a 429
+}
+
+// BurstResponse holds every sample of a burst probe and what they reveal
+// about the limiter.
+type BurstResponse struct {
+	Samples        []BurstSample `json:"samples"`
+	Succeeded      int           `json:"succeeded"`                // Responses other than 429
+	Limited        int           `json:"limited"`                  // 429 responses
+	Failed         int           `json:"failed"`                   // Requests without a response
+	FirstLimited   *int          `json:"firstLimited,omitempty"`   // Sequence number of the first 429
+	ObservedLimit  *int          `json:"observedLimit,omitempty"`  // Largest advertised limit
+	ObservedWindow *int          `json:"observedWindow,omitempty"` // Largest advertised window or reset in seconds
+	RetryAfter     *int          `json:"retryAfter,omitempty"`     // Retry-After of the first 429, in seconds
+	TotalTime      uint64        `json:"totalTime"`                // Milliseconds
+}
+
+// BurstSample is one request of a burst probe.
+type BurstSample struct {
+	Burst     int            `json:"burst"`    // 1-based burst number
+	Sequence  int            `json:"sequence"` // 1-based position across all bursts
+	SentAt    uint64         `json:"sentAt"`   // Milliseconds since the probe started
+	Status    *uint16        `json:"status,omitempty"`
+	Time      uint64         `json:"time"` // Milliseconds
+	Error     *string        `json:"error,omitempty"`
+	RateLimit *RateLimitInfo `json:"rateLimit,omitempty"`
+}
+
+// LoadTestRequest sends one request repeatedly with fixed concurrency for
+// lightweight benchmarking.
+type LoadTestRequest struct {
+	Request     ProxyRequest `json:"request"`
+	Iterations  *int         `json:"iterations,omitempty"`  // Requests to send (default 100, or 10000 with a duration)
+	Concurrency *int         `json:"concurrency,omitempty"` // Requests in flight at once (default 10, max 100)
+	DurationMS  *int         `json:"durationMs,omitempty"`  // Stop sending after this long, even with iterations left (max 10 minutes)
+}
+
+// LoadTestResponse aggregates the requests of a load test, while it runs
+// and once it is done.
+type LoadTestResponse struct {
+	Done        bool                 `json:"done"`
+	Completed   int                  `json:"completed"`
+	Succeeded   int                  `json:"succeeded"`   // Requests that received a response
+	Failed      int                  `json:"failed"`      // Requests that ended in an error
+	ErrorStatus int                  `json:"errorStatus"` // Responses with a 4xx or 5xx status
+	ErrorRate   float64              `json:"errorRate"`   // Percentage of requests failed or with an error status
+	Elapsed     uint64               `json:"elapsed"`     // Milliseconds since the test started
+	RPS         float64              `json:"rps"`         // Completed requests per second
+	Bytes       int                  `json:"bytes"`       // Bytes sent and received, as TransferredBytes counts them
+	Latency     *LatencyDistribution `json:"latency,omitempty"`
+	Phases      *TimingPhases        `json:"phases,omitempty"`
+	Statuses    map[string]int       `json:"statuses"` // Responses per status code
+	Errors      map[string]int       `json:"errors"`   // Failed requests per error code
+}
+
+// TimingPhases holds the timing distribution of each phase of several
+// executions. Phases that never happened, such as TLS over plain HTTP, are
+// omitted.
+type TimingPhases struct {
+	DNS      *LatencyDistribution `json:"dns,omitempty"`
+	TCP      *LatencyDistribution `json:"tcp,omitempty"`
+	TLS      *LatencyDistribution `json:"tls,omitempty"`
+	TTFB     *LatencyDistribution `json:"ttfb,omitempty"`
+	Download *LatencyDistribution `json:"download,omitempty"`
+}
+
+// LatencyDistribution summarizes timings in milliseconds, with
+// nearest-rank percentiles.
+type LatencyDistribution struct {
+	Samples   int               `json:"samples"`
+	Min       uint64            `json:"min"`
+	Mean      uint64            `json:"mean"`
+	P50       uint64            `json:"p50"`
+	P90       uint64            `json:"p90"`
+	P95       uint64            `json:"p95"`
+	P99       uint64            `json:"p99"`
+	Max       uint64            `json:"max"`
+	Histogram []HistogramBucket `json:"histogram"` // Bounds of 1, 2 and 5 times a power of ten, covering min to max
+}
+
+// HistogramBucket counts the timings above the previous bucket, up to and
+// including Upper.
+type HistogramBucket struct {
+	Upper uint64 `json:"upper"` // Milliseconds
+	Count int    `json:"count"`
+}
+
+// PipelineRequest pipelines requests on one HTTP/1.1 connection. It is an
+// experimental diagnostic; see HTTPService.ExecutePipeline.
+type PipelineRequest struct {
+	URL      string             `json:"url"`               // Origin to connect to; its path is not used
+	Requests []PipelinedRequest `json:"requests"`          // Written in order, at most 20
+	Timeout  *uint64            `json:"timeout,omitempty"` // Milliseconds for the whole exchange (default 30000)
+}
+
+// PipelinedRequest is one request of a pipeline.
+type PipelinedRequest struct {
+	Method  string            `json:"method,omitempty"` // Default GET
+	Path    string            `json:"path,omitempty"`   // Request target, e.g. /items?page=2 (default /)
+	Headers map[string]string `json:"headers,omitempty"`
+	Body    *string           `json:"body,omitempty"`
+}
+
+// PipelineResponse reports how a server answered pipelined requests.
+type PipelineResponse struct {
+	Success      bool             `json:"success"`
+	Experimental bool             `json:"experimental"` // Always set; results describe one connection at one moment
+	ServerIP     *string          `json:"serverIp,omitempty"`
+	TLS          *TLSInfo         `json:"tls,omitempty"`
+	Connect      uint64           `json:"connect"` // Milliseconds to open the connection, including TLS
+	Results      []PipelineResult `json:"results"`
+	Sent         int              `json:"sent"`                  // Requests fully written
+	Answered     int              `json:"answered"`              // Responses read
+	InOrder      bool             `json:"inOrder"`               // False when an echoed X-Pipeline-Sequence did not match
+	ClosedAfter  *int             `json:"closedAfter,omitempty"` // Responses read before the server closed the connection
+	TotalTime    uint64           `json:"totalTime"`             // Milliseconds
+	Error        *ErrorData       `json:"error,omitempty"`
+}
+
+// PipelineResult is a pipelined request and the response read in its place.
+// Times are milliseconds since the connection was opened.
+type PipelineResult struct {
+	Sequence    int           `json:"sequence"` // 1-based, also sent as X-Pipeline-Sequence
+	Method      string        `json:"method"`
+	Path        string        `json:"path"`
+	SentAt      uint64        `json:"sentAt"`
+	FirstByteAt *uint64       `json:"firstByteAt,omitempty"`
+	CompletedAt *uint64       `json:"completedAt,omitempty"`
+	Latency     *uint64       `json:"latency,omitempty"` // From written to fully read
+	Status      *uint16       `json:"status,omitempty"`
+	Headers     []HeaderField `json:"headers,omitempty"`
+	Size        int           `json:"size"`
+	Echoed      *int          `json:"echoed,omitempty"` // X-Pipeline-Sequence echoed by the server
+	Error       *string       `json:"error,omitempty"`
+}
+
+// IdempotencyRequest sends a request twice with the same idempotency key.
+type IdempotencyRequest struct {
+	Request    ProxyRequest `json:"request"`
+	Key        *string      `json:"key,omitempty"`        // Key to send; a random UUID when empty
+	HeaderName *string      `json:"headerName,omitempty"` // Header carrying the key (default Idempotency-Key)
+	DelayMS    *int         `json:"delayMs,omitempty"`    // Pause between the two requests in milliseconds
+}
+
+// IdempotencyResponse compares the two responses of an idempotency check.
+type IdempotencyResponse struct {
+	Key          string        `json:"key"`
+	HeaderName   string        `json:"headerName"`
+	First        ProxyResponse `json:"first"`
+	Second       ProxyResponse `json:"second"`
+	SameStatus   bool          `json:"sameStatus"`
+	SameBody     bool          `json:"sameBody"`
+	FirstID      *string       `json:"firstId,omitempty"`  // Resource ID found in the first body
+	SecondID     *string       `json:"secondId,omitempty"` // Resource ID found in the second body
+	Replayed     bool          `json:"replayed"`           // The server flagged the second response as a replay
+	Deduplicated bool          `json:"deduplicated"`       // The retry did not perform the operation again
+	Verdict      string        `json:"verdict"`            // deduplicated, rejected, duplicated, inconclusive or error
+}
+
+// ETagAuditRequest fetches a request repeatedly to check its cache validators.
+type ETagAuditRequest struct {
+	Request    ProxyRequest `json:"request"`
+	Requests   *int         `json:"requests,omitempty"`   // Times the request is sent (default 3, max 10)
+	IntervalMS *int         `json:"intervalMs,omitempty"` // Pause between requests in milliseconds
+}
+
+// ETagAuditResponse reports whether ETag and Last-Modified stay stable for identical content.
+type ETagAuditResponse struct {
+	Samples            []ETagSample `json:"samples"`
+	ContentStable      bool         `json:"contentStable"`
+	ETagStable         *bool        `json:"etagStable,omitempty"`         // Nil when no response had an ETag
+	LastModifiedStable *bool        `json:"lastModifiedStable,omitempty"` // Nil when no response had Last-Modified
+	WeakETag           bool         `json:"weakEtag"`
+	Revalidated        *bool        `json:"revalidated,omitempty"`        // A request with If-None-Match received 304
+	RevalidationStatus *uint16      `json:"revalidationStatus,omitempty"` // Status of the conditional request
+	Findings           []string     `json:"findings"`
+}
+
+// ETagSample is one fetch of an ETag audit.
+type ETagSample struct {
+	Sequence     int     `json:"sequence"`
+	Status       *uint16 `json:"status,omitempty"`
+	ETag         *string `json:"etag,omitempty"`
+	LastModified *string `json:"lastModified,omitempty"`
+	BodyHash     *string `json:"bodyHash,omitempty"` // SHA-256 of the decoded body
+	Size         int     `json:"size"`
+	Error        *string `json:"error,omitempty"`
+}
+
+// GRPCRequest is a unary gRPC or gRPC-Web call.
+type GRPCRequest struct {
+	URL           string                 `json:"url"`                     // Server base URL, e.g. https://api.example.com:443
+	Method        string                 `json:"method"`                  // package.Service/Method
+	Web           bool                   `json:"web,omitempty"`           // Use gRPC-Web over HTTP/1.1 instead of gRPC over HTTP/2
+	Descriptor    *string                `json:"descriptor,omitempty"`    // Base64 FileDescriptorSet describing the method
+	Message       map[string]interface{} `json:"message,omitempty"`       // Request in proto3 JSON form; requires descriptor
+	PayloadBase64 *string                `json:"payloadBase64,omitempty"` // Encoded request message; overrides message
+	Metadata      map[string]string      `json:"metadata,omitempty"`      // Request metadata sent as headers
+	Timeout       *uint64                `json:"timeout,omitempty"`       // Timeout in milliseconds, also sent as grpc-timeout
+}
+
+// GRPCResponse is the outcome of a unary gRPC call.
+type GRPCResponse struct {
+	Success       bool          `json:"success"`                 // The call completed with grpc-status 0
+	Status        *int          `json:"status,omitempty"`        // grpc-status
+	StatusName    *string       `json:"statusName,omitempty"`    // Name of the status code, e.g. UNAVAILABLE
+	StatusMessage *string       `json:"statusMessage,omitempty"` // Decoded grpc-message
+	RequestSize   int           `json:"requestSize"`             // Bytes of the request message
+	Messages      []GRPCMessage `json:"messages"`
+	Trailers      []HeaderField `json:"trailers,omitempty"`
+	FrameError    *string       `json:"frameError,omitempty"` // The body was not a sequence of complete frames
+	HTTP          *ResponseData `json:"http,omitempty"`       // Transport view: headers, timing, TLS
+	Error         *ErrorData    `json:"error,omitempty"`
+}
+
+// GRPCMessage is a response message of a gRPC call.
+type GRPCMessage struct {
+	Size          int                    `json:"size"` // Bytes on the wire, before decompression
+	Compressed    bool                   `json:"compressed"`
+	PayloadBase64 string                 `json:"payloadBase64"`
+	Message       map[string]interface{} `json:"message,omitempty"` // Decoded with the descriptor
+	Fields        map[string]interface{} `json:"fields,omitempty"`  // Decoded without a schema, keyed by field number
+	DecodeError   *string                `json:"decodeError,omitempty"`
+}
+
+// DNSLookupRequest resolves a name outside of an HTTP request.
+type DNSLookupRequest struct {
+	Name    string   `json:"name"`
+	Types   []string `json:"types,omitempty"`   // Record types to query (default A, AAAA, CNAME, MX, TXT, NS and CAA)
+	Server  *string  `json:"server,omitempty"`  // Nameserver IP, optionally with a port; defaults to the system nameserver
+	Timeout *uint64  `json:"timeout,omitempty"` // Timeout per query in milliseconds (default 5000)
+}
+
+// DNSLookupResponse holds one query result per requested record type.
+type DNSLookupResponse struct {
+	Success bool             `json:"success"` // Every query got a reply, including NXDOMAIN
+	Name    string           `json:"name"`
+	Server  string           `json:"server,omitempty"` // Nameserver queried, host:port
+	Queries []DNSQueryResult `json:"queries"`
+	Error   *ErrorData       `json:"error,omitempty"`
+}
+
+// DNSQueryResult is the reply to the query for one record type.
+type DNSQueryResult struct {
+	Type          string      `json:"type"`
+	Time          uint64      `json:"time"`                // Milliseconds, including a TCP retry
+	Server        string      `json:"server"`              // Nameserver that answered
+	Transport     string      `json:"transport,omitempty"` // udp, or tcp after a truncated reply
+	RCode         string      `json:"rcode,omitempty"`     // e.g. NOERROR, NXDOMAIN, SERVFAIL
+	Authoritative bool        `json:"authoritative"`
+	Records       []DNSRecord `json:"records"`
+	Error         *string     `json:"error,omitempty"`
+}
+
+// DNSRecord is an answer record in zone file presentation form.
+type DNSRecord struct {
+	Name  string `json:"name"`
+	Type  string `json:"type"`
+	TTL   uint32 `json:"ttl"`
+	Value string `json:"value"` // e.g. "10 mail.example.com." for MX
+}
+
+// EmailPostureRequest looks up how a domain authenticates its mail.
+type EmailPostureRequest struct {
+	Domain  string  `json:"domain"`
+	Server  *string `json:"server,omitempty"`  // Nameserver IP, optionally with a port; defaults to the system nameserver
+	Timeout *uint64 `json:"timeout,omitempty"` // Timeout per query in milliseconds (default 5000)
+}
+
+// EmailPostureResponse summarizes the MX, SPF and DMARC records of a domain.
+type EmailPostureResponse struct {
+	Success  bool             `json:"success"` // Every query got a reply, including NXDOMAIN
+	Domain   string           `json:"domain"`
+	Server   string           `json:"server,omitempty"`
+	MX       []MXHost         `json:"mx"`     // By preference, most preferred first
+	NullMX   bool             `json:"nullMx"` // A single "0 ." record: the domain accepts no mail
+	SPF      *SPFInfo         `json:"spf,omitempty"`
+	DMARC    *DMARCInfo       `json:"dmarc,omitempty"`
+	Findings []EmailFinding   `json:"findings"`
+	Queries  []DNSQueryResult `json:"queries"` // MX and TXT of the domain, and TXT of _dmarc.domain
+	Error    *ErrorData       `json:"error,omitempty"`
+}
+
+// MXHost is a mail server of a domain.
+type MXHost struct {
+	Preference int    `json:"preference"` // Lower is tried first
+	Host       string `json:"host"`
+}
+
+// SPFInfo is the SPF record of a domain.
+type SPFInfo struct {
+	Record   string    `json:"record"`
+	All      *string   `json:"all,omitempty"`      // e.g. -all; what unlisted senders get
+	Redirect *string   `json:"redirect,omitempty"` // Domain whose record applies instead
+	Includes []string  `json:"includes,omitempty"`
+	Lookups  int       `json:"lookups"` // DNS-querying terms of this record; includes add their own, up to 10 in total
+	Terms    []SPFTerm `json:"terms"`
+}
+
+// SPFTerm is a mechanism or modifier of an SPF record.
+type SPFTerm struct {
+	Qualifier string `json:"qualifier,omitempty"` // +, -, ~ or ?; empty for modifiers
+	Name      string `json:"name"`                // e.g. ip4, include, all or redirect
+	Value     string `json:"value,omitempty"`
+	Modifier  bool   `json:"modifier,omitempty"`
+}
+
+// DMARCInfo is the DMARC record of a domain.
+type DMARCInfo struct {
+	Record          string   `json:"record"`
+	Policy          string   `json:"policy"`              // none, quarantine or reject
+	SubdomainPolicy string   `json:"subdomainPolicy"`     // Defaults to the policy
+	Percent         int      `json:"percent"`             // Share of failing mail the policy applies to
+	DKIMAlignment   string   `json:"dkimAlignment"`       // r (relaxed) or s (strict)
+	SPFAlignment    string   `json:"spfAlignment"`        // r (relaxed) or s (strict)
+	Aggregate       []string `json:"aggregate,omitempty"` // rua report addresses
+	Forensic        []string `json:"forensic,omitempty"`  // ruf report addresses
+}
+
+// EmailFinding is an observation about the mail posture of a domain.
+type EmailFinding struct {
+	Severity string `json:"severity"` // error, warning or info
+	Message  string `json:"message"`
+}
+
+// DNSSecurityInfo holds the CAA policy and DNSSEC status of a host, as
+// reported by the nameserver queried.
+type DNSSecurityInfo struct {
+	Server string      `json:"server,omitempty"`
+	CAA    *CAAInfo    `json:"caa,omitempty"`
+	DNSSEC *DNSSECInfo `json:"dnssec,omitempty"`
+	Error  *string     `json:"error,omitempty"`
+}
+
+// CAAInfo is the CAA record set governing which CAs may issue certificates
+// for a host.
+type CAAInfo struct {
+	Domain    *string     `json:"domain,omitempty"`    // Name the records were found at; absent when none apply and any CA may issue
+	Issue     []string    `json:"issue,omitempty"`     // CAs allowed to issue; ";" forbids issuance
+	IssueWild []string    `json:"issueWild,omitempty"` // CAs allowed to issue wildcard certificates
+	IODEF     []string    `json:"iodef,omitempty"`     // Where CAs report refused requests
+	Critical  []string    `json:"critical,omitempty"`  // Unknown tags flagged critical
+	Records   []DNSRecord `json:"records"`
+	Error     *string     `json:"error,omitempty"`
+}
+
+// DNSSECInfo is the DNSSEC status of the addresses of a host.
+type DNSSECInfo struct {
+	Status     string         `json:"status,omitempty"` // secure, insecure, unvalidated (signed, resolver not validating) or bogus
+	Signatures []DNSSignature `json:"signatures"`
+	Error      *string        `json:"error,omitempty"`
+}
+
+// DNSSignature is an RRSIG record of an answer.
+type DNSSignature struct {
+	TypeCovered string `json:"typeCovered"`
+	Algorithm   string `json:"algorithm"` // e.g. ECDSAP256SHA256
+	Signer      string `json:"signer"`    // Signing zone
+	KeyTag      uint16 `json:"keyTag"`
+	Inception   uint64 `json:"inception"`  // Unix seconds
+	Expiration  uint64 `json:"expiration"` // Unix seconds
+}
+
+// JWTRequest is a token to decode, optionally with a key to verify it.
+type JWTRequest struct {
+	Token string  `json:"token"`         // Compact JWT, optionally prefixed with "Bearer "
+	Key   *string `json:"key,omitempty"` // HMAC secret, or PEM public key or certificate for RSA, ECDSA and EdDSA
+}
+
+// JWTResponse holds a decoded token.
+type JWTResponse struct {
+	Success bool       `json:"success"`
+	Token   *JWTInfo   `json:"token,omitempty"`
+	Error   *ErrorData `json:"error,omitempty"`
+}
+
+// ScriptRequest evaluates a script over a stored response.
+type ScriptRequest struct {
+	Script    string                 `json:"script"`
+	Response  *ResponseData          `json:"response,omitempty"`  // Exposed as response, status, headers, body, json and timing
+	HistoryID *string                `json:"historyId,omitempty"` // History entry exposed as entry
+	Variables map[string]interface{} `json:"variables,omitempty"` // Exposed as vars
+	Timeout   *uint64                `json:"timeout,omitempty"`   // Milliseconds, at most the default of 2000
+}
+
+// ScriptResponse holds the value a script returned.
+type ScriptResponse struct {
+	Success  bool         `json:"success"`
+	Result   interface{}  `json:"result"`
+	Duration uint64       `json:"duration"` // Milliseconds
+	Error    *ScriptError `json:"error,omitempty"`
+}
+
+// ScriptError is a failed evaluation, with the position in the script when known.
+type ScriptError struct {
+	Message string `json:"message"`
+	Code    string `json:"code"`
+	Line    *int   `json:"line,omitempty"`
+	Column  *int   `json:"column,omitempty"`
+}
+
+// Assertion is a declarative check of a response; see the Assert* constants
+// for the fields each type uses.
+type Assertion struct {
+	Type   string          `json:"type"`
+	Name   *string         `json:"name,omitempty"`   // Label shown in results
+	Header string          `json:"header,omitempty"` // header assertions
+	Path   string          `json:"path,omitempty"`   // jsonPath assertions, e.g. $.items[0].id
+	Value  json.RawMessage `json:"value,omitempty"`
+}
+
+// AssertionResult is the outcome of an assertion.
+type AssertionResult struct {
+	Type    string      `json:"type"`
+	Name    *string     `json:"name,omitempty"`
+	Passed  bool        `json:"passed"`
+	Actual  interface{} `json:"actual,omitempty"`  // Value checked, when it could be read
+	Message *string     `json:"message,omitempty"` // Why the assertion failed
+}
+
+// Extraction saves a value of a JSON response body to a variable, so later
+// requests can use it, e.g. a token returned by a login request.
+type Extraction struct {
+	Variable string `json:"variable"`
+	Path     string `json:"path"` // JSONPath, e.g. $.token
+}
+
+// ExtractionResult is the outcome of an extraction.
+type ExtractionResult struct {
+	Variable string  `json:"variable"`
+	Path     string  `json:"path"`
+	Value    *string `json:"value,omitempty"` // Value set; objects, arrays and several matches as JSON
+	Error    *string `json:"error,omitempty"`
+}
+
+// JSONPathRequest applies a JSONPath expression to a JSON document.
+type JSONPathRequest struct {
+	JSON string `json:"json"`
+	Path string `json:"path"`
+}
+
+// JSONPathResponse holds the values a JSONPath expression selected.
+type JSONPathResponse struct {
+	Success bool          `json:"success"`
+	Matches []interface{} `json:"matches"`
+	Error   *ErrorData    `json:"error,omitempty"`
+}
+
+// RequestScripts are JavaScript run around a request, like /api/script. The
+// pre-request script sees request and vars and its value may be
+// {method, url, headers, body, vars} to change them; null headers are
+// removed. The test script also sees the response like /api/script and its
+// value may be {tests, vars}, where tests maps names to booleans or to
+// {pass, message}. Variables set are saved to the request's environment.
+type RequestScripts struct {
+	PreRequest string `json:"preRequest,omitempty"`
+	Test       string `json:"test,omitempty"`
+}
+
+// ScriptResults holds the outcome of the scripts of a request.
+type ScriptResults struct {
+	PreRequest *ScriptRun `json:"preRequest,omitempty"`
+	Test       *ScriptRun `json:"test,omitempty"`
+}
+
+// ScriptRun is the outcome of one script.
+type ScriptRun struct {
+	Assertions []ScriptAssertion  `json:"assertions,omitempty"` // Test script only, sorted by name
+	Passed     int                `json:"passed"`
+	Failed     int                `json:"failed"`
+	Variables  map[string]*string `json:"variables,omitempty"` // Variables set; null when removed
+	Duration   uint64             `json:"duration"`            // Milliseconds
+	Error      *ScriptError       `json:"error,omitempty"`
+}
+
+// PluginRun is the outcome of one plugin hook.
+type PluginRun struct {
+	Plugin       string            `json:"plugin"`
+	Hook         string            `json:"hook"`                   // on_request or on_response
+	Duration     uint64            `json:"duration"`               // Milliseconds
+	Annotations  map[string]string `json:"annotations,omitempty"`  // Findings of an analyzer
+	BodyReplaced bool              `json:"bodyReplaced,omitempty"` // Whether a decoder replaced the response body
+	Error        *string           `json:"error,omitempty"`
+}
+
+// ScriptAssertion is a named check of a test script.
+type ScriptAssertion struct {
+	Name    string  `json:"name"`
+	Passed  bool    `json:"passed"`
+	Message *string `json:"message,omitempty"`
+}
+
+// RateLimitInfo holds the rate limit state advertised by a response, from
+// the IETF RateLimit fields or the X-RateLimit-* variants.
+type RateLimitInfo struct {
+	Limit      *int    `json:"limit,omitempty"`
+	Remaining  *int    `json:"remaining,omitempty"`
+	Used       *int    `json:"used,omitempty"`
+	Reset      *int    `json:"reset,omitempty"`      // Seconds until the window resets
+	Window     *int    `json:"window,omitempty"`     // Window length in seconds from the policy
+	RetryAfter *int    `json:"retryAfter,omitempty"` // Seconds
+	Policy     *string `json:"policy,omitempty"`
+	Source     string  `json:"source,omitempty"` // ietf or x-ratelimit
+}
+
+// WebSocketRequest opens a WebSocket session.
+type WebSocketRequest struct {
+	URL       string            `json:"url"` // ws://, wss://, http:// or https:// URL
+	Headers   map[string]string `json:"headers"`
+	Protocols []string          `json:"protocols,omitempty"` // Subprotocols offered in Sec-WebSocket-Protocol
+	Timeout   *uint64           `json:"timeout,omitempty"`   // Handshake timeout in milliseconds
+}
+
+// WebSocketHandshake is the outcome of a WebSocket upgrade. SessionID is set
+// only when the server accepted it.
+type WebSocketHandshake struct {
+	SessionID  *string       `json:"sessionId,omitempty"`
+	Accepted   bool          `json:"accepted"`
+	URL        string        `json:"url"`
+	Status     uint16        `json:"status"`
+	Headers    []HeaderField `json:"headers"`
+	Protocol   *string       `json:"protocol,omitempty"`   // Subprotocol selected by the server
+	Extensions *string       `json:"extensions,omitempty"` // Extensions negotiated by the server
+	ServerIP   *string       `json:"serverIp,omitempty"`
+	TLS        *TLSInfo      `json:"tls,omitempty"`
+	Timing     TimingInfo    `json:"timing"`
+	Error      *string       `json:"error,omitempty"` // Why the upgrade was refused
+}
+
+// WebSocketMessage is a frame sent on an open session.
+type WebSocketMessage struct {
+	Type   string `json:"type"`             // text, binary, ping, pong or close (default text)
+	Data   string `json:"data"`             // Payload; the reason of a close frame
+	Base64 bool   `json:"base64,omitempty"` // Data is base64-encoded binary
+	Code   *int   `json:"code,omitempty"`   // Close status code (default 1000)
+}
+
+// WebSocketFrame is a frame recorded on a session.
+type WebSocketFrame struct {
+	Seq       int     `json:"seq"`
+	Direction string  `json:"direction"` // sent or received
+	Opcode    int     `json:"opcode"`
+	Type      string  `json:"type"` // Name of the opcode
+	Fin       bool    `json:"fin"`
+	Size      int     `json:"size"`                // Payload length in bytes
+	Offset    uint64  `json:"offset"`              // Milliseconds since the session opened
+	Text      *string `json:"text,omitempty"`      // UTF-8 payload
+	Base64    *string `json:"base64,omitempty"`    // Binary payload
+	Truncated bool    `json:"truncated,omitempty"` // Payload cut to the first 64 KiB
+	CloseCode *int    `json:"closeCode,omitempty"` // Status code of a close frame
+}
+
+// WebSocketFrameLog holds the frames recorded on a session after a sequence number.
+type WebSocketFrameLog struct {
+	SessionID   string           `json:"sessionId"`
+	Frames      []WebSocketFrame `json:"frames"`
+	Closed      bool             `json:"closed"`
+	CloseReason *string          `json:"closeReason,omitempty"`
+	Dropped     int              `json:"dropped,omitempty"` // Older frames discarded beyond the per-session limit
+}
+
 // NewSuccessResponse creates a successful proxy response.
 func NewSuccessResponse(data ResponseData) ProxyResponse {
 	return ProxyResponse{
@@ -114,3 +1504,24 @@ func NewErrorResponse(message, code string) ProxyResponse {
 		},
 	}
 }
+
+// TransferredBytes approximates the bytes sent and received for a response:
+// the request body, the response headers and the body as it was transferred.
+func (r ProxyResponse) TransferredBytes() int {
+	if r.Data == nil {
+		return 0
+	}
+	total := 0
+	if r.Data.RequestBodySize != nil {
+		total += *r.Data.RequestBodySize
+	}
+	if sizes := r.Data.SizeBreakdown; sizes != nil {
+		total += sizes.Headers
+		if sizes.Compressed != nil {
+			total += *sizes.Compressed
+		} else {
+			total += sizes.Body
+		}
+	}
+	return total
+}
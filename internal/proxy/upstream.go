@@ -0,0 +1,130 @@
+package proxy
+
+import (
+	"fmt"
+	"net/url"
+	"strings"
+
+	"golang.org/x/net/http/httpproxy"
+
+	"zone.digit.tommie/internal/pac"
+)
+
+// upstreamProxy decides per target URL whether to go direct or through an upstream proxy.
+type upstreamProxy struct {
+	cfg   *httpproxy.Config
+	proxy func(*url.URL) (*url.URL, error)
+	pac   *pac.Script
+}
+
+// newUpstreamProxy creates a resolver for the given proxy configuration.
+// A PAC script takes precedence over the static configuration. Without
+// either, it yields nil, meaning all requests go direct.
+func newUpstreamProxy(cfg *httpproxy.Config, script *pac.Script) *upstreamProxy {
+	if script != nil {
+		return &upstreamProxy{pac: script}
+	}
+	if cfg == nil || (cfg.HTTPProxy == "" && cfg.HTTPSProxy == "") {
+		return nil
+	}
+	return &upstreamProxy{
+		cfg:   cfg,
+		proxy: cfg.ProxyFunc(),
+	}
+}
+
+// decide returns the proxy to use for target (nil when direct) and a report of the decision.
+func (p *upstreamProxy) decide(target string) (*url.URL, *ProxyDecision, error) {
+	if p == nil {
+		return nil, nil, nil
+	}
+
+	parsed, err := url.Parse(target)
+	if err != nil {
+		return nil, nil, err
+	}
+	if p.pac != nil {
+		return p.decidePAC(parsed)
+	}
+
+	configured := p.cfg.HTTPProxy
+	if parsed.Scheme == "https" {
+		configured = p.cfg.HTTPSProxy
+	}
+	if configured == "" {
+		return nil, &ProxyDecision{Direct: true, Reason: fmt.Sprintf("no proxy configured for %s", parsed.Scheme)}, nil
+	}
+
+	proxyURL, err := p.proxy(parsed)
+	if err != nil {
+		return nil, nil, fmt.Errorf("invalid upstream proxy: %w", err)
+	}
+	if proxyURL == nil {
+		return nil, &ProxyDecision{Direct: true, Reason: "host matches NO_PROXY or is a loopback address"}, nil
+	}
+
+	return proxyURL, &ProxyDecision{
+		Direct: false,
+		Proxy:  proxyURL.Redacted(),
+		Reason: fmt.Sprintf("%s proxy for %s", parsed.Scheme, parsed.Hostname()),
+	}, nil
+}
+
+// decidePAC evaluates FindProxyForURL and uses the first directive this
+// transport can honor. Failover to later directives on connection errors is
+// not attempted; they are listed in the reason instead.
+func (p *upstreamProxy) decidePAC(target *url.URL) (*url.URL, *ProxyDecision, error) {
+	result, err := p.pac.FindProxyForURL(target.String(), target.Hostname())
+	if err != nil {
+		return nil, nil, err
+	}
+	directives, err := pac.ParseResult(result)
+	if err != nil {
+		return nil, nil, fmt.Errorf("PAC returned %q: %w", result, err)
+	}
+
+	var skipped []string
+	for _, d := range directives {
+		if d.Type == "DIRECT" {
+			return nil, &ProxyDecision{Direct: true, Reason: pacReason(skipped), PAC: result}, nil
+		}
+
+		proxyURL := pacProxyURL(d)
+		if proxyURL == nil {
+			skipped = append(skipped, d.Type+" "+d.Host)
+			continue
+		}
+		return proxyURL, &ProxyDecision{
+			Direct: false,
+			Proxy:  proxyURL.Redacted(),
+			Reason: pacReason(skipped),
+			PAC:    result,
+		}, nil
+	}
+
+	return nil, nil, fmt.Errorf("PAC returned no supported directive: %q", result)
+}
+
+// pacProxyURL converts a PAC directive to a proxy URL, or nil if unsupported.
+func pacProxyURL(d pac.Directive) *url.URL {
+	var scheme string
+	switch d.Type {
+	case "PROXY", "HTTP":
+		scheme = "http"
+	case "HTTPS":
+		scheme = "https"
+	case "SOCKS", "SOCKS5":
+		scheme = "socks5"
+	default:
+		// SOCKS4 has no net/http transport support
+		return nil
+	}
+	return &url.URL{Scheme: scheme, Host: d.Host}
+}
+
+func pacReason(skipped []string) string {
+	if len(skipped) == 0 {
+		return "selected by PAC file"
+	}
+	return fmt.Sprintf("selected by PAC file (skipped unsupported %s)", strings.Join(skipped, ", "))
+}
@@ -0,0 +1,616 @@
+package proxy
+
+import (
+	"bufio"
+	"context"
+	"crypto/rand"
+	"crypto/sha1"
+	"encoding/base64"
+	"encoding/binary"
+	"encoding/hex"
+	"errors"
+	"fmt"
+	"io"
+	"net"
+	"net/http"
+	"strings"
+	"sync"
+	"time"
+	"unicode/utf8"
+)
+
+const (
+	// MaxWebSocketSessions bounds the sessions kept at once, open or closed.
+	MaxWebSocketSessions = 32
+	// MaxWebSocketFrames is the number of frames kept per session; older ones are dropped.
+	MaxWebSocketFrames = 2000
+	// MaxWebSocketPayload is the largest frame payload accepted from a server.
+	MaxWebSocketPayload = 16 << 20
+
+	// maxFramePreview bounds the payload kept with each recorded frame.
+	maxFramePreview = 64 << 10
+	// closeTimeout is how long a closing handshake may take before the connection is dropped.
+	closeTimeout = 5 * time.Second
+
+	websocketGUID = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11"
+)
+
+// WebSocket opcodes (RFC 6455 5.2).
+const (
+	opContinuation byte = 0x0
+	opText         byte = 0x1
+	opBinary       byte = 0x2
+	opClose        byte = 0x8
+	opPing         byte = 0x9
+	opPong         byte = 0xA
+)
+
+var opcodeNames = map[byte]string{
+	opContinuation: "continuation",
+	opText:         "text",
+	opBinary:       "binary",
+	opClose:        "close",
+	opPing:         "ping",
+	opPong:         "pong",
+}
+
+// WebSocketError is returned when a WebSocket session cannot be opened or used.
+type WebSocketError struct {
+	Message string
+	Code    string
+}
+
+func (e *WebSocketError) Error() string {
+	return e.Message
+}
+
+// webSocketSessions tracks the sessions of a service by ID.
+type webSocketSessions struct {
+	mu       sync.Mutex
+	sessions map[string]*webSocketSession
+}
+
+// webSocketSession relays frames over one upgraded connection and records them.
+type webSocketSession struct {
+	id     string
+	owner  string
+	opened time.Time
+	conn   io.ReadWriteCloser
+	egress *egressBudget
+
+	writeMu sync.Mutex
+
+	mu          sync.Mutex
+	frames      []WebSocketFrame
+	nextSeq     int
+	dropped     int
+	closeSent   bool
+	closed      bool
+	closeReason string
+	changed     chan struct{} // Closed and replaced whenever the log changes
+}
+
+// OpenWebSocket performs a WebSocket upgrade through the same DNS, policy,
+// proxy and TLS pipeline as regular requests. A handshake the server refuses
+// is returned without a session; the session belongs to owner.
+func (s *HTTPService) OpenWebSocket(owner string, request WebSocketRequest) (*WebSocketHandshake, error) {
+	timing := NewDetailedTiming()
+
+	rawURL := request.URL
+	switch {
+	case strings.HasPrefix(strings.ToLower(rawURL), "ws://"):
+		rawURL = "http://" + rawURL[len("ws://"):]
+	case strings.HasPrefix(strings.ToLower(rawURL), "wss://"):
+		rawURL = "https://" + rawURL[len("wss://"):]
+	}
+	ctx, err := newRequestContext(rawURL)
+	if err != nil {
+		return nil, &WebSocketError{err.Error(), "INVALID_URL"}
+	}
+
+	timeoutMS := DefaultTimeoutMS
+	if request.Timeout != nil {
+		timeoutMS = int(*request.Timeout)
+	}
+	timeout := time.Duration(timeoutMS) * time.Millisecond
+
+	if budget := s.egress.exhausted(); budget != "" {
+		return nil, &WebSocketError{fmt.Sprintf("Egress %s byte budget exceeded", budget), "QUOTA_EXCEEDED"}
+	}
+	if err := s.policy.Load().checkHost(ctx.host); err != nil {
+		return nil, &WebSocketError{err.Error(), "BLOCKED_BY_POLICY"}
+	}
+
+	timing.StartDNS()
+	dnsResult, err := s.opts.Resolver.Resolve(context.Background(), ctx.host)
+	if err != nil {
+		return nil, &WebSocketError{fmt.Sprintf("DNS lookup failed: %v", err), "DNS_ERROR"}
+	}
+	timing.EndDNS()
+	if err := s.policy.Load().checkIPs(dnsResult.IPs); err != nil {
+		return nil, &WebSocketError{err.Error(), "BLOCKED_BY_POLICY"}
+	}
+
+	proxyURL, _, err := s.upstream.decide(ctx.url)
+	if err != nil {
+		return nil, &WebSocketError{err.Error(), "PROXY_CONFIG_ERROR"}
+	}
+
+	// Upgrades only exist in HTTP/1.1
+	dialer := &hopDialer{
+		timing:     timing,
+		timeout:    timeout,
+		serverName: ctx.host,
+		rootCAs:    s.opts.RootCAs,
+		nextProtos: httpVersion11.alpnProtocols(),
+		secure:     ctx.isHTTPS,
+	}
+	if proxyURL == nil {
+		dialer.policy = s.policy.Load()
+	}
+	// The dial races the addresses already resolved rather than resolving again
+	dialer.addresses = map[string][]net.IP{ctx.host: dnsResult.IPs}
+	transport, err := newRoundTripper(dialer, httpVersion11, proxyURL, ctx.isHTTPS)
+	if err != nil {
+		return nil, &WebSocketError{err.Error(), "UNSUPPORTED_PROTOCOL"}
+	}
+	// A client timeout would also end the upgraded connection, so only the handshake is bounded
+	if t, ok := transport.(*http.Transport); ok {
+		t.ResponseHeaderTimeout = timeout
+	}
+
+	httpReq, err := http.NewRequest(http.MethodGet, ctx.url, nil)
+	if err != nil {
+		return nil, &WebSocketError{fmt.Sprintf("Failed to create request: %v", err), "REQUEST_BUILD_ERROR"}
+	}
+	for key, value := range request.Headers {
+		httpReq.Header.Set(key, value)
+	}
+	key := make([]byte, 16)
+	if _, err := rand.Read(key); err != nil {
+		return nil, err
+	}
+	challenge := base64.StdEncoding.EncodeToString(key)
+	httpReq.Header.Set("Connection", "Upgrade")
+	httpReq.Header.Set("Upgrade", "websocket")
+	httpReq.Header.Set("Sec-WebSocket-Version", "13")
+	httpReq.Header.Set("Sec-WebSocket-Key", challenge)
+	if len(request.Protocols) > 0 {
+		httpReq.Header.Set("Sec-WebSocket-Protocol", strings.Join(request.Protocols, ", "))
+	}
+
+	timing.StartRequest()
+	resp, err := transport.RoundTrip(httpReq)
+	var policyErr *PolicyError
+	if errors.As(err, &policyErr) {
+		return nil, &WebSocketError{policyErr.Error(), "BLOCKED_BY_POLICY"}
+	}
+	var certErr *CertificateError
+	if errors.As(err, &certErr) {
+		return nil, &WebSocketError{fmt.Sprintf("Handshake failed: %v", certErr), "CERT_INVALID"}
+	}
+	if err != nil {
+		return nil, &WebSocketError{fmt.Sprintf("Handshake failed: %v", err), "REQUEST_FAILED"}
+	}
+	timing.MarkTTFB()
+
+	serverIP := dialer.remoteIP
+	if proxyURL != nil && len(dnsResult.IPs) > 0 {
+		serverIP = dnsResult.IPs[0].String()
+	}
+	handshake := &WebSocketHandshake{
+		URL:        request.URL,
+		Status:     uint16(resp.StatusCode),
+		Headers:    headerFieldsFromHeader(resp.Header, false),
+		Protocol:   optionalString(resp.Header.Get("Sec-WebSocket-Protocol")),
+		Extensions: optionalString(resp.Header.Get("Sec-WebSocket-Extensions")),
+		ServerIP:   optionalString(serverIP),
+		TLS:        buildTLSInfo(dialer.tlsInfo),
+		Timing:     timing.ToTimingInfo(),
+	}
+
+	if resp.StatusCode != http.StatusSwitchingProtocols {
+		resp.Body.Close()
+		handshake.Error = strPtr(fmt.Sprintf("Server did not switch protocols (status %d)", resp.StatusCode))
+		return handshake, nil
+	}
+	if resp.Header.Get("Sec-WebSocket-Accept") != acceptKey(challenge) {
+		resp.Body.Close()
+		handshake.Error = strPtr("Invalid Sec-WebSocket-Accept in handshake response")
+		return handshake, nil
+	}
+	conn, ok := resp.Body.(io.ReadWriteCloser)
+	if !ok {
+		resp.Body.Close()
+		return nil, &WebSocketError{"Upgraded connection is not writable", "UNSUPPORTED_PROTOCOL"}
+	}
+
+	session := &webSocketSession{
+		owner:   owner,
+		opened:  time.Now(),
+		conn:    conn,
+		egress:  s.egress,
+		changed: make(chan struct{}),
+	}
+	if err := s.websockets.add(session); err != nil {
+		conn.Close()
+		return nil, err
+	}
+	go session.readLoop()
+
+	handshake.SessionID = &session.id
+	handshake.Accepted = true
+	return handshake, nil
+}
+
+// SendWebSocket writes a message to an open session and returns the recorded frame.
+func (s *HTTPService) SendWebSocket(owner, id string, message WebSocketMessage) (*WebSocketFrame, error) {
+	session, err := s.websockets.get(owner, id)
+	if err != nil {
+		return nil, err
+	}
+
+	var payload []byte
+	if message.Base64 {
+		if payload, err = base64.StdEncoding.DecodeString(message.Data); err != nil {
+			return nil, &WebSocketError{fmt.Sprintf("Invalid base64 payload: %v", err), "INVALID_REQUEST"}
+		}
+	} else {
+		payload = []byte(message.Data)
+	}
+
+	var opcode byte
+	switch message.Type {
+	case "", "text":
+		opcode = opText
+	case "binary":
+		opcode = opBinary
+	case "ping":
+		opcode = opPing
+	case "pong":
+		opcode = opPong
+	case "close":
+		return session.close(message.Code, message.Data)
+	default:
+		return nil, &WebSocketError{fmt.Sprintf("Unknown message type: %s", message.Type), "INVALID_REQUEST"}
+	}
+	if opcode >= opClose && len(payload) > 125 {
+		return nil, &WebSocketError{"Control frame payloads are limited to 125 bytes", "INVALID_REQUEST"}
+	}
+	return session.send(opcode, payload)
+}
+
+// WebSocketFrames returns the frames recorded after sequence number after,
+// and a channel closed when more are recorded.
+func (s *HTTPService) WebSocketFrames(owner, id string, after int) (*WebSocketFrameLog, <-chan struct{}, error) {
+	session, err := s.websockets.get(owner, id)
+	if err != nil {
+		return nil, nil, err
+	}
+
+	session.mu.Lock()
+	defer session.mu.Unlock()
+	log := &WebSocketFrameLog{
+		SessionID: id,
+		Frames:    []WebSocketFrame{},
+		Closed:    session.closed,
+		Dropped:   session.dropped,
+	}
+	if session.closed {
+		log.CloseReason = optionalString(session.closeReason)
+	}
+	for _, frame := range session.frames {
+		if frame.Seq > after {
+			log.Frames = append(log.Frames, frame)
+		}
+	}
+	return log, session.changed, nil
+}
+
+// CloseWebSocket performs the closing handshake, if the session is still
+// open, and forgets the session.
+func (s *HTTPService) CloseWebSocket(owner, id string) error {
+	session, err := s.websockets.get(owner, id)
+	if err != nil {
+		return err
+	}
+	s.websockets.remove(id)
+	// An already closed session only needs to be forgotten
+	session.close(nil, "")
+	return nil
+}
+
+func (m *webSocketSessions) add(session *webSocketSession) error {
+	id := make([]byte, 12)
+	if _, err := rand.Read(id); err != nil {
+		return err
+	}
+	session.id = hex.EncodeToString(id)
+
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	if m.sessions == nil {
+		m.sessions = make(map[string]*webSocketSession)
+	}
+	if len(m.sessions) >= MaxWebSocketSessions {
+		// Make room by forgetting the oldest closed session
+		var oldest *webSocketSession
+		for _, s := range m.sessions {
+			if s.isClosed() && (oldest == nil || s.opened.Before(oldest.opened)) {
+				oldest = s
+			}
+		}
+		if oldest == nil {
+			return &WebSocketError{fmt.Sprintf("At most %d WebSocket sessions may be open", MaxWebSocketSessions), "TOO_MANY_SESSIONS"}
+		}
+		delete(m.sessions, oldest.id)
+	}
+	m.sessions[session.id] = session
+	return nil
+}
+
+func (m *webSocketSessions) get(owner, id string) (*webSocketSession, error) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	session, ok := m.sessions[id]
+	if !ok || session.owner != owner {
+		return nil, &WebSocketError{"WebSocket session not found", "NOT_FOUND"}
+	}
+	return session, nil
+}
+
+func (m *webSocketSessions) remove(id string) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	delete(m.sessions, id)
+}
+
+// readLoop records incoming frames until the connection ends, answering
+// pings and the server's close frame.
+func (ws *webSocketSession) readLoop() {
+	reader := bufio.NewReader(ws.conn)
+	for {
+		fin, opcode, payload, err := readFrame(reader)
+		if err != nil {
+			if !ws.isClosed() {
+				ws.finish(fmt.Sprintf("Connection lost: %v", err))
+			}
+			return
+		}
+		ws.egress.record(len(payload))
+		ws.record("received", fin, opcode, payload)
+
+		switch opcode {
+		case opPing:
+			ws.send(opPong, payload)
+		case opClose:
+			ws.mu.Lock()
+			echo := !ws.closeSent
+			ws.closeSent = true
+			ws.mu.Unlock()
+			if echo {
+				// Echo the status code as the closing handshake requires
+				code := payload
+				if len(code) > 2 {
+					code = code[:2]
+				}
+				ws.writeAndRecord(opClose, code)
+			}
+			ws.finish("Closed by server" + closeSuffix(payload))
+			return
+		}
+	}
+}
+
+// send writes a data or control frame and records it.
+func (ws *webSocketSession) send(opcode byte, payload []byte) (*WebSocketFrame, error) {
+	if ws.isClosed() {
+		return nil, &WebSocketError{"WebSocket session is closed", "SESSION_CLOSED"}
+	}
+	return ws.writeAndRecord(opcode, payload)
+}
+
+func (ws *webSocketSession) writeAndRecord(opcode byte, payload []byte) (*WebSocketFrame, error) {
+	ws.writeMu.Lock()
+	err := writeFrame(ws.conn, opcode, payload)
+	ws.writeMu.Unlock()
+	if err != nil {
+		ws.finish(fmt.Sprintf("Write failed: %v", err))
+		return nil, &WebSocketError{fmt.Sprintf("Write failed: %v", err), "SEND_FAILED"}
+	}
+	ws.egress.record(len(payload))
+	return ws.record("sent", true, opcode, payload), nil
+}
+
+// close starts the closing handshake and drops the connection if the server
+// does not answer in time.
+func (ws *webSocketSession) close(code *int, reason string) (*WebSocketFrame, error) {
+	ws.mu.Lock()
+	if ws.closed || ws.closeSent {
+		ws.mu.Unlock()
+		return nil, &WebSocketError{"WebSocket session is closed", "SESSION_CLOSED"}
+	}
+	ws.closeSent = true
+	ws.mu.Unlock()
+
+	status := 1000
+	if code != nil {
+		status = *code
+	}
+	payload := binary.BigEndian.AppendUint16(nil, uint16(status))
+	payload = append(payload, reason...)
+	if len(payload) > 125 {
+		payload = payload[:125]
+	}
+
+	frame, err := ws.writeAndRecord(opClose, payload)
+	time.AfterFunc(closeTimeout, func() {
+		if !ws.isClosed() {
+			ws.finish("Server did not complete the closing handshake")
+		}
+	})
+	return frame, err
+}
+
+// finish marks the session closed and releases the connection.
+func (ws *webSocketSession) finish(reason string) {
+	ws.mu.Lock()
+	if ws.closed {
+		ws.mu.Unlock()
+		return
+	}
+	ws.closed = true
+	ws.closeReason = reason
+	ws.notifyLocked()
+	ws.mu.Unlock()
+	ws.conn.Close()
+}
+
+func (ws *webSocketSession) isClosed() bool {
+	ws.mu.Lock()
+	defer ws.mu.Unlock()
+	return ws.closed
+}
+
+// record appends a frame to the log, dropping the oldest beyond the limit.
+func (ws *webSocketSession) record(direction string, fin bool, opcode byte, payload []byte) *WebSocketFrame {
+	now := time.Now()
+	frame := WebSocketFrame{
+		Direction: direction,
+		Opcode:    int(opcode),
+		Type:      opcodeNames[opcode],
+		Fin:       fin,
+		Size:      len(payload),
+		Offset:    uint64(now.Sub(ws.opened).Milliseconds()),
+	}
+	if frame.Type == "" {
+		frame.Type = "reserved"
+	}
+
+	preview := payload
+	if opcode == opClose && len(preview) >= 2 {
+		code := int(binary.BigEndian.Uint16(preview))
+		frame.CloseCode = &code
+		preview = preview[2:]
+	}
+	if len(preview) > maxFramePreview {
+		preview = preview[:maxFramePreview]
+		frame.Truncated = true
+	}
+	if len(preview) > 0 {
+		if opcode != opBinary && utf8.Valid(preview) {
+			frame.Text = strPtr(string(preview))
+		} else {
+			frame.Base64 = strPtr(base64.StdEncoding.EncodeToString(preview))
+		}
+	}
+
+	ws.mu.Lock()
+	defer ws.mu.Unlock()
+	ws.nextSeq++
+	frame.Seq = ws.nextSeq
+	ws.frames = append(ws.frames, frame)
+	if len(ws.frames) > MaxWebSocketFrames {
+		ws.dropped += len(ws.frames) - MaxWebSocketFrames
+		ws.frames = ws.frames[len(ws.frames)-MaxWebSocketFrames:]
+	}
+	ws.notifyLocked()
+	return &frame
+}
+
+func (ws *webSocketSession) notifyLocked() {
+	close(ws.changed)
+	ws.changed = make(chan struct{})
+}
+
+// readFrame reads one frame (RFC 6455 5.2), unmasking it if needed.
+func readFrame(r *bufio.Reader) (fin bool, opcode byte, payload []byte, err error) {
+	var head [2]byte
+	if _, err = io.ReadFull(r, head[:]); err != nil {
+		return
+	}
+	fin = head[0]&0x80 != 0
+	opcode = head[0] & 0x0F
+	masked := head[1]&0x80 != 0
+
+	length := uint64(head[1] & 0x7F)
+	switch length {
+	case 126:
+		var ext [2]byte
+		if _, err = io.ReadFull(r, ext[:]); err != nil {
+			return
+		}
+		length = uint64(binary.BigEndian.Uint16(ext[:]))
+	case 127:
+		var ext [8]byte
+		if _, err = io.ReadFull(r, ext[:]); err != nil {
+			return
+		}
+		length = binary.BigEndian.Uint64(ext[:])
+	}
+	if length > MaxWebSocketPayload {
+		err = fmt.Errorf("frame of %d bytes exceeds the %d byte limit", length, MaxWebSocketPayload)
+		return
+	}
+
+	var mask [4]byte
+	if masked {
+		if _, err = io.ReadFull(r, mask[:]); err != nil {
+			return
+		}
+	}
+	payload = make([]byte, length)
+	if _, err = io.ReadFull(r, payload); err != nil {
+		return
+	}
+	if masked {
+		for i := range payload {
+			payload[i] ^= mask[i%4]
+		}
+	}
+	return
+}
+
+// writeFrame writes a single final frame, masked as clients must.
+func writeFrame(w io.Writer, opcode byte, payload []byte) error {
+	frame := []byte{0x80 | opcode}
+	switch n := len(payload); {
+	case n < 126:
+		frame = append(frame, 0x80|byte(n))
+	case n <= 0xFFFF:
+		frame = append(frame, 0x80|126)
+		frame = binary.BigEndian.AppendUint16(frame, uint16(n))
+	default:
+		frame = append(frame, 0x80|127)
+		frame = binary.BigEndian.AppendUint64(frame, uint64(n))
+	}
+
+	var mask [4]byte
+	if _, err := rand.Read(mask[:]); err != nil {
+		return err
+	}
+	frame = append(frame, mask[:]...)
+	for i, b := range payload {
+		frame = append(frame, b^mask[i%4])
+	}
+	_, err := w.Write(frame)
+	return err
+}
+
+// acceptKey computes the expected Sec-WebSocket-Accept for a key.
+func acceptKey(challenge string) string {
+	sum := sha1.Sum([]byte(challenge + websocketGUID))
+	return base64.StdEncoding.EncodeToString(sum[:])
+}
+
+// closeSuffix describes the status code and reason of a close payload.
+func closeSuffix(payload []byte) string {
+	if len(payload) < 2 {
+		return ""
+	}
+	suffix := fmt.Sprintf(" (%d", binary.BigEndian.Uint16(payload))
+	if reason := string(payload[2:]); reason != "" {
+		suffix += ": " + reason
+	}
+	return suffix + ")"
+}
@@ -0,0 +1,255 @@
+package script
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"math"
+	"runtime/metrics"
+	"time"
+
+	"github.com/dop251/goja"
+	"github.com/dop251/goja/parser"
+)
+
+// memoryCheckInterval is the time between checks of the heap while a script runs.
+const memoryCheckInterval = 10 * time.Millisecond
+
+// errMemoryLimit interrupts a script that exceeded its memory limit.
+var errMemoryLimit = errors.New("memory limit exceeded")
+
+// Runtime is a sandboxed JavaScript interpreter. Globals and functions
+// defined on it persist between runs, so one program may define functions
+// that are called later, as proxy auto-config files do. A Runtime is not
+// safe for concurrent use.
+type Runtime struct {
+	vm     *goja.Runtime
+	limits Limits
+}
+
+// Function is a Go function exposed to scripts. Arguments arrive as
+// JSON-compatible values, with undefined as nil; a returned error is thrown
+// as a JavaScript exception.
+type Function func(args []interface{}) (interface{}, error)
+
+// NewRuntime creates a runtime holding only the standard library.
+func NewRuntime(limits Limits) *Runtime {
+	vm := goja.New()
+	vm.SetMaxCallStackSize(limits.MaxCallDepth)
+	r := &Runtime{vm: vm, limits: limits}
+	if limits.MaxMemoryBytes > 0 {
+		r.capBuilders()
+	}
+	return r
+}
+
+// capBuilders makes the built-in methods that build a string or fill an
+// array of a requested size throw when it would exceed the memory limit.
+// They allocate it in one step, before the heap can be checked.
+func (r *Runtime) capBuilders() {
+	thisLength := func(call goja.FunctionCall) float64 {
+		return float64(len(call.This.String()))
+	}
+	r.capMethod("String", "repeat", func(call goja.FunctionCall) float64 {
+		return thisLength(call) * call.Argument(0).ToFloat()
+	})
+	for _, name := range []string{"padStart", "padEnd"} {
+		r.capMethod("String", name, func(call goja.FunctionCall) float64 {
+			return call.Argument(0).ToFloat()
+		})
+	}
+	r.capMethod("Array", "fill", func(call goja.FunctionCall) float64 {
+		// A slot of an array holds a 16-byte interface value
+		return call.This.ToObject(r.vm).Get("length").ToFloat() * 16
+	})
+}
+
+// capMethod wraps a method of a built-in prototype to check the bytes size
+// returns for a call against the memory limit first.
+func (r *Runtime) capMethod(constructor, name string, size func(goja.FunctionCall) float64) {
+	proto := r.vm.Get(constructor).ToObject(r.vm).Get("prototype").ToObject(r.vm)
+	original, ok := goja.AssertFunction(proto.Get(name))
+	if !ok {
+		return
+	}
+	proto.Set(name, func(call goja.FunctionCall) goja.Value {
+		if n := size(call); n > float64(r.limits.MaxMemoryBytes) && !math.IsInf(n, 0) {
+			panic(r.vm.NewGoError(errMemoryLimit))
+		}
+		value, err := original(call.This, call.Arguments...)
+		if err != nil {
+			panic(err)
+		}
+		return value
+	})
+}
+
+// Set defines a global variable. value must be JSON-compatible, as for
+// Program.Run.
+func (r *Runtime) Set(name string, value interface{}) error {
+	return r.vm.Set(name, normalize(value))
+}
+
+// Define defines a global function implemented in Go.
+func (r *Runtime) Define(name string, fn Function) error {
+	return r.vm.Set(name, func(call goja.FunctionCall) goja.Value {
+		args := make([]interface{}, len(call.Arguments))
+		for i, arg := range call.Arguments {
+			args[i] = exportValue(arg.Export())
+		}
+		result, err := fn(args)
+		if err != nil {
+			panic(r.vm.NewGoError(err))
+		}
+		return r.vm.ToValue(normalize(result))
+	})
+}
+
+// Run evaluates a program, returning the value of its last expression.
+func (r *Runtime) Run(ctx context.Context, program *Program) (interface{}, error) {
+	return r.guard(ctx, func() (goja.Value, error) {
+		return r.vm.RunProgram(program.program)
+	})
+}
+
+// Call calls a global function with JSON-compatible arguments.
+func (r *Runtime) Call(ctx context.Context, name string, args ...interface{}) (interface{}, error) {
+	fn, ok := goja.AssertFunction(r.vm.Get(name))
+	if !ok {
+		return nil, &Error{Message: fmt.Sprintf("%s is not a function", name)}
+	}
+	values := make([]goja.Value, len(args))
+	for i, arg := range args {
+		values[i] = r.vm.ToValue(normalize(arg))
+	}
+	return r.guard(ctx, func() (goja.Value, error) {
+		return fn(goja.Undefined(), values...)
+	})
+}
+
+// Defined reports whether a global variable is defined.
+func (r *Runtime) Defined(name string) bool {
+	value := r.vm.Get(name)
+	return value != nil && !goja.IsUndefined(value)
+}
+
+// guard runs f, interrupting it when ctx is done, the time limit passes or
+// the live heap grows beyond the memory limit, and exports the value it
+// returns. The heap is that of the whole process, so the memory limit
+// bounds what a script holds on to only as long as little else grows.
+func (r *Runtime) guard(ctx context.Context, f func() (goja.Value, error)) (interface{}, error) {
+	ctx, cancel := context.WithTimeout(ctx, r.limits.Timeout)
+	defer cancel()
+
+	done := make(chan struct{})
+	watched := make(chan struct{})
+	go func() {
+		defer close(watched)
+		var tick <-chan time.Time
+		if r.limits.MaxMemoryBytes > 0 {
+			ticker := time.NewTicker(memoryCheckInterval)
+			defer ticker.Stop()
+			tick = ticker.C
+		}
+		base := liveHeap()
+		for {
+			select {
+			case <-ctx.Done():
+				r.vm.Interrupt(ctx.Err())
+				return
+			case <-tick:
+				if liveHeap() > base+uint64(r.limits.MaxMemoryBytes) {
+					r.vm.Interrupt(errMemoryLimit)
+					return
+				}
+			case <-done:
+				return
+			}
+		}
+	}()
+	value, err := f()
+	close(done)
+	<-watched
+	// An interrupt that arrived as f returned must not stop the next run
+	r.vm.ClearInterrupt()
+
+	if err != nil {
+		var interrupted *goja.InterruptedError
+		if errors.As(err, &interrupted) {
+			switch {
+			case interrupted.Value() == errMemoryLimit:
+				return nil, r.memoryError()
+			case errors.Is(ctx.Err(), context.DeadlineExceeded):
+				return nil, &Error{Message: fmt.Sprintf("script exceeded the time limit of %s", r.limits.Timeout)}
+			}
+			return nil, &Error{Message: "script was cancelled"}
+		}
+		// Thrown by a capped method and not caught
+		if errors.Is(err, errMemoryLimit) {
+			return nil, r.memoryError()
+		}
+		return nil, scriptError(err)
+	}
+	if value == nil {
+		return nil, nil
+	}
+	if _, ok := goja.AssertFunction(value); ok {
+		return nil, &Error{Message: "a script cannot return a function"}
+	}
+	result := exportValue(value.Export())
+	if limit := r.limits.MaxOutputBytes; limit > 0 {
+		if data, _ := json.Marshal(result); len(data) > limit {
+			return nil, &Error{Message: fmt.Sprintf("script result larger than %d bytes", limit)}
+		}
+	}
+	return result, nil
+}
+
+func (r *Runtime) memoryError() *Error {
+	return &Error{Message: fmt.Sprintf("script exceeded the memory limit of %d MiB", r.limits.MaxMemoryBytes>>20)}
+}
+
+// liveHeap returns the bytes of heap objects live at the last garbage collection.
+func liveHeap() uint64 {
+	sample := []metrics.Sample{{Name: "/gc/heap/live:bytes"}}
+	metrics.Read(sample)
+	if sample[0].Value.Kind() != metrics.KindUint64 {
+		return 0
+	}
+	return sample[0].Value.Uint64()
+}
+
+// scriptError converts a syntax error or uncaught exception of the engine
+// to an *Error with the position it occurred at.
+func scriptError(err error) error {
+	var parseErrors parser.ErrorList
+	if errors.As(err, &parseErrors) && len(parseErrors) > 0 {
+		first := parseErrors[0]
+		return &Error{
+			Pos:     &Pos{Line: first.Position.Line, Column: first.Position.Column},
+			Message: first.Message,
+		}
+	}
+	var syntax *goja.CompilerSyntaxError
+	if errors.As(err, &syntax) {
+		scriptErr := &Error{Message: syntax.Message}
+		if syntax.File != nil {
+			position := syntax.File.Position(syntax.Offset)
+			scriptErr.Pos = &Pos{Line: position.Line, Column: position.Column}
+		}
+		return scriptErr
+	}
+	var exception *goja.Exception
+	if errors.As(err, &exception) {
+		scriptErr := &Error{Message: exception.Value().String()}
+		for _, frame := range exception.Stack() {
+			if position := frame.Position(); position.Line > 0 {
+				scriptErr.Pos = &Pos{Line: position.Line, Column: position.Column}
+				break
+			}
+		}
+		return scriptErr
+	}
+	return &Error{Message: err.Error()}
+}
@@ -0,0 +1,177 @@
+// Package script runs sandboxed JavaScript on the embedded goja engine: the
+// scripts of /api/script, the pre-request and test scripts of requests and
+// proxy auto-config files. Scripts see only the globals they are given and
+// the standard library; they cannot reach the network or the filesystem,
+// and run within a time limit, a memory limit and a bounded call stack.
+//
+// The value of a script is that of its last expression statement. Object
+// literals in that position need parentheses:
+//
+//	const items = json.items.filter(i => i.price > 10);
+//	({count: items.length, total: items.reduce((sum, i) => sum + i.price, 0)})
+package script
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"math"
+	"time"
+
+	"github.com/dop251/goja"
+	"github.com/dop251/goja/parser"
+)
+
+// Limits bound the resources a script may use.
+type Limits struct {
+	MaxSourceBytes int           // Length of the script itself
+	MaxCallDepth   int           // Nested function calls, bounding recursion
+	MaxMemoryBytes int           // Growth of the live heap while running (0 = unlimited)
+	MaxOutputBytes int           // JSON encoding of the value returned (0 = unlimited)
+	Timeout        time.Duration // Wall-clock time
+}
+
+// DefaultLimits suit ad-hoc transformations of a single response.
+var DefaultLimits = Limits{
+	MaxSourceBytes: 64 << 10,
+	MaxCallDepth:   1000,
+	MaxMemoryBytes: 128 << 20,
+	MaxOutputBytes: 4 << 20,
+	Timeout:        2 * time.Second,
+}
+
+// Pos is a position in a script, counted from 1.
+type Pos struct {
+	Line   int `json:"line"`
+	Column int `json:"column"`
+}
+
+// Error is a syntax or runtime error of a script, at a position when known.
+type Error struct {
+	Pos     *Pos
+	Message string
+}
+
+func (e *Error) Error() string {
+	if e.Pos == nil {
+		return e.Message
+	}
+	return fmt.Sprintf("%d:%d: %s", e.Pos.Line, e.Pos.Column, e.Message)
+}
+
+// Program is a compiled script. It may run in several runtimes at once.
+type Program struct {
+	program *goja.Program
+}
+
+// Compile parses a script.
+func Compile(src string, limits Limits) (*Program, error) {
+	if len(src) > limits.MaxSourceBytes {
+		return nil, &Error{Message: fmt.Sprintf("script longer than %d bytes", limits.MaxSourceBytes)}
+	}
+	// Parsed apart from compiling, as the engine drops the position of parse errors
+	ast, err := parser.ParseFile(nil, "script", src, 0)
+	if err != nil {
+		return nil, scriptError(err)
+	}
+	program, err := goja.CompileAST(ast, false)
+	if err != nil {
+		return nil, scriptError(err)
+	}
+	return &Program{program: program}, nil
+}
+
+// Result is the outcome of running a program.
+type Result struct {
+	Value interface{} // Value of the last expression; JSON-compatible
+}
+
+// Run evaluates the program in a new runtime with globals as its variables.
+// Globals must be JSON-compatible values, as produced by decoding JSON into
+// interface{}; other values are converted through their JSON encoding.
+func (p *Program) Run(ctx context.Context, globals map[string]interface{}, limits Limits) (*Result, error) {
+	r := NewRuntime(limits)
+	for name, value := range globals {
+		if err := r.Set(name, value); err != nil {
+			return nil, err
+		}
+	}
+	value, err := r.Run(ctx, p)
+	if err != nil {
+		return nil, err
+	}
+	return &Result{Value: value}, nil
+}
+
+// Eval compiles and runs a script.
+func Eval(ctx context.Context, src string, globals map[string]interface{}, limits Limits) (*Result, error) {
+	program, err := Compile(src, limits)
+	if err != nil {
+		return nil, err
+	}
+	return program.Run(ctx, globals, limits)
+}
+
+// normalize converts the numbers of decoded JSON, and round-trips values of
+// other Go types, into the float64, string, bool, nil, slice and map values
+// scripts work with.
+func normalize(v interface{}) interface{} {
+	switch v := v.(type) {
+	case nil, bool, float64, string:
+		return v
+	case json.Number:
+		n, _ := v.Float64()
+		return n
+	case int:
+		return float64(v)
+	case int64:
+		return float64(v)
+	case []interface{}:
+		out := make([]interface{}, len(v))
+		for i, item := range v {
+			out[i] = normalize(item)
+		}
+		return out
+	case map[string]interface{}:
+		out := make(map[string]interface{}, len(v))
+		for k, item := range v {
+			out[k] = normalize(item)
+		}
+		return out
+	}
+	data, err := json.Marshal(v)
+	if err != nil {
+		return nil
+	}
+	var decoded interface{}
+	json.Unmarshal(data, &decoded)
+	return decoded
+}
+
+// exportValue makes a value exported from the engine encodable as JSON:
+// integers become float64, NaN and infinities, which JSON cannot represent,
+// become null, as do functions nested in the value.
+func exportValue(v interface{}) interface{} {
+	switch v := v.(type) {
+	case float64:
+		if math.IsNaN(v) || math.IsInf(v, 0) {
+			return nil
+		}
+		return v
+	case []interface{}:
+		out := make([]interface{}, len(v))
+		for i, item := range v {
+			out[i] = exportValue(item)
+		}
+		return out
+	case map[string]interface{}:
+		out := make(map[string]interface{}, len(v))
+		for k, item := range v {
+			out[k] = exportValue(item)
+		}
+		return out
+	case func(goja.FunctionCall) goja.Value:
+		return nil
+	}
+	return normalize(v)
+}
@@ -0,0 +1,192 @@
+package script_test
+
+import (
+	"context"
+	"errors"
+	"reflect"
+	"strings"
+	"testing"
+	"time"
+
+	"zone.digit.tommie/internal/script"
+)
+
+func TestEval(t *testing.T) {
+	globals := map[string]interface{}{
+		"json": map[string]interface{}{
+			"items": []interface{}{
+				map[string]interface{}{"name": "alpha", "price": 5.0},
+				map[string]interface{}{"name": "beta", "price": 12.5},
+				map[string]interface{}{"name": "gamma", "price": 20},
+			},
+		},
+		"status": 200,
+	}
+
+	tests := []struct {
+		name string
+		src  string
+		want interface{}
+	}{
+		{"arithmetic", "1 + 2 * 3", 7.0},
+		{"global", "status === 200", true},
+		{"last expression", "let a = 1; a += 1; a", 2.0},
+		{"object", "const items = json.items.filter(i => i.price > 10);\n({count: items.length, total: items.reduce((sum, i) => sum + i.price, 0)})",
+			map[string]interface{}{"count": 2.0, "total": 32.5}},
+		{"array", "json.items.map(i => i.name.toUpperCase())", []interface{}{"ALPHA", "BETA", "GAMMA"}},
+		{"regexp", "/^b(.)/.exec(json.items[1].name)[1]", "e"},
+		{"json", `JSON.parse('{"a":[1,2]}').a`, []interface{}{1.0, 2.0}},
+		{"undefined", "undefined", nil},
+		{"not a number", "0 / 0", nil},
+		{"nested function", "({f: () => 1, n: 1})", map[string]interface{}{"f": nil, "n": 1.0}},
+		{"capped methods", `["ab".repeat(3), "7".padStart(3, "0"), [0, 0].fill(1)]`, []interface{}{"ababab", "007", []interface{}{1.0, 1.0}}},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			result, err := script.Eval(context.Background(), tt.src, globals, script.DefaultLimits)
+			if err != nil {
+				t.Fatalf("Eval: %v", err)
+			}
+			if !reflect.DeepEqual(result.Value, tt.want) {
+				t.Errorf("got %#v, want %#v", result.Value, tt.want)
+			}
+		})
+	}
+}
+
+func TestEvalErrors(t *testing.T) {
+	tests := []struct {
+		name    string
+		src     string
+		message string
+		line    int // 0 when no position is reported
+	}{
+		{"syntax", "let a = 1;\nlet b = ;", "Unexpected token", 2},
+		{"exception", "let a = 1;\nmissing.field", "ReferenceError: missing is not defined", 2},
+		{"thrown", "\nthrow new Error(\"failed\")", "Error: failed", 2},
+		{"go error", `JSON.parse("{")`, "SyntaxError", 1},
+		{"function", "() => 1", "a script cannot return a function", 0},
+		{"recursion", "function f() { return f() }\nf()", "Maximum call stack size exceeded", 0},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			_, err := script.Eval(context.Background(), tt.src, nil, script.DefaultLimits)
+			var scriptErr *script.Error
+			if !errors.As(err, &scriptErr) {
+				t.Fatalf("got %v, want a *script.Error", err)
+			}
+			if !strings.Contains(scriptErr.Message, tt.message) {
+				t.Errorf("got %q, want it to contain %q", scriptErr.Message, tt.message)
+			}
+			if tt.line != 0 && (scriptErr.Pos == nil || scriptErr.Pos.Line != tt.line) {
+				t.Errorf("got position %v, want line %d", scriptErr.Pos, tt.line)
+			}
+		})
+	}
+}
+
+func TestEvalLimits(t *testing.T) {
+	limits := script.DefaultLimits
+	limits.Timeout = 50 * time.Millisecond
+
+	started := time.Now()
+	_, err := script.Eval(context.Background(), "while (true) {}", nil, limits)
+	if err == nil || !strings.Contains(err.Error(), "time limit") {
+		t.Errorf("got %v, want the time limit exceeded", err)
+	}
+	if elapsed := time.Since(started); elapsed > time.Second {
+		t.Errorf("took %s to stop after 50ms", elapsed)
+	}
+
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+	if _, err := script.Eval(ctx, "while (true) {}", nil, limits); err == nil || !strings.Contains(err.Error(), "cancelled") {
+		t.Errorf("got %v, want the script cancelled", err)
+	}
+
+	limits.MaxSourceBytes = 8
+	if _, err := script.Eval(context.Background(), "1 + 2 + 3 + 4", nil, limits); err == nil {
+		t.Error("got no error for a script over MaxSourceBytes")
+	}
+}
+
+func TestEvalMemoryLimits(t *testing.T) {
+	limits := script.DefaultLimits
+	limits.MaxMemoryBytes = 16 << 20
+	limits.MaxOutputBytes = 1 << 10
+	limits.Timeout = 10 * time.Second
+
+	tests := []struct {
+		name    string
+		src     string
+		message string
+	}{
+		{"repeat", `"x".repeat(2 ** 29)`, "memory limit of 16 MiB"},
+		{"pad", `"x".padEnd(2 ** 29)`, "memory limit of 16 MiB"},
+		{"fill", `new Array(2 ** 24).fill(0)`, "memory limit of 16 MiB"},
+		{"growing", `const kept = []; while (true) kept.push("x".repeat(1 << 20) + kept.length)`, "memory limit of 16 MiB"},
+		{"output", `"x".repeat(2000)`, "larger than 1024 bytes"},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			_, err := script.Eval(context.Background(), tt.src, nil, limits)
+			if err == nil || !strings.Contains(err.Error(), tt.message) {
+				t.Errorf("got %v, want an error containing %q", err, tt.message)
+			}
+		})
+	}
+
+	// A capped method throws an exception scripts can catch
+	result, err := script.Eval(context.Background(), `try { "x".repeat(2 ** 29) } catch (e) { "caught" }`, nil, limits)
+	if err != nil || result.Value != "caught" {
+		t.Errorf("got %v, %v, want the exception caught", result, err)
+	}
+}
+
+func TestRuntime(t *testing.T) {
+	r := script.NewRuntime(script.DefaultLimits)
+	calls := 0
+	err := r.Define("double", func(args []interface{}) (interface{}, error) {
+		calls++
+		n, ok := args[0].(float64)
+		if !ok {
+			return nil, errors.New("not a number")
+		}
+		return n * 2, nil
+	})
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	program, err := script.Compile("var counter = 0;\nfunction next(step) { counter += double(step); return counter }", script.DefaultLimits)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if _, err := r.Run(context.Background(), program); err != nil {
+		t.Fatal(err)
+	}
+	if !r.Defined("next") || r.Defined("missing") {
+		t.Error("Defined does not report the globals of the program")
+	}
+
+	// Globals persist between calls
+	for _, call := range []struct{ step, want float64 }{{1, 2}, {2, 6}} {
+		got, err := r.Call(context.Background(), "next", call.step)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if got != call.want {
+			t.Errorf("next(%v): got %v, want %v", call.step, got, call.want)
+		}
+	}
+
+	if _, err := r.Call(context.Background(), "next", "x"); err == nil || !strings.Contains(err.Error(), "not a number") {
+		t.Errorf("got %v, want the error of the Go function", err)
+	}
+	if _, err := r.Call(context.Background(), "counter"); err == nil {
+		t.Error("got no error calling a variable")
+	}
+	if calls != 3 {
+		t.Errorf("Go function called %d times, want 3", calls)
+	}
+}
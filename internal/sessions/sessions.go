@@ -0,0 +1,373 @@
+// Package sessions records every request executed between the start and
+// stop of a debugging session in a storage.Store, so a whole investigation
+// can be annotated, exported and replayed as a unit.
+package sessions
+
+import (
+	"crypto/rand"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"sort"
+	"strings"
+	"sync"
+	"time"
+
+	"zone.digit.tommie/internal/storage"
+)
+
+const (
+	// StoreName is the storage store holding sessions, keyed by session ID.
+	StoreName = "sessions"
+	// ExchangeStoreName is the storage store holding the recorded exchanges,
+	// keyed by session ID and the time they started.
+	ExchangeStoreName = "session-exchanges"
+	// activeStoreName holds the ID of the session being recorded under activeKey.
+	activeStoreName = "session-active"
+	activeKey       = "current"
+)
+
+// MaxExchanges is the number of exchanges kept per session; later ones are
+// counted but dropped.
+const MaxExchanges = 1000
+
+// MaxBodyBytes is the response body kept per recorded exchange.
+const MaxBodyBytes = 1 << 20
+
+// ErrNotFound is returned when a session does not exist.
+var ErrNotFound = errors.New("session not found")
+
+// ValidationError reports invalid input such as an empty note.
+type ValidationError struct {
+	Message string
+}
+
+func (e *ValidationError) Error() string {
+	return e.Message
+}
+
+// Session groups the requests executed while it was recorded.
+type Session struct {
+	ID        string     `json:"id"`
+	Name      string     `json:"name"`
+	Notes     []Note     `json:"notes"`
+	Started   time.Time  `json:"started"`
+	Stopped   *time.Time `json:"stopped,omitempty"` // Nil while recording
+	Exchanges int        `json:"exchanges"`         // Recorded exchanges, including those dropped beyond MaxExchanges
+}
+
+// Note annotates a session, such as with what was tried at some point of
+// the investigation.
+type Note struct {
+	Timestamp time.Time `json:"timestamp"`
+	Text      string    `json:"text"`
+}
+
+// Exchange is a recorded request with its response, in the JSON shape of
+// har.Exchange.
+type Exchange struct {
+	Timestamp time.Time       `json:"timestamp"`
+	Data      json.RawMessage `json:"data"`
+}
+
+// BundleVersion identifies the format of exported bundles.
+const BundleVersion = 1
+
+// Bundle is a session exported with every exchange as recorded, to be
+// archived and imported again.
+type Bundle struct {
+	Version   int        `json:"version"`
+	Session   Session    `json:"session"`
+	Exchanges []Exchange `json:"exchanges"`
+}
+
+// mu serializes read-modify-write cycles on sessions.
+var mu sync.Mutex
+
+// Start begins recording a new session, stopping the one being recorded.
+func Start(store storage.Store, name string) (*Session, error) {
+	suffix := make([]byte, 8)
+	if _, err := rand.Read(suffix); err != nil {
+		return nil, err
+	}
+	now := time.Now().UTC()
+	session := Session{ID: hex.EncodeToString(suffix), Name: strings.TrimSpace(name), Notes: []Note{}, Started: now}
+	if session.Name == "" {
+		session.Name = "Session " + now.Format("2006-01-02 15:04")
+	}
+
+	mu.Lock()
+	defer mu.Unlock()
+	if _, err := stop(store, now); err != nil {
+		return nil, err
+	}
+	if err := save(store, &session); err != nil {
+		return nil, err
+	}
+	if err := store.Set(activeStoreName, activeKey, session.ID); err != nil {
+		return nil, err
+	}
+	return &session, nil
+}
+
+// Stop ends recording, returning the stopped session or nil when none was
+// being recorded.
+func Stop(store storage.Store) (*Session, error) {
+	mu.Lock()
+	defer mu.Unlock()
+	return stop(store, time.Now().UTC())
+}
+
+func stop(store storage.Store, now time.Time) (*Session, error) {
+	session, err := active(store)
+	if err != nil || session == nil {
+		return nil, err
+	}
+	session.Stopped = &now
+	if err := save(store, session); err != nil {
+		return nil, err
+	}
+	if err := store.Remove(activeStoreName, activeKey); err != nil {
+		return nil, err
+	}
+	return session, nil
+}
+
+// Active returns the session being recorded, or nil when none is.
+func Active(store storage.Store) (*Session, error) {
+	mu.Lock()
+	defer mu.Unlock()
+	return active(store)
+}
+
+func active(store storage.Store) (*Session, error) {
+	id, err := store.Get(activeStoreName, activeKey)
+	if err != nil || id == nil {
+		return nil, err
+	}
+	session, err := Get(store, *id)
+	if errors.Is(err, ErrNotFound) {
+		return nil, nil
+	}
+	return session, err
+}
+
+// List returns all sessions, newest first.
+func List(store storage.Store) ([]Session, error) {
+	ids, err := store.Keys(StoreName)
+	if err != nil {
+		return nil, err
+	}
+	sessions := []Session{}
+	for _, id := range ids {
+		session, err := Get(store, id)
+		if err != nil {
+			return nil, err
+		}
+		sessions = append(sessions, *session)
+	}
+	sort.Slice(sessions, func(i, j int) bool { return sessions[i].Started.After(sessions[j].Started) })
+	return sessions, nil
+}
+
+// Get returns a session.
+func Get(store storage.Store, id string) (*Session, error) {
+	value, err := store.Get(StoreName, id)
+	if err != nil {
+		return nil, err
+	}
+	if value == nil {
+		return nil, ErrNotFound
+	}
+	var session Session
+	if err := json.Unmarshal([]byte(*value), &session); err != nil {
+		return nil, fmt.Errorf("corrupt session %s: %w", id, err)
+	}
+	return &session, nil
+}
+
+// Rename changes the name of a session.
+func Rename(store storage.Store, id, name string) (*Session, error) {
+	name = strings.TrimSpace(name)
+	if name == "" {
+		return nil, &ValidationError{Message: "Name is required"}
+	}
+	mu.Lock()
+	defer mu.Unlock()
+	session, err := Get(store, id)
+	if err != nil {
+		return nil, err
+	}
+	session.Name = name
+	return session, save(store, session)
+}
+
+// AddNote appends a note to a session, timestamped now.
+func AddNote(store storage.Store, id, text string) (*Session, error) {
+	text = strings.TrimSpace(text)
+	if text == "" {
+		return nil, &ValidationError{Message: "Note text is required"}
+	}
+	mu.Lock()
+	defer mu.Unlock()
+	session, err := Get(store, id)
+	if err != nil {
+		return nil, err
+	}
+	session.Notes = append(session.Notes, Note{Timestamp: time.Now().UTC(), Text: text})
+	return session, save(store, session)
+}
+
+// Delete removes a session and its exchanges, stopping it if it is being
+// recorded.
+func Delete(store storage.Store, id string) error {
+	mu.Lock()
+	defer mu.Unlock()
+	if current, err := store.Get(activeStoreName, activeKey); err != nil {
+		return err
+	} else if current != nil && *current == id {
+		if err := store.Remove(activeStoreName, activeKey); err != nil {
+			return err
+		}
+	}
+	keys, err := exchangeKeys(store, id)
+	if err != nil {
+		return err
+	}
+	for _, key := range keys {
+		if err := store.Remove(ExchangeStoreName, key); err != nil {
+			return err
+		}
+	}
+	return store.Remove(StoreName, id)
+}
+
+// Record adds an exchange to the session being recorded, if any. data is
+// the exchange in the JSON shape of har.Exchange.
+func Record(store storage.Store, timestamp time.Time, data []byte) error {
+	mu.Lock()
+	defer mu.Unlock()
+	session, err := active(store)
+	if err != nil || session == nil {
+		return err
+	}
+	return add(store, session, timestamp, data)
+}
+
+// Export returns the bundle of a session.
+func Export(store storage.Store, id string) (*Bundle, error) {
+	session, err := Get(store, id)
+	if err != nil {
+		return nil, err
+	}
+	exchanges, err := Exchanges(store, id)
+	if err != nil {
+		return nil, err
+	}
+	return &Bundle{Version: BundleVersion, Session: *session, Exchanges: exchanges}, nil
+}
+
+// Import stores the session of a bundle as a stopped session with a new ID.
+func Import(store storage.Store, bundle Bundle) (*Session, error) {
+	if bundle.Version != BundleVersion {
+		return nil, &ValidationError{Message: fmt.Sprintf("Unsupported bundle version %d", bundle.Version)}
+	}
+	suffix := make([]byte, 8)
+	if _, err := rand.Read(suffix); err != nil {
+		return nil, err
+	}
+	session := bundle.Session
+	session.ID = hex.EncodeToString(suffix)
+	session.Name = strings.TrimSpace(session.Name)
+	if session.Name == "" {
+		return nil, &ValidationError{Message: "Name is required"}
+	}
+	if session.Notes == nil {
+		session.Notes = []Note{}
+	}
+	if session.Stopped == nil {
+		now := time.Now().UTC()
+		session.Stopped = &now
+	}
+	session.Exchanges = 0
+
+	mu.Lock()
+	defer mu.Unlock()
+	for _, exchange := range bundle.Exchanges {
+		if err := add(store, &session, exchange.Timestamp, exchange.Data); err != nil {
+			return nil, err
+		}
+	}
+	if err := save(store, &session); err != nil {
+		return nil, err
+	}
+	return &session, nil
+}
+
+func add(store storage.Store, session *Session, timestamp time.Time, data []byte) error {
+	session.Exchanges++
+	if session.Exchanges <= MaxExchanges {
+		// Exchanges of the same instant keep their order by count
+		key := fmt.Sprintf("%s/%019d-%04d", session.ID, timestamp.UnixNano(), session.Exchanges)
+		value, err := json.Marshal(Exchange{Timestamp: timestamp, Data: data})
+		if err != nil {
+			return err
+		}
+		if err := store.Set(ExchangeStoreName, key, string(value)); err != nil {
+			return err
+		}
+	}
+	return save(store, session)
+}
+
+// Exchanges returns the exchanges recorded in a session, oldest first.
+func Exchanges(store storage.Store, id string) ([]Exchange, error) {
+	if _, err := Get(store, id); err != nil {
+		return nil, err
+	}
+	keys, err := exchangeKeys(store, id)
+	if err != nil {
+		return nil, err
+	}
+	exchanges := []Exchange{}
+	for _, key := range keys {
+		value, err := store.Get(ExchangeStoreName, key)
+		if err != nil {
+			return nil, err
+		}
+		if value == nil {
+			continue
+		}
+		var exchange Exchange
+		if err := json.Unmarshal([]byte(*value), &exchange); err != nil {
+			return nil, fmt.Errorf("corrupt exchange %s: %w", key, err)
+		}
+		exchanges = append(exchanges, exchange)
+	}
+	return exchanges, nil
+}
+
+func exchangeKeys(store storage.Store, id string) ([]string, error) {
+	keys, err := store.Keys(ExchangeStoreName)
+	if err != nil {
+		return nil, err
+	}
+	prefix := id + "/"
+	var matching []string
+	for _, key := range keys {
+		if strings.HasPrefix(key, prefix) {
+			matching = append(matching, key)
+		}
+	}
+	sort.Strings(matching)
+	return matching, nil
+}
+
+func save(store storage.Store, session *Session) error {
+	data, err := json.Marshal(session)
+	if err != nil {
+		return err
+	}
+	return store.Set(StoreName, session.ID, string(data))
+}
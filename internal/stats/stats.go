@@ -0,0 +1,99 @@
+// Package stats summarizes repeated measurements, such as the timings of
+// the requests of a batch or load test, as percentiles and histograms.
+package stats
+
+import "sort"
+
+// Summary describes the distribution of a set of values.
+type Summary struct {
+	Count     int
+	Min       uint64
+	Mean      uint64
+	P50       uint64
+	P90       uint64
+	P95       uint64
+	P99       uint64
+	Max       uint64
+	Histogram []Bucket
+}
+
+// Bucket counts the values above the upper bound of the previous bucket,
+// up to and including Upper.
+type Bucket struct {
+	Upper uint64
+	Count int
+}
+
+// Summarize returns the distribution of values, or nil for none. Values
+// are not modified.
+func Summarize(values []uint64) *Summary {
+	if len(values) == 0 {
+		return nil
+	}
+	sorted := append([]uint64(nil), values...)
+	sort.Slice(sorted, func(i, j int) bool { return sorted[i] < sorted[j] })
+	var total uint64
+	for _, v := range sorted {
+		total += v
+	}
+	return &Summary{
+		Count:     len(sorted),
+		Min:       sorted[0],
+		Mean:      total / uint64(len(sorted)),
+		P50:       Percentile(sorted, 50),
+		P90:       Percentile(sorted, 90),
+		P95:       Percentile(sorted, 95),
+		P99:       Percentile(sorted, 99),
+		Max:       sorted[len(sorted)-1],
+		Histogram: Histogram(sorted),
+	}
+}
+
+// Percentile returns the nearest-rank percentile p (1-100) of sorted
+// values, which must not be empty.
+func Percentile(sorted []uint64, p int) uint64 {
+	rank := (len(sorted)*p + 99) / 100
+	return sorted[max(rank, 1)-1]
+}
+
+// Histogram counts sorted values in buckets with upper bounds of 1, 2 and
+// 5 times a power of ten, from the bucket of the smallest value to that of
+// the largest. Buckets in between may be empty.
+func Histogram(sorted []uint64) []Bucket {
+	if len(sorted) == 0 {
+		return nil
+	}
+	buckets := []Bucket{}
+	upper := bucketBound(sorted[0])
+	for i := 0; i < len(sorted); {
+		bucket := Bucket{Upper: upper}
+		for i < len(sorted) && sorted[i] <= upper {
+			bucket.Count++
+			i++
+		}
+		buckets = append(buckets, bucket)
+		upper = nextBound(upper)
+	}
+	return buckets
+}
+
+// bucketBound returns the smallest bucket bound at or above v.
+func bucketBound(v uint64) uint64 {
+	bound := uint64(1)
+	for bound < v {
+		bound = nextBound(bound)
+	}
+	return bound
+}
+
+// nextBound steps through 1, 2, 5, 10, 20, 50 and so on.
+func nextBound(bound uint64) uint64 {
+	magnitude := uint64(1)
+	for bound/magnitude >= 10 {
+		magnitude *= 10
+	}
+	if bound/magnitude == 2 {
+		return 5 * magnitude
+	}
+	return 2 * bound
+}
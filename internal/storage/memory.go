@@ -0,0 +1,108 @@
+package storage
+
+import (
+	"sort"
+	"sync"
+)
+
+// Memory is a non-persistent Store kept entirely in memory.
+// It is useful for tests and ephemeral server deployments.
+type Memory struct {
+	stores map[string]map[string]string
+	mu     sync.Mutex
+}
+
+// NewMemory creates an empty in-memory store.
+func NewMemory() *Memory {
+	return &Memory{stores: make(map[string]map[string]string)}
+}
+
+// Close is a no-op for the in-memory store.
+func (m *Memory) Close() error {
+	return nil
+}
+
+// Get retrieves a value from storage.
+func (m *Memory) Get(store, key string) (*string, error) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+
+	value, ok := m.stores[store][key]
+	if !ok {
+		return nil, nil
+	}
+
+	return &value, nil
+}
+
+// Set stores a value in storage.
+func (m *Memory) Set(store, key, value string) error {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+
+	values, ok := m.stores[store]
+	if !ok {
+		values = make(map[string]string)
+		m.stores[store] = values
+	}
+	values[key] = value
+
+	return nil
+}
+
+// Remove deletes a value from storage.
+func (m *Memory) Remove(store, key string) error {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+
+	delete(m.stores[store], key)
+	return nil
+}
+
+// Has checks if a key exists in storage.
+func (m *Memory) Has(store, key string) (bool, error) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+
+	_, ok := m.stores[store][key]
+	return ok, nil
+}
+
+// Clear removes all values in a store.
+func (m *Memory) Clear(store string) error {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+
+	delete(m.stores, store)
+	return nil
+}
+
+// Keys returns all keys in a store.
+func (m *Memory) Keys(store string) ([]string, error) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+
+	var keys []string
+	for key := range m.stores[store] {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys)
+
+	return keys, nil
+}
+
+// Stores returns the names of all non-empty stores.
+func (m *Memory) Stores() ([]string, error) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+
+	var stores []string
+	for store, values := range m.stores {
+		if len(values) > 0 {
+			stores = append(stores, store)
+		}
+	}
+	sort.Strings(stores)
+
+	return stores, nil
+}
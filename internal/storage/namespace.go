@@ -0,0 +1,109 @@
+package storage
+
+import (
+	"errors"
+	"strings"
+)
+
+// ErrInvalidStoreName is returned for store names containing the "/" that
+// separates a namespace from its stores, as they could reach another namespace.
+var ErrInvalidStoreName = errors.New(`store names must not contain "/"`)
+
+// Namespaced scopes every store of an underlying Store to a namespace, so
+// several users can share one backend without seeing each other's data.
+type Namespaced struct {
+	inner  Store
+	prefix string
+}
+
+// NewNamespaced wraps a store so all store names are prefixed with namespace,
+// which must not contain "/".
+func NewNamespaced(inner Store, namespace string) *Namespaced {
+	return &Namespaced{
+		inner:  inner,
+		prefix: namespace + "/",
+	}
+}
+
+func (n *Namespaced) name(store string) (string, error) {
+	if strings.Contains(store, "/") {
+		return "", ErrInvalidStoreName
+	}
+	return n.prefix + store, nil
+}
+
+// Get retrieves a value from storage.
+func (n *Namespaced) Get(store, key string) (*string, error) {
+	name, err := n.name(store)
+	if err != nil {
+		return nil, err
+	}
+	return n.inner.Get(name, key)
+}
+
+// Set stores a value in storage.
+func (n *Namespaced) Set(store, key, value string) error {
+	name, err := n.name(store)
+	if err != nil {
+		return err
+	}
+	return n.inner.Set(name, key, value)
+}
+
+// Remove deletes a value from storage.
+func (n *Namespaced) Remove(store, key string) error {
+	name, err := n.name(store)
+	if err != nil {
+		return err
+	}
+	return n.inner.Remove(name, key)
+}
+
+// Has checks if a key exists in storage.
+func (n *Namespaced) Has(store, key string) (bool, error) {
+	name, err := n.name(store)
+	if err != nil {
+		return false, err
+	}
+	return n.inner.Has(name, key)
+}
+
+// Clear removes all values in a store.
+func (n *Namespaced) Clear(store string) error {
+	name, err := n.name(store)
+	if err != nil {
+		return err
+	}
+	return n.inner.Clear(name)
+}
+
+// Keys returns all keys in a store.
+func (n *Namespaced) Keys(store string) ([]string, error) {
+	name, err := n.name(store)
+	if err != nil {
+		return nil, err
+	}
+	return n.inner.Keys(name)
+}
+
+// Stores returns the names of all non-empty stores within the namespace.
+func (n *Namespaced) Stores() ([]string, error) {
+	all, err := n.inner.Stores()
+	if err != nil {
+		return nil, err
+	}
+
+	var stores []string
+	for _, store := range all {
+		if name, ok := strings.CutPrefix(store, n.prefix); ok && !strings.Contains(name, "/") {
+			stores = append(stores, name)
+		}
+	}
+
+	return stores, nil
+}
+
+// Close is a no-op; the underlying store is owned by the caller.
+func (n *Namespaced) Close() error {
+	return nil
+}
@@ -1,4 +1,4 @@
-// Package storage provides SQLite-based persistent storage for the desktop app.
+// Package storage provides persistent key/value storage backed by SQLite or memory.
 package storage
 
 import (
@@ -176,3 +176,30 @@ func (d *Database) Keys(store string) ([]string, error) {
 
 	return keys, nil
 }
+
+// Stores returns the names of all non-empty stores.
+func (d *Database) Stores() ([]string, error) {
+	d.mu.Lock()
+	defer d.mu.Unlock()
+
+	rows, err := d.db.Query("SELECT DISTINCT store FROM storage ORDER BY store")
+	if err != nil {
+		return nil, fmt.Errorf("query error: %w", err)
+	}
+	defer rows.Close()
+
+	var stores []string
+	for rows.Next() {
+		var store string
+		if err := rows.Scan(&store); err != nil {
+			return nil, fmt.Errorf("scan error: %w", err)
+		}
+		stores = append(stores, store)
+	}
+
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("rows error: %w", err)
+	}
+
+	return stores, nil
+}
@@ -0,0 +1,42 @@
+package storage
+
+import "fmt"
+
+// Backend names accepted by Open.
+const (
+	BackendSQLite = "sqlite"
+	BackendMemory = "memory"
+)
+
+// Store is the persistence interface shared by all storage backends.
+// Values are opaque strings (usually JSON) grouped by store and key.
+type Store interface {
+	Get(store, key string) (*string, error)
+	Set(store, key, value string) error
+	Remove(store, key string) error
+	Has(store, key string) (bool, error)
+	Clear(store string) error
+	Keys(store string) ([]string, error)
+	Stores() ([]string, error)
+	Close() error
+}
+
+// Ensure the built-in backends satisfy Store.
+var (
+	_ Store = (*Database)(nil)
+	_ Store = (*Memory)(nil)
+	_ Store = (*Namespaced)(nil)
+)
+
+// Open creates a Store for the named backend. The path is only used by
+// file-based backends.
+func Open(backend, path string) (Store, error) {
+	switch backend {
+	case BackendSQLite, "":
+		return New(path)
+	case BackendMemory:
+		return NewMemory(), nil
+	default:
+		return nil, fmt.Errorf("unsupported storage backend: %s", backend)
+	}
+}
@@ -0,0 +1,230 @@
+package testkit
+
+import (
+	"bytes"
+	"compress/flate"
+	"compress/gzip"
+	"encoding/json"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"strconv"
+	"time"
+
+	"github.com/andybalholm/brotli"
+)
+
+// maxRecordedBody bounds the request body kept for Requests.
+const maxRecordedBody = 1 << 20
+
+// routes registers the built-in routes:
+//
+//	/ok                          200 with body "ok"
+//	/status/{code}               Empty response with the status
+//	/echo                        JSON of the method, path, headers and body received
+//	/redirect/{n}                Chain of n redirects ending at /ok (?status=301|302|303|307|308, ?absolute=1)
+//	/redirect-to?url=            One redirect to url (?status=)
+//	/loop/{name}                 Redirects between /loop/a and /loop/b forever
+//	/delay/{ms}                  Waits before sending the headers
+//	/slow?bytes=&chunk=&interval= Trickles a body, flushing chunk bytes every interval milliseconds
+//	/bytes/{n}                   n bytes of binary data
+//	/gzip, /deflate, /br         JSON body with the content coding
+//	/drop                        Closes the connection without a response
+func (t *Target) routes() {
+	t.mux.HandleFunc("/ok", func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Type", "text/plain")
+		io.WriteString(w, "ok")
+	})
+
+	t.mux.HandleFunc("/status/{code}", func(w http.ResponseWriter, r *http.Request) {
+		code, err := strconv.Atoi(r.PathValue("code"))
+		if err != nil || code < 100 || code > 999 {
+			http.Error(w, "invalid status", http.StatusBadRequest)
+			return
+		}
+		w.WriteHeader(code)
+	})
+
+	t.mux.HandleFunc("/echo", func(w http.ResponseWriter, r *http.Request) {
+		body, _ := io.ReadAll(r.Body)
+		w.Header().Set("Content-Type", "application/json")
+		json.NewEncoder(w).Encode(map[string]interface{}{
+			"method":  r.Method,
+			"path":    r.URL.RequestURI(),
+			"headers": r.Header,
+			"body":    string(body),
+		})
+	})
+
+	t.mux.HandleFunc("/redirect/{n}", func(w http.ResponseWriter, r *http.Request) {
+		n, err := strconv.Atoi(r.PathValue("n"))
+		if err != nil || n < 1 {
+			http.Error(w, "invalid count", http.StatusBadRequest)
+			return
+		}
+		next := "/ok"
+		if n > 1 {
+			next = fmt.Sprintf("/redirect/%d", n-1)
+			if r.URL.RawQuery != "" {
+				next += "?" + r.URL.RawQuery
+			}
+		}
+		if r.URL.Query().Get("absolute") == "1" {
+			next = t.URL + next
+		}
+		redirect(w, r, next)
+	})
+
+	t.mux.HandleFunc("/redirect-to", func(w http.ResponseWriter, r *http.Request) {
+		target := r.URL.Query().Get("url")
+		if target == "" {
+			http.Error(w, "url is required", http.StatusBadRequest)
+			return
+		}
+		redirect(w, r, target)
+	})
+
+	t.mux.HandleFunc("/loop/{name}", func(w http.ResponseWriter, r *http.Request) {
+		next := "/loop/a"
+		if r.PathValue("name") == "a" {
+			next = "/loop/b"
+		}
+		redirect(w, r, next)
+	})
+
+	t.mux.HandleFunc("/delay/{ms}", func(w http.ResponseWriter, r *http.Request) {
+		ms, err := strconv.Atoi(r.PathValue("ms"))
+		if err != nil || ms < 0 {
+			http.Error(w, "invalid delay", http.StatusBadRequest)
+			return
+		}
+		if !sleep(r, time.Duration(ms)*time.Millisecond) {
+			return
+		}
+		io.WriteString(w, "ok")
+	})
+
+	t.mux.HandleFunc("/slow", func(w http.ResponseWriter, r *http.Request) {
+		query := r.URL.Query()
+		total := queryInt(query, "bytes", 1024)
+		chunk := max(queryInt(query, "chunk", 64), 1)
+		interval := time.Duration(queryInt(query, "interval", 100)) * time.Millisecond
+		w.Header().Set("Content-Type", "application/octet-stream")
+		w.Header().Set("Content-Length", strconv.Itoa(total))
+		flusher, _ := w.(http.Flusher)
+		for sent := 0; sent < total; sent += chunk {
+			w.Write(pattern(min(chunk, total-sent)))
+			if flusher != nil {
+				flusher.Flush()
+			}
+			if sent+chunk < total && !sleep(r, interval) {
+				return
+			}
+		}
+	})
+
+	t.mux.HandleFunc("/bytes/{n}", func(w http.ResponseWriter, r *http.Request) {
+		n, err := strconv.Atoi(r.PathValue("n"))
+		if err != nil || n < 0 {
+			http.Error(w, "invalid length", http.StatusBadRequest)
+			return
+		}
+		w.Header().Set("Content-Type", "application/octet-stream")
+		w.Write(pattern(n))
+	})
+
+	t.mux.HandleFunc("/gzip", compressed("gzip", func(w io.Writer) io.WriteCloser { return gzip.NewWriter(w) }))
+	t.mux.HandleFunc("/deflate", compressed("deflate", func(w io.Writer) io.WriteCloser {
+		fw, _ := flate.NewWriter(w, flate.DefaultCompression)
+		return fw
+	}))
+	t.mux.HandleFunc("/br", compressed("br", func(w io.Writer) io.WriteCloser { return brotli.NewWriter(w) }))
+
+	t.mux.HandleFunc("/drop", func(w http.ResponseWriter, r *http.Request) {
+		hijacker, ok := w.(http.Hijacker)
+		if !ok {
+			// HTTP/2 cannot hijack; abort the stream instead
+			panic(http.ErrAbortHandler)
+		}
+		conn, _, err := hijacker.Hijack()
+		if err == nil {
+			conn.Close()
+		}
+	})
+}
+
+// serve records a request and passes it to the routes.
+func (t *Target) serve(w http.ResponseWriter, r *http.Request) {
+	body, _ := io.ReadAll(io.LimitReader(r.Body, maxRecordedBody))
+	r.Body = io.NopCloser(bytes.NewReader(body))
+
+	t.mu.Lock()
+	t.requests = append(t.requests, RecordedRequest{
+		Method: r.Method,
+		Path:   r.URL.RequestURI(),
+		Header: r.Header.Clone(),
+		Body:   body,
+	})
+	t.mu.Unlock()
+
+	if t.h3 != nil && r.ProtoMajor < 3 {
+		t.h3.SetQUICHeaders(w.Header())
+	}
+	t.mux.ServeHTTP(w, r)
+}
+
+// redirect answers with the status in the query, 302 by default.
+func redirect(w http.ResponseWriter, r *http.Request, location string) {
+	status := queryInt(r.URL.Query(), "status", http.StatusFound)
+	if status < 300 || status > 399 {
+		status = http.StatusFound
+	}
+	w.Header().Set("Location", location)
+	w.WriteHeader(status)
+}
+
+// compressed serves a JSON body with a content coding.
+func compressed(encoding string, newWriter func(io.Writer) io.WriteCloser) http.HandlerFunc {
+	return func(w http.ResponseWriter, r *http.Request) {
+		var buf bytes.Buffer
+		zw := newWriter(&buf)
+		json.NewEncoder(zw).Encode(map[string]interface{}{
+			"encoding": encoding,
+			"items":    []string{"alpha", "beta", "gamma", "delta"},
+		})
+		zw.Close()
+		w.Header().Set("Content-Type", "application/json")
+		w.Header().Set("Content-Encoding", encoding)
+		w.Write(buf.Bytes())
+	}
+}
+
+// sleep waits for d, returning false when the client went away first.
+func sleep(r *http.Request, d time.Duration) bool {
+	timer := time.NewTimer(d)
+	defer timer.Stop()
+	select {
+	case <-timer.C:
+		return true
+	case <-r.Context().Done():
+		return false
+	}
+}
+
+func queryInt(query url.Values, name string, fallback int) int {
+	n, err := strconv.Atoi(query.Get(name))
+	if err != nil || n < 0 {
+		return fallback
+	}
+	return n
+}
+
+// pattern returns n bytes cycling through every byte value.
+func pattern(n int) []byte {
+	data := make([]byte, n)
+	for i := range data {
+		data[i] = byte(i)
+	}
+	return data
+}
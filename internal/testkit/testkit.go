@@ -0,0 +1,154 @@
+// Package testkit runs an in-process target server for exercising the proxy
+// service without the network: redirect chains and loops, slow and
+// compressed bodies, dropped connections, and TLS and HTTP/3 with a
+// self-signed certificate. It is meant for tests and is not linked into the binaries.
+//
+//	target := testkit.StartTLS()
+//	defer target.Close()
+//	response := target.Execute(proxy.ProxyRequest{Method: "GET", URL: "/redirect/3"})
+package testkit
+
+import (
+	"crypto/x509"
+	"fmt"
+	"net"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"sync"
+
+	"github.com/quic-go/quic-go/http3"
+
+	"zone.digit.tommie/internal/proxy"
+)
+
+// Target is an HTTP server with the routes listed in routes.go. Tests may
+// add their own with Handle.
+type Target struct {
+	// URL is the base URL of the server, e.g. http://127.0.0.1:54321.
+	URL string
+
+	server *httptest.Server
+	h3     *http3.Server // Set by StartHTTP3
+	mux    *http.ServeMux
+
+	mu       sync.Mutex
+	requests []RecordedRequest
+}
+
+// RecordedRequest is a request the target received.
+type RecordedRequest struct {
+	Method string
+	Path   string // Path and query
+	Header http.Header
+	Body   []byte
+}
+
+// Start starts a plain HTTP target.
+func Start() *Target {
+	t := newTarget()
+	t.server = httptest.NewServer(http.HandlerFunc(t.serve))
+	t.URL = t.server.URL
+	return t
+}
+
+// StartTLS starts an HTTPS target with a self-signed certificate, valid for
+// 127.0.0.1 and example.com. Services from Service trust it.
+func StartTLS() *Target {
+	t := newTarget()
+	t.server = httptest.NewUnstartedServer(http.HandlerFunc(t.serve))
+	t.server.EnableHTTP2 = true
+	t.server.StartTLS()
+	t.URL = t.server.URL
+	return t
+}
+
+// StartHTTP3 starts an HTTPS target, as StartTLS does, that also serves
+// HTTP/3 over UDP on the same port and advertises it with Alt-Svc.
+func StartHTTP3() *Target {
+	t := newTarget()
+	t.h3 = &http3.Server{Handler: http.HandlerFunc(t.serve)}
+	t.server = httptest.NewUnstartedServer(http.HandlerFunc(t.serve))
+	t.server.EnableHTTP2 = true
+	t.server.StartTLS()
+	t.URL = t.server.URL
+
+	conn, err := net.ListenPacket("udp", t.server.Listener.Addr().String())
+	if err != nil {
+		t.server.Close()
+		panic(fmt.Sprintf("testkit: failed to listen for HTTP/3: %v", err))
+	}
+	t.h3.TLSConfig = http3.ConfigureTLSConfig(t.server.TLS.Clone())
+	go t.h3.Serve(conn)
+	return t
+}
+
+func newTarget() *Target {
+	t := &Target{mux: http.NewServeMux()}
+	t.routes()
+	return t
+}
+
+// Close shuts the server down, closing open connections.
+func (t *Target) Close() {
+	if t.h3 != nil {
+		t.h3.Close()
+	}
+	t.server.CloseClientConnections()
+	t.server.Close()
+}
+
+// Handle adds a route, using the patterns of http.ServeMux.
+func (t *Target) Handle(pattern string, handler http.HandlerFunc) {
+	t.mux.HandleFunc(pattern, handler)
+}
+
+// CertPool returns a pool trusting the target's certificate, or nil for a
+// plain HTTP target.
+func (t *Target) CertPool() *x509.CertPool {
+	if t.server.TLS == nil {
+		return nil
+	}
+	pool := x509.NewCertPool()
+	pool.AddCert(t.server.Certificate())
+	return pool
+}
+
+// Service creates a proxy service with opts that trusts the target.
+func (t *Target) Service(opts proxy.Options) *proxy.HTTPService {
+	if pool := t.CertPool(); pool != nil && opts.RootCAs == nil {
+		opts.RootCAs = pool
+	}
+	return proxy.NewHTTPService(opts)
+}
+
+// Execute executes a request against the target with a default service.
+// A URL starting with a slash is taken relative to the target.
+func (t *Target) Execute(request proxy.ProxyRequest) proxy.ProxyResponse {
+	return t.Service(proxy.Options{}).Execute(t.Request(request))
+}
+
+// Request resolves a request URL starting with a slash against the target.
+func (t *Target) Request(request proxy.ProxyRequest) proxy.ProxyRequest {
+	if strings.HasPrefix(request.URL, "/") {
+		request.URL = t.URL + request.URL
+	}
+	if request.Method == "" {
+		request.Method = http.MethodGet
+	}
+	return request
+}
+
+// Requests returns the requests received so far, oldest first.
+func (t *Target) Requests() []RecordedRequest {
+	t.mu.Lock()
+	defer t.mu.Unlock()
+	return append([]RecordedRequest(nil), t.requests...)
+}
+
+// Reset forgets the requests received so far.
+func (t *Target) Reset() {
+	t.mu.Lock()
+	defer t.mu.Unlock()
+	t.requests = nil
+}
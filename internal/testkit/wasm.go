@@ -0,0 +1,140 @@
+package testkit
+
+// WasmHook is a hook of a module built by Plugin.
+type WasmHook struct {
+	Name   string // Exported name, e.g. on_request
+	Output string // JSON the hook returns; empty returns no output
+	Echo   bool   // Return the input as the output instead
+	Spin   bool   // Loop forever
+	Trap   bool   // Trap as soon as called
+}
+
+// Offsets in the memory of modules built by Plugin.
+const (
+	wasmOutputOffset = 1024  // Outputs follow each other, 1 KiB apart
+	wasmInputOffset  = 32768 // Returned by alloc
+)
+
+// Plugin assembles a WebAssembly plugin module for tests, without a
+// toolchain: memoryPages pages of memory, an alloc reserving the input at a
+// fixed offset and the hooks given. Outputs must be under 1 KiB.
+func Plugin(memoryPages uint32, hooks ...WasmHook) []byte {
+	const (
+		i32 = 0x7f
+		i64 = 0x7e
+	)
+	types := section(1, vector(
+		append([]byte{0x60}, append(sized([]byte{i32}), sized([]byte{i32})...)...),
+		append([]byte{0x60}, append(sized([]byte{i32, i32}), sized([]byte{i64})...)...),
+	))
+
+	functions := [][]byte{{0}}
+	exports := [][]byte{
+		export("memory", 0x02, 0),
+		export("alloc", 0x00, 0),
+	}
+	bodies := [][]byte{instructions(0x41, sleb(wasmInputOffset))}
+	var data [][]byte
+	for i, hook := range hooks {
+		functions = append(functions, []byte{1})
+		exports = append(exports, export(hook.Name, 0x00, uint32(i+1)))
+		switch {
+		case hook.Echo:
+			// (ptr << 32) | len
+			bodies = append(bodies, instructions(
+				0x20, 0x00, 0xad, 0x42, 32, 0x86,
+				0x20, 0x01, 0xad, 0x84,
+			))
+		case hook.Spin:
+			bodies = append(bodies, instructions(0x03, 0x40, 0x0c, 0x00, 0x0b, 0x00))
+		case hook.Trap:
+			bodies = append(bodies, instructions(0x00))
+		case hook.Output == "":
+			bodies = append(bodies, instructions(0x42, 0x00))
+		default:
+			offset := wasmOutputOffset * (i + 1)
+			packed := int64(offset)<<32 | int64(len(hook.Output))
+			bodies = append(bodies, instructions(0x42, sleb(packed)))
+			segment := append([]byte{0x00}, instructions(0x41, sleb(int64(offset)))...)
+			data = append(data, append(segment, sized([]byte(hook.Output))...))
+		}
+	}
+
+	var codes [][]byte
+	for _, body := range bodies {
+		// No locals
+		codes = append(codes, sized(append([]byte{0x00}, body...)))
+	}
+
+	module := []byte{0x00, 'a', 's', 'm', 0x01, 0x00, 0x00, 0x00}
+	module = append(module, types...)
+	module = append(module, section(3, vector(functions...))...)
+	module = append(module, section(5, vector(append([]byte{0x00}, uleb(uint64(memoryPages))...)))...)
+	module = append(module, section(7, vector(exports...))...)
+	module = append(module, section(10, vector(codes...))...)
+	if len(data) > 0 {
+		module = append(module, section(11, vector(data...))...)
+	}
+	return module
+}
+
+// instructions joins instructions, given as bytes and byte slices, and ends them.
+func instructions(parts ...interface{}) []byte {
+	var out []byte
+	for _, part := range parts {
+		switch part := part.(type) {
+		case int:
+			out = append(out, byte(part))
+		case []byte:
+			out = append(out, part...)
+		}
+	}
+	return append(out, 0x0b)
+}
+
+func section(id byte, content []byte) []byte {
+	return append([]byte{id}, sized(content)...)
+}
+
+// vector prefixes the concatenated items with their count.
+func vector(items ...[]byte) []byte {
+	out := uleb(uint64(len(items)))
+	for _, item := range items {
+		out = append(out, item...)
+	}
+	return out
+}
+
+// sized prefixes bytes with their length.
+func sized(b []byte) []byte {
+	return append(uleb(uint64(len(b))), b...)
+}
+
+func export(name string, kind byte, index uint32) []byte {
+	out := append(uleb(uint64(len(name))), name...)
+	return append(append(out, kind), uleb(uint64(index))...)
+}
+
+func uleb(v uint64) []byte {
+	var out []byte
+	for {
+		b := byte(v & 0x7f)
+		v >>= 7
+		if v == 0 {
+			return append(out, b)
+		}
+		out = append(out, b|0x80)
+	}
+}
+
+func sleb(v int64) []byte {
+	var out []byte
+	for {
+		b := byte(v & 0x7f)
+		v >>= 7
+		if (v == 0 && b&0x40 == 0) || (v == -1 && b&0x40 != 0) {
+			return append(out, b)
+		}
+		out = append(out, b|0x80)
+	}
+}
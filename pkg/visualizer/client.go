@@ -0,0 +1,191 @@
+// Package visualizer embeds the request execution engine of the visualizer
+// in other programs, without its HTTP API, storage or desktop app. Requests
+// and responses have the JSON shape of the proxy protocol.
+//
+//	client, err := visualizer.NewBuilder().
+//		DNS("https://cloudflare-dns.com/dns-query").
+//		TLS("/etc/ssl/corp-ca.pem").
+//		InFlight(64<<20, 5*time.Second).
+//		Build()
+//	if err != nil {
+//		return err
+//	}
+//	response := client.Do(ctx, visualizer.Request{Method: "GET", URL: "https://example.com"})
+//
+// Each request opens its own connections, so that every phase of it is
+// measured; there is no connection pool to configure.
+package visualizer
+
+import (
+	"context"
+	"crypto/x509"
+	"fmt"
+	"time"
+
+	"golang.org/x/net/http/httpproxy"
+
+	"zone.digit.tommie/internal/infra"
+	"zone.digit.tommie/internal/proxy"
+)
+
+// Request and response types of the proxy protocol.
+type (
+	Request          = proxy.ProxyRequest
+	Response         = proxy.ProxyResponse
+	ResponseData     = proxy.ResponseData
+	TimingInfo       = proxy.TimingInfo
+	ErrorData        = proxy.ErrorData
+	BatchRequest     = proxy.BatchRequest
+	BatchResponse    = proxy.BatchResponse
+	BurstRequest     = proxy.BurstRequest
+	BurstResponse    = proxy.BurstResponse
+	LoadTestRequest  = proxy.LoadTestRequest
+	LoadTestResponse = proxy.LoadTestResponse
+	Stats            = proxy.ServiceStats
+)
+
+// Interceptor observes and rewrites every request and response.
+type Interceptor = proxy.Interceptor
+
+// Clock times executions; see NewMockClock for deterministic timings.
+type Clock = proxy.Clock
+
+// MockClock is a Clock that only moves when told to.
+type MockClock = proxy.MockClock
+
+// NewMockClock returns a clock stopped at start, to be moved with its
+// Advance and Set methods.
+func NewMockClock(start time.Time) *MockClock {
+	return proxy.NewMockClock(start)
+}
+
+// ProtocolVersion is the version of the JSON shape of responses.
+const ProtocolVersion = proxy.ProtocolVersion
+
+// Client executes requests with detailed timing. It is safe for concurrent use.
+type Client struct {
+	service *proxy.HTTPService
+}
+
+// Builder configures a Client. Its methods record the first invalid setting,
+// which Build returns.
+type Builder struct {
+	opts proxy.Options
+	err  error
+}
+
+// NewBuilder starts a Client with the defaults of the server: the system
+// resolver and roots, no upstream proxy and no limits.
+func NewBuilder() *Builder {
+	return &Builder{}
+}
+
+// DNS resolves hostnames with nameservers such as "1.1.1.1",
+// "tls://1.1.1.1" or "https://cloudflare-dns.com/dns-query" instead of the
+// system resolver.
+func (b *Builder) DNS(nameservers ...string) *Builder {
+	resolver, err := infra.NewResolver(nameservers)
+	b.fail(err, "DNS")
+	b.opts.Resolver = resolver
+	return b
+}
+
+// TLS trusts the certificates of a PEM file or directory in addition to the
+// system roots.
+func (b *Builder) TLS(caBundle string) *Builder {
+	pool, err := infra.LoadCertPool(caBundle)
+	b.fail(err, "TLS")
+	b.opts.RootCAs = pool
+	return b
+}
+
+// RootCAs replaces the trusted root certificates.
+func (b *Builder) RootCAs(pool *x509.CertPool) *Builder {
+	b.opts.RootCAs = pool
+	return b
+}
+
+// Proxy routes requests through an HTTP(S) proxy, except for the hosts of
+// noProxy, in the format of the NO_PROXY variable.
+func (b *Builder) Proxy(proxyURL, noProxy string) *Builder {
+	b.opts.UpstreamProxy = &httpproxy.Config{HTTPProxy: proxyURL, HTTPSProxy: proxyURL, NoProxy: noProxy}
+	return b
+}
+
+// InFlight bounds the request and response bodies held across concurrent
+// executions. Executions over it wait up to wait, then fail with BUSY.
+func (b *Builder) InFlight(maxBodyBytes int, wait time.Duration) *Builder {
+	b.opts.MaxInFlightBodyBytes = maxBodyBytes
+	b.opts.BusyWait = wait
+	return b
+}
+
+// MaxBodyBytes is the response body kept per execution; longer bodies are
+// truncated.
+func (b *Builder) MaxBodyBytes(n int) *Builder {
+	b.opts.MaxBodyBytes = n
+	return b
+}
+
+// Egress bounds the bytes sent and received per minute and per UTC day
+// (0 = unlimited).
+func (b *Builder) Egress(perMinute, perDay int) *Builder {
+	b.opts.EgressBytesPerMinute = perMinute
+	b.opts.EgressBytesPerDay = perDay
+	return b
+}
+
+// Interceptors adds interceptors, run in order.
+func (b *Builder) Interceptors(interceptors ...Interceptor) *Builder {
+	b.opts.Interceptors = append(b.opts.Interceptors, interceptors...)
+	return b
+}
+
+// Clock times executions with clock instead of the system clock.
+func (b *Builder) Clock(clock Clock) *Builder {
+	b.opts.Clock = clock
+	return b
+}
+
+// Build creates the Client.
+func (b *Builder) Build() (*Client, error) {
+	if b.err != nil {
+		return nil, b.err
+	}
+	return &Client{service: proxy.NewHTTPService(b.opts)}, nil
+}
+
+func (b *Builder) fail(err error, setting string) {
+	if err != nil && b.err == nil {
+		b.err = fmt.Errorf("%s: %w", setting, err)
+	}
+}
+
+// Do executes a request until it is done or ctx is cancelled. Failures are
+// reported in the response rather than as an error, as in the proxy protocol.
+func (c *Client) Do(ctx context.Context, request Request) Response {
+	return c.service.ExecuteContext(ctx, nil, request)
+}
+
+// Batch executes several requests, in parallel or as a chain, until they
+// are done or ctx is cancelled.
+func (c *Client) Batch(ctx context.Context, batch BatchRequest) BatchResponse {
+	return c.service.ExecuteBatch(ctx, nil, batch)
+}
+
+// Burst sends a request in timed bursts to find where the server starts
+// rate limiting, sending no further bursts once ctx is cancelled.
+func (c *Client) Burst(ctx context.Context, burst BurstRequest) BurstResponse {
+	return c.service.ExecuteBurst(ctx, nil, burst)
+}
+
+// LoadTest sends a request repeatedly with fixed concurrency until it is
+// done or ctx is cancelled, passing the stats so far to onProgress.
+func (c *Client) LoadTest(ctx context.Context, test LoadTestRequest, onProgress func(LoadTestResponse)) LoadTestResponse {
+	return c.service.ExecuteLoadTest(ctx, nil, test, onProgress)
+}
+
+// Stats describes the work the client has in progress.
+func (c *Client) Stats() Stats {
+	return c.service.Stats()
+}